@@ -58,6 +58,72 @@ fn build_grid_flat_hierarchy(col_count: usize, row_count: usize) -> (TaffyTree,
     (taffy, root)
 }
 
+/// A grid with many auto-placed children of varying spans, packed using the given auto flow
+fn build_grid_auto_placement_hierarchy(
+    col_count: usize,
+    child_count: usize,
+    flow: GridAutoFlow,
+) -> (TaffyTree, NodeId) {
+    let mut taffy = TaffyTree::new();
+    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+
+    let style = Style {
+        display: Display::Grid,
+        grid_auto_flow: flow,
+        grid_template_columns: iter::repeat(length(20.0)).take(col_count).collect(),
+        ..Default::default()
+    };
+
+    let children: Vec<_> = iter::from_fn(|| {
+        let column_span = rng.gen_range(1..=3);
+        let row_span = rng.gen_range(1..=2);
+        Some(
+            taffy
+                .new_leaf(Style { grid_column: span(column_span), grid_row: span(row_span), ..Default::default() })
+                .unwrap(),
+        )
+    })
+    .take(child_count)
+    .collect();
+
+    let root = taffy.new_with_children(style, children.as_slice()).unwrap();
+    (taffy, root)
+}
+
+/// A "data grid" of intrinsically sized tracks in which every item spans multiple tracks, so that many items share
+/// identical spans (and therefore identical sizing functions)
+fn build_grid_spanning_items_hierarchy(track_count: usize) -> (TaffyTree, NodeId) {
+    let mut taffy = TaffyTree::new();
+    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+
+    let style = Style {
+        display: Display::Grid,
+        grid_template_columns: iter::repeat(auto()).take(track_count).collect(),
+        grid_template_rows: iter::repeat(auto()).take(track_count).collect(),
+        ..Default::default()
+    };
+
+    let mut children = Vec::with_capacity(track_count * track_count / 4);
+    for row in (1..=track_count as i16).step_by(2) {
+        for column in (1..=track_count as i16).step_by(2) {
+            let width: f32 = rng.gen_range(10.0..50.0);
+            let height: f32 = rng.gen_range(10.0..50.0);
+            let child = taffy
+                .new_leaf(Style {
+                    size: Size { width: length(width), height: length(height) },
+                    grid_row: Line { start: line(row), end: span(2) },
+                    grid_column: Line { start: line(column), end: span(2) },
+                    ..Default::default()
+                })
+                .unwrap();
+            children.push(child);
+        }
+    }
+
+    let root = taffy.new_with_children(style, children.as_slice()).unwrap();
+    (taffy, root)
+}
+
 /// A helper function to recursively construct a deep tree
 pub fn build_deep_grid_tree(
     tree: &mut TaffyTree,
@@ -118,6 +184,30 @@ fn taffy_benchmarks(c: &mut Criterion) {
     }
     group.finish();
 
+    let mut group = c.benchmark_group("grid/auto_placement");
+    group.sample_size(10);
+    for flow in [GridAutoFlow::Row, GridAutoFlow::RowDense].iter() {
+        group.bench_with_input(BenchmarkId::new(format!("{flow:?}"), 5000), flow, |b, &flow| {
+            b.iter_batched(
+                || build_grid_auto_placement_hierarchy(50, 5000, flow),
+                |(mut taffy, root)| taffy.compute_layout(root, max_content()).unwrap(),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("grid/spanning_items");
+    group.sample_size(10);
+    group.bench_with_input(BenchmarkId::new("100x100", 2500), &100, |b, &track_count| {
+        b.iter_batched(
+            || build_grid_spanning_items_hierarchy(track_count),
+            |(mut taffy, root)| taffy.compute_layout(root, max_content()).unwrap(),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
     let mut group = c.benchmark_group("grid/deep");
     group.sample_size(10);
     for (tracks, levels) in [(2, 5), (3, 4), (2, 7) /*, (3, 5)*/].iter() {
@@ -0,0 +1,92 @@
+//! This file includes benchmarks for trees in which every node has only a few children, the case that the `smallvec`
+//! feature optimises for. Compare against a run with `--no-default-features --features content_size` to see its effect.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::Rng;
+use taffy::prelude::*;
+use taffy::style::Style as TaffyStyle;
+
+use taffy_benchmarks::{BuildTree, BuildTreeExt, FixedStyleGenerator, GenStyle, TaffyTreeBuilder};
+
+/// Flex containers which wrap their children, with leaves of a random fixed size
+#[derive(Clone)]
+pub struct WrappingFlexStyleGenerator;
+impl GenStyle<TaffyStyle> for WrappingFlexStyleGenerator {
+    fn create_leaf_style(&mut self, rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle { size: length(rng.gen_range(10.0f32..100.0)), ..Default::default() }
+    }
+    fn create_container_style(&mut self, _rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle {
+            flex_wrap: FlexWrap::Wrap,
+            max_size: Size { width: length(150.0), height: auto() },
+            ..Default::default()
+        }
+    }
+}
+
+/// Two-column grid containers, with leaves of a random fixed size
+#[derive(Clone)]
+pub struct TwoColumnGridStyleGenerator;
+impl GenStyle<TaffyStyle> for TwoColumnGridStyleGenerator {
+    fn create_leaf_style(&mut self, rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle { size: length(rng.gen_range(10.0f32..100.0)), ..Default::default() }
+    }
+    fn create_container_style(&mut self, _rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle {
+            display: Display::Grid,
+            grid_template_columns: vec![fr(1.0), fr(1.0)],
+            gap: length(5.0),
+            ..Default::default()
+        }
+    }
+}
+
+fn tree_creation_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Small fanout tree creation");
+    for node_count in [1_000u32, 10_000, 100_000].iter() {
+        let benchmark_id = BenchmarkId::new("Taffy 0.4 (3 children per node)", node_count);
+        group.bench_with_input(benchmark_id, node_count, |b, &node_count| {
+            b.iter(|| {
+                let mut builder = TaffyTreeBuilder::new(FixedStyleGenerator(TaffyStyle::DEFAULT));
+                builder.build_deep_hierarchy(node_count, 3);
+                std::hint::black_box(builder.into_tree_and_root());
+            })
+        });
+    }
+    group.finish();
+}
+
+fn layout_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Small fanout layout");
+    group.sample_size(10); // Decrease sample size, because the tasks take longer
+    for node_count in [1_000u32, 10_000, 100_000].iter() {
+        let benchmark_id = BenchmarkId::new("Taffy 0.4 Flexbox (3 children per node)", node_count);
+        group.bench_with_input(benchmark_id, node_count, |b, &node_count| {
+            b.iter_batched(
+                || {
+                    let mut builder = TaffyTreeBuilder::new(WrappingFlexStyleGenerator);
+                    builder.build_deep_hierarchy(node_count, 3);
+                    builder
+                },
+                |mut builder| builder.compute_layout(None, None),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+
+        let benchmark_id = BenchmarkId::new("Taffy 0.4 Grid (3 children per node)", node_count);
+        group.bench_with_input(benchmark_id, node_count, |b, &node_count| {
+            b.iter_batched(
+                || {
+                    let mut builder = TaffyTreeBuilder::new(TwoColumnGridStyleGenerator);
+                    builder.build_deep_hierarchy(node_count, 3);
+                    builder
+                },
+                |mut builder| builder.compute_layout(None, None),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tree_creation_benchmarks, layout_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+//! This file includes benchmarks for wide flex containers whose children have percentage margins, padding and borders,
+//! the case that the `simd` feature optimises for. Compare against a run with `--features simd` (which requires the
+//! nightly toolchain) to see its effect.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::Rng;
+use taffy::prelude::*;
+use taffy::style::Style as TaffyStyle;
+
+use taffy_benchmarks::{BuildTree, BuildTreeExt, GenStyle, TaffyTreeBuilder};
+
+fn random_spacing(rng: &mut impl Rng) -> LengthPercentage {
+    match rng.gen_bool(0.5) {
+        true => LengthPercentage::Percent(rng.gen_range(0.0..0.02)),
+        false => LengthPercentage::Length(rng.gen_range(0.0..10.0)),
+    }
+}
+
+fn random_rect(rng: &mut impl Rng) -> Rect<LengthPercentage> {
+    Rect {
+        left: random_spacing(rng),
+        right: random_spacing(rng),
+        top: random_spacing(rng),
+        bottom: random_spacing(rng),
+    }
+}
+
+/// A wrapping flex container with a definite width, whose leaves have a mix of length and percentage spacing
+#[derive(Clone)]
+pub struct PercentageSpacingStyleGenerator;
+impl GenStyle<TaffyStyle> for PercentageSpacingStyleGenerator {
+    fn create_leaf_style(&mut self, rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle {
+            size: length(rng.gen_range(10.0f32..100.0)),
+            margin: random_rect(rng).map(LengthPercentageAuto::from),
+            padding: random_rect(rng),
+            border: random_rect(rng),
+            ..Default::default()
+        }
+    }
+    fn create_container_style(&mut self, _rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle::DEFAULT
+    }
+    fn create_root_style(&mut self, _rng: &mut impl Rng) -> TaffyStyle {
+        TaffyStyle {
+            flex_wrap: FlexWrap::Wrap,
+            size: Size { width: length(1000.0), height: auto() },
+            ..Default::default()
+        }
+    }
+}
+
+fn layout_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Wide container with percentage spacing");
+    for child_count in [100usize, 1_000, 10_000].iter() {
+        let benchmark_id = BenchmarkId::new("Taffy 0.4 Flexbox", child_count);
+        group.bench_with_input(benchmark_id, child_count, |b, &child_count| {
+            b.iter_batched(
+                || {
+                    let mut builder = TaffyTreeBuilder::new(PercentageSpacingStyleGenerator);
+                    let children = builder.build_n_leaf_nodes(child_count);
+                    builder.set_root_children(&children);
+                    builder
+                },
+                |mut builder| builder.compute_layout(None, None),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, layout_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+#!/bin/bash
+
+# Lays out each tree exported by the compare harness (with `--export-html <dir>`) in headless Chrome, and writes the
+# resulting layouts to `<dir>/<scenario>.txt` so that they can be compared against Taffy's (with `--chrome <dir>`).
+#
+# Set the CHROME environment variable to the path of the Chrome (or Chromium) binary if it is not `google-chrome`.
+
+if [[ -z "$1" || ! -d "$1" ]]; then
+  echo "Usage: $0 <dir>"
+  echo "Where <dir> is the directory passed to the compare harness with --export-html"
+  exit 1;
+fi
+
+CHROME=${CHROME:-google-chrome}
+if ! command -v "$CHROME" > /dev/null; then
+  echo "Error: $CHROME not found. Set the CHROME environment variable to the path of a Chrome binary."
+  exit 1;
+fi
+
+for HTML_FILE in "$1"/*.html; do
+  NAME=$(basename "$HTML_FILE" .html)
+  "$CHROME" --headless --disable-gpu --hide-scrollbars --dump-dom "file://$(realpath "$HTML_FILE")" 2> /dev/null \
+    | sed -n '/<pre id="layouts"/,/<\/pre>/p' \
+    | sed -e 's/.*<pre id="layouts"[^>]*>//' -e 's/<\/pre>.*//' \
+    > "$1/$NAME.txt"
+  echo "Wrote $1/$NAME.txt"
+done
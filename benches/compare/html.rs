@@ -0,0 +1,156 @@
+//! Export trees as HTML documents which record their own layout when loaded by a browser (see `chrome_layouts.sh`)
+use std::fmt::{Debug, Write};
+
+use taffy::prelude::*;
+use taffy::style::{LengthPercentage, LengthPercentageAuto};
+
+/// Base styles which make the browser's defaults match Taffy's
+const BASE_STYLE: &str = "
+body { margin: 0; padding: 0; }
+div { display: flex; position: relative; box-sizing: border-box; margin: 0; padding: 0; border: 0 solid black; }
+.viewport { position: absolute; width: max-content; height: max-content; align-items: start; justify-content: start; }
+";
+
+/// Writes the position of each node relative to its parent (in creation order) into `<pre id="layouts">`
+const LAYOUT_SCRIPT: &str = "
+const lines = [];
+for (const el of document.querySelectorAll('[data-node]')) {
+  const rect = el.getBoundingClientRect();
+  const parent = el.parentElement.closest('[data-node]');
+  const parentRect = parent ? parent.getBoundingClientRect() : { left: 0, top: 0 };
+  lines[Number(el.dataset.node)] = `${rect.left - parentRect.left} ${rect.top - parentRect.top} ${rect.width} ${rect.height}`;
+}
+const pre = document.createElement('pre');
+pre.id = 'layouts';
+pre.style.display = 'none';
+pre.textContent = lines.join('\\n');
+document.body.appendChild(pre);
+";
+
+/// Convert a tree to an HTML document. Each node is labelled with its index in `nodes` (the order in which the nodes
+/// were created) so that the browser's layouts can be matched up with those of other engines.
+pub fn tree_to_html(tree: &TaffyTree, root: NodeId, nodes: &[NodeId]) -> String {
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<style>{BASE_STYLE}</style>\n</head>\n<body>").unwrap();
+    writeln!(html, "<div class=\"viewport\">").unwrap();
+    write_node(&mut html, tree, root, nodes);
+    writeln!(html, "</div>\n<script>{LAYOUT_SCRIPT}</script>\n</body>\n</html>").unwrap();
+    html
+}
+
+fn write_node(html: &mut String, tree: &TaffyTree, node: NodeId, nodes: &[NodeId]) {
+    let index = nodes.iter().position(|&n| n == node).unwrap();
+    let style = style_to_css(tree.style(node).unwrap());
+    write!(html, "<div data-node=\"{index}\" style=\"{style}\">").unwrap();
+    for child in tree.children(node).unwrap() {
+        write_node(html, tree, child, nodes);
+    }
+    writeln!(html, "</div>").unwrap();
+}
+
+/// Convert a (CamelCase) enum variant to the equivalent CSS keyword
+fn keyword(value: impl Debug) -> String {
+    let name = format!("{value:?}");
+    match name.as_str() {
+        "NoWrap" => "nowrap".to_string(),
+        "LastBaseline" => "last baseline".to_string(),
+        _ => {
+            let mut keyword = String::new();
+            for (i, c) in name.chars().enumerate() {
+                if c.is_uppercase() && i > 0 {
+                    keyword.push('-');
+                }
+                keyword.push(c.to_ascii_lowercase());
+            }
+            keyword
+        }
+    }
+}
+
+fn dimension(value: Dimension) -> String {
+    match value {
+        Dimension::Length(length) => format!("{length}px"),
+        Dimension::Percent(fraction) => format!("{}%", fraction * 100.0),
+        Dimension::Auto => "auto".to_string(),
+        Dimension::Content => "content".to_string(),
+        // CSS has no equivalent of percentages of the root's size, and they aren't generated by the benchmarks
+        Dimension::RootPercent(_) => "auto".to_string(),
+    }
+}
+
+fn length_percentage(value: LengthPercentage) -> String {
+    match value {
+        LengthPercentage::Length(length) => format!("{length}px"),
+        LengthPercentage::Percent(fraction) => format!("{}%", fraction * 100.0),
+    }
+}
+
+fn length_percentage_auto(value: LengthPercentageAuto) -> String {
+    match value {
+        LengthPercentageAuto::Length(length) => format!("{length}px"),
+        LengthPercentageAuto::Percent(fraction) => format!("{}%", fraction * 100.0),
+        LengthPercentageAuto::Auto => "auto".to_string(),
+    }
+}
+
+/// Convert the properties of a style which are used by the comparison scenarios to inline CSS declarations
+fn style_to_css(style: &Style) -> String {
+    let mut css = String::new();
+    let mut declare = |property: &str, value: String| write!(css, "{property}: {value}; ").unwrap();
+
+    declare("display", keyword(style.display));
+    declare("box-sizing", keyword(style.box_sizing));
+    declare("position", keyword(style.position));
+    for (side, value) in [
+        ("left", style.inset.left),
+        ("right", style.inset.right),
+        ("top", style.inset.top),
+        ("bottom", style.inset.bottom),
+    ] {
+        declare(side, length_percentage_auto(value));
+    }
+
+    declare("width", dimension(style.size.width));
+    declare("height", dimension(style.size.height));
+    declare("min-width", dimension(style.min_size.width));
+    declare("min-height", dimension(style.min_size.height));
+    declare("max-width", dimension(style.max_size.width).replace("auto", "none"));
+    declare("max-height", dimension(style.max_size.height).replace("auto", "none"));
+    if let Some(aspect_ratio) = style.aspect_ratio {
+        declare("aspect-ratio", aspect_ratio.to_string());
+    }
+
+    for (side, margin, padding, border) in [
+        ("left", style.margin.left, style.padding.left, style.border.left),
+        ("right", style.margin.right, style.padding.right, style.border.right),
+        ("top", style.margin.top, style.padding.top, style.border.top),
+        ("bottom", style.margin.bottom, style.padding.bottom, style.border.bottom),
+    ] {
+        declare(&format!("margin-{side}"), length_percentage_auto(margin));
+        declare(&format!("padding-{side}"), length_percentage(padding));
+        declare(&format!("border-{side}-width"), length_percentage(border));
+    }
+
+    if let Some(align_items) = style.align_items {
+        declare("align-items", keyword(align_items));
+    }
+    if let Some(align_self) = style.align_self {
+        declare("align-self", keyword(align_self));
+    }
+    if let Some(align_content) = style.align_content {
+        declare("align-content", keyword(align_content));
+    }
+    if let Some(justify_content) = style.justify_content {
+        declare("justify-content", keyword(justify_content));
+    }
+    declare("column-gap", length_percentage(style.gap.width));
+    declare("row-gap", length_percentage(style.gap.height));
+
+    declare("flex-direction", keyword(style.flex_direction));
+    declare("flex-wrap", keyword(style.flex_wrap));
+    declare("flex-basis", dimension(style.flex_basis));
+    declare("flex-grow", style.flex_grow.to_string());
+    declare("flex-shrink", style.flex_shrink.to_string());
+
+    css
+}
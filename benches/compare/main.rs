@@ -0,0 +1,268 @@
+//! A harness which lays out identical trees with Taffy and other layout engines, and reports both how long each
+//! engine took and where their layouts differ.
+//!
+//! ```text
+//! # Compare Taffy against Yoga
+//! cargo bench --bench compare --features compare,yoga
+//!
+//! # Compare Taffy against Chrome
+//! cargo bench --bench compare --features compare -- --export-html target/compare
+//! ./compare/chrome_layouts.sh target/compare
+//! cargo bench --bench compare --features compare -- --chrome target/compare
+//! ```
+//!
+//! Options:
+//!
+//! - `--iterations <n>`: the number of times to lay out each tree when timing (default: 20)
+//! - `--tolerance <px>`: the amount by which a layout value may differ before it is reported (default: 0.5)
+//! - `--export-html <dir>`: write each tree to `<dir>/<scenario>.html` so that it can be laid out by a browser
+//! - `--chrome <dir>`: compare against the `<dir>/<scenario>.txt` layouts written by `chrome_layouts.sh`
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use taffy::prelude::*;
+use taffy::style::Style as TaffyStyle;
+use taffy_benchmarks::{BuildTreeExt, CollectLayouts, GenStyle, NodeLayout, TaffyTreeBuilder};
+
+#[cfg(feature = "yoga")]
+use taffy_benchmarks::YogaTreeBuilder;
+
+mod html;
+
+/// The styles with which the nodes of a scenario's tree are created
+#[derive(Clone)]
+enum ScenarioStyle {
+    /// Every node has the same style
+    Fixed(TaffyStyle),
+    /// Every node has a pseudo-randomly chosen size (which may be auto, a length or a percentage)
+    Random,
+}
+
+fn random_dimension(rng: &mut impl Rng) -> Dimension {
+    match rng.gen_range(0.0..=1.0) {
+        rand if rand < 0.2 => Dimension::Auto,
+        rand if rand < 0.8 => Dimension::Length(rng.gen_range(0.0..500.0)),
+        _ => Dimension::Percent(rng.gen_range(0.0..1.0)),
+    }
+}
+
+impl GenStyle<TaffyStyle> for ScenarioStyle {
+    fn create_leaf_style(&mut self, rng: &mut impl Rng) -> TaffyStyle {
+        match self {
+            ScenarioStyle::Fixed(style) => style.clone(),
+            ScenarioStyle::Random => TaffyStyle {
+                size: Size { width: random_dimension(rng), height: random_dimension(rng) },
+                ..Default::default()
+            },
+        }
+    }
+    fn create_container_style(&mut self, rng: &mut impl Rng) -> TaffyStyle {
+        self.create_leaf_style(rng)
+    }
+}
+
+/// The shape of a scenario's tree
+#[derive(Clone, Copy)]
+enum Shape {
+    /// See [`BuildTreeExt::build_deep_hierarchy`]
+    Deep { node_count: u32, branching_factor: u32 },
+    /// See [`BuildTreeExt::build_flat_hierarchy`]
+    Flat { node_count: u32 },
+    /// See [`BuildTreeExt::build_super_deep_hierarchy`]
+    SuperDeep { depth: u32, nodes_per_level: u32 },
+}
+
+/// A tree which is laid out by each layout engine
+struct Scenario {
+    name: &'static str,
+    style: ScenarioStyle,
+    shape: Shape,
+}
+
+impl Scenario {
+    /// Build the scenario's tree. The same tree is built for each engine as the tree builders share a seed.
+    fn build<B: BuildTreeExt<ScenarioStyle>>(&self) -> B {
+        let mut builder = B::new(self.style.clone());
+        match self.shape {
+            Shape::Deep { node_count, branching_factor } => builder.build_deep_hierarchy(node_count, branching_factor),
+            Shape::Flat { node_count } => builder.build_flat_hierarchy(node_count),
+            Shape::SuperDeep { depth, nodes_per_level } => builder.build_super_deep_hierarchy(depth, nodes_per_level),
+        }
+        builder
+    }
+}
+
+fn scenarios() -> Vec<Scenario> {
+    vec![
+        Scenario {
+            name: "huge_nested",
+            style: ScenarioStyle::Fixed(Style { size: length(10.0), flex_grow: 1.0, ..Default::default() }),
+            shape: Shape::Deep { node_count: 1_000, branching_factor: 10 },
+        },
+        Scenario { name: "wide", style: ScenarioStyle::Random, shape: Shape::Flat { node_count: 1_000 } },
+        Scenario {
+            name: "deep_random",
+            style: ScenarioStyle::Random,
+            shape: Shape::Deep { node_count: 1_000, branching_factor: 2 },
+        },
+        Scenario {
+            name: "deep_auto",
+            style: ScenarioStyle::Fixed(Style { flex_grow: 1.0, margin: length(10.0), ..Default::default() }),
+            shape: Shape::Deep { node_count: 1_000, branching_factor: 2 },
+        },
+        Scenario {
+            name: "super_deep",
+            style: ScenarioStyle::Fixed(Style { flex_grow: 1.0, margin: length(10.0), ..Default::default() }),
+            shape: Shape::SuperDeep { depth: 20, nodes_per_level: 3 },
+        },
+    ]
+}
+
+/// The result of laying out a scenario with a single engine
+struct EngineResult {
+    /// The mean time taken to lay out the tree (excluding the time taken to build it)
+    mean_time: Duration,
+    /// The layout of each node, in the order in which the nodes were created
+    layouts: Vec<NodeLayout>,
+}
+
+/// Lay out the scenario's tree `iterations` times (rebuilding it each time so that no layout is ever cached)
+fn run_engine<B: BuildTreeExt<ScenarioStyle> + CollectLayouts>(scenario: &Scenario, iterations: u32) -> EngineResult {
+    let mut total_time = Duration::ZERO;
+    let mut layouts = Vec::new();
+    for _ in 0..iterations.max(1) {
+        let mut builder: B = scenario.build();
+        let start = Instant::now();
+        builder.compute_layout(None, None);
+        total_time += start.elapsed();
+        layouts = builder.collect_layouts();
+    }
+    EngineResult { mean_time: total_time / iterations.max(1), layouts }
+}
+
+/// A summary of how the layouts computed by two engines differ
+struct LayoutDiff {
+    /// The number of nodes which have at least one layout value differing by more than the tolerance
+    differing_nodes: usize,
+    /// The index (in creation order) of the first such node
+    first_differing_node: Option<usize>,
+    /// The largest difference between any layout value
+    max_difference: f32,
+}
+
+fn diff_layouts(expected: &[NodeLayout], actual: &[NodeLayout], tolerance: f32) -> LayoutDiff {
+    let mut diff = LayoutDiff { differing_nodes: 0, first_differing_node: None, max_difference: 0.0 };
+    for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        let difference = [
+            expected.x - actual.x,
+            expected.y - actual.y,
+            expected.width - actual.width,
+            expected.height - actual.height,
+        ]
+        .iter()
+        .fold(0.0f32, |max, value| max.max(value.abs()));
+
+        diff.max_difference = diff.max_difference.max(difference);
+        if difference > tolerance {
+            diff.differing_nodes += 1;
+            diff.first_differing_node.get_or_insert(index);
+        }
+    }
+
+    // Nodes missing from either side count as differing
+    let missing_nodes = expected.len().abs_diff(actual.len());
+    if missing_nodes > 0 {
+        diff.differing_nodes += missing_nodes;
+        diff.first_differing_node.get_or_insert(expected.len().min(actual.len()));
+    }
+
+    diff
+}
+
+fn print_diff(engine: &str, diff: &LayoutDiff, node_count: usize) {
+    match diff.first_differing_node {
+        None => println!("    {engine}: layouts match (max difference {:.3}px)", diff.max_difference),
+        Some(first) => println!(
+            "    {engine}: {} of {node_count} nodes differ (max difference {:.3}px, first differing node #{first})",
+            diff.differing_nodes, diff.max_difference,
+        ),
+    }
+}
+
+/// Read the layouts written by `chrome_layouts.sh`: one line per node (in creation order) of the form `x y width height`
+fn read_chrome_layouts(path: &Path) -> Option<Vec<NodeLayout>> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut values = line.split_whitespace().map(|value| value.parse::<f32>().ok());
+            let mut next = || values.next().flatten();
+            Some(NodeLayout { x: next()?, y: next()?, width: next()?, height: next()? })
+        })
+        .collect()
+}
+
+struct Options {
+    iterations: u32,
+    tolerance: f32,
+    export_html: Option<PathBuf>,
+    chrome: Option<PathBuf>,
+}
+
+fn parse_options() -> Options {
+    let mut options = Options { iterations: 20, tolerance: 0.5, export_html: None, chrome: None };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" => options.iterations = args.next().and_then(|n| n.parse().ok()).expect("expected a count"),
+            "--tolerance" => options.tolerance = args.next().and_then(|n| n.parse().ok()).expect("expected a length"),
+            "--export-html" => options.export_html = Some(args.next().expect("expected a directory").into()),
+            "--chrome" => options.chrome = Some(args.next().expect("expected a directory").into()),
+            // Ignore arguments passed by cargo (such as --bench)
+            _ => {}
+        }
+    }
+    options
+}
+
+fn main() {
+    let options = parse_options();
+    if let Some(dir) = &options.export_html {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+
+    for scenario in scenarios() {
+        let taffy = run_engine::<TaffyTreeBuilder<ChaCha8Rng, ScenarioStyle>>(&scenario, options.iterations);
+        let node_count = taffy.layouts.len();
+        println!("{} ({node_count} nodes)", scenario.name);
+        println!("    Taffy: {:?}", taffy.mean_time);
+
+        #[cfg(feature = "yoga")]
+        {
+            let yoga = run_engine::<YogaTreeBuilder<ChaCha8Rng, ScenarioStyle>>(&scenario, options.iterations);
+            let speedup = yoga.mean_time.as_secs_f64() / taffy.mean_time.as_secs_f64();
+            println!("    Yoga: {:?} (Taffy is {speedup:.2}x as fast)", yoga.mean_time);
+            print_diff("Yoga", &diff_layouts(&yoga.layouts, &taffy.layouts, options.tolerance), node_count);
+        }
+
+        if let Some(dir) = &options.export_html {
+            let builder: TaffyTreeBuilder<ChaCha8Rng, ScenarioStyle> = scenario.build();
+            let path = dir.join(format!("{}.html", scenario.name));
+            std::fs::write(&path, html::tree_to_html(builder.tree(), builder.root(), builder.nodes())).unwrap();
+            println!("    Exported to {}", path.display());
+        }
+
+        if let Some(dir) = &options.chrome {
+            let path = dir.join(format!("{}.txt", scenario.name));
+            match read_chrome_layouts(&path) {
+                Some(chrome) => {
+                    print_diff("Chrome", &diff_layouts(&chrome, &taffy.layouts, options.tolerance), node_count)
+                }
+                None => println!("    Chrome: no valid layouts found at {}", path.display()),
+            }
+        }
+    }
+}
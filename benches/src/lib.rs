@@ -39,6 +39,21 @@ impl GenStyle<TaffyStyle> for FixedStyleGenerator {
     }
 }
 
+/// The computed layout of a single node, relative to its parent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeLayout {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A tree builder whose computed layout can be read back, so that the output of different layout libraries can be compared
+pub trait CollectLayouts {
+    /// The layout of every node in the tree, in the order in which the nodes were created (starting with the root)
+    fn collect_layouts(&self) -> Vec<NodeLayout>;
+}
+
 pub trait BuildTree<R: Rng, G: GenStyle<TaffyStyle>> {
     const NAME: &'static str;
     type Tree;
@@ -5,13 +5,15 @@ use taffy::style::Style as TaffyStyle;
 use taffy::tree::NodeId as TaffyNodeId;
 use taffy::TaffyTree;
 
-use super::{BuildTree, BuildTreeExt, GenStyle};
+use super::{BuildTree, BuildTreeExt, CollectLayouts, GenStyle, NodeLayout};
 
 pub struct TaffyTreeBuilder<R: Rng, G: GenStyle<TaffyStyle>> {
     rng: R,
     style_generator: G,
     tree: TaffyTree,
     root: TaffyNodeId,
+    /// Every node in the tree, in the order in which they were created
+    nodes: Vec<TaffyNodeId>,
 }
 
 // Implement the BuildTree trait
@@ -23,7 +25,7 @@ impl<R: Rng, G: GenStyle<TaffyStyle>> BuildTree<R, G> for TaffyTreeBuilder<R, G>
     fn with_rng(mut rng: R, mut style_generator: G) -> Self {
         let mut tree = TaffyTree::new();
         let root = tree.new_leaf(style_generator.create_root_style(&mut rng)).unwrap();
-        TaffyTreeBuilder { rng, style_generator, tree, root }
+        TaffyTreeBuilder { rng, style_generator, tree, root, nodes: vec![root] }
     }
 
     fn compute_layout_inner(&mut self, available_width: Option<f32>, available_height: Option<f32>) {
@@ -37,12 +39,16 @@ impl<R: Rng, G: GenStyle<TaffyStyle>> BuildTree<R, G> for TaffyTreeBuilder<R, G>
 
     fn create_leaf_node(&mut self) -> Self::Node {
         let style = self.style_generator.create_leaf_style(&mut self.rng);
-        self.tree.new_leaf(style).unwrap()
+        let node = self.tree.new_leaf(style).unwrap();
+        self.nodes.push(node);
+        node
     }
 
     fn create_container_node(&mut self, children: &[Self::Node]) -> Self::Node {
         let style = self.style_generator.create_container_style(&mut self.rng);
-        self.tree.new_with_children(style, children).unwrap()
+        let node = self.tree.new_with_children(style, children).unwrap();
+        self.nodes.push(node);
+        node
     }
 
     fn total_node_count(&mut self) -> usize {
@@ -59,3 +65,37 @@ impl<R: Rng, G: GenStyle<TaffyStyle>> BuildTree<R, G> for TaffyTreeBuilder<R, G>
 }
 
 impl<G: GenStyle<TaffyStyle>> BuildTreeExt<G> for TaffyTreeBuilder<ChaCha8Rng, G> {}
+
+impl<R: Rng, G: GenStyle<TaffyStyle>> TaffyTreeBuilder<R, G> {
+    /// The tree being built
+    pub fn tree(&self) -> &TaffyTree {
+        &self.tree
+    }
+
+    /// The root node of the tree being built
+    pub fn root(&self) -> TaffyNodeId {
+        self.root
+    }
+
+    /// Every node in the tree, in the order in which they were created
+    pub fn nodes(&self) -> &[TaffyNodeId] {
+        &self.nodes
+    }
+}
+
+impl<R: Rng, G: GenStyle<TaffyStyle>> CollectLayouts for TaffyTreeBuilder<R, G> {
+    fn collect_layouts(&self) -> Vec<NodeLayout> {
+        self.nodes
+            .iter()
+            .map(|&node| {
+                let layout = self.tree.layout(node).unwrap();
+                NodeLayout {
+                    x: layout.location.x,
+                    y: layout.location.y,
+                    width: layout.size.width,
+                    height: layout.size.height,
+                }
+            })
+            .collect()
+    }
+}
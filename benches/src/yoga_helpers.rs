@@ -5,7 +5,7 @@ use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use slotmap::{DefaultKey, SlotMap};
 
-use super::{BuildTree, BuildTreeExt, GenStyle};
+use super::{BuildTree, BuildTreeExt, CollectLayouts, GenStyle, NodeLayout};
 
 pub mod yg {
     pub use ordered_float::OrderedFloat;
@@ -77,6 +77,21 @@ impl<R: Rng, G: GenStyle<TaffyStyle>> BuildTree<R, G> for YogaTreeBuilder<R, G>
 
 impl<G: GenStyle<TaffyStyle>> BuildTreeExt<G> for YogaTreeBuilder<ChaCha8Rng, G> {}
 
+impl<R: Rng, G: GenStyle<TaffyStyle>> CollectLayouts for YogaTreeBuilder<R, G> {
+    fn collect_layouts(&self) -> Vec<NodeLayout> {
+        // Nodes are never removed from the slotmap, so iterating over it visits them in the order they were created
+        self.tree
+            .values()
+            .map(|node| NodeLayout {
+                x: node.get_layout_left(),
+                y: node.get_layout_top(),
+                width: node.get_layout_width(),
+                height: node.get_layout_height(),
+            })
+            .collect()
+    }
+}
+
 // impl<R: Rng, G: GenStyle<TaffyStyle>> YogaTreeBuilder<R, G> {
 //     /// Create a YogaTreeBuilder with a standard rng from a style generator
 //     fn new<NG: GenStyle<TaffyStyle>>(mut style_generator: NG) -> YogaTreeBuilder<ChaCha8Rng, NG> {
@@ -132,6 +147,9 @@ fn into_yg_units(dim: impl Into<tf::Dimension>) -> yg::StyleUnit {
         tf::Dimension::Auto => yg::StyleUnit::Auto,
         tf::Dimension::Length(val) => yg::StyleUnit::Point(yg::OrderedFloat(val)),
         tf::Dimension::Percent(val) => yg::StyleUnit::Percent(yg::OrderedFloat(val)),
+        tf::Dimension::Content => yg::StyleUnit::Auto,
+        // Yoga has no equivalent of percentages of the root's size, and they aren't generated by the benchmarks
+        tf::Dimension::RootPercent(_) => yg::StyleUnit::Auto,
     }
 }
 
@@ -149,6 +167,7 @@ fn items_into_align(align: Option<tf::AlignSelf>) -> yg::Align {
         Some(tf::AlignSelf::Stretch) => yg::Align::Stretch,
         Some(tf::AlignSelf::Start) => unimplemented!(),
         Some(tf::AlignSelf::End) => unimplemented!(),
+        Some(tf::AlignSelf::LastBaseline) => panic!("Yoga does not support last baseline alignment"),
     }
 }
 
@@ -6,6 +6,10 @@ fn main() {
         .input_extern_file("src/tree.rs")
         .input_extern_file("src/value.rs")
         .input_extern_file("src/error.rs")
+        .input_extern_file("src/allocator.rs")
+        .input_extern_file("src/version.rs")
+        .input_extern_file("src/panic.rs")
+        .input_extern_file("src/log.rs")
         .csharp_dll_name("primrose_rust")
         .csharp_dll_name_if("PRIMROSE_IOS", "__Internal")
         .csharp_namespace("Primrose.Native.taffy")
@@ -0,0 +1,41 @@
+//! Allocator hook allowing the embedder to route all of Taffy's heap allocations through their own allocator
+//!
+//! This module is compiled in when the `allocator_hook` feature is enabled, and always when the `std` feature is
+//! disabled. It only installs [`taffy::HookAllocator`] as the `#[global_allocator]` with the `global_allocator` feature:
+//! a library must not install a global allocator when it is linked into a Rust binary which has its own, so that is left
+//! to the builds of the static and dynamic libraries which are linked into C programs.
+
+use super::TaffyReturnCode;
+use ::core::ffi::c_void;
+use taffy::set_allocator_hook;
+#[cfg(feature = "global_allocator")]
+use taffy::HookAllocator;
+
+pub use taffy::{AllocFunction as TaffyAllocFunction, FreeFunction as TaffyFreeFunction};
+
+#[cfg(feature = "global_allocator")]
+#[global_allocator]
+static GLOBAL: HookAllocator = HookAllocator;
+
+/// Set the functions used by Taffy to allocate and free memory
+///
+/// Must be called before any other Taffy function (which may allocate). Once memory has been allocated the allocator
+/// can no longer be changed and `TAFFY_RETURN_CODE_ALLOCATOR_IN_USE` is returned. Passing null for both functions
+/// restores the default allocator. Passing null for only one of them returns `TAFFY_RETURN_CODE_INVALID_ALLOCATOR`.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn Taffy_SetAllocator(
+    alloc_function: Option<TaffyAllocFunction>,
+    free_function: Option<TaffyFreeFunction>,
+    user_data: *mut c_void,
+) -> TaffyReturnCode {
+    let functions = match (alloc_function, free_function) {
+        (Some(alloc_fn), Some(free_fn)) => Some((alloc_fn, free_fn)),
+        (None, None) => None,
+        _ => return TaffyReturnCode::InvalidAllocator,
+    };
+    match set_allocator_hook(functions, user_data) {
+        Ok(()) => TaffyReturnCode::Ok,
+        Err(_) => TaffyReturnCode::AllocatorInUse,
+    }
+}
@@ -46,6 +46,19 @@ macro_rules! try_or {
     };
 }
 
+/// Unwraps the result of a [`taffy::TaffyTree`] method, or returns the [`TaffyReturnCode`] corresponding to its error
+#[macro_export]
+macro_rules! try_tree {
+    ($block:expr) => {
+        match { $block } {
+            Ok(val) => val,
+            Err(error) => {
+                return TaffyFFIResult::from_return_code(TaffyReturnCode::from(error));
+            }
+        }
+    };
+}
+
 pub(crate) trait TaffyFFIResult {
     type Value;
     fn from_value(value: Self::Value) -> Self;
@@ -75,7 +88,7 @@ pub enum TaffyReturnCode {
     NullStylePointer,
     /// The tree pointer passed was null
     NullTreePointer,
-    /// The node referenced by the node id passed does not exist
+    /// The node referenced by the node id passed does not exist (for example, because it has been removed)
     InvalidNodeId,
     /// An enum value was specified that was outside the range of valid value for this enum
     InvalidEnumValue,
@@ -103,6 +116,52 @@ pub enum TaffyReturnCode {
     UnexpectedInfinity,
     /// A negative value was specified but is not valid in this context
     UnexpectedNegative,
+    /// The allocator could not be changed because memory has already been allocated with the current allocator
+    AllocatorInUse,
+    /// Only one of the allocation and free functions was provided
+    InvalidAllocator,
+    /// The string pointer passed was null
+    NullStringPointer,
+    /// A grid placement was specified with a zero line, a non-positive span, or an end line which is not after the start line
+    InvalidGridPlacement,
+    /// A named grid line was specified, but named grid lines are not yet supported
+    UnsupportedNamedGridLine,
+    /// A string was not valid UTF-16
+    InvalidUtf16,
+    /// A grid track list string could not be parsed
+    InvalidTrackList,
+    /// An array pointer passed was null
+    NullArrayPointer,
+    /// A parent index was specified which does not refer to an earlier node
+    InvalidParentIndex,
+    /// The node id passed was created by a different tree (only detected when built with the `node_id_brand` feature)
+    ForeignNodeId,
+    /// The tree being laid out is deeper than the maximum depth set with `TaffyTree_SetMaxDepth`
+    MaxDepthExceeded,
+    /// A Content unit was specified but is not valid in this context
+    InvalidContent,
+    /// A display mode was specified whose layout algorithm was not compiled into the library
+    UnsupportedDisplay,
+    /// Taffy panicked. The panic message can be retrieved with `Taffy_GetLastPanicMessage`.
+    Panicked,
+    /// A child index was specified which is past the end of the node's children
+    ChildIndexOutOfBounds,
+    /// A RootPercent unit was specified but is not valid in this context
+    InvalidRootPercent,
+    /// A state variant of 64 or more was specified
+    InvalidVariant,
+}
+
+impl From<taffy::TaffyError> for TaffyReturnCode {
+    fn from(error: taffy::TaffyError) -> Self {
+        match error {
+            taffy::TaffyError::ForeignNode(_) => TaffyReturnCode::ForeignNodeId,
+            taffy::TaffyError::MaxDepthExceeded(_) => TaffyReturnCode::MaxDepthExceeded,
+            taffy::TaffyError::ChildIndexOutOfBounds { .. } => TaffyReturnCode::ChildIndexOutOfBounds,
+            taffy::TaffyError::InvalidParentIndex { .. } => TaffyReturnCode::InvalidParentIndex,
+            _ => TaffyReturnCode::InvalidNodeId,
+        }
+    }
 }
 
 impl TaffyFFIResult for TaffyReturnCode {
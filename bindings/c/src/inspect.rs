@@ -0,0 +1,309 @@
+//! JSON serialization of node inspections and style validation reports for C FFI
+
+use ::core::fmt::{Debug, Write};
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use taffy::prelude as core;
+#[cfg(feature = "grid")]
+use taffy::GridOccupancyStats;
+use taffy::{CacheState, Layout, NodeId, NodeInspection, Point, ResolvedStyle, StyleWarning};
+
+/// Serializes a [`NodeInspection`] as a JSON object
+pub(crate) fn inspection_to_json(inspection: &NodeInspection) -> String {
+    let mut json = JsonWriter::default();
+    json.begin_object();
+    json.key("node").integer(inspection.node.into());
+    json.key("label").string(inspection.label);
+    match inspection.parent {
+        Some(parent) => json.key("parent").integer(parent.into()),
+        None => json.key("parent").null(),
+    };
+    json.key("children").begin_array();
+    for child in &inspection.children {
+        json.integer((*child).into());
+    }
+    json.end_array();
+    json.key("algorithm").debug(&inspection.algorithm);
+    json.key("style");
+    write_style(&mut json, &inspection.style);
+    json.key("resolved_style");
+    write_resolved_style(&mut json, &inspection.resolved_style);
+    json.key("layout");
+    write_layout(&mut json, &inspection.layout);
+    json.key("unrounded_layout");
+    write_layout(&mut json, &inspection.unrounded_layout);
+    json.key("cache");
+    write_cache_state(&mut json, &inspection.cache);
+    json.key("grid_occupancy");
+    #[cfg(feature = "grid")]
+    match &inspection.grid_occupancy {
+        Some(stats) => write_grid_occupancy_stats(&mut json, stats),
+        None => {
+            json.null();
+        }
+    }
+    #[cfg(not(feature = "grid"))]
+    json.null();
+    json.end_object();
+    json.output
+}
+
+/// Serializes the warnings returned by [`TaffyTree::validate_tree`](taffy::TaffyTree::validate_tree) as a JSON array
+/// of objects with the node, property and a description of each warning
+pub(crate) fn validation_to_json(warnings: &[(NodeId, StyleWarning)]) -> String {
+    let mut json = JsonWriter::default();
+    json.begin_array();
+    for (node, warning) in warnings {
+        json.begin_object();
+        json.key("node").integer((*node).into());
+        json.key("property").string(warning.property());
+        let mut message = String::new();
+        let _ = write!(message, "{warning}");
+        json.key("message").string(&message);
+        json.end_object();
+    }
+    json.end_array();
+    json.output
+}
+
+fn write_style(json: &mut JsonWriter, style: &core::Style) {
+    json.begin_object();
+    json.key("display").debug(&style.display);
+    json.key("position").debug(&style.position);
+    json.key("direction").debug(&style.direction);
+    json.key("overflow").debug_point(style.overflow);
+    json.key("size").debug_size(style.size);
+    json.key("min_size").debug_size(style.min_size);
+    json.key("max_size").debug_size(style.max_size);
+    json.key("aspect_ratio").option(style.aspect_ratio);
+    json.key("inset").debug_rect(style.inset);
+    json.key("margin").debug_rect(style.margin);
+    json.key("padding").debug_rect(style.padding);
+    json.key("border").debug_rect(style.border);
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    {
+        json.key("gap").debug_size(style.gap);
+        json.key("align_items").debug(&style.align_items);
+        json.key("align_self").debug(&style.align_self);
+        json.key("align_content").debug(&style.align_content);
+        json.key("justify_content").debug(&style.justify_content);
+    }
+    #[cfg(feature = "flexbox")]
+    {
+        json.key("flex_direction").debug(&style.flex_direction);
+        json.key("flex_wrap").debug(&style.flex_wrap);
+        json.key("flex_basis").debug(&style.flex_basis);
+        json.key("flex_grow").number(style.flex_grow);
+        json.key("flex_shrink").number(style.flex_shrink);
+    }
+    #[cfg(feature = "grid")]
+    {
+        json.key("justify_items").debug(&style.justify_items);
+        json.key("justify_self").debug(&style.justify_self);
+        json.key("grid_auto_flow").debug(&style.grid_auto_flow);
+        json.key("grid_row").debug(&style.grid_row);
+        json.key("grid_column").debug(&style.grid_column);
+    }
+    json.end_object();
+}
+
+fn write_resolved_style(json: &mut JsonWriter, style: &ResolvedStyle) {
+    json.begin_object();
+    json.key("direction").debug(&style.direction);
+    json.key("size").option_size(style.size);
+    json.key("min_size").option_size(style.min_size);
+    json.key("max_size").option_size(style.max_size);
+    json.key("inset").option_rect(style.inset);
+    json.key("margin").option_rect(style.margin);
+    json.key("padding").option_rect(style.padding.map(Some));
+    json.key("border").option_rect(style.border.map(Some));
+    json.end_object();
+}
+
+fn write_layout(json: &mut JsonWriter, layout: &Layout) {
+    json.begin_object();
+    json.key("order").integer(layout.order.into());
+    json.key("x").number(layout.location.x);
+    json.key("y").number(layout.location.y);
+    json.key("size").option_size(layout.size.map(Some));
+    json.key("content_size").option_size(layout.content_size.map(Some));
+    json.key("scrollbar_size").option_size(layout.scrollbar_size.map(Some));
+    json.key("margin").option_rect(layout.margin.map(Some));
+    json.key("padding").option_rect(layout.padding.map(Some));
+    json.key("border").option_rect(layout.border.map(Some));
+    json.key("first_baseline").number(layout.first_baseline.unwrap_or(f32::NAN));
+    json.key("scroll_origin_shift_x").number(layout.scroll_origin_shift.x);
+    json.key("scroll_origin_shift_y").number(layout.scroll_origin_shift.y);
+    json.end_object();
+}
+
+fn write_cache_state(json: &mut JsonWriter, cache: &CacheState) {
+    json.begin_object();
+    json.key("is_dirty").boolean(cache.is_dirty);
+    json.key("has_final_layout").boolean(cache.has_final_layout);
+    json.key("measure_entry_count").integer(cache.measure_entry_count as u64);
+    json.end_object();
+}
+
+#[cfg(feature = "grid")]
+fn write_grid_occupancy_stats(json: &mut JsonWriter, stats: &GridOccupancyStats) {
+    json.begin_object();
+    json.key("row_count").integer(stats.row_count.into());
+    json.key("column_count").integer(stats.column_count.into());
+    json.key("occupied_cell_count").integer(stats.occupied_cell_count.into());
+    json.key("interval_count").integer(stats.interval_count.into());
+    json.key("allocated_bytes").integer(stats.allocated_bytes as u64);
+    json.end_object();
+}
+
+/// A minimal JSON writer which takes care of separators between values
+#[derive(Default)]
+struct JsonWriter {
+    output: String,
+    /// Whether the next value is the first in the current object or array
+    is_first: bool,
+    /// Whether a key has just been written (and so the next value should not be preceded by a separator)
+    after_key: bool,
+}
+
+impl JsonWriter {
+    fn separator(&mut self) {
+        if self.after_key {
+            self.after_key = false;
+        } else if !self.is_first && !self.output.is_empty() {
+            self.output.push(',');
+        }
+        self.is_first = false;
+    }
+
+    fn begin_object(&mut self) -> &mut Self {
+        self.separator();
+        self.output.push('{');
+        self.is_first = true;
+        self
+    }
+
+    fn end_object(&mut self) -> &mut Self {
+        self.output.push('}');
+        self.is_first = false;
+        self
+    }
+
+    fn begin_array(&mut self) -> &mut Self {
+        self.separator();
+        self.output.push('[');
+        self.is_first = true;
+        self
+    }
+
+    fn end_array(&mut self) -> &mut Self {
+        self.output.push(']');
+        self.is_first = false;
+        self
+    }
+
+    fn key(&mut self, key: &str) -> &mut Self {
+        self.string(key);
+        self.output.push(':');
+        self.after_key = true;
+        self
+    }
+
+    fn null(&mut self) -> &mut Self {
+        self.separator();
+        self.output.push_str("null");
+        self
+    }
+
+    fn boolean(&mut self, value: bool) -> &mut Self {
+        self.separator();
+        self.output.push_str(if value { "true" } else { "false" });
+        self
+    }
+
+    /// Writes a number. Non-finite numbers can't be represented in JSON and are written as null.
+    fn number(&mut self, value: f32) -> &mut Self {
+        if !value.is_finite() {
+            return self.null();
+        }
+        self.separator();
+        let _ = write!(self.output, "{value}");
+        self
+    }
+
+    fn integer(&mut self, value: u64) -> &mut Self {
+        self.separator();
+        let _ = write!(self.output, "{value}");
+        self
+    }
+
+    fn option(&mut self, value: Option<f32>) -> &mut Self {
+        match value {
+            Some(value) => self.number(value),
+            None => self.null(),
+        }
+    }
+
+    fn string(&mut self, value: &str) -> &mut Self {
+        self.separator();
+        self.output.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                c if (c as u32) < 0x20 => {
+                    let _ = write!(self.output, "\\u{:04x}", c as u32);
+                }
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+        self
+    }
+
+    /// Writes the `Debug` representation of a value as a string
+    fn debug(&mut self, value: &impl Debug) -> &mut Self {
+        let mut formatted = String::new();
+        let _ = write!(formatted, "{value:?}");
+        self.string(&formatted)
+    }
+
+    fn debug_point<T: Debug>(&mut self, value: Point<T>) -> &mut Self {
+        self.begin_object();
+        self.key("x").debug(&value.x);
+        self.key("y").debug(&value.y);
+        self.end_object()
+    }
+
+    fn debug_size<T: Debug>(&mut self, value: core::Size<T>) -> &mut Self {
+        self.begin_object();
+        self.key("width").debug(&value.width);
+        self.key("height").debug(&value.height);
+        self.end_object()
+    }
+
+    fn debug_rect<T: Debug>(&mut self, value: core::Rect<T>) -> &mut Self {
+        self.begin_object();
+        self.key("left").debug(&value.left);
+        self.key("right").debug(&value.right);
+        self.key("top").debug(&value.top);
+        self.key("bottom").debug(&value.bottom);
+        self.end_object()
+    }
+
+    fn option_size(&mut self, value: core::Size<Option<f32>>) -> &mut Self {
+        self.begin_object();
+        self.key("width").option(value.width);
+        self.key("height").option(value.height);
+        self.end_object()
+    }
+
+    fn option_rect(&mut self, value: core::Rect<Option<f32>>) -> &mut Self {
+        self.begin_object();
+        self.key("left").option(value.left);
+        self.key("right").option(value.right);
+        self.key("top").option(value.top);
+        self.key("bottom").option(value.bottom);
+        self.end_object()
+    }
+}
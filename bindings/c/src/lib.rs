@@ -1,26 +1,68 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(any(feature = "allocator_hook", not(feature = "std")))]
+mod allocator;
 mod error;
+mod inspect;
+mod log;
+mod panic;
 mod style;
 mod style_enums;
 mod tree;
 mod value;
+mod version;
 
-pub struct TaffyStyle;
-pub type TaffyStyleMutRef = *mut TaffyStyle;
-pub type TaffyStyleConstRef = *const TaffyStyle;
-
-impl TaffyFFIDefault for TaffyStyleMutRef {
-    fn default() -> Self {
-        core::ptr::null_mut()
-    }
+/// A handle to the style of a node in a `TaffyTree`, as returned by `TaffyTree_GetStyleMut`
+///
+/// The handle refers to the node by id rather than pointing into the tree's storage, so it stays valid when nodes are
+/// added to the tree. Node ids include a generation, so a handle to a node which has been removed is detected even if
+/// its slot has been reused: setters return `InvalidNodeId` and getters return the default value of the property.
+///
+/// Setting a style property through the handle replaces the node's style with an edited copy, and marks the node dirty
+/// if the property changed, so there is no need to call `TaffyTree_MarkDirty` after editing a style.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TaffyStyleRef {
+    pub tree: TaffyTreeMutRef,
+    pub node_id: TaffyNodeId,
 }
-impl TaffyFFIDefault for TaffyStyleConstRef {
+pub type TaffyStyleMutRef = TaffyStyleRef;
+pub type TaffyStyleConstRef = TaffyStyleRef;
+
+/// A null-terminated UTF-8 string allocated by Taffy. Must be freed with `TaffyString_Free`.
+pub type TaffyStringOwnedRef = *mut ::core::ffi::c_char;
+
+impl TaffyFFIDefault for TaffyStyleRef {
     fn default() -> Self {
-        core::ptr::null()
+        TaffyStyleRef { tree: core::ptr::null_mut(), node_id: TaffyNodeId::default() }
     }
 }
 
+#[cfg(any(feature = "allocator_hook", not(feature = "std")))]
+pub use allocator::*;
 pub use error::*;
+pub use log::{TaffyLogFunction, TaffyLogLevel};
+pub use panic::Taffy_GetLastPanicMessage;
 pub use style::*;
 pub use style_enums::*;
 pub use tree::*;
 pub use value::*;
+pub use version::*;
+
+#[cfg(all(not(feature = "std"), not(test)))]
+extern "C" {
+    /// The C standard library's `abort`, which every C caller of the library links against
+    fn abort() -> !;
+}
+
+#[cfg(all(not(feature = "std"), not(test)))]
+#[panic_handler]
+fn panic(_info: &::core::panic::PanicInfo) -> ! {
+    // no_std builds are compiled with `panic = "abort"`, so there is nothing to unwind. Abort the process (rather than
+    // hanging the calling thread), as a `std` build would.
+    // SAFETY: `abort` takes no arguments and never returns
+    unsafe { abort() }
+}
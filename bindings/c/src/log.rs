@@ -0,0 +1,59 @@
+//! Routing Taffy's diagnostics to an embedder-provided logging function
+//!
+//! Shipped applications typically have no visible stderr, so rather than printing warnings Taffy passes the diagnostics
+//! of each tree to the function set for it with `TaffyTree_SetLogCallback` (and discards them if none has been set).
+//! Diagnostics are only formatted, and the checks which produce them only run, while a callback is set with a maximum
+//! level which includes them.
+
+use ::core::ffi::{c_char, c_void};
+#[cfg(not(feature = "std"))]
+use alloc::{ffi::CString, string::String};
+#[cfg(feature = "std")]
+use std::ffi::CString;
+
+/// The severity of a logged message. Each level includes the levels before it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaffyLogLevel {
+    /// Taffy panicked, so the operation which was being performed did not complete
+    Error,
+    /// A problem which layout worked around, but which is likely a mistake (such as a measure function which returned
+    /// a NaN size)
+    Warning,
+    /// A problem found by a check which is too expensive to run on every layout of a shipped build: a style property
+    /// which has no effect (as reported by `TaffyTree_ValidateTree`), or a measure function which returned different
+    /// sizes when called twice with the same inputs (so that layouts cached from its results are inconsistent)
+    Debug,
+}
+
+/// Receives a message logged by Taffy. `message` is a null-terminated UTF-8 string which is only valid for the duration
+/// of the call.
+pub type TaffyLogFunction = extern "C" fn(level: TaffyLogLevel, message: *const c_char, user_data: *mut c_void);
+
+/// An embedder-provided log function, along with the user data passed to it and the most verbose level of messages
+/// passed to it. Set for a tree with `TaffyTree_SetLogCallback`.
+#[derive(Clone, Copy)]
+pub(crate) struct Logger {
+    /// The function which messages are passed to
+    pub(crate) function: TaffyLogFunction,
+    /// The pointer passed to every call of `function`
+    pub(crate) user_data: *mut c_void,
+    /// The most verbose level of messages which are passed to `function`
+    pub(crate) max_level: TaffyLogLevel,
+}
+
+/// Whether messages of `level` are passed to `logger`. Checks which only exist to produce such messages should be
+/// skipped if not.
+pub(crate) fn is_logging(logger: Option<Logger>, level: TaffyLogLevel) -> bool {
+    matches!(logger, Some(logger) if level <= logger.max_level)
+}
+
+/// Pass the message produced by `message` to the log function of `logger`, if there is one and messages of `level` are
+/// passed to it
+pub(crate) fn log(logger: Option<Logger>, level: TaffyLogLevel, message: impl FnOnce() -> String) {
+    if let Some(logger) = logger.filter(|logger| level <= logger.max_level) {
+        // Interior nul bytes can't be represented in a C string, so replace them
+        let message = CString::new(message().replace('\0', "\u{FFFD}")).unwrap_or_default();
+        (logger.function)(level, message.as_ptr(), logger.user_data);
+    }
+}
@@ -0,0 +1,98 @@
+//! Catching panics at the FFI boundary
+//!
+//! A panic must not unwind out of an `extern "C"` function: Rust aborts the process if it tries to, which would take
+//! down the whole host application. So every entry point runs its body through [`catch_panic`], which turns a panic into
+//! a [`TaffyReturnCode::Panicked`] return code and stores the panic message for `Taffy_GetLastPanicMessage` to retrieve.
+//! The message is also logged as an error to the logger of the tree which the entry point was called on (if any).
+//!
+//! Without the `std` feature there is no unwinding to catch (`no_std` builds must be compiled with `panic = "abort"`),
+//! so the body is simply called.
+
+use super::{TaffyFFIResult, TaffyReturnCode, TaffyStringOwnedRef};
+use crate::log::Logger;
+
+#[cfg(feature = "std")]
+mod imp {
+    use crate::log::{log, Logger};
+    use crate::TaffyLogLevel;
+    use std::any::Any;
+    use std::cell::RefCell;
+    use std::ffi::CString;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    thread_local! {
+        /// The message of the last panic caught on this thread which has not yet been retrieved
+        static LAST_PANIC_MESSAGE: RefCell<Option<CString>> = const { RefCell::new(None) };
+    }
+
+    /// Store the message of a caught panic so that it can be retrieved with `Taffy_GetLastPanicMessage`, and log it to
+    /// `logger`
+    fn record_panic(payload: Box<dyn Any + Send>, logger: Option<Logger>) {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => String::from(*message),
+                Err(_) => String::from("Box<dyn Any>"),
+            },
+        };
+        // Interior nul bytes can't be represented in a C string, so replace them
+        log(logger, TaffyLogLevel::Error, || format!("Taffy panicked: {message}"));
+        let message = CString::new(message.replace('\0', "\u{FFFD}")).unwrap_or_default();
+        LAST_PANIC_MESSAGE.with(|last| *last.borrow_mut() = Some(message));
+    }
+
+    /// Call `f`, or `fallback` if `f` panics (logging the panic to `logger`)
+    pub(crate) fn catch_panic_or_else<R>(
+        logger: Option<Logger>,
+        f: impl FnOnce() -> R,
+        fallback: impl FnOnce() -> R,
+    ) -> R {
+        match catch_unwind(AssertUnwindSafe(f)) {
+            Ok(value) => value,
+            Err(payload) => {
+                record_panic(payload, logger);
+                fallback()
+            }
+        }
+    }
+
+    /// Take the message of the last panic caught on this thread
+    pub(crate) fn take_last_panic_message() -> Option<CString> {
+        LAST_PANIC_MESSAGE.with(|last| last.borrow_mut().take())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    use crate::log::Logger;
+    use alloc::ffi::CString;
+
+    /// Call `f`. Panics abort in `no_std` builds, so `fallback` is never called.
+    pub(crate) fn catch_panic_or_else<R>(
+        _logger: Option<Logger>,
+        f: impl FnOnce() -> R,
+        _fallback: impl FnOnce() -> R,
+    ) -> R {
+        f()
+    }
+
+    /// No panic is ever caught in `no_std` builds
+    pub(crate) fn take_last_panic_message() -> Option<CString> {
+        None
+    }
+}
+
+pub(crate) use imp::catch_panic_or_else;
+
+/// Call `f`, returning [`TaffyReturnCode::Panicked`] if it panics (logging the panic to `logger`)
+pub(crate) fn catch_panic<R: TaffyFFIResult>(logger: Option<Logger>, f: impl FnOnce() -> R) -> R {
+    catch_panic_or_else(logger, f, || R::from_return_code(TaffyReturnCode::Panicked))
+}
+
+/// Get the message of the last panic caught on the calling thread (which the function that panicked reported by
+/// returning `TAFFY_RETURN_CODE_PANICKED`), or null if no panic has been caught since the message was last retrieved.
+/// Retrieving the message clears it. The returned string must be freed with `TaffyString_Free`.
+#[no_mangle]
+pub extern "C" fn Taffy_GetLastPanicMessage() -> TaffyStringOwnedRef {
+    imp::take_last_panic_message().map(|message| message.into_raw()).unwrap_or(::core::ptr::null_mut())
+}
@@ -1,41 +1,73 @@
 //! Public API for C FFI
 
-use widestring::U16Str;
 use super::{
-    debug_assert_non_null, TaffyAlignContent, TaffyAlignItems, TaffyDimension, TaffyDisplay, TaffyEdge,
-    TaffyFlexDirection, TaffyFlexWrap, TaffyGridAutoFlow, TaffyGridPlacement, TaffyOverflow, TaffyPosition,
-    TaffyReturnCode, TaffyStyleConstRef, TaffyStyleMutRef, TaffyUnit,
+    bail_if_null, debug_assert_non_null, try_tree, TaffyDimension, TaffyDisplay, TaffyEdge, TaffyFFIResult,
+    TaffyOverflow, TaffyPosition, TaffyReturnCode, TaffyStyleConstRef, TaffyStyleMutRef, TaffyTree, TaffyUnit,
 };
-use taffy::{prelude as core, TrackSizingFunction};
-
-/// Assert that the passed raw style pointer is non-null
-/// Then give the passed expression access to the value of the inner [`core::Style`] struct pointed to by the raw style pointer
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use super::{TaffyAlignContent, TaffyAlignItems};
+#[cfg(feature = "flexbox")]
+use super::{TaffyFlexDirection, TaffyFlexWrap};
+#[cfg(feature = "grid")]
+use super::{TaffyGridAutoFlow, TaffyGridPlacement};
+use crate::panic::{catch_panic, catch_panic_or_else};
+#[cfg(all(feature = "grid", not(feature = "std")))]
+use alloc::{string::String, vec::Vec};
+use taffy::prelude as core;
+
+/// The style read by getters when a style handle refers to a node which no longer exists
+static DEFAULT_STYLE: core::Style = core::Style::DEFAULT;
+
+/// Assert that the tree pointer of the passed style handle is non-null
+/// Then give the passed expression access to the value of the [`core::Style`] of the node referred to by the style handle
+/// (or the default style if the node has been removed)
 /// Return whatever the expression evaluates to wrapped in a [`TaffyDimensionResult`] if the expression does not interally return.
+/// If the expression panics, return what it evaluates to for the default style instead.
 macro_rules! get_style {
-    ($raw_style_ptr:expr, $style_ident:ident, $block:expr) => {{
-        debug_assert_non_null!($raw_style_ptr);
-        let $style_ident = unsafe { &*($raw_style_ptr as *const core::Style) };
-
-        let return_value = $block;
-
-        return_value.into()
+    ($raw_style:expr, $style_ident:ident, $block:expr) => {{
+        debug_assert_non_null!($raw_style.tree);
+        let tree = unsafe { &*($raw_style.tree as *const TaffyTree) };
+        let style = tree.inner.style($raw_style.node_id.into()).unwrap_or(&DEFAULT_STYLE);
+
+        let read = |$style_ident: &core::Style| {
+            let return_value = $block;
+            return_value.into()
+        };
+        catch_panic_or_else(tree.logger, || read(style), || read(&DEFAULT_STYLE))
     }};
 }
 
-/// Assert that the passed raw style pointer is non-null
-/// Then give the passed expression mutable access to the value of the inner [`core::Style`] struct pointed to by the raw style pointer
-/// Return [`TaffyReturnCode::Ok`] if the expression does not internally return.
+/// Check that the tree pointer of the passed style handle is non-null
+/// Then edit the [`core::Style`] of the node referred to by the style handle in place with [`core::TaffyTree::edit_style`],
+/// either by assigning a single property (whose value is computed first, and may return a [`TaffyReturnCode`]) or with
+/// a closure returning whether it changed the style. Only a change marks the node dirty (or copies a shared style).
+/// Return [`TaffyReturnCode::Ok`] if the value does not internally return, or [`TaffyReturnCode::Panicked`] if it panics.
 macro_rules! with_style_mut {
-    ($raw_style_ptr:expr, $style_ident:ident, $block:expr) => {{
-        debug_assert_non_null!($raw_style_ptr);
-        let $style_ident = unsafe { &mut *($raw_style_ptr as *mut core::Style) };
-
-        $block;
-
-        TaffyReturnCode::Ok
+    ($raw_style:expr, $style_ident:ident, $style:ident $(. $field:ident)+ = $value:expr) => {
+        with_style_mut!($raw_style, {
+            let value = $value;
+            move |$style_ident: &mut core::Style| set_property(&mut $style $(. $field)+, value)
+        })
+    };
+    ($raw_style:expr, $edit:expr) => {{
+        bail_if_null!($raw_style.tree, NullTreePointer);
+        let logger = unsafe { (*($raw_style.tree as *const TaffyTree)).logger };
+        catch_panic(logger, || {
+            let tree = unsafe { &mut *($raw_style.tree as *mut TaffyTree) };
+            let edit = $edit;
+            try_tree!(tree.inner.edit_style($raw_style.node_id.into(), edit));
+            TaffyReturnCode::Ok
+        })
     }};
 }
 
+/// Set `property` to `value`, returning whether its value changed
+fn set_property<T: PartialEq>(property: &mut T, value: T) -> bool {
+    let changed = *property != value;
+    *property = value;
+    changed
+}
+
 /// Attempt to convert a [`TaffyDimension`] into a type that implements `TryFrom<TaffyDimension>`
 /// In the case of a conversion error, return a [`TaffyReturnCode`].
 macro_rules! try_from_value {
@@ -140,7 +172,7 @@ pub unsafe extern "C" fn TaffyStyle_GetDisplay(raw_style:TaffyStyleConstRef) ->
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetDisplay(raw_style:TaffyStyleMutRef,value:TaffyDisplay) -> TaffyReturnCode {
-    with_style_mut!(raw_style,style,style.display = value.into())
+    with_style_mut!(raw_style,style,style.display = try_from_value!(value))
 }
 
 #[no_mangle]
@@ -175,93 +207,155 @@ pub unsafe extern "C" fn TaffyStyle_SetOverflowY(raw_style:TaffyStyleMutRef,valu
     with_style_mut!(raw_style,style,style.overflow.y = value.into())
 }
 
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetAlignContent(raw_style:TaffyStyleConstRef) -> i32 {
     get_style!(raw_style,style,style.align_content.map(|v|v as i32).unwrap_or(0))
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetAlignItems(raw_style:TaffyStyleConstRef) -> i32 {
     get_style!(raw_style,style,style.align_items.map(|v|v as i32).unwrap_or(0))
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetAlignSelf(raw_style:TaffyStyleConstRef) -> i32 {
     get_style!(raw_style,style,style.align_self.map(|v|v as i32).unwrap_or(0))
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetJustifyContent(raw_style:TaffyStyleConstRef) -> i32 {
     get_style!(raw_style,style,style.justify_content.map(|v|v as i32).unwrap_or(0))
 }
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetJustifyItems(raw_style:TaffyStyleConstRef) -> i32 {
     get_style!(raw_style,style,style.justify_items.map(|v|v as i32).unwrap_or(0))
 }
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetJustifySelf(raw_style:TaffyStyleConstRef) -> i32 {
     get_style!(raw_style,style,style.justify_self.map(|v|v as i32).unwrap_or(0))
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetAlignContent(raw_style:TaffyStyleMutRef,value:TaffyAlignContent) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.align_content = value.into())
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetAlignItems(raw_style:TaffyStyleMutRef,value:TaffyAlignItems) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.align_items = value.into())
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetAlignSelf(raw_style:TaffyStyleMutRef,value:TaffyAlignItems) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.align_self = value.into())
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetJustifyContent(raw_style:TaffyStyleMutRef,value:TaffyAlignContent) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.justify_content = value.into())
 }
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetJustifyItems(raw_style:TaffyStyleMutRef,value:TaffyAlignItems) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.justify_items = value.into())
 }
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetJustifySelf(raw_style:TaffyStyleMutRef,value:TaffyAlignItems) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.justify_self = value.into())
 }
+/// Set align-content and justify-content together, like the CSS `place-content` shorthand (pass the same value twice for its single-value form)
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyStyle_SetPlaceContent(raw_style:TaffyStyleMutRef,align:TaffyAlignContent,justify:TaffyAlignContent) -> TaffyReturnCode {
+    with_style_mut!(raw_style, |style: &mut core::Style| {
+        let changed = set_property(&mut style.align_content, align.into());
+        set_property(&mut style.justify_content, justify.into()) | changed
+    })
+}
+/// Set align-items and justify-items together, like the CSS `place-items` shorthand (pass the same value twice for its single-value form)
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyStyle_SetPlaceItems(
+    raw_style: TaffyStyleMutRef,
+    align: TaffyAlignItems,
+    #[cfg_attr(not(feature = "grid"), allow(unused_variables))] justify: TaffyAlignItems,
+) -> TaffyReturnCode {
+    with_style_mut!(raw_style, |style: &mut core::Style| {
+        let changed = set_property(&mut style.align_items, align.into());
+        // `justify_items` only exists with the `grid` feature
+        #[cfg(feature = "grid")]
+        let changed = set_property(&mut style.justify_items, justify.into()) | changed;
+        changed
+    })
+}
+/// Set align-self and justify-self together, like the CSS `place-self` shorthand (pass the same value twice for its single-value form)
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyStyle_SetPlaceSelf(
+    raw_style: TaffyStyleMutRef,
+    align: TaffyAlignItems,
+    #[cfg_attr(not(feature = "grid"), allow(unused_variables))] justify: TaffyAlignItems,
+) -> TaffyReturnCode {
+    with_style_mut!(raw_style, |style: &mut core::Style| {
+        let changed = set_property(&mut style.align_self, align.into());
+        // `justify_self` only exists with the `grid` feature
+        #[cfg(feature = "grid")]
+        let changed = set_property(&mut style.justify_self, justify.into()) | changed;
+        changed
+    })
+}
 
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetFlexDirection(raw_style:TaffyStyleConstRef) -> TaffyFlexDirection {
     get_style!(raw_style,style,style.flex_direction)
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetFlexDirection(raw_style:TaffyStyleMutRef,value:TaffyFlexDirection) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.flex_direction = value.into())
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetFlexWrap(raw_style:TaffyStyleConstRef) -> TaffyFlexWrap {
     get_style!(raw_style,style,style.flex_wrap)
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetFlexWrap(raw_style:TaffyStyleMutRef,value:TaffyFlexWrap) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.flex_wrap = value.into())
 }
 
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetGridAutoFlow(raw_style:TaffyStyleConstRef) -> TaffyGridAutoFlow {
     get_style!(raw_style,style,style.grid_auto_flow)
 }
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetGridAutoFlow(raw_style:TaffyStyleMutRef,value:TaffyGridAutoFlow) -> TaffyReturnCode {
@@ -497,21 +591,25 @@ pub unsafe extern "C" fn TaffyStyle_SetBorderRight(raw_style:TaffyStyleMutRef,va
     with_style_mut!(raw_style,style,style.border.right = try_from_raw!(unit,value))
 }
 
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetColumnGap(raw_style:TaffyStyleConstRef) -> TaffyDimension {
     get_style!(raw_style,style,style.gap.width)
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetColumnGap(raw_style:TaffyStyleMutRef,value:f32,unit:TaffyUnit) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.gap.width = try_from_raw!(unit,value))
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetRowGap(raw_style:TaffyStyleConstRef) -> TaffyDimension {
     get_style!(raw_style,style,style.gap.height)
 }
+#[cfg(any(feature = "flexbox", feature = "grid"))]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetRowGap(raw_style:TaffyStyleMutRef,value:f32,unit:TaffyUnit) -> TaffyReturnCode {
@@ -527,13 +625,8 @@ pub unsafe extern "C" fn TaffyStyle_GetAspectRatio(raw_style: TaffyStyleConstRef
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetAspectRatio(raw_style: TaffyStyleMutRef, value: f32) -> TaffyReturnCode {
-    with_style_mut!(raw_style, style, {
-        if value.is_finite() && value > 0.0 {
-            style.aspect_ratio = Some(value)
-        } else {
-            style.aspect_ratio = None;
-        }
-    })
+    let value = if value.is_finite() && value > 0.0 { Some(value) } else { None };
+    with_style_mut!(raw_style, style, style.aspect_ratio = value)
 }
 
 #[no_mangle]
@@ -547,31 +640,37 @@ pub unsafe extern "C" fn TaffyStyle_SetScrollbarWidth(raw_style:TaffyStyleMutRef
     with_style_mut!(raw_style,style,style.scrollbar_width = value)
 }
 
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetFlexBasis(raw_style:TaffyStyleConstRef) -> TaffyDimension {
     get_style!(raw_style,style,style.flex_basis)
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetFlexBasis(raw_style:TaffyStyleMutRef,value:f32,unit:TaffyUnit) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.flex_basis = try_from_raw!(unit,value))
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetFlexGrow(raw_style:TaffyStyleConstRef) -> f32 {
     get_style!(raw_style,style,style.flex_grow)
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetFlexGrow(raw_style:TaffyStyleMutRef,value:f32) -> TaffyReturnCode {
     with_style_mut!(raw_style,style,style.flex_grow = value)
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetFlexShrink(raw_style:TaffyStyleConstRef) -> f32 {
     get_style!(raw_style,style,style.flex_shrink)
 }
+#[cfg(feature = "flexbox")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetFlexShrink(raw_style:TaffyStyleMutRef,value:f32) -> TaffyReturnCode {
@@ -587,33 +686,36 @@ pub unsafe extern "C" fn TaffyStyle_SetMargin(
     value: TaffyDimension,
 ) -> TaffyReturnCode {
     let value = try_from_value!(value);
-    with_style_mut!(raw_style, style, {
+    with_style_mut!(raw_style, |style: &mut core::Style| {
+        let mut margin = style.margin;
         match edge {
-            TaffyEdge::Top => style.margin.top = value,
-            TaffyEdge::Bottom => style.margin.bottom = value,
-            TaffyEdge::Left => style.margin.left = value,
-            TaffyEdge::Right => style.margin.right = value,
+            TaffyEdge::Top => margin.top = value,
+            TaffyEdge::Bottom => margin.bottom = value,
+            TaffyEdge::Left => margin.left = value,
+            TaffyEdge::Right => margin.right = value,
             TaffyEdge::Vertical => {
-                style.margin.top = value;
-                style.margin.bottom = value;
+                margin.top = value;
+                margin.bottom = value;
             }
             TaffyEdge::Horizontal => {
-                style.margin.left = value;
-                style.margin.right = value;
+                margin.left = value;
+                margin.right = value;
             }
             TaffyEdge::All => {
-                style.margin.top = value;
-                style.margin.bottom = value;
-                style.margin.left = value;
-                style.margin.right = value;
+                margin.top = value;
+                margin.bottom = value;
+                margin.left = value;
+                margin.right = value;
             }
         };
+        set_property(&mut style.margin, margin)
     })
 }
 
 /* Grid APIs */
 
 /// Get grid item's column placement
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetGridColumn(raw_style: TaffyStyleMutRef) -> TaffyGridPlacement {
@@ -621,16 +723,18 @@ pub unsafe extern "C" fn TaffyStyle_GetGridColumn(raw_style: TaffyStyleMutRef) -
 }
 
 /// Set grid item's column placement
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetGridColumn(
     raw_style: TaffyStyleMutRef,
     placement: TaffyGridPlacement,
 ) -> TaffyReturnCode {
-    with_style_mut!(raw_style, style, style.grid_column = placement.into())
+    with_style_mut!(raw_style, style, style.grid_column = try_from_value!(placement))
 }
 
 /// Get grid item's row placement
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_GetGridRow(raw_style: TaffyStyleMutRef) -> TaffyGridPlacement {
@@ -638,13 +742,70 @@ pub unsafe extern "C" fn TaffyStyle_GetGridRow(raw_style: TaffyStyleMutRef) -> T
 }
 
 /// Set grid item's row placement
+#[cfg(feature = "grid")]
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyStyle_SetGridRow(
     raw_style: TaffyStyleMutRef,
     placement: TaffyGridPlacement,
 ) -> TaffyReturnCode {
-    with_style_mut!(raw_style, style, style.grid_row = placement.into())
+    with_style_mut!(raw_style, style, style.grid_row = try_from_value!(placement))
+}
+
+/// Decode a UTF-16 CSS track list string and parse it into a list of track sizing functions
+#[cfg(feature = "grid")]
+unsafe fn parse_utf16_track_list(
+    ptr: *const u16,
+    len: usize,
+) -> Result<Vec<core::TrackSizingFunction>, TaffyReturnCode> {
+    if ptr.is_null() && len > 0 {
+        return Err(TaffyReturnCode::NullStringPointer);
+    }
+    let utf16 = if len == 0 { &[] } else { ::core::slice::from_raw_parts(ptr, len) };
+    let track_list: String = char::decode_utf16(utf16.iter().copied())
+        .collect::<Result<_, _>>()
+        .map_err(|_| TaffyReturnCode::InvalidUtf16)?;
+    taffy::parse_track_list(&track_list).map_err(|_| TaffyReturnCode::InvalidTrackList)
+}
+
+/// Set a grid container's column track sizing functions from a UTF-16 encoded CSS track list string
+/// such as `1fr auto repeat(3, 100px)`. Line names are accepted but ignored.
+#[cfg(feature = "grid")]
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyStyle_SetGridTemplateColumnsFromString(
+    raw_style: TaffyStyleMutRef,
+    ptr: *const u16,
+    len: usize,
+) -> TaffyReturnCode {
+    with_style_mut!(
+        raw_style,
+        style,
+        style.grid_template_columns = match parse_utf16_track_list(ptr, len) {
+            Ok(tracks) => tracks,
+            Err(return_code) => return return_code,
+        }
+    )
+}
+
+/// Set a grid container's row track sizing functions from a UTF-16 encoded CSS track list string
+/// such as `1fr auto repeat(3, 100px)`. Line names are accepted but ignored.
+#[cfg(feature = "grid")]
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyStyle_SetGridTemplateRowsFromString(
+    raw_style: TaffyStyleMutRef,
+    ptr: *const u16,
+    len: usize,
+) -> TaffyReturnCode {
+    with_style_mut!(
+        raw_style,
+        style,
+        style.grid_template_rows = match parse_utf16_track_list(ptr, len) {
+            Ok(tracks) => tracks,
+            Err(return_code) => return return_code,
+        }
+    )
 }
 
 #[repr(C)]
@@ -674,8 +835,8 @@ pub unsafe extern "C" fn TaffyStyle_SetGridTemplateColumn(raw_style:TaffyStyleMu
     grid_template_columns.clear();
     for i in 0..count {
         let func = unsafe { &(*tracking_functions.add(i as usize)) };
-        let cstr = U16Str::from_ptr(func.ptr, func.len);
-        let track = cstr.to_string_lossy().as_str();
+        let utf16 = unsafe { ::core::slice::from_raw_parts(func.ptr, func.len) };
+        let track: String = char::decode_utf16(utf16.iter().copied()).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect();
         grid_template_columns.push(TrackSizingFunction::try_from(track).unwrap());
     }
 
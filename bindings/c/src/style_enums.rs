@@ -1,3 +1,4 @@
+use super::TaffyReturnCode;
 use taffy::prelude as core;
 
 /// Sets the layout used for the children of this node
@@ -15,21 +16,33 @@ pub enum TaffyDisplay {
     /// The children will not be laid out, and will follow absolute positioning
     None,
 }
-impl From<TaffyDisplay> for core::Display {
-    fn from(input: TaffyDisplay) -> core::Display {
+impl TryFrom<TaffyDisplay> for core::Display {
+    type Error = TaffyReturnCode;
+
+    /// Fails with [`TaffyReturnCode::UnsupportedDisplay`] if the layout algorithm for the display mode was not
+    /// compiled into the library (see the `flexbox`, `grid` and `block` features)
+    fn try_from(input: TaffyDisplay) -> Result<core::Display, TaffyReturnCode> {
         match input {
-            TaffyDisplay::Block => core::Display::Block,
-            TaffyDisplay::Flex => core::Display::Flex,
-            TaffyDisplay::Grid => core::Display::Grid,
-            TaffyDisplay::None => core::Display::None,
+            #[cfg(feature = "block")]
+            TaffyDisplay::Block => Ok(core::Display::Block),
+            #[cfg(feature = "flexbox")]
+            TaffyDisplay::Flex => Ok(core::Display::Flex),
+            #[cfg(feature = "grid")]
+            TaffyDisplay::Grid => Ok(core::Display::Grid),
+            TaffyDisplay::None => Ok(core::Display::None),
+            #[allow(unreachable_patterns)]
+            _ => Err(TaffyReturnCode::UnsupportedDisplay),
         }
     }
 }
 impl From<core::Display> for TaffyDisplay {
     fn from(input: core::Display) -> TaffyDisplay {
         match input {
+            #[cfg(feature = "block")]
             core::Display::Block => TaffyDisplay::Block,
+            #[cfg(feature = "flexbox")]
             core::Display::Flex => TaffyDisplay::Flex,
+            #[cfg(feature = "grid")]
             core::Display::Grid => TaffyDisplay::Grid,
             core::Display::None => TaffyDisplay::None,
         }
@@ -154,6 +167,9 @@ pub enum TaffyAlignItems {
     Baseline,
     /// Stretch to fill the container
     Stretch,
+    /// Items are aligned such that their last baselines align, with the group of aligned items packed toward the
+    /// end of the axis
+    LastBaseline,
 }
 impl From<TaffyAlignItems> for Option<core::AlignItems> {
     fn from(input: TaffyAlignItems) -> Option<core::AlignItems> {
@@ -166,6 +182,7 @@ impl From<TaffyAlignItems> for Option<core::AlignItems> {
             TaffyAlignItems::Center => Some(core::AlignItems::Center),
             TaffyAlignItems::Baseline => Some(core::AlignItems::Baseline),
             TaffyAlignItems::Stretch => Some(core::AlignItems::Stretch),
+            TaffyAlignItems::LastBaseline => Some(core::AlignItems::LastBaseline),
         }
     }
 }
@@ -180,6 +197,7 @@ impl From<Option<core::AlignItems>> for TaffyAlignItems {
             Some(core::AlignItems::Center) => TaffyAlignItems::Center,
             Some(core::AlignItems::Baseline) => TaffyAlignItems::Baseline,
             Some(core::AlignItems::Stretch) => TaffyAlignItems::Stretch,
+            Some(core::AlignItems::LastBaseline) => TaffyAlignItems::LastBaseline,
         }
     }
 }
@@ -271,6 +289,7 @@ pub enum TaffyFlexWrap {
     /// Items will wrap in the opposite direction to this item's [`FlexDirection`]
     WrapReverse,
 }
+#[cfg(feature = "flexbox")]
 impl From<TaffyFlexWrap> for core::FlexWrap {
     fn from(input: TaffyFlexWrap) -> core::FlexWrap {
         match input {
@@ -280,6 +299,7 @@ impl From<TaffyFlexWrap> for core::FlexWrap {
         }
     }
 }
+#[cfg(feature = "flexbox")]
 impl From<core::FlexWrap> for TaffyFlexWrap {
     fn from(input: core::FlexWrap) -> TaffyFlexWrap {
         match input {
@@ -321,6 +341,7 @@ pub enum TaffyFlexDirection {
     /// Items will be added from bottom to top in a column.
     ColumnReverse,
 }
+#[cfg(feature = "flexbox")]
 impl From<TaffyFlexDirection> for core::FlexDirection {
     fn from(input: TaffyFlexDirection) -> core::FlexDirection {
         match input {
@@ -331,6 +352,7 @@ impl From<TaffyFlexDirection> for core::FlexDirection {
         }
     }
 }
+#[cfg(feature = "flexbox")]
 impl From<core::FlexDirection> for TaffyFlexDirection {
     fn from(input: core::FlexDirection) -> TaffyFlexDirection {
         match input {
@@ -362,6 +384,7 @@ pub enum TaffyGridAutoFlow {
     /// Combines `Column` with the dense packing algorithm.
     ColumnDense,
 }
+#[cfg(feature = "grid")]
 impl From<TaffyGridAutoFlow> for core::GridAutoFlow {
     fn from(input: TaffyGridAutoFlow) -> core::GridAutoFlow {
         match input {
@@ -372,6 +395,7 @@ impl From<TaffyGridAutoFlow> for core::GridAutoFlow {
         }
     }
 }
+#[cfg(feature = "grid")]
 impl From<core::GridAutoFlow> for TaffyGridAutoFlow {
     fn from(input: core::GridAutoFlow) -> TaffyGridAutoFlow {
         match input {
@@ -382,3 +406,55 @@ impl From<core::GridAutoFlow> for TaffyGridAutoFlow {
         }
     }
 }
+
+/// The set of default style values used for nodes created with `TaffyTree_NewNode`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub enum TaffyStyleDefaults {
+    /// The CSS initial values
+    Css,
+    /// The defaults used by Yoga and React Native: `flex_direction: Column`, `align_content: FlexStart` and `flex_shrink: 0`
+    Yoga,
+}
+impl From<TaffyStyleDefaults> for taffy::StyleDefaults {
+    fn from(input: TaffyStyleDefaults) -> taffy::StyleDefaults {
+        match input {
+            TaffyStyleDefaults::Css => taffy::StyleDefaults::Css,
+            TaffyStyleDefaults::Yoga => taffy::StyleDefaults::Yoga,
+        }
+    }
+}
+impl From<taffy::StyleDefaults> for TaffyStyleDefaults {
+    fn from(input: taffy::StyleDefaults) -> TaffyStyleDefaults {
+        match input {
+            taffy::StyleDefaults::Css => TaffyStyleDefaults::Css,
+            taffy::StyleDefaults::Yoga => TaffyStyleDefaults::Yoga,
+        }
+    }
+}
+
+/// Which dimensions of the containing block percentage margins and padding are resolved against
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub enum TaffyPercentResolutionMode {
+    /// All four sides resolve against the containing block's width (as specified by CSS)
+    Width,
+    /// The left and right sides resolve against the containing block's width, and the top and bottom sides against its height
+    PerAxis,
+}
+impl From<TaffyPercentResolutionMode> for taffy::PercentResolutionMode {
+    fn from(input: TaffyPercentResolutionMode) -> taffy::PercentResolutionMode {
+        match input {
+            TaffyPercentResolutionMode::Width => taffy::PercentResolutionMode::Width,
+            TaffyPercentResolutionMode::PerAxis => taffy::PercentResolutionMode::PerAxis,
+        }
+    }
+}
+impl From<taffy::PercentResolutionMode> for TaffyPercentResolutionMode {
+    fn from(input: taffy::PercentResolutionMode) -> TaffyPercentResolutionMode {
+        match input {
+            taffy::PercentResolutionMode::Width => TaffyPercentResolutionMode::Width,
+            taffy::PercentResolutionMode::PerAxis => TaffyPercentResolutionMode::PerAxis,
+        }
+    }
+}
@@ -1,12 +1,30 @@
-use crate::{TaffyResult_TaffyLayout, TaffyResult_TaffyNodeId, TaffyResult_TaffyStyleMutRef};
+use crate::{
+    TaffyResult_TaffyLayout, TaffyResult_TaffyLayoutConfig, TaffyResult_TaffyNodeId, TaffyResult_TaffyStringOwnedRef,
+    TaffyResult_TaffyStyleMutRef, TaffyResult_TaffyStyleSnapshot, TaffyResult_usize, TaffyStyleCommand,
+    TaffyStyleSnapshot,
+};
 
 use super::{
-    bail, bail_if_null, ok, try_or, TaffyFFIDefault, TaffyFFIResult, TaffyLayout, TaffyMeasureMode, TaffyResult,
-    TaffyReturnCode, TaffySize, TaffyStyleMutRef,
+    bail_if_null, ok, try_tree, TaffyAvailableSpaceType, TaffyFFIDefault, TaffyFFIResult, TaffyLayout,
+    TaffyLayoutConfig, TaffyLogFunction, TaffyLogLevel, TaffyMeasureMode, TaffyReturnCode, TaffySize, TaffyStringOwnedRef,
+    TaffyStyleDefaults, TaffyStyleMutRef, TaffyTraversalOrder,
 };
+use crate::log::{is_logging, log, Logger};
+use crate::panic::{catch_panic, catch_panic_or_else};
 use ::core::ffi::c_void;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::ffi::CString;
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::ffi::CString;
 use taffy::prelude as core;
 use taffy::style::AvailableSpace;
+use taffy::StyleCommandBuffer;
 use taffy::TaffyTree as CoreTaffy;
 
 pub type TaffyMeasureFunction = extern "C" fn(
@@ -18,19 +36,24 @@ pub type TaffyMeasureFunction = extern "C" fn(
 ) -> TaffySize;
 
 #[allow(dead_code)] // false positive
-struct NodeContext {
+pub(crate) struct NodeContext {
     context: *mut c_void,
     measure_function: TaffyMeasureFunction,
 }
 
 pub struct TaffyTree {
-    inner: CoreTaffy<NodeContext>,
+    pub(crate) inner: CoreTaffy<NodeContext>,
+    /// Reused by `TaffyTree_ApplyStyleCommands`, so that applying commands every frame doesn't allocate
+    style_commands: StyleCommandBuffer,
+    /// The logger set with `TaffyTree_SetLogCallback`, which receives the diagnostics of this tree
+    pub(crate) logger: Option<Logger>,
 }
 pub type TaffyTreeOwnedRef = *mut TaffyTree;
 pub type TaffyTreeMutRef = *mut TaffyTree;
 pub type TaffyTreeConstRef = *const TaffyTree;
 
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TaffyNodeId(u64);
 impl TaffyFFIDefault for TaffyNodeId {
     fn default() -> Self {
@@ -48,19 +71,29 @@ impl From<TaffyNodeId> for core::NodeId {
     }
 }
 
+/// Check that the tree pointer is non-null, then give the passed expression access to the tree.
+/// Return [`TaffyReturnCode::Panicked`] if the expression panics.
 macro_rules! with_tree {
     ($raw_tree_ptr:expr, $tree_ident:ident, $block:expr) => {{
         bail_if_null!($raw_tree_ptr, NullTreePointer);
-        let $tree_ident = unsafe { &*($raw_tree_ptr as *const TaffyTree) };
-        $block
+        let logger = unsafe { (*($raw_tree_ptr as *const TaffyTree)).logger };
+        catch_panic(logger, || {
+            let $tree_ident = unsafe { &*($raw_tree_ptr as *const TaffyTree) };
+            $block
+        })
     }};
 }
 
+/// Check that the tree pointer is non-null, then give the passed expression mutable access to the tree.
+/// Return [`TaffyReturnCode::Panicked`] if the expression panics.
 macro_rules! with_tree_mut {
     ($raw_tree_ptr:expr, $tree_ident:ident, $block:expr) => {{
         bail_if_null!($raw_tree_ptr, NullTreePointer);
-        let $tree_ident = unsafe { &mut *($raw_tree_ptr as *mut TaffyTree) };
-        $block
+        let logger = unsafe { (*($raw_tree_ptr as *const TaffyTree)).logger };
+        catch_panic(logger, || {
+            let $tree_ident = unsafe { &mut *($raw_tree_ptr as *mut TaffyTree) };
+            $block
+        })
     }};
 }
 
@@ -78,11 +111,64 @@ fn available_space_from_f32(input: f32) -> core::AvailableSpace {
 // Create and Free
 // -------------------------------------------------
 
-/// Create a TaffyTree instance
+/// Create a TaffyTree instance. Returns null if Taffy panicked.
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_New() -> TaffyTreeOwnedRef {
-    Box::into_raw(Box::new(TaffyTree { inner: CoreTaffy::new() }))
+    catch_panic_or_else(
+        None,
+        || {
+            let inner = CoreTaffy::new();
+            Box::into_raw(Box::new(TaffyTree { inner, style_commands: StyleCommandBuffer::new(), logger: None }))
+        },
+        ::core::ptr::null_mut,
+    )
+}
+
+/// Create a TaffyTree instance which can store `capacity` nodes before reallocating, and in which nodes created
+/// without children can have `children_per_node` children added before their list of children reallocates.
+/// Returns null if Taffy panicked.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_WithCapacity(capacity: usize, children_per_node: usize) -> TaffyTreeOwnedRef {
+    catch_panic_or_else(
+        None,
+        || {
+            let inner = CoreTaffy::with_capacity_and_children(capacity, children_per_node);
+            Box::into_raw(Box::new(TaffyTree { inner, style_commands: StyleCommandBuffer::new(), logger: None }))
+        },
+        ::core::ptr::null_mut,
+    )
+}
+
+/// Reserve capacity for at least `additional` more nodes to be created before the tree reallocates
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_Reserve(raw_tree: TaffyTreeMutRef, additional: usize) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.reserve(additional);
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Remove all of the nodes in the tree. The tree keeps the storage it allocated for nodes, so reusing it for a tree of
+/// a similar size is cheaper than freeing it and creating a new one.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_Clear(raw_tree: TaffyTreeMutRef) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.clear();
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Get the number of nodes in the tree
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_NodeCount(raw_tree: TaffyTreeConstRef) -> TaffyResult_usize {
+    with_tree!(raw_tree, tree, {
+        ok!(tree.inner.total_node_count());
+    })
 }
 
 /// Free a TaffyTree instance
@@ -90,15 +176,20 @@ pub unsafe extern "C" fn TaffyTree_New() -> TaffyTreeOwnedRef {
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_Free(raw_tree: TaffyTreeOwnedRef) -> TaffyReturnCode {
     bail_if_null!(raw_tree, NullTreePointer);
-    drop(Box::from_raw(raw_tree));
-    TaffyReturnCode::Ok
+    catch_panic((*raw_tree).logger, || {
+        drop(Box::from_raw(raw_tree));
+        TaffyReturnCode::Ok
+    })
 }
 
 // -------------------------------------------------
 // Compute and Print
 // -------------------------------------------------
 
-/// Create a new Node in the TaffyTree. Returns a NodeId handle to the node.
+/// Compute the layout of the tree rooted at the specified node
+///
+/// A finite non-negative available width or height is treated as a definite amount of space, negative infinity
+/// as a min-content constraint, and any other value as a max-content constraint.
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_ComputeLayout(
@@ -112,33 +203,115 @@ pub unsafe extern "C" fn TaffyTree_ComputeLayout(
             width: available_space_from_f32(available_width),
             height: available_space_from_f32(available_height),
         };
-        try_or!(
-            InvalidNodeId,
-            tree.inner.compute_layout_with_measure(
-                node_id.into(),
-                available_space,
-                |known_dimensions, available_space, _node_id, node_context, style| {
-                    let (width, width_measure_mode) = match (known_dimensions.width, available_space.width) {
-                        (Some(width), _) => (width, TaffyMeasureMode::Exact),
-                        (None, AvailableSpace::Definite(width)) => (width, TaffyMeasureMode::FitContent),
-                        (None, AvailableSpace::MaxContent) => (f32::INFINITY, TaffyMeasureMode::MaxContent),
-                        (None, AvailableSpace::MinContent) => (f32::INFINITY, TaffyMeasureMode::MinContent),
-                    };
-                    let (height, height_measure_mode) = match (known_dimensions.height, available_space.height) {
-                        (Some(height), _) => (height, TaffyMeasureMode::Exact),
-                        (None, AvailableSpace::Definite(height)) => (height, TaffyMeasureMode::FitContent),
-                        (None, AvailableSpace::MaxContent) => (f32::INFINITY, TaffyMeasureMode::MaxContent),
-                        (None, AvailableSpace::MinContent) => (f32::INFINITY, TaffyMeasureMode::MinContent),
-                    };
-                    match node_context {
-                        Some(NodeContext { measure_function, context }) => {
-                            measure_function(width_measure_mode, width, height_measure_mode, height, *context).into()
+        compute_layout(tree, node_id, available_space)
+    })
+}
+
+/// Compute the layout of the tree rooted at the specified node, with the type of available space specified
+/// explicitly for each axis. The width and height values are ignored unless the corresponding type is `Definite`.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_ComputeLayoutWithAvailableSpace(
+    raw_tree: TaffyTreeMutRef,
+    node_id: TaffyNodeId,
+    available_width_type: TaffyAvailableSpaceType,
+    available_width: f32,
+    available_height_type: TaffyAvailableSpaceType,
+    available_height: f32,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        let width = match available_width_type.with_value(available_width) {
+            Ok(width) => width,
+            Err(return_code) => return return_code,
+        };
+        let height = match available_height_type.with_value(available_height) {
+            Ok(height) => height,
+            Err(return_code) => return return_code,
+        };
+        compute_layout(tree, node_id, core::Size { width, height })
+    })
+}
+
+fn compute_layout(
+    tree: &mut TaffyTree,
+    node_id: TaffyNodeId,
+    available_space: core::Size<core::AvailableSpace>,
+) -> TaffyReturnCode {
+    let logger = tree.logger;
+    let is_debugging = is_logging(logger, TaffyLogLevel::Debug);
+    if is_debugging {
+        for (node, warning) in try_tree!(tree.inner.validate_tree(node_id.into())) {
+            log(logger, TaffyLogLevel::Debug, || format!("Style of node {node:?}: {warning}"));
+        }
+    }
+
+    try_tree!(tree.inner.compute_layout_with_measure(
+        node_id.into(),
+        available_space,
+        |known_dimensions, available_space, node_id, node_context, style| {
+            let (width, width_measure_mode) = match (known_dimensions.width, available_space.width) {
+                (Some(width), _) => (width, TaffyMeasureMode::Exact),
+                (None, AvailableSpace::Definite(width)) => (width, TaffyMeasureMode::FitContent),
+                (None, AvailableSpace::MaxContent) => (f32::INFINITY, TaffyMeasureMode::MaxContent),
+                (None, AvailableSpace::MinContent) => (f32::INFINITY, TaffyMeasureMode::MinContent),
+            };
+            let (height, height_measure_mode) = match (known_dimensions.height, available_space.height) {
+                (Some(height), _) => (height, TaffyMeasureMode::Exact),
+                (None, AvailableSpace::Definite(height)) => (height, TaffyMeasureMode::FitContent),
+                (None, AvailableSpace::MaxContent) => (f32::INFINITY, TaffyMeasureMode::MaxContent),
+                (None, AvailableSpace::MinContent) => (f32::INFINITY, TaffyMeasureMode::MinContent),
+            };
+            match node_context {
+                Some(NodeContext { measure_function, context }) => {
+                    let size: core::Size<f32> =
+                        measure_function(width_measure_mode, width, height_measure_mode, height, *context).into();
+                    if !(size.width.is_finite() && size.width >= 0.0 && size.height.is_finite() && size.height >= 0.0) {
+                        log(logger, TaffyLogLevel::Warning, || {
+                            format!("Measure function of node {node_id:?} returned an invalid size {size:?}")
+                        });
+                    } else if is_debugging {
+                        let repeated_size: core::Size<f32> =
+                            measure_function(width_measure_mode, width, height_measure_mode, height, *context).into();
+                        if repeated_size != size {
+                            log(logger, TaffyLogLevel::Debug, || {
+                                format!(
+                                    "Measure function of node {node_id:?} returned {size:?} and then {repeated_size:?} \
+                                    for the same inputs, so layouts cached from its results are inconsistent"
+                                )
+                            });
                         }
-                        _ => core::Size::ZERO,
                     }
+                    size
                 }
-            )
-        );
+                _ => core::Size::ZERO,
+            }
+        }
+    ));
+    TaffyReturnCode::Ok
+}
+
+/// Set the function which the diagnostics of this tree up to `max_level` are passed to, along with a `user_data` pointer
+/// which is passed to every call. Passing null stops logging (the default).
+///
+/// The function only receives the diagnostics of this tree, and is only called from the thread which is calling into
+/// Taffy with this tree, so different trees used from different threads can log to different (or unsynchronized)
+/// destinations. Panics caught while operating on this tree are logged as errors.
+///
+/// While a function is set, computing layout checks the sizes returned by measure functions for NaN, infinite or
+/// negative values, logging a warning for each problem found. If `max_level` is `Debug`, computing layout also checks
+/// the styles of the laid out nodes for properties which have no effect (as reported by `TaffyTree_ValidateTree`) and
+/// calls each measure function twice to check that it returns the same size for the same inputs (as Taffy caches the
+/// sizes it returns), logging a debug message for each problem found.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetLogCallback(
+    raw_tree: TaffyTreeMutRef,
+    log_function: Option<TaffyLogFunction>,
+    max_level: TaffyLogLevel,
+    user_data: *mut c_void,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.logger = log_function.map(|function| Logger { function, user_data, max_level });
         TaffyReturnCode::Ok
     })
 }
@@ -153,11 +326,106 @@ pub unsafe extern "C" fn TaffyTree_PrintTree(raw_tree: TaffyTreeMutRef, node_id:
     })
 }
 
+/// Enable rounding of the layouts returned by `TaffyTree_GetLayout` to whole pixels. Rounding is enabled by default.
+/// Takes effect the next time layout is computed.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_EnableRounding(raw_tree: TaffyTreeMutRef) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.enable_rounding();
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Disable rounding of the layouts returned by `TaffyTree_GetLayout`. Rounding is enabled by default.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_DisableRounding(raw_tree: TaffyTreeMutRef) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.disable_rounding();
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Set the configuration of behaviours where layout may deviate from the web specifications.
+/// Takes effect the next time layout is computed.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetConfig(raw_tree: TaffyTreeMutRef, config: TaffyLayoutConfig) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.set_layout_config(config.into());
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Get the configuration of behaviours where layout may deviate from the web specifications
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_GetConfig(raw_tree: TaffyTreeConstRef) -> TaffyResult_TaffyLayoutConfig {
+    with_tree!(raw_tree, tree, {
+        ok!(tree.inner.layout_config().into());
+    })
+}
+
+/// Set the default style values used for nodes subsequently created with `TaffyTree_NewNode`. Existing nodes are unaffected.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetStyleDefaults(
+    raw_tree: TaffyTreeMutRef,
+    style_defaults: TaffyStyleDefaults,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.set_default_style(taffy::StyleDefaults::from(style_defaults).style());
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Set the maximum depth of the trees which layout is computed for, or a negative value (the default) for no maximum.
+/// Computing the layout of a tree deeper than this returns `TaffyReturnCode::MaxDepthExceeded` rather than risking a
+/// stack overflow, which would abort the process.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetMaxDepth(raw_tree: TaffyTreeMutRef, max_depth: i32) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        tree.inner.set_max_depth(usize::try_from(max_depth).ok());
+        TaffyReturnCode::Ok
+    })
+}
+
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_MarkDirty(raw_tree: TaffyTreeMutRef, node_id: TaffyNodeId) -> TaffyReturnCode {
     with_tree_mut!(raw_tree, tree, {
-        tree.inner.mark_dirty(node_id.into()).expect("Invalid node id");
+        try_tree!(tree.inner.mark_dirty(node_id.into()));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Apply the `count` style edits in `commands` to the styles of their nodes, in order. Each node whose style changed is
+/// marked dirty once, however many of its properties were edited.
+///
+/// The edits are applied atomically: if any of them has an invalid node id or value, no style is modified and the error
+/// for the first invalid edit is returned.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_ApplyStyleCommands(
+    raw_tree: TaffyTreeMutRef,
+    commands: *const TaffyStyleCommand,
+    count: usize,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        if count == 0 {
+            return TaffyReturnCode::Ok;
+        }
+        bail_if_null!(commands, NullArrayPointer);
+        tree.style_commands.clear();
+        for command in ::core::slice::from_raw_parts(commands, count) {
+            match command.to_core() {
+                Ok(style_command) => tree.style_commands.push(command.node_id.into(), style_command),
+                Err(return_code) => return return_code,
+            }
+        }
+        try_tree!(tree.inner.apply(&mut tree.style_commands));
         TaffyReturnCode::Ok
     })
 }
@@ -171,18 +439,154 @@ pub unsafe extern "C" fn TaffyTree_MarkDirty(raw_tree: TaffyTreeMutRef, node_id:
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_NewNode(raw_tree: TaffyTreeMutRef) -> TaffyResult_TaffyNodeId {
     with_tree_mut!(raw_tree, tree, {
-        // TODO: make new_leaf infallible
-        let node_id = tree.inner.new_leaf(core::Style::default()).unwrap();
+        let node_id = try_tree!(tree.inner.new_leaf(tree.inner.default_style()));
         ok!(node_id.into());
     })
 }
 
+/// Get the style which nodes created with `TaffyTree_NewNode` start with, as a snapshot which can be edited and passed to
+/// `TaffyTree_NewLeafWithStyle` or `TaffyTree_NewWithChildren`
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_GetDefaultStyleSnapshot(
+    raw_tree: TaffyTreeConstRef,
+) -> TaffyResult_TaffyStyleSnapshot {
+    with_tree!(raw_tree, tree, {
+        ok!(TaffyStyleSnapshot::from(&tree.inner.default_style()));
+    })
+}
+
+/// Set the style which nodes created with `TaffyTree_NewNode` start with (and which style snapshots are applied to), such
+/// as the base style of a theme. Existing nodes are unaffected, and `TaffyTree_SetStyleDefaults` resets it.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetDefaultStyle(
+    raw_tree: TaffyTreeMutRef,
+    style: *const TaffyStyleSnapshot,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        let style = match style_from_snapshot(tree, style) {
+            Ok(style) => style,
+            Err(return_code) => return return_code,
+        };
+        tree.inner.set_default_style(style);
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Copy an array of `count` node ids passed from C. The pointer may be null if `count` is zero.
+unsafe fn node_ids_from_raw(node_ids: *const TaffyNodeId, count: usize) -> Result<Vec<core::NodeId>, TaffyReturnCode> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    if node_ids.is_null() {
+        return Err(TaffyReturnCode::NullArrayPointer);
+    }
+    Ok(::core::slice::from_raw_parts(node_ids, count).iter().map(|&node_id| node_id.into()).collect())
+}
+
+/// Apply a style snapshot to the tree's default style
+unsafe fn style_from_snapshot(
+    tree: &TaffyTree,
+    style: *const TaffyStyleSnapshot,
+) -> Result<core::Style, TaffyReturnCode> {
+    if style.is_null() {
+        return Err(TaffyReturnCode::NullStylePointer);
+    }
+    let mut core_style = tree.inner.default_style();
+    (*style).apply_to(&mut core_style)?;
+    Ok(core_style)
+}
+
+/// Create a new node with the style described by `style`, in a single call. Returns a NodeId handle to the node.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_NewLeafWithStyle(
+    raw_tree: TaffyTreeMutRef,
+    style: *const TaffyStyleSnapshot,
+) -> TaffyResult_TaffyNodeId {
+    with_tree_mut!(raw_tree, tree, {
+        let style = match style_from_snapshot(tree, style) {
+            Ok(style) => style,
+            Err(return_code) => return TaffyFFIResult::from_return_code(return_code),
+        };
+        let node_id = try_tree!(tree.inner.new_leaf(style));
+        ok!(node_id.into());
+    })
+}
+
+/// Create a new node with the style described by `style` and the `count` children in `children` (which must not have
+/// parents), in a single call. Returns a NodeId handle to the node.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_NewWithChildren(
+    raw_tree: TaffyTreeMutRef,
+    style: *const TaffyStyleSnapshot,
+    children: *const TaffyNodeId,
+    count: usize,
+) -> TaffyResult_TaffyNodeId {
+    with_tree_mut!(raw_tree, tree, {
+        let style = match style_from_snapshot(tree, style) {
+            Ok(style) => style,
+            Err(return_code) => return TaffyFFIResult::from_return_code(return_code),
+        };
+        let children = match node_ids_from_raw(children, count) {
+            Ok(children) => children,
+            Err(return_code) => return TaffyFFIResult::from_return_code(return_code),
+        };
+        let node_id = try_tree!(tree.inner.new_with_children(style, &children));
+        ok!(node_id.into());
+    })
+}
+
+/// Create `count` nodes at once from a flat description of a tree, which is much faster than creating them one at a time.
+/// Node `i` is created with the style described by `styles[i]`. `parent_indices[i]` is the index of the parent of node `i`
+/// (which must be less than `i`), or a negative value if node `i` has no parent. The ids of the nodes are written to
+/// `out_node_ids`.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_BuildFromSlices(
+    raw_tree: TaffyTreeMutRef,
+    styles: *const TaffyStyleSnapshot,
+    parent_indices: *const i32,
+    count: usize,
+    out_node_ids: *mut TaffyNodeId,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        if count == 0 {
+            return TaffyReturnCode::Ok;
+        }
+        bail_if_null!(styles, NullStylePointer);
+        bail_if_null!(parent_indices, NullArrayPointer);
+        bail_if_null!(out_node_ids, NullArrayPointer);
+
+        let parents: Vec<Option<usize>> = ::core::slice::from_raw_parts(parent_indices, count)
+            .iter()
+            .map(|&parent| usize::try_from(parent).ok())
+            .collect();
+        let mut core_styles = Vec::with_capacity(count);
+        for index in 0..count {
+            match style_from_snapshot(tree, styles.add(index)) {
+                Ok(style) => core_styles.push(style),
+                Err(return_code) => return return_code,
+            }
+        }
+
+        let node_ids = try_tree!(tree.inner.build_from_slices(&core_styles, &parents));
+        let out_node_ids = ::core::slice::from_raw_parts_mut(out_node_ids, count);
+        for (out, node_id) in out_node_ids.iter_mut().zip(node_ids) {
+            *out = node_id.into();
+        }
+        TaffyReturnCode::Ok
+    })
+}
+
 /// Remove and Free a Node within a TaffyTree
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_RemoveNode(raw_tree: TaffyTreeMutRef, node_id: TaffyNodeId) -> TaffyReturnCode {
     with_tree_mut!(raw_tree, tree, {
-        try_or!(InvalidNodeId, tree.inner.remove(node_id.into()));
+        try_tree!(tree.inner.remove(node_id.into()));
         ok!(TaffyReturnCode::Ok);
     })
 }
@@ -196,7 +600,7 @@ pub unsafe extern "C" fn TaffyTree_AppendChild(
     child_node_id: TaffyNodeId,
 ) -> TaffyReturnCode {
     with_tree_mut!(raw_tree, tree, {
-        try_or!(InvalidNodeId, tree.inner.add_child(parent_node_id.into(), child_node_id.into()));
+        try_tree!(tree.inner.add_child(parent_node_id.into(), child_node_id.into()));
         ok!(TaffyReturnCode::Ok);
     })
 }
@@ -209,16 +613,135 @@ pub unsafe extern "C" fn TaffyTree_RemoveChild(
     child_node_id: TaffyNodeId,
 ) -> TaffyReturnCode {
     with_tree_mut!(raw_tree, tree, {
-        try_or!(InvalidNodeId, tree.inner.remove_child(parent_node_id.into(), child_node_id.into()));
+        try_tree!(tree.inner.remove_child(parent_node_id.into(), child_node_id.into()));
         ok!(TaffyReturnCode::Ok);
     })
 }
 
+/// Insert a child at `child_index` in the children of a node, shifting all of the children after it along. An index
+/// equal to the number of children appends the child.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_InsertChildAtIndex(
+    raw_tree: TaffyTreeMutRef,
+    parent_node_id: TaffyNodeId,
+    child_index: usize,
+    child_node_id: TaffyNodeId,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        try_tree!(tree.inner.insert_child_at_index(parent_node_id.into(), child_index, child_node_id.into()));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Replace the child at `child_index` in the children of a node with `new_child_node_id`. Returns the id of the
+/// replaced child, which is detached but not removed from the tree.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_ReplaceChildAtIndex(
+    raw_tree: TaffyTreeMutRef,
+    parent_node_id: TaffyNodeId,
+    child_index: usize,
+    new_child_node_id: TaffyNodeId,
+) -> TaffyResult_TaffyNodeId {
+    with_tree_mut!(raw_tree, tree, {
+        let old_child =
+            try_tree!(tree.inner.replace_child_at_index(parent_node_id.into(), child_index, new_child_node_id.into()));
+        ok!(old_child.into());
+    })
+}
+
+/// Replace all of the children of a node with the `count` nodes in `children`, in a single call. The previous children
+/// are detached but not removed from the tree. If any of the node ids is invalid the children are left unchanged.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetChildren(
+    raw_tree: TaffyTreeMutRef,
+    parent_node_id: TaffyNodeId,
+    children: *const TaffyNodeId,
+    count: usize,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        let children = match node_ids_from_raw(children, count) {
+            Ok(children) => children,
+            Err(return_code) => return return_code,
+        };
+        try_tree!(tree.inner.set_children(parent_node_id.into(), &children));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Append the `count` nodes in `children` to the children of a node, in a single call. If any of the node ids is
+/// invalid no children are added.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_AddChildren(
+    raw_tree: TaffyTreeMutRef,
+    parent_node_id: TaffyNodeId,
+    children: *const TaffyNodeId,
+    count: usize,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        let added_children = match node_ids_from_raw(children, count) {
+            Ok(children) => children,
+            Err(return_code) => return return_code,
+        };
+        let mut children = try_tree!(tree.inner.children(parent_node_id.into()));
+        children.extend(added_children);
+        try_tree!(tree.inner.set_children(parent_node_id.into(), &children));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Write the ids of `root_node_id` and all of its descendants, in the specified order, to `out_node_ids` (which has room
+/// for `capacity` ids) and return the number of nodes in the subtree. If the subtree has more nodes than `capacity`,
+/// only the first `capacity` ids are written, so the buffer can be sized by first calling this with a `capacity` of
+/// zero (in which case `out_node_ids` may be null). The order is stable for as long as the tree is unchanged.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_GetSubtreeNodeIds(
+    raw_tree: TaffyTreeConstRef,
+    root_node_id: TaffyNodeId,
+    order: TaffyTraversalOrder,
+    out_node_ids: *mut TaffyNodeId,
+    capacity: usize,
+) -> TaffyResult_usize {
+    with_tree!(raw_tree, tree, {
+        if capacity > 0 {
+            bail_if_null!(out_node_ids, NullArrayPointer);
+        }
+        let out_node_ids: &mut [TaffyNodeId] =
+            if capacity == 0 { &mut [] } else { ::core::slice::from_raw_parts_mut(out_node_ids, capacity) };
+        let count = match order {
+            TaffyTraversalOrder::DepthFirst => {
+                write_node_ids(try_tree!(tree.inner.iter_nodes_depth_first(root_node_id.into())), out_node_ids)
+            }
+            TaffyTraversalOrder::BreadthFirst => {
+                write_node_ids(try_tree!(tree.inner.iter_nodes_breadth_first(root_node_id.into())), out_node_ids)
+            }
+        };
+        ok!(count);
+    })
+}
+
+/// Write as many of `node_ids` as fit to `out_node_ids`, and return the total number of ids
+fn write_node_ids(node_ids: impl Iterator<Item = core::NodeId>, out_node_ids: &mut [TaffyNodeId]) -> usize {
+    let mut count = 0;
+    for node_id in node_ids {
+        if let Some(out) = out_node_ids.get_mut(count) {
+            *out = node_id.into();
+        }
+        count += 1;
+    }
+    count
+}
+
 // -------------------------------------------------
 // Style and Layout access
 // -------------------------------------------------
 
-/// Create a new Node in the TaffyTree. Returns a NodeId handle to the node.
+/// Get a handle to the style of a node, for use with the `TaffyStyle_*` functions. Setting a style property through
+/// the handle marks the node dirty. The handle remains valid (even if other nodes are added) until the node is removed.
 #[no_mangle]
 #[allow(clippy::missing_safety_doc)]
 pub unsafe extern "C" fn TaffyTree_GetStyleMut(
@@ -226,8 +749,8 @@ pub unsafe extern "C" fn TaffyTree_GetStyleMut(
     node_id: TaffyNodeId,
 ) -> TaffyResult_TaffyStyleMutRef {
     with_tree_mut!(raw_tree, tree, {
-        let style = try_or!(InvalidNodeId, tree.inner.try_style_mut(node_id.into()));
-        ok!(style as *mut core::Style as TaffyStyleMutRef);
+        try_tree!(tree.inner.style(node_id.into()));
+        ok!(TaffyStyleMutRef { tree: raw_tree, node_id });
     })
 }
 
@@ -241,10 +764,7 @@ pub unsafe extern "C" fn TaffyTree_SetNodeContext(
     context: *mut c_void,
 ) -> TaffyReturnCode {
     with_tree_mut!(raw_tree, tree, {
-        try_or!(
-            InvalidNodeId,
-            tree.inner.set_node_context(node_id.into(), Some(NodeContext { measure_function, context }))
-        );
+        try_tree!(tree.inner.set_node_context(node_id.into(), Some(NodeContext { measure_function, context })));
         ok!(TaffyReturnCode::Ok);
     })
 }
@@ -257,18 +777,154 @@ pub unsafe extern "C" fn TaffyTree_GetLayout(
     node_id: TaffyNodeId,
 ) -> TaffyResult_TaffyLayout {
     with_tree!(raw_tree, tree, {
-        let layout = try_or!(InvalidNodeId, tree.inner.layout(node_id.into()));
-        ok!(TaffyLayout {
-            x: layout.location.x,
-            y: layout.location.y,
-            width: layout.size.width,
-            height: layout.size.height,
-            content_width: layout.content_size.width,
-            content_height: layout.content_size.height,
-            border_left: layout.border.left,
-            border_right: layout.border.right,
-            border_top: layout.border.top,
-            border_bottom: layout.border.bottom,
-        });
+        let layout = try_tree!(tree.inner.layout(node_id.into()));
+        let user_data = try_tree!(tree.inner.user_data(node_id.into()));
+        ok!(TaffyLayout { user_data, ..TaffyLayout::from(layout) });
+    })
+}
+
+/// Get the unrounded layout of a node. Unlike `TaffyTree_GetLayout`, this is not snapped to whole pixels even if
+/// rounding is enabled, which makes it suitable for smoothly interpolating positions.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_GetUnroundedLayout(
+    raw_tree: TaffyTreeConstRef,
+    node_id: TaffyNodeId,
+) -> TaffyResult_TaffyLayout {
+    with_tree!(raw_tree, tree, {
+        let layout = try_tree!(tree.inner.unrounded_layout(node_id.into()));
+        let user_data = try_tree!(tree.inner.user_data(node_id.into()));
+        ok!(TaffyLayout { user_data, ..TaffyLayout::from(layout) });
+    })
+}
+
+/// Set an offset which is added to the location of a node after it has been laid out, for animations (such as shaking
+/// or sliding a node) which move a node without affecting the layout of anything else. The layouts of the node and its
+/// descendants are moved immediately, without marking anything dirty.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetLayoutOffset(
+    raw_tree: TaffyTreeMutRef,
+    node_id: TaffyNodeId,
+    x: f32,
+    y: f32,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        try_tree!(tree.inner.set_layout_offset(node_id.into(), taffy::geometry::Point { x, y }));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Attach a value to a node which Taffy stores but never interprets (such as a hit slop or a pointer events flag packed
+/// into 64 bits). It is returned in the `user_data` field of the node's `TaffyLayout`, and changing it never marks the
+/// node as dirty.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetNodeUserData(
+    raw_tree: TaffyTreeMutRef,
+    node_id: TaffyNodeId,
+    user_data: u64,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        try_tree!(tree.inner.set_user_data(node_id.into(), user_data));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Set the overlay of one of a node's state variants (such as hovered or pressed): the `count` style edits in `commands`,
+/// which are applied to the node's style while bit `variant` of its active variants is set (see
+/// `TaffyTree_SetActiveVariants`). The `node_id` of the edits is ignored. `variant` must be less than 64.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetVariantOverlay(
+    raw_tree: TaffyTreeMutRef,
+    node_id: TaffyNodeId,
+    variant: u32,
+    commands: *const TaffyStyleCommand,
+    count: usize,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        if variant >= 64 {
+            return TaffyReturnCode::InvalidVariant;
+        }
+        let mut overlay = Vec::with_capacity(count);
+        if count != 0 {
+            bail_if_null!(commands, NullArrayPointer);
+            for command in ::core::slice::from_raw_parts(commands, count) {
+                match command.to_core() {
+                    Ok(style_command) => overlay.push(style_command),
+                    Err(return_code) => return return_code,
+                }
+            }
+        }
+        let mut overlays = try_tree!(tree.inner.variant_overlays(node_id.into())).to_vec();
+        if overlays.len() <= variant as usize {
+            overlays.resize(variant as usize + 1, Vec::new());
+        }
+        overlays[variant as usize] = overlay;
+        try_tree!(tree.inner.set_variant_overlays(node_id.into(), overlays));
+        TaffyReturnCode::Ok
+    })
+}
+
+/// Set the bitset of a node's active state variants. The overlays of the active variants are merged into the node's
+/// style when they change, and the node is only marked as dirty if the merged style differs.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_SetActiveVariants(
+    raw_tree: TaffyTreeMutRef,
+    node_id: TaffyNodeId,
+    variants: u64,
+) -> TaffyReturnCode {
+    with_tree_mut!(raw_tree, tree, {
+        try_tree!(tree.inner.set_active_variants(node_id.into(), variants));
+        TaffyReturnCode::Ok
+    })
+}
+
+// -------------------------------------------------
+// Inspection
+// -------------------------------------------------
+
+/// Get a JSON description of a node combining its style, resolved style, layout, cache state, parent, children and
+/// the algorithm which lays it out (along with statistics about the placement of its items if it is a grid container).
+/// The returned string must be freed with `TaffyString_Free`.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_Inspect(
+    raw_tree: TaffyTreeConstRef,
+    node_id: TaffyNodeId,
+) -> TaffyResult_TaffyStringOwnedRef {
+    with_tree!(raw_tree, tree, {
+        let inspection = try_tree!(tree.inner.inspect(node_id.into()));
+        let json = crate::inspect::inspection_to_json(&inspection);
+        ok!(CString::new(json).unwrap_or_default().into_raw());
+    })
+}
+
+/// Get a JSON report of the style properties in the subtree rooted at `node_id` which have no effect on layout, for
+/// linting. The report is an array with an object for each warning, holding the `node` whose style it was found in, the
+/// `property` and a `message` describing the problem. The returned string must be freed with `TaffyString_Free`.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyTree_ValidateTree(
+    raw_tree: TaffyTreeConstRef,
+    node_id: TaffyNodeId,
+) -> TaffyResult_TaffyStringOwnedRef {
+    with_tree!(raw_tree, tree, {
+        let warnings = try_tree!(tree.inner.validate_tree(node_id.into()));
+        let json = crate::inspect::validation_to_json(&warnings);
+        ok!(CString::new(json).unwrap_or_default().into_raw());
+    })
+}
+
+/// Free a string returned by Taffy
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn TaffyString_Free(raw_string: TaffyStringOwnedRef) -> TaffyReturnCode {
+    bail_if_null!(raw_string, NullStringPointer);
+    catch_panic(None, || {
+        drop(CString::from_raw(raw_string));
+        TaffyReturnCode::Ok
     })
 }
@@ -2,7 +2,11 @@
 
 use taffy::prelude as core;
 
-use crate::{TaffyFFIResult, TaffyNodeId, TaffyStyleMutRef};
+use crate::{
+    TaffyAlignContent, TaffyAlignItems, TaffyDisplay, TaffyFFIResult, TaffyFlexDirection, TaffyFlexWrap,
+    TaffyGridAutoFlow, TaffyNodeId, TaffyOverflow, TaffyPercentResolutionMode, TaffyPosition, TaffyStringOwnedRef,
+    TaffyStyleMutRef,
+};
 
 use super::{TaffyFFIDefault, TaffyReturnCode};
 
@@ -46,6 +50,10 @@ pub enum TaffyUnit {
     Auto,
     /// fr unit
     Fr,
+    /// Size based on the content of the node (`flex-basis: content`). Behaves as `Auto` for other properties.
+    Content,
+    /// Percentage of the size of the layout root (the node passed to `TaffyTree_ComputeLayout`)
+    RootPercent,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -61,6 +69,48 @@ pub enum TaffyMeasureMode {
     MaxContent,
 }
 
+/// The order in which the nodes of a subtree are listed by `TaffyTree_GetSubtreeNodeIds`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum TaffyTraversalOrder {
+    /// Each node is followed by the subtree of its first child, then the subtree of its second child, and so on
+    DepthFirst,
+    /// The root, then its children in order, then their children, and so on
+    BreadthFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum TaffyAvailableSpaceType {
+    /// The amount of space available is the specified number of pixels
+    Definite,
+    /// The amount of space available is indefinite and the node should be laid out under a min-content constraint
+    MinContent,
+    /// The amount of space available is indefinite and the node should be laid out under a max-content constraint
+    MaxContent,
+}
+
+impl TaffyAvailableSpaceType {
+    /// Combine with a value (which is only used for `Definite` space) into a core `AvailableSpace`
+    pub(crate) fn with_value(self, value: f32) -> Result<core::AvailableSpace, TaffyReturnCode> {
+        match self {
+            TaffyAvailableSpaceType::Definite => {
+                if value.is_nan() {
+                    Err(TaffyReturnCode::UnexpectedNaN)
+                } else if value.is_infinite() {
+                    Err(TaffyReturnCode::UnexpectedInfinity)
+                } else if value < 0.0 {
+                    Err(TaffyReturnCode::UnexpectedNegative)
+                } else {
+                    Ok(core::AvailableSpace::Definite(value))
+                }
+            }
+            TaffyAvailableSpaceType::MinContent => Ok(core::AvailableSpace::MinContent),
+            TaffyAvailableSpaceType::MaxContent => Ok(core::AvailableSpace::MaxContent),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct TaffySize {
@@ -86,10 +136,77 @@ pub struct TaffyLayout {
     pub border_right: f32,
     pub border_top: f32,
     pub border_bottom: f32,
+    pub margin_left: f32,
+    pub margin_right: f32,
+    pub margin_top: f32,
+    pub margin_bottom: f32,
+    pub padding_left: f32,
+    pub padding_right: f32,
+    pub padding_top: f32,
+    pub padding_bottom: f32,
+    /// The distance from the top of the node to its first baseline, or NaN if it doesn't have one
+    pub first_baseline: f32,
+    /// How far the content of a scroll container was moved towards the end of each axis so that content alignment
+    /// doesn't place any of it past the start edge, where it couldn't be scrolled to
+    pub scroll_origin_shift_x: f32,
+    pub scroll_origin_shift_y: f32,
+    /// The value set with `TaffyTree_SetNodeUserData`, which Taffy stores but never interprets
+    pub user_data: u64,
+}
+impl From<&core::Layout> for TaffyLayout {
+    fn from(layout: &core::Layout) -> Self {
+        TaffyLayout {
+            x: layout.location.x,
+            y: layout.location.y,
+            width: layout.size.width,
+            height: layout.size.height,
+            content_width: layout.content_size.width,
+            content_height: layout.content_size.height,
+            border_left: layout.border.left,
+            border_right: layout.border.right,
+            border_top: layout.border.top,
+            border_bottom: layout.border.bottom,
+            margin_left: layout.margin.left,
+            margin_right: layout.margin.right,
+            margin_top: layout.margin.top,
+            margin_bottom: layout.margin.bottom,
+            padding_left: layout.padding.left,
+            padding_right: layout.padding.right,
+            padding_top: layout.padding.top,
+            padding_bottom: layout.padding.bottom,
+            first_baseline: layout.first_baseline.unwrap_or(f32::NAN),
+            scroll_origin_shift_x: layout.scroll_origin_shift.x,
+            scroll_origin_shift_y: layout.scroll_origin_shift.y,
+            user_data: 0,
+        }
+    }
 }
 impl TaffyFFIDefault for TaffyLayout {
     fn default() -> Self {
-        TaffyLayout { x: 0.0, y: 0.0, width: 0.0, height: 0.0, content_width: 0.0, content_height: 0.0, border_left: 0.0, border_right: 0.0, border_top: 0.0, border_bottom: 0.0 }
+        TaffyLayout {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            content_width: 0.0,
+            content_height: 0.0,
+            border_left: 0.0,
+            border_right: 0.0,
+            border_top: 0.0,
+            border_bottom: 0.0,
+            margin_left: 0.0,
+            margin_right: 0.0,
+            margin_top: 0.0,
+            margin_bottom: 0.0,
+            padding_left: 0.0,
+            padding_right: 0.0,
+            padding_top: 0.0,
+            padding_bottom: 0.0,
+            first_baseline: f32::NAN,
+            scroll_origin_shift_x: 0.0,
+            scroll_origin_shift_y: 0.0,
+            user_data: 0,
+        }
     }
 }
 
@@ -136,6 +253,8 @@ impl TryFrom<TaffyDimension> for core::LengthPercentage {
             TaffyUnit::FitContentPx => Err(TaffyReturnCode::InvalidFitContentPx),
             TaffyUnit::FitContentPercent => Err(TaffyReturnCode::InvalidFitContentPercent),
             TaffyUnit::Fr => Err(TaffyReturnCode::InvalidFr),
+            TaffyUnit::Content => Err(TaffyReturnCode::InvalidContent),
+            TaffyUnit::RootPercent => Err(TaffyReturnCode::InvalidRootPercent),
         }
     }
 }
@@ -164,6 +283,8 @@ impl TryFrom<TaffyDimension> for core::LengthPercentageAuto {
             TaffyUnit::FitContentPx => Err(TaffyReturnCode::InvalidFitContentPx),
             TaffyUnit::FitContentPercent => Err(TaffyReturnCode::InvalidFitContentPercent),
             TaffyUnit::Fr => Err(TaffyReturnCode::InvalidFr),
+            TaffyUnit::Content => Err(TaffyReturnCode::InvalidContent),
+            TaffyUnit::RootPercent => Err(TaffyReturnCode::InvalidRootPercent),
         }
     }
 }
@@ -174,6 +295,8 @@ impl From<core::Dimension> for TaffyDimension {
             core::Dimension::Length(value) => Self { unit: TaffyUnit::Length, value },
             core::Dimension::Percent(value) => Self { unit: TaffyUnit::Percent, value },
             core::Dimension::Auto => Self { unit: TaffyUnit::Auto, value: 0.0 },
+            core::Dimension::Content => Self { unit: TaffyUnit::Content, value: 0.0 },
+            core::Dimension::RootPercent(value) => Self { unit: TaffyUnit::RootPercent, value },
         }
     }
 }
@@ -184,8 +307,10 @@ impl TryFrom<TaffyDimension> for core::Dimension {
     fn try_from(value: TaffyDimension) -> Result<Self, Self::Error> {
         match value.unit {
             TaffyUnit::Auto => Ok(core::Dimension::Auto),
+            TaffyUnit::Content => Ok(core::Dimension::Content),
             TaffyUnit::Length => Ok(core::Dimension::Length(value.value)),
             TaffyUnit::Percent => Ok(core::Dimension::Percent(value.value)),
+            TaffyUnit::RootPercent => Ok(core::Dimension::RootPercent(value.value)),
             TaffyUnit::None => Err(TaffyReturnCode::InvalidNone),
             TaffyUnit::MinContent => Err(TaffyReturnCode::InvalidMinContent),
             TaffyUnit::MaxContent => Err(TaffyReturnCode::InvalidMaxContent),
@@ -196,38 +321,142 @@ impl TryFrom<TaffyDimension> for core::Dimension {
     }
 }
 
-/// For all fields, zero represents not set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum TaffyGridPlacementType {
+    /// Place the item automatically. The value and name are ignored.
+    Auto,
+    /// Place the item at the grid line specified by the value. Negative values count backwards from the end of the grid.
+    Line,
+    /// Span the number of tracks specified by the value
+    Span,
+    /// Place the item at the named grid line specified by the name (not yet supported)
+    Named,
+}
+
+/// The placement of one edge (start or end) of a grid item
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct TaffyGridPlacementValue {
+    /// How the value and name should be interpreted
+    pub kind: TaffyGridPlacementType,
+    /// The line index (for `Line`) or number of tracks (for `Span`). Zero is never valid.
+    pub value: i16,
+    /// A UTF-16 encoded line name (for `Named`). Null for all other types.
+    pub name: *const u16,
+    /// The length of the name in UTF-16 code units
+    pub name_len: usize,
+}
+
+impl TaffyGridPlacementValue {
+    /// An automatic placement
+    const AUTO: Self = Self { kind: TaffyGridPlacementType::Auto, value: 0, name: ::core::ptr::null(), name_len: 0 };
+}
+
+#[cfg(feature = "grid")]
+impl TryFrom<TaffyGridPlacementValue> for core::GridPlacement {
+    type Error = TaffyReturnCode;
+
+    fn try_from(placement: TaffyGridPlacementValue) -> Result<Self, Self::Error> {
+        match placement.kind {
+            TaffyGridPlacementType::Auto => Ok(core::GridPlacement::Auto),
+            TaffyGridPlacementType::Line if placement.value != 0 => Ok(core::line(placement.value)),
+            TaffyGridPlacementType::Span if placement.value > 0 => Ok(core::span(placement.value as u16)),
+            TaffyGridPlacementType::Line | TaffyGridPlacementType::Span => Err(TaffyReturnCode::InvalidGridPlacement),
+            TaffyGridPlacementType::Named => Err(TaffyReturnCode::UnsupportedNamedGridLine),
+        }
+    }
+}
+
+#[cfg(feature = "grid")]
+impl From<core::GridPlacement> for TaffyGridPlacementValue {
+    fn from(placement: core::GridPlacement) -> Self {
+        match placement {
+            core::GridPlacement::Auto => Self::AUTO,
+            core::GridPlacement::Line(line) => {
+                Self { kind: TaffyGridPlacementType::Line, value: line.as_i16(), ..Self::AUTO }
+            }
+            core::GridPlacement::Span(span) => {
+                Self { kind: TaffyGridPlacementType::Span, value: span.min(i16::MAX as u16) as i16, ..Self::AUTO }
+            }
+        }
+    }
+}
+
+/// The placement of a grid item in one axis
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub struct TaffyGridPlacement {
-    pub start: i16,
-    pub end: i16,
-    pub span: u16,
+    pub start: TaffyGridPlacementValue,
+    pub end: TaffyGridPlacementValue,
 }
 
 impl TaffyFFIDefault for TaffyGridPlacement {
     fn default() -> Self {
-        Self { start: 0, end: 0, span: 0 }
+        Self { start: TaffyGridPlacementValue::AUTO, end: TaffyGridPlacementValue::AUTO }
     }
 }
 
-impl From<TaffyGridPlacement> for core::Line<core::GridPlacement> {
-    fn from(placement: TaffyGridPlacement) -> Self {
-        Self::from_raw_parts(placement.start, placement.span, placement.end)
+#[cfg(feature = "grid")]
+impl TryFrom<TaffyGridPlacement> for core::Line<core::GridPlacement> {
+    type Error = TaffyReturnCode;
+
+    fn try_from(placement: TaffyGridPlacement) -> Result<Self, Self::Error> {
+        let placement = Self { start: placement.start.try_into()?, end: placement.end.try_into()? };
+        placement.validate().map_err(|_| TaffyReturnCode::InvalidGridPlacement)?;
+        Ok(placement)
     }
 }
 
+#[cfg(feature = "grid")]
 impl From<core::Line<core::GridPlacement>> for TaffyGridPlacement {
     fn from(placement: core::Line<core::GridPlacement>) -> Self {
-        let (start, span, end) = placement.into_raw_parts();
-        Self { start, span, end }
+        Self { start: placement.start.into(), end: placement.end.into() }
+    }
+}
+
+/// Switches for behaviours where layout may deviate from the web specifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct TaffyLayoutConfig {
+    pub use_rounding: bool,
+    pub percent_resolution_mode: TaffyPercentResolutionMode,
+    pub use_automatic_minimum_size: bool,
+    pub legacy_stretch: bool,
+    pub legacy_hidden_grid_items: bool,
+}
+impl From<taffy::LayoutConfig> for TaffyLayoutConfig {
+    fn from(config: taffy::LayoutConfig) -> Self {
+        TaffyLayoutConfig {
+            use_rounding: config.use_rounding,
+            percent_resolution_mode: config.percent_resolution_mode.into(),
+            use_automatic_minimum_size: config.use_automatic_minimum_size,
+            legacy_stretch: config.legacy_stretch,
+            legacy_hidden_grid_items: config.legacy_hidden_grid_items,
+        }
+    }
+}
+impl From<TaffyLayoutConfig> for taffy::LayoutConfig {
+    fn from(config: TaffyLayoutConfig) -> Self {
+        taffy::LayoutConfig {
+            use_rounding: config.use_rounding,
+            percent_resolution_mode: config.percent_resolution_mode.into(),
+            use_automatic_minimum_size: config.use_automatic_minimum_size,
+            legacy_stretch: config.legacy_stretch,
+            legacy_hidden_grid_items: config.legacy_hidden_grid_items,
+        }
+    }
+}
+impl TaffyFFIDefault for TaffyLayoutConfig {
+    fn default() -> Self {
+        taffy::LayoutConfig::DEFAULT.into()
     }
 }
 
 #[repr(C)]
 pub struct TaffyResult_TaffyLayout {
     pub return_code: TaffyReturnCode,
-    pub value: TaffyLayout
+    pub value: TaffyLayout,
 }
 
 impl TaffyFFIResult for TaffyResult_TaffyLayout {
@@ -235,7 +464,7 @@ impl TaffyFFIResult for TaffyResult_TaffyLayout {
         Self { return_code: TaffyReturnCode::Ok, value }
     }
     fn from_return_code(return_code: TaffyReturnCode) -> Self {
-        Self { return_code, value: TaffyLayout { x: 0.0, y: 0.0, width: 0.0, height: 0.0, content_width: 0.0, content_height: 0.0, border_left: 0.0, border_right: 0.0, border_top: 0.0, border_bottom: 0.0 } }
+        Self { return_code, value: TaffyLayout::default() }
     }
     type Value = TaffyLayout;
 }
@@ -243,7 +472,7 @@ impl TaffyFFIResult for TaffyResult_TaffyLayout {
 #[repr(C)]
 pub struct TaffyResult_TaffyNodeId {
     pub return_code: TaffyReturnCode,
-    pub value: TaffyNodeId
+    pub value: TaffyNodeId,
 }
 
 impl TaffyFFIResult for TaffyResult_TaffyNodeId {
@@ -259,7 +488,7 @@ impl TaffyFFIResult for TaffyResult_TaffyNodeId {
 #[repr(C)]
 pub struct TaffyResult_TaffyStyleMutRef {
     pub return_code: TaffyReturnCode,
-    pub value: TaffyStyleMutRef
+    pub value: TaffyStyleMutRef,
 }
 
 impl TaffyFFIResult for TaffyResult_TaffyStyleMutRef {
@@ -267,7 +496,407 @@ impl TaffyFFIResult for TaffyResult_TaffyStyleMutRef {
         Self { return_code: TaffyReturnCode::Ok, value }
     }
     fn from_return_code(return_code: TaffyReturnCode) -> Self {
-        Self { return_code, value: std::ptr::null_mut() }
+        Self { return_code, value: TaffyStyleMutRef::default() }
     }
     type Value = TaffyStyleMutRef;
-}
\ No newline at end of file
+}
+
+#[repr(C)]
+pub struct TaffyResult_TaffyStringOwnedRef {
+    pub return_code: TaffyReturnCode,
+    pub value: TaffyStringOwnedRef,
+}
+
+impl TaffyFFIResult for TaffyResult_TaffyStringOwnedRef {
+    fn from_value(value: TaffyStringOwnedRef) -> Self {
+        Self { return_code: TaffyReturnCode::Ok, value }
+    }
+    fn from_return_code(return_code: TaffyReturnCode) -> Self {
+        Self { return_code, value: ::core::ptr::null_mut() }
+    }
+    type Value = TaffyStringOwnedRef;
+}
+
+#[repr(C)]
+pub struct TaffyResult_TaffyLayoutConfig {
+    pub return_code: TaffyReturnCode,
+    pub value: TaffyLayoutConfig,
+}
+
+impl TaffyFFIResult for TaffyResult_TaffyLayoutConfig {
+    fn from_value(value: TaffyLayoutConfig) -> Self {
+        Self { return_code: TaffyReturnCode::Ok, value }
+    }
+    fn from_return_code(return_code: TaffyReturnCode) -> Self {
+        Self { return_code, value: TaffyLayoutConfig::default() }
+    }
+    type Value = TaffyLayoutConfig;
+}
+
+/// A numeric style property which can be set by a `TaffyStyleCommand`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum TaffyStyleProperty {
+    Width,
+    Height,
+    MinWidth,
+    MinHeight,
+    MaxWidth,
+    MaxHeight,
+    InsetLeft,
+    InsetRight,
+    InsetTop,
+    InsetBottom,
+    MarginLeft,
+    MarginRight,
+    MarginTop,
+    MarginBottom,
+    PaddingLeft,
+    PaddingRight,
+    PaddingTop,
+    PaddingBottom,
+    BorderLeft,
+    BorderRight,
+    BorderTop,
+    BorderBottom,
+    /// The unit is ignored. A value which is not finite and positive unsets the aspect ratio.
+    AspectRatio,
+    ColumnGap,
+    RowGap,
+    FlexBasis,
+    /// The unit is ignored
+    FlexGrow,
+    /// The unit is ignored
+    FlexShrink,
+}
+
+/// An edit to a single property of a node's style, applied with `TaffyTree_ApplyStyleCommands`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct TaffyStyleCommand {
+    pub node_id: TaffyNodeId,
+    pub property: TaffyStyleProperty,
+    pub value: TaffyDimension,
+}
+
+impl TaffyStyleCommand {
+    /// Convert the command into an edit of a [`core::Style`]
+    ///
+    /// Setting a property of a layout algorithm which was not compiled into the library returns `InvalidEnumValue`.
+    pub(crate) fn to_core(self) -> Result<taffy::StyleCommand, TaffyReturnCode> {
+        use taffy::StyleCommand as Command;
+        let value = self.value;
+        Ok(match self.property {
+            TaffyStyleProperty::Width => Command::Width(value.try_into()?),
+            TaffyStyleProperty::Height => Command::Height(value.try_into()?),
+            TaffyStyleProperty::MinWidth => Command::MinWidth(value.try_into()?),
+            TaffyStyleProperty::MinHeight => Command::MinHeight(value.try_into()?),
+            TaffyStyleProperty::MaxWidth => Command::MaxWidth(value.try_into()?),
+            TaffyStyleProperty::MaxHeight => Command::MaxHeight(value.try_into()?),
+            TaffyStyleProperty::InsetLeft => Command::InsetLeft(value.try_into()?),
+            TaffyStyleProperty::InsetRight => Command::InsetRight(value.try_into()?),
+            TaffyStyleProperty::InsetTop => Command::InsetTop(value.try_into()?),
+            TaffyStyleProperty::InsetBottom => Command::InsetBottom(value.try_into()?),
+            TaffyStyleProperty::MarginLeft => Command::MarginLeft(value.try_into()?),
+            TaffyStyleProperty::MarginRight => Command::MarginRight(value.try_into()?),
+            TaffyStyleProperty::MarginTop => Command::MarginTop(value.try_into()?),
+            TaffyStyleProperty::MarginBottom => Command::MarginBottom(value.try_into()?),
+            TaffyStyleProperty::PaddingLeft => Command::PaddingLeft(value.try_into()?),
+            TaffyStyleProperty::PaddingRight => Command::PaddingRight(value.try_into()?),
+            TaffyStyleProperty::PaddingTop => Command::PaddingTop(value.try_into()?),
+            TaffyStyleProperty::PaddingBottom => Command::PaddingBottom(value.try_into()?),
+            TaffyStyleProperty::BorderLeft => Command::BorderLeft(value.try_into()?),
+            TaffyStyleProperty::BorderRight => Command::BorderRight(value.try_into()?),
+            TaffyStyleProperty::BorderTop => Command::BorderTop(value.try_into()?),
+            TaffyStyleProperty::BorderBottom => Command::BorderBottom(value.try_into()?),
+            TaffyStyleProperty::AspectRatio => {
+                Command::AspectRatio((value.value.is_finite() && value.value > 0.0).then_some(value.value))
+            }
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            TaffyStyleProperty::ColumnGap => Command::ColumnGap(value.try_into()?),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            TaffyStyleProperty::RowGap => Command::RowGap(value.try_into()?),
+            #[cfg(feature = "flexbox")]
+            TaffyStyleProperty::FlexBasis => Command::FlexBasis(value.try_into()?),
+            #[cfg(feature = "flexbox")]
+            TaffyStyleProperty::FlexGrow => Command::FlexGrow(value.value),
+            #[cfg(feature = "flexbox")]
+            TaffyStyleProperty::FlexShrink => Command::FlexShrink(value.value),
+            #[allow(unreachable_patterns)]
+            _ => return Err(TaffyReturnCode::InvalidEnumValue),
+        })
+    }
+}
+
+/// The values of a node's style properties, for setting them all at once (with `TaffyTree_NewLeafWithStyle` or
+/// `TaffyTree_NewWithChildren`) rather than one function call at a time
+///
+/// Start from the snapshot returned by `TaffyTree_GetDefaultStyleSnapshot` and change the properties you need. Grid
+/// track lists can't be represented and are left as they are in the tree's default style. Properties of layout
+/// algorithms which were not compiled into the library are ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct TaffyStyleSnapshot {
+    pub display: TaffyDisplay,
+    pub position: TaffyPosition,
+    pub overflow_x: TaffyOverflow,
+    pub overflow_y: TaffyOverflow,
+    /// The width reserved for the scrollbar of a scroll container
+    pub scrollbar_width: f32,
+
+    pub width: TaffyDimension,
+    pub height: TaffyDimension,
+    pub min_width: TaffyDimension,
+    pub min_height: TaffyDimension,
+    pub max_width: TaffyDimension,
+    pub max_height: TaffyDimension,
+    /// The aspect ratio (width divided by height), or NaN for none
+    pub aspect_ratio: f32,
+
+    pub inset_left: TaffyDimension,
+    pub inset_right: TaffyDimension,
+    pub inset_top: TaffyDimension,
+    pub inset_bottom: TaffyDimension,
+    pub margin_left: TaffyDimension,
+    pub margin_right: TaffyDimension,
+    pub margin_top: TaffyDimension,
+    pub margin_bottom: TaffyDimension,
+    pub padding_left: TaffyDimension,
+    pub padding_right: TaffyDimension,
+    pub padding_top: TaffyDimension,
+    pub padding_bottom: TaffyDimension,
+    pub border_left: TaffyDimension,
+    pub border_right: TaffyDimension,
+    pub border_top: TaffyDimension,
+    pub border_bottom: TaffyDimension,
+
+    pub align_content: TaffyAlignContent,
+    pub align_items: TaffyAlignItems,
+    pub align_self: TaffyAlignItems,
+    pub justify_content: TaffyAlignContent,
+    pub justify_items: TaffyAlignItems,
+    pub justify_self: TaffyAlignItems,
+    pub column_gap: TaffyDimension,
+    pub row_gap: TaffyDimension,
+
+    pub flex_direction: TaffyFlexDirection,
+    pub flex_wrap: TaffyFlexWrap,
+    pub flex_basis: TaffyDimension,
+    pub flex_grow: f32,
+    pub flex_shrink: f32,
+
+    pub grid_auto_flow: TaffyGridAutoFlow,
+    pub grid_row: TaffyGridPlacement,
+    pub grid_column: TaffyGridPlacement,
+}
+
+impl TaffyStyleSnapshot {
+    /// Set the properties of `style` to the values in the snapshot, or return the [`TaffyReturnCode`] describing why a
+    /// value is invalid (in which case `style` may have been partially updated)
+    pub(crate) fn apply_to(&self, style: &mut core::Style) -> Result<(), TaffyReturnCode> {
+        style.display = self.display.try_into()?;
+        style.position = self.position.into();
+        style.overflow = taffy::geometry::Point { x: self.overflow_x.into(), y: self.overflow_y.into() };
+        style.scrollbar_width = self.scrollbar_width;
+
+        style.size = core::Size { width: self.width.try_into()?, height: self.height.try_into()? };
+        style.min_size = core::Size { width: self.min_width.try_into()?, height: self.min_height.try_into()? };
+        style.max_size = core::Size { width: self.max_width.try_into()?, height: self.max_height.try_into()? };
+        style.aspect_ratio = (self.aspect_ratio.is_finite() && self.aspect_ratio > 0.0).then_some(self.aspect_ratio);
+
+        style.inset = core::Rect {
+            left: self.inset_left.try_into()?,
+            right: self.inset_right.try_into()?,
+            top: self.inset_top.try_into()?,
+            bottom: self.inset_bottom.try_into()?,
+        };
+        style.margin = core::Rect {
+            left: self.margin_left.try_into()?,
+            right: self.margin_right.try_into()?,
+            top: self.margin_top.try_into()?,
+            bottom: self.margin_bottom.try_into()?,
+        };
+        style.padding = core::Rect {
+            left: self.padding_left.try_into()?,
+            right: self.padding_right.try_into()?,
+            top: self.padding_top.try_into()?,
+            bottom: self.padding_bottom.try_into()?,
+        };
+        style.border = core::Rect {
+            left: self.border_left.try_into()?,
+            right: self.border_right.try_into()?,
+            top: self.border_top.try_into()?,
+            bottom: self.border_bottom.try_into()?,
+        };
+
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        {
+            style.align_content = self.align_content.into();
+            style.align_items = self.align_items.into();
+            style.align_self = self.align_self.into();
+            style.justify_content = self.justify_content.into();
+            style.gap = core::Size { width: self.column_gap.try_into()?, height: self.row_gap.try_into()? };
+        }
+
+        #[cfg(feature = "flexbox")]
+        {
+            style.flex_direction = self.flex_direction.into();
+            style.flex_wrap = self.flex_wrap.into();
+            style.flex_basis = self.flex_basis.try_into()?;
+            style.flex_grow = self.flex_grow;
+            style.flex_shrink = self.flex_shrink;
+        }
+
+        #[cfg(feature = "grid")]
+        {
+            style.justify_items = self.justify_items.into();
+            style.justify_self = self.justify_self.into();
+            style.grid_auto_flow = self.grid_auto_flow.into();
+            style.grid_row = self.grid_row.try_into()?;
+            style.grid_column = self.grid_column.try_into()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&core::Style> for TaffyStyleSnapshot {
+    fn from(style: &core::Style) -> Self {
+        TaffyStyleSnapshot {
+            display: style.display.into(),
+            position: style.position.into(),
+            overflow_x: style.overflow.x.into(),
+            overflow_y: style.overflow.y.into(),
+            scrollbar_width: style.scrollbar_width,
+
+            width: style.size.width.into(),
+            height: style.size.height.into(),
+            min_width: style.min_size.width.into(),
+            min_height: style.min_size.height.into(),
+            max_width: style.max_size.width.into(),
+            max_height: style.max_size.height.into(),
+            aspect_ratio: style.aspect_ratio.unwrap_or(f32::NAN),
+
+            inset_left: style.inset.left.into(),
+            inset_right: style.inset.right.into(),
+            inset_top: style.inset.top.into(),
+            inset_bottom: style.inset.bottom.into(),
+            margin_left: style.margin.left.into(),
+            margin_right: style.margin.right.into(),
+            margin_top: style.margin.top.into(),
+            margin_bottom: style.margin.bottom.into(),
+            padding_left: style.padding.left.into(),
+            padding_right: style.padding.right.into(),
+            padding_top: style.padding.top.into(),
+            padding_bottom: style.padding.bottom.into(),
+            border_left: style.border.left.into(),
+            border_right: style.border.right.into(),
+            border_top: style.border.top.into(),
+            border_bottom: style.border.bottom.into(),
+
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_content: style.align_content.into(),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_items: style.align_items.into(),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_self: style.align_self.into(),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            justify_content: style.justify_content.into(),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            column_gap: style.gap.width.into(),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            row_gap: style.gap.height.into(),
+            #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+            align_content: TaffyAlignContent::Normal,
+            #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+            align_items: TaffyAlignItems::Normal,
+            #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+            align_self: TaffyAlignItems::Normal,
+            #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+            justify_content: TaffyAlignContent::Normal,
+            #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+            column_gap: core::LengthPercentage::Length(0.0).into(),
+            #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+            row_gap: core::LengthPercentage::Length(0.0).into(),
+
+            #[cfg(feature = "flexbox")]
+            flex_direction: style.flex_direction.into(),
+            #[cfg(feature = "flexbox")]
+            flex_wrap: style.flex_wrap.into(),
+            #[cfg(feature = "flexbox")]
+            flex_basis: style.flex_basis.into(),
+            #[cfg(feature = "flexbox")]
+            flex_grow: style.flex_grow,
+            #[cfg(feature = "flexbox")]
+            flex_shrink: style.flex_shrink,
+            #[cfg(not(feature = "flexbox"))]
+            flex_direction: TaffyFlexDirection::Row,
+            #[cfg(not(feature = "flexbox"))]
+            flex_wrap: TaffyFlexWrap::NoWrap,
+            #[cfg(not(feature = "flexbox"))]
+            flex_basis: core::Dimension::Auto.into(),
+            #[cfg(not(feature = "flexbox"))]
+            flex_grow: 0.0,
+            #[cfg(not(feature = "flexbox"))]
+            flex_shrink: 1.0,
+
+            #[cfg(feature = "grid")]
+            justify_items: style.justify_items.into(),
+            #[cfg(feature = "grid")]
+            justify_self: style.justify_self.into(),
+            #[cfg(feature = "grid")]
+            grid_auto_flow: style.grid_auto_flow.into(),
+            #[cfg(feature = "grid")]
+            grid_row: style.grid_row.into(),
+            #[cfg(feature = "grid")]
+            grid_column: style.grid_column.into(),
+            #[cfg(not(feature = "grid"))]
+            justify_items: TaffyAlignItems::Normal,
+            #[cfg(not(feature = "grid"))]
+            justify_self: TaffyAlignItems::Normal,
+            #[cfg(not(feature = "grid"))]
+            grid_auto_flow: TaffyGridAutoFlow::Row,
+            #[cfg(not(feature = "grid"))]
+            grid_row: TaffyGridPlacement::default(),
+            #[cfg(not(feature = "grid"))]
+            grid_column: TaffyGridPlacement::default(),
+        }
+    }
+}
+impl TaffyFFIDefault for TaffyStyleSnapshot {
+    fn default() -> Self {
+        (&core::Style::DEFAULT).into()
+    }
+}
+
+#[repr(C)]
+pub struct TaffyResult_TaffyStyleSnapshot {
+    pub return_code: TaffyReturnCode,
+    pub value: TaffyStyleSnapshot,
+}
+
+impl TaffyFFIResult for TaffyResult_TaffyStyleSnapshot {
+    fn from_value(value: TaffyStyleSnapshot) -> Self {
+        Self { return_code: TaffyReturnCode::Ok, value }
+    }
+    fn from_return_code(return_code: TaffyReturnCode) -> Self {
+        Self { return_code, value: TaffyStyleSnapshot::default() }
+    }
+    type Value = TaffyStyleSnapshot;
+}
+
+#[repr(C)]
+pub struct TaffyResult_usize {
+    pub return_code: TaffyReturnCode,
+    pub value: usize,
+}
+
+impl TaffyFFIResult for TaffyResult_usize {
+    fn from_value(value: usize) -> Self {
+        Self { return_code: TaffyReturnCode::Ok, value }
+    }
+    fn from_return_code(return_code: TaffyReturnCode) -> Self {
+        Self { return_code, value: 0 }
+    }
+    type Value = usize;
+}
@@ -0,0 +1,93 @@
+//! Version and capability queries for C FFI, so that bindings can detect at runtime which version of the library they
+//! are linked against and which optional features it was built with
+
+/// The major version of the library's ABI. This is bumped (and the minor and patch versions are reset) whenever a
+/// function, type, enum variant or return code is removed or changed in a way which breaks existing callers.
+pub const TAFFY_ABI_VERSION_MAJOR: u32 = 1;
+/// The minor version of the library's ABI. This is bumped (and the patch version is reset) whenever a function, type,
+/// enum variant, feature or return code is added.
+pub const TAFFY_ABI_VERSION_MINOR: u32 = 0;
+/// The patch version of the library's ABI. This is bumped whenever the behaviour of existing functions is fixed without
+/// changing the ABI.
+pub const TAFFY_ABI_VERSION_PATCH: u32 = 0;
+
+/// A semantic version number
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaffyVersion {
+    /// The major version, which differs between incompatible versions
+    pub major: u32,
+    /// The minor version, which is greater in versions which add to the previous version
+    pub minor: u32,
+    /// The patch version, which is greater in versions which fix the previous version
+    pub patch: u32,
+}
+
+/// An optional feature which the library may have been built with. See `Taffy_HasFeature`.
+///
+/// New features are only ever added to the end of this list, so the id of each feature is stable.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaffyFeature {
+    /// The Flexbox layout algorithm (`TAFFY_DISPLAY_FLEX`)
+    Flexbox,
+    /// The CSS Grid layout algorithm (`TAFFY_DISPLAY_GRID`)
+    Grid,
+    /// The Block layout algorithm (`TAFFY_DISPLAY_BLOCK`)
+    Block,
+    /// Measuring leaf nodes with a measure function (`TaffyTree_SetNodeContext`)
+    MeasureCallbacks,
+    /// Linking against the Rust standard library (which enables `TaffyTree_PrintTree`)
+    Std,
+    /// Routing allocations through the functions set with `Taffy_SetAllocator`
+    AllocatorHook,
+    /// Detecting node ids which were created by a different tree (`TAFFY_RETURN_CODE_FOREIGN_NODE_ID`)
+    NodeIdBrand,
+}
+
+impl TaffyFeature {
+    /// Every feature, in order of id
+    const ALL: [TaffyFeature; 7] = [
+        TaffyFeature::Flexbox,
+        TaffyFeature::Grid,
+        TaffyFeature::Block,
+        TaffyFeature::MeasureCallbacks,
+        TaffyFeature::Std,
+        TaffyFeature::AllocatorHook,
+        TaffyFeature::NodeIdBrand,
+    ];
+
+    /// Whether the library was built with the feature
+    fn is_enabled(self) -> bool {
+        match self {
+            TaffyFeature::Flexbox => cfg!(feature = "flexbox"),
+            TaffyFeature::Grid => cfg!(feature = "grid"),
+            TaffyFeature::Block => cfg!(feature = "block"),
+            TaffyFeature::MeasureCallbacks => true,
+            TaffyFeature::Std => cfg!(feature = "std"),
+            TaffyFeature::AllocatorHook => cfg!(any(feature = "allocator_hook", not(feature = "std"))),
+            TaffyFeature::NodeIdBrand => cfg!(feature = "node_id_brand"),
+        }
+    }
+}
+
+/// Get the version of the library's ABI (`TAFFY_ABI_VERSION_MAJOR`, `TAFFY_ABI_VERSION_MINOR` and
+/// `TAFFY_ABI_VERSION_PATCH` as the library was built). This is independent of the version of the Rust crates.
+///
+/// Code built against a header is compatible with a library with the same major version and a minor version at least
+/// as great as the header's, as later minor versions only add functions, types, enum variants, features and return
+/// codes.
+#[no_mangle]
+pub extern "C" fn Taffy_GetVersion() -> TaffyVersion {
+    TaffyVersion { major: TAFFY_ABI_VERSION_MAJOR, minor: TAFFY_ABI_VERSION_MINOR, patch: TAFFY_ABI_VERSION_PATCH }
+}
+
+/// Whether the library was built with the feature with the specified id (the value of a `TaffyFeature`). Returns false
+/// for ids which are unknown to this version of the library.
+#[no_mangle]
+pub extern "C" fn Taffy_HasFeature(feature_id: i32) -> bool {
+    usize::try_from(feature_id)
+        .ok()
+        .and_then(|index| TaffyFeature::ALL.get(index))
+        .is_some_and(|feature| feature.is_enabled())
+}
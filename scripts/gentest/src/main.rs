@@ -462,6 +462,7 @@ fn generate_node(ident: &str, node: &Value) -> TokenStream {
             "flex-end" => quote!(align_items: Some(taffy::style::AlignItems::FlexEnd),),
             "center" => quote!(align_items: Some(taffy::style::AlignItems::Center),),
             "baseline" => quote!(align_items: Some(taffy::style::AlignItems::Baseline),),
+            "last baseline" => quote!(align_items: Some(taffy::style::AlignItems::LastBaseline),),
             "stretch" => quote!(align_items: Some(taffy::style::AlignItems::Stretch),),
             _ => quote!(),
         },
@@ -476,6 +477,7 @@ fn generate_node(ident: &str, node: &Value) -> TokenStream {
             "flex-end" => quote!(align_self: Some(taffy::style::AlignSelf::FlexEnd),),
             "center" => quote!(align_self: Some(taffy::style::AlignSelf::Center),),
             "baseline" => quote!(align_self: Some(taffy::style::AlignSelf::Baseline),),
+            "last baseline" => quote!(align_self: Some(taffy::style::AlignSelf::LastBaseline),),
             "stretch" => quote!(align_self: Some(taffy::style::AlignSelf::Stretch),),
             _ => quote!(),
         },
@@ -490,6 +492,7 @@ fn generate_node(ident: &str, node: &Value) -> TokenStream {
             "flex-end" => quote!(justify_items: Some(taffy::style::JustifyItems::FlexEnd),),
             "center" => quote!(justify_items: Some(taffy::style::JustifyItems::Center),),
             "baseline" => quote!(justify_items: Some(taffy::style::JustifyItems::Baseline),),
+            "last baseline" => quote!(justify_items: Some(taffy::style::JustifyItems::LastBaseline),),
             "stretch" => quote!(justify_items: Some(taffy::style::JustifyItems::Stretch),),
             _ => quote!(),
         },
@@ -504,6 +507,7 @@ fn generate_node(ident: &str, node: &Value) -> TokenStream {
             "flex-end" => quote!(justify_self: Some(taffy::style::JustifySelf::FlexEnd),),
             "center" => quote!(justify_self: Some(taffy::style::JustifySelf::Center),),
             "baseline" => quote!(justify_self: Some(taffy::style::JustifySelf::Baseline),),
+            "last baseline" => quote!(justify_self: Some(taffy::style::JustifySelf::LastBaseline),),
             "stretch" => quote!(justify_self: Some(taffy::style::JustifySelf::Stretch),),
             _ => quote!(),
         },
@@ -741,6 +745,7 @@ fn generate_dimension(dimen: &serde_json::Map<String, Value>) -> TokenStream {
     match unit {
         Value::String(ref unit) => match unit.as_ref() {
             "auto" => quote!(taffy::style::Dimension::Auto),
+            "content" => quote!(taffy::style::Dimension::Content),
             "px" => {
                 let value = value();
                 quote!(taffy::style::Dimension::Length(#value))
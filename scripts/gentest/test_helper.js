@@ -116,6 +116,7 @@ function parseDimension(input, options = { allowFrUnits: false }) {
   if (input.endsWith('px')) return { unit: 'px', value: parseFloat(input.replace('px', '')) };
   if (input.endsWith('%')) return { unit: 'percent', value: parseFloat(input.replace('%', '')) / 100 };
   if (input === 'auto') return { unit: 'auto' };
+  if (input === 'content') return { unit: 'content' };
   if (input === 'min-content') return { unit: 'min-content' };
   if (input === 'max-content') return { unit: 'max-content' };
   return undefined;
@@ -0,0 +1,255 @@
+//! Save a [`TaffyTree`] to, and load it from, a compact binary format
+//!
+//! This allows pre-built layouts to be shipped as assets and loaded without constructing styles by hand or parsing a text
+//! format. See [`TaffyTree::to_bytes`] and [`TaffyTree::from_bytes`].
+//!
+//! The format starts with a header identifying the format and its version. Everything after the header is written as
+//! lists of optional fields, each identified by a numeric tag: fields which are equal to their default value are omitted,
+//! and fields which a reader doesn't know about (because they were added by a newer version of Taffy, or belong to a
+//! layout algorithm which is not enabled) are skipped. The version is only incremented for changes which can't be
+//! represented this way.
+//!
+//! Node contexts are not saved.
+use core::fmt;
+
+use crate::style::Style;
+use crate::tree::{LayoutConfig, NodeId, StyleHandle, TaffyResult, TaffyTree};
+use crate::util::codec::{fields_codec, read_fields, Codec, CodecError, FieldWriter, Reader};
+use crate::util::sys::Vec;
+
+/// The bytes at the start of every saved tree
+const MAGIC: &[u8; 8] = b"TAFFYBIN";
+/// The version of the format. Only incremented for changes which older versions could not read.
+const FORMAT_VERSION: u8 = 1;
+
+/// An error that occurs while loading a tree with [`TaffyTree::from_bytes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryError {
+    /// The data does not start with the header of a saved tree
+    InvalidHeader,
+    /// The data was saved in a version of the format which is not supported by this version of Taffy
+    UnsupportedVersion(u8),
+    /// The data ends part way through the tree
+    UnexpectedEnd,
+    /// The data contains a value which is not valid
+    InvalidValue,
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinaryError::InvalidHeader => write!(f, "The data is not a saved Taffy tree"),
+            BinaryError::UnsupportedVersion(version) => {
+                write!(f, "Version {version} of the binary format is not supported (expected {FORMAT_VERSION})")
+            }
+            BinaryError::UnexpectedEnd => write!(f, "The data ends part way through the tree"),
+            BinaryError::InvalidValue => write!(f, "The data contains an invalid value"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BinaryError {}
+
+impl From<CodecError> for BinaryError {
+    fn from(error: CodecError) -> Self {
+        match error {
+            CodecError::UnexpectedEnd => BinaryError::UnexpectedEnd,
+            CodecError::InvalidValue => BinaryError::InvalidValue,
+        }
+    }
+}
+
+/// A saved node
+#[derive(Debug, Clone, PartialEq, Default)]
+struct SavedNode {
+    /// The style of the node, if it does not use a shared style
+    style: Style,
+    /// The index of the node's shared style in [`SavedTree::shared_styles`] (if it uses one)
+    shared_style: Option<usize>,
+    /// The indexes of the node's children in [`SavedTree::nodes`]. These are always greater than the index of the node
+    /// itself, and increasing.
+    children: Vec<usize>,
+}
+
+fields_codec!(SavedNode = SavedNode::default(); {
+    0 => style,
+    1 => shared_style,
+    2 => children,
+});
+
+/// A saved tree
+#[derive(Debug, Clone, PartialEq)]
+struct SavedTree {
+    /// The tree's layout configuration
+    config: LayoutConfig,
+    /// The shared styles used by the saved nodes
+    shared_styles: Vec<Style>,
+    /// The saved nodes, in breadth-first order
+    nodes: Vec<SavedNode>,
+    /// The indexes of the root nodes in [`SavedTree::nodes`]
+    roots: Vec<usize>,
+}
+
+impl SavedTree {
+    /// A tree with no nodes and the default configuration
+    const EMPTY: SavedTree =
+        SavedTree { config: LayoutConfig::DEFAULT, shared_styles: Vec::new(), nodes: Vec::new(), roots: Vec::new() };
+}
+
+fields_codec!(SavedTree = SavedTree::EMPTY; {
+    0 => config,
+    1 => shared_styles,
+    2 => nodes,
+    3 => roots,
+});
+
+impl<NodeContext> TaffyTree<NodeContext> {
+    /// Saves the subtrees rooted at `roots` (and the tree's [`LayoutConfig`]) in a compact binary format
+    ///
+    /// The saved tree can be loaded with [`from_bytes`](Self::from_bytes). See the [`binary`](crate::binary) module for
+    /// details of the format.
+    pub fn to_bytes(&self, roots: &[NodeId]) -> TaffyResult<Vec<u8>> {
+        let mut order: Vec<NodeId> = Vec::new();
+        for &root in roots {
+            self.style(root)?;
+            order.push(root);
+        }
+
+        let mut saved = SavedTree { config: self.layout_config(), ..SavedTree::EMPTY };
+        let mut shared_handles: Vec<StyleHandle> = Vec::new();
+        let mut index = 0;
+        while index < order.len() {
+            let node = order[index];
+            let shared_style = self.node_style_handle(node)?.map(|handle| {
+                shared_handles.iter().position(|&h| h == handle).unwrap_or_else(|| {
+                    shared_handles.push(handle);
+                    saved.shared_styles.push(self.shared_style(handle).unwrap().clone());
+                    shared_handles.len() - 1
+                })
+            });
+            let style = match shared_style {
+                Some(_) => Style::DEFAULT,
+                None => self.style(node)?.clone(),
+            };
+            let children = self.children(node)?;
+            let first_child = order.len();
+            order.extend(children.iter().copied());
+            saved.nodes.push(SavedNode { style, shared_style, children: (first_child..order.len()).collect() });
+            index += 1;
+        }
+        saved.roots = (0..roots.len()).collect();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        saved.encode(&mut bytes);
+        Ok(bytes)
+    }
+
+    /// Loads a tree saved with [`to_bytes`](Self::to_bytes), and returns it along with its root nodes (in the order in
+    /// which they were passed to `to_bytes`)
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, Vec<NodeId>), BinaryError> {
+        let rest = bytes.strip_prefix(MAGIC.as_slice()).ok_or(BinaryError::InvalidHeader)?;
+        let (&version, rest) = rest.split_first().ok_or(BinaryError::InvalidHeader)?;
+        if version != FORMAT_VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+        let saved = SavedTree::decode(&mut Reader::new(rest))?;
+
+        // Each node must be the child of at most one node which comes before it, so that the nodes form a forest
+        let mut parents: Vec<Option<usize>> = core::iter::repeat(None).take(saved.nodes.len()).collect();
+        for (index, node) in saved.nodes.iter().enumerate() {
+            let mut previous_child = index;
+            for &child in &node.children {
+                if child <= previous_child || child >= parents.len() || parents[child].is_some() {
+                    return Err(BinaryError::InvalidValue);
+                }
+                parents[child] = Some(index);
+                previous_child = child;
+            }
+            if matches!(node.shared_style, Some(shared_style) if shared_style >= saved.shared_styles.len()) {
+                return Err(BinaryError::InvalidValue);
+            }
+        }
+        if saved.roots.iter().any(|&root| root >= parents.len() || parents[root].is_some()) {
+            return Err(BinaryError::InvalidValue);
+        }
+
+        let mut tree = Self::with_capacity(saved.nodes.len());
+        tree.set_layout_config(saved.config);
+        let shared_handles: Vec<StyleHandle> =
+            saved.shared_styles.into_iter().map(|style| tree.create_shared_style(style)).collect();
+        let shared_styles: Vec<Option<usize>> = saved.nodes.iter().map(|node| node.shared_style).collect();
+        let styles: Vec<Style> = saved.nodes.into_iter().map(|node| node.style).collect();
+        let ids = tree.build_from_slices(&styles, &parents).map_err(|_| BinaryError::InvalidValue)?;
+        for (&id, shared_style) in ids.iter().zip(shared_styles) {
+            if let Some(shared_style) = shared_style {
+                tree.set_node_style_handle(id, shared_handles[shared_style]).unwrap();
+            }
+        }
+
+        let roots = saved.roots.iter().map(|&root| ids[root]).collect();
+        Ok((tree, roots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryError;
+    use crate::prelude::*;
+
+    #[test]
+    fn saved_tree_loads_with_same_layout() {
+        let mut tree: TaffyTree = TaffyTree::new();
+        tree.disable_rounding();
+        let shared = tree.create_shared_style(Style { flex_grow: 1.0, ..Default::default() });
+        let a = tree.new_leaf(Style::default()).unwrap();
+        let b = tree.new_leaf(Style::default()).unwrap();
+        tree.set_node_style_handle(a, shared).unwrap();
+        tree.set_node_style_handle(b, shared).unwrap();
+        let fixed = tree.new_leaf(Style { size: Size::from_lengths(30.0, 10.0), ..Default::default() }).unwrap();
+        let inner = tree
+            .new_with_children(Style { flex_direction: FlexDirection::Column, ..Default::default() }, &[fixed])
+            .unwrap();
+        let root = tree
+            .new_with_children(Style { size: Size::from_lengths(200.0, 100.0), ..Default::default() }, &[a, inner, b])
+            .unwrap();
+        let bytes = tree.to_bytes(&[root]).unwrap();
+
+        let (mut loaded, roots): (TaffyTree, _) = TaffyTree::from_bytes(&bytes).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(loaded.total_node_count(), 5);
+        assert!(!loaded.layout_config().use_rounding);
+        let loaded_children = loaded.children(roots[0]).unwrap();
+        assert_eq!(loaded.node_style_handle(loaded_children[0]), loaded.node_style_handle(loaded_children[2]));
+        assert!(loaded.node_style_handle(loaded_children[0]).unwrap().is_some());
+
+        tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        loaded.compute_layout(roots[0], Size::MAX_CONTENT).unwrap();
+        for (node, loaded_node) in [root, a, inner, b].into_iter().zip([roots[0]].into_iter().chain(loaded_children)) {
+            assert_eq!(tree.layout(node).unwrap(), loaded.layout(loaded_node).unwrap());
+        }
+    }
+
+    #[test]
+    fn unknown_fields_are_skipped() {
+        let mut tree: TaffyTree = TaffyTree::new();
+        let root = tree.new_leaf(Style::default()).unwrap();
+        let mut bytes = tree.to_bytes(&[root]).unwrap();
+
+        // Append a field with an unknown tag (99) and a two byte value to the end of the top level field list
+        bytes[9] += 4;
+        bytes.extend_from_slice(&[99, 2, 0xAB, 0xCD]);
+        let (loaded, roots): (TaffyTree, _) = TaffyTree::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.total_node_count(), 1);
+        assert_eq!(loaded.style(roots[0]).unwrap(), &Style::default());
+    }
+
+    #[test]
+    fn invalid_data_is_rejected() {
+        assert_eq!(TaffyTree::<()>::from_bytes(b"TAFFYLOG").unwrap_err(), BinaryError::InvalidHeader);
+        assert_eq!(TaffyTree::<()>::from_bytes(b"TAFFYBIN\x02").unwrap_err(), BinaryError::UnsupportedVersion(2));
+        assert_eq!(TaffyTree::<()>::from_bytes(b"TAFFYBIN\x01").unwrap_err(), BinaryError::UnexpectedEnd);
+    }
+}
@@ -5,6 +5,7 @@ use crate::style_helpers::TaffyMaxContent;
 use crate::tree::{CollapsibleMarginSet, Layout, LayoutInput, LayoutOutput, RunMode, SizingMode};
 use crate::tree::{LayoutPartialTree, LayoutPartialTreeExt, NodeId};
 use crate::util::debug::debug_log;
+use crate::util::precision::Offset;
 use crate::util::sys::f32_max;
 use crate::util::sys::Vec;
 use crate::util::MaybeMath;
@@ -12,7 +13,7 @@ use crate::util::{MaybeResolve, ResolveOrZero};
 use crate::{BlockContainerStyle, BlockItemStyle, BoxGenerationMode, BoxSizing, LayoutBlockContainer, TextAlign};
 
 #[cfg(feature = "content_size")]
-use super::common::content_size::compute_content_size_contribution;
+use super::common::content_size::{clip_content_size, compute_content_size_contribution};
 
 /// Per-child data that is accumulated and modified over the course of the layout algorithm
 struct BlockItem {
@@ -66,12 +67,12 @@ pub fn compute_block_layout(
     node_id: NodeId,
     inputs: LayoutInput,
 ) -> LayoutOutput {
-    let LayoutInput { known_dimensions, parent_size, run_mode, .. } = inputs;
+    let LayoutInput { known_dimensions, parent_size, run_mode, config, .. } = inputs;
     let style = tree.get_block_container_style(node_id);
 
     // Pull these out earlier to avoid borrowing issues
     let aspect_ratio = style.aspect_ratio();
-    let padding = style.padding().resolve_or_zero(parent_size.width);
+    let padding = style.padding().resolve_or_zero(config.percent_resolution_mode.basis(parent_size));
     let border = style.border().resolve_or_zero(parent_size.width);
     let padding_border_size = (padding + border).sum_axes();
     let box_sizing_adjustment =
@@ -124,7 +125,13 @@ pub fn compute_block_layout(
 /// Computes the layout of [`LayoutBlockContainer`] according to the block layout algorithm
 fn compute_inner(tree: &mut impl LayoutBlockContainer, node_id: NodeId, inputs: LayoutInput) -> LayoutOutput {
     let LayoutInput {
-        known_dimensions, parent_size, available_space, run_mode, vertical_margins_are_collapsible, ..
+        known_dimensions,
+        parent_size,
+        available_space,
+        run_mode,
+        vertical_margins_are_collapsible,
+        config,
+        ..
     } = inputs;
 
     let style = tree.get_block_container_style(node_id);
@@ -132,20 +139,12 @@ fn compute_inner(tree: &mut impl LayoutBlockContainer, node_id: NodeId, inputs:
     let raw_border = style.border();
     let raw_margin = style.margin();
     let aspect_ratio = style.aspect_ratio();
-    let padding = raw_padding.resolve_or_zero(parent_size.width);
+    let padding = raw_padding.resolve_or_zero(config.percent_resolution_mode.basis(parent_size));
     let border = raw_border.resolve_or_zero(parent_size.width);
 
-    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`.
-    // However, the axis are switched (transposed) because a node that scrolls vertically needs
-    // *horizontal* space to be reserved for a scrollbar
-    let scrollbar_gutter = {
-        let offsets = style.overflow().transpose().map(|overflow| match overflow {
-            Overflow::Scroll => style.scrollbar_width(),
-            _ => 0.0,
-        });
-        // TODO: make side configurable based on the `direction` property
-        Rect { top: 0.0, left: 0.0, right: offsets.x, bottom: offsets.y }
-    };
+    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`, or when the node
+    // is a scroll container with a stable `scrollbar_gutter`
+    let scrollbar_gutter = style.scrollbar_gutter().resolve(style.overflow(), style.scrollbar_width());
     let padding_border = padding + border;
     let padding_border_size = padding_border.sum_axes();
     let content_box_inset = padding_border + scrollbar_gutter;
@@ -214,14 +213,20 @@ fn compute_inner(tree: &mut impl LayoutBlockContainer, node_id: NodeId, inputs:
     }
 
     // 3. Perform final item layout and return content height
-    let resolved_padding = raw_padding.resolve_or_zero(Some(container_outer_width));
+    let resolved_padding = raw_padding.resolve_or_zero(
+        config.percent_resolution_mode.basis(Size { width: Some(container_outer_width), height: parent_size.height }),
+    );
     let resolved_border = raw_border.resolve_or_zero(Some(container_outer_width));
     let resolved_content_box_inset = resolved_padding + resolved_border + scrollbar_gutter;
+    let item_margin_percent_basis = config
+        .percent_resolution_mode
+        .basis(Size { width: Some(container_outer_width), height: container_content_box_size.height });
     let (inflow_content_size, intrinsic_outer_height, first_child_top_margin_set, last_child_bottom_margin_set) =
         perform_final_layout_on_in_flow_children(
             tree,
             &mut items,
             container_outer_width,
+            item_margin_percent_basis,
             content_box_inset,
             resolved_content_box_inset,
             text_align,
@@ -270,25 +275,32 @@ fn compute_inner(tree: &mut impl LayoutBlockContainer, node_id: NodeId, inputs:
 
     #[cfg_attr(not(feature = "content_size"), allow(unused_variables))]
     let content_size = inflow_content_size.f32_max(absolute_content_size);
+    #[cfg(feature = "content_size")]
+    let content_size =
+        clip_content_size(final_outer_size, content_size, tree.get_block_container_style(node_id).overflow());
 
     LayoutOutput {
         size: final_outer_size,
         #[cfg(feature = "content_size")]
         content_size,
         first_baselines: Point::NONE,
+        last_baselines: Point::NONE,
         top_margin: if own_margins_collapse_with_children.start {
             first_child_top_margin_set
         } else {
-            let margin_top = raw_margin.top.resolve_or_zero(parent_size.width);
+            let margin_top = raw_margin.top.resolve_or_zero(config.percent_resolution_mode.basis(parent_size).height);
             CollapsibleMarginSet::from_margin(margin_top)
         },
         bottom_margin: if own_margins_collapse_with_children.end {
             last_child_bottom_margin_set
         } else {
-            let margin_bottom = raw_margin.bottom.resolve_or_zero(parent_size.width);
+            let margin_bottom =
+                raw_margin.bottom.resolve_or_zero(config.percent_resolution_mode.basis(parent_size).height);
             CollapsibleMarginSet::from_margin(margin_bottom)
         },
         margins_can_collapse_through: can_be_collapsed_through,
+        scroll_origin_shift: Point::ZERO,
+        depends_on_available_width: true,
     }
 }
 
@@ -299,13 +311,14 @@ fn generate_item_list(
     node: NodeId,
     node_inner_size: Size<Option<f32>>,
 ) -> Vec<BlockItem> {
+    let percent_basis = tree.get_layout_config().percent_resolution_mode.basis(node_inner_size);
     tree.child_ids(node)
         .map(|child_node_id| (child_node_id, tree.get_block_child_style(child_node_id)))
         .filter(|(_, style)| style.box_generation_mode() != BoxGenerationMode::None)
         .enumerate()
         .map(|(order, (child_node_id, child_style))| {
             let aspect_ratio = child_style.aspect_ratio();
-            let padding = child_style.padding().resolve_or_zero(node_inner_size);
+            let padding = child_style.padding().resolve_or_zero(percent_basis);
             let border = child_style.border().resolve_or_zero(node_inner_size);
             let pb_sum = (padding + border).sum_axes();
             let box_sizing_adjustment =
@@ -384,10 +397,12 @@ fn determine_content_based_container_width(
 
 /// Compute each child's final size and position
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn perform_final_layout_on_in_flow_children(
     tree: &mut impl LayoutPartialTree,
     items: &mut [BlockItem],
     container_outer_width: f32,
+    item_margin_percent_basis: Size<Option<f32>>,
     content_box_inset: Rect<f32>,
     resolved_content_box_inset: Rect<f32>,
     text_align: TextAlign,
@@ -401,15 +416,16 @@ fn perform_final_layout_on_in_flow_children(
 
     #[cfg_attr(not(feature = "content_size"), allow(unused_mut))]
     let mut inflow_content_size = Size::ZERO;
-    let mut committed_y_offset = resolved_content_box_inset.top;
+    let mut committed_y_offset = Offset::new(resolved_content_box_inset.top);
     let mut first_child_top_margin_set = CollapsibleMarginSet::ZERO;
     let mut active_collapsible_margin_set = CollapsibleMarginSet::ZERO;
     let mut is_collapsing_with_first_margin_set = true;
     for item in items.iter_mut() {
         if item.position == Position::Absolute {
-            item.static_position = Point { x: resolved_content_box_inset.left, y: committed_y_offset }
+            item.static_position = Point { x: resolved_content_box_inset.left, y: committed_y_offset.get() }
         } else {
-            let item_margin = item.margin.map(|margin| margin.resolve_to_option(container_outer_width));
+            let item_margin =
+                item.margin.zip_size(item_margin_percent_basis, |margin, basis| margin.maybe_resolve(basis));
             let item_non_auto_margin = item_margin.map(|m| m.unwrap_or(0.0));
             let item_non_auto_x_margin_sum = item_non_auto_margin.horizontal_axis_sum();
             let known_dimensions = if item.is_table {
@@ -478,11 +494,11 @@ fn perform_final_layout_on_in_flow_children(
             item.can_be_collapsed_through = item_layout.margins_can_collapse_through;
             item.static_position = Point {
                 x: resolved_content_box_inset.left,
-                y: committed_y_offset + active_collapsible_margin_set.resolve(),
+                y: committed_y_offset.get() + active_collapsible_margin_set.resolve(),
             };
             let mut location = Point {
                 x: resolved_content_box_inset.left + inset_offset.x + resolved_margin.left,
-                y: committed_y_offset + inset_offset.y + y_margin_offset,
+                y: committed_y_offset.get() + inset_offset.y + y_margin_offset,
             };
 
             // Apply alignment
@@ -517,6 +533,8 @@ fn perform_final_layout_on_in_flow_children(
                     padding: item.padding,
                     border: item.border,
                     margin: resolved_margin,
+                    first_baseline: item_layout.first_baselines.y,
+                    scroll_origin_shift: item_layout.scroll_origin_shift,
                 },
             );
 
@@ -548,7 +566,7 @@ fn perform_final_layout_on_in_flow_children(
                     .collapse_with_set(top_margin_set)
                     .collapse_with_set(bottom_margin_set);
             } else {
-                committed_y_offset += item_layout.size.height + y_margin_offset;
+                committed_y_offset.add(item_layout.size.height + y_margin_offset);
                 active_collapsible_margin_set = bottom_margin_set;
             }
         }
@@ -558,8 +576,8 @@ fn perform_final_layout_on_in_flow_children(
     let bottom_y_margin_offset =
         if own_margins_collapse_with_children.end { 0.0 } else { last_child_bottom_margin_set.resolve() };
 
-    committed_y_offset += resolved_content_box_inset.bottom + bottom_y_margin_offset;
-    let content_height = f32_max(0.0, committed_y_offset);
+    committed_y_offset.add(resolved_content_box_inset.bottom + bottom_y_margin_offset);
+    let content_height = f32_max(0.0, committed_y_offset.get());
     (inflow_content_size, content_height, first_child_top_margin_set, last_child_bottom_margin_set)
 }
 
@@ -573,6 +591,7 @@ fn perform_absolute_layout_on_absolute_children(
 ) -> Size<f32> {
     let area_width = area_size.width;
     let area_height = area_size.height;
+    let percent_basis = tree.get_layout_config().percent_resolution_mode.basis(area_size);
 
     #[cfg_attr(not(feature = "content_size"), allow(unused_mut))]
     let mut absolute_content_size = Size::ZERO;
@@ -587,8 +606,8 @@ fn perform_absolute_layout_on_absolute_children(
         }
 
         let aspect_ratio = child_style.aspect_ratio();
-        let margin = child_style.margin().map(|margin| margin.resolve_to_option(area_width));
-        let padding = child_style.padding().resolve_or_zero(Some(area_width));
+        let margin = child_style.margin().zip_size(percent_basis, |margin, basis| margin.resolve_to_option(basis));
+        let padding = child_style.padding().resolve_or_zero(percent_basis.map(Some));
         let border = child_style.border().resolve_or_zero(Some(area_width));
         let padding_border_sum = (padding + border).sum_axes();
         let box_sizing_adjustment =
@@ -757,6 +776,8 @@ fn perform_absolute_layout_on_absolute_children(
                 padding,
                 border,
                 margin: resolved_margin,
+                first_baseline: layout_output.first_baselines.y,
+                scroll_origin_shift: layout_output.scroll_origin_shift,
             },
         );
 
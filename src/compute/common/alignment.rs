@@ -1,5 +1,6 @@
 //! Generic CSS alignment code that is shared between both the Flexbox and CSS Grid algorithms.
 use crate::style::AlignContent;
+use crate::util::sys::f32_max;
 
 /// Implement fallback alignment.
 ///
@@ -33,10 +34,33 @@ pub(crate) fn apply_alignment_fallback(
     alignment_mode
 }
 
+/// Returns how far the first item was moved towards the end of the container by making `alignment_mode` safe. That is
+/// how far past the start edge of the container the first item would have been placed by the unsafe alignment, where
+/// it could not be scrolled to if the container is a scroll container.
+pub(crate) fn compute_safe_alignment_shift(
+    free_space: f32,
+    num_items: usize,
+    alignment_mode: AlignContent,
+    layout_is_flex_reversed: bool,
+) -> f32 {
+    let unsafe_alignment_mode = apply_alignment_fallback(free_space, num_items, alignment_mode, false);
+    let unsafe_offset =
+        compute_alignment_offset(free_space, num_items, 0.0, unsafe_alignment_mode, layout_is_flex_reversed, 0);
+    f32_max(0.0, -unsafe_offset)
+}
+
 /// Generic alignment function that is used:
 ///   - For both align-content and justify-content alignment
 ///   - For both the Flexbox and CSS Grid algorithms
 ///
+/// Returns the offset of the item at `index` (counted in layout order) from the position it would have if it were
+/// start-aligned with no gaps. That is the sum of all of the gaps and distributed free space before it.
+///
+/// The offset is computed directly from the index rather than by summing the space between each pair of items, so that
+/// floating point error does not build up along a line of items. The distributed space after the midpoint of the line
+/// is computed from the end, so the space after the last item exactly mirrors the space before the first item (and a
+/// `SpaceBetween` line ends exactly at the end of the container).
+///
 /// CSS Grid does not apply gaps as part of alignment, so the gap parameter should
 /// always be set to zero for CSS Grid.
 pub(crate) fn compute_alignment_offset(
@@ -45,56 +69,55 @@ pub(crate) fn compute_alignment_offset(
     gap: f32,
     alignment_mode: AlignContent,
     layout_is_flex_reversed: bool,
-    is_first: bool,
+    index: usize,
 ) -> f32 {
-    if is_first {
-        match alignment_mode {
-            AlignContent::Start => 0.0,
-            AlignContent::FlexStart => {
-                if layout_is_flex_reversed {
-                    free_space
-                } else {
-                    0.0
-                }
+    let gaps = gap * index as f32;
+    match alignment_mode {
+        AlignContent::Start => gaps,
+        AlignContent::FlexStart => {
+            if layout_is_flex_reversed {
+                free_space + gaps
+            } else {
+                gaps
             }
-            AlignContent::End => free_space,
-            AlignContent::FlexEnd => {
-                if layout_is_flex_reversed {
-                    0.0
-                } else {
-                    free_space
-                }
+        }
+        AlignContent::End => free_space + gaps,
+        AlignContent::FlexEnd => {
+            if layout_is_flex_reversed {
+                gaps
+            } else {
+                free_space + gaps
             }
-            AlignContent::Center => free_space / 2.0,
-            AlignContent::Stretch => 0.0,
-            AlignContent::SpaceBetween => 0.0,
-            AlignContent::SpaceAround => {
-                if free_space >= 0.0 {
-                    (free_space / num_items as f32) / 2.0
-                } else {
-                    free_space / 2.0
-                }
+        }
+        AlignContent::Center => free_space / 2.0 + gaps,
+        AlignContent::Stretch => gaps,
+        AlignContent::SpaceBetween => distribute_free_space(free_space, index, num_items.saturating_sub(1)) + gaps,
+        AlignContent::SpaceAround => {
+            if free_space >= 0.0 {
+                distribute_free_space(free_space, 2 * index + 1, 2 * num_items) + gaps
+            } else {
+                free_space / 2.0 + gaps
             }
-            AlignContent::SpaceEvenly => {
-                if free_space >= 0.0 {
-                    free_space / (num_items + 1) as f32
-                } else {
-                    free_space / 2.0
-                }
+        }
+        AlignContent::SpaceEvenly => {
+            if free_space >= 0.0 {
+                distribute_free_space(free_space, index + 1, num_items + 1) + gaps
+            } else {
+                free_space / 2.0 + gaps
             }
         }
+    }
+}
+
+/// Returns `parts` of `free_space` divided into `total_parts` equal parts. Parts after the midpoint are computed by
+/// subtracting the remaining parts from the end, so that `total_parts` parts are exactly `free_space`.
+fn distribute_free_space(free_space: f32, parts: usize, total_parts: usize) -> f32 {
+    let free_space = free_space.max(0.0);
+    if total_parts == 0 {
+        0.0
+    } else if 2 * parts <= total_parts {
+        free_space * parts as f32 / total_parts as f32
     } else {
-        let free_space = free_space.max(0.0);
-        gap + match alignment_mode {
-            AlignContent::Start => 0.0,
-            AlignContent::FlexStart => 0.0,
-            AlignContent::End => 0.0,
-            AlignContent::FlexEnd => 0.0,
-            AlignContent::Center => 0.0,
-            AlignContent::Stretch => 0.0,
-            AlignContent::SpaceBetween => free_space / (num_items - 1) as f32,
-            AlignContent::SpaceAround => free_space / num_items as f32,
-            AlignContent::SpaceEvenly => free_space / (num_items + 1) as f32,
-        }
+        free_space - free_space * (total_parts - parts) as f32 / total_parts as f32
     }
 }
@@ -1,16 +1,32 @@
 //! Generic CSS content size code that is shared between all CSS algorithms.
 use crate::geometry::{Point, Size};
 use crate::style::Overflow;
-use crate::util::sys::f32_max;
+use crate::util::sys::{f32_max, f32_min};
+
+#[inline(always)]
+/// Resolves the overflow a node's content is actually subject to. A node which is a scroll container in one axis is a
+/// scroll container in both, so if either axis is `Hidden` or `Scroll` then `Visible` and `Clip` in the other axis behave
+/// like `Hidden` (see <https://www.w3.org/TR/css-overflow-3/#overflow-control>).
+fn used_overflow(overflow: Point<Overflow>) -> Point<Overflow> {
+    if overflow.x.is_scroll_container() || overflow.y.is_scroll_container() {
+        overflow.map(|overflow| if overflow.is_scroll_container() { overflow } else { Overflow::Hidden })
+    } else {
+        overflow
+    }
+}
 
 #[inline(always)]
 /// Determine how much width/height a given node contributes to it's parent's content size
+///
+/// Content which overflows the node only contributes in axes in which it is visible: overflow which is scrolled by the
+/// node itself (or clipped) is accounted for by the node's own content size rather than that of its ancestors.
 pub(crate) fn compute_content_size_contribution(
     location: Point<f32>,
     size: Size<f32>,
     content_size: Size<f32>,
     overflow: Point<Overflow>,
 ) -> Size<f32> {
+    let overflow = used_overflow(overflow);
     let size_content_size_contribution = Size {
         width: match overflow.x {
             Overflow::Visible => f32_max(size.width, content_size.width),
@@ -30,3 +46,141 @@ pub(crate) fn compute_content_size_contribution(
         Size::ZERO
     }
 }
+
+#[inline(always)]
+/// Excludes content which is clipped by `Overflow::Clip` from a node's own content size. Clipped content can't be
+/// scrolled to, so in each axis in which overflow is clipped the content size is limited to the size of the node.
+pub(crate) fn clip_content_size(size: Size<f32>, content_size: Size<f32>, overflow: Point<Overflow>) -> Size<f32> {
+    let overflow = used_overflow(overflow);
+    Size {
+        width: match overflow.x {
+            Overflow::Clip => f32_min(content_size.width, size.width),
+            _ => content_size.width,
+        },
+        height: match overflow.y {
+            Overflow::Clip => f32_min(content_size.height, size.height),
+            _ => content_size.height,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::Point;
+
+    /// Lays out a 100x100 container with the given display and overflow, containing a 300x200 child, and returns the
+    /// content size of the container
+    fn content_size_of_container(display: Display, overflow: Point<Overflow>) -> Size<f32> {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy
+            .new_leaf(Style { size: Size::from_lengths(300.0, 200.0), flex_shrink: 0.0, ..Default::default() })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style { display, overflow, size: Size::from_lengths(100.0, 100.0), ..Default::default() },
+                &[child],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        taffy.layout(container).unwrap().content_size
+    }
+
+    #[test]
+    fn clipped_overflow_is_excluded_from_content_size() {
+        for display in [Display::Flex, Display::Grid, Display::Block] {
+            let visible = Point { x: Overflow::Visible, y: Overflow::Visible };
+            assert_eq!(content_size_of_container(display, visible), Size { width: 300.0, height: 200.0 });
+
+            let clip = Point { x: Overflow::Clip, y: Overflow::Clip };
+            assert_eq!(content_size_of_container(display, clip), Size { width: 100.0, height: 100.0 });
+
+            let clip_x = Point { x: Overflow::Clip, y: Overflow::Visible };
+            assert_eq!(content_size_of_container(display, clip_x), Size { width: 100.0, height: 200.0 });
+
+            let hidden = Point { x: Overflow::Hidden, y: Overflow::Hidden };
+            assert_eq!(content_size_of_container(display, hidden), Size { width: 300.0, height: 200.0 });
+
+            // Clip behaves like hidden when the other axis is scrollable
+            let clip_x_hidden_y = Point { x: Overflow::Clip, y: Overflow::Hidden };
+            assert_eq!(content_size_of_container(display, clip_x_hidden_y), Size { width: 300.0, height: 200.0 });
+        }
+    }
+
+    /// Lays out a 100x100 scroll container containing a 50x50 child with the given display and overflow, which in turn
+    /// contains a 300x200 grandchild, and returns the content size of the scroll container
+    fn content_size_of_outer_scroll_container(display: Display, inner_overflow: Point<Overflow>) -> Size<f32> {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let grandchild = taffy
+            .new_leaf(Style { size: Size::from_lengths(300.0, 200.0), flex_shrink: 0.0, ..Default::default() })
+            .unwrap();
+        let child = taffy
+            .new_with_children(
+                Style {
+                    display,
+                    overflow: inner_overflow,
+                    scrollbar_width: 0.0,
+                    size: Size::from_lengths(50.0, 50.0),
+                    flex_shrink: 0.0,
+                    ..Default::default()
+                },
+                &[grandchild],
+            )
+            .unwrap();
+        let absolute_grandchild =
+            taffy.new_leaf(Style { size: Size::from_lengths(300.0, 200.0), ..Default::default() }).unwrap();
+        let absolute_child = taffy
+            .new_with_children(
+                Style {
+                    display,
+                    position: Position::Absolute,
+                    overflow: inner_overflow,
+                    scrollbar_width: 0.0,
+                    size: Size::from_lengths(50.0, 50.0),
+                    ..Default::default()
+                },
+                &[absolute_grandchild],
+            )
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    overflow: Point { x: Overflow::Scroll, y: Overflow::Scroll },
+                    scrollbar_width: 0.0,
+                    size: Size::from_lengths(100.0, 100.0),
+                    ..Default::default()
+                },
+                &[child, absolute_child],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+        taffy.layout(container).unwrap().content_size
+    }
+
+    #[test]
+    fn overflow_scrolled_by_a_descendant_is_excluded_from_ancestor_content_size() {
+        for display in [Display::Flex, Display::Grid, Display::Block] {
+            let visible = Point { x: Overflow::Visible, y: Overflow::Visible };
+            assert_eq!(content_size_of_outer_scroll_container(display, visible), Size { width: 300.0, height: 200.0 });
+
+            for inner_overflow in [
+                Point { x: Overflow::Hidden, y: Overflow::Hidden },
+                Point { x: Overflow::Scroll, y: Overflow::Scroll },
+                Point { x: Overflow::Clip, y: Overflow::Clip },
+                // A node which scrolls in one axis scrolls in both, so neither axis overflows into the ancestor
+                Point { x: Overflow::Visible, y: Overflow::Hidden },
+                Point { x: Overflow::Scroll, y: Overflow::Visible },
+            ] {
+                assert_eq!(
+                    content_size_of_outer_scroll_container(display, inner_overflow),
+                    Size { width: 50.0, height: 50.0 },
+                    "{display:?} {inner_overflow:?}"
+                );
+            }
+
+            // Content which is only clipped in one axis still overflows in the other
+            let clip_x = Point { x: Overflow::Clip, y: Overflow::Visible };
+            assert_eq!(content_size_of_outer_scroll_container(display, clip_x), Size { width: 50.0, height: 200.0 });
+        }
+    }
+}
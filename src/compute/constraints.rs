@@ -0,0 +1,194 @@
+//! Adapts constraints-based layout protocols (such as Flutter's `BoxConstraints`) to Taffy's layout inputs
+use crate::geometry::Size;
+use crate::style::AvailableSpace;
+use crate::tree::{LayoutPartialTree, NodeId};
+
+use super::compute_root_layout_with_known_dimensions;
+
+/// A minimum and maximum size in each axis. Implement this for the constraints type of another layout protocol to lay
+/// out Taffy nodes under it with [`compute_constrained_layout`].
+///
+/// In a constraints-based protocol a parent passes each child a minimum and maximum size, and the child picks a size
+/// within that range. Taffy instead passes known dimensions and available space. The two are mapped per axis:
+///
+/// | Constraint                                 | Known dimension | Available space      |
+/// | ---                                        | ---             | ---                  |
+/// | tight (`min == max`)                       | `Some(max)`     | `Definite(max)`      |
+/// | loose or bounded (`min < max`, finite max) | `None`          | `Definite(max)`      |
+/// | unbounded (infinite max)                   | `None`          | `MaxContent`         |
+///
+/// So a node under loose constraints shrink-wraps its content (up to the maximum size), and the size it picks is then
+/// clamped to the constraints (in which case the node is laid out again at the clamped size).
+///
+/// In the other direction (for example, when a leaf node's measure function lays out a widget from the other protocol),
+/// a known dimension becomes a tight constraint, definite available space becomes a loose constraint, a max-content
+/// constraint becomes an unbounded constraint and a min-content constraint becomes a loose constraint with a maximum of
+/// zero (which makes content shrink to its minimum size).
+pub trait LayoutConstraints: Sized {
+    /// Creates constraints from a minimum and maximum size. Maximums may be `f32::INFINITY`.
+    fn from_min_max(min: Size<f32>, max: Size<f32>) -> Self;
+
+    /// The minimum size
+    fn min_size(&self) -> Size<f32>;
+
+    /// The maximum size. An infinite maximum means that the axis is unbounded.
+    fn max_size(&self) -> Size<f32>;
+
+    /// Converts the constraints into the known dimensions and available space of a root node
+    fn to_layout_inputs(&self) -> (Size<Option<f32>>, Size<AvailableSpace>) {
+        let (min, max) = (self.min_size(), self.max_size());
+        let known_dimensions = min.zip_map(max, |min, max| (min == max && max.is_finite()).then_some(max));
+        let available_space = max.map(|max| match max.is_finite() {
+            true => AvailableSpace::Definite(max),
+            false => AvailableSpace::MaxContent,
+        });
+        (known_dimensions, available_space)
+    }
+
+    /// Converts the known dimensions and available space passed to a node into constraints
+    fn from_layout_inputs(known_dimensions: Size<Option<f32>>, available_space: Size<AvailableSpace>) -> Self {
+        let min = known_dimensions.map(|known| known.unwrap_or(0.0));
+        let max = known_dimensions.zip_map(available_space, |known, available| match (known, available) {
+            (Some(known), _) => known,
+            (None, AvailableSpace::Definite(available)) => available.max(0.0),
+            (None, AvailableSpace::MinContent) => 0.0,
+            (None, AvailableSpace::MaxContent) => f32::INFINITY,
+        });
+        Self::from_min_max(min, max)
+    }
+
+    /// Returns the size within the constraints which is closest to `size`
+    fn constrain(&self, size: Size<f32>) -> Size<f32> {
+        let (min, max) = (self.min_size(), self.max_size());
+        Size { width: size.width.min(max.width).max(min.width), height: size.height.min(max.height).max(min.height) }
+    }
+}
+
+/// Constraints on the size of a node, in the style of Flutter's `BoxConstraints`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxConstraints {
+    /// The minimum size
+    pub min: Size<f32>,
+    /// The maximum size. An infinite maximum means that the axis is unbounded.
+    pub max: Size<f32>,
+}
+
+impl BoxConstraints {
+    /// Constraints which allow any size
+    pub const UNBOUNDED: Self = Self { min: Size::ZERO, max: Size { width: f32::INFINITY, height: f32::INFINITY } };
+
+    /// Constraints which only allow exactly `size`
+    pub const fn tight(size: Size<f32>) -> Self {
+        Self { min: size, max: size }
+    }
+
+    /// Constraints which allow any size up to `size`
+    pub const fn loose(size: Size<f32>) -> Self {
+        Self { min: Size::ZERO, max: size }
+    }
+
+    /// Returns whether only a single size satisfies the constraints
+    pub fn is_tight(&self) -> bool {
+        self.min == self.max
+    }
+}
+
+impl LayoutConstraints for BoxConstraints {
+    fn from_min_max(min: Size<f32>, max: Size<f32>) -> Self {
+        Self { min, max }
+    }
+
+    fn min_size(&self) -> Size<f32> {
+        self.min
+    }
+
+    fn max_size(&self) -> Size<f32> {
+        self.max
+    }
+}
+
+/// Lays out the root node of a tree under size constraints, and returns the size that it picks
+///
+/// The node is first laid out with the known dimensions and available space given by
+/// [`LayoutConstraints::to_layout_inputs`]. If the resulting size does not satisfy the constraints then it is clamped,
+/// and the node is laid out again at the clamped size.
+pub fn compute_constrained_layout(
+    tree: &mut impl LayoutPartialTree,
+    root: NodeId,
+    constraints: &impl LayoutConstraints,
+) -> Size<f32> {
+    let (known_dimensions, available_space) = constraints.to_layout_inputs();
+    let size = compute_root_layout_with_known_dimensions(tree, root, known_dimensions, available_space);
+    let constrained_size = constraints.constrain(size);
+    if constrained_size != size {
+        compute_root_layout_with_known_dimensions(tree, root, constrained_size.map(Some), available_space);
+    }
+    constrained_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoxConstraints, LayoutConstraints};
+    use crate::prelude::*;
+
+    #[test]
+    fn tight_constraints_become_known_dimensions() {
+        let (known_dimensions, available_space) =
+            BoxConstraints::tight(Size { width: 100.0, height: 50.0 }).to_layout_inputs();
+        assert_eq!(known_dimensions, Size { width: Some(100.0), height: Some(50.0) });
+        assert_eq!(
+            available_space,
+            Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::Definite(50.0) }
+        );
+
+        let constraints = BoxConstraints::from_layout_inputs(known_dimensions, available_space);
+        assert!(constraints.is_tight());
+        assert_eq!(constraints.max, Size { width: 100.0, height: 50.0 });
+    }
+
+    #[test]
+    fn loose_constraints_become_available_space() {
+        let constraints = BoxConstraints {
+            min: Size { width: 10.0, height: 0.0 },
+            max: Size { width: 100.0, height: f32::INFINITY },
+        };
+        let (known_dimensions, available_space) = constraints.to_layout_inputs();
+        assert_eq!(known_dimensions, Size::NONE);
+        assert_eq!(
+            available_space,
+            Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::MaxContent }
+        );
+
+        let constraints = BoxConstraints::from_layout_inputs(known_dimensions, available_space);
+        assert_eq!(constraints.min, Size::ZERO);
+        assert_eq!(constraints.max, Size { width: 100.0, height: f32::INFINITY });
+
+        let constraints = BoxConstraints::from_layout_inputs(Size::NONE, Size::MIN_CONTENT);
+        assert_eq!(constraints, BoxConstraints::loose(Size::ZERO));
+    }
+
+    #[test]
+    fn constrained_layout_picks_size_within_constraints() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 20.0), ..Default::default() }).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[child]).unwrap();
+
+        // Loose constraints shrink-wrap the content
+        let size = taffy.compute_constrained_layout(root, &BoxConstraints::loose(Size { width: 200.0, height: 200.0 }));
+        assert_eq!(size.unwrap(), Size { width: 50.0, height: 20.0 });
+
+        // Tight constraints force the size
+        let size = taffy.compute_constrained_layout(root, &BoxConstraints::tight(Size { width: 80.0, height: 30.0 }));
+        assert_eq!(size.unwrap(), Size { width: 80.0, height: 30.0 });
+
+        // A minimum size which is larger than the content grows the node
+        let constraints =
+            BoxConstraints { min: Size { width: 60.0, height: 40.0 }, max: BoxConstraints::UNBOUNDED.max };
+        assert_eq!(taffy.compute_constrained_layout(root, &constraints).unwrap(), Size { width: 60.0, height: 40.0 });
+        assert_eq!(taffy.layout(root).unwrap().size, Size { width: 60.0, height: 40.0 });
+
+        // A maximum size which is smaller than the content shrinks the node
+        let size = taffy.compute_constrained_layout(root, &BoxConstraints::loose(Size { width: 30.0, height: 10.0 }));
+        assert_eq!(size.unwrap(), Size { width: 30.0, height: 10.0 });
+    }
+}
@@ -7,17 +7,21 @@ use crate::style::{
 };
 use crate::style::{CoreStyle, FlexDirection, FlexboxContainerStyle, FlexboxItemStyle};
 use crate::style_helpers::{TaffyMaxContent, TaffyMinContent};
-use crate::tree::{Layout, LayoutInput, LayoutOutput, RunMode, SizingMode};
+use crate::tree::{Layout, LayoutConfig, LayoutInput, LayoutOutput, PercentResolutionMode, RunMode, SizingMode};
 use crate::tree::{LayoutFlexboxContainer, LayoutPartialTreeExt, NodeId};
 use crate::util::debug::debug_log;
-use crate::util::sys::{f32_max, new_vec_with_capacity, Vec};
+use crate::util::precision::{Offset, PreciseSum};
+use crate::util::sys::{f32_max, ChildrenVec, Vec};
 use crate::util::MaybeMath;
-use crate::util::{MaybeResolve, ResolveOrZero};
+use crate::util::{MaybeResolve, RectLanes, ResolveOrZero};
 use crate::{BoxGenerationMode, BoxSizing};
 
-use super::common::alignment::apply_alignment_fallback;
+use super::common::alignment::{apply_alignment_fallback, compute_safe_alignment_shift};
 #[cfg(feature = "content_size")]
-use super::common::content_size::compute_content_size_contribution;
+use super::common::content_size::{clip_content_size, compute_content_size_contribution};
+
+/// The known dimensions, parent size and available space with which to measure the min-content main size of a flex item
+type MinContentInputs = (Size<Option<f32>>, Size<Option<f32>>, Size<AvailableSpace>);
 
 /// The intermediate results of a flexbox calculation for a single item
 struct FlexItem {
@@ -48,6 +52,9 @@ struct FlexItem {
     /// The minimum size of the item. This differs from min_size above because it also
     /// takes into account content based automatic minimum sizes
     resolved_minimum_main_size: f32,
+    /// The inputs with which to measure the min-content main size of the item if computing its content based
+    /// automatic minimum size has been deferred (in which case `resolved_minimum_main_size` is an upper bound)
+    deferred_min_content_inputs: Option<MinContentInputs>,
 
     /// The final offset of this item
     inset: Rect<Option<f32>>,
@@ -82,13 +89,16 @@ struct FlexItem {
     /// The size that this item wants to be, plus any padding and border
     outer_target_size: Size<f32>,
 
-    /// The position of the bottom edge of this item
+    /// The position of the first baseline of this item
     baseline: f32,
+    /// The position of the last baseline of this item
+    last_baseline: f32,
 
     /// A temporary value for the main offset
     ///
     /// Offset is the relative position from the item's natural flow position based on
     /// relative position values, alignment, and justification. Does not include margin/padding/border.
+    /// Includes the gaps and distributed free space before the item in its line.
     offset_main: f32,
     /// A temporary value for the cross offset
     ///
@@ -103,7 +113,7 @@ struct FlexLine<'a> {
     items: &'a mut [FlexItem],
     /// The dimensions of the cross-axis
     cross_size: f32,
-    /// The relative offset of the cross-axis
+    /// The relative offset of the cross-axis, including the gaps and distributed free space before the line
     offset_cross: f32,
 }
 
@@ -131,8 +141,8 @@ struct AlgoConstants {
     /// The space between the content box and the border box.
     /// This consists of padding + border + scrollbar_gutter.
     content_box_inset: Rect<f32>,
-    /// The size reserved for scrollbar gutters in each axis
-    scrollbar_gutter: Point<f32>,
+    /// The space reserved for scrollbar gutters on each edge
+    scrollbar_gutter: Rect<f32>,
     /// The gap of this section
     gap: Size<f32>,
     /// The align_items property of this node
@@ -141,6 +151,10 @@ struct AlgoConstants {
     align_content: AlignContent,
     /// The justify_content property of this node
     justify_content: Option<JustifyContent>,
+    /// Whether the node is a scroll container in each axis, in which case content alignment is safe
+    is_scroll_container: Size<bool>,
+    /// The configuration of behaviours where layout may deviate from the web specifications
+    config: LayoutConfig,
 
     /// The border-box size of the node being laid out (if known)
     node_outer_size: Size<Option<f32>>,
@@ -159,12 +173,12 @@ pub fn compute_flexbox_layout(
     node: NodeId,
     inputs: LayoutInput,
 ) -> LayoutOutput {
-    let LayoutInput { known_dimensions, parent_size, run_mode, .. } = inputs;
+    let LayoutInput { known_dimensions, parent_size, run_mode, config, .. } = inputs;
     let style = tree.get_flexbox_container_style(node);
 
     // Pull these out earlier to avoid borrowing issues
     let aspect_ratio = style.aspect_ratio();
-    let padding = style.padding().resolve_or_zero(parent_size.width);
+    let padding = style.padding().resolve_or_zero(config.percent_resolution_mode.basis(parent_size));
     let border = style.border().resolve_or_zero(parent_size.width);
     let padding_border_sum = padding.sum_axes() + border.sum_axes();
     let box_sizing_adjustment =
@@ -217,10 +231,11 @@ pub fn compute_flexbox_layout(
 
 /// Compute a preliminary size for an item
 fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
-    let LayoutInput { known_dimensions, parent_size, available_space, run_mode, .. } = inputs;
+    let LayoutInput { known_dimensions, parent_size, available_space, run_mode, config, .. } = inputs;
 
     // Define some general constants we will need for the remainder of the algorithm.
-    let mut constants = compute_constants(tree.get_flexbox_container_style(node), known_dimensions, parent_size);
+    let mut constants =
+        compute_constants(tree.get_flexbox_container_style(node), known_dimensions, parent_size, config);
 
     // 9. Flex Layout Algorithm
 
@@ -236,9 +251,16 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
     debug_log!("determine_available_space");
     let available_space = determine_available_space(known_dimensions, available_space, &constants);
 
+    // If the container's size is known and its items' sizes are fully determined by their styles, then the flex base
+    // and hypothetical sizes of the items are determined directly from their styles, skipping steps 3 and 7 below.
+    let has_fixed_size_items =
+        run_mode == RunMode::PerformLayout && size_fixed_size_items(tree, &mut flex_items, &constants, available_space);
+
     // 3. Determine the flex base size and hypothetical main size of each item.
-    debug_log!("determine_flex_base_size");
-    determine_flex_base_size(tree, &constants, available_space, &mut flex_items);
+    if !has_fixed_size_items {
+        debug_log!("determine_flex_base_size");
+        determine_flex_base_size(tree, &constants, available_space, &mut flex_items);
+    }
 
     #[cfg(feature = "debug")]
     for item in flex_items.iter() {
@@ -274,31 +296,44 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
         debug_log!("constants.node_outer_size", dbg:constants.node_outer_size);
         debug_log!("constants.node_inner_size", dbg:constants.node_inner_size);
 
-        // Re-resolve percentage gaps
-        let style = tree.get_flexbox_container_style(node);
-        let inner_container_size = constants.inner_container_size.main(constants.dir);
-        let new_gap = style.gap().main(constants.dir).maybe_resolve(inner_container_size).unwrap_or(0.0);
-        constants.gap.set_main(constants.dir, new_gap);
+        // Re-resolve percentage gaps. Only horizontal gaps are re-resolved: a height determined by the container's
+        // content is not definite, so vertical percentage gaps continue to resolve to zero.
+        if constants.is_row {
+            let style = tree.get_flexbox_container_style(node);
+            let inner_container_size = constants.inner_container_size.main(constants.dir);
+            let new_gap = style.gap().main(constants.dir).maybe_resolve(inner_container_size).unwrap_or(0.0);
+            constants.gap.set_main(constants.dir, new_gap);
+        }
     }
 
     // 6. Resolve the flexible lengths of all the flex items to find their used main size.
-    debug_log!("resolve_flexible_lengths");
-    for line in &mut flex_lines {
+    for line in flex_lines.iter_mut() {
+        debug_log!("resolve_flexible_lengths");
+        resolve_deferred_minimum_main_sizes(tree, line, &constants);
         resolve_flexible_lengths(line, &constants);
+
+        // Snap the flexed sizes to the fixed point grid so that the positions of the items are exact sums
+        #[cfg(feature = "fixed_point")]
+        for item in line.items.iter_mut() {
+            item.target_size = item.target_size.map(crate::tree::quantize);
+            item.outer_target_size = item.outer_target_size.map(crate::tree::quantize);
+        }
     }
 
     // 9.4. Cross Size Determination
 
-    // 7. Determine the hypothetical cross size of each item.
-    debug_log!("determine_hypothetical_cross_size");
-    for line in &mut flex_lines {
-        determine_hypothetical_cross_size(tree, line, &constants, available_space);
-    }
+    if !has_fixed_size_items {
+        // 7. Determine the hypothetical cross size of each item.
+        debug_log!("determine_hypothetical_cross_size");
+        for line in &mut flex_lines {
+            determine_hypothetical_cross_size(tree, line, &constants, available_space);
+        }
 
-    // Calculate child baselines. This function is internally smart and only computes child baselines
-    // if they are necessary.
-    debug_log!("calculate_children_base_lines");
-    calculate_children_base_lines(tree, known_dimensions, available_space, &mut flex_lines, &constants);
+        // Calculate child baselines. This function is internally smart and only computes child baselines
+        // if they are necessary.
+        debug_log!("calculate_children_base_lines");
+        calculate_children_base_lines(tree, known_dimensions, available_space, &mut flex_lines, &constants);
+    }
 
     // 8. Calculate the cross size of each flex line.
     debug_log!("calculate_cross_size");
@@ -331,7 +366,8 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
 
     // 12. Distribute any remaining free space.
     debug_log!("distribute_remaining_free_space");
-    distribute_remaining_free_space(&mut flex_lines, &constants);
+    let mut scroll_origin_shift = Size::ZERO;
+    scroll_origin_shift.set_main(constants.dir, distribute_remaining_free_space(&mut flex_lines, &constants));
 
     // 9.6. Cross-Axis Alignment
 
@@ -349,9 +385,21 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
         return LayoutOutput::from_outer_size(constants.container_size);
     }
 
+    // If the container's width was indefinite then percentage column gaps resolved to zero when sizing it. Re-resolve
+    // them against the now determined width, as is done for the main axis of row containers above.
+    if constants.is_column && constants.node_inner_size.cross(constants.dir).is_none() {
+        let style = tree.get_flexbox_container_style(node);
+        let inner_container_size = constants.inner_container_size.cross(constants.dir);
+        let new_gap = style.gap().cross(constants.dir).maybe_resolve(inner_container_size).unwrap_or(0.0);
+        constants.gap.set_cross(constants.dir, new_gap);
+    }
+
     // 16. Align all flex lines per align-content.
     debug_log!("align_flex_lines_per_align_content");
-    align_flex_lines_per_align_content(&mut flex_lines, &constants, total_line_cross_size);
+    scroll_origin_shift.set_cross(
+        constants.dir,
+        align_flex_lines_per_align_content(&mut flex_lines, &constants, total_line_cross_size),
+    );
 
     // Do a final layout pass and gather the resulting layouts
     debug_log!("final_layout_pass");
@@ -378,27 +426,36 @@ fn compute_preliminary(tree: &mut impl LayoutFlexboxContainer, node: NodeId, inp
         }
     }
 
-    // 8.5. Flex Container Baselines: calculate the flex container's first baseline
+    // 8.5. Flex Container Baselines: calculate the flex container's first and last baselines
     // See https://www.w3.org/TR/css-flexbox-1/#flex-baselines
-    let first_vertical_baseline = if flex_lines.is_empty() {
-        None
-    } else {
-        flex_lines[0]
-            .items
+    let first_vertical_baseline = flex_lines.first().and_then(|line| {
+        line.items
             .iter()
             .find(|item| constants.is_column || item.align_self == AlignSelf::Baseline)
-            .or_else(|| flex_lines[0].items.iter().next())
-            .map(|child| {
-                let offset_vertical = if constants.is_row { child.offset_cross } else { child.offset_main };
-                offset_vertical + child.baseline
-            })
-    };
+            .or_else(|| line.items.first())
+            .map(|child| child.baseline)
+    });
+    let last_vertical_baseline = flex_lines.last().and_then(|line| {
+        line.items
+            .iter()
+            .rev()
+            .find(|item| constants.is_column || item.align_self == AlignSelf::LastBaseline)
+            .or_else(|| line.items.last())
+            .map(|child| child.last_baseline)
+    });
+
+    let content_size = inflow_content_size.f32_max(absolute_content_size);
+    #[cfg(feature = "content_size")]
+    let content_size =
+        clip_content_size(constants.container_size, content_size, tree.get_flexbox_container_style(node).overflow());
 
     LayoutOutput::from_sizes_and_baselines(
         constants.container_size,
-        inflow_content_size.f32_max(absolute_content_size),
+        content_size,
         Point { x: None, y: first_vertical_baseline },
     )
+    .with_last_baselines(Point { x: None, y: last_vertical_baseline })
+    .with_scroll_origin_shift(Point { x: scroll_origin_shift.width, y: scroll_origin_shift.height })
 }
 
 /// Compute constants that can be reused during the flexbox algorithm.
@@ -407,6 +464,7 @@ fn compute_constants(
     style: impl FlexboxContainerStyle,
     known_dimensions: Size<Option<f32>>,
     parent_size: Size<Option<f32>>,
+    config: LayoutConfig,
 ) -> AlgoConstants {
     let dir = style.flex_direction();
     let is_row = dir.is_row();
@@ -415,8 +473,9 @@ fn compute_constants(
     let is_wrap_reverse = style.flex_wrap() == FlexWrap::WrapReverse;
 
     let aspect_ratio = style.aspect_ratio();
-    let margin = style.margin().resolve_or_zero(parent_size.width);
-    let padding = style.padding().resolve_or_zero(parent_size.width);
+    let percent_basis = config.percent_resolution_mode.basis(parent_size);
+    let margin = style.margin().resolve_or_zero(percent_basis);
+    let padding = style.padding().resolve_or_zero(percent_basis);
     let border = style.border().resolve_or_zero(parent_size.width);
     let padding_border_sum = padding.sum_axes() + border.sum_axes();
     let box_sizing_adjustment =
@@ -426,20 +485,17 @@ fn compute_constants(
     let align_content = style.align_content().unwrap_or(AlignContent::Stretch);
     let justify_content = style.justify_content();
 
-    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`.
-    // However, the axis are switched (transposed) because a node that scrolls vertically needs
-    // *horizontal* space to be reserved for a scrollbar
-    let scrollbar_gutter = style.overflow().transpose().map(|overflow| match overflow {
-        Overflow::Scroll => style.scrollbar_width(),
-        _ => 0.0,
-    });
-    // TODO: make side configurable based on the `direction` property
-    let mut content_box_inset = padding + border;
-    content_box_inset.right += scrollbar_gutter.x;
-    content_box_inset.bottom += scrollbar_gutter.y;
+    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`, or when the node
+    // is a scroll container with a stable `scrollbar_gutter`
+    let scrollbar_gutter = style.scrollbar_gutter().resolve(style.overflow(), style.scrollbar_width());
+    let content_box_inset = padding + border + scrollbar_gutter;
 
     let node_outer_size = known_dimensions;
     let node_inner_size = node_outer_size.maybe_sub(content_box_inset.sum_axes());
+
+    // Percentage gaps resolve against the known inner size, and to zero in an axis whose size is not yet known. The
+    // size of a content-sized container is only known once its parent lays it out, so gaps resolve to zero while it
+    // is being measured and against its final size when its children are laid out.
     let gap = style.gap().resolve_or_zero(node_inner_size.or(Size::zero()));
 
     let container_size = Size::zero();
@@ -469,6 +525,8 @@ fn compute_constants(
         align_items,
         align_content,
         justify_content,
+        is_scroll_container: Size::from(style.overflow().map(Overflow::is_scroll_container)),
+        config,
         node_outer_size,
         node_inner_size,
         container_size,
@@ -486,16 +544,33 @@ fn generate_anonymous_flex_items(
     tree: &impl LayoutFlexboxContainer,
     node: NodeId,
     constants: &AlgoConstants,
-) -> Vec<FlexItem> {
-    tree.child_ids(node)
+) -> ChildrenVec<FlexItem> {
+    let children: ChildrenVec<(usize, NodeId)> = tree
+        .child_ids(node)
         .enumerate()
-        .map(|(index, child)| (index, child, tree.get_flexbox_child_style(child)))
-        .filter(|(_, _, style)| style.position() != Position::Absolute)
-        .filter(|(_, _, style)| style.box_generation_mode() != BoxGenerationMode::None)
-        .map(|(index, child, child_style)| {
+        .filter(|&(_, child)| {
+            let style = tree.get_flexbox_child_style(child);
+            style.position() != Position::Absolute && style.box_generation_mode() != BoxGenerationMode::None
+        })
+        .collect();
+
+    let percent_basis = constants.config.percent_resolution_mode.basis(constants.node_inner_size);
+    // Borders resolve percentages against the container's inner width in both axes
+    let border_basis = Size { width: constants.node_inner_size.width, height: constants.node_inner_size.width };
+
+    // The margins, padding and borders of four children at a time are transposed into lanes (one per child) so that
+    // the same side of all four can be resolved at once
+    let mut items = ChildrenVec::with_capacity(children.len());
+    for chunk in children.chunks(4) {
+        let styles = || chunk.iter().map(|&(_, child)| tree.get_flexbox_child_style(child));
+        let margins = RectLanes::new(styles().map(|style| style.margin())).resolve_or_zero(percent_basis);
+        let paddings = RectLanes::new(styles().map(|style| style.padding())).resolve_or_zero(percent_basis);
+        let borders = RectLanes::new(styles().map(|style| style.border())).resolve_or_zero(border_basis);
+
+        items.extend(chunk.iter().zip(styles()).enumerate().map(|(lane, (&(index, child), child_style))| {
             let aspect_ratio = child_style.aspect_ratio();
-            let padding = child_style.padding().resolve_or_zero(constants.node_inner_size.width);
-            let border = child_style.border().resolve_or_zero(constants.node_inner_size.width);
+            let padding = paddings[lane];
+            let border = borders[lane];
             let pb_sum = (padding + border).sum_axes();
             let box_sizing_adjustment =
                 if child_style.box_sizing() == BoxSizing::ContentBox { pb_sum } else { Size::ZERO };
@@ -519,10 +594,10 @@ fn generate_anonymous_flex_items(
                     .maybe_add(box_sizing_adjustment),
 
                 inset: child_style.inset().zip_size(constants.node_inner_size, |p, s| p.maybe_resolve(s)),
-                margin: child_style.margin().resolve_or_zero(constants.node_inner_size.width),
+                margin: margins[lane],
                 margin_is_auto: child_style.margin().map(|m| m == LengthPercentageAuto::Auto),
-                padding: child_style.padding().resolve_or_zero(constants.node_inner_size.width),
-                border: child_style.border().resolve_or_zero(constants.node_inner_size.width),
+                padding,
+                border,
                 align_self: child_style.align_self().unwrap_or(constants.align_items),
                 overflow: child_style.overflow(),
                 scrollbar_width: child_style.scrollbar_width(),
@@ -534,6 +609,7 @@ fn generate_anonymous_flex_items(
                 frozen: false,
 
                 resolved_minimum_main_size: 0.0,
+                deferred_min_content_inputs: None,
                 hypothetical_inner_size: Size::zero(),
                 hypothetical_outer_size: Size::zero(),
                 target_size: Size::zero(),
@@ -541,12 +617,14 @@ fn generate_anonymous_flex_items(
                 content_flex_fraction: 0.0,
 
                 baseline: 0.0,
+                last_baseline: 0.0,
 
                 offset_main: 0.0,
                 offset_cross: 0.0,
             }
-        })
-        .collect()
+        }));
+    }
+    items
 }
 
 /// Determine the available main and cross space for the flex items.
@@ -630,13 +708,7 @@ fn determine_flex_base_size(
         let child_parent_size = Size::from_cross(dir, cross_axis_parent_size);
 
         // Available space for child sizing
-        let cross_axis_margin_sum = constants.margin.cross_axis_sum(dir);
-        let child_min_cross = child.min_size.cross(dir).maybe_add(cross_axis_margin_sum);
-        let child_max_cross = child.max_size.cross(dir).maybe_add(cross_axis_margin_sum);
-        let cross_axis_available_space: AvailableSpace = available_space
-            .cross(dir)
-            .map_definite_value(|val| cross_axis_parent_size.unwrap_or(val))
-            .maybe_clamp(child_min_cross, child_max_cross);
+        let cross_axis_available_space = child_cross_axis_available_space(child, constants, available_space);
 
         // Known dimensions for child sizing
         let child_known_dimensions = {
@@ -652,14 +724,19 @@ fn determine_flex_base_size(
 
         let container_width = constants.node_inner_size.main(dir);
         let box_sizing_adjustment = if child_style.box_sizing() == BoxSizing::ContentBox {
-            let padding = child_style.padding().resolve_or_zero(container_width);
+            let padding = match constants.config.percent_resolution_mode {
+                PercentResolutionMode::Width => child_style.padding().resolve_or_zero(container_width),
+                PercentResolutionMode::PerAxis => child_style.padding().resolve_or_zero(constants.node_inner_size),
+            };
             let border = child_style.border().resolve_or_zero(container_width);
             (padding + border).sum_axes()
         } else {
             Size::ZERO
         }
         .main(dir);
-        let flex_basis = child_style.flex_basis().maybe_resolve(container_width).maybe_add(box_sizing_adjustment);
+        let flex_basis_style = child_style.flex_basis();
+        let flex_basis = flex_basis_style.maybe_resolve(container_width).maybe_add(box_sizing_adjustment);
+        let aspect_ratio = child_style.aspect_ratio();
 
         drop(child_style);
 
@@ -673,7 +750,14 @@ fn determine_flex_base_size(
 
             // Note: `child.size` has already been resolved against aspect_ratio in generate_anonymous_flex_items
             // So B will just work here by using main_size without special handling for aspect_ratio
-            let main_size = child.size.main(dir);
+            //
+            // A flex basis of `content` does not fall back to the main size, so in that case only a main size
+            // derived from the cross size and aspect ratio is used.
+            let main_size = if flex_basis_style == Dimension::Content {
+                child.size.with_main(dir, None).maybe_apply_aspect_ratio(aspect_ratio).main(dir)
+            } else {
+                child.size.main(dir)
+            };
             if let Some(flex_basis) = flex_basis.or(main_size) {
                 break 'flex_basis flex_basis;
             };
@@ -736,54 +820,206 @@ fn determine_flex_base_size(
         let padding_border_sum = child.padding.main_axis_sum(constants.dir) + child.border.main_axis_sum(constants.dir);
         child.flex_basis = child.flex_basis.max(padding_border_sum);
 
-        // The hypothetical main size is the item’s flex base size clamped according to its
-        // used min and max main sizes (and flooring the content box size at zero).
-
         child.inner_flex_basis =
             child.flex_basis - child.padding.main_axis_sum(constants.dir) - child.border.main_axis_sum(constants.dir);
 
-        let padding_border_axes_sums = (child.padding + child.border).sum_axes().map(Some);
-
         // Note that it is important that the `parent_size` parameter in the main axis is not set for this
         // function call as it used for resolving percentages, and percentage size in an axis should not contribute
         // to a min-content contribution in that same axis. However the `parent_size` and `available_space` *should*
         // be set to their usual values in the cross axis so that wrapping content can wrap correctly.
         //
         // See https://drafts.csswg.org/css-sizing-3/#min-percentage-contribution
-        let style_min_main_size =
-            child.min_size.or(child.overflow.map(Overflow::maybe_into_automatic_min_size).into()).main(dir);
-
-        child.resolved_minimum_main_size = style_min_main_size.unwrap_or({
-            let min_content_main_size = {
-                let child_available_space = Size::MIN_CONTENT.with_cross(dir, cross_axis_available_space);
-
-                debug_log!("COMPUTE CHILD MIN SIZE:");
-                tree.measure_child_size(
-                    child.node,
-                    child_known_dimensions,
-                    child_parent_size,
-                    child_available_space,
-                    SizingMode::ContentSize,
-                    dir.main_axis(),
-                    Line::FALSE,
-                )
-            };
+        let child_min_content_inputs =
+            (child_known_dimensions, child_parent_size, Size::MIN_CONTENT.with_cross(dir, cross_axis_available_space));
+        determine_hypothetical_main_size(tree, child, constants, child_min_content_inputs);
+    }
+}
 
-            // 4.5. Automatic Minimum Size of Flex Items
-            // https://www.w3.org/TR/css-flexbox-1/#min-size-auto
-            let clamped_min_content_size =
-                min_content_main_size.maybe_min(child.size.main(dir)).maybe_min(child.max_size.main(dir));
-            clamped_min_content_size.maybe_max(padding_border_axes_sums.main(dir))
-        });
+/// Resolves the minimum main size of an item whose flex base size has been determined, and from it the item's
+/// hypothetical main size: its flex base size clamped according to its used min and max main sizes (and flooring the
+/// content box size at zero). If the item's automatic minimum size is needed then its min-content main size is measured
+/// with `min_content_inputs`, or deferred (see below).
+#[inline]
+fn determine_hypothetical_main_size(
+    tree: &mut impl LayoutFlexboxContainer,
+    child: &mut FlexItem,
+    constants: &AlgoConstants,
+    min_content_inputs: MinContentInputs,
+) {
+    let dir = constants.dir;
+    let padding_border_axes_sums = (child.padding + child.border).sum_axes().map(Some);
+
+    let style_min_main_size =
+        child.min_size.or(child.overflow.map(Overflow::maybe_into_automatic_min_size).into()).main(dir);
+
+    // If automatic minimum sizes are disabled then an `auto` min size simply resolves to zero
+    let style_min_main_size = if constants.config.use_automatic_minimum_size {
+        style_min_main_size
+    } else {
+        style_min_main_size.or(Some(0.0))
+    };
+
+    // The content-based minimum size of an item is never larger than its specified size suggestion (its main size
+    // clamped by its max size). If the container's main size is definite (so that no intrinsic sizing of the
+    // container takes place) and the item's flex base size is not smaller than this bound, then the minimum size
+    // can only affect the item's size if its line shrinks. So we use the bound for now and defer measuring the
+    // min-content size of the item until it is known that the line shrinks. This avoids a content-sizing pass
+    // over every subtree of fixed-size nodes.
+    //
+    // Spec: https://www.w3.org/TR/css-flexbox-1/#specified-size-suggestion
+    let specified_size_suggestion = child
+        .size
+        .main(dir)
+        .maybe_min(child.max_size.main(dir))
+        .maybe_max(padding_border_axes_sums.main(dir))
+        .filter(|&suggestion| constants.node_inner_size.main(dir).is_some() && suggestion <= child.flex_basis);
+
+    child.deferred_min_content_inputs = None;
+    child.resolved_minimum_main_size = match (style_min_main_size, specified_size_suggestion) {
+        (Some(min_main_size), _) => min_main_size,
+        (None, Some(suggestion)) => {
+            child.deferred_min_content_inputs = Some(min_content_inputs);
+            suggestion
+        }
+        (None, None) => compute_automatic_minimum_main_size(tree, child, dir, min_content_inputs),
+    };
+
+    let hypothetical_inner_min_main = child.resolved_minimum_main_size.maybe_max(padding_border_axes_sums.main(dir));
+    let hypothetical_inner_size =
+        child.flex_basis.maybe_clamp(Some(hypothetical_inner_min_main), child.max_size.main(dir));
+    let hypothetical_outer_size = hypothetical_inner_size + child.margin.main_axis_sum(dir);
+
+    child.hypothetical_inner_size.set_main(dir, hypothetical_inner_size);
+    child.hypothetical_outer_size.set_main(dir, hypothetical_outer_size);
+}
+
+/// The space available to an item in the cross axis when determining its flex base size: the container's inner cross
+/// size if it is definite (or else the available cross space), clamped by the item's min and max cross sizes
+#[inline]
+fn child_cross_axis_available_space(
+    child: &FlexItem,
+    constants: &AlgoConstants,
+    available_space: Size<AvailableSpace>,
+) -> AvailableSpace {
+    let dir = constants.dir;
+    let cross_axis_margin_sum = constants.margin.cross_axis_sum(dir);
+    let child_min_cross = child.min_size.cross(dir).maybe_add(cross_axis_margin_sum);
+    let child_max_cross = child.max_size.cross(dir).maybe_add(cross_axis_margin_sum);
+    available_space
+        .cross(dir)
+        .map_definite_value(|val| constants.node_inner_size.cross(dir).unwrap_or(val))
+        .maybe_clamp(child_min_cross, child_max_cross)
+}
+
+/// Determines the flex base size and hypothetical main and cross sizes of the items of a single-line container whose
+/// inner size is known directly from their styles, if each item has a size fully determined by its style. Returns
+/// whether the items were sized, in which case steps 3 and 7 of the algorithm are skipped.
+///
+/// The results are those of [`determine_flex_base_size`] and [`determine_hypothetical_cross_size`] (neither of which
+/// measures these items, as their specified size suggestion never exceeds their flex base size), which are found
+/// without resolving the flex basis and box sizing of the items or whether they are stretched.
+#[inline]
+fn size_fixed_size_items(
+    tree: &mut impl LayoutFlexboxContainer,
+    flex_items: &mut [FlexItem],
+    constants: &AlgoConstants,
+    available_space: Size<AvailableSpace>,
+) -> bool {
+    let dir = constants.dir;
+    let is_inner_size_definite =
+        constants.node_inner_size.width.is_some() && constants.node_inner_size.height.is_some();
+    if constants.is_wrap || !is_inner_size_definite {
+        return false;
+    }
+
+    // An item whose main size isn't its flex basis, whose size is transferred through an aspect ratio (so that an auto
+    // cross size may be stretched), or which is aligned by baseline or by auto margins must be sized by the algorithm
+    let is_fixed_size = |item: &FlexItem| {
+        let style = tree.get_flexbox_child_style(item.node);
+        let margin_is_auto = item.margin_is_auto;
+        item.size.width.is_some()
+            && item.size.height.is_some()
+            && style.flex_basis() == Dimension::Auto
+            && style.aspect_ratio().is_none()
+            && !matches!(item.align_self, AlignSelf::Baseline | AlignSelf::LastBaseline)
+            && !(margin_is_auto.left || margin_is_auto.right || margin_is_auto.top || margin_is_auto.bottom)
+    };
+    if !flex_items.iter().all(is_fixed_size) {
+        return false;
+    }
+
+    for child in flex_items.iter_mut() {
+        // A. The item's main size is its flex base size (floored by its padding and border)
+        let padding_border_sum = child.padding.main_axis_sum(dir) + child.border.main_axis_sum(dir);
+        child.flex_basis = child.size.main(dir).unwrap_or(0.0).max(padding_border_sum);
+        child.inner_flex_basis = child.flex_basis - padding_border_sum;
+
+        let min_content_inputs = (
+            child.size.with_main(dir, None),
+            Size::from_cross(dir, constants.node_inner_size.cross(dir)),
+            Size::MIN_CONTENT.with_cross(dir, child_cross_axis_available_space(child, constants, available_space)),
+        );
+        determine_hypothetical_main_size(tree, child, constants, min_content_inputs);
+
+        let hypothetical_inner_cross = definite_cross_size(child, dir).unwrap_or(0.0);
+        child.hypothetical_inner_size.set_cross(dir, hypothetical_inner_cross);
+        child.hypothetical_outer_size.set_cross(dir, hypothetical_inner_cross + child.margin.cross_axis_sum(dir));
+    }
+
+    true
+}
+
+/// Computes the content-based automatic minimum main size of a flex item by measuring its min-content main size
+///
+/// # [4.5. Automatic Minimum Size of Flex Items](https://www.w3.org/TR/css-flexbox-1/#min-size-auto)
+#[inline]
+fn compute_automatic_minimum_main_size(
+    tree: &mut impl LayoutFlexboxContainer,
+    child: &FlexItem,
+    dir: FlexDirection,
+    min_content_inputs: MinContentInputs,
+) -> f32 {
+    let (known_dimensions, parent_size, available_space) = min_content_inputs;
+
+    debug_log!("COMPUTE CHILD MIN SIZE:");
+    let min_content_main_size = tree.measure_child_size(
+        child.node,
+        known_dimensions,
+        parent_size,
+        available_space,
+        SizingMode::ContentSize,
+        dir.main_axis(),
+        Line::FALSE,
+    );
 
-        let hypothetical_inner_min_main =
-            child.resolved_minimum_main_size.maybe_max(padding_border_axes_sums.main(constants.dir));
-        let hypothetical_inner_size =
-            child.flex_basis.maybe_clamp(Some(hypothetical_inner_min_main), child.max_size.main(constants.dir));
-        let hypothetical_outer_size = hypothetical_inner_size + child.margin.main_axis_sum(constants.dir);
+    let padding_border_sum = child.padding.main_axis_sum(dir) + child.border.main_axis_sum(dir);
+    let clamped_min_content_size =
+        min_content_main_size.maybe_min(child.size.main(dir)).maybe_min(child.max_size.main(dir));
+    clamped_min_content_size.max(padding_border_sum)
+}
 
-        child.hypothetical_inner_size.set_main(constants.dir, hypothetical_inner_size);
-        child.hypothetical_outer_size.set_main(constants.dir, hypothetical_outer_size);
+/// Computes the automatic minimum main size of items whose minimum size was deferred by [`determine_flex_base_size`]
+/// for lines in which the items will be shrunk (and thus in which the minimum size may affect their used main size)
+#[inline]
+fn resolve_deferred_minimum_main_sizes(
+    tree: &mut impl LayoutFlexboxContainer,
+    line: &mut FlexLine,
+    constants: &AlgoConstants,
+) {
+    let total_main_axis_gap = sum_axis_gaps(constants.gap.main(constants.dir), line.items.len());
+    let total_hypothetical_outer_main_size =
+        line.items.iter().map(|child| child.hypothetical_outer_size.main(constants.dir)).precise_sum();
+    let shrinking = total_main_axis_gap + total_hypothetical_outer_main_size
+        > constants.node_inner_size.main(constants.dir).unwrap_or(0.0);
+    if !shrinking {
+        return;
+    }
+
+    for child in line.items.iter_mut() {
+        if let Some(min_content_inputs) = child.deferred_min_content_inputs.take() {
+            child.resolved_minimum_main_size =
+                compute_automatic_minimum_main_size(tree, child, constants.dir, min_content_inputs);
+        }
     }
 }
 
@@ -808,10 +1044,10 @@ fn determine_flex_base_size(
 fn collect_flex_lines<'a>(
     constants: &AlgoConstants,
     available_space: Size<AvailableSpace>,
-    flex_items: &'a mut Vec<FlexItem>,
-) -> Vec<FlexLine<'a>> {
+    flex_items: &'a mut ChildrenVec<FlexItem>,
+) -> ChildrenVec<FlexLine<'a>> {
     if !constants.is_wrap {
-        let mut lines = new_vec_with_capacity(1);
+        let mut lines = ChildrenVec::new();
         lines.push(FlexLine { items: flex_items.as_mut_slice(), cross_size: 0.0, offset_cross: 0.0 });
         lines
     } else {
@@ -830,24 +1066,17 @@ fn collect_flex_lines<'a>(
             // If we're sizing under a max-content constraint then the flex items will never wrap
             // (at least for now - future extensions to the CSS spec may add provisions for forced wrap points)
             AvailableSpace::MaxContent => {
-                let mut lines = new_vec_with_capacity(1);
+                let mut lines = ChildrenVec::new();
                 lines.push(FlexLine { items: flex_items.as_mut_slice(), cross_size: 0.0, offset_cross: 0.0 });
                 lines
             }
             // If flex-wrap is Wrap and we're sizing under a min-content constraint, then we take every possible wrapping opportunity
             // and place each item in it's own line
             AvailableSpace::MinContent => {
-                let mut lines = new_vec_with_capacity(flex_items.len());
-                let mut items = &mut flex_items[..];
-                while !items.is_empty() {
-                    let (line_items, rest) = items.split_at_mut(1);
-                    lines.push(FlexLine { items: line_items, cross_size: 0.0, offset_cross: 0.0 });
-                    items = rest;
-                }
-                lines
+                flex_items.chunks_mut(1).map(|items| FlexLine { items, cross_size: 0.0, offset_cross: 0.0 }).collect()
             }
             AvailableSpace::Definite(main_axis_available_space) => {
-                let mut lines = new_vec_with_capacity(1);
+                let mut lines = ChildrenVec::new();
                 let mut flex_items = &mut flex_items[..];
                 let main_axis_gap = constants.gap.main(constants.dir);
 
@@ -902,14 +1131,23 @@ fn determine_container_main_size(
                                 let padding_border_sum = (child.padding + child.border).main_axis_sum(constants.dir);
                                 (child.flex_basis + child.margin.main_axis_sum(constants.dir)).max(padding_border_sum)
                             })
-                            .sum::<f32>();
+                            .precise_sum();
                         total_target_size + line_main_axis_gap
                     })
                     .max_by(|a, b| a.total_cmp(b))
                     .unwrap_or(0.0);
                 let size = longest_line_length + main_content_box_inset;
-                if lines.len() > 1 {
-                    f32_max(size, main_axis_available_space)
+                let size = if lines.len() > 1 { f32_max(size, main_axis_available_space) } else { size };
+
+                // An auto-sized row container is sized to fit-content: min(max-content, max(min-content, available)).
+                // So if the lines overflow the available space, the container shrinks down to the available space
+                // (but no smaller than its min-content size) and lets its items shrink to fit, rather than reserving
+                // the full max-content width. This does not apply to column containers, as the automatic block size
+                // of a flex container is its max-content size.
+                if constants.is_row && size > main_axis_available_space {
+                    let min_content_size = determine_min_content_main_size(tree, available_space, lines, constants)
+                        + main_content_box_inset;
+                    f32_max(main_axis_available_space, min_content_size).min(size)
                 } else {
                     size
                 }
@@ -926,7 +1164,7 @@ fn determine_container_main_size(
                                 let padding_border_sum = (child.padding + child.border).main_axis_sum(constants.dir);
                                 (child.flex_basis + child.margin.main_axis_sum(constants.dir)).max(padding_border_sum)
                             })
-                            .sum::<f32>();
+                            .precise_sum();
                         total_target_size + line_main_axis_gap
                     })
                     .max_by(|a, b| a.total_cmp(b))
@@ -1088,7 +1326,7 @@ fn determine_container_main_size(
                             item.target_size.set_main(constants.dir, size);
                             size
                         })
-                        .sum::<f32>();
+                        .precise_sum();
 
                     let gap_sum = sum_axis_gaps(constants.gap.main(constants.dir), line.items.len());
                     main_size = f32_max(main_size, item_main_size_sum + gap_sum)
@@ -1101,7 +1339,7 @@ fn determine_container_main_size(
 
     let outer_main_size = outer_main_size
         .maybe_clamp(constants.min_size.main(constants.dir), constants.max_size.main(constants.dir))
-        .max(main_content_box_inset - constants.scrollbar_gutter.main(constants.dir));
+        .max(main_content_box_inset - constants.scrollbar_gutter.main_axis_sum(constants.dir));
 
     // let outer_main_size = inner_main_size + constants.padding_border.main_axis_sum(constants.dir);
     let inner_main_size = f32_max(outer_main_size - main_content_box_inset, 0.0);
@@ -1110,6 +1348,96 @@ fn determine_container_main_size(
     constants.node_inner_size.set_main(constants.dir, Some(inner_main_size));
 }
 
+/// Compute the min-content main size of the flex container's content box from the items' min-content contributions.
+/// This is the largest contribution for a multi-line container, and the largest sum of the contributions within a line
+/// (plus gaps) for a single-line container.
+///
+/// # [9.9.1. Flex Container Intrinsic Main Sizes](https://www.w3.org/TR/css-flexbox-1/#intrinsic-main-sizes)
+#[inline]
+fn determine_min_content_main_size(
+    tree: &mut impl LayoutFlexboxContainer,
+    available_space: Size<AvailableSpace>,
+    lines: &[FlexLine<'_>],
+    constants: &AlgoConstants,
+) -> f32 {
+    let dir = constants.dir;
+    let mut min_content_size: f32 = 0.0;
+
+    for line in lines.iter() {
+        let mut line_sum = sum_axis_gaps(constants.gap.main(dir), line.items.len());
+        for item in line.items.iter() {
+            let style_min = item.min_size.main(dir);
+            let style_preferred = item.size.main(dir);
+            let style_max = item.max_size.main(dir);
+
+            // Items which cannot shrink cannot contribute less than their flex basis
+            let flex_basis_min = Some(item.flex_basis).filter(|_| item.flex_shrink == 0.0);
+            let min_main_size = style_min
+                .maybe_max(flex_basis_min)
+                .or(flex_basis_min)
+                .unwrap_or(item.resolved_minimum_main_size)
+                .max(item.resolved_minimum_main_size);
+
+            let content_size = match style_preferred {
+                Some(pref) => pref,
+                None => {
+                    // Parent size for child sizing
+                    let cross_axis_parent_size = constants.node_inner_size.cross(dir);
+
+                    // Available space for child sizing
+                    let cross_axis_margin_sum = constants.margin.cross_axis_sum(dir);
+                    let child_min_cross = item.min_size.cross(dir).maybe_add(cross_axis_margin_sum);
+                    let child_max_cross = item.max_size.cross(dir).maybe_add(cross_axis_margin_sum);
+                    let cross_axis_available_space: AvailableSpace = available_space
+                        .cross(dir)
+                        .map_definite_value(|val| cross_axis_parent_size.unwrap_or(val))
+                        .maybe_clamp(child_min_cross, child_max_cross);
+
+                    let child_available_space = Size::MIN_CONTENT.with_cross(dir, cross_axis_available_space);
+
+                    // Known dimensions for child sizing
+                    let child_known_dimensions = {
+                        let mut ckd = item.size.with_main(dir, None);
+                        if item.align_self == AlignSelf::Stretch && ckd.cross(dir).is_none() {
+                            ckd.set_cross(
+                                dir,
+                                cross_axis_available_space.into_option().maybe_sub(item.margin.cross_axis_sum(dir)),
+                            );
+                        }
+                        ckd
+                    };
+
+                    debug_log!("COMPUTE CHILD MIN-CONTENT SIZE (for fit-content main size):");
+                    tree.measure_child_size(
+                        item.node,
+                        child_known_dimensions,
+                        constants.node_inner_size,
+                        child_available_space,
+                        SizingMode::InherentSize,
+                        dir.main_axis(),
+                        Line::FALSE,
+                    )
+                }
+            };
+
+            let padding_border_sum = (item.padding + item.border).main_axis_sum(dir);
+            let contribution = content_size.maybe_min(style_max).max(min_main_size).max(padding_border_sum)
+                + item.margin.main_axis_sum(dir);
+
+            if constants.is_wrap {
+                min_content_size = f32_max(min_content_size, contribution);
+            } else {
+                line_sum += contribution;
+            }
+        }
+        if !constants.is_wrap {
+            min_content_size = f32_max(min_content_size, line_sum);
+        }
+    }
+
+    min_content_size
+}
+
 /// Resolve the flexible lengths of the items within a flex line.
 /// Sets the `main` component of each item's `target_size` and `outer_target_size`
 ///
@@ -1124,7 +1452,7 @@ fn resolve_flexible_lengths(line: &mut FlexLine, constants: &AlgoConstants) {
     //    flex shrink factor.
 
     let total_hypothetical_outer_main_size =
-        line.items.iter().map(|child| child.hypothetical_outer_size.main(constants.dir)).sum::<f32>();
+        line.items.iter().map(|child| child.hypothetical_outer_size.main(constants.dir)).precise_sum();
     let used_flex_factor: f32 = total_main_axis_gap + total_hypothetical_outer_main_size;
     let growing = used_flex_factor < constants.node_inner_size.main(constants.dir).unwrap_or(0.0);
     let shrinking = used_flex_factor > constants.node_inner_size.main(constants.dir).unwrap_or(0.0);
@@ -1171,7 +1499,7 @@ fn resolve_flexible_lengths(line: &mut FlexLine, constants: &AlgoConstants) {
                     child.flex_basis + child.margin.main_axis_sum(constants.dir)
                 }
             })
-            .sum::<f32>();
+            .precise_sum();
 
     let initial_free_space = constants.node_inner_size.main(constants.dir).maybe_sub(used_space).unwrap_or(0.0);
 
@@ -1202,7 +1530,7 @@ fn resolve_flexible_lengths(line: &mut FlexLine, constants: &AlgoConstants) {
                         child.flex_basis + child.margin.main_axis_sum(constants.dir)
                     }
                 })
-                .sum::<f32>();
+                .precise_sum();
 
         let mut unfrozen: Vec<&mut FlexItem> = line.items.iter_mut().filter(|child| !child.frozen).collect();
 
@@ -1250,7 +1578,7 @@ fn resolve_flexible_lengths(line: &mut FlexLine, constants: &AlgoConstants) {
                 }
             } else if shrinking && sum_flex_shrink > 0.0 {
                 let sum_scaled_shrink_factor: f32 =
-                    unfrozen.iter().map(|child| child.inner_flex_basis * child.flex_shrink).sum();
+                    unfrozen.iter().map(|child| child.inner_flex_basis * child.flex_shrink).precise_sum();
 
                 if sum_scaled_shrink_factor > 0.0 {
                     for child in &mut unfrozen {
@@ -1322,18 +1650,34 @@ fn determine_hypothetical_cross_size(
 
         let child_known_main = constants.container_size.main(constants.dir).into();
 
-        let child_cross = child
-            .size
-            .cross(constants.dir)
-            .maybe_clamp(child.min_size.cross(constants.dir), child.max_size.cross(constants.dir))
-            .maybe_max(padding_border_sum);
+        let child_cross = definite_cross_size(child, constants.dir);
 
         let child_available_cross = available_space
             .cross(constants.dir)
             .maybe_clamp(child.min_size.cross(constants.dir), child.max_size.cross(constants.dir))
             .maybe_max(padding_border_sum);
 
-        let child_inner_cross = child_cross.unwrap_or_else(|| {
+        // If the container is single-line with a definite cross size then the cross size of a stretched item is
+        // definite and known up front (it was already passed down when determining the flex base size), so there is
+        // no need to measure the item again here.
+        //
+        // Spec: https://www.w3.org/TR/css-flexbox-1/#definite-sizes
+        let stretched_cross = constants
+            .node_inner_size
+            .cross(constants.dir)
+            .filter(|_| {
+                !constants.is_wrap
+                    && child.align_self == AlignSelf::Stretch
+                    && !child.margin_is_auto.cross_start(constants.dir)
+                    && !child.margin_is_auto.cross_end(constants.dir)
+            })
+            .map(|inner_cross| {
+                (inner_cross - child.margin.cross_axis_sum(constants.dir))
+                    .maybe_clamp(child.min_size.cross(constants.dir), child.max_size.cross(constants.dir))
+                    .max(padding_border_sum)
+            });
+
+        let child_inner_cross = child_cross.or(stretched_cross).unwrap_or_else(|| {
             tree.measure_child_size(
                 child.node,
                 Size {
@@ -1359,6 +1703,18 @@ fn determine_hypothetical_cross_size(
     }
 }
 
+/// The cross size of an item given by its style (if any), clamped by its min and max cross sizes and floored by its
+/// padding and border
+#[inline]
+fn definite_cross_size(child: &FlexItem, dir: FlexDirection) -> Option<f32> {
+    let padding_border_sum = (child.padding + child.border).cross_axis_sum(dir);
+    child
+        .size
+        .cross(dir)
+        .maybe_clamp(child.min_size.cross(dir), child.max_size.cross(dir))
+        .maybe_max(padding_border_sum)
+}
+
 /// Calculate the base lines of the children.
 #[inline]
 fn calculate_children_base_lines(
@@ -1376,16 +1732,24 @@ fn calculate_children_base_lines(
     }
 
     for line in flex_lines {
-        // If a flex line has one or zero items participating in baseline alignment then baseline alignment is a no-op so we skip
+        // Items aligned to their first and last baselines form separate baseline-sharing groups. If a group has one
+        // or zero items then baseline alignment is a no-op for that group so we skip it
         let line_baseline_child_count =
             line.items.iter().filter(|child| child.align_self == AlignSelf::Baseline).count();
-        if line_baseline_child_count <= 1 {
+        let line_last_baseline_child_count =
+            line.items.iter().filter(|child| child.align_self == AlignSelf::LastBaseline).count();
+        if line_baseline_child_count <= 1 && line_last_baseline_child_count <= 1 {
             continue;
         }
 
         for child in line.items.iter_mut() {
             // Only calculate baselines for children participating in baseline alignment
-            if child.align_self != AlignSelf::Baseline {
+            let participates_in_baseline_alignment = match child.align_self {
+                AlignSelf::Baseline => line_baseline_child_count > 1,
+                AlignSelf::LastBaseline => line_last_baseline_child_count > 1,
+                _ => false,
+            };
+            if !participates_in_baseline_alignment {
                 continue;
             }
 
@@ -1420,10 +1784,14 @@ fn calculate_children_base_lines(
                 Line::FALSE,
             );
 
-            let baseline = measured_size_and_baselines.first_baselines.y;
             let height = measured_size_and_baselines.size.height;
-
-            child.baseline = baseline.unwrap_or(height) + child.margin.top;
+            if child.align_self == AlignSelf::LastBaseline {
+                let baseline = measured_size_and_baselines.last_baselines.y;
+                child.last_baseline = baseline.unwrap_or(height) + child.margin.top;
+            } else {
+                let baseline = measured_size_and_baselines.first_baselines.y;
+                child.baseline = baseline.unwrap_or(height) + child.margin.top;
+            }
         }
     }
 }
@@ -1455,6 +1823,7 @@ fn calculate_cross_size(flex_lines: &mut [FlexLine], node_size: Size<Option<f32>
         //       largest of the distances between each item’s baseline and its hypothetical outer
         //       cross-start edge, and the largest of the distances between each item’s baseline
         //       and its hypothetical outer cross-end edge, and sum these two values.
+        //       (Items aligned to their last baselines form a separate group which is measured the same way)
 
         //    2. Among all the items not collected by the previous step, find the largest
         //       outer hypothetical cross size.
@@ -1463,17 +1832,22 @@ fn calculate_cross_size(flex_lines: &mut [FlexLine], node_size: Size<Option<f32>
         //       previous two steps and zero.
         for line in flex_lines.iter_mut() {
             let max_baseline: f32 = line.items.iter().map(|child| child.baseline).fold(0.0, |acc, x| acc.max(x));
+            let max_last_baseline: f32 =
+                line.items.iter().map(|child| child.last_baseline).fold(0.0, |acc, x| acc.max(x));
             line.cross_size = line
                 .items
                 .iter()
                 .map(|child| {
-                    if child.align_self == AlignSelf::Baseline
-                        && !child.margin_is_auto.cross_start(constants.dir)
-                        && !child.margin_is_auto.cross_end(constants.dir)
-                    {
-                        max_baseline - child.baseline + child.hypothetical_outer_size.cross(constants.dir)
-                    } else {
-                        child.hypothetical_outer_size.cross(constants.dir)
+                    let has_auto_margin = child.margin_is_auto.cross_start(constants.dir)
+                        || child.margin_is_auto.cross_end(constants.dir);
+                    match child.align_self {
+                        AlignSelf::Baseline if !has_auto_margin => {
+                            max_baseline - child.baseline + child.hypothetical_outer_size.cross(constants.dir)
+                        }
+                        AlignSelf::LastBaseline if !has_auto_margin => {
+                            max_last_baseline - child.last_baseline + child.hypothetical_outer_size.cross(constants.dir)
+                        }
+                        _ => child.hypothetical_outer_size.cross(constants.dir),
                     }
                 })
                 .fold(0.0, |acc, x| acc.max(x));
@@ -1515,7 +1889,7 @@ fn handle_align_content_stretch(flex_lines: &mut [FlexLine], node_size: Size<Opt
             .unwrap_or(0.0);
 
         let total_cross_axis_gap = sum_axis_gaps(constants.gap.cross(constants.dir), flex_lines.len());
-        let lines_total_cross: f32 = flex_lines.iter().map(|line| line.cross_size).sum::<f32>() + total_cross_axis_gap;
+        let lines_total_cross: f32 = flex_lines.iter().map(|line| line.cross_size).precise_sum() + total_cross_axis_gap;
 
         if lines_total_cross < container_min_inner_cross {
             let remaining = container_min_inner_cross - lines_total_cross;
@@ -1552,7 +1926,7 @@ fn determine_used_cross_size(
                 if child.align_self == AlignSelf::Stretch
                     && !child.margin_is_auto.cross_start(constants.dir)
                     && !child.margin_is_auto.cross_end(constants.dir)
-                    && child_style.size().cross(constants.dir) == Dimension::Auto
+                    && child_style.size().cross(constants.dir).is_auto()
                 {
                     // For some reason this particular usage of max_width is an exception to the rule that max_width's transfer
                     // using the aspect_ratio (if set). Both Chrome and Firefox agree on this. And reading the spec, it seems like
@@ -1568,10 +1942,15 @@ fn determine_used_cross_size(
                         .maybe_resolve(constants.node_inner_size)
                         .maybe_add(box_sizing_adjustment);
 
-                    (line_cross_size - child.margin.cross_axis_sum(constants.dir)).maybe_clamp(
-                        child.min_size.cross(constants.dir),
-                        max_size_ignoring_aspect_ratio.cross(constants.dir),
-                    )
+                    // The `stretch` crate didn't make this exception and clamped by the aspect-ratio-transferred max size
+                    let max_cross_size = if constants.config.legacy_stretch {
+                        child.max_size.cross(constants.dir)
+                    } else {
+                        max_size_ignoring_aspect_ratio.cross(constants.dir)
+                    };
+
+                    (line_cross_size - child.margin.cross_axis_sum(constants.dir))
+                        .maybe_clamp(child.min_size.cross(constants.dir), max_cross_size)
                 } else {
                     child.hypothetical_inner_size.cross(constants.dir)
                 },
@@ -1595,12 +1974,16 @@ fn determine_used_cross_size(
 ///         Otherwise, set all `auto` margins to zero.
 ///
 ///     2. Align the items along the main-axis per `justify-content`.
+///
+/// Alignment is safe in scroll containers. Returns the furthest that the items of any line were moved towards the main
+/// end by this (see [`Layout::scroll_origin_shift`]).
 #[inline]
-fn distribute_remaining_free_space(flex_lines: &mut [FlexLine], constants: &AlgoConstants) {
+fn distribute_remaining_free_space(flex_lines: &mut [FlexLine], constants: &AlgoConstants) -> f32 {
+    let mut scroll_origin_shift = 0.0;
     for line in flex_lines {
         let total_main_axis_gap = sum_axis_gaps(constants.gap.main(constants.dir), line.items.len());
         let used_space: f32 = total_main_axis_gap
-            + line.items.iter().map(|child| child.outer_target_size.main(constants.dir)).sum::<f32>();
+            + line.items.iter().map(|child| child.outer_target_size.main(constants.dir)).precise_sum();
         let free_space = constants.inner_container_size.main(constants.dir) - used_space;
         let mut num_auto_margins = 0;
 
@@ -1636,14 +2019,19 @@ fn distribute_remaining_free_space(flex_lines: &mut [FlexLine], constants: &Algo
             let num_items = line.items.len();
             let layout_reverse = constants.dir.is_reverse();
             let gap = constants.gap.main(constants.dir);
-            let is_safe = false; // TODO: Implement safe alignment
+            let is_safe = constants.is_scroll_container.main(constants.dir);
             let raw_justify_content_mode = constants.justify_content.unwrap_or(JustifyContent::FlexStart);
             let justify_content_mode =
                 apply_alignment_fallback(free_space, num_items, raw_justify_content_mode, is_safe);
+            if is_safe {
+                let shift =
+                    compute_safe_alignment_shift(free_space, num_items, raw_justify_content_mode, layout_reverse);
+                scroll_origin_shift = f32_max(scroll_origin_shift, shift);
+            }
 
             let justify_item = |(i, child): (usize, &mut FlexItem)| {
                 child.offset_main =
-                    compute_alignment_offset(free_space, num_items, gap, justify_content_mode, layout_reverse, i == 0);
+                    compute_alignment_offset(free_space, num_items, gap, justify_content_mode, layout_reverse, i);
             };
 
             if layout_reverse {
@@ -1653,6 +2041,7 @@ fn distribute_remaining_free_space(flex_lines: &mut [FlexLine], constants: &Algo
             }
         }
     }
+    scroll_origin_shift
 }
 
 /// Resolve cross-axis `auto` margins.
@@ -1672,6 +2061,12 @@ fn resolve_cross_axis_auto_margins(flex_lines: &mut [FlexLine], constants: &Algo
     for line in flex_lines {
         let line_cross_size = line.cross_size;
         let max_baseline: f32 = line.items.iter_mut().map(|child| child.baseline).fold(0.0, |acc, x| acc.max(x));
+        let max_last_baseline_descent: f32 = line
+            .items
+            .iter()
+            .filter(|child| child.align_self == AlignSelf::LastBaseline)
+            .map(|child| child.outer_target_size.cross(constants.dir) - child.last_baseline)
+            .fold(0.0, |acc, x| acc.max(x));
 
         for child in line.items.iter_mut() {
             let free_space = line_cross_size - child.outer_target_size.cross(constants.dir);
@@ -1698,7 +2093,13 @@ fn resolve_cross_axis_auto_margins(flex_lines: &mut [FlexLine], constants: &Algo
                 }
             } else {
                 // 14. Align all flex items along the cross-axis.
-                child.offset_cross = align_flex_items_along_cross_axis(child, free_space, max_baseline, constants);
+                child.offset_cross = align_flex_items_along_cross_axis(
+                    child,
+                    free_space,
+                    max_baseline,
+                    max_last_baseline_descent,
+                    constants,
+                );
             }
         }
     }
@@ -1715,6 +2116,7 @@ fn align_flex_items_along_cross_axis(
     child: &FlexItem,
     free_space: f32,
     max_baseline: f32,
+    max_last_baseline_descent: f32,
     constants: &AlgoConstants,
 ) -> f32 {
     match child.align_self {
@@ -1748,6 +2150,21 @@ fn align_flex_items_along_cross_axis(
                 }
             }
         }
+        AlignSelf::LastBaseline => {
+            if constants.is_row {
+                // The group of items is packed toward the cross-end edge of the line, so each item is offset
+                // from the end by the difference between its distance below its last baseline and the group's
+                let descent = child.outer_target_size.cross(constants.dir) - child.last_baseline;
+                free_space - (max_last_baseline_descent - descent)
+            } else {
+                // As with first baseline alignment, we treat it as flex-end alignment in columns.
+                if constants.is_wrap_reverse {
+                    0.0
+                } else {
+                    free_space
+                }
+            }
+        }
         AlignSelf::Stretch => {
             if constants.is_wrap_reverse {
                 free_space
@@ -1775,10 +2192,10 @@ fn determine_container_cross_size(
     constants: &mut AlgoConstants,
 ) -> f32 {
     let total_cross_axis_gap = sum_axis_gaps(constants.gap.cross(constants.dir), flex_lines.len());
-    let total_line_cross_size: f32 = flex_lines.iter().map(|line| line.cross_size).sum::<f32>();
+    let total_line_cross_size: f32 = flex_lines.iter().map(|line| line.cross_size).precise_sum();
 
     let padding_border_sum = constants.content_box_inset.cross_axis_sum(constants.dir);
-    let cross_scrollbar_gutter = constants.scrollbar_gutter.cross(constants.dir);
+    let cross_scrollbar_gutter = constants.scrollbar_gutter.cross_axis_sum(constants.dir);
     let min_cross_size = constants.min_size.cross(constants.dir);
     let max_cross_size = constants.max_size.cross(constants.dir);
     let outer_container_size = node_size
@@ -1799,19 +2216,26 @@ fn determine_container_cross_size(
 /// # [9.6. Cross-Axis Alignment](https://www.w3.org/TR/css-flexbox-1/#cross-alignment)
 ///
 /// - [**Align all flex lines**](https://www.w3.org/TR/css-flexbox-1/#algo-line-align) per `align-content`.
+///
+/// Alignment is safe in scroll containers. Returns how far the lines were moved towards the cross end by this (see
+/// [`Layout::scroll_origin_shift`]).
 #[inline]
-fn align_flex_lines_per_align_content(flex_lines: &mut [FlexLine], constants: &AlgoConstants, total_cross_size: f32) {
+fn align_flex_lines_per_align_content(
+    flex_lines: &mut [FlexLine],
+    constants: &AlgoConstants,
+    total_cross_size: f32,
+) -> f32 {
     let num_lines = flex_lines.len();
     let gap = constants.gap.cross(constants.dir);
     let total_cross_axis_gap = sum_axis_gaps(gap, num_lines);
     let free_space = constants.inner_container_size.cross(constants.dir) - total_cross_size - total_cross_axis_gap;
-    let is_safe = false; // TODO: Implement safe alignment
+    let is_safe = constants.is_scroll_container.cross(constants.dir);
 
     let align_content_mode = apply_alignment_fallback(free_space, num_lines, constants.align_content, is_safe);
 
     let align_line = |(i, line): (usize, &mut FlexLine)| {
         line.offset_cross =
-            compute_alignment_offset(free_space, num_lines, gap, align_content_mode, constants.is_wrap_reverse, i == 0);
+            compute_alignment_offset(free_space, num_lines, gap, align_content_mode, constants.is_wrap_reverse, i);
     };
 
     if constants.is_wrap_reverse {
@@ -1819,6 +2243,12 @@ fn align_flex_lines_per_align_content(flex_lines: &mut [FlexLine], constants: &A
     } else {
         flex_lines.iter_mut().enumerate().for_each(align_line);
     }
+
+    if is_safe {
+        compute_safe_alignment_shift(free_space, num_lines, constants.align_content, constants.is_wrap_reverse)
+    } else {
+        0.0
+    }
 }
 
 /// Calculates the layout for a flex-item
@@ -1826,7 +2256,7 @@ fn align_flex_lines_per_align_content(flex_lines: &mut [FlexLine], constants: &A
 fn calculate_flex_item(
     tree: &mut impl LayoutFlexboxContainer,
     item: &mut FlexItem,
-    total_offset_main: &mut f32,
+    total_offset_main: &mut Offset,
     total_offset_cross: f32,
     line_offset_cross: f32,
     #[cfg(feature = "content_size")] total_content_size: &mut Size<f32>,
@@ -1849,7 +2279,7 @@ fn calculate_flex_item(
         ..
     } = layout_output;
 
-    let offset_main = *total_offset_main
+    let offset_main = total_offset_main.get()
         + item.offset_main
         + item.margin.main_start(direction)
         + (item.inset.main_start(direction).or(item.inset.main_end(direction).map(|pos| -pos)).unwrap_or(0.0));
@@ -1860,15 +2290,15 @@ fn calculate_flex_item(
         + item.margin.cross_start(direction)
         + (item.inset.cross_start(direction).or(item.inset.cross_end(direction).map(|pos| -pos)).unwrap_or(0.0));
 
-    if direction.is_row() {
-        let baseline_offset_cross = total_offset_cross + item.offset_cross + item.margin.cross_start(direction);
-        let inner_baseline = layout_output.first_baselines.y.unwrap_or(size.height);
-        item.baseline = baseline_offset_cross + inner_baseline;
+    // The baselines of the item relative to the container (ignoring relative positioning), which are used to
+    // compute the container's own baselines. Items without baselines have them synthesized from their bottom edge.
+    let baseline_offset = if direction.is_row() {
+        total_offset_cross + line_offset_cross + item.offset_cross + item.margin.cross_start(direction)
     } else {
-        let baseline_offset_main = *total_offset_main + item.offset_main + item.margin.main_start(direction);
-        let inner_baseline = layout_output.first_baselines.y.unwrap_or(size.height);
-        item.baseline = baseline_offset_main + inner_baseline;
-    }
+        total_offset_main.get() + item.offset_main + item.margin.main_start(direction)
+    };
+    item.baseline = baseline_offset + layout_output.first_baselines.y.unwrap_or(size.height);
+    item.last_baseline = baseline_offset + layout_output.last_baselines.y.unwrap_or(size.height);
 
     let location = match direction.is_row() {
         true => Point { x: offset_main, y: offset_cross },
@@ -1891,10 +2321,12 @@ fn calculate_flex_item(
             padding: item.padding,
             border: item.border,
             margin: item.margin,
+            first_baseline: layout_output.first_baselines.y,
+            scroll_origin_shift: layout_output.scroll_origin_shift,
         },
     );
 
-    *total_offset_main += item.offset_main + item.margin.main_axis_sum(direction) + size.main(direction);
+    total_offset_main.add(item.margin.main_axis_sum(direction) + size.main(direction));
 
     #[cfg(feature = "content_size")]
     {
@@ -1908,14 +2340,14 @@ fn calculate_flex_item(
 fn calculate_layout_line(
     tree: &mut impl LayoutFlexboxContainer,
     line: &mut FlexLine,
-    total_offset_cross: &mut f32,
+    total_offset_cross: &mut Offset,
     #[cfg(feature = "content_size")] content_size: &mut Size<f32>,
     container_size: Size<f32>,
     node_inner_size: Size<Option<f32>>,
     padding_border: Rect<f32>,
     direction: FlexDirection,
 ) {
-    let mut total_offset_main = padding_border.main_start(direction);
+    let mut total_offset_main = Offset::new(padding_border.main_start(direction));
     let line_offset_cross = line.offset_cross;
 
     if direction.is_reverse() {
@@ -1924,7 +2356,7 @@ fn calculate_layout_line(
                 tree,
                 item,
                 &mut total_offset_main,
-                *total_offset_cross,
+                total_offset_cross.get(),
                 line_offset_cross,
                 #[cfg(feature = "content_size")]
                 content_size,
@@ -1939,7 +2371,7 @@ fn calculate_layout_line(
                 tree,
                 item,
                 &mut total_offset_main,
-                *total_offset_cross,
+                total_offset_cross.get(),
                 line_offset_cross,
                 #[cfg(feature = "content_size")]
                 content_size,
@@ -1950,7 +2382,7 @@ fn calculate_layout_line(
         }
     }
 
-    *total_offset_cross += line_offset_cross + line.cross_size;
+    total_offset_cross.add(line.cross_size);
 }
 
 /// Do a final layout pass and collect the resulting layouts.
@@ -1960,7 +2392,7 @@ fn final_layout_pass(
     flex_lines: &mut [FlexLine],
     constants: &AlgoConstants,
 ) -> Size<f32> {
-    let mut total_offset_cross = constants.content_box_inset.cross_start(constants.dir);
+    let mut total_offset_cross = Offset::new(constants.content_box_inset.cross_start(constants.dir));
 
     #[cfg_attr(not(feature = "content_size"), allow(unused_mut))]
     let mut content_size = Size::ZERO;
@@ -2008,7 +2440,7 @@ fn perform_absolute_layout_on_absolute_children(
     let container_width = constants.container_size.width;
     let container_height = constants.container_size.height;
     let inset_relative_size =
-        constants.container_size - constants.border.sum_axes() - constants.scrollbar_gutter.into();
+        constants.container_size - constants.border.sum_axes() - constants.scrollbar_gutter.sum_axes();
 
     #[cfg_attr(not(feature = "content_size"), allow(unused_mut))]
     let mut content_size = Size::ZERO;
@@ -2027,8 +2459,9 @@ fn perform_absolute_layout_on_absolute_children(
         let scrollbar_width = child_style.scrollbar_width();
         let aspect_ratio = child_style.aspect_ratio();
         let align_self = child_style.align_self().unwrap_or(constants.align_items);
-        let margin = child_style.margin().map(|margin| margin.resolve_to_option(inset_relative_size.width));
-        let padding = child_style.padding().resolve_or_zero(Some(inset_relative_size.width));
+        let percent_basis = constants.config.percent_resolution_mode.basis(inset_relative_size);
+        let margin = child_style.margin().zip_size(percent_basis, |margin, basis| margin.resolve_to_option(basis));
+        let padding = child_style.padding().resolve_or_zero(percent_basis.map(Some));
         let border = child_style.border().resolve_or_zero(Some(inset_relative_size.width));
         let padding_border_sum = (padding + border).sum_axes();
         let box_sizing_adjustment =
@@ -2139,11 +2572,14 @@ fn perform_absolute_layout_on_absolute_children(
         // Apply main-axis alignment
         // let free_main_space = free_space.main(constants.dir) - resolved_margin.main_axis_sum(constants.dir);
         let offset_main = if let Some(start) = start_main {
-            start + constants.border.main_start(constants.dir) + resolved_margin.main_start(constants.dir)
+            start
+                + constants.border.main_start(constants.dir)
+                + constants.scrollbar_gutter.main_start(constants.dir)
+                + resolved_margin.main_start(constants.dir)
         } else if let Some(end) = end_main {
             constants.container_size.main(constants.dir)
                 - constants.border.main_end(constants.dir)
-                - constants.scrollbar_gutter.main(constants.dir)
+                - constants.scrollbar_gutter.main_end(constants.dir)
                 - final_size.main(constants.dir)
                 - end
                 - resolved_margin.main_end(constants.dir)
@@ -2182,11 +2618,14 @@ fn perform_absolute_layout_on_absolute_children(
         // Apply cross-axis alignment
         // let free_cross_space = free_space.cross(constants.dir) - resolved_margin.cross_axis_sum(constants.dir);
         let offset_cross = if let Some(start) = start_cross {
-            start + constants.border.cross_start(constants.dir) + resolved_margin.cross_start(constants.dir)
+            start
+                + constants.border.cross_start(constants.dir)
+                + constants.scrollbar_gutter.cross_start(constants.dir)
+                + resolved_margin.cross_start(constants.dir)
         } else if let Some(end) = end_cross {
             constants.container_size.cross(constants.dir)
                 - constants.border.cross_end(constants.dir)
-                - constants.scrollbar_gutter.cross(constants.dir)
+                - constants.scrollbar_gutter.cross_end(constants.dir)
                 - final_size.cross(constants.dir)
                 - end
                 - resolved_margin.cross_end(constants.dir)
@@ -2197,12 +2636,12 @@ fn perform_absolute_layout_on_absolute_children(
                 // Note: Stretch should be FlexStart not Start when we support both
                 (AlignSelf::Start, _)
                 | (AlignSelf::Baseline | AlignSelf::Stretch | AlignSelf::FlexStart, false)
-                | (AlignSelf::FlexEnd, true) => {
+                | (AlignSelf::LastBaseline | AlignSelf::FlexEnd, true) => {
                     constants.content_box_inset.cross_start(constants.dir) + resolved_margin.cross_start(constants.dir)
                 }
                 (AlignSelf::End, _)
                 | (AlignSelf::Baseline | AlignSelf::Stretch | AlignSelf::FlexStart, true)
-                | (AlignSelf::FlexEnd, false) => {
+                | (AlignSelf::LastBaseline | AlignSelf::FlexEnd, false) => {
                     constants.container_size.cross(constants.dir)
                         - constants.content_box_inset.cross_end(constants.dir)
                         - final_size.cross(constants.dir)
@@ -2240,28 +2679,19 @@ fn perform_absolute_layout_on_absolute_children(
                 padding,
                 border,
                 margin: resolved_margin,
+                first_baseline: layout_output.first_baselines.y,
+                scroll_origin_shift: layout_output.scroll_origin_shift,
             },
         );
 
         #[cfg(feature = "content_size")]
         {
-            let size_content_size_contribution = Size {
-                width: match overflow.x {
-                    Overflow::Visible => f32_max(final_size.width, layout_output.content_size.width),
-                    _ => final_size.width,
-                },
-                height: match overflow.y {
-                    Overflow::Visible => f32_max(final_size.height, layout_output.content_size.height),
-                    _ => final_size.height,
-                },
-            };
-            if size_content_size_contribution.has_non_zero_area() {
-                let content_size_contribution = Size {
-                    width: location.x + size_content_size_contribution.width,
-                    height: location.y + size_content_size_contribution.height,
-                };
-                content_size = content_size.f32_max(content_size_contribution);
-            }
+            content_size = content_size.f32_max(compute_content_size_contribution(
+                location,
+                final_size,
+                layout_output.content_size,
+                overflow,
+            ));
         }
     }
 
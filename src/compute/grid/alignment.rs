@@ -1,9 +1,12 @@
 //! Alignment of tracks and final positioning of items
 use super::types::GridTrack;
-use crate::compute::common::alignment::{apply_alignment_fallback, compute_alignment_offset};
+use crate::compute::common::alignment::{
+    apply_alignment_fallback, compute_alignment_offset, compute_safe_alignment_shift,
+};
 use crate::geometry::{InBothAbsAxis, Line, Point, Rect, Size};
 use crate::style::{AlignContent, AlignItems, AlignSelf, AvailableSpace, CoreStyle, GridItemStyle, Overflow, Position};
 use crate::tree::{Layout, LayoutPartialTreeExt, NodeId, SizingMode};
+use crate::util::precision::{Offset, PreciseSum};
 use crate::util::sys::f32_max;
 use crate::util::{MaybeMath, MaybeResolve, ResolveOrZero};
 
@@ -14,14 +17,18 @@ use crate::{BoxSizing, LayoutGridContainer};
 /// Align the grid tracks within the grid according to the align-content (rows) or
 /// justify-content (columns) property. This only does anything if the size of the
 /// grid is not equal to the size of the grid container in the axis being aligned.
+///
+/// Alignment is safe if the container is a scroll container in the axis being aligned. Returns how far the tracks were
+/// moved towards the end of the axis by this (see [`Layout::scroll_origin_shift`]).
 pub(super) fn align_tracks(
     grid_container_content_box_size: f32,
     padding: Line<f32>,
     border: Line<f32>,
     tracks: &mut [GridTrack],
     track_alignment_style: AlignContent,
-) {
-    let used_size: f32 = tracks.iter().map(|track| track.base_size).sum();
+    is_scroll_container: bool,
+) -> f32 {
+    let used_size: f32 = tracks.iter().map(|track| track.base_size).precise_sum();
     let free_space = grid_container_content_box_size - used_size;
     let origin = padding.start + border.start;
 
@@ -32,30 +39,40 @@ pub(super) fn align_tracks(
     // simply pass zero here. Grid layout is never reversed.
     let gap = 0.0;
     let layout_is_reversed = false;
-    let is_safe = false; // TODO: Implement safe alignment
+    let is_safe = is_scroll_container;
     let track_alignment = apply_alignment_fallback(free_space, num_tracks, track_alignment_style, is_safe);
 
-    // Compute offsets
-    let mut total_offset = origin;
+    // Compute offsets. The sizes of the tracks and the space distributed by alignment are summed separately so that
+    // floating point error from summing the track sizes does not compound the error from distributing space.
+    let mut total_size = Offset::default();
+    let mut alignment_offset = 0.0;
     tracks.iter_mut().enumerate().for_each(|(i, track)| {
         // Odd tracks are gutters (but slices are zero-indexed, so odd tracks have even indices)
         let is_gutter = i % 2 == 0;
 
-        // The first non-gutter track is index 1
-        let is_first = i == 1;
-
-        let offset = if is_gutter {
-            0.0
-        } else {
-            compute_alignment_offset(free_space, num_tracks, gap, track_alignment, layout_is_reversed, is_first)
-        };
+        // Gutters directly follow the preceding track, so only non-gutter tracks update the alignment offset.
+        // The first non-gutter track is index 1.
+        if !is_gutter {
+            let index = i / 2;
+            alignment_offset =
+                compute_alignment_offset(free_space, num_tracks, gap, track_alignment, layout_is_reversed, index);
+        }
 
-        track.offset = total_offset + offset;
-        total_offset = total_offset + offset + track.base_size;
+        track.offset = origin + total_size.get() + alignment_offset;
+        total_size.add(track.base_size);
     });
+
+    if is_safe {
+        compute_safe_alignment_shift(free_space, num_tracks, track_alignment_style, layout_is_reversed)
+    } else {
+        0.0
+    }
 }
 
 /// Align and size a grid item into it's final position
+///
+/// Returns the item's content size contribution, its final y position and height, and the final position of its
+/// last baseline (synthesized from its bottom edge if it does not have one)
 pub(super) fn align_and_position_item(
     tree: &mut impl LayoutGridContainer,
     node: NodeId,
@@ -63,8 +80,9 @@ pub(super) fn align_and_position_item(
     grid_area: Rect<f32>,
     container_alignment_styles: InBothAbsAxis<Option<AlignItems>>,
     baseline_shim: f32,
-) -> (Size<f32>, f32, f32) {
+) -> (Size<f32>, f32, f32, f32) {
     let grid_area_size = Size { width: grid_area.right - grid_area.left, height: grid_area.bottom - grid_area.top };
+    let percent_basis = tree.get_layout_config().percent_resolution_mode.basis(grid_area_size);
 
     let style = tree.get_grid_child_style(node);
 
@@ -78,7 +96,7 @@ pub(super) fn align_and_position_item(
     let inset_horizontal =
         style.inset().horizontal_components().map(|size| size.resolve_to_option(grid_area_size.width));
     let inset_vertical = style.inset().vertical_components().map(|size| size.resolve_to_option(grid_area_size.height));
-    let padding = style.padding().map(|p| p.resolve_or_zero(Some(grid_area_size.width)));
+    let padding = style.padding().zip_size(percent_basis, |p, basis| p.resolve_or_zero(Some(basis)));
     let border = style.border().map(|p| p.resolve_or_zero(Some(grid_area_size.width)));
     let padding_border_size = (padding + border).sum_axes();
 
@@ -125,8 +143,8 @@ pub(super) fn align_and_position_item(
     };
 
     // Note: This is not a bug. It is part of the CSS spec that both horizontal and vertical margins
-    // resolve against the WIDTH of the grid area.
-    let margin = style.margin().map(|margin| margin.resolve_to_option(grid_area_size.width));
+    // resolve against the WIDTH of the grid area (unless the tree uses `PercentResolutionMode::PerAxis`).
+    let margin = style.margin().zip_size(percent_basis, |margin, basis| margin.resolve_to_option(basis));
 
     let grid_area_minus_item_margins_size = Size {
         width: grid_area_size.width.maybe_sub(margin.left).maybe_sub(margin.right),
@@ -241,6 +259,8 @@ pub(super) fn align_and_position_item(
             padding,
             border,
             margin: resolved_margin,
+            first_baseline: layout_output.first_baselines.y,
+            scroll_origin_shift: layout_output.scroll_origin_shift,
         },
     );
 
@@ -250,7 +270,9 @@ pub(super) fn align_and_position_item(
     #[cfg(not(feature = "content_size"))]
     let contribution = Size::ZERO;
 
-    (contribution, y, height)
+    let last_baseline = y + layout_output.last_baselines.y.unwrap_or(height);
+
+    (contribution, y, height, last_baseline)
 }
 
 /// Align and size a grid item along a single axis
@@ -263,8 +285,18 @@ pub(super) fn align_item_within_area(
     margin: Line<Option<f32>>,
     baseline_shim: f32,
 ) -> (f32, Line<f32>) {
+    // The baseline shim acts as an extra margin on the side of the area that the item's baseline-sharing group is
+    // packed toward: the start for first baseline alignment and the end for last baseline alignment
+    let baseline_shim = match alignment_style {
+        AlignSelf::LastBaseline => Line { start: 0.0, end: baseline_shim },
+        _ => Line { start: baseline_shim, end: 0.0 },
+    };
+
     // Calculate grid area dimension in the axis
-    let non_auto_margin = Line { start: margin.start.unwrap_or(0.0) + baseline_shim, end: margin.end.unwrap_or(0.0) };
+    let non_auto_margin = Line {
+        start: margin.start.unwrap_or(0.0) + baseline_shim.start,
+        end: margin.end.unwrap_or(0.0) + baseline_shim.end,
+    };
     let grid_area_size = f32_max(grid_area.end - grid_area.start, 0.0);
     let free_space = f32_max(grid_area_size - resolved_size - non_auto_margin.sum(), 0.0);
 
@@ -272,8 +304,8 @@ pub(super) fn align_item_within_area(
     let auto_margin_count = margin.start.is_none() as u8 + margin.end.is_none() as u8;
     let auto_margin_size = if auto_margin_count > 0 { free_space / auto_margin_count as f32 } else { 0.0 };
     let resolved_margin = Line {
-        start: margin.start.unwrap_or(auto_margin_size) + baseline_shim,
-        end: margin.end.unwrap_or(auto_margin_size),
+        start: margin.start.unwrap_or(auto_margin_size) + baseline_shim.start,
+        end: margin.end.unwrap_or(auto_margin_size) + baseline_shim.end,
     };
 
     // Compute offset in the axis
@@ -281,8 +313,10 @@ pub(super) fn align_item_within_area(
         AlignSelf::Start | AlignSelf::FlexStart => resolved_margin.start,
         AlignSelf::End | AlignSelf::FlexEnd => grid_area_size - resolved_size - resolved_margin.end,
         AlignSelf::Center => (grid_area_size - resolved_size + resolved_margin.start - resolved_margin.end) / 2.0,
-        // TODO: Add support for baseline alignment. For now we treat it as "start".
+        // Baseline alignment is applied through the baseline shim. Items without a shim (including all items
+        // in the horizontal axis, as we don't support vertical writing modes) fall back to start or end alignment
         AlignSelf::Baseline => resolved_margin.start,
+        AlignSelf::LastBaseline => grid_area_size - resolved_size - resolved_margin.end,
         AlignSelf::Stretch => resolved_margin.start,
     };
 
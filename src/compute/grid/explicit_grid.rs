@@ -4,7 +4,8 @@ use super::types::{GridTrack, TrackCounts};
 use crate::geometry::{AbsoluteAxis, Size};
 use crate::style::{GridTrackRepetition, LengthPercentage, NonRepeatedTrackSizingFunction, TrackSizingFunction};
 use crate::style_helpers::TaffyAuto;
-use crate::util::sys::Vec;
+use crate::util::precision::PreciseSum;
+use crate::util::sys::LayoutTrackVec;
 use crate::util::MaybeMath;
 use crate::util::ResolveOrZero;
 use crate::GridContainerStyle;
@@ -120,20 +121,20 @@ pub(crate) fn compute_explicit_grid_size_in_axis(
                             let sum = repeated_tracks
                                 .iter()
                                 .map(|sizing_function| track_definite_value(sizing_function, parent_size))
-                                .sum::<f32>();
+                                .precise_sum();
                             sum * (*count as f32)
                         }
                         TrackSizingFunction::Repeat(AutoFit | AutoFill, _) => 0.0,
                     }
                 })
-                .sum();
+                .precise_sum();
             let gap_size = style.gap().get_abs(axis).resolve_or_zero(Some(inner_container_size));
 
             // Compute the amount of space that a single repetition of the repeated track list takes
             let per_repetition_track_used_space: f32 = repetition_definition
                 .iter()
                 .map(|sizing_function| track_definite_value(sizing_function, parent_size))
-                .sum::<f32>();
+                .precise_sum();
 
             // We special case the first repetition here because the number of gaps in the first repetition
             // depends on the number of non-repeating tracks in the template
@@ -173,7 +174,7 @@ pub(crate) fn compute_explicit_grid_size_in_axis(
 /// Resolve the track sizing functions of explicit tracks, automatically created tracks, and gutters
 /// given a set of track counts and all of the relevant styles
 pub(super) fn initialize_grid_tracks(
-    tracks: &mut Vec<GridTrack>,
+    tracks: &mut LayoutTrackVec<GridTrack>,
     counts: TrackCounts,
     track_template: &[TrackSizingFunction],
     auto_tracks: &[NonRepeatedTrackSizingFunction],
@@ -266,7 +267,7 @@ pub(super) fn initialize_grid_tracks(
 
 /// Utility function for repeating logic of creating implicit tracks
 fn create_implicit_tracks(
-    tracks: &mut Vec<GridTrack>,
+    tracks: &mut LayoutTrackVec<GridTrack>,
     count: u16,
     mut auto_tracks_iter: impl Iterator<Item = NonRepeatedTrackSizingFunction>,
     gap: LengthPercentage,
@@ -287,6 +288,7 @@ mod test {
     use crate::compute::grid::util::*;
     use crate::geometry::AbsoluteAxis;
     use crate::prelude::*;
+    use crate::util::sys::LayoutTrackVec;
 
     #[test]
     fn explicit_grid_sizing_no_repeats() {
@@ -568,7 +570,7 @@ mod test {
         let gap = px20;
 
         // Call function
-        let mut tracks = Vec::new();
+        let mut tracks = LayoutTrackVec::new();
         initialize_grid_tracks(&mut tracks, track_counts, &track_template, &auto_tracks, gap, |_| false);
 
         // Assertions
@@ -122,7 +122,7 @@ fn child_min_line_max_line_span(
         (Line(track), Span(_)) => track,
 
         // End track specified
-        (Auto, Line(track)) => track,
+        (Auto, Line(track)) => track - 1,
         (Span(span), Line(track)) => track - span,
 
         // Only spans or autos
@@ -188,6 +188,14 @@ mod tests {
             assert_eq!(max_col, OriginZeroLine(5));
             assert_eq!(span, 1);
         }
+
+        #[test]
+        fn child_min_max_line_negative_end_line() {
+            let (min_col, max_col, span) = child_min_line_max_line_span(Line { start: auto(), end: line(-9) }, 6);
+            assert_eq!(min_col, OriginZeroLine(-3));
+            assert_eq!(max_col, OriginZeroLine(-2));
+            assert_eq!(span, 1);
+        }
     }
 
     mod test_initial_grid_sizing {
@@ -4,15 +4,16 @@ use core::borrow::Borrow;
 
 use crate::geometry::{AbsoluteAxis, AbstractAxis, InBothAbsAxis};
 use crate::geometry::{Line, Point, Rect, Size};
-use crate::style::{AlignItems, AlignSelf, AvailableSpace, Overflow, Position};
+use crate::style::{AlignItems, AlignSelf, AvailableSpace, Position};
 use crate::tree::{Layout, LayoutInput, LayoutOutput, LayoutPartialTreeExt, NodeId, RunMode, SizingMode};
 use crate::util::debug::debug_log;
-use crate::util::sys::{f32_max, GridTrackVec, Vec};
+use crate::util::precision::PreciseSum;
+use crate::util::sys::{f32_max, LayoutTrackVec, Vec};
 use crate::util::MaybeMath;
 use crate::util::{MaybeResolve, ResolveOrZero};
 use crate::{
     style_helpers::*, AlignContent, BoxGenerationMode, BoxSizing, CoreStyle, GridContainerStyle, GridItemStyle,
-    JustifyContent, LayoutGridContainer,
+    JustifyContent, LayoutGridContainer, Overflow,
 };
 use alignment::{align_and_position_item, align_tracks};
 use explicit_grid::{compute_explicit_grid_size_in_axis, initialize_grid_tracks};
@@ -23,6 +24,10 @@ use track_sizing::{
 };
 use types::{CellOccupancyMatrix, GridTrack};
 
+#[cfg(feature = "content_size")]
+use super::common::content_size::clip_content_size;
+
+pub use types::GridOccupancyStats;
 pub(crate) use types::{GridCoordinate, GridLine, OriginZeroLine};
 
 mod alignment;
@@ -40,14 +45,14 @@ mod util;
 ///   - Track (row/column) sizing
 ///   - Alignment & Final item placement
 pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
-    let LayoutInput { known_dimensions, parent_size, available_space, run_mode, .. } = inputs;
+    let LayoutInput { known_dimensions, parent_size, available_space, run_mode, config, .. } = inputs;
 
     let style = tree.get_grid_container_style(node);
 
     // 1. Compute "available grid space"
     // https://www.w3.org/TR/css-grid-1/#available-grid-space
     let aspect_ratio = style.aspect_ratio();
-    let padding = style.padding().resolve_or_zero(parent_size.width);
+    let padding = style.padding().resolve_or_zero(config.percent_resolution_mode.basis(parent_size));
     let border = style.border().resolve_or_zero(parent_size.width);
     let padding_border = padding + border;
     let padding_border_size = padding_border.sum_axes();
@@ -74,20 +79,14 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         Size::NONE
     };
 
-    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`.
-    // However, the axis are switched (transposed) because a node that scrolls vertically needs
-    // *horizontal* space to be reserved for a scrollbar
-    let scrollbar_gutter = style.overflow().transpose().map(|overflow| match overflow {
-        Overflow::Scroll => style.scrollbar_width(),
-        _ => 0.0,
-    });
-    // TODO: make side configurable based on the `direction` property
-    let mut content_box_inset = padding_border;
-    content_box_inset.right += scrollbar_gutter.x;
-    content_box_inset.bottom += scrollbar_gutter.y;
+    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`, or when the node
+    // is a scroll container with a stable `scrollbar_gutter`
+    let scrollbar_gutter = style.scrollbar_gutter().resolve(style.overflow(), style.scrollbar_width());
+    let content_box_inset = padding_border + scrollbar_gutter;
 
     let align_content = style.align_content().unwrap_or(AlignContent::Stretch);
     let justify_content = style.justify_content().unwrap_or(JustifyContent::Stretch);
+    let is_scroll_container = style.overflow().map(Overflow::is_scroll_container);
     let align_items = style.align_items();
     let justify_items = style.justify_items();
 
@@ -130,9 +129,12 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         return LayoutOutput::from_outer_size(Size { width, height });
     }
 
-    let get_child_styles_iter =
-        |node| tree.child_ids(node).map(|child_node: NodeId| tree.get_grid_child_style(child_node));
-    let child_styles_iter = get_child_styles_iter(node);
+    // Children with `display: none` don't generate grid items, so they must not generate implicit tracks either
+    // (unless emulating the legacy behaviour)
+    let child_styles_iter = tree
+        .child_ids(node)
+        .map(|child_node: NodeId| tree.get_grid_child_style(child_node))
+        .filter(|style| config.legacy_hidden_grid_items || style.box_generation_mode() != BoxGenerationMode::None);
 
     // 2. Resolve the explicit grid
 
@@ -193,8 +195,8 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
     // 5. Initialize Tracks
     // Initialize (explicit and implicit) grid tracks (and gutters)
     // This resolves the min and max track sizing functions for all tracks and gutters
-    let mut columns = GridTrackVec::new();
-    let mut rows = GridTrackVec::new();
+    let mut columns = LayoutTrackVec::new();
+    let mut rows = LayoutTrackVec::new();
     initialize_grid_tracks(
         &mut columns,
         final_col_counts,
@@ -218,6 +220,9 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
     drop(grid_auto_columms);
     drop(style);
 
+    // Report how large the grid ended up being (for inspection)
+    tree.set_grid_occupancy_stats(node, cell_occupancy_matrix.stats());
+
     // 6. Track Sizing
 
     // Convert grid placements in origin-zero coordinates to indexes into the GridTrack (rows and columns) vectors
@@ -230,14 +235,17 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
     determine_if_item_crosses_flexible_or_intrinsic_tracks(&mut items, &columns, &rows);
 
     // Determine if the grid has any baseline aligned items
-    let has_baseline_aligned_item = items.iter().any(|item| item.align_self == AlignSelf::Baseline);
+    let has_baseline_aligned_item =
+        items.iter().any(|item| matches!(item.align_self, AlignSelf::Baseline | AlignSelf::LastBaseline));
 
     // Run track sizing algorithm for Inline axis
+    let mut track_sizing_passes = 2;
     track_sizing_algorithm(
         tree,
         AbstractAxis::Inline,
         min_size.get(AbstractAxis::Inline),
         max_size.get(AbstractAxis::Inline),
+        justify_content,
         align_content,
         available_grid_space,
         inner_node_size,
@@ -247,7 +255,7 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         |track: &GridTrack, parent_size: Option<f32>| track.max_track_sizing_function.definite_value(parent_size),
         has_baseline_aligned_item,
     );
-    let initial_column_sum = columns.iter().map(|track| track.base_size).sum::<f32>();
+    let initial_column_sum = columns.iter().map(|track| track.base_size).precise_sum();
     inner_node_size.width = inner_node_size.width.or_else(|| initial_column_sum.into());
 
     items.iter_mut().for_each(|item| item.available_space_cache = None);
@@ -258,6 +266,7 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         AbstractAxis::Block,
         min_size.get(AbstractAxis::Block),
         max_size.get(AbstractAxis::Block),
+        align_content,
         justify_content,
         available_grid_space,
         inner_node_size,
@@ -267,7 +276,7 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         |track: &GridTrack, _| Some(track.base_size),
         false, // TODO: Support baseline alignment in the vertical axis
     );
-    let initial_row_sum = rows.iter().map(|track| track.base_size).sum::<f32>();
+    let initial_row_sum = rows.iter().map(|track| track.base_size).precise_sum();
     inner_node_size.height = inner_node_size.height.or_else(|| initial_row_sum.into());
 
     debug_log!("initial_column_sum", dbg:initial_column_sum);
@@ -366,11 +375,13 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
 
     if rerun_column_sizing {
         // Re-run track sizing algorithm for Inline axis
+        track_sizing_passes += 1;
         track_sizing_algorithm(
             tree,
             AbstractAxis::Inline,
             min_size.get(AbstractAxis::Inline),
             max_size.get(AbstractAxis::Inline),
+            justify_content,
             align_content,
             available_grid_space,
             inner_node_size,
@@ -428,11 +439,13 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
 
         if rerun_row_sizing {
             // Re-run track sizing algorithm for Block axis
+            track_sizing_passes += 1;
             track_sizing_algorithm(
                 tree,
                 AbstractAxis::Block,
                 min_size.get(AbstractAxis::Block),
                 max_size.get(AbstractAxis::Block),
+                align_content,
                 justify_content,
                 available_grid_space,
                 inner_node_size,
@@ -445,23 +458,27 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         }
     }
 
+    tree.add_grid_track_sizing_passes(node, track_sizing_passes);
+
     // 8. Track Alignment
 
     // Align columns
-    align_tracks(
+    let scroll_origin_shift_x = align_tracks(
         container_content_box.get(AbstractAxis::Inline),
         Line { start: padding.left, end: padding.right },
-        Line { start: border.left, end: border.right },
+        Line { start: border.left + scrollbar_gutter.left, end: border.right + scrollbar_gutter.right },
         &mut columns,
         justify_content,
+        is_scroll_container.x,
     );
     // Align rows
-    align_tracks(
+    let scroll_origin_shift_y = align_tracks(
         container_content_box.get(AbstractAxis::Block),
         Line { start: padding.top, end: padding.bottom },
-        Line { start: border.top, end: border.bottom },
+        Line { start: border.top + scrollbar_gutter.top, end: border.bottom + scrollbar_gutter.bottom },
         &mut rows,
         align_content,
+        is_scroll_container.y,
     );
 
     // 9. Size, Align, and Position Grid Items
@@ -483,7 +500,7 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
             right: columns[item.column_indexes.end as usize].offset,
         };
         #[cfg_attr(not(feature = "content_size"), allow(unused_variables))]
-        let (content_size_contribution, y_position, height) = align_and_position_item(
+        let (content_size_contribution, y_position, height, last_baseline) = align_and_position_item(
             tree,
             item.node,
             index as u32,
@@ -493,6 +510,7 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         );
         item.y_position = y_position;
         item.height = height;
+        item.last_baseline = last_baseline;
 
         #[cfg(feature = "content_size")]
         {
@@ -544,22 +562,28 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
                 });
 
             let grid_area = Rect {
-                top: maybe_row_indexes.start.map(|index| rows[index].offset).unwrap_or(border.top),
+                top: maybe_row_indexes
+                    .start
+                    .map(|index| rows[index].offset)
+                    .unwrap_or(border.top + scrollbar_gutter.top),
                 bottom: maybe_row_indexes
                     .end
                     .map(|index| rows[index].offset)
-                    .unwrap_or(container_border_box.height - border.bottom - scrollbar_gutter.y),
-                left: maybe_col_indexes.start.map(|index| columns[index].offset).unwrap_or(border.left),
+                    .unwrap_or(container_border_box.height - border.bottom - scrollbar_gutter.bottom),
+                left: maybe_col_indexes
+                    .start
+                    .map(|index| columns[index].offset)
+                    .unwrap_or(border.left + scrollbar_gutter.left),
                 right: maybe_col_indexes
                     .end
                     .map(|index| columns[index].offset)
-                    .unwrap_or(container_border_box.width - border.right - scrollbar_gutter.x),
+                    .unwrap_or(container_border_box.width - border.right - scrollbar_gutter.right),
             };
             drop(child_style);
 
             // TODO: Baseline alignment support for absolutely positioned items (should check if is actuallty specified)
             #[cfg_attr(not(feature = "content_size"), allow(unused_variables))]
-            let (content_size_contribution, _, _) =
+            let (content_size_contribution, _, _, _) =
                 align_and_position_item(tree, child, order, grid_area, container_alignment_styles, 0.0);
             #[cfg(feature = "content_size")]
             {
@@ -575,7 +599,7 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         return LayoutOutput::from_outer_size(container_border_box);
     }
 
-    // Determine the grid container baseline(s) (currently we only compute the first baseline)
+    // Determine the grid container's first baseline
     let grid_container_baseline: f32 = {
         // Sort items by row start position so that we can iterate items in groups which are in the same row
         items.sort_by_key(|item| item.row_indexes.start);
@@ -598,9 +622,31 @@ pub fn compute_grid_layout(tree: &mut impl LayoutGridContainer, node: NodeId, in
         item.y_position + item.baseline.unwrap_or(item.height)
     };
 
+    // Determine the grid container's last baseline from the items in the last row containing items
+    let grid_container_last_baseline: f32 = {
+        let last_row = items.iter().map(|item| item.row_indexes.end).max().unwrap();
+        let mut last_row_items = items.iter().filter(|item| item.row_indexes.end == last_row);
+
+        let item = match last_row_items.clone().rfind(|item| item.align_self == AlignSelf::LastBaseline) {
+            Some(item) => item,
+            None => last_row_items.next_back().unwrap(),
+        };
+
+        item.last_baseline
+    };
+
+    #[cfg(feature = "content_size")]
+    let item_content_size_contribution = clip_content_size(
+        container_border_box,
+        item_content_size_contribution,
+        tree.get_grid_container_style(node).overflow(),
+    );
+
     LayoutOutput::from_sizes_and_baselines(
         container_border_box,
         item_content_size_contribution,
         Point { x: None, y: Some(grid_container_baseline) },
     )
+    .with_last_baselines(Point { x: None, y: Some(grid_container_last_baseline) })
+    .with_scroll_origin_shift(Point { x: scroll_origin_shift_x, y: scroll_origin_shift_y })
 }
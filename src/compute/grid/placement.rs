@@ -6,6 +6,7 @@ use crate::geometry::Line;
 use crate::geometry::{AbsoluteAxis, InBothAbsAxis};
 use crate::style::{AlignItems, GridAutoFlow, OriginZeroGridPlacement};
 use crate::tree::NodeId;
+use crate::util::debug::debug_log;
 use crate::util::sys::Vec;
 use crate::GridItemStyle;
 
@@ -48,8 +49,7 @@ pub(super) fn place_grid_items<'a, S, ChildIter>(
         .map(map_child_style_to_origin_zero_placement)
         .for_each(|(index, child_node, child_placement, style)| {
             idx += 1;
-            #[cfg(test)]
-            println!("Definite Item {idx}\n==============");
+            debug_log!("Definite Item", idx);
 
             let (row_span, col_span) = place_definite_grid_item(child_placement, primary_axis);
             record_grid_placement(
@@ -77,8 +77,7 @@ pub(super) fn place_grid_items<'a, S, ChildIter>(
         .map(map_child_style_to_origin_zero_placement)
         .for_each(|(index, child_node, child_placement, style)| {
             idx += 1;
-            #[cfg(test)]
-            println!("Definite Secondary Item {idx}\n==============");
+            debug_log!("Definite Secondary Item", idx);
 
             let (primary_span, secondary_span) =
                 place_definite_secondary_axis_item(&*cell_occupancy_matrix, child_placement, grid_auto_flow);
@@ -129,8 +128,7 @@ pub(super) fn place_grid_items<'a, S, ChildIter>(
         .map(map_child_style_to_origin_zero_placement)
         .for_each(|(index, child_node, child_placement, style)| {
             idx += 1;
-            #[cfg(test)]
-            println!("\nAuto Item {idx}\n==============");
+            debug_log!("Auto Item", idx);
 
             // Compute placement
             let (primary_span, secondary_span) = place_indefinitely_positioned_item(
@@ -196,22 +194,15 @@ fn place_definite_secondary_axis_item(
             .unwrap_or(primary_axis_grid_start_line),
     };
 
-    let mut position: OriginZeroLine = starting_position;
-    loop {
-        let primary_axis_placement = placement.get(primary_axis).resolve_indefinite_grid_tracks(position);
+    let position = cell_occupancy_matrix.first_unoccupied_position(
+        primary_axis,
+        placement.get(primary_axis).indefinite_span(),
+        secondary_axis_placement,
+        starting_position,
+    );
+    let primary_axis_placement = placement.get(primary_axis).resolve_indefinite_grid_tracks(position);
 
-        let does_fit = cell_occupancy_matrix.line_area_is_unoccupied(
-            primary_axis,
-            primary_axis_placement,
-            secondary_axis_placement,
-        );
-
-        if does_fit {
-            return (primary_axis_placement, secondary_axis_placement);
-        } else {
-            position += 1;
-        }
-    }
+    (primary_axis_placement, secondary_axis_placement)
 }
 
 /// 8.5. Grid Item Placement Algorithm
@@ -231,8 +222,6 @@ fn place_indefinitely_positioned_item(
     let has_definite_primary_axis_position = primary_placement_style.is_definite();
     let primary_axis_grid_start_line = cell_occupancy_matrix.track_counts(primary_axis).implicit_start_line();
     let primary_axis_grid_end_line = cell_occupancy_matrix.track_counts(primary_axis).implicit_end_line();
-    let secondary_axis_grid_start_line =
-        cell_occupancy_matrix.track_counts(primary_axis.other_axis()).implicit_start_line();
 
     let line_area_is_occupied = |primary_span, secondary_span| {
         !cell_occupancy_matrix.line_area_is_unoccupied(primary_axis, primary_span, secondary_span)
@@ -244,13 +233,13 @@ fn place_indefinitely_positioned_item(
         let definite_primary_placement = primary_placement_style.resolve_definite_grid_lines();
         let defined_primary_idx = definite_primary_placement.start;
 
-        // Compute starting position for search
-        if defined_primary_idx < primary_idx && secondary_idx != secondary_axis_grid_start_line {
-            secondary_idx = secondary_axis_grid_start_line;
-            primary_idx = defined_primary_idx + 1;
-        } else {
-            primary_idx = defined_primary_idx;
+        // Compute starting position for search: the cursor moves to the item's primary axis start line, and if this
+        // is before the previous cursor position then it moves on to the next track in the secondary axis.
+        // (with "dense" packing the cursor has already been reset to the start of the grid, so this never applies)
+        if defined_primary_idx < primary_idx {
+            secondary_idx += 1;
         }
+        primary_idx = defined_primary_idx;
 
         // Item has fixed primary axis position: so we simply increment the secondary axis position
         // until we find a space that the item fits in
@@ -274,10 +263,16 @@ fn place_indefinitely_positioned_item(
         // existent tracks, and then we reset the primary axis back to zero and increment the secondary axis index.
         // We continue in this vein until we find a space that the item fits in.
         loop {
-            let primary_span = Line { start: primary_idx, end: primary_idx + primary_span };
             let secondary_span = Line { start: secondary_idx, end: secondary_idx + secondary_span };
+            let primary_start = cell_occupancy_matrix.first_unoccupied_position(
+                primary_axis,
+                primary_span,
+                secondary_span,
+                primary_idx,
+            );
+            let primary_span = Line { start: primary_start, end: primary_start + primary_span };
 
-            // If the primary index is out of bounds, then increment the secondary index and reset the primary
+            // If the first free space is out of bounds, then increment the secondary index and reset the primary
             // index back to the start of the grid
             let primary_out_of_bounds = primary_span.end > primary_axis_grid_end_line;
             if primary_out_of_bounds {
@@ -286,12 +281,6 @@ fn place_indefinitely_positioned_item(
                 continue;
             }
 
-            // If area is occupied, increment the primary index and try again
-            if line_area_is_occupied(primary_span, secondary_span) {
-                primary_idx += 1;
-                continue;
-            }
-
             // Once we find a free space that's in bounds, return that position
             return (primary_span, secondary_span);
         }
@@ -314,10 +303,7 @@ fn record_grid_placement<S: GridItemStyle>(
     secondary_span: Line<OriginZeroLine>,
     placement_type: CellOccupancyState,
 ) {
-    #[cfg(test)]
-    println!("BEFORE placement:");
-    #[cfg(test)]
-    println!("{cell_occupancy_matrix:?}");
+    debug_log!("BEFORE placement:", dbg:&cell_occupancy_matrix);
 
     // Mark area of grid as occupied
     cell_occupancy_matrix.mark_area_as(primary_axis, primary_span, secondary_span, placement_type);
@@ -337,12 +323,7 @@ fn record_grid_placement<S: GridItemStyle>(
         index as u16,
     ));
 
-    #[cfg(test)]
-    println!("AFTER placement:");
-    #[cfg(test)]
-    println!("{cell_occupancy_matrix:?}");
-    #[cfg(test)]
-    println!("\n");
+    debug_log!("AFTER placement:", dbg:&cell_occupancy_matrix);
 }
 
 #[cfg(test)]
@@ -595,5 +576,97 @@ mod tests {
             let expected_rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
             placement_test_runner(explicit_col_count, explicit_row_count, children, expected_cols, expected_rows, flow);
         }
+
+        #[test]
+        fn test_definite_primary_axis_in_negative_tracks_sparse() {
+            let flow = GridAutoFlow::Row;
+            let explicit_col_count = 2;
+            let explicit_row_count = 2;
+            let children = {
+                vec![
+                    // output order, node, style (grid coords), expected_placement (oz coords)
+                    (1, (line(1), span(2), auto(), auto()).into_grid_child(), (0, 2, 0, 1)), // Row 1. Definitely positioned in columns 1-2
+                    (2, (line(-4), auto(), auto(), auto()).into_grid_child(), (-1, 0, 1, 2)), // Row 2 as column -1 is behind the cursor
+                ]
+            };
+            let expected_cols = TrackCounts { negative_implicit: 1, explicit: 2, positive_implicit: 0 };
+            let expected_rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+            placement_test_runner(explicit_col_count, explicit_row_count, children, expected_cols, expected_rows, flow);
+        }
+
+        #[test]
+        fn test_definite_primary_axis_in_negative_tracks_dense() {
+            let flow = GridAutoFlow::RowDense;
+            let explicit_col_count = 2;
+            let explicit_row_count = 2;
+            let children = {
+                vec![
+                    // output order, node, style (grid coords), expected_placement (oz coords)
+                    (1, (line(1), span(2), auto(), auto()).into_grid_child(), (0, 2, 0, 1)), // Row 1. Definitely positioned in columns 1-2
+                    (2, (line(-4), auto(), auto(), auto()).into_grid_child(), (-1, 0, 0, 1)), // Row 1 as the cursor is reset
+                ]
+            };
+            let expected_cols = TrackCounts { negative_implicit: 1, explicit: 2, positive_implicit: 0 };
+            let expected_rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+            placement_test_runner(explicit_col_count, explicit_row_count, children, expected_cols, expected_rows, flow);
+        }
+
+        #[test]
+        fn test_repeated_definite_primary_axis_in_negative_tracks() {
+            let flow = GridAutoFlow::Row;
+            let explicit_col_count = 2;
+            let explicit_row_count = 2;
+            let children = {
+                let auto_child = (auto(), auto(), auto(), auto()).into_grid_child();
+                let negative_child = (line(-4), auto(), auto(), auto()).into_grid_child();
+                vec![
+                    // output order, node, style (grid coords), expected_placement (oz coords)
+                    (1, negative_child.clone(), (-1, 0, 0, 1)),
+                    (2, auto_child.clone(), (0, 1, 0, 1)),
+                    (3, auto_child.clone(), (1, 2, 0, 1)),
+                    (4, auto_child.clone(), (-1, 0, 1, 2)),
+                    (5, auto_child.clone(), (0, 1, 1, 2)),
+                    (6, negative_child.clone(), (-1, 0, 2, 3)), // Stays in column -1 after wrapping onto a new row
+                ]
+            };
+            let expected_cols = TrackCounts { negative_implicit: 1, explicit: 2, positive_implicit: 0 };
+            let expected_rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 1 };
+            placement_test_runner(explicit_col_count, explicit_row_count, children, expected_cols, expected_rows, flow);
+        }
+
+        #[test]
+        fn test_definite_in_secondary_axis_sparse_with_negative_tracks() {
+            let flow = GridAutoFlow::Row;
+            let explicit_col_count = 2;
+            let explicit_row_count = 2;
+            let children = {
+                vec![
+                    // output order, node, style (grid coords), expected_placement (oz coords)
+                    (1, (line(-5), auto(), line(2), auto()).into_grid_child(), (-2, -1, 1, 2)), // Row 2. Definitely positioned in column -2
+                    (2, (auto(), auto(), line(1), auto()).into_grid_child(), (-2, -1, 0, 1)), // Row 1. Auto positioned in column -2
+                    (3, (auto(), auto(), line(1), auto()).into_grid_child(), (-1, 0, 0, 1)), // Row 1. Auto positioned in column -1
+                ]
+            };
+            let expected_cols = TrackCounts { negative_implicit: 2, explicit: 2, positive_implicit: 0 };
+            let expected_rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+            placement_test_runner(explicit_col_count, explicit_row_count, children, expected_cols, expected_rows, flow);
+        }
+
+        #[test]
+        fn test_negative_end_line_with_auto_start() {
+            let flow = GridAutoFlow::Row;
+            let explicit_col_count = 2;
+            let explicit_row_count = 2;
+            let children = {
+                vec![
+                    // output order, node, style (grid coords), expected_placement (oz coords)
+                    (1, (auto(), line(-5), auto(), auto()).into_grid_child(), (-3, -2, 0, 1)), // Row 1. Ends at column line -5
+                    (2, (auto(), auto(), auto(), auto()).into_grid_child(), (-2, -1, 0, 1)), // Row 1. Auto positioned in column -2
+                ]
+            };
+            let expected_cols = TrackCounts { negative_implicit: 3, explicit: 2, positive_implicit: 0 };
+            let expected_rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+            placement_test_runner(explicit_col_count, explicit_row_count, children, expected_cols, expected_rows, flow);
+        }
     }
 }
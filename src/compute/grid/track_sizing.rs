@@ -7,9 +7,11 @@ use crate::style::{
 };
 use crate::style_helpers::TaffyMinContent;
 use crate::tree::{LayoutPartialTree, LayoutPartialTreeExt, SizingMode};
+use crate::util::precision::PreciseSum;
 use crate::util::sys::{f32_max, f32_min, Vec};
 use crate::util::{MaybeMath, ResolveOrZero};
 use core::cmp::Ordering;
+use core::ops::Range;
 
 /// Takes an axis, and a list of grid items sorted firstly by whether they cross a flex track
 /// in the specified axis (items that don't cross a flex track first) and then by the number
@@ -63,6 +65,37 @@ impl ItemBatcher {
     }
 }
 
+/// Remembers the last space distribution performed within a single step of the "increase sizes to accommodate spanning items"
+/// part of the track sizing algorithm, so that repeated distributions for items spanning the same tracks can be skipped.
+///
+/// Within a step the base sizes and growth limits of the tracks are not modified (increases are only *planned* and are
+/// flushed at the end of the step), and each track's planned increase is the maximum of the item-incurred increases. As the
+/// item-incurred increases never decrease as the space being distributed grows, distributing a space no larger than one
+/// that has already been distributed over the same tracks (with the same limits) can never change the outcome. Items are
+/// sorted by span and then start line, so items spanning identical tracks are always adjacent to each other.
+#[derive(Default)]
+struct SpanningItemDistributionCache {
+    /// The tracks to which space was last distributed
+    track_range: Range<usize>,
+    /// Whether the last distribution was performed for a scroll container (which affects the limits used)
+    is_scroll_container: bool,
+    /// The largest space that has been distributed to `track_range` so far
+    space: f32,
+}
+
+impl SpanningItemDistributionCache {
+    /// Returns true if distributing `space` to the tracks in `track_range` could affect the planned increases, and records
+    /// the distribution. Returns false if an equal or larger space has already been distributed to the same tracks.
+    #[inline(always)]
+    fn needs_distribution(&mut self, track_range: Range<usize>, is_scroll_container: bool, space: f32) -> bool {
+        if track_range == self.track_range && is_scroll_container == self.is_scroll_container && space <= self.space {
+            return false;
+        }
+        *self = SpanningItemDistributionCache { track_range, is_scroll_container, space };
+        true
+    }
+}
+
 /// This struct captures a bunch of variables which are used to compute the intrinsic sizes of children so that those variables
 /// don't have to be passed around all over the place below. It then has methods that implement the intrinsic sizing computations
 struct IntrisicSizeMeasurer<'tree, 'oat, Tree, EstimateFunction>
@@ -106,7 +139,9 @@ where
     /// to zero if the container size is indefinite as otherwise this would introduce a cyclic dependency.
     #[inline(always)]
     fn margins_axis_sums_with_baseline_shims(&self, item: &GridItem) -> Size<f32> {
-        item.margins_axis_sums_with_baseline_shims(self.inner_node_size.width)
+        item.margins_axis_sums_with_baseline_shims(
+            self.tree.get_layout_config().percent_resolution_mode.basis(self.inner_node_size).height,
+        )
     }
 
     /// Retrieve the item's min content contribution from the cache or compute it using the provided parameters
@@ -266,6 +301,7 @@ pub(super) fn track_sizing_algorithm<Tree: LayoutPartialTree>(
     axis: AbstractAxis,
     axis_min_size: Option<f32>,
     axis_max_size: Option<f32>,
+    axis_alignment: AlignContent,
     other_axis_alignment: AlignContent,
     available_grid_space: Size<AvailableSpace>,
     inner_node_size: Size<Option<f32>>,
@@ -352,7 +388,18 @@ pub(super) fn track_sizing_algorithm<Tree: LayoutPartialTree>(
 
     // 11.8. Stretch auto Tracks
     // This step expands tracks that have an auto max track sizing function by dividing any remaining positive, definite free space equally amongst them.
-    stretch_auto_tracks(axis_tracks, axis_min_size, axis_available_space_for_expansion);
+    // It only applies if the content-distribution property of the grid container is normal or stretch in this axis. Otherwise the remaining free
+    // space is distributed by track alignment.
+    if axis_alignment == AlignContent::Stretch {
+        stretch_auto_tracks(axis_tracks, axis_min_size, axis_available_space_for_expansion);
+    }
+
+    // Snap the sizes of the tracks to the fixed point grid so that the offsets of the tracks are exact sums
+    #[cfg(feature = "fixed_point")]
+    for track in axis_tracks.iter_mut() {
+        track.base_size = crate::tree::quantize(track.base_size);
+        track.growth_limit = crate::tree::quantize(track.growth_limit);
+    }
 }
 
 /// Whether it is a minimum or maximum size's space being distributed
@@ -461,16 +508,27 @@ fn resolve_item_baselines(
             row_items
         };
 
-        // Count how many items in *this row* are baseline aligned
-        // If a row has one or zero items participating in baseline alignment then baseline alignment is a no-op
-        // for those items and we skip further computations for that row
+        // Count how many items in *this row* are aligned to their first and last baselines. These items form separate
+        // baseline-sharing groups. If a group has one or zero items then baseline alignment is a no-op for those
+        // items and we skip further computations for that group
         let row_baseline_item_count = row_items.iter().filter(|item| item.align_self == AlignSelf::Baseline).count();
-        if row_baseline_item_count <= 1 {
+        let row_last_baseline_item_count =
+            row_items.iter().filter(|item| item.align_self == AlignSelf::LastBaseline).count();
+        if row_baseline_item_count <= 1 && row_last_baseline_item_count <= 1 {
             continue;
         }
+        let participates_in_baseline_alignment = |item: &GridItem| match item.align_self {
+            AlignSelf::Baseline => row_baseline_item_count > 1,
+            AlignSelf::LastBaseline => row_last_baseline_item_count > 1,
+            _ => false,
+        };
 
-        // Compute the baselines of all items in the row
+        // Compute the baselines of the items in the row which participate in baseline alignment
         for item in row_items.iter_mut() {
+            if !participates_in_baseline_alignment(item) {
+                continue;
+            }
+
             let measured_size_and_baselines = tree.perform_child_layout(
                 item.node,
                 Size::NONE,
@@ -480,19 +538,42 @@ fn resolve_item_baselines(
                 Line::FALSE,
             );
 
-            let baseline = measured_size_and_baselines.first_baselines.y;
             let height = measured_size_and_baselines.size.height;
-
-            item.baseline = Some(baseline.unwrap_or(height) + item.margin.top.resolve_or_zero(inner_node_size.width));
+            let margin_basis = tree.get_layout_config().percent_resolution_mode.basis(inner_node_size).height;
+
+            if item.align_self == AlignSelf::LastBaseline {
+                // Items aligned to their last baseline are shimmed from below, so temporarily store the distance
+                // from the item's last baseline to its bottom margin edge in the shim until the whole group is measured
+                let baseline = measured_size_and_baselines.last_baselines.y;
+                item.baseline_shim =
+                    height - baseline.unwrap_or(height) + item.margin.bottom.resolve_or_zero(margin_basis);
+            } else {
+                let baseline = measured_size_and_baselines.first_baselines.y;
+                item.baseline = Some(baseline.unwrap_or(height) + item.margin.top.resolve_or_zero(margin_basis));
+            }
         }
 
-        // Compute the max baseline of all items in the row
-        let row_max_baseline =
-            row_items.iter().map(|item| item.baseline.unwrap_or(0.0)).max_by(|a, b| a.total_cmp(b)).unwrap();
+        // Compute the max baseline of the items in each group
+        let row_max_baseline = row_items
+            .iter()
+            .filter(|item| item.align_self == AlignSelf::Baseline)
+            .map(|item| item.baseline.unwrap_or(0.0))
+            .fold(0.0, f32_max);
+        let row_max_last_baseline_descent = row_items
+            .iter()
+            .filter(|item| item.align_self == AlignSelf::LastBaseline)
+            .map(|item| item.baseline_shim)
+            .fold(0.0, f32_max);
 
         // Compute the baseline shim for each item in the row
         for item in row_items.iter_mut() {
-            item.baseline_shim = row_max_baseline - item.baseline.unwrap_or(0.0);
+            if !participates_in_baseline_alignment(item) {
+                continue;
+            }
+            item.baseline_shim = match item.align_self {
+                AlignSelf::LastBaseline => row_max_last_baseline_descent - item.baseline_shim,
+                _ => row_max_baseline - item.baseline.unwrap_or(0.0),
+            };
         }
     }
 }
@@ -532,7 +613,7 @@ fn resolve_intrinsic_track_sizes(
     // Also, minimum contribution <= min-content contribution <= max-content contribution.
 
     let axis_inner_node_size = inner_node_size.get(axis);
-    let flex_factor_sum = axis_tracks.iter().map(|track| track.flex_factor()).sum::<f32>();
+    let flex_factor_sum = axis_tracks.iter().map(|track| track.flex_factor()).precise_sum();
     let mut item_sizer =
         IntrisicSizeMeasurer { tree, other_axis_tracks, axis, inner_node_size, get_track_size_estimate };
 
@@ -647,6 +728,7 @@ fn resolve_intrinsic_track_sizes(
         // First increase the base size of tracks with an intrinsic min track sizing function
         let has_intrinsic_min_track_sizing_function =
             move |track: &GridTrack| track.min_track_sizing_function.definite_value(axis_inner_node_size).is_none();
+        let mut distribution_cache = SpanningItemDistributionCache::default();
         for item in batch.iter_mut().filter(|item| item.crosses_intrinsic_track(axis)) {
             // ...by distributing extra space as needed to accommodate these items’ minimum contributions.
             //
@@ -668,9 +750,11 @@ fn resolve_intrinsic_track_sizes(
                 }
                 _ => item_sizer.minimum_contribution(item, axis_tracks),
             };
-            let tracks = &mut axis_tracks[item.track_range_excluding_lines(axis)];
-            if space > 0.0 {
-                if item.overflow.get(axis).is_scroll_container() {
+            let track_range = item.track_range_excluding_lines(axis);
+            let is_scroll_container = item.overflow.get(axis).is_scroll_container();
+            if space > 0.0 && distribution_cache.needs_distribution(track_range.clone(), is_scroll_container, space) {
+                let tracks = &mut axis_tracks[track_range];
+                if is_scroll_container {
                     let fit_content_limit =
                         move |track: &GridTrack| track.fit_content_limited_growth_limit(axis_inner_node_size);
                     distribute_item_space_to_base_size(
@@ -704,11 +788,14 @@ fn resolve_intrinsic_track_sizes(
             use MinTrackSizingFunction::{MaxContent, MinContent};
             matches!(track.min_track_sizing_function, MinContent | MaxContent)
         };
+        let mut distribution_cache = SpanningItemDistributionCache::default();
         for item in batch.iter_mut() {
             let space = item_sizer.min_content_contribution(item);
-            let tracks = &mut axis_tracks[item.track_range_excluding_lines(axis)];
-            if space > 0.0 {
-                if item.overflow.get(axis).is_scroll_container() {
+            let track_range = item.track_range_excluding_lines(axis);
+            let is_scroll_container = item.overflow.get(axis).is_scroll_container();
+            if space > 0.0 && distribution_cache.needs_distribution(track_range.clone(), is_scroll_container, space) {
+                let tracks = &mut axis_tracks[track_range];
+                if is_scroll_container {
                     let fit_content_limit =
                         move |track: &GridTrack| track.fit_content_limited_growth_limit(axis_inner_node_size);
                     distribute_item_space_to_base_size(
@@ -765,12 +852,14 @@ fn resolve_intrinsic_track_sizes(
                 track.min_track_sizing_function == MinTrackSizingFunction::MaxContent
             }
 
+            let mut distribution_cache = SpanningItemDistributionCache::default();
             for item in batch.iter_mut() {
                 let axis_max_content_size = item_sizer.max_content_contribution(item);
                 let limit = item.spanned_track_limit(axis, axis_tracks, axis_inner_node_size);
                 let space = axis_max_content_size.maybe_min(limit);
-                let tracks = &mut axis_tracks[item.track_range_excluding_lines(axis)];
-                if space > 0.0 {
+                let track_range = item.track_range_excluding_lines(axis);
+                if space > 0.0 && distribution_cache.needs_distribution(track_range.clone(), false, space) {
+                    let tracks = &mut axis_tracks[track_range];
                     // If any of the tracks spanned by the item have a MaxContent min track sizing function then
                     // distribute space only to those tracks. Otherwise distribute space to tracks with an Auto min
                     // track sizing function.
@@ -813,11 +902,13 @@ fn resolve_intrinsic_track_sizes(
         // extra space as needed to account for these items' max-content contributions.
         let has_max_content_min_track_sizing_function =
             move |track: &GridTrack| matches!(track.min_track_sizing_function, MinTrackSizingFunction::MaxContent);
+        let mut distribution_cache = SpanningItemDistributionCache::default();
         for item in batch.iter_mut() {
             let axis_max_content_size = item_sizer.max_content_contribution(item);
             let space = axis_max_content_size;
-            let tracks = &mut axis_tracks[item.track_range_excluding_lines(axis)];
-            if space > 0.0 {
+            let track_range = item.track_range_excluding_lines(axis);
+            if space > 0.0 && distribution_cache.needs_distribution(track_range.clone(), false, space) {
+                let tracks = &mut axis_tracks[track_range];
                 distribute_item_space_to_base_size(
                     is_flex,
                     use_flex_factor_for_distribution,
@@ -845,11 +936,13 @@ fn resolve_intrinsic_track_sizes(
             // distributing extra space as needed to account for these items' min-content contributions.
             let has_intrinsic_max_track_sizing_function =
                 move |track: &GridTrack| track.max_track_sizing_function.definite_value(axis_inner_node_size).is_none();
+            let mut distribution_cache = SpanningItemDistributionCache::default();
             for item in batch.iter_mut() {
                 let axis_min_content_size = item_sizer.min_content_contribution(item);
                 let space = axis_min_content_size;
-                let tracks = &mut axis_tracks[item.track_range_excluding_lines(axis)];
-                if space > 0.0 {
+                let track_range = item.track_range_excluding_lines(axis);
+                if space > 0.0 && distribution_cache.needs_distribution(track_range.clone(), false, space) {
+                    let tracks = &mut axis_tracks[track_range];
                     distribute_item_space_to_growth_limit(
                         space,
                         tracks,
@@ -868,11 +961,13 @@ fn resolve_intrinsic_track_sizes(
                 track.max_track_sizing_function.is_max_content_alike()
                     || (track.max_track_sizing_function.uses_percentage() && axis_inner_node_size.is_none())
             };
+            let mut distribution_cache = SpanningItemDistributionCache::default();
             for item in batch.iter_mut() {
                 let axis_max_content_size = item_sizer.max_content_contribution(item);
                 let space = axis_max_content_size;
-                let tracks = &mut axis_tracks[item.track_range_excluding_lines(axis)];
-                if space > 0.0 {
+                let track_range = item.track_range_excluding_lines(axis);
+                if space > 0.0 && distribution_cache.needs_distribution(track_range.clone(), false, space) {
+                    let tracks = &mut axis_tracks[track_range];
                     distribute_item_space_to_growth_limit(
                         space,
                         tracks,
@@ -961,7 +1056,7 @@ fn distribute_item_space_to_base_size(
         let get_base_size = |track: &GridTrack| track.base_size;
 
         // 1. Find the space to distribute
-        let track_sizes: f32 = tracks.iter().map(|track| track.base_size).sum();
+        let track_sizes: f32 = tracks.iter().map(|track| track.base_size).precise_sum();
         let extra_space: f32 = f32_max(0.0, space - track_sizes);
 
         // 2. Distribute space up to limits:
@@ -1051,7 +1146,7 @@ fn distribute_item_space_to_growth_limit(
     let track_sizes: f32 = tracks
         .iter()
         .map(|track| if track.growth_limit == f32::INFINITY { track.base_size } else { track.growth_limit })
-        .sum();
+        .precise_sum();
     let extra_space: f32 = f32_max(0.0, space - track_sizes);
 
     // 2. Distribute space up to limits:
@@ -1106,7 +1201,7 @@ fn maximise_tracks(
     axis_inner_node_size: Option<f32>,
     axis_available_grid_space: AvailableSpace,
 ) {
-    let used_space: f32 = axis_tracks.iter().map(|track| track.base_size).sum();
+    let used_space: f32 = axis_tracks.iter().map(|track| track.base_size).precise_sum();
     let free_space = axis_available_grid_space.compute_free_space(used_space);
     if free_space == f32::INFINITY {
         axis_tracks.iter_mut().for_each(|track| track.base_size = track.growth_limit);
@@ -1148,7 +1243,7 @@ fn expand_flexible_tracks(
         //   The used flex fraction is the result of finding the size of an fr using all of the grid tracks and
         //   a space to fill of the available grid space.
         AvailableSpace::Definite(available_space) => {
-            let used_space: f32 = axis_tracks.iter().map(|track| track.base_size).sum();
+            let used_space: f32 = axis_tracks.iter().map(|track| track.base_size).precise_sum();
             let free_space = available_space - used_space;
             if free_space <= 0.0 {
                 0.0
@@ -1205,7 +1300,7 @@ fn expand_flexible_tracks(
                     }
                     _ => track.base_size,
                 })
-                .sum();
+                .precise_sum();
             let axis_min_size = axis_min_size.unwrap_or(0.0);
             let axis_max_size = axis_max_size.unwrap_or(f32::INFINITY);
             if hypothetical_grid_size < axis_min_size {
@@ -1298,7 +1393,7 @@ fn stretch_auto_tracks(
     let num_auto_tracks =
         axis_tracks.iter().filter(|track| track.max_track_sizing_function == MaxTrackSizingFunction::Auto).count();
     if num_auto_tracks > 0 {
-        let used_space: f32 = axis_tracks.iter().map(|track| track.base_size).sum();
+        let used_space: f32 = axis_tracks.iter().map(|track| track.base_size).precise_sum();
 
         // If the free space is indefinite, but the grid container has a definite min-width/height
         // use that size to calculate the free space for this step instead.
@@ -1342,7 +1437,7 @@ fn distribute_space_up_to_limits(
             .filter(|track| track_affected_property(track) + track.item_incurred_increase < track_limit(track))
             .filter(|track| track_is_affected(track))
             .map(&track_distribution_proportion)
-            .sum();
+            .precise_sum();
 
         if track_distribution_proportion_sum == 0.0 {
             break;
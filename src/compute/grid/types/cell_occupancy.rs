@@ -6,8 +6,8 @@ use crate::geometry::Line;
 use crate::util::sys::Vec;
 use core::cmp::{max, min};
 use core::fmt::Debug;
+use core::mem::size_of;
 use core::ops::Range;
-use grid::Grid;
 
 /// The occupancy state of a single grid cell
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
@@ -21,11 +21,48 @@ pub(crate) enum CellOccupancyState {
     AutoPlaced,
 }
 
+/// Statistics about the matrix used to track which cells of a grid container are occupied while its items are placed.
+/// Returned as part of a [`NodeInspection`](crate::NodeInspection).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GridOccupancyStats {
+    /// The number of rows in the implicit grid
+    pub row_count: u16,
+    /// The number of columns in the implicit grid
+    pub column_count: u16,
+    /// The number of grid cells that are occupied by an item
+    pub occupied_cell_count: u32,
+    /// The number of intervals (runs of consecutive occupied cells within a row) stored to represent the occupied cells
+    pub interval_count: u32,
+    /// Bytes allocated by the matrix, including allocated but unused capacity
+    pub allocated_bytes: usize,
+}
+
+/// A run of consecutive occupied cells within a row which are all in the same state
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OccupiedInterval {
+    /// The index of the first column of the interval
+    start: u16,
+    /// The index of the column after the last column of the interval
+    end: u16,
+    /// The occupancy state of the cells in the interval. Never `Unoccupied`.
+    state: CellOccupancyState,
+}
+
 /// A dynamically sized matrix (2d grid) which tracks the occupancy of each grid cell during auto-placement
 /// It also keeps tabs on how many tracks there are and which tracks are implicit and which are explicit.
+///
+/// Each row is stored as a sorted list of intervals of occupied cells, so the memory used depends on the number of items
+/// rather than on the number of cells (which can be huge for sparse grids with items placed at large line numbers).
+/// The first unoccupied cell of each row and column is cached so that searches for free space (which the "dense"
+/// packing algorithm starts from the beginning of the grid for every item) can skip straight past the filled start of
+/// each track.
 pub(crate) struct CellOccupancyMatrix {
-    /// The grid of occupancy states
-    inner: Grid<CellOccupancyState>,
+    /// The intervals of occupied cells in each row, sorted by column and non-overlapping
+    occupied: Vec<Vec<OccupiedInterval>>,
+    /// The index of the first unoccupied cell in each row
+    first_unoccupied_in_row: Vec<u16>,
+    /// The index of the first unoccupied cell in each column
+    first_unoccupied_in_column: Vec<u16>,
     /// The counts of implicit and explicit columns
     columns: TrackCounts,
     /// The counts of implicit and explicit rows
@@ -47,9 +84,9 @@ impl Debug for CellOccupancyMatrix {
         )?;
         writeln!(f, "State:")?;
 
-        for row_idx in 0..self.inner.rows() {
-            for cell in self.inner.iter_row(row_idx) {
-                let letter = match *cell {
+        for row_idx in 0..self.rows.len() {
+            for col_idx in 0..self.columns.len() {
+                let letter = match self.get(row_idx, col_idx) {
                     CellOccupancyState::Unoccupied => '_',
                     CellOccupancyState::DefinitelyPlaced => 'D',
                     CellOccupancyState::AutoPlaced => 'A',
@@ -63,11 +100,24 @@ impl Debug for CellOccupancyMatrix {
     }
 }
 
+/// Clamp a range of track indexes (which may lie partially or entirely outside of the matrix) to the tracks that exist
+fn clamp_track_range(range: Range<i16>, track_count: usize) -> Range<usize> {
+    let start = min(max(range.start, 0) as usize, track_count);
+    let end = min(max(range.end, 0) as usize, track_count);
+    start..max(start, end)
+}
+
 impl CellOccupancyMatrix {
     /// Create a CellOccupancyMatrix given a set of provisional track counts. The grid can expand as needed to fit more tracks,
     /// the provisional track counts represent a best effort attempt to avoid the extra allocations this requires.
     pub fn with_track_counts(columns: TrackCounts, rows: TrackCounts) -> Self {
-        Self { inner: Grid::new(rows.len(), columns.len()), rows, columns }
+        Self {
+            occupied: core::iter::repeat_with(Vec::new).take(rows.len()).collect(),
+            first_unoccupied_in_row: core::iter::repeat(0).take(rows.len()).collect(),
+            first_unoccupied_in_column: core::iter::repeat(0).take(columns.len()).collect(),
+            rows,
+            columns,
+        }
     }
 
     /// Determines whether the specified area fits within the tracks currently represented by the matrix
@@ -90,50 +140,37 @@ impl CellOccupancyMatrix {
     /// Expands the grid (potentially in all 4 directions) in order to ensure that the specified range fits within the allocated space
     fn expand_to_fit_range(&mut self, row_range: Range<i16>, col_range: Range<i16>) {
         // Calculate number of rows and columns missing to accommodate ranges (if any)
-        let req_negative_rows = min(row_range.start, 0);
-        let req_positive_rows = max(row_range.end - self.rows.len() as i16, 0);
-        let req_negative_cols = min(col_range.start, 0);
-        let req_positive_cols = max(col_range.end - self.columns.len() as i16, 0);
-
-        let old_row_count = self.rows.len();
-        let old_col_count = self.columns.len();
-        let new_row_count = old_row_count + (req_negative_rows + req_positive_rows) as usize;
-        let new_col_count = old_col_count + (req_negative_cols + req_positive_cols) as usize;
+        let req_negative_rows = max(-row_range.start, 0) as u16;
+        let req_positive_rows = max(row_range.end - self.rows.len() as i16, 0) as u16;
+        let req_negative_cols = max(-col_range.start, 0) as u16;
+        let req_positive_cols = max(col_range.end - self.columns.len() as i16, 0) as u16;
 
-        let mut data = Vec::with_capacity(new_row_count * new_col_count);
-
-        // Push new negative rows
-        for _ in 0..(req_negative_rows as usize * new_col_count) {
-            data.push(CellOccupancyState::Unoccupied);
-        }
-
-        // Push existing rows
-        for row in 0..old_row_count {
-            // Push new negative columns
-            for _ in 0..req_negative_cols {
-                data.push(CellOccupancyState::Unoccupied);
-            }
-            // Push existing columns
-            for col in 0..old_col_count {
-                data.push(*self.inner.get(row, col).unwrap());
-            }
-            // Push new positive columns
-            for _ in 0..req_positive_cols {
-                data.push(CellOccupancyState::Unoccupied);
+        // The new tracks are unoccupied. So the first unoccupied cell of each existing track is unchanged, unless tracks
+        // were added to the start of the other axis in which case it is the first (new) cell of the track.
+        if req_negative_cols > 0 {
+            for interval in self.occupied.iter_mut().flatten() {
+                interval.start += req_negative_cols;
+                interval.end += req_negative_cols;
             }
+            self.first_unoccupied_in_row.iter_mut().for_each(|first_unoccupied| *first_unoccupied = 0);
         }
-
-        // Push new negative rows
-        for _ in 0..(req_positive_rows as usize * new_col_count) {
-            data.push(CellOccupancyState::Unoccupied);
+        if req_negative_rows > 0 {
+            self.first_unoccupied_in_column.iter_mut().for_each(|first_unoccupied| *first_unoccupied = 0);
         }
 
-        // Update self with new data
-        self.inner = Grid::from_vec(data, new_col_count);
-        self.rows.negative_implicit += req_negative_rows as u16;
-        self.rows.positive_implicit += req_positive_rows as u16;
-        self.columns.negative_implicit += req_negative_cols as u16;
-        self.columns.positive_implicit += req_positive_cols as u16;
+        let new_rows = |count: u16| core::iter::repeat_with(Vec::new).take(count as usize);
+        self.occupied.splice(0..0, new_rows(req_negative_rows));
+        self.occupied.extend(new_rows(req_positive_rows));
+        let new_tracks = |count: u16| core::iter::repeat(0).take(count as usize);
+        self.first_unoccupied_in_row.splice(0..0, new_tracks(req_negative_rows));
+        self.first_unoccupied_in_row.extend(new_tracks(req_positive_rows));
+        self.first_unoccupied_in_column.splice(0..0, new_tracks(req_negative_cols));
+        self.first_unoccupied_in_column.extend(new_tracks(req_positive_cols));
+
+        self.rows.negative_implicit += req_negative_rows;
+        self.rows.positive_implicit += req_positive_rows;
+        self.columns.negative_implicit += req_negative_cols;
+        self.columns.positive_implicit += req_positive_cols;
     }
 
     /// Mark an area of the matrix as occupied, expanding the allocated space as necessary to accommodate the passed area.
@@ -161,10 +198,31 @@ impl CellOccupancyMatrix {
             row_range = self.rows.oz_line_range_to_track_range(row_span);
         }
 
-        for x in row_range {
-            for y in col_range.clone() {
-                *self.inner.get_mut(x as usize, y as usize).unwrap() = value;
-            }
+        let row_range = row_range.start as u16..row_range.end as u16;
+        let col_range = col_range.start as u16..col_range.end as u16;
+        for row in row_range.clone() {
+            self.set_row_cells(row as usize, col_range.clone(), value);
+        }
+
+        // Keep the cached first unoccupied cell of each affected track up to date. Cells before the cached position are
+        // all occupied, so it only moves if the newly marked area starts at or before it.
+        for row in row_range.clone() {
+            let first_unoccupied = self.first_unoccupied_in_row[row as usize];
+            self.first_unoccupied_in_row[row as usize] = match value {
+                CellOccupancyState::Unoccupied => min(first_unoccupied, col_range.start),
+                _ if col_range.start <= first_unoccupied => self.next_unoccupied_in_row(row as usize, first_unoccupied),
+                _ => first_unoccupied,
+            };
+        }
+        for col in col_range {
+            let first_unoccupied = self.first_unoccupied_in_column[col as usize];
+            self.first_unoccupied_in_column[col as usize] = match value {
+                CellOccupancyState::Unoccupied => min(first_unoccupied, row_range.start),
+                _ if row_range.start <= first_unoccupied => {
+                    self.next_unoccupied_in_column(col as usize, first_unoccupied)
+                }
+                _ => first_unoccupied,
+            };
         }
     }
 
@@ -195,26 +253,71 @@ impl CellOccupancyMatrix {
         };
 
         // Search for occupied cells in the specified area. Out of bounds cells are considered unoccupied.
-        for x in row_range {
-            for y in col_range.clone() {
-                match self.inner.get(x as usize, y as usize) {
-                    None | Some(CellOccupancyState::Unoccupied) => continue,
-                    _ => return false,
-                }
-            }
+        let col_range = clamp_track_range(col_range, self.columns.len());
+        clamp_track_range(row_range, self.rows.len())
+            .all(|row| self.first_occupied_interval(row, col_range.clone()).is_none())
+    }
+
+    /// Finds the first grid line (at or after `start`) in the primary axis at which an item spanning `span` tracks in the
+    /// primary axis and `secondary_span` in the secondary axis would not overlap any occupied grid cells. Cells outside of
+    /// the matrix are considered unoccupied, so the returned line may lie beyond the end of the grid.
+    pub fn first_unoccupied_position(
+        &self,
+        primary_axis: AbsoluteAxis,
+        span: u16,
+        secondary_span: Line<OriginZeroLine>,
+        start: OriginZeroLine,
+    ) -> OriginZeroLine {
+        let primary_track_counts = self.track_counts(primary_axis);
+        let secondary_track_counts = self.track_counts(primary_axis.other_axis());
+        let secondary_range = clamp_track_range(
+            secondary_track_counts.oz_line_range_to_track_range(secondary_span),
+            secondary_track_counts.len(),
+        );
+
+        // Every cell before the first unoccupied cell of a track is occupied, so the item can't start before that cell
+        // in any of the tracks that it spans
+        let first_unoccupied = match primary_axis {
+            AbsoluteAxis::Horizontal => &self.first_unoccupied_in_row,
+            AbsoluteAxis::Vertical => &self.first_unoccupied_in_column,
+        };
+        let mut position = max(primary_track_counts.oz_line_to_next_track(start), 0) as usize;
+        for track in secondary_range.clone() {
+            position = max(position, first_unoccupied[track] as usize);
         }
 
-        true
+        // An occupied cell within the candidate area also blocks every later start position up to and including
+        // that cell (or the end of its interval), so the search can jump straight past it
+        loop {
+            let candidate = position..position + span as usize;
+            let next_position = match primary_axis {
+                AbsoluteAxis::Horizontal => secondary_range
+                    .clone()
+                    .filter_map(|row| self.first_occupied_interval(row, candidate.clone()))
+                    .map(|interval| interval.end as usize)
+                    .max(),
+                AbsoluteAxis::Vertical => candidate
+                    .clone()
+                    .take_while(|&row| row < self.rows.len())
+                    .filter(|&row| self.first_occupied_interval(row, secondary_range.clone()).is_some())
+                    .last()
+                    .map(|row| row + 1),
+            };
+            match next_position {
+                Some(next_position) => position = next_position,
+                None => return primary_track_counts.track_to_prev_oz_line(position as u16),
+            }
+        }
     }
 
     /// Determines whether the specified row contains any items
     pub fn row_is_occupied(&self, row_index: usize) -> bool {
-        self.inner.iter_row(row_index).any(|cell| !matches!(cell, CellOccupancyState::Unoccupied))
+        !self.occupied[row_index].is_empty()
     }
 
     /// Determines whether the specified column contains any items
     pub fn column_is_occupied(&self, column_index: usize) -> bool {
-        self.inner.iter_col(column_index).any(|cell| !matches!(cell, CellOccupancyState::Unoccupied))
+        (0..self.rows.len()).any(|row| self.first_occupied_interval(row, column_index..column_index + 1).is_some())
     }
 
     /// Returns the track counts of this CellOccunpancyMatrix in the relevant axis
@@ -234,18 +337,240 @@ impl CellOccupancyMatrix {
         start_at: OriginZeroLine,
         kind: CellOccupancyState,
     ) -> Option<OriginZeroLine> {
-        let track_counts = self.track_counts(track_type.other_axis());
-        let track_computed_index = track_counts.oz_line_to_next_track(start_at);
+        let track_computed_index = self.track_counts(track_type.other_axis()).oz_line_to_next_track(start_at) as usize;
 
         let maybe_index = match track_type {
+            AbsoluteAxis::Horizontal if kind != CellOccupancyState::Unoccupied => self
+                .occupied
+                .get(track_computed_index)
+                .and_then(|intervals| intervals.iter().rev().find(|interval| interval.state == kind))
+                .map(|interval| interval.end as usize - 1),
             AbsoluteAxis::Horizontal => {
-                self.inner.iter_row(track_computed_index as usize).rposition(|item| *item == kind)
+                (0..self.columns.len()).rev().find(|&col| self.get(track_computed_index, col) == kind)
             }
             AbsoluteAxis::Vertical => {
-                self.inner.iter_col(track_computed_index as usize).rposition(|item| *item == kind)
+                (0..self.rows.len()).rev().find(|&row| self.get(row, track_computed_index) == kind)
             }
         };
 
-        maybe_index.map(|idx| track_counts.track_to_prev_oz_line(idx as u16))
+        maybe_index.map(|idx| self.track_counts(track_type).track_to_prev_oz_line(idx as u16))
+    }
+
+    /// Returns statistics about the size of the grid and the memory used to track its occupancy
+    pub fn stats(&self) -> GridOccupancyStats {
+        let intervals = || self.occupied.iter().flatten();
+        GridOccupancyStats {
+            row_count: self.rows.len() as u16,
+            column_count: self.columns.len() as u16,
+            occupied_cell_count: intervals().map(|interval| (interval.end - interval.start) as u32).sum(),
+            interval_count: intervals().count() as u32,
+            allocated_bytes: self.occupied.capacity() * size_of::<Vec<OccupiedInterval>>()
+                + self.occupied.iter().map(|row| row.capacity() * size_of::<OccupiedInterval>()).sum::<usize>()
+                + (self.first_unoccupied_in_row.capacity() + self.first_unoccupied_in_column.capacity())
+                    * size_of::<u16>(),
+        }
+    }
+
+    /// Get the occupancy state of a single cell. Out of bounds cells are considered unoccupied.
+    fn get(&self, row: usize, col: usize) -> CellOccupancyState {
+        if row >= self.rows.len() {
+            return CellOccupancyState::Unoccupied;
+        }
+        match self.first_occupied_interval(row, col..col + 1) {
+            Some(interval) => interval.state,
+            None => CellOccupancyState::Unoccupied,
+        }
+    }
+
+    /// Set the occupancy state of a range of cells within a row
+    fn set_row_cells(&mut self, row: usize, col_range: Range<u16>, value: CellOccupancyState) {
+        let intervals = &mut self.occupied[row];
+
+        // Replace the intervals which overlap the range with the parts of them which lie outside of the range,
+        // and an interval covering the range itself (unless the range is being marked as unoccupied)
+        let first = intervals.partition_point(|interval| interval.end <= col_range.start);
+        let last = intervals.partition_point(|interval| interval.start < col_range.end);
+        let before = intervals[first..last]
+            .first()
+            .filter(|interval| interval.start < col_range.start)
+            .map(|interval| OccupiedInterval { end: col_range.start, ..*interval });
+        let after = intervals[first..last]
+            .last()
+            .filter(|interval| interval.end > col_range.end)
+            .map(|interval| OccupiedInterval { start: col_range.end, ..*interval });
+        let new = match value {
+            CellOccupancyState::Unoccupied => None,
+            state => Some(OccupiedInterval { start: col_range.start, end: col_range.end, state }),
+        };
+        intervals.splice(first..last, [before, new, after].into_iter().flatten());
+
+        // Merge the inserted intervals with any adjoining intervals in the same state
+        let mut idx = first.saturating_sub(1);
+        let mut end = min(first + 4, intervals.len());
+        while idx + 1 < end {
+            if intervals[idx].end == intervals[idx + 1].start && intervals[idx].state == intervals[idx + 1].state {
+                intervals[idx].end = intervals[idx + 1].end;
+                intervals.remove(idx + 1);
+                end -= 1;
+            } else {
+                idx += 1;
+            }
+        }
+    }
+
+    /// Returns the first interval of occupied cells in a row which overlaps the specified range of columns (if any)
+    fn first_occupied_interval(&self, row: usize, col_range: Range<usize>) -> Option<OccupiedInterval> {
+        if col_range.is_empty() {
+            return None;
+        }
+        let intervals = &self.occupied[row];
+        let idx = intervals.partition_point(|interval| (interval.end as usize) <= col_range.start);
+        intervals.get(idx).copied().filter(|interval| (interval.start as usize) < col_range.end)
+    }
+
+    /// Returns the index of the first unoccupied cell of a row at or after the specified column. This will be
+    /// the number of columns if all of the remaining cells in the row are occupied.
+    fn next_unoccupied_in_row(&self, row: usize, start: u16) -> u16 {
+        let intervals = &self.occupied[row];
+        let mut col = start;
+        let mut idx = intervals.partition_point(|interval| interval.end <= col);
+        while let Some(interval) = intervals.get(idx).filter(|interval| interval.start <= col) {
+            col = interval.end;
+            idx += 1;
+        }
+        col
+    }
+
+    /// Returns the index of the first unoccupied cell of a column at or after the specified row. This will be
+    /// the number of rows if all of the remaining cells in the column are occupied.
+    fn next_unoccupied_in_column(&self, col: usize, start: u16) -> u16 {
+        (start as usize..self.rows.len())
+            .find(|&row| self.get(row, col) == CellOccupancyState::Unoccupied)
+            .unwrap_or(self.rows.len()) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CellOccupancyMatrix, CellOccupancyState};
+    use crate::compute::grid::types::TrackCounts;
+    use crate::compute::grid::OriginZeroLine;
+    use crate::geometry::{AbsoluteAxis, Line};
+
+    fn oz_line(start: i16, end: i16) -> Line<OriginZeroLine> {
+        Line { start: OriginZeroLine(start), end: OriginZeroLine(end) }
+    }
+
+    #[test]
+    fn first_unoccupied_position_skips_occupied_cells_across_words() {
+        let columns = TrackCounts { negative_implicit: 0, explicit: 100, positive_implicit: 0 };
+        let rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+        let mut matrix = CellOccupancyMatrix::with_track_counts(columns, rows);
+        let horizontal = AbsoluteAxis::Horizontal;
+        matrix.mark_area_as(horizontal, oz_line(0, 70), oz_line(0, 1), CellOccupancyState::AutoPlaced);
+        matrix.mark_area_as(horizontal, oz_line(72, 73), oz_line(1, 2), CellOccupancyState::DefinitelyPlaced);
+
+        // The filled start of the first row is skipped, and a span of 3 doesn't fit before column 72 in the second row
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 1, oz_line(0, 1), OriginZeroLine(0)),
+            OriginZeroLine(70)
+        );
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 3, oz_line(0, 2), OriginZeroLine(0)),
+            OriginZeroLine(73)
+        );
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 2, oz_line(1, 2), OriginZeroLine(0)),
+            OriginZeroLine(0)
+        );
+
+        // Searching in the vertical axis finds the first row that is free within the given columns
+        let vertical = AbsoluteAxis::Vertical;
+        assert_eq!(
+            matrix.first_unoccupied_position(vertical, 1, oz_line(68, 71), OriginZeroLine(0)),
+            OriginZeroLine(1)
+        );
+        assert_eq!(
+            matrix.first_unoccupied_position(vertical, 1, oz_line(69, 73), OriginZeroLine(0)),
+            OriginZeroLine(2)
+        );
+
+        assert_eq!(
+            matrix.last_of_type(horizontal, OriginZeroLine(0), CellOccupancyState::AutoPlaced),
+            Some(OriginZeroLine(69))
+        );
+        assert!(matrix.track_area_is_unoccupied(horizontal, 70..72, 0..2));
+        assert!(!matrix.track_area_is_unoccupied(horizontal, 63..65, 0..1));
+    }
+
+    #[test]
+    fn expanding_the_matrix_preserves_occupied_cells() {
+        let columns = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+        let rows = TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 0 };
+        let mut matrix = CellOccupancyMatrix::with_track_counts(columns, rows);
+        let horizontal = AbsoluteAxis::Horizontal;
+        matrix.mark_area_as(horizontal, oz_line(0, 2), oz_line(0, 1), CellOccupancyState::AutoPlaced);
+        matrix.mark_area_as(horizontal, oz_line(-1, 0), oz_line(2, 3), CellOccupancyState::DefinitelyPlaced);
+
+        assert_eq!(
+            *matrix.track_counts(horizontal),
+            TrackCounts { negative_implicit: 1, explicit: 2, positive_implicit: 0 }
+        );
+        assert_eq!(
+            *matrix.track_counts(AbsoluteAxis::Vertical),
+            TrackCounts { negative_implicit: 0, explicit: 2, positive_implicit: 1 }
+        );
+        assert!(!matrix.line_area_is_unoccupied(horizontal, oz_line(0, 2), oz_line(0, 1)));
+        assert!(matrix.line_area_is_unoccupied(horizontal, oz_line(-1, 0), oz_line(0, 2)));
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 1, oz_line(0, 1), OriginZeroLine(-1)),
+            OriginZeroLine(-1)
+        );
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 1, oz_line(2, 3), OriginZeroLine(-1)),
+            OriginZeroLine(0)
+        );
+    }
+
+    #[test]
+    fn intervals_are_split_and_merged() {
+        let columns = TrackCounts { negative_implicit: 0, explicit: 10, positive_implicit: 0 };
+        let rows = TrackCounts { negative_implicit: 0, explicit: 1, positive_implicit: 0 };
+        let mut matrix = CellOccupancyMatrix::with_track_counts(columns, rows);
+        let horizontal = AbsoluteAxis::Horizontal;
+        matrix.mark_area_as(horizontal, oz_line(0, 3), oz_line(0, 1), CellOccupancyState::AutoPlaced);
+        matrix.mark_area_as(horizontal, oz_line(3, 6), oz_line(0, 1), CellOccupancyState::AutoPlaced);
+        assert_eq!(matrix.stats().interval_count, 1);
+
+        matrix.mark_area_as(horizontal, oz_line(2, 4), oz_line(0, 1), CellOccupancyState::DefinitelyPlaced);
+        assert_eq!(matrix.stats().interval_count, 3);
+        assert_eq!(matrix.stats().occupied_cell_count, 6);
+
+        matrix.mark_area_as(horizontal, oz_line(1, 5), oz_line(0, 1), CellOccupancyState::Unoccupied);
+        assert_eq!(matrix.stats().interval_count, 2);
+        assert_eq!(matrix.stats().occupied_cell_count, 2);
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 4, oz_line(0, 1), OriginZeroLine(0)),
+            OriginZeroLine(1)
+        );
+        assert_eq!(
+            matrix.first_unoccupied_position(horizontal, 5, oz_line(0, 1), OriginZeroLine(0)),
+            OriginZeroLine(6)
+        );
+    }
+
+    #[test]
+    fn sparse_grid_does_not_allocate_per_cell() {
+        let columns = TrackCounts { negative_implicit: 0, explicit: 1, positive_implicit: 0 };
+        let rows = TrackCounts { negative_implicit: 0, explicit: 1, positive_implicit: 0 };
+        let mut matrix = CellOccupancyMatrix::with_track_counts(columns, rows);
+        let horizontal = AbsoluteAxis::Horizontal;
+        matrix.mark_area_as(horizontal, oz_line(0, 1), oz_line(0, 1), CellOccupancyState::DefinitelyPlaced);
+        matrix.mark_area_as(horizontal, oz_line(4999, 5000), oz_line(4999, 5000), CellOccupancyState::DefinitelyPlaced);
+
+        let stats = matrix.stats();
+        assert_eq!((stats.row_count, stats.column_count), (5000, 5000));
+        assert_eq!(stats.occupied_cell_count, 2);
+        assert!(stats.allocated_bytes < 200 * 1024, "{}", stats.allocated_bytes);
     }
 }
@@ -7,7 +7,8 @@ use crate::style::{
     AlignItems, AlignSelf, AvailableSpace, Dimension, LengthPercentageAuto, MaxTrackSizingFunction,
     MinTrackSizingFunction, Overflow,
 };
-use crate::tree::{LayoutPartialTree, LayoutPartialTreeExt, NodeId, SizingMode};
+use crate::tree::{LayoutPartialTree, LayoutPartialTreeExt, NodeId, PercentResolutionMode, SizingMode};
+use crate::util::precision::PreciseSum;
 use crate::util::{MaybeMath, MaybeResolve, ResolveOrZero};
 use crate::{BoxSizing, GridItemStyle, LengthPercentage};
 use core::ops::Range;
@@ -55,8 +56,9 @@ pub(in super::super) struct GridItem {
     pub justify_self: AlignSelf,
     /// The items first baseline (horizontal)
     pub baseline: Option<f32>,
-    /// Shim for baseline alignment that acts like an extra top margin
-    /// TODO: Support last baseline and vertical text baselines
+    /// Shim for baseline alignment that acts like an extra top margin (or an extra bottom margin for items aligned
+    /// to their last baseline)
+    /// TODO: Support vertical text baselines
     pub baseline_shim: f32,
 
     /// The item's definite row-start and row-end (same as `row` field, except in a different coordinate system)
@@ -89,6 +91,8 @@ pub(in super::super) struct GridItem {
     pub y_position: f32,
     /// Final height. Used to compute baseline alignment for the container.
     pub height: f32,
+    /// Final position of the item's last baseline. Used to compute baseline alignment for the container.
+    pub last_baseline: f32,
 }
 
 impl GridItem {
@@ -132,6 +136,7 @@ impl GridItem {
             minimum_contribution_cache: Size::NONE,
             y_position: 0.0,
             height: 0.0,
+            last_baseline: 0.0,
         }
     }
 
@@ -201,7 +206,7 @@ impl GridItem {
             let limit: f32 = spanned_tracks
                 .iter()
                 .map(|track| track.max_track_sizing_function.definite_limit(axis_parent_size).unwrap())
-                .sum();
+                .precise_sum();
             Some(limit)
         } else {
             None
@@ -224,7 +229,7 @@ impl GridItem {
             let limit: f32 = spanned_tracks
                 .iter()
                 .map(|track| track.max_track_sizing_function.definite_value(axis_parent_size).unwrap())
-                .sum();
+                .precise_sum();
             Some(limit)
         } else {
             None
@@ -238,8 +243,9 @@ impl GridItem {
         &self,
         inner_node_size: Size<Option<f32>>,
         grid_area_size: Size<Option<f32>>,
+        percent_resolution_mode: PercentResolutionMode,
     ) -> Size<Option<f32>> {
-        let margins = self.margins_axis_sums_with_baseline_shims(inner_node_size.width);
+        let margins = self.margins_axis_sums_with_baseline_shims(percent_resolution_mode.basis(inner_node_size).height);
 
         let aspect_ratio = self.aspect_ratio;
         let padding = self.padding.resolve_or_zero(grid_area_size);
@@ -352,13 +358,14 @@ impl GridItem {
 
     /// Compute the item's resolved margins for size contributions. Horizontal percentage margins always resolve
     /// to zero if the container size is indefinite as otherwise this would introduce a cyclic dependency.
+    /// Vertical percentage margins resolve against `vertical_percent_basis`.
     #[inline(always)]
-    pub fn margins_axis_sums_with_baseline_shims(&self, inner_node_width: Option<f32>) -> Size<f32> {
+    pub fn margins_axis_sums_with_baseline_shims(&self, vertical_percent_basis: Option<f32>) -> Size<f32> {
         Rect {
             left: self.margin.left.resolve_or_zero(Some(0.0)),
             right: self.margin.right.resolve_or_zero(Some(0.0)),
-            top: self.margin.top.resolve_or_zero(inner_node_width) + self.baseline_shim,
-            bottom: self.margin.bottom.resolve_or_zero(inner_node_width),
+            top: self.margin.top.resolve_or_zero(vertical_percent_basis) + self.baseline_shim,
+            bottom: self.margin.bottom.resolve_or_zero(vertical_percent_basis),
         }
         .sum_axes()
     }
@@ -371,7 +378,8 @@ impl GridItem {
         available_space: Size<Option<f32>>,
         inner_node_size: Size<Option<f32>>,
     ) -> f32 {
-        let known_dimensions = self.known_dimensions(inner_node_size, available_space);
+        let known_dimensions =
+            self.known_dimensions(inner_node_size, available_space, tree.get_layout_config().percent_resolution_mode);
         tree.measure_child_size(
             self.node,
             known_dimensions,
@@ -410,7 +418,8 @@ impl GridItem {
         available_space: Size<Option<f32>>,
         inner_node_size: Size<Option<f32>>,
     ) -> f32 {
-        let known_dimensions = self.known_dimensions(inner_node_size, available_space);
+        let known_dimensions =
+            self.known_dimensions(inner_node_size, available_space, tree.get_layout_config().percent_resolution_mode);
         tree.measure_child_size(
             self.node,
             known_dimensions,
@@ -483,23 +492,23 @@ impl GridItem {
                 // in a given axis is the content-based minimum size if all of the following are true:
                 let item_axis_tracks = &axis_tracks[self.track_range_excluding_lines(axis)];
 
-                // it is not a scroll container
-                // TODO: support overflow property
+                // it is not a scroll container (in this axis, which is handled above)
 
                 // it spans at least one track in that axis whose min track sizing function is auto
-                let spans_auto_min_track = axis_tracks
+                let spans_auto_min_track = item_axis_tracks
                     .iter()
                     // TODO: should this be 'behaves as auto' rather than just literal auto?
                     .any(|track| track.min_track_sizing_function == MinTrackSizingFunction::Auto);
 
                 // if it spans more than one track in that axis, none of those tracks are flexible
                 let only_span_one_track = item_axis_tracks.len() == 1;
-                let spans_a_flexible_track = axis_tracks
+                let spans_a_flexible_track = item_axis_tracks
                     .iter()
                     .any(|track| matches!(track.max_track_sizing_function, MaxTrackSizingFunction::Fraction(_)));
 
-                let use_content_based_minimum =
-                    spans_auto_min_track && (only_span_one_track || !spans_a_flexible_track);
+                let use_content_based_minimum = spans_auto_min_track
+                    && (only_span_one_track || !spans_a_flexible_track)
+                    && tree.get_layout_config().use_automatic_minimum_size;
 
                 // Otherwise, the automatic minimum size is zero, as usual.
                 if use_content_based_minimum {
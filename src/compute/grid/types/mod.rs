@@ -6,6 +6,7 @@ mod grid_track;
 mod grid_track_counts;
 
 // Publish only locally in the grid module
+pub use cell_occupancy::GridOccupancyStats;
 pub(super) use cell_occupancy::{CellOccupancyMatrix, CellOccupancyState};
 pub(crate) use coordinates::{GridCoordinate, GridLine, OriginZeroLine};
 pub(super) use grid_item::GridItem;
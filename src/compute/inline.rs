@@ -0,0 +1,496 @@
+//! Computes the layout of inline containers, whose children are placed on lines (along with content which is not
+//! represented in the tree, such as text) by a formatter provided by the embedder. See [`LayoutInlineContainer`].
+use crate::geometry::{Line, Point, Rect, Size};
+use crate::style::{AvailableSpace, CoreStyle, Overflow, Position};
+use crate::tree::LayoutPartialTreeExt;
+use crate::tree::{Layout, LayoutInput, LayoutOutput, NodeId, RequestedAxis, RunMode, SizingMode};
+use crate::util::debug::debug_log;
+use crate::util::sys::{f32_max, Vec};
+use crate::util::{MaybeMath, MaybeResolve, ResolveOrZero};
+use crate::{BoxGenerationMode, BoxSizing, LayoutInlineContainer};
+
+#[cfg(feature = "content_size")]
+use super::common::content_size::compute_content_size_contribution;
+use super::leaf::compute_leaf_layout;
+
+/// An in-flow child of an inline container, which is placed on a line as an atomic inline-level box (like an
+/// `inline-block` element in CSS)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InlineAtom {
+    /// The id of the child
+    pub node_id: NodeId,
+    /// The size of the child's margin box
+    pub size: Size<f32>,
+}
+
+/// The input to an inline formatter. See [`LayoutInlineContainer::format_inline_content`].
+#[derive(Debug, Clone, Copy)]
+pub struct InlineFormatInput<'a> {
+    /// The width available for the lines. This is definite if the width of the container's content box is known, and
+    /// otherwise the min-content or max-content constraint the container is being measured under.
+    pub available_width: AvailableSpace,
+    /// The in-flow children of the container, in order
+    pub atoms: &'a [InlineAtom],
+}
+
+/// A line of an inline container, as returned by an inline formatter
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineBox {
+    /// The position of the top-left corner of the line, relative to the container's content box
+    pub location: Point<f32>,
+    /// The size of the line
+    pub size: Size<f32>,
+    /// The distance from the top of the line to its baseline
+    pub baseline: f32,
+    /// The atoms placed on the line
+    pub items: Vec<LineBoxItem>,
+}
+
+/// The position of an [`InlineAtom`] on a [`LineBox`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineBoxItem {
+    /// The index of the atom in [`InlineFormatInput::atoms`]
+    pub atom: usize,
+    /// The position of the top-left corner of the atom's margin box, relative to the top-left corner of the line
+    pub offset: Point<f32>,
+}
+
+/// Places the atoms of an inline container from left to right on lines of the available width, starting a new line
+/// before each atom which would overflow it, and aligns each atom to the bottom of its line. This is the default
+/// inline formatter, for containers which have no content other than their children.
+pub fn break_atoms_into_lines(input: &InlineFormatInput) -> Vec<LineBox> {
+    let mut lines: Vec<LineBox> = Vec::new();
+    let mut line = LineBox { location: Point::ZERO, size: Size::ZERO, baseline: 0.0, items: Vec::new() };
+    for (index, atom) in input.atoms.iter().enumerate() {
+        let fits_on_line = match input.available_width {
+            AvailableSpace::Definite(width) => line.size.width + atom.size.width <= width,
+            AvailableSpace::MinContent => false,
+            AvailableSpace::MaxContent => true,
+        };
+        if !fits_on_line && !line.items.is_empty() {
+            let location = Point { x: 0.0, y: line.location.y + line.size.height };
+            let next_line = LineBox { location, size: Size::ZERO, baseline: 0.0, items: Vec::new() };
+            lines.push(core::mem::replace(&mut line, next_line));
+        }
+        line.items.push(LineBoxItem { atom: index, offset: Point { x: line.size.width, y: 0.0 } });
+        line.size.width += atom.size.width;
+        line.size.height = f32_max(line.size.height, atom.size.height);
+    }
+    if !line.items.is_empty() {
+        lines.push(line);
+    }
+
+    for line in lines.iter_mut() {
+        line.baseline = line.size.height;
+        for item in line.items.iter_mut() {
+            item.offset.y = line.size.height - input.atoms[item.atom].size.height;
+        }
+    }
+    lines
+}
+
+/// The resolved styles of an in-flow child of an inline container
+struct InlineItem {
+    /// The id of the child
+    node_id: NodeId,
+    /// The index of the child amongst the container's children
+    order: u32,
+    /// The size of the child's border box, once measured
+    size: Size<f32>,
+    /// The child's resolved margin
+    margin: Rect<f32>,
+    /// The child's resolved padding
+    padding: Rect<f32>,
+    /// The child's resolved border
+    border: Rect<f32>,
+    /// The size of the child's scrollbars
+    scrollbar_size: Size<f32>,
+}
+
+/// Computes the layout of an inline container: a node whose in-flow children are measured as atoms and placed on lines
+/// by [`LayoutInlineContainer::format_inline_content`], which may also lay out content of the container which is not
+/// represented in the tree (such as text). The container is otherwise sized like a leaf (see [`compute_leaf_layout`])
+/// whose content is its lines.
+pub fn compute_inline_layout(
+    tree: &mut impl LayoutInlineContainer,
+    node_id: NodeId,
+    inputs: LayoutInput,
+) -> LayoutOutput {
+    let LayoutInput { known_dimensions, parent_size, available_space, sizing_mode, run_mode, config, .. } = inputs;
+    let style = tree.get_core_container_style(node_id);
+
+    // Pull these out earlier to avoid borrowing issues
+    let aspect_ratio = style.aspect_ratio();
+    let percent_basis = config.percent_resolution_mode.basis(parent_size);
+    let margin = style.margin().resolve_or_zero(percent_basis);
+    let padding = style.padding().resolve_or_zero(percent_basis);
+    let border = style.border().resolve_or_zero(parent_size.width);
+    let scrollbar_gutter = style.scrollbar_gutter().resolve(style.overflow(), style.scrollbar_width());
+    let content_box_inset = padding + border + scrollbar_gutter;
+    let box_sizing_adjustment =
+        if style.box_sizing() == BoxSizing::ContentBox { (padding + border).sum_axes() } else { Size::ZERO };
+    let (node_width, min_width, max_width) = match sizing_mode {
+        SizingMode::ContentSize => (known_dimensions.width, None, None),
+        SizingMode::InherentSize => {
+            let style_width = style
+                .size()
+                .maybe_resolve(parent_size)
+                .maybe_apply_aspect_ratio(aspect_ratio)
+                .maybe_add(box_sizing_adjustment)
+                .width;
+            let min_width = style
+                .min_size()
+                .maybe_resolve(parent_size)
+                .maybe_apply_aspect_ratio(aspect_ratio)
+                .maybe_add(box_sizing_adjustment)
+                .width;
+            let max_width = style.max_size().maybe_resolve(parent_size).maybe_add(box_sizing_adjustment).width;
+            (known_dimensions.width.or(style_width), min_width, max_width)
+        }
+    };
+
+    drop(style);
+
+    debug_log!("INLINE");
+
+    // The lines are as wide as the container's content box if its width is known
+    let available_width = available_space
+        .width
+        .maybe_sub(margin.horizontal_axis_sum())
+        .maybe_set(max_width)
+        .maybe_set(node_width)
+        .map_definite_value(|width| {
+            f32_max(width.maybe_clamp(min_width, max_width) - content_box_inset.horizontal_axis_sum(), 0.0)
+        });
+    let inner_size = Size { width: available_width.into_option(), height: None };
+
+    // Resolve the styles of the in-flow children, setting aside the absolutely positioned children
+    let child_percent_basis = config.percent_resolution_mode.basis(inner_size);
+    let mut items = Vec::with_capacity(tree.child_count(node_id));
+    let mut absolute_children = Vec::new();
+    for order in 0..tree.child_count(node_id) {
+        let child = tree.get_child_id(node_id, order);
+        let child_style = tree.get_core_container_style(child);
+        if child_style.box_generation_mode() == BoxGenerationMode::None {
+            drop(child_style);
+            if run_mode == RunMode::PerformLayout {
+                tree.compute_child_layout(child, LayoutInput::HIDDEN);
+            }
+            continue;
+        }
+        if child_style.position() == Position::Absolute {
+            absolute_children.push((child, order as u32));
+            continue;
+        }
+        let overflow = child_style.overflow();
+        let scrollbar_width = child_style.scrollbar_width();
+        items.push(InlineItem {
+            node_id: child,
+            order: order as u32,
+            size: Size::ZERO,
+            margin: child_style.margin().resolve_or_zero(child_percent_basis),
+            padding: child_style.padding().resolve_or_zero(child_percent_basis),
+            border: child_style.border().resolve_or_zero(inner_size),
+            scrollbar_size: Size {
+                width: if overflow.y == Overflow::Scroll { scrollbar_width } else { 0.0 },
+                height: if overflow.x == Overflow::Scroll { scrollbar_width } else { 0.0 },
+            },
+        });
+    }
+
+    // Measure each child as an atom which is shrunk to fit within the width of the lines
+    let mut atoms = Vec::with_capacity(items.len());
+    for item in items.iter_mut() {
+        item.size = tree
+            .compute_child_layout(
+                item.node_id,
+                LayoutInput {
+                    known_dimensions: Size::NONE,
+                    parent_size: inner_size,
+                    available_space: Size {
+                        width: available_width.maybe_sub(item.margin.horizontal_axis_sum()),
+                        height: AvailableSpace::MaxContent,
+                    },
+                    sizing_mode: SizingMode::InherentSize,
+                    axis: RequestedAxis::Both,
+                    run_mode: RunMode::ComputeSize,
+                    vertical_margins_are_collapsible: Line::FALSE,
+                    config,
+                },
+            )
+            .size;
+        atoms.push(InlineAtom { node_id: item.node_id, size: item.size + item.margin.sum_axes() });
+    }
+
+    let lines = tree.format_inline_content(node_id, InlineFormatInput { available_width, atoms: &atoms });
+    let lines_size = lines.iter().fold(Size::ZERO, |size, line| Size {
+        width: f32_max(size.width, line.location.x + line.size.width),
+        height: f32_max(size.height, line.location.y + line.size.height),
+    });
+
+    let style = tree.get_core_container_style(node_id);
+    let mut output = compute_leaf_layout(inputs, &style, |_, _| lines_size);
+    drop(style);
+    output.first_baselines.y = lines.first().map(|line| content_box_inset.top + line.location.y + line.baseline);
+    output.last_baselines.y = lines.last().map(|line| content_box_inset.top + line.location.y + line.baseline);
+    if run_mode == RunMode::ComputeSize {
+        return output;
+    }
+
+    // Position the atoms on their lines
+    let mut is_placed = Vec::new();
+    is_placed.resize(items.len(), false);
+    for line in lines.iter() {
+        for line_item in line.items.iter() {
+            let item = match items.get(line_item.atom) {
+                Some(item) => item,
+                None => continue,
+            };
+            let item_output = tree.perform_child_layout(
+                item.node_id,
+                item.size.map(Some),
+                inner_size,
+                item.size.map(AvailableSpace::Definite),
+                SizingMode::InherentSize,
+                Line::FALSE,
+            );
+            let location = Point {
+                x: content_box_inset.left + line.location.x + line_item.offset.x + item.margin.left,
+                y: content_box_inset.top + line.location.y + line_item.offset.y + item.margin.top,
+            };
+            tree.set_unrounded_layout(
+                item.node_id,
+                &Layout {
+                    order: item.order,
+                    size: item_output.size,
+                    #[cfg(feature = "content_size")]
+                    content_size: item_output.content_size,
+                    scrollbar_size: item.scrollbar_size,
+                    location,
+                    padding: item.padding,
+                    border: item.border,
+                    margin: item.margin,
+                    first_baseline: item_output.first_baselines.y,
+                    scroll_origin_shift: item_output.scroll_origin_shift,
+                },
+            );
+            is_placed[line_item.atom] = true;
+        }
+    }
+
+    // Atoms which the formatter didn't place on a line (such as those truncated along with overflowing text) are hidden
+    for (item, is_placed) in items.iter().zip(is_placed) {
+        if !is_placed {
+            tree.compute_child_layout(item.node_id, LayoutInput::HIDDEN);
+        }
+    }
+
+    // Lay out the absolutely positioned children against the padding box, with a static position at the start of the
+    // content box
+    let absolute_position_inset = border + scrollbar_gutter;
+    let absolute_position_area = output.size - absolute_position_inset.sum_axes();
+    let absolute_position_offset = Point { x: absolute_position_inset.left, y: absolute_position_inset.top };
+    let static_position = Point { x: content_box_inset.left, y: content_box_inset.top };
+    #[cfg_attr(not(feature = "content_size"), allow(unused_variables))]
+    let absolute_content_size = perform_absolute_layout_on_absolute_children(
+        tree,
+        &absolute_children,
+        absolute_position_area,
+        absolute_position_offset,
+        static_position,
+    );
+    #[cfg(feature = "content_size")]
+    {
+        output.content_size = output.content_size.f32_max(absolute_content_size);
+    }
+
+    output
+}
+
+/// Lays out the absolutely positioned children of an inline container, each given as its id and its index amongst the
+/// container's children, within the area of size `area_size` at `area_offset`. Children without an inset are placed
+/// at `static_position`. Returns the size of the content of the children.
+fn perform_absolute_layout_on_absolute_children(
+    tree: &mut impl LayoutInlineContainer,
+    children: &[(NodeId, u32)],
+    area_size: Size<f32>,
+    area_offset: Point<f32>,
+    static_position: Point<f32>,
+) -> Size<f32> {
+    let area_width = area_size.width;
+    let area_height = area_size.height;
+    let percent_basis = tree.get_layout_config().percent_resolution_mode.basis(area_size.map(Some));
+
+    #[cfg_attr(not(feature = "content_size"), allow(unused_mut))]
+    let mut absolute_content_size = Size::ZERO;
+
+    for &(node_id, order) in children {
+        let child_style = tree.get_core_container_style(node_id);
+        let aspect_ratio = child_style.aspect_ratio();
+        let margin = child_style.margin().zip_size(percent_basis, |margin, basis| margin.maybe_resolve(basis));
+        let padding = child_style.padding().resolve_or_zero(percent_basis);
+        let border = child_style.border().resolve_or_zero(Some(area_width));
+        let padding_border_sum = (padding + border).sum_axes();
+        let box_sizing_adjustment =
+            if child_style.box_sizing() == BoxSizing::ContentBox { padding_border_sum } else { Size::ZERO };
+        let overflow = child_style.overflow();
+        let scrollbar_width = child_style.scrollbar_width();
+
+        // Resolve inset
+        let left = child_style.inset().left.maybe_resolve(area_width);
+        let right = child_style.inset().right.maybe_resolve(area_width);
+        let top = child_style.inset().top.maybe_resolve(area_height);
+        let bottom = child_style.inset().bottom.maybe_resolve(area_height);
+
+        // Compute known dimensions from min/max/inherent size styles
+        let style_size = child_style
+            .size()
+            .maybe_resolve(area_size)
+            .maybe_apply_aspect_ratio(aspect_ratio)
+            .maybe_add(box_sizing_adjustment);
+        let min_size = child_style
+            .min_size()
+            .maybe_resolve(area_size)
+            .maybe_apply_aspect_ratio(aspect_ratio)
+            .maybe_add(box_sizing_adjustment)
+            .or(padding_border_sum.map(Some))
+            .maybe_max(padding_border_sum);
+        let max_size = child_style
+            .max_size()
+            .maybe_resolve(area_size)
+            .maybe_apply_aspect_ratio(aspect_ratio)
+            .maybe_add(box_sizing_adjustment);
+        let mut known_dimensions = style_size.maybe_clamp(min_size, max_size);
+
+        drop(child_style);
+
+        // Fill in the width from the left and right insets, and the height from the top and bottom insets
+        if let (None, Some(left), Some(right)) = (known_dimensions.width, left, right) {
+            let new_width_raw = area_width.maybe_sub(margin.left).maybe_sub(margin.right) - left - right;
+            known_dimensions.width = Some(f32_max(new_width_raw, 0.0));
+            known_dimensions = known_dimensions.maybe_apply_aspect_ratio(aspect_ratio).maybe_clamp(min_size, max_size);
+        }
+        if let (None, Some(top), Some(bottom)) = (known_dimensions.height, top, bottom) {
+            let new_height_raw = area_height.maybe_sub(margin.top).maybe_sub(margin.bottom) - top - bottom;
+            known_dimensions.height = Some(f32_max(new_height_raw, 0.0));
+            known_dimensions = known_dimensions.maybe_apply_aspect_ratio(aspect_ratio).maybe_clamp(min_size, max_size);
+        }
+
+        let layout_output = tree.perform_child_layout(
+            node_id,
+            known_dimensions,
+            area_size.map(Some),
+            Size {
+                width: AvailableSpace::Definite(area_width.maybe_clamp(min_size.width, max_size.width)),
+                height: AvailableSpace::Definite(area_height.maybe_clamp(min_size.height, max_size.height)),
+            },
+            SizingMode::ContentSize,
+            Line::FALSE,
+        );
+        let final_size = known_dimensions.unwrap_or(layout_output.size).maybe_clamp(min_size, max_size);
+
+        // Auto margins only take up the free space when both insets of their axis are set, and otherwise resolve to 0
+        let auto_margin_size = Size {
+            width: match (left, right, margin.left.is_none() as u8 + margin.right.is_none() as u8) {
+                (Some(left), Some(right), auto_margin_count) if auto_margin_count > 0 => {
+                    let free_space = area_width
+                        - left
+                        - right
+                        - final_size.width
+                        - margin.left.unwrap_or(0.0)
+                        - margin.right.unwrap_or(0.0);
+                    free_space / auto_margin_count as f32
+                }
+                _ => 0.0,
+            },
+            height: match (top, bottom, margin.top.is_none() as u8 + margin.bottom.is_none() as u8) {
+                (Some(top), Some(bottom), auto_margin_count) if auto_margin_count > 0 => {
+                    let free_space = area_height
+                        - top
+                        - bottom
+                        - final_size.height
+                        - margin.top.unwrap_or(0.0)
+                        - margin.bottom.unwrap_or(0.0);
+                    free_space / auto_margin_count as f32
+                }
+                _ => 0.0,
+            },
+        };
+        let resolved_margin = Rect {
+            left: margin.left.unwrap_or(auto_margin_size.width),
+            right: margin.right.unwrap_or(auto_margin_size.width),
+            top: margin.top.unwrap_or(auto_margin_size.height),
+            bottom: margin.bottom.unwrap_or(auto_margin_size.height),
+        };
+
+        let location = Point {
+            x: left
+                .map(|left| left + resolved_margin.left)
+                .or(right.map(|right| area_width - final_size.width - right - resolved_margin.right))
+                .maybe_add(area_offset.x)
+                .unwrap_or(static_position.x + resolved_margin.left),
+            y: top
+                .map(|top| top + resolved_margin.top)
+                .or(bottom.map(|bottom| area_height - final_size.height - bottom - resolved_margin.bottom))
+                .maybe_add(area_offset.y)
+                .unwrap_or(static_position.y + resolved_margin.top),
+        };
+        tree.set_unrounded_layout(
+            node_id,
+            &Layout {
+                order,
+                size: final_size,
+                #[cfg(feature = "content_size")]
+                content_size: layout_output.content_size,
+                scrollbar_size: Size {
+                    width: if overflow.y == Overflow::Scroll { scrollbar_width } else { 0.0 },
+                    height: if overflow.x == Overflow::Scroll { scrollbar_width } else { 0.0 },
+                },
+                location,
+                padding,
+                border,
+                margin: resolved_margin,
+                first_baseline: layout_output.first_baselines.y,
+                scroll_origin_shift: layout_output.scroll_origin_shift,
+            },
+        );
+
+        #[cfg(feature = "content_size")]
+        {
+            absolute_content_size = absolute_content_size.f32_max(compute_content_size_contribution(
+                location,
+                final_size,
+                layout_output.content_size,
+                overflow,
+            ));
+        }
+    }
+
+    absolute_content_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{break_atoms_into_lines, InlineAtom, InlineFormatInput};
+    use crate::geometry::{Point, Size};
+    use crate::style::AvailableSpace;
+    use crate::tree::NodeId;
+
+    #[test]
+    fn atoms_wrap_onto_new_lines_when_they_overflow() {
+        let atom =
+            |id: u64, width: f32| InlineAtom { node_id: NodeId::from(id), size: Size { width, height: width / 4.0 } };
+        let atoms = [atom(0, 40.0), atom(1, 80.0), atom(2, 40.0)];
+        let input = |available_width| InlineFormatInput { available_width, atoms: &atoms };
+
+        let lines = break_atoms_into_lines(&input(AvailableSpace::Definite(120.0)));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].size, Size { width: 120.0, height: 20.0 });
+        assert_eq!(lines[0].items[0].offset, Point { x: 0.0, y: 10.0 });
+        assert_eq!(lines[0].items[1].offset, Point { x: 40.0, y: 0.0 });
+        assert_eq!((lines[1].location, lines[1].items[0].atom), (Point { x: 0.0, y: 20.0 }, 2));
+
+        assert_eq!(break_atoms_into_lines(&input(AvailableSpace::MinContent)).len(), 3);
+        assert_eq!(break_atoms_into_lines(&input(AvailableSpace::MaxContent)).len(), 1);
+    }
+}
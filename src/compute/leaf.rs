@@ -1,9 +1,9 @@
 //! Computes size using styles and measure functions
 
-use crate::geometry::{Point, Size};
-use crate::style::{AvailableSpace, Overflow, Position};
+use crate::geometry::{Point, Rect, Size};
+use crate::style::{AvailableSpace, Position};
 use crate::tree::{CollapsibleMarginSet, RunMode};
-use crate::tree::{LayoutInput, LayoutOutput, SizingMode};
+use crate::tree::{LayoutInput, LayoutOutput, MeasureOutput, SizingMode};
 use crate::util::debug::debug_log;
 use crate::util::sys::f32_max;
 use crate::util::MaybeMath;
@@ -11,21 +11,30 @@ use crate::util::{MaybeResolve, ResolveOrZero};
 use crate::{BoxSizing, CoreStyle};
 use core::unreachable;
 
+#[cfg(feature = "content_size")]
+use super::common::content_size::clip_content_size;
+
 /// Compute the size of a leaf node (node with no children)
-pub fn compute_leaf_layout<MeasureFunction>(
+///
+/// The measure function returns the size of the node's content, either as a [`Size`] or as a [`MeasureOutput`] which
+/// also holds the baselines of the content and whether it depends on the available width.
+pub fn compute_leaf_layout<MeasureFunction, Measured>(
     inputs: LayoutInput,
     style: &impl CoreStyle,
     measure_function: MeasureFunction,
 ) -> LayoutOutput
 where
-    MeasureFunction: FnOnce(Size<Option<f32>>, Size<AvailableSpace>) -> Size<f32>,
+    MeasureFunction: FnOnce(Size<Option<f32>>, Size<AvailableSpace>) -> Measured,
+    Measured: Into<MeasureOutput>,
 {
-    let LayoutInput { known_dimensions, parent_size, available_space, sizing_mode, run_mode, .. } = inputs;
+    let LayoutInput { known_dimensions, parent_size, available_space, sizing_mode, run_mode, config, .. } = inputs;
 
     // Note: both horizontal and vertical percentage padding/borders are resolved against the container's inline size (i.e. width).
     // This is not a bug, but is how CSS is specified (see: https://developer.mozilla.org/en-US/docs/Web/CSS/padding#values)
-    let margin = style.margin().resolve_or_zero(parent_size.width);
-    let padding = style.padding().resolve_or_zero(parent_size.width);
+    // Margins and padding may be configured to resolve vertical percentages against the height using `PercentResolutionMode`.
+    let percent_basis = config.percent_resolution_mode.basis(parent_size);
+    let margin = style.margin().resolve_or_zero(percent_basis);
+    let padding = style.padding().resolve_or_zero(percent_basis);
     let border = style.border().resolve_or_zero(parent_size.width);
     let padding_border = padding + border;
     let pb_sum = padding_border.sum_axes();
@@ -59,17 +68,10 @@ where
         }
     };
 
-    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`.
-    // However, the axis are switched (transposed) because a node that scrolls vertically needs
-    // *horizontal* space to be reserved for a scrollbar
-    let scrollbar_gutter = style.overflow().transpose().map(|overflow| match overflow {
-        Overflow::Scroll => style.scrollbar_width(),
-        _ => 0.0,
-    });
-    // TODO: make side configurable based on the `direction` property
-    let mut content_box_inset = padding_border;
-    content_box_inset.right += scrollbar_gutter.x;
-    content_box_inset.bottom += scrollbar_gutter.y;
+    // Scrollbar gutters are reserved when the `overflow` property is set to `Overflow::Scroll`, or when the node
+    // is a scroll container with a stable `scrollbar_gutter`
+    let scrollbar_gutter = style.scrollbar_gutter().resolve(style.overflow(), style.scrollbar_width());
+    let content_box_inset = padding_border + scrollbar_gutter;
 
     let has_styles_preventing_being_collapsed_through = !style.is_block()
         || style.overflow().x.is_scroll_container()
@@ -98,9 +100,12 @@ where
                 #[cfg(feature = "content_size")]
                 content_size: Size::ZERO,
                 first_baselines: Point::NONE,
+                last_baselines: Point::NONE,
                 top_margin: CollapsibleMarginSet::ZERO,
                 bottom_margin: CollapsibleMarginSet::ZERO,
                 margins_can_collapse_through: false,
+                scroll_origin_shift: Point::ZERO,
+                depends_on_available_width: true,
             };
         };
     }
@@ -132,14 +137,18 @@ where
     };
 
     // Measure node
-    let measured_size = measure_function(
+    let measured: MeasureOutput = measure_function(
         match run_mode {
             RunMode::ComputeSize => known_dimensions,
             RunMode::PerformLayout => Size::NONE,
             RunMode::PerformHiddenLayout => unreachable!(),
         },
         available_space,
-    );
+    )
+    .into();
+    let measured_size = measured.size;
+    #[cfg(feature = "fixed_point")]
+    let measured_size = measured_size.map(crate::tree::quantize);
     let clamped_size = known_dimensions
         .or(node_size)
         .unwrap_or(measured_size + content_box_inset.sum_axes())
@@ -153,12 +162,21 @@ where
     LayoutOutput {
         size,
         #[cfg(feature = "content_size")]
-        content_size: measured_size + padding.sum_axes(),
-        first_baselines: Point::NONE,
+        content_size: clip_content_size(size, measured_size + padding.sum_axes(), style.overflow()),
+        first_baselines: offset_baselines(measured.first_baselines, content_box_inset),
+        last_baselines: offset_baselines(measured.last_baselines, content_box_inset),
         top_margin: CollapsibleMarginSet::ZERO,
         bottom_margin: CollapsibleMarginSet::ZERO,
         margins_can_collapse_through: !has_styles_preventing_being_collapsed_through
             && size.height == 0.0
             && measured_size.height == 0.0,
+        scroll_origin_shift: Point::ZERO,
+        depends_on_available_width: measured.depends_on_available_width,
     }
 }
+
+/// Converts baselines measured from the top-left corner of a leaf's content box to baselines measured from its border box
+#[inline]
+fn offset_baselines(baselines: Point<Option<f32>>, content_box_inset: Rect<f32>) -> Point<Option<f32>> {
+    Point { x: baselines.x.map(|x| x + content_box_inset.left), y: baselines.y.map(|y| y + content_box_inset.top) }
+}
@@ -9,8 +9,10 @@
 //! | [`compute_flexbox_layout`]        | Layout a Flexbox container and it's direct children                                                                                                                                                |
 //! | [`compute_grid_layout`]           | Layout a CSS Grid container and it's direct children                                                                                                                                               |
 //! | [`compute_block_layout`]          | Layout a Block container and it's direct children                                                                                                                                                  |
+//! | [`compute_inline_layout`]         | Layout an inline container, placing it's direct children on lines with an inline formatter provided by the embedder                                                                               |
 //! | [`compute_leaf_layout`]           | Applies common properties like padding/border/aspect-ratio to a node before deferring to a passed closure to determine it's size. Can be applied to nodes like text or image nodes.                |
 //! | [`compute_root_layout`]           | Layout the root node of a tree (regardless of it's layout mode). This function is typically called once to begin a layout run.                                                                     |                                                                      |
+//! | [`compute_constrained_layout`]    | Layout the root node of a tree under minimum and maximum size constraints (such as Flutter's `BoxConstraints`), for embedding Taffy under a constraints-based layout protocol.                       |
 //! | [`compute_hidden_layout`]         | Mark a node as hidden during layout (like `Display::None`)                                                                                                                                         |
 //! | [`compute_cached_layout`]         | Attempts to find a cached layout for the specified node and layout inputs. Uses the provided closure to compute the layout (and then stores the result in the cache) if no cached layout is found. |
 //!
@@ -22,6 +24,7 @@
 //! | [`print_tree`](crate::print_tree) | [`PrintTree`](crate::PrintTree)                                                                                                                                                                    | Print a debug representation of a node tree and it's computed layout |
 //!
 pub(crate) mod common;
+pub(crate) mod constraints;
 pub(crate) mod leaf;
 
 #[cfg(feature = "block_layout")]
@@ -33,6 +36,10 @@ pub(crate) mod flexbox;
 #[cfg(feature = "grid")]
 pub(crate) mod grid;
 
+#[cfg(feature = "inline_layout")]
+pub(crate) mod inline;
+
+pub use self::constraints::{compute_constrained_layout, BoxConstraints, LayoutConstraints};
 pub use leaf::compute_leaf_layout;
 
 #[cfg(feature = "block_layout")]
@@ -42,10 +49,15 @@ pub use self::block::compute_block_layout;
 pub use self::flexbox::compute_flexbox_layout;
 
 #[cfg(feature = "grid")]
-pub use self::grid::compute_grid_layout;
+pub use self::grid::{compute_grid_layout, GridOccupancyStats};
+
+#[cfg(feature = "inline_layout")]
+pub use self::inline::{
+    break_atoms_into_lines, compute_inline_layout, InlineAtom, InlineFormatInput, LineBox, LineBoxItem,
+};
 
 use crate::geometry::{Line, Point, Size};
-use crate::style::{AvailableSpace, CoreStyle, Overflow};
+use crate::style::{AvailableSpace, CoreStyle, Dimension, Overflow};
 use crate::tree::{
     Layout, LayoutInput, LayoutOutput, LayoutPartialTree, LayoutPartialTreeExt, NodeId, RoundTree, SizingMode,
 };
@@ -56,18 +68,28 @@ use crate::{BoxSizing, MaybeMath, MaybeResolve};
 
 /// Compute layout for the root node in the tree
 pub fn compute_root_layout(tree: &mut impl LayoutPartialTree, root: NodeId, available_space: Size<AvailableSpace>) {
-    let mut known_dimensions = Size::NONE;
+    compute_root_layout_with_known_dimensions(tree, root, Size::NONE, available_space);
+}
 
+/// Compute layout for the root node in the tree, forcing it to the specified `known_dimensions` (where they are set).
+/// Returns the (unrounded) size of the root node.
+pub(crate) fn compute_root_layout_with_known_dimensions(
+    tree: &mut impl LayoutPartialTree,
+    root: NodeId,
+    mut known_dimensions: Size<Option<f32>>,
+    available_space: Size<AvailableSpace>,
+) -> Size<f32> {
     #[cfg(feature = "block_layout")]
     {
         let parent_size = available_space.into_options();
+        let percent_basis = tree.get_layout_config().percent_resolution_mode.basis(parent_size);
         let style = tree.get_core_container_style(root);
 
         if style.is_block() {
             // Pull these out earlier to avoid borrowing issues
             let aspect_ratio = style.aspect_ratio();
-            let margin = style.margin().resolve_or_zero(parent_size.width);
-            let padding = style.padding().resolve_or_zero(parent_size.width);
+            let margin = style.margin().resolve_or_zero(percent_basis);
+            let padding = style.padding().resolve_or_zero(percent_basis);
             let border = style.border().resolve_or_zero(parent_size.width);
             let padding_border_size = (padding + border).sum_axes();
             let box_sizing_adjustment =
@@ -122,10 +144,11 @@ pub fn compute_root_layout(tree: &mut impl LayoutPartialTree, root: NodeId, avai
         Line::FALSE,
     );
 
+    let percent_basis = tree.get_layout_config().percent_resolution_mode.basis(available_space.into_options());
     let style = tree.get_core_container_style(root);
-    let padding = style.padding().resolve_or_zero(available_space.width.into_option());
+    let padding = style.padding().resolve_or_zero(percent_basis);
     let border = style.border().resolve_or_zero(available_space.width.into_option());
-    let margin = style.margin().resolve_or_zero(available_space.width.into_option());
+    let margin = style.margin().resolve_or_zero(percent_basis);
     let scrollbar_size = Size {
         width: if style.overflow().y == Overflow::Scroll { style.scrollbar_width() } else { 0.0 },
         height: if style.overflow().x == Overflow::Scroll { style.scrollbar_width() } else { 0.0 },
@@ -145,8 +168,57 @@ pub fn compute_root_layout(tree: &mut impl LayoutPartialTree, root: NodeId, avai
             border,
             // TODO: support auto margins for root node?
             margin,
+            first_baseline: output.first_baselines.y,
+            scroll_origin_shift: output.scroll_origin_shift,
         },
     );
+
+    output.size
+}
+
+/// Determines whether the node's style contains percentages (which are resolved against the parent size), and whether it
+/// may determine or clamp the node's size in each axis (see [`Cache::store_layout`](crate::tree::Cache::store_layout))
+///
+/// Results are cached against every input which may affect them. The parent size is only such an input if the node's
+/// style contains percentages.
+#[inline(always)]
+fn style_dependencies<Tree: LayoutPartialTree + ?Sized>(tree: &Tree, node: NodeId) -> (bool, Size<bool>) {
+    let style = tree.get_core_container_style(node);
+    let (size, min_size, max_size) = (style.size(), style.min_size(), style.max_size());
+
+    let is_percent = |dimension: Dimension| matches!(dimension, Dimension::Percent(_));
+    let depends_on_parent_size = [size, min_size, max_size].iter().any(|s| is_percent(s.width) || is_percent(s.height))
+        || [
+            style.padding().map(Dimension::from),
+            style.border().map(Dimension::from),
+            style.margin().map(Dimension::from),
+        ]
+        .iter()
+        .any(|r| is_percent(r.left) || is_percent(r.right) || is_percent(r.top) || is_percent(r.bottom));
+
+    let has_aspect_ratio = style.aspect_ratio().is_some();
+    let is_constrained = |size: Dimension, min: Dimension, max: Dimension| {
+        has_aspect_ratio || !size.is_auto() || !min.is_auto() || !max.is_auto()
+    };
+    let size_is_constrained = Size {
+        width: is_constrained(size.width, min_size.width, max_size.width),
+        height: is_constrained(size.height, min_size.height, max_size.height),
+    };
+
+    (depends_on_parent_size, size_is_constrained)
+}
+
+/// Stores a layout of a node which is known to still be valid (such as one computed by an earlier call to
+/// [`compute_cached_layout`] with the same inputs) in the node's cache
+#[cfg(feature = "taffy_tree")]
+pub(crate) fn store_cached_layout<Tree: LayoutPartialTree + ?Sized>(
+    tree: &mut Tree,
+    node: NodeId,
+    inputs: LayoutInput,
+    output: LayoutOutput,
+) {
+    let (depends_on_parent_size, size_is_constrained) = style_dependencies(tree, node);
+    tree.get_cache_mut(node).store_layout(&inputs, output, depends_on_parent_size, size_is_constrained);
 }
 
 /// Attempts to find a cached layout for the specified node and layout inputs.
@@ -163,23 +235,44 @@ where
     ComputeFunction: FnMut(&mut Tree, NodeId, LayoutInput) -> LayoutOutput,
 {
     debug_push_node!(node);
-    let LayoutInput { known_dimensions, available_space, run_mode, .. } = inputs;
+    #[cfg(feature = "fixed_point")]
+    let inputs = inputs.quantized();
 
     // First we check if we have a cached result for the given input
-    let cache_entry = tree.get_cache_mut(node).get(known_dimensions, available_space, run_mode);
+    let cache_entry = tree.get_cache_mut(node).get_layout(&inputs);
     if let Some(cached_size_and_baselines) = cache_entry {
-        debug_log_node!(known_dimensions, inputs.parent_size, available_space, run_mode, inputs.sizing_mode);
+        debug_log_node!(
+            inputs.known_dimensions,
+            inputs.parent_size,
+            inputs.available_space,
+            inputs.run_mode,
+            inputs.sizing_mode
+        );
         debug_log!("RESULT (CACHED)", dbg:cached_size_and_baselines.size);
         debug_pop_node!();
         return cached_size_and_baselines;
     }
 
-    debug_log_node!(known_dimensions, inputs.parent_size, available_space, run_mode, inputs.sizing_mode);
+    debug_log_node!(
+        inputs.known_dimensions,
+        inputs.parent_size,
+        inputs.available_space,
+        inputs.run_mode,
+        inputs.sizing_mode
+    );
 
     let computed_size_and_baselines = compute_uncached(tree, node, inputs);
+    #[cfg(feature = "fixed_point")]
+    let computed_size_and_baselines = computed_size_and_baselines.quantized();
 
     // Cache result
-    tree.get_cache_mut(node).store(known_dimensions, available_space, run_mode, computed_size_and_baselines);
+    let (depends_on_parent_size, size_is_constrained) = style_dependencies(tree, node);
+    tree.get_cache_mut(node).store_layout(
+        &inputs,
+        computed_size_and_baselines,
+        depends_on_parent_size,
+        size_is_constrained,
+    );
 
     debug_log!("RESULT", dbg:computed_size_and_baselines.size);
     debug_pop_node!();
@@ -200,37 +293,83 @@ where
 /// In order to prevent innacuracies caused by rounding already-rounded values, we read from `unrounded_layout`
 /// and write to `final_layout`.
 pub fn round_layout(tree: &mut impl RoundTree, node_id: NodeId) {
-    return round_layout_inner(tree, node_id, 0.0, 0.0);
+    round_subtree_layout(tree, node_id, &[]);
+}
+
+/// Rounds the calculated layout of the subtree rooted at `node_id` exactly as [`round_layout`] would when rounding the
+/// whole tree, given the unrounded locations of the node's ancestors (ordered from the root down)
+#[allow(clippy::unnecessary_cast)] // The casts are only unnecessary if the `f64` feature is disabled
+pub(crate) fn round_subtree_layout(tree: &mut impl RoundTree, node_id: NodeId, ancestor_locations: &[Point<f32>]) {
+    // With the `f64` feature enabled absolute positions are accumulated in double precision, which keeps rounding exact
+    // for coordinates well beyond the ~16 million px at which f32 loses integer precision (and beyond the ~100k px at
+    // which it loses sub-pixel precision).
+    use crate::util::precision::{round_scalar, Scalar as CumulativeScalar};
+
+    let (cumulative_x, cumulative_y) = ancestor_locations
+        .iter()
+        .fold((0.0, 0.0), |(x, y), location| (x + location.x as CumulativeScalar, y + location.y as CumulativeScalar));
+    return round_layout_inner(tree, node_id, cumulative_x, cumulative_y);
 
     /// Recursive function to apply rounding to all descendents
-    fn round_layout_inner(tree: &mut impl RoundTree, node_id: NodeId, cumulative_x: f32, cumulative_y: f32) {
+    fn round_layout_inner(
+        tree: &mut impl RoundTree,
+        node_id: NodeId,
+        cumulative_x: CumulativeScalar,
+        cumulative_y: CumulativeScalar,
+    ) {
         let unrounded_layout = *tree.get_unrounded_layout(node_id);
         let mut layout = unrounded_layout;
 
-        let cumulative_x = cumulative_x + unrounded_layout.location.x;
-        let cumulative_y = cumulative_y + unrounded_layout.location.y;
+        let cumulative_x = cumulative_x + unrounded_layout.location.x as CumulativeScalar;
+        let cumulative_y = cumulative_y + unrounded_layout.location.y as CumulativeScalar;
+
+        // Rounds the absolute position `cumulative + start` and `cumulative + end` and returns the distance between them
+        let rounded_span = |cumulative: CumulativeScalar, start: f32, end: f32| -> f32 {
+            (round_scalar(cumulative + end as CumulativeScalar) - round_scalar(cumulative + start as CumulativeScalar))
+                as f32
+        };
 
         layout.location.x = round(unrounded_layout.location.x);
         layout.location.y = round(unrounded_layout.location.y);
-        layout.size.width = round(cumulative_x + unrounded_layout.size.width) - round(cumulative_x);
-        layout.size.height = round(cumulative_y + unrounded_layout.size.height) - round(cumulative_y);
+        layout.size.width = rounded_span(cumulative_x, 0.0, unrounded_layout.size.width);
+        layout.size.height = rounded_span(cumulative_y, 0.0, unrounded_layout.size.height);
         layout.scrollbar_size.width = round(unrounded_layout.scrollbar_size.width);
         layout.scrollbar_size.height = round(unrounded_layout.scrollbar_size.height);
-        layout.border.left = round(cumulative_x + unrounded_layout.border.left) - round(cumulative_x);
-        layout.border.right = round(cumulative_x + unrounded_layout.size.width)
-            - round(cumulative_x + unrounded_layout.size.width - unrounded_layout.border.right);
-        layout.border.top = round(cumulative_y + unrounded_layout.border.top) - round(cumulative_y);
-        layout.border.bottom = round(cumulative_y + unrounded_layout.size.height)
-            - round(cumulative_y + unrounded_layout.size.height - unrounded_layout.border.bottom);
-        layout.padding.left = round(cumulative_x + unrounded_layout.padding.left) - round(cumulative_x);
-        layout.padding.right = round(cumulative_x + unrounded_layout.size.width)
-            - round(cumulative_x + unrounded_layout.size.width - unrounded_layout.padding.right);
-        layout.padding.top = round(cumulative_y + unrounded_layout.padding.top) - round(cumulative_y);
-        layout.padding.bottom = round(cumulative_y + unrounded_layout.size.height)
-            - round(cumulative_y + unrounded_layout.size.height - unrounded_layout.padding.bottom);
+        layout.border.left = rounded_span(cumulative_x, 0.0, unrounded_layout.border.left);
+        layout.border.right = rounded_span(
+            cumulative_x,
+            unrounded_layout.size.width - unrounded_layout.border.right,
+            unrounded_layout.size.width,
+        );
+        layout.border.top = rounded_span(cumulative_y, 0.0, unrounded_layout.border.top);
+        layout.border.bottom = rounded_span(
+            cumulative_y,
+            unrounded_layout.size.height - unrounded_layout.border.bottom,
+            unrounded_layout.size.height,
+        );
+        layout.padding.left = rounded_span(cumulative_x, 0.0, unrounded_layout.padding.left);
+        layout.padding.right = rounded_span(
+            cumulative_x,
+            unrounded_layout.size.width - unrounded_layout.padding.right,
+            unrounded_layout.size.width,
+        );
+        layout.padding.top = rounded_span(cumulative_y, 0.0, unrounded_layout.padding.top);
+        layout.padding.bottom = rounded_span(
+            cumulative_y,
+            unrounded_layout.size.height - unrounded_layout.padding.bottom,
+            unrounded_layout.size.height,
+        );
+
+        layout.first_baseline =
+            unrounded_layout.first_baseline.map(|baseline| rounded_span(cumulative_y, 0.0, baseline));
+        layout.scroll_origin_shift.x = round(unrounded_layout.scroll_origin_shift.x);
+        layout.scroll_origin_shift.y = round(unrounded_layout.scroll_origin_shift.y);
 
         #[cfg(feature = "content_size")]
-        round_content_size(&mut layout, unrounded_layout.content_size, cumulative_x, cumulative_y);
+        {
+            layout.content_size.width = rounded_span(cumulative_x, 0.0, unrounded_layout.content_size.width);
+            layout.content_size.height = rounded_span(cumulative_y, 0.0, unrounded_layout.content_size.height);
+        }
 
         tree.set_final_layout(node_id, &layout);
 
@@ -240,20 +379,6 @@ pub fn round_layout(tree: &mut impl RoundTree, node_id: NodeId) {
             round_layout_inner(tree, child, cumulative_x, cumulative_y);
         }
     }
-
-    #[cfg(feature = "content_size")]
-    #[inline(always)]
-    /// Round content size variables.
-    /// This is split into a separate function to make it easier to feature flag.
-    fn round_content_size(
-        layout: &mut Layout,
-        unrounded_content_size: Size<f32>,
-        cumulative_x: f32,
-        cumulative_y: f32,
-    ) {
-        layout.content_size.width = round(cumulative_x + unrounded_content_size.width) - round(cumulative_x);
-        layout.content_size.height = round(cumulative_y + unrounded_content_size.height) - round(cumulative_y);
-    }
 }
 
 /// Creates a layout for this node and its children, recursively.
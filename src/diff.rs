@@ -0,0 +1,184 @@
+//! Utilities for computing the difference between two sets of layout results
+//!
+//! This is useful for renderers which want to apply a minimal set of updates after a relayout. Take a copy of the tree
+//! (or any other [`PrintTree`]) before modifying it, and compare it with the tree after the new layout has been computed:
+//!
+//! ```rust
+//! # use taffy::prelude::*;
+//! # use taffy::diff::{diff_layouts, LayoutChange};
+//! let mut tree: TaffyTree<()> = TaffyTree::new();
+//! let child = tree.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+//! let root = tree.new_with_children(Style::default(), &[child]).unwrap();
+//! tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+//!
+//! let old_tree = tree.clone();
+//! tree.set_style(child, Style { size: Size::from_lengths(20.0, 10.0), ..Default::default() }).unwrap();
+//! tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+//!
+//! let changes = diff_layouts(&old_tree, root, &tree, root);
+//! assert!(changes.contains(&LayoutChange::Resized {
+//!     node: child,
+//!     old_size: Size { width: 10.0, height: 10.0 },
+//!     new_size: Size { width: 20.0, height: 10.0 },
+//! }));
+//! ```
+use crate::geometry::{Point, Size};
+use crate::tree::{Layout, NodeId, PrintTree};
+use crate::util::sys::Vec;
+
+/// A single difference between two sets of layout results. See [`diff_layouts`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayoutChange {
+    /// The node is only present in the new tree
+    Added {
+        /// The node that was added
+        node: NodeId,
+        /// The layout of the node in the new tree
+        layout: Layout,
+    },
+    /// The node is only present in the old tree
+    Removed {
+        /// The node that was removed
+        node: NodeId,
+        /// The layout of the node in the old tree
+        layout: Layout,
+    },
+    /// The location of the node (relative to its parent) has changed
+    Moved {
+        /// The node that was moved
+        node: NodeId,
+        /// The location of the node in the old tree
+        old_location: Point<f32>,
+        /// The location of the node in the new tree
+        new_location: Point<f32>,
+    },
+    /// The size of the node has changed
+    Resized {
+        /// The node that was resized
+        node: NodeId,
+        /// The size of the node in the old tree
+        old_size: Size<f32>,
+        /// The size of the node in the new tree
+        new_size: Size<f32>,
+    },
+}
+
+/// Computes the changes between the final layouts of the subtree rooted at `old_root` in `old_tree` and the subtree
+/// rooted at `new_root` in `new_tree`
+///
+/// Nodes are matched up by [`NodeId`]. Locations are compared relative to the parent node, so a node whose parent has
+/// moved is not itself reported as moved. A node that has both moved and been resized produces both a
+/// [`LayoutChange::Moved`] and a [`LayoutChange::Resized`] record.
+///
+/// Changes for nodes in the new tree are returned in depth-first order, followed by any removed nodes (also in
+/// depth-first order).
+pub fn diff_layouts(
+    old_tree: &impl PrintTree,
+    old_root: NodeId,
+    new_tree: &impl PrintTree,
+    new_root: NodeId,
+) -> Vec<LayoutChange> {
+    let mut old_layouts = collect_layouts(old_tree, old_root);
+    old_layouts.sort_unstable_by_key(|(node, _)| u64::from(*node));
+    let mut new_layouts = collect_layouts(new_tree, new_root);
+
+    let mut changes = Vec::new();
+    for (node, new_layout) in new_layouts.iter() {
+        let Ok(index) = old_layouts.binary_search_by_key(&u64::from(*node), |(node, _)| u64::from(*node)) else {
+            changes.push(LayoutChange::Added { node: *node, layout: *new_layout });
+            continue;
+        };
+        let old_layout = &old_layouts[index].1;
+        if old_layout.location != new_layout.location {
+            changes.push(LayoutChange::Moved {
+                node: *node,
+                old_location: old_layout.location,
+                new_location: new_layout.location,
+            });
+        }
+        if old_layout.size != new_layout.size {
+            changes.push(LayoutChange::Resized { node: *node, old_size: old_layout.size, new_size: new_layout.size });
+        }
+    }
+
+    // Preserve the depth-first order of the old tree when reporting removed nodes
+    let mut old_layouts = collect_layouts(old_tree, old_root);
+    new_layouts.sort_unstable_by_key(|(node, _)| u64::from(*node));
+    old_layouts
+        .retain(|(node, _)| new_layouts.binary_search_by_key(&u64::from(*node), |(n, _)| u64::from(*n)).is_err());
+    changes.extend(old_layouts.into_iter().map(|(node, layout)| LayoutChange::Removed { node, layout }));
+
+    changes
+}
+
+/// Collects the final layouts of every node in the subtree rooted at `root` in depth-first order
+fn collect_layouts(tree: &impl PrintTree, root: NodeId) -> Vec<(NodeId, Layout)> {
+    let mut layouts = Vec::new();
+    let mut stack = Vec::new();
+    stack.push(root);
+    while let Some(node) = stack.pop() {
+        layouts.push((node, *tree.get_final_layout(node)));
+        // Push children in reverse so that they are popped (and thus visited) in order
+        let child_count = tree.child_count(node);
+        for index in (0..child_count).rev() {
+            stack.push(tree.get_child_id(node, index));
+        }
+    }
+    layouts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_layouts, LayoutChange};
+    use crate::prelude::*;
+
+    fn fixed(width: f32, height: f32) -> Style {
+        Style { size: Size::from_lengths(width, height), ..Default::default() }
+    }
+
+    #[test]
+    fn unchanged_layout_has_no_changes() {
+        let mut tree: TaffyTree<()> = TaffyTree::new();
+        let child = tree.new_leaf(fixed(10.0, 10.0)).unwrap();
+        let root = tree.new_with_children(Style::default(), &[child]).unwrap();
+        tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        assert!(diff_layouts(&tree, root, &tree, root).is_empty());
+    }
+
+    #[test]
+    fn reports_added_removed_moved_and_resized_nodes() {
+        let mut tree: TaffyTree<()> = TaffyTree::new();
+        let first = tree.new_leaf(fixed(10.0, 10.0)).unwrap();
+        let second = tree.new_leaf(fixed(10.0, 10.0)).unwrap();
+        let root = tree.new_with_children(Style::default(), &[first, second]).unwrap();
+        tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        let old_tree = tree.clone();
+
+        // Grow the first node, remove the second node, and add a new node
+        tree.set_style(first, fixed(20.0, 10.0)).unwrap();
+        let added = tree.new_leaf(fixed(5.0, 5.0)).unwrap();
+        tree.add_child(root, added).unwrap();
+        tree.remove_child(root, second).unwrap();
+        tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        let changes = diff_layouts(&old_tree, root, &tree, root);
+        assert_eq!(
+            changes,
+            [
+                LayoutChange::Resized {
+                    node: root,
+                    old_size: Size { width: 20.0, height: 10.0 },
+                    new_size: Size { width: 25.0, height: 10.0 },
+                },
+                LayoutChange::Resized {
+                    node: first,
+                    old_size: Size { width: 10.0, height: 10.0 },
+                    new_size: Size { width: 20.0, height: 10.0 },
+                },
+                LayoutChange::Added { node: added, layout: *tree.layout(added).unwrap() },
+                LayoutChange::Removed { node: second, layout: *old_tree.layout(second).unwrap() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Export a tree's computed layout as an SVG image or HTML document for visual inspection
+//!
+//! Each node is drawn using the same colour scheme as browser devtools: margins in orange, borders in yellow,
+//! padding in green and the content box in blue. Nodes are labelled with their debug label and [`NodeId`].
+//! This is intended for quickly eyeballing layouts (for example, when attaching a layout to a bug report) rather than
+//! as a faithful rendering.
+use core::fmt::Write;
+
+use crate::geometry::{Point, Rect};
+use crate::tree::{Layout, NodeId, PrintTree};
+
+/// Fill colour for the margin area
+const MARGIN_COLOR: &str = "rgba(249, 204, 157, 0.55)";
+/// Fill colour for the border area
+const BORDER_COLOR: &str = "rgba(253, 221, 155, 0.75)";
+/// Fill colour for the padding area
+const PADDING_COLOR: &str = "rgba(195, 208, 139, 0.75)";
+/// Fill colour for the content box
+const CONTENT_COLOR: &str = "rgba(139, 182, 194, 0.75)";
+
+/// An axis-aligned box in absolute coordinates
+#[derive(Debug, Clone, Copy)]
+struct AbsoluteBox {
+    /// The x coordinate of the left edge
+    x: f32,
+    /// The y coordinate of the top edge
+    y: f32,
+    /// The width of the box
+    width: f32,
+    /// The height of the box
+    height: f32,
+}
+
+impl AbsoluteBox {
+    /// Returns the box grown by the specified amount on each side (or shrunk for negative values)
+    fn outset(self, amount: Rect<f32>) -> Self {
+        Self {
+            x: self.x - amount.left,
+            y: self.y - amount.top,
+            width: (self.width + amount.left + amount.right).max(0.0),
+            height: (self.height + amount.top + amount.bottom).max(0.0),
+        }
+    }
+
+    /// Returns the box shrunk by the specified amount on each side
+    fn inset(self, amount: Rect<f32>) -> Self {
+        self.outset(amount.map(|value| -value))
+    }
+}
+
+/// The boxes of a single node, computed from its layout and absolute position
+struct NodeBoxes {
+    /// The id of the node
+    node_id: NodeId,
+    /// The debug label of the node
+    label: &'static str,
+    /// The layout of the node
+    layout: Layout,
+    /// The margin box of the node
+    margin_box: AbsoluteBox,
+    /// The border box of the node
+    border_box: AbsoluteBox,
+    /// The padding box of the node
+    padding_box: AbsoluteBox,
+    /// The content box of the node
+    content_box: AbsoluteBox,
+}
+
+/// Collects the boxes for every node in the subtree rooted at `root`, in paint order (parents before children)
+fn collect_boxes(tree: &impl PrintTree, root: NodeId) -> Vec<NodeBoxes> {
+    let mut boxes = Vec::new();
+    collect(tree, root, Point::ZERO, &mut boxes);
+    return boxes;
+
+    /// Recursive function that collects the boxes for each node
+    fn collect(tree: &impl PrintTree, node_id: NodeId, parent_origin: Point<f32>, boxes: &mut Vec<NodeBoxes>) {
+        let layout = *tree.get_final_layout(node_id);
+        let origin = Point { x: parent_origin.x + layout.location.x, y: parent_origin.y + layout.location.y };
+        let border_box = AbsoluteBox { x: origin.x, y: origin.y, width: layout.size.width, height: layout.size.height };
+        let padding_box = border_box.inset(layout.border);
+        boxes.push(NodeBoxes {
+            node_id,
+            label: tree.get_debug_label(node_id),
+            layout,
+            margin_box: border_box.outset(layout.margin),
+            border_box,
+            padding_box,
+            content_box: padding_box.inset(layout.padding),
+        });
+        for child in tree.child_ids(node_id) {
+            collect(tree, child, origin, boxes);
+        }
+    }
+}
+
+/// Returns the bounding box of all of the margin boxes (which always includes the origin)
+fn bounds(boxes: &[NodeBoxes]) -> AbsoluteBox {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for node in boxes {
+        let b = node.margin_box;
+        min_x = min_x.min(b.x);
+        min_y = min_y.min(b.y);
+        max_x = max_x.max(b.x + b.width);
+        max_y = max_y.max(b.y + b.height);
+    }
+    AbsoluteBox { x: min_x, y: min_y, width: max_x - min_x, height: max_y - min_y }
+}
+
+/// Escapes text for inclusion in XML/HTML content or attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Returns a human readable description of a node's layout
+fn describe(node: &NodeBoxes) -> String {
+    let layout = &node.layout;
+    format!(
+        "{label} ({id:?}) x: {x} y: {y} width: {width} height: {height}",
+        label = node.label,
+        id = node.node_id,
+        x = layout.location.x,
+        y = layout.location.y,
+        width = layout.size.width,
+        height = layout.size.height,
+    )
+}
+
+/// Renders the computed layout of the subtree rooted at `root` as an SVG image
+pub fn to_svg(tree: &impl PrintTree, root: NodeId) -> String {
+    let boxes = collect_boxes(tree, root);
+    let bounds = bounds(&boxes);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}" font-family="sans-serif" font-size="10">"#,
+        bounds.x, bounds.y, bounds.width, bounds.height, bounds.width, bounds.height
+    );
+    for node in &boxes {
+        let _ = writeln!(svg, "  <g>");
+        let _ = writeln!(svg, "    <title>{}</title>", escape(&describe(node)));
+        for (b, color) in [
+            (node.margin_box, MARGIN_COLOR),
+            (node.border_box, BORDER_COLOR),
+            (node.padding_box, PADDING_COLOR),
+            (node.content_box, CONTENT_COLOR),
+        ] {
+            let _ = writeln!(
+                svg,
+                r#"    <rect x="{}" y="{}" width="{}" height="{}" fill="{}" />"#,
+                b.x, b.y, b.width, b.height, color
+            );
+        }
+        let b = node.border_box;
+        let _ = writeln!(
+            svg,
+            r#"    <rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black" stroke-width="0.5" />"#,
+            b.x, b.y, b.width, b.height
+        );
+        let _ = writeln!(
+            svg,
+            r#"    <text x="{}" y="{}">{} ({:?})</text>"#,
+            b.x + 2.0,
+            b.y + 10.0,
+            escape(node.label),
+            node.node_id
+        );
+        let _ = writeln!(svg, "  </g>");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Renders the computed layout of the subtree rooted at `root` as a standalone HTML document
+///
+/// Each node is rendered as an absolutely positioned element. Hovering over a node shows a description of its layout.
+pub fn to_html(tree: &impl PrintTree, root: NodeId) -> String {
+    let boxes = collect_boxes(tree, root);
+    let bounds = bounds(&boxes);
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Taffy layout</title>\n<style>\n");
+    html.push_str("body { margin: 0; font: 10px sans-serif; }\n");
+    html.push_str(".node { position: absolute; box-sizing: border-box; outline: 0.5px solid black; }\n");
+    html.push_str(".content { position: absolute; }\n");
+    html.push_str("</style>\n</head>\n<body>\n");
+    let _ =
+        writeln!(html, r#"<div style="position: relative; width: {}px; height: {}px;">"#, bounds.width, bounds.height);
+    for node in &boxes {
+        let (margin, border, padding, content) = (node.margin_box, node.border_box, node.padding_box, node.content_box);
+        let offset = |x: f32, y: f32| (x - bounds.x, y - bounds.y);
+
+        let (x, y) = offset(margin.x, margin.y);
+        let _ = writeln!(
+            html,
+            r#"  <div class="node" style="left: {x}px; top: {y}px; width: {}px; height: {}px; background: {MARGIN_COLOR}; outline: none;"></div>"#,
+            margin.width, margin.height
+        );
+
+        let (x, y) = offset(border.x, border.y);
+        let layout = &node.layout;
+        let _ = writeln!(
+            html,
+            r#"  <div class="node" title="{title}" style="left: {x}px; top: {y}px; width: {}px; height: {}px; border-style: solid; border-color: {BORDER_COLOR}; border-width: {}px {}px {}px {}px; background: {PADDING_COLOR};">"#,
+            border.width,
+            border.height,
+            layout.border.top,
+            layout.border.right,
+            layout.border.bottom,
+            layout.border.left,
+            title = escape(&describe(node)),
+        );
+        let _ = writeln!(
+            html,
+            r#"    <div class="content" style="left: {}px; top: {}px; width: {}px; height: {}px; background: {CONTENT_COLOR};">{} ({:?})</div>"#,
+            content.x - padding.x,
+            content.y - padding.y,
+            content.width,
+            content.height,
+            escape(node.label),
+            node.node_id
+        );
+        let _ = writeln!(html, "  </div>");
+    }
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(all(test, feature = "taffy_tree"))]
+mod tests {
+    use super::{to_html, to_svg};
+    use crate::prelude::*;
+
+    #[test]
+    fn export_contains_every_node() {
+        let mut tree: TaffyTree<()> = TaffyTree::new();
+        let child = tree
+            .new_leaf(Style {
+                size: Size::from_lengths(10.0, 20.0),
+                margin: length(1.0),
+                padding: length(2.0),
+                border: length(1.0),
+                ..Default::default()
+            })
+            .unwrap();
+        let root = tree.new_with_children(Style::default(), &[child]).unwrap();
+        tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        let svg = to_svg(&tree, root);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(&format!("LEAF ({child:?})")));
+        assert!(svg.contains(&format!("FLEX ROW ({root:?})")));
+        assert_eq!(svg.matches("<g>").count(), 2);
+
+        let html = to_html(&tree, root);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(&format!("LEAF ({child:?})")));
+        assert_eq!(html.matches("title=").count(), 2);
+    }
+}
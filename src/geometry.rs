@@ -144,7 +144,7 @@ impl<T> Rect<T> {
     /// When applied to the left and right sides, the width is used
     /// as the second parameter of `f`.
     /// When applied to the top or bottom sides, the height is used instead.
-    #[cfg(any(feature = "flexbox", feature = "block_layout"))]
+    #[cfg(any(feature = "flexbox", feature = "grid", feature = "block_layout", feature = "taffy_tree"))]
     pub(crate) fn zip_size<R, F, U>(self, size: Size<U>, f: F) -> Rect<R>
     where
         F: Fn(T, U) -> R,
@@ -177,6 +177,26 @@ impl<T> Rect<T> {
     pub fn vertical_components(self) -> Line<T> {
         Line { start: self.top, end: self.bottom }
     }
+
+    /// Creates a new Rect from its sides in the order of its fields
+    #[must_use]
+    pub const fn xyzw(left: T, right: T, top: T, bottom: T) -> Self {
+        Self { left, right, top, bottom }
+    }
+}
+
+impl<T: Clone> Rect<T> {
+    /// Creates a new Rect with `value` on all four sides
+    #[must_use]
+    pub fn all(value: T) -> Self {
+        Self { left: value.clone(), right: value.clone(), top: value.clone(), bottom: value }
+    }
+
+    /// Creates a new Rect with `horizontal` on the left and right sides and `vertical` on the top and bottom sides
+    #[must_use]
+    pub fn axes(horizontal: T, vertical: T) -> Self {
+        Self { left: horizontal.clone(), right: horizontal, top: vertical.clone(), bottom: vertical }
+    }
 }
 
 impl<T, U> Rect<T>
@@ -606,6 +626,14 @@ impl<T> Size<Option<T>> {
     }
 }
 
+impl<T: Clone> Size<T> {
+    /// Creates a new Size with `value` as both the width and height
+    #[must_use]
+    pub fn square(value: T) -> Self {
+        Size { width: value.clone(), height: value }
+    }
+}
+
 impl Size<Dimension> {
     /// Generates a [`Size<Dimension>`] using [`Dimension::Length`] values
     #[must_use]
@@ -686,30 +714,6 @@ impl<T> Point<T> {
             AbstractAxis::Block => self.y = value,
         }
     }
-
-    /// Gets the component in the main layout axis
-    ///
-    /// Whether this is the x or y depends on the `direction` provided
-    #[cfg(feature = "flexbox")]
-    pub(crate) fn main(self, direction: FlexDirection) -> T {
-        if direction.is_row() {
-            self.x
-        } else {
-            self.y
-        }
-    }
-
-    /// Gets the component in the cross layout axis
-    ///
-    /// Whether this is the x or y depends on the `direction` provided
-    #[cfg(feature = "flexbox")]
-    pub(crate) fn cross(self, direction: FlexDirection) -> T {
-        if direction.is_row() {
-            self.y
-        } else {
-            self.x
-        }
-    }
 }
 
 impl<T> From<Point<T>> for Size<T> {
@@ -0,0 +1,1099 @@
+//! Conversion of HTML inline style attributes (such as `style="display: flex; margin: 0 10px"`) into [`Style`]s, for
+//! renderers of HTML-like documents
+//!
+//! Styles can be given either as the text of a `style` attribute, with [`parse_inline_style`] and
+//! [`apply_inline_style`], or as a list of property names and values (as produced by frameworks which represent styles
+//! as a map), with [`apply_style_properties`]. A [`StyleMap`] holds such a list, and can be converted to and from a
+//! `Style`. With the `lightningcss` feature, a `StyleMap` can also be created from declarations parsed by the
+//! [`lightningcss`](https://docs.rs/lightningcss) crate, so that stylesheets can be applied without a hand-written
+//! mapping between the two.
+//!
+//! Supported properties are `display`, `position`, `box-sizing`, `direction`, `writing-mode`, `overflow(-x/-y)`,
+//! `scrollbar-gutter`, `width`, `height`, `min-/max-width`, `min-/max-height`, `aspect-ratio`, `left`, `right`, `top`,
+//! `bottom`, `inset`, `margin`, `padding` and `border-width` (including per-side longhands such as `margin-left`),
+//! `gap`, `row-gap`, `column-gap`, `align-items`, `align-self`, `align-content`, `justify-content`, `flex` and the
+//! `flex-*` longhands and, with the `grid` feature, `justify-items`, `justify-self` and `grid-template-rows/columns`.
+//! Lengths must be in `px` (or unitless). Sizes may also be a percentage of the layout root's size, with the
+//! non-standard `root(50%)` syntax (see [`Dimension::RootPercent`]). Shorthands set every property that they expand to,
+//! as in CSS.
+//!
+//! Property names are case-insensitive, and may also be written in `camelCase` or `snake_case` (for example
+//! `flexDirection` or `flex_direction`) as is common in key/value style maps.
+use std::string::String;
+
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use crate::geometry::Size;
+use crate::geometry::{Point, Rect};
+use crate::style::Style;
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use crate::style::{AlignContent, AlignItems};
+use crate::style::{
+    BoxSizing, Dimension, Direction, Display, LengthPercentage, LengthPercentageAuto, Overflow, Position,
+    ScrollbarGutter, WritingMode,
+};
+#[cfg(feature = "flexbox")]
+use crate::style::{FlexDirection, FlexWrap};
+#[cfg(feature = "grid")]
+use crate::style::{
+    GridTrackRepetition, MaxTrackSizingFunction, MinTrackSizingFunction, NonRepeatedTrackSizingFunction,
+    TrackSizingFunction,
+};
+use crate::util::sys::Vec;
+
+/// The reason that an inline style failed to parse. See [`InlineStyleError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineStyleErrorKind {
+    /// A declaration was not of the form `property: value`
+    InvalidDeclaration,
+    /// A style property is not supported
+    UnknownProperty,
+    /// The value of a style property is not valid
+    InvalidValue,
+}
+
+/// Error returned when an inline style could not be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InlineStyleError {
+    /// The byte offset of the invalid declaration in an inline style string, or the index of the invalid property in a
+    /// list of properties passed to [`apply_style_properties`]
+    pub offset: usize,
+    /// The reason that parsing failed
+    pub kind: InlineStyleErrorKind,
+}
+
+impl std::error::Error for InlineStyleError {}
+impl core::fmt::Display for InlineStyleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let reason = match self.kind {
+            InlineStyleErrorKind::InvalidDeclaration => "expected a declaration of the form `property: value`",
+            InlineStyleErrorKind::UnknownProperty => "unsupported style property",
+            InlineStyleErrorKind::InvalidValue => "invalid style value",
+        };
+        write!(f, "Invalid inline style at offset {}: {}", self.offset, reason)
+    }
+}
+
+/// Parses the text of a `style` attribute (a list of CSS declarations separated by `;`) into a [`Style`], starting
+/// from the default style
+///
+/// ```
+/// # use taffy::prelude::*;
+/// # use taffy::inline_style::parse_inline_style;
+/// let style = parse_inline_style("display: flex; flex: 1; margin: 0 10px").unwrap();
+/// assert_eq!(style.flex_grow, 1.0);
+/// assert_eq!(style.margin.left, length(10.0));
+/// ```
+pub fn parse_inline_style(declarations: &str) -> Result<Style, InlineStyleError> {
+    let mut style = Style::default();
+    apply_inline_style(&mut style, declarations)?;
+    Ok(style)
+}
+
+/// Applies the text of a `style` attribute (a list of CSS declarations separated by `;`) to `style`, in order
+///
+/// If an error occurs then the declarations before the invalid one have already been applied.
+pub fn apply_inline_style(style: &mut Style, declarations: &str) -> Result<(), InlineStyleError> {
+    let mut offset = 0;
+    for declaration in declarations.split(';') {
+        let error = |kind| InlineStyleError { offset, kind };
+        if !declaration.trim().is_empty() {
+            let (property, value) =
+                declaration.split_once(':').ok_or(error(InlineStyleErrorKind::InvalidDeclaration))?;
+            apply_property(style, &property_name(property), value.trim()).map_err(error)?;
+        }
+        offset += declaration.len() + 1;
+    }
+    Ok(())
+}
+
+/// Applies a list of property names and values (such as the entries of a map) to `style`, in order
+///
+/// As a shorthand overrides any longhands set before it, properties should be given in a deterministic order: iterating
+/// over a `HashMap` which holds both `margin` and `margin-left` may apply them in either order.
+///
+/// ```
+/// # use taffy::prelude::*;
+/// # use taffy::inline_style::apply_style_properties;
+/// let mut style = Style::default();
+/// apply_style_properties(&mut style, [("flexDirection", "column"), ("padding", "4px 8px")]).unwrap();
+/// assert_eq!(style.flex_direction, FlexDirection::Column);
+/// assert_eq!(style.padding.top, length(4.0));
+/// ```
+pub fn apply_style_properties<Name: AsRef<str>, Value: AsRef<str>>(
+    style: &mut Style,
+    properties: impl IntoIterator<Item = (Name, Value)>,
+) -> Result<(), InlineStyleError> {
+    for (index, (property, value)) in properties.into_iter().enumerate() {
+        apply_property(style, &property_name(property.as_ref()), value.as_ref().trim())
+            .map_err(|kind| InlineStyleError { offset: index, kind })?;
+    }
+    Ok(())
+}
+
+/// Converts a property name to lowercase `kebab-case`, so that `marginLeft`, `margin_left` and `MARGIN-LEFT` are all
+/// `margin-left`
+fn property_name(property: &str) -> String {
+    let property = property.trim();
+    // A name without any lowercase letters is not camelCase, but written in capitals
+    let is_camel_case = property.bytes().any(|byte| byte.is_ascii_lowercase());
+    let mut name = String::with_capacity(property.len() + 4);
+    for (index, char) in property.chars().enumerate() {
+        match char {
+            '_' => name.push('-'),
+            char if char.is_ascii_uppercase() => {
+                if is_camel_case && index > 0 && !name.ends_with('-') {
+                    name.push('-');
+                }
+                name.push(char.to_ascii_lowercase());
+            }
+            char => name.push(char),
+        }
+    }
+    name
+}
+
+/// An ordered list of style properties and their (unparsed) values, such as the declarations of a `style` attribute
+///
+/// Property names are normalised as described in the [module](crate::inline_style) documentation. Converting a
+/// [`Style`] into a `StyleMap` writes each supported property which differs from its default value, and converting
+/// a `StyleMap` back into a `Style` parses each property in order.
+///
+/// ```
+/// # use taffy::prelude::*;
+/// # use taffy::inline_style::StyleMap;
+/// let style = Style { display: Display::Grid, padding: length(4.0), ..Default::default() };
+/// let map = StyleMap::from(&style);
+/// assert_eq!(map.to_string(), "display: grid; padding: 4px");
+/// assert_eq!(Style::try_from(&map), Ok(style));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyleMap {
+    /// The properties, in the order they were last set
+    properties: Vec<(String, String)>,
+}
+
+impl StyleMap {
+    /// Creates an empty map
+    pub const fn new() -> Self {
+        Self { properties: Vec::new() }
+    }
+
+    /// Sets the value of a property, replacing any previous value. The property is moved to the end of the map, so
+    /// that it overrides any shorthand or longhand of it set earlier.
+    pub fn insert(&mut self, property: &str, value: impl Into<String>) {
+        let property = property_name(property);
+        self.properties.retain(|(name, _)| *name != property);
+        self.properties.push((property, value.into()));
+    }
+
+    /// Returns the value of a property
+    pub fn get(&self, property: &str) -> Option<&str> {
+        let property = property_name(property);
+        self.properties.iter().find(|(name, _)| *name == property).map(|(_, value)| value.as_str())
+    }
+
+    /// Removes a property, returning its value
+    pub fn remove(&mut self, property: &str) -> Option<String> {
+        let property = property_name(property);
+        let index = self.properties.iter().position(|(name, _)| *name == property)?;
+        Some(self.properties.remove(index).1)
+    }
+
+    /// Returns the number of properties in the map
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    /// Returns true if the map holds no properties
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Returns an iterator over the properties and their values, in order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Formats the map as the text of a `style` attribute
+impl core::fmt::Display for StyleMap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, (name, value)) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{name}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&StyleMap> for Style {
+    type Error = InlineStyleError;
+
+    /// Parses each property of the map in order, starting from the default style. See [`apply_style_properties`].
+    fn try_from(map: &StyleMap) -> Result<Self, InlineStyleError> {
+        let mut style = Style::default();
+        apply_style_properties(&mut style, map.iter())?;
+        Ok(style)
+    }
+}
+
+impl From<&Style> for StyleMap {
+    /// Writes each supported property of `style` which differs from the default style. Properties which can't be set
+    /// by an inline style (such as `grid-row`) are not written.
+    fn from(style: &Style) -> Self {
+        let mut map = StyleMap::new();
+        let defaults = &Style::DEFAULT;
+        let mut set = |property: &str, is_set: bool, value: &dyn Fn() -> String| {
+            if is_set {
+                map.insert(property, value());
+            }
+        };
+
+        set("display", style.display != defaults.display, &|| display_css(style.display).into());
+        set("position", style.position != defaults.position, &|| {
+            match style.position {
+                Position::Relative => "relative",
+                Position::Absolute => "absolute",
+            }
+            .into()
+        });
+        set("box-sizing", style.box_sizing != defaults.box_sizing, &|| {
+            match style.box_sizing {
+                BoxSizing::BorderBox => "border-box",
+                BoxSizing::ContentBox => "content-box",
+            }
+            .into()
+        });
+        set("direction", style.direction != defaults.direction, &|| {
+            match style.direction {
+                Direction::Inherit => "inherit",
+                Direction::Ltr => "ltr",
+                Direction::Rtl => "rtl",
+            }
+            .into()
+        });
+        set("writing-mode", style.writing_mode != defaults.writing_mode, &|| {
+            match style.writing_mode {
+                WritingMode::Inherit => "inherit",
+                WritingMode::HorizontalTb => "horizontal-tb",
+                WritingMode::VerticalRl => "vertical-rl",
+                WritingMode::VerticalLr => "vertical-lr",
+            }
+            .into()
+        });
+        set("overflow", style.overflow != defaults.overflow, &|| {
+            format!("{} {}", overflow_css(style.overflow.x), overflow_css(style.overflow.y))
+        });
+        set("scrollbar-gutter", style.scrollbar_gutter != defaults.scrollbar_gutter, &|| {
+            match style.scrollbar_gutter {
+                ScrollbarGutter::Auto => "auto",
+                ScrollbarGutter::Stable => "stable",
+                ScrollbarGutter::StableBothEdges => "stable both-edges",
+            }
+            .into()
+        });
+        set("width", style.size.width != defaults.size.width, &|| dimension_css(style.size.width));
+        set("height", style.size.height != defaults.size.height, &|| dimension_css(style.size.height));
+        set("min-width", style.min_size.width != defaults.min_size.width, &|| dimension_css(style.min_size.width));
+        set("min-height", style.min_size.height != defaults.min_size.height, &|| dimension_css(style.min_size.height));
+        set("max-width", style.max_size.width != defaults.max_size.width, &|| dimension_css(style.max_size.width));
+        set("max-height", style.max_size.height != defaults.max_size.height, &|| dimension_css(style.max_size.height));
+        set("aspect-ratio", style.aspect_ratio != defaults.aspect_ratio, &|| match style.aspect_ratio {
+            Some(ratio) => format!("{ratio}"),
+            None => "auto".into(),
+        });
+        set("inset", style.inset != defaults.inset, &|| rect_css(style.inset, length_percentage_auto_css));
+        set("margin", style.margin != defaults.margin, &|| rect_css(style.margin, length_percentage_auto_css));
+        set("padding", style.padding != defaults.padding, &|| rect_css(style.padding, length_percentage_css));
+        set("border-width", style.border != defaults.border, &|| rect_css(style.border, length_percentage_css));
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        {
+            set("gap", style.gap != defaults.gap, &|| {
+                format!("{} {}", length_percentage_css(style.gap.height), length_percentage_css(style.gap.width))
+            });
+            set("align-items", style.align_items != defaults.align_items, &|| align_items_css(style.align_items));
+            set("align-self", style.align_self != defaults.align_self, &|| align_items_css(style.align_self));
+            set("align-content", style.align_content != defaults.align_content, &|| {
+                align_content_css(style.align_content)
+            });
+            set("justify-content", style.justify_content != defaults.justify_content, &|| {
+                align_content_css(style.justify_content)
+            });
+        }
+        #[cfg(feature = "flexbox")]
+        {
+            set("flex-direction", style.flex_direction != defaults.flex_direction, &|| {
+                match style.flex_direction {
+                    FlexDirection::Row => "row",
+                    FlexDirection::Column => "column",
+                    FlexDirection::RowReverse => "row-reverse",
+                    FlexDirection::ColumnReverse => "column-reverse",
+                }
+                .into()
+            });
+            set("flex-wrap", style.flex_wrap != defaults.flex_wrap, &|| {
+                match style.flex_wrap {
+                    FlexWrap::NoWrap => "nowrap",
+                    FlexWrap::Wrap => "wrap",
+                    FlexWrap::WrapReverse => "wrap-reverse",
+                }
+                .into()
+            });
+            set("flex-grow", style.flex_grow != defaults.flex_grow, &|| format!("{}", style.flex_grow));
+            set("flex-shrink", style.flex_shrink != defaults.flex_shrink, &|| format!("{}", style.flex_shrink));
+            set("flex-basis", style.flex_basis != defaults.flex_basis, &|| dimension_css(style.flex_basis));
+        }
+        #[cfg(feature = "grid")]
+        {
+            set("justify-items", style.justify_items != defaults.justify_items, &|| {
+                align_items_css(style.justify_items)
+            });
+            set("justify-self", style.justify_self != defaults.justify_self, &|| align_items_css(style.justify_self));
+            set("grid-template-rows", !style.grid_template_rows.is_empty(), &|| {
+                track_list_css(&style.grid_template_rows)
+            });
+            set("grid-template-columns", !style.grid_template_columns.is_empty(), &|| {
+                track_list_css(&style.grid_template_columns)
+            });
+        }
+        map
+    }
+}
+
+/// Sets a single style property
+fn apply_property(style: &mut Style, property: &str, value: &str) -> Result<(), InlineStyleErrorKind> {
+    /// Parses a value with a parsing function which returns `None` for invalid values
+    fn parse<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Result<T, InlineStyleErrorKind> {
+        parse(value).ok_or(InlineStyleErrorKind::InvalidValue)
+    }
+
+    match property {
+        "display" => style.display = parse(value, display)?,
+        "position" => {
+            style.position = parse(value, |value| match value {
+                "relative" => Some(Position::Relative),
+                "absolute" => Some(Position::Absolute),
+                _ => None,
+            })?
+        }
+        "box-sizing" => {
+            style.box_sizing = parse(value, |value| match value {
+                "border-box" => Some(BoxSizing::BorderBox),
+                "content-box" => Some(BoxSizing::ContentBox),
+                _ => None,
+            })?
+        }
+        "direction" => {
+            style.direction = parse(value, |value| match value {
+                "inherit" => Some(Direction::Inherit),
+                "ltr" => Some(Direction::Ltr),
+                "rtl" => Some(Direction::Rtl),
+                _ => None,
+            })?
+        }
+        "writing-mode" => {
+            style.writing_mode = parse(value, |value| match value {
+                "inherit" => Some(WritingMode::Inherit),
+                "horizontal-tb" => Some(WritingMode::HorizontalTb),
+                "vertical-rl" => Some(WritingMode::VerticalRl),
+                "vertical-lr" => Some(WritingMode::VerticalLr),
+                _ => None,
+            })?
+        }
+        "overflow" => {
+            style.overflow = parse(value, |value| match *values(value).as_slice() {
+                [both] => Some(Point { x: overflow(both)?, y: overflow(both)? }),
+                [x, y] => Some(Point { x: overflow(x)?, y: overflow(y)? }),
+                _ => None,
+            })?
+        }
+        "overflow-x" => style.overflow.x = parse(value, overflow)?,
+        "overflow-y" => style.overflow.y = parse(value, overflow)?,
+        "scrollbar-gutter" => {
+            style.scrollbar_gutter = parse(value, |value| match *values(value).as_slice() {
+                ["auto"] => Some(ScrollbarGutter::Auto),
+                ["stable"] => Some(ScrollbarGutter::Stable),
+                ["stable", "both-edges"] | ["both-edges", "stable"] => Some(ScrollbarGutter::StableBothEdges),
+                _ => None,
+            })?
+        }
+        "width" => style.size.width = parse(value, dimension)?,
+        "height" => style.size.height = parse(value, dimension)?,
+        "min-width" => style.min_size.width = parse(value, dimension)?,
+        "min-height" => style.min_size.height = parse(value, dimension)?,
+        "max-width" => style.max_size.width = parse(value, dimension)?,
+        "max-height" => style.max_size.height = parse(value, dimension)?,
+        "aspect-ratio" => {
+            style.aspect_ratio = parse(value, |value| match value {
+                "auto" => Some(None),
+                _ => match value.split_once('/') {
+                    Some((width, height)) => Some(Some(number(width.trim())? / number(height.trim())?)),
+                    None => Some(Some(number(value)?)),
+                },
+            })?
+        }
+        "left" => style.inset.left = parse(value, length_percentage_auto)?,
+        "right" => style.inset.right = parse(value, length_percentage_auto)?,
+        "top" => style.inset.top = parse(value, length_percentage_auto)?,
+        "bottom" => style.inset.bottom = parse(value, length_percentage_auto)?,
+        "inset" => style.inset = parse(value, |value| rect(value, length_percentage_auto))?,
+        "margin" => style.margin = parse(value, |value| rect(value, length_percentage_auto))?,
+        "margin-left" => style.margin.left = parse(value, length_percentage_auto)?,
+        "margin-right" => style.margin.right = parse(value, length_percentage_auto)?,
+        "margin-top" => style.margin.top = parse(value, length_percentage_auto)?,
+        "margin-bottom" => style.margin.bottom = parse(value, length_percentage_auto)?,
+        "padding" => style.padding = parse(value, |value| rect(value, length_percentage))?,
+        "padding-left" => style.padding.left = parse(value, length_percentage)?,
+        "padding-right" => style.padding.right = parse(value, length_percentage)?,
+        "padding-top" => style.padding.top = parse(value, length_percentage)?,
+        "padding-bottom" => style.padding.bottom = parse(value, length_percentage)?,
+        "border-width" => style.border = parse(value, |value| rect(value, length_percentage))?,
+        "border-left-width" => style.border.left = parse(value, length_percentage)?,
+        "border-right-width" => style.border.right = parse(value, length_percentage)?,
+        "border-top-width" => style.border.top = parse(value, length_percentage)?,
+        "border-bottom-width" => style.border.bottom = parse(value, length_percentage)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "gap" => {
+            style.gap = parse(value, |value| match *values(value).as_slice() {
+                [both] => Some(Size { width: length_percentage(both)?, height: length_percentage(both)? }),
+                [row, column] => Some(Size { width: length_percentage(column)?, height: length_percentage(row)? }),
+                _ => None,
+            })?
+        }
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "row-gap" => style.gap.height = parse(value, length_percentage)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "column-gap" => style.gap.width = parse(value, length_percentage)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-items" => style.align_items = parse(value, align_items)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-self" => style.align_self = parse(value, align_items)?,
+        #[cfg(feature = "grid")]
+        "justify-items" => style.justify_items = parse(value, align_items)?,
+        #[cfg(feature = "grid")]
+        "justify-self" => style.justify_self = parse(value, align_items)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "align-content" => style.align_content = parse(value, align_content)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "justify-content" => style.justify_content = parse(value, align_content)?,
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "place-content" => {
+            let (align, justify) = parse(value, |value| match *values(value).as_slice() {
+                [both] => Some((align_content(both)?, align_content(both)?)),
+                [align, justify] => Some((align_content(align)?, align_content(justify)?)),
+                _ => None,
+            })?;
+            style.set_place_content(align, justify);
+        }
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "place-items" => {
+            let (align, justify) = parse(value, place_items)?;
+            style.set_place_items(align, justify);
+        }
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        "place-self" => {
+            let (align, justify) = parse(value, place_items)?;
+            style.set_place_self(align, justify);
+        }
+        #[cfg(feature = "flexbox")]
+        "flex-direction" => {
+            style.flex_direction = parse(value, |value| match value {
+                "row" => Some(FlexDirection::Row),
+                "column" => Some(FlexDirection::Column),
+                "row-reverse" => Some(FlexDirection::RowReverse),
+                "column-reverse" => Some(FlexDirection::ColumnReverse),
+                _ => None,
+            })?
+        }
+        #[cfg(feature = "flexbox")]
+        "flex-wrap" => {
+            style.flex_wrap = parse(value, |value| match value {
+                "nowrap" => Some(FlexWrap::NoWrap),
+                "wrap" => Some(FlexWrap::Wrap),
+                "wrap-reverse" => Some(FlexWrap::WrapReverse),
+                _ => None,
+            })?
+        }
+        #[cfg(feature = "flexbox")]
+        "flex-grow" => style.flex_grow = parse(value, non_negative_number)?,
+        #[cfg(feature = "flexbox")]
+        "flex-shrink" => style.flex_shrink = parse(value, non_negative_number)?,
+        #[cfg(feature = "flexbox")]
+        "flex-basis" => style.flex_basis = parse(value, flex_basis)?,
+        #[cfg(feature = "flexbox")]
+        "flex" => (style.flex_grow, style.flex_shrink, style.flex_basis) = parse(value, flex)?,
+        #[cfg(feature = "grid")]
+        "grid-template-rows" => {
+            style.grid_template_rows =
+                crate::style::parse_track_list(value).map_err(|_| InlineStyleErrorKind::InvalidValue)?
+        }
+        #[cfg(feature = "grid")]
+        "grid-template-columns" => {
+            style.grid_template_columns =
+                crate::style::parse_track_list(value).map_err(|_| InlineStyleErrorKind::InvalidValue)?
+        }
+        _ => return Err(InlineStyleErrorKind::UnknownProperty),
+    }
+    Ok(())
+}
+
+/// Splits a value into its whitespace separated components
+fn values(value: &str) -> Vec<&str> {
+    value.split_whitespace().collect()
+}
+
+/// Parses a number
+fn number(value: &str) -> Option<f32> {
+    value.parse().ok().filter(|value: &f32| value.is_finite())
+}
+
+/// Parses a number which must not be negative
+#[cfg(feature = "flexbox")]
+fn non_negative_number(value: &str) -> Option<f32> {
+    number(value).filter(|value| *value >= 0.0)
+}
+
+/// Parses a `flex` shorthand into a grow factor, shrink factor and basis
+#[cfg(feature = "flexbox")]
+fn flex(value: &str) -> Option<(f32, f32, Dimension)> {
+    // A basis omitted from the shorthand is zero, rather than the initial value of `flex-basis`
+    let omitted_basis = Dimension::Length(0.0);
+    match *values(value).as_slice() {
+        ["none"] => Some((0.0, 0.0, Dimension::Auto)),
+        ["auto"] => Some((1.0, 1.0, Dimension::Auto)),
+        ["initial"] => Some((0.0, 1.0, Dimension::Auto)),
+        // A single number is the grow factor, and a second number the shrink factor, rather than a unitless basis
+        [grow] if number(grow).is_some() => Some((non_negative_number(grow)?, 1.0, omitted_basis)),
+        [basis] => Some((1.0, 1.0, flex_basis(basis)?)),
+        [grow, shrink] if number(shrink).is_some() => {
+            Some((non_negative_number(grow)?, non_negative_number(shrink)?, omitted_basis))
+        }
+        [grow, basis] => Some((non_negative_number(grow)?, 1.0, flex_basis(basis)?)),
+        [grow, shrink, basis] => Some((non_negative_number(grow)?, non_negative_number(shrink)?, flex_basis(basis)?)),
+        _ => None,
+    }
+}
+
+/// Parses a `flex-basis`: a length, percentage, `auto` or `content`
+#[cfg(feature = "flexbox")]
+fn flex_basis(value: &str) -> Option<Dimension> {
+    match value {
+        "content" => Some(Dimension::Content),
+        _ => dimension(value),
+    }
+}
+
+/// Parses a length in pixels (with a `px` unit or unitless) or a percentage
+fn length_percentage(value: &str) -> Option<LengthPercentage> {
+    match value.strip_suffix('%') {
+        Some(percent) => Some(LengthPercentage::Percent(number(percent)? / 100.0)),
+        None => Some(LengthPercentage::Length(number(value.strip_suffix("px").unwrap_or(value))?)),
+    }
+}
+
+/// Parses a length, percentage or `auto`
+fn length_percentage_auto(value: &str) -> Option<LengthPercentageAuto> {
+    match value {
+        "auto" => Some(LengthPercentageAuto::Auto),
+        _ => Some(length_percentage(value)?.into()),
+    }
+}
+
+/// Parses a length, percentage, `auto` or percentage of the root's size (`root(50%)`)
+fn dimension(value: &str) -> Option<Dimension> {
+    match value {
+        "auto" => Some(Dimension::Auto),
+        _ => match value.strip_prefix("root(").and_then(|value| value.strip_suffix("%)")) {
+            Some(percent) => Some(Dimension::RootPercent(number(percent)? / 100.0)),
+            None => Some(length_percentage(value)?.into()),
+        },
+    }
+}
+
+/// Parses one to four values in the order of the CSS shorthands: top, right, bottom, left
+fn rect<T: Copy>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Rect<T>> {
+    let (top, right, bottom, left) = match *values(value).as_slice() {
+        [all] => (all, all, all, all),
+        [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+        [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+        [top, right, bottom, left] => (top, right, bottom, left),
+        _ => return None,
+    };
+    Some(Rect { left: parse(left)?, right: parse(right)?, top: parse(top)?, bottom: parse(bottom)? })
+}
+
+/// Parses a `display` value
+fn display(value: &str) -> Option<Display> {
+    match value {
+        "none" => Some(Display::None),
+        #[cfg(feature = "block_layout")]
+        "block" => Some(Display::Block),
+        #[cfg(feature = "flexbox")]
+        "flex" => Some(Display::Flex),
+        #[cfg(feature = "grid")]
+        "grid" => Some(Display::Grid),
+        #[cfg(feature = "inline_layout")]
+        "inline" => Some(Display::Inline),
+        _ => None,
+    }
+}
+
+/// Parses an `overflow` value
+fn overflow(value: &str) -> Option<Overflow> {
+    match value {
+        "visible" => Some(Overflow::Visible),
+        "clip" => Some(Overflow::Clip),
+        "hidden" => Some(Overflow::Hidden),
+        "scroll" => Some(Overflow::Scroll),
+        _ => None,
+    }
+}
+
+/// Parses an `align-items`, `align-self`, `justify-items` or `justify-self` value (`normal` and `auto` are `None`)
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn align_items(value: &str) -> Option<Option<AlignItems>> {
+    match value {
+        "normal" | "auto" => Some(None),
+        "start" => Some(Some(AlignItems::Start)),
+        "end" => Some(Some(AlignItems::End)),
+        "flex-start" => Some(Some(AlignItems::FlexStart)),
+        "flex-end" => Some(Some(AlignItems::FlexEnd)),
+        "center" => Some(Some(AlignItems::Center)),
+        "baseline" | "first baseline" => Some(Some(AlignItems::Baseline)),
+        "last baseline" => Some(Some(AlignItems::LastBaseline)),
+        "stretch" => Some(Some(AlignItems::Stretch)),
+        _ => None,
+    }
+}
+
+/// Parses a `place-items` or `place-self` value into its align and justify values. A single value applies to both.
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn place_items(value: &str) -> Option<(Option<AlignItems>, Option<AlignItems>)> {
+    // Baseline positions may be written as two keywords (e.g. `last baseline`), so join them before matching
+    let mut keywords = values(value).into_iter();
+    let mut items = Vec::new();
+    while let Some(keyword) = keywords.next() {
+        let item = match keyword {
+            "first" | "last" => align_items(&format!("{keyword} {}", keywords.next()?))?,
+            _ => align_items(keyword)?,
+        };
+        items.push(item);
+    }
+
+    match *items.as_slice() {
+        [both] => Some((both, both)),
+        [align, justify] => Some((align, justify)),
+        _ => None,
+    }
+}
+
+/// Parses an `align-content` or `justify-content` value (`normal` is `None`)
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn align_content(value: &str) -> Option<Option<AlignContent>> {
+    match value {
+        "normal" => Some(None),
+        "start" => Some(Some(AlignContent::Start)),
+        "end" => Some(Some(AlignContent::End)),
+        "flex-start" => Some(Some(AlignContent::FlexStart)),
+        "flex-end" => Some(Some(AlignContent::FlexEnd)),
+        "center" => Some(Some(AlignContent::Center)),
+        "stretch" => Some(Some(AlignContent::Stretch)),
+        "space-between" => Some(Some(AlignContent::SpaceBetween)),
+        "space-evenly" => Some(Some(AlignContent::SpaceEvenly)),
+        "space-around" => Some(Some(AlignContent::SpaceAround)),
+        _ => None,
+    }
+}
+
+/// Formats a length in pixels or a percentage
+fn length_percentage_css(value: LengthPercentage) -> String {
+    match value {
+        LengthPercentage::Length(length) => format!("{length}px"),
+        LengthPercentage::Percent(percent) => format!("{}%", percent * 100.0),
+    }
+}
+
+/// Formats a length, percentage or `auto`
+fn length_percentage_auto_css(value: LengthPercentageAuto) -> String {
+    match value {
+        LengthPercentageAuto::Length(length) => length_percentage_css(LengthPercentage::Length(length)),
+        LengthPercentageAuto::Percent(percent) => length_percentage_css(LengthPercentage::Percent(percent)),
+        LengthPercentageAuto::Auto => "auto".into(),
+    }
+}
+
+/// Formats a length, percentage, `auto`, `content` or percentage of the root's size
+fn dimension_css(value: Dimension) -> String {
+    match value {
+        Dimension::Length(length) => length_percentage_css(LengthPercentage::Length(length)),
+        Dimension::Percent(percent) => length_percentage_css(LengthPercentage::Percent(percent)),
+        Dimension::Auto => "auto".into(),
+        Dimension::Content => "content".into(),
+        Dimension::RootPercent(percent) => format!("root({}%)", percent * 100.0),
+    }
+}
+
+/// Formats the four sides of a rect in the order of the CSS shorthands: top, right, bottom, left
+fn rect_css<T: PartialEq + Copy>(rect: Rect<T>, format: fn(T) -> String) -> String {
+    if rect.left == rect.right && rect.top == rect.bottom {
+        if rect.left == rect.top {
+            format(rect.top)
+        } else {
+            format!("{} {}", format(rect.top), format(rect.left))
+        }
+    } else {
+        format!("{} {} {} {}", format(rect.top), format(rect.right), format(rect.bottom), format(rect.left))
+    }
+}
+
+/// Formats a `display` value
+fn display_css(value: Display) -> &'static str {
+    match value {
+        Display::None => "none",
+        #[cfg(feature = "block_layout")]
+        Display::Block => "block",
+        #[cfg(feature = "flexbox")]
+        Display::Flex => "flex",
+        #[cfg(feature = "grid")]
+        Display::Grid => "grid",
+        #[cfg(feature = "inline_layout")]
+        Display::Inline => "inline",
+    }
+}
+
+/// Formats an `overflow` value
+fn overflow_css(value: Overflow) -> &'static str {
+    match value {
+        Overflow::Visible => "visible",
+        Overflow::Clip => "clip",
+        Overflow::Hidden => "hidden",
+        Overflow::Scroll => "scroll",
+    }
+}
+
+/// Formats an `align-items`, `align-self`, `justify-items` or `justify-self` value
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn align_items_css(value: Option<AlignItems>) -> String {
+    match value {
+        None => "normal",
+        Some(AlignItems::Start) => "start",
+        Some(AlignItems::End) => "end",
+        Some(AlignItems::FlexStart) => "flex-start",
+        Some(AlignItems::FlexEnd) => "flex-end",
+        Some(AlignItems::Center) => "center",
+        Some(AlignItems::Baseline) => "baseline",
+        Some(AlignItems::LastBaseline) => "last baseline",
+        Some(AlignItems::Stretch) => "stretch",
+    }
+    .into()
+}
+
+/// Formats an `align-content` or `justify-content` value
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn align_content_css(value: Option<AlignContent>) -> String {
+    match value {
+        None => "normal",
+        Some(AlignContent::Start) => "start",
+        Some(AlignContent::End) => "end",
+        Some(AlignContent::FlexStart) => "flex-start",
+        Some(AlignContent::FlexEnd) => "flex-end",
+        Some(AlignContent::Center) => "center",
+        Some(AlignContent::Stretch) => "stretch",
+        Some(AlignContent::SpaceBetween) => "space-between",
+        Some(AlignContent::SpaceEvenly) => "space-evenly",
+        Some(AlignContent::SpaceAround) => "space-around",
+    }
+    .into()
+}
+
+/// Formats a track list in the syntax accepted by [`parse_track_list`](crate::style::parse_track_list)
+#[cfg(feature = "grid")]
+fn track_list_css(tracks: &[TrackSizingFunction]) -> String {
+    let tracks: Vec<String> = tracks
+        .iter()
+        .map(|track| match track {
+            TrackSizingFunction::Single(track) => track_css(track),
+            TrackSizingFunction::Repeat(repetition, tracks) => {
+                let count = match repetition {
+                    GridTrackRepetition::AutoFill => "auto-fill".into(),
+                    GridTrackRepetition::AutoFit => "auto-fit".into(),
+                    GridTrackRepetition::Count(count) => format!("{count}"),
+                };
+                let tracks: Vec<String> = tracks.iter().map(track_css).collect();
+                format!("repeat({count}, {})", tracks.join(" "))
+            }
+        })
+        .collect();
+    tracks.join(" ")
+}
+
+/// Formats a single track size, using the shortest form which parses back to the same track
+#[cfg(feature = "grid")]
+fn track_css(track: &NonRepeatedTrackSizingFunction) -> String {
+    use MaxTrackSizingFunction as Max;
+    use MinTrackSizingFunction as Min;
+
+    match (track.min, track.max) {
+        (Min::Auto, Max::Fraction(fraction)) => format!("{fraction}fr"),
+        (Min::Auto, Max::FitContent(limit)) => format!("fit-content({})", length_percentage_css(limit)),
+        (Min::Auto, Max::Auto) => "auto".into(),
+        (Min::MinContent, Max::MinContent) => "min-content".into(),
+        (Min::MaxContent, Max::MaxContent) => "max-content".into(),
+        (Min::Fixed(min), Max::Fixed(max)) if min == max => length_percentage_css(min),
+        (min, max) => {
+            let min = match min {
+                Min::Fixed(value) => length_percentage_css(value),
+                Min::MinContent => "min-content".into(),
+                Min::MaxContent => "max-content".into(),
+                Min::Auto => "auto".into(),
+            };
+            let max = match max {
+                Max::Fixed(value) => length_percentage_css(value),
+                Max::MinContent => "min-content".into(),
+                Max::MaxContent => "max-content".into(),
+                Max::FitContent(limit) => format!("fit-content({})", length_percentage_css(limit)),
+                Max::Auto => "auto".into(),
+                Max::Fraction(fraction) => format!("{fraction}fr"),
+            };
+            format!("minmax({min}, {max})")
+        }
+    }
+}
+
+/// Collects the declarations parsed by [`lightningcss`] which are supported by [`Style`], in order, with `!important`
+/// declarations last so that they take precedence
+///
+/// Unsupported properties (such as `color`) and values (such as lengths in `em`) are skipped, so that the map can
+/// always be converted into a `Style`.
+#[cfg(feature = "lightningcss")]
+impl<'i> From<&lightningcss::declaration::DeclarationBlock<'i>> for StyleMap {
+    fn from(block: &lightningcss::declaration::DeclarationBlock<'i>) -> Self {
+        let mut map = StyleMap::new();
+        for property in block.declarations.iter().chain(block.important_declarations.iter()) {
+            let value = match property.value_to_css_string(lightningcss::stylesheet::PrinterOptions::default()) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            let name = property_name(property.property_id().name());
+            if apply_property(&mut Style::default(), &name, &value).is_ok() {
+                map.insert(&name, value);
+            }
+        }
+        map
+    }
+}
+
+/// Collects the declarations of a `style` attribute parsed by [`lightningcss`]. See the conversion from a
+/// [`DeclarationBlock`](lightningcss::declaration::DeclarationBlock).
+#[cfg(feature = "lightningcss")]
+impl<'i> From<&lightningcss::stylesheet::StyleAttribute<'i>> for StyleMap {
+    fn from(attribute: &lightningcss::stylesheet::StyleAttribute<'i>) -> Self {
+        StyleMap::from(&attribute.declarations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_style_properties, parse_inline_style, property_name, InlineStyleError, InlineStyleErrorKind, StyleMap,
+    };
+    use crate::geometry::Point;
+    use crate::prelude::*;
+    #[cfg(feature = "grid")]
+    use crate::style::parse_track_list;
+    use crate::style::WritingMode;
+
+    #[test]
+    fn shorthands_are_expanded() {
+        let style =
+            parse_inline_style("margin: 1px 2px 3px; padding: 5%; inset: 1px auto; border-width: 1px 2px").unwrap();
+        assert_eq!(style.margin, Rect::xyzw(2.0, 2.0, 1.0, 3.0).map(LengthPercentageAuto::Length));
+        assert_eq!(style.padding, Rect::all(LengthPercentage::Percent(0.05)));
+        assert_eq!(style.inset, Rect::axes(LengthPercentageAuto::Auto, LengthPercentageAuto::Length(1.0)));
+        assert_eq!(style.border, Rect::axes(LengthPercentage::Length(2.0), LengthPercentage::Length(1.0)));
+
+        let style = parse_inline_style("gap: 1px 2px; margin: 0; margin-top: 4px").unwrap();
+        assert_eq!(style.gap, Size { width: LengthPercentage::Length(2.0), height: LengthPercentage::Length(1.0) });
+        assert_eq!(style.margin.top, LengthPercentageAuto::Length(4.0));
+        assert_eq!(style.margin.bottom, LengthPercentageAuto::Length(0.0));
+    }
+
+    #[test]
+    #[cfg(feature = "flexbox")]
+    fn flex_shorthand_is_expanded() {
+        let flex = |value: &str| {
+            let style = parse_inline_style(&format!("flex: {value}")).unwrap();
+            (style.flex_grow, style.flex_shrink, style.flex_basis)
+        };
+        assert_eq!(flex("none"), (0.0, 0.0, Dimension::Auto));
+        assert_eq!(flex("auto"), (1.0, 1.0, Dimension::Auto));
+        assert_eq!(flex("2"), (2.0, 1.0, Dimension::Length(0.0)));
+        assert_eq!(flex("30px"), (1.0, 1.0, Dimension::Length(30.0)));
+        assert_eq!(flex("2 0"), (2.0, 0.0, Dimension::Length(0.0)));
+        assert_eq!(flex("2 50%"), (2.0, 1.0, Dimension::Percent(0.5)));
+        assert_eq!(flex("2 3 auto"), (2.0, 3.0, Dimension::Auto));
+        assert_eq!(flex("1 content"), (1.0, 1.0, Dimension::Content));
+        assert_eq!(parse_inline_style("flex-basis: content").unwrap().flex_basis, Dimension::Content);
+        assert_eq!(
+            parse_inline_style("width: content"),
+            Err(InlineStyleError { offset: 0, kind: InlineStyleErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            parse_inline_style("flex: -1"),
+            Err(InlineStyleError { offset: 0, kind: InlineStyleErrorKind::InvalidValue })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn place_shorthands_are_expanded() {
+        let style = parse_inline_style(
+            "place-content: center space-between; place-items: end; place-self: last baseline start",
+        )
+        .unwrap();
+        assert_eq!(
+            (style.align_content, style.justify_content),
+            (Some(AlignContent::Center), Some(JustifyContent::SpaceBetween))
+        );
+        assert_eq!((style.align_items, style.justify_items), (Some(AlignItems::End), Some(JustifyItems::End)));
+        assert_eq!((style.align_self, style.justify_self), (Some(AlignSelf::LastBaseline), Some(JustifySelf::Start)));
+
+        let style = parse_inline_style("place-items: first baseline").unwrap();
+        assert_eq!(
+            (style.align_items, style.justify_items),
+            (Some(AlignItems::Baseline), Some(JustifyItems::Baseline))
+        );
+        assert_eq!(
+            parse_inline_style("place-self: last"),
+            Err(InlineStyleError { offset: 0, kind: InlineStyleErrorKind::InvalidValue })
+        );
+    }
+
+    #[test]
+    fn property_names_are_normalised() {
+        assert_eq!(property_name("marginLeft"), "margin-left");
+        assert_eq!(property_name("margin_left"), "margin-left");
+        assert_eq!(property_name(" MARGIN-LEFT "), "margin-left");
+        assert_eq!(property_name("Margin-Left"), "margin-left");
+
+        let mut style = Style::default();
+        apply_style_properties(&mut style, vec![("minWidth", "10px"), ("max_height", "50%")]).unwrap();
+        assert_eq!(style.min_size.width, Dimension::Length(10.0));
+        assert_eq!(style.max_size.height, Dimension::Percent(0.5));
+        assert_eq!(
+            apply_style_properties(&mut style, [("width", "1px"), ("colour", "red")]),
+            Err(InlineStyleError { offset: 1, kind: InlineStyleErrorKind::UnknownProperty })
+        );
+    }
+
+    #[test]
+    fn errors_report_declaration_offset() {
+        assert_eq!(
+            parse_inline_style("width: 1px; height"),
+            Err(InlineStyleError { offset: 11, kind: InlineStyleErrorKind::InvalidDeclaration })
+        );
+        assert_eq!(
+            parse_inline_style("width: wide"),
+            Err(InlineStyleError { offset: 0, kind: InlineStyleErrorKind::InvalidValue })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "flexbox")]
+    fn style_map_round_trips_styles() {
+        let style = Style {
+            display: Display::Flex,
+            position: Position::Absolute,
+            direction: Direction::Rtl,
+            writing_mode: WritingMode::VerticalRl,
+            overflow: Point { x: Overflow::Hidden, y: Overflow::Scroll },
+            size: Size { width: Dimension::Length(120.5), height: Dimension::Percent(0.5) },
+            min_size: Size { width: Dimension::RootPercent(0.25), height: Dimension::Auto },
+            max_size: Size { width: Dimension::Auto, height: Dimension::Length(300.0) },
+            aspect_ratio: Some(1.5),
+            inset: Rect::xyzw(1.0, 2.0, 3.0, 4.0).map(LengthPercentageAuto::Length),
+            margin: Rect::axes(LengthPercentageAuto::Auto, LengthPercentageAuto::Length(8.0)),
+            padding: Rect::all(LengthPercentage::Percent(0.25)),
+            gap: Size { width: LengthPercentage::Length(2.0), height: LengthPercentage::Length(1.0) },
+            align_items: Some(AlignItems::Center),
+            justify_content: Some(JustifyContent::SpaceBetween),
+            flex_direction: FlexDirection::ColumnReverse,
+            flex_wrap: FlexWrap::Wrap,
+            flex_grow: 2.0,
+            flex_basis: Dimension::Length(0.0),
+            ..Default::default()
+        };
+        let map = StyleMap::from(&style);
+        assert_eq!(map.get("margin"), Some("8px auto"));
+        assert_eq!(map.get("inset"), Some("3px 2px 4px 1px"));
+        assert_eq!(map.get("gap"), Some("1px 2px"));
+        assert_eq!(map.get("min-width"), Some("root(25%)"));
+        assert_eq!(map.get("writing-mode"), Some("vertical-rl"));
+        assert_eq!(Style::try_from(&map), Ok(style));
+        assert_eq!(parse_inline_style(&map.to_string()), Style::try_from(&map));
+
+        assert!(StyleMap::from(&Style::default()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn style_map_round_trips_grid_templates() {
+        let style = Style {
+            display: Display::Grid,
+            grid_template_columns: parse_track_list(
+                "1fr auto 10px 50% min-content max-content fit-content(20px) minmax(10px, 2fr) repeat(auto-fill, 5px)",
+            )
+            .unwrap(),
+            grid_template_rows: vec![repeat(3, vec![minmax(length(1.0), max_content())])],
+            justify_items: Some(AlignItems::End),
+            ..Default::default()
+        };
+        let map = StyleMap::from(&style);
+        assert_eq!(map.get("grid-template-rows"), Some("repeat(3, minmax(1px, max-content))"));
+        assert_eq!(Style::try_from(&map), Ok(style));
+    }
+
+    #[test]
+    fn style_map_insert_replaces_and_reorders() {
+        let mut map = StyleMap::new();
+        map.insert("margin", "1px");
+        map.insert("marginLeft", "2px");
+        map.insert("margin", "3px");
+        assert_eq!(map.to_string(), "margin-left: 2px; margin: 3px");
+        assert_eq!(map.remove("margin_left"), Some("2px".into()));
+        assert_eq!(map.len(), 1);
+        assert_eq!(Style::try_from(&map).unwrap().margin, Rect::all(LengthPercentageAuto::Length(3.0)));
+    }
+
+    #[test]
+    #[cfg(feature = "lightningcss")]
+    fn style_map_is_collected_from_lightningcss_declarations() {
+        use lightningcss::declaration::DeclarationBlock;
+        use lightningcss::stylesheet::ParserOptions;
+
+        let css = "width: 10px !important; color: red; height: 2em; margin: 1px 2px; width: 20px; flex-grow: 2";
+        let block = DeclarationBlock::parse_string(css, ParserOptions::default()).unwrap();
+        let map = StyleMap::from(&block);
+
+        // The unsupported `color` property and `em` length are skipped, and the `!important` width takes precedence
+        assert_eq!(map.get("color"), None);
+        assert_eq!(map.get("height"), None);
+        let style = Style::try_from(&map).unwrap();
+        assert_eq!(style.size.width, Dimension::Length(10.0));
+        assert_eq!(style.margin, Rect::axes(LengthPercentageAuto::Length(2.0), LengthPercentageAuto::Length(1.0)));
+        assert_eq!(style.flex_grow, 2.0);
+    }
+
+    #[test]
+    #[cfg(feature = "lightningcss")]
+    fn style_map_is_collected_from_a_lightningcss_style_attribute() {
+        use lightningcss::stylesheet::{ParserOptions, StyleAttribute};
+
+        let attribute = StyleAttribute::parse("padding: 5%; min-width: 10px", ParserOptions::default()).unwrap();
+        let style = Style::try_from(&StyleMap::from(&attribute)).unwrap();
+        assert_eq!(style.padding, Rect::all(LengthPercentage::Percent(0.05)));
+        assert_eq!(style, parse_inline_style("padding: 5%; min-width: 10px").unwrap());
+    }
+}
@@ -61,6 +61,7 @@
 #![cfg_attr(feature = "document-features", doc = document_features::document_features!())]
 // annotate items with their required features (gated by docsrs flag as this requires the nightly toolchain)
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![deny(unsafe_code)]
 // #![forbid(unsafe_code)]
@@ -80,9 +81,21 @@ extern crate alloc;
 #[cfg(feature = "serde")]
 extern crate serde;
 
+#[cfg(feature = "binary")]
+pub mod binary;
 pub mod compute;
+#[cfg(all(feature = "taffy_tree", any(feature = "std", feature = "alloc")))]
+pub mod diff;
+#[cfg(feature = "visualize")]
+pub mod export;
 pub mod geometry;
+#[cfg(feature = "inline_style")]
+pub mod inline_style;
+#[cfg(feature = "markup")]
+pub mod markup;
 pub mod prelude;
+#[cfg(feature = "record")]
+pub mod record;
 pub mod style;
 pub mod style_helpers;
 pub mod tree;
@@ -99,13 +112,19 @@ pub use crate::compute::compute_block_layout;
 #[cfg(feature = "flexbox")]
 #[doc(inline)]
 pub use crate::compute::compute_flexbox_layout;
-#[cfg(feature = "grid")]
+#[cfg(feature = "inline_layout")]
 #[doc(inline)]
-pub use crate::compute::compute_grid_layout;
+pub use crate::compute::{
+    break_atoms_into_lines, compute_inline_layout, InlineAtom, InlineFormatInput, LineBox, LineBoxItem,
+};
 #[doc(inline)]
 pub use crate::compute::{
-    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout,
+    compute_cached_layout, compute_constrained_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout,
+    round_layout, BoxConstraints, LayoutConstraints,
 };
+#[cfg(feature = "grid")]
+#[doc(inline)]
+pub use crate::compute::{compute_grid_layout, GridOccupancyStats};
 #[doc(inline)]
 pub use crate::style::Style;
 #[doc(inline)]
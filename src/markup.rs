@@ -0,0 +1,382 @@
+//! Build a [`TaffyTree`] from a simple XML/HTML-like markup, for prototyping layouts
+//!
+//! Each element becomes a node, and its `style` attribute is parsed as a list of CSS declarations. For example:
+//!
+//! ```text
+//! <div id="root" style="display: flex; width: 300px; height: 200px; padding: 10px; gap: 10px">
+//!   <div id="sidebar" style="width: 80px" />
+//!   <div id="content" style="flex-grow: 1" />
+//! </div>
+//! ```
+//!
+//! An element with a `preset` attribute starts from the named [style preset](crate::TaffyTree::set_style_preset), and
+//! other elements start from the tree's [`default_style`](crate::TaffyTree::default_style), so that the `style`
+//! attribute only needs to contain the properties which differ from it.
+//!
+//! Element names are not interpreted (but closing tags must match their opening tag), and attributes other than `id`,
+//! `preset` and `style` are ignored, as are text content, comments, processing instructions and doctypes. Entities are not
+//! decoded. Styles are parsed as described in the [`inline_style`](crate::inline_style) module.
+use std::collections::{HashMap, HashSet};
+use std::string::String;
+
+use crate::inline_style::{apply_inline_style, InlineStyleErrorKind};
+use crate::tree::{NodeId, TaffyTree};
+use crate::util::sys::Vec;
+
+/// The reason that markup failed to load. See [`MarkupError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkupErrorKind {
+    /// The input ended part way through an element
+    UnexpectedEnd,
+    /// An unexpected character was found
+    UnexpectedToken,
+    /// A closing tag did not match the element that it closes
+    MismatchedClosingTag,
+    /// Two elements have the same `id`
+    DuplicateId,
+    /// A style declaration was not of the form `property: value`
+    InvalidDeclaration,
+    /// A style property is not supported
+    UnknownProperty,
+    /// The value of a style property is not valid
+    InvalidValue,
+    /// A `preset` attribute names a style preset which is not in the tree
+    UnknownPreset,
+}
+
+/// Error returned when markup could not be loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkupError {
+    /// The byte offset in the input at which the error occurred
+    pub offset: usize,
+    /// The reason that loading failed
+    pub kind: MarkupErrorKind,
+}
+
+impl std::error::Error for MarkupError {}
+impl core::fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let reason = match self.kind {
+            MarkupErrorKind::UnexpectedEnd => "unexpected end of input",
+            MarkupErrorKind::UnexpectedToken => "unexpected token",
+            MarkupErrorKind::MismatchedClosingTag => "closing tag does not match opening tag",
+            MarkupErrorKind::DuplicateId => "duplicate id",
+            MarkupErrorKind::InvalidDeclaration => "expected a declaration of the form `property: value`",
+            MarkupErrorKind::UnknownProperty => "unsupported style property",
+            MarkupErrorKind::InvalidValue => "invalid style value",
+            MarkupErrorKind::UnknownPreset => "unknown style preset",
+        };
+        write!(f, "Invalid markup at byte {}: {}", self.offset, reason)
+    }
+}
+
+/// The nodes created by [`load_markup`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Markup {
+    /// The nodes created for the top-level elements, in document order
+    pub roots: Vec<NodeId>,
+    /// The nodes created for elements with an `id` attribute, keyed by id
+    pub ids: HashMap<String, NodeId>,
+}
+
+/// Creates a node in `tree` for every element in `markup`, and returns the nodes created for top-level elements and
+/// elements with an `id`
+///
+/// Styles are based on the element's style preset or the tree's [`default_style`](TaffyTree::default_style). See the [module](crate::markup)
+/// documentation for the supported syntax. If an error occurs then the nodes created so far are removed again, leaving
+/// the tree as it was.
+pub fn load_markup<NodeContext>(tree: &mut TaffyTree<NodeContext>, markup: &str) -> Result<Markup, MarkupError> {
+    let mut parser = Parser { input: markup, offset: 0, seen_ids: HashSet::new(), created: Vec::new() };
+    let mut loaded = Markup::default();
+    match parser.document(tree, &mut loaded) {
+        Ok(()) => Ok(loaded),
+        Err(error) => {
+            for node in parser.created {
+                tree.remove(node).expect("nodes were created by this parser");
+            }
+            Err(error)
+        }
+    }
+}
+
+/// A recursive descent parser over the markup
+struct Parser<'a> {
+    /// The full input
+    input: &'a str,
+    /// The byte offset of the next unparsed character
+    offset: usize,
+    /// The ids of all of the elements parsed so far, including those which have not been closed yet
+    seen_ids: HashSet<&'a str>,
+    /// The nodes created so far, which are removed if the markup fails to load
+    created: Vec<NodeId>,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns the unparsed input
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    /// Returns an error of the given kind at the current offset
+    fn error(&self, kind: MarkupErrorKind) -> MarkupError {
+        MarkupError { offset: self.offset, kind }
+    }
+
+    /// Skips any whitespace
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `token` if the input starts with it
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.offset += token.len();
+        }
+        found
+    }
+
+    /// Consumes `token` or returns an error
+    fn expect(&mut self, token: &str) -> Result<(), MarkupError> {
+        match self.eat(token) {
+            true => Ok(()),
+            false if self.rest().is_empty() => Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+            false => Err(self.error(MarkupErrorKind::UnexpectedToken)),
+        }
+    }
+
+    /// Skips everything up to and including `terminator`
+    fn skip_past(&mut self, terminator: &str) -> Result<(), MarkupError> {
+        match self.rest().find(terminator) {
+            Some(index) => {
+                self.offset += index + terminator.len();
+                Ok(())
+            }
+            None => {
+                self.offset = self.input.len();
+                Err(self.error(MarkupErrorKind::UnexpectedEnd))
+            }
+        }
+    }
+
+    /// Skips text, comments, processing instructions and doctypes up to the next element or closing tag
+    fn skip_content(&mut self) -> Result<(), MarkupError> {
+        loop {
+            self.offset += self.rest().find('<').unwrap_or(self.rest().len());
+            if self.eat("<!--") {
+                self.skip_past("-->")?;
+            } else if self.eat("<?") {
+                self.skip_past("?>")?;
+            } else if self.eat("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parses an element or attribute name
+    fn name(&mut self) -> Result<&'a str, MarkupError> {
+        let rest = self.rest();
+        let len =
+            rest.find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error(match rest.is_empty() {
+                true => MarkupErrorKind::UnexpectedEnd,
+                false => MarkupErrorKind::UnexpectedToken,
+            }));
+        }
+        self.offset += len;
+        Ok(&rest[..len])
+    }
+
+    /// Parses a quoted attribute value, and returns it along with its offset
+    fn attribute_value(&mut self) -> Result<(&'a str, usize), MarkupError> {
+        let quote = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            Some(_) => return Err(self.error(MarkupErrorKind::UnexpectedToken)),
+            None => return Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+        };
+        self.offset += 1;
+        let start = self.offset;
+        let len = self.rest().find(quote).ok_or(MarkupError { offset: start, kind: MarkupErrorKind::UnexpectedEnd })?;
+        self.offset += len + 1;
+        Ok((&self.input[start..start + len], start))
+    }
+
+    /// Parses the top-level elements of the document (and their descendants) and creates nodes for them
+    fn document<NodeContext>(
+        &mut self,
+        tree: &mut TaffyTree<NodeContext>,
+        loaded: &mut Markup,
+    ) -> Result<(), MarkupError> {
+        loop {
+            self.skip_content()?;
+            if self.rest().is_empty() {
+                return Ok(());
+            }
+            let root = self.element(tree, &mut loaded.ids)?;
+            loaded.roots.push(root);
+        }
+    }
+
+    /// Parses an element (and its descendants) and creates a node for it
+    fn element<NodeContext>(
+        &mut self,
+        tree: &mut TaffyTree<NodeContext>,
+        ids: &mut HashMap<String, NodeId>,
+    ) -> Result<NodeId, MarkupError> {
+        self.expect("<")?;
+        let name = self.name()?;
+        let mut id = None;
+        let mut preset = None;
+        let mut inline_style = None;
+        let is_empty = loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                break true;
+            }
+            if self.eat(">") {
+                break false;
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let (value, offset) = self.attribute_value()?;
+            match attribute {
+                "id" => {
+                    if !self.seen_ids.insert(value) {
+                        return Err(MarkupError { offset, kind: MarkupErrorKind::DuplicateId });
+                    }
+                    id = Some(value);
+                }
+                "preset" => preset = Some((value, offset)),
+                "style" => inline_style = Some((value, offset)),
+                _ => {}
+            }
+        };
+
+        let mut style = match preset {
+            Some((name, offset)) => {
+                tree.style_preset(name).cloned().ok_or(MarkupError { offset, kind: MarkupErrorKind::UnknownPreset })?
+            }
+            None => tree.default_style(),
+        };
+        if let Some((value, offset)) = inline_style {
+            apply_inline_style(&mut style, value).map_err(|error| MarkupError {
+                offset: offset + error.offset,
+                kind: match error.kind {
+                    InlineStyleErrorKind::InvalidDeclaration => MarkupErrorKind::InvalidDeclaration,
+                    InlineStyleErrorKind::UnknownProperty => MarkupErrorKind::UnknownProperty,
+                    InlineStyleErrorKind::InvalidValue => MarkupErrorKind::InvalidValue,
+                },
+            })?;
+        }
+
+        let mut children = Vec::new();
+        if !is_empty {
+            loop {
+                self.skip_content()?;
+                if self.rest().is_empty() {
+                    return Err(self.error(MarkupErrorKind::UnexpectedEnd));
+                }
+                let closing_tag_offset = self.offset;
+                if self.eat("</") {
+                    if self.name()? != name {
+                        return Err(MarkupError {
+                            offset: closing_tag_offset,
+                            kind: MarkupErrorKind::MismatchedClosingTag,
+                        });
+                    }
+                    self.skip_whitespace();
+                    self.expect(">")?;
+                    break;
+                }
+                children.push(self.element(tree, ids)?);
+            }
+        }
+
+        let node = tree.new_with_children(style, &children).expect("children were created by this parser");
+        self.created.push(node);
+        if let Some(id) = id {
+            ids.insert(String::from(id), node);
+        }
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_markup, MarkupError, MarkupErrorKind};
+    use crate::prelude::*;
+
+    #[test]
+    fn markup_builds_tree() {
+        let mut tree: TaffyTree = TaffyTree::new();
+        let markup = load_markup(
+            &mut tree,
+            r#"<?xml version="1.0"?>
+            <!-- A sidebar layout -->
+            <div id="root" style="display: flex; width: 300px; height: 200px; padding: 10px; gap: 10px">
+              <div id="sidebar" class="ignored" style="width: 80px">Text is ignored</div>
+              <div id="content" style="flex-grow: 1; margin: 0 10px" />
+            </div>
+            <span id="other" />"#,
+        )
+        .unwrap();
+        assert_eq!(markup.roots, vec![markup.ids["root"], markup.ids["other"]]);
+        assert_eq!(tree.children(markup.ids["root"]).unwrap(), vec![markup.ids["sidebar"], markup.ids["content"]]);
+
+        tree.compute_layout(markup.roots[0], Size::MAX_CONTENT).unwrap();
+        let sidebar = tree.layout(markup.ids["sidebar"]).unwrap();
+        assert_eq!((sidebar.location.x, sidebar.size.width, sidebar.size.height), (10.0, 80.0, 180.0));
+        let content = tree.layout(markup.ids["content"]).unwrap();
+        assert_eq!((content.location.x, content.size.width), (110.0, 170.0));
+    }
+
+    #[test]
+    fn markup_errors() {
+        let error = |input: &str| load_markup(&mut TaffyTree::<()>::new(), input).unwrap_err();
+        assert_eq!(error("<a><b></a>"), MarkupError { offset: 6, kind: MarkupErrorKind::MismatchedClosingTag });
+        assert_eq!(error("<a>"), MarkupError { offset: 3, kind: MarkupErrorKind::UnexpectedEnd });
+        assert_eq!(error(r#"<a id="x"/><b id="x"/>"#), MarkupError { offset: 18, kind: MarkupErrorKind::DuplicateId });
+        assert_eq!(
+            error(r#"<a style="width: 1px; colour: red"/>"#),
+            MarkupError { offset: 21, kind: MarkupErrorKind::UnknownProperty }
+        );
+        assert_eq!(
+            error(r#"<a style="width: wide"/>"#),
+            MarkupError { offset: 10, kind: MarkupErrorKind::InvalidValue }
+        );
+        assert_eq!(error(r#"<a preset="card"/>"#), MarkupError { offset: 11, kind: MarkupErrorKind::UnknownPreset });
+        assert_eq!(
+            error(r#"<a id="x"><b id="x"/></a>"#),
+            MarkupError { offset: 17, kind: MarkupErrorKind::DuplicateId }
+        );
+    }
+
+    #[test]
+    fn markup_errors_leave_the_tree_unchanged() {
+        let mut tree: TaffyTree = TaffyTree::new();
+        let existing = tree.new_leaf(Style::default()).unwrap();
+        let error = load_markup(&mut tree, r#"<a><b/><c/></a><d><e style="width: wide"/></d>"#).unwrap_err();
+        assert_eq!(error.kind, MarkupErrorKind::InvalidValue);
+        assert_eq!(tree.total_node_count(), 1);
+        assert!(tree.layout(existing).is_ok());
+    }
+
+    #[test]
+    fn markup_elements_start_from_their_preset() {
+        let mut tree: TaffyTree = TaffyTree::new();
+        tree.set_default_style(Style { flex_grow: 1.0, ..Default::default() });
+        tree.set_style_preset("card", Style { padding: length(8.0), flex_grow: 2.0, ..Default::default() });
+        let markup =
+            load_markup(&mut tree, r#"<a id="card" style="width: 10px" preset="card"><b id="plain"/></a>"#).unwrap();
+
+        let card = tree.style(markup.ids["card"]).unwrap();
+        assert_eq!((card.padding, card.flex_grow, card.size.width), (length(8.0), 2.0, length(10.0)));
+        assert_eq!(tree.style(markup.ids["plain"]).unwrap().flex_grow, 1.0);
+    }
+}
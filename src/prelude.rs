@@ -1,14 +1,16 @@
 //! Commonly used types
 
 pub use crate::{
+    border, gap,
     geometry::{Line, Rect, Size},
+    inset, margin, padding,
     style::{
-        AlignContent, AlignItems, AlignSelf, AvailableSpace, BoxSizing, Dimension, Display, JustifyContent, JustifyItems,
-        JustifySelf, LengthPercentage, LengthPercentageAuto, Overflow, Position, Style,
+        AlignContent, AlignItems, AlignSelf, AvailableSpace, BoxSizing, Dimension, Direction, Display, JustifyContent,
+        JustifyItems, JustifySelf, LengthPercentage, LengthPercentageAuto, Overflow, Position, ScrollbarGutter, Style,
     },
     style_helpers::{
         auto, fit_content, length, max_content, min_content, percent, zero, FromFlex, FromLength, FromPercent,
-        TaffyAuto, TaffyFitContent, TaffyMaxContent, TaffyMinContent, TaffyZero,
+        TaffyAuto, TaffyFitContent, TaffyMaxContent, TaffyMinContent, TaffyNumber, TaffyZero,
     },
     tree::{Layout, LayoutPartialTree, NodeId, PrintTree, RoundTree, TraversePartialTree, TraverseTree},
 };
@@ -0,0 +1,656 @@
+//! Record the mutations and layout computations performed on a [`TaffyTree`] into a compact binary log, and replay such
+//! logs into a fresh tree
+//!
+//! This is intended for reproducing layout bugs from shipped builds: call [`TaffyTree::start_recording`] when the
+//! application starts (or when the user starts reproducing a bug), save the log returned by
+//! [`TaffyTree::stop_recording`], and load it during debugging with [`replay`].
+//!
+//! Node contexts (and measure functions) can't be recorded. Instead, the output of every call to the measure function
+//! (its size and baselines) is recorded, and the replayed tree returns the same outputs in the same order. Logs can only be replayed by a
+//! build of Taffy with the same version of the log format and the same set of layout algorithm features enabled.
+use core::fmt;
+
+use slotmap::DefaultKey;
+#[cfg(not(feature = "std"))]
+use slotmap::SecondaryMap;
+#[cfg(feature = "std")]
+use slotmap::SparseSecondaryMap as SecondaryMap;
+
+use crate::compute::{BoxConstraints, LayoutConstraints};
+use crate::geometry::Size;
+use crate::style::{AvailableSpace, Style, StyleVariant};
+use crate::tree::{LayoutConfig, MeasureOutput, NodeId, StyleCommand, StyleHandle, TaffyError, TaffyTree};
+use crate::util::codec::{Codec, CodecError, Reader};
+use crate::util::sys::Vec;
+
+/// The bytes at the start of every log
+const MAGIC: &[u8; 8] = b"TAFFYLOG";
+/// The version of the log format. Must be incremented whenever the encoding of a command or value changes.
+const FORMAT_VERSION: u8 = 2;
+
+/// A bitmask of the enabled layout algorithm features. Layouts can only be reproduced with the same set of algorithms.
+const FEATURES: u8 = {
+    let mut features = 0;
+    if cfg!(feature = "block_layout") {
+        features |= 1;
+    }
+    if cfg!(feature = "flexbox") {
+        features |= 2;
+    }
+    if cfg!(feature = "grid") {
+        features |= 4;
+    }
+    features
+};
+
+/// An error that occurs while replaying a log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The log does not start with a header written by a compatible build of Taffy
+    InvalidHeader,
+    /// The log ends part way through a command
+    UnexpectedEnd,
+    /// The log contains a value which is not valid
+    InvalidValue,
+    /// The log refers to a node which was not created earlier in the log
+    UnknownNode(NodeId),
+    /// The log refers to a shared style which was not created earlier in the log
+    UnknownStyleHandle(StyleHandle),
+    /// A recorded operation failed when it was replayed
+    Tree(TaffyError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::InvalidHeader => write!(f, "The log was not recorded by a compatible build of Taffy"),
+            ReplayError::UnexpectedEnd => write!(f, "The log ends part way through a command"),
+            ReplayError::InvalidValue => write!(f, "The log contains an invalid value"),
+            ReplayError::UnknownNode(node) => write!(f, "The log refers to unknown node {node:?}"),
+            ReplayError::UnknownStyleHandle(handle) => write!(f, "The log refers to unknown shared style {handle:?}"),
+            ReplayError::Tree(error) => write!(f, "A recorded operation failed when replayed: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReplayError {}
+
+impl From<CodecError> for ReplayError {
+    fn from(error: CodecError) -> Self {
+        match error {
+            CodecError::UnexpectedEnd => ReplayError::UnexpectedEnd,
+            CodecError::InvalidValue => ReplayError::InvalidValue,
+        }
+    }
+}
+
+impl From<TaffyError> for ReplayError {
+    fn from(error: TaffyError) -> Self {
+        ReplayError::Tree(error)
+    }
+}
+
+/// A single recorded operation on a [`TaffyTree`]
+///
+/// Nodes and shared styles are identified by their ids in the recorded tree. Styles and children are not stored in the
+/// command: they are read from the tree when the command is encoded (which happens immediately after the operation).
+pub(crate) enum Command<'a> {
+    /// A leaf node was created, with a context (`true`) or without one (`false`)
+    NewLeaf(NodeId, bool),
+    /// A node was created with children
+    NewWithChildren(NodeId),
+    /// A node was removed
+    Remove(NodeId),
+    /// A node's context was set (`true`) or removed (`false`)
+    SetHasContext(NodeId, bool),
+    /// A child was appended to a node
+    AddChild(NodeId, NodeId),
+    /// A child was inserted into a node at an index
+    InsertChildAtIndex(NodeId, usize, NodeId),
+    /// A node's children were replaced
+    SetChildren(NodeId),
+    /// The child at an index was removed from a node
+    RemoveChildAtIndex(NodeId, usize),
+    /// The child at an index was replaced
+    ReplaceChildAtIndex(NodeId, usize, NodeId),
+    /// A node's style was set with [`TaffyTree::set_style`]
+    SetStyle(NodeId),
+    /// A shared style was created
+    CreateSharedStyle(StyleHandle),
+    /// A shared style was removed
+    RemoveSharedStyle(StyleHandle),
+    /// A shared style was modified
+    SetSharedStyle(StyleHandle),
+    /// A node was made to use a shared style
+    SetNodeStyleHandle(NodeId, StyleHandle),
+    /// A node was marked dirty
+    MarkDirty(NodeId),
+    /// All nodes were removed
+    Clear,
+    /// The tree's [`LayoutConfig`] was changed
+    SetLayoutConfig(LayoutConfig),
+    /// Layout was computed with [`TaffyTree::compute_layout_with_measure`]
+    ComputeLayout {
+        /// The root node of the layout
+        node: NodeId,
+        /// The space available to the root node
+        available_space: Size<AvailableSpace>,
+        /// The outputs of the measure function, in the order it was called
+        measurements: &'a [MeasureOutput],
+    },
+    /// Layout was computed with [`TaffyTree::compute_fit_layout_with_measure`]
+    ComputeFitLayout {
+        /// The root node of the layout
+        node: NodeId,
+        /// The maximum size of the root node
+        max_size: Size<f32>,
+        /// The outputs of the measure function, in the order it was called
+        measurements: &'a [MeasureOutput],
+    },
+    /// Layout was computed with [`TaffyTree::compute_constrained_layout_with_measure`]
+    ComputeConstrainedLayout {
+        /// The root node of the layout
+        node: NodeId,
+        /// The minimum size of the root node
+        min_size: Size<f32>,
+        /// The maximum size of the root node
+        max_size: Size<f32>,
+        /// The outputs of the measure function, in the order it was called
+        measurements: &'a [MeasureOutput],
+    },
+    /// A node was enabled (`true`) or disabled (`false`) with [`TaffyTree::set_node_enabled`]
+    SetNodeEnabled(NodeId, bool),
+    /// The size of a node was forced with [`TaffyTree::set_node_forced_size`]
+    SetNodeForcedSize(NodeId, Option<Size<f32>>),
+    /// A node's style variants were set
+    SetStyleVariants(NodeId),
+    /// The overlays of a node's state variants were set
+    SetVariantOverlays(NodeId),
+    /// A node's active state variants were set
+    SetActiveVariants(NodeId, u64),
+}
+
+/// Tags identifying each [`Command`] in the log
+mod tag {
+    /// [`Command::NewLeaf`](super::Command::NewLeaf)
+    pub(super) const NEW_LEAF: u8 = 0;
+    /// [`Command::NewWithChildren`](super::Command::NewWithChildren)
+    pub(super) const NEW_WITH_CHILDREN: u8 = 1;
+    /// [`Command::Remove`](super::Command::Remove)
+    pub(super) const REMOVE: u8 = 2;
+    /// [`Command::SetHasContext`](super::Command::SetHasContext)
+    pub(super) const SET_HAS_CONTEXT: u8 = 3;
+    /// [`Command::AddChild`](super::Command::AddChild)
+    pub(super) const ADD_CHILD: u8 = 4;
+    /// [`Command::InsertChildAtIndex`](super::Command::InsertChildAtIndex)
+    pub(super) const INSERT_CHILD_AT_INDEX: u8 = 5;
+    /// [`Command::SetChildren`](super::Command::SetChildren)
+    pub(super) const SET_CHILDREN: u8 = 6;
+    /// [`Command::RemoveChildAtIndex`](super::Command::RemoveChildAtIndex)
+    pub(super) const REMOVE_CHILD_AT_INDEX: u8 = 7;
+    /// [`Command::ReplaceChildAtIndex`](super::Command::ReplaceChildAtIndex)
+    pub(super) const REPLACE_CHILD_AT_INDEX: u8 = 8;
+    /// [`Command::SetStyle`](super::Command::SetStyle)
+    pub(super) const SET_STYLE: u8 = 9;
+    /// A node's style was modified in place through [`TaffyTree::try_style_mut`](crate::TaffyTree::try_style_mut)
+    /// (which does not mark the node dirty)
+    pub(super) const STYLE_MUT: u8 = 10;
+    /// [`Command::CreateSharedStyle`](super::Command::CreateSharedStyle)
+    pub(super) const CREATE_SHARED_STYLE: u8 = 11;
+    /// [`Command::RemoveSharedStyle`](super::Command::RemoveSharedStyle)
+    pub(super) const REMOVE_SHARED_STYLE: u8 = 12;
+    /// [`Command::SetSharedStyle`](super::Command::SetSharedStyle)
+    pub(super) const SET_SHARED_STYLE: u8 = 13;
+    /// [`Command::SetNodeStyleHandle`](super::Command::SetNodeStyleHandle)
+    pub(super) const SET_NODE_STYLE_HANDLE: u8 = 14;
+    /// [`Command::MarkDirty`](super::Command::MarkDirty)
+    pub(super) const MARK_DIRTY: u8 = 15;
+    /// [`Command::Clear`](super::Command::Clear)
+    pub(super) const CLEAR: u8 = 16;
+    /// [`Command::SetLayoutConfig`](super::Command::SetLayoutConfig)
+    pub(super) const SET_LAYOUT_CONFIG: u8 = 17;
+    /// [`Command::ComputeLayout`](super::Command::ComputeLayout)
+    pub(super) const COMPUTE_LAYOUT: u8 = 18;
+    /// [`Command::ComputeFitLayout`](super::Command::ComputeFitLayout)
+    pub(super) const COMPUTE_FIT_LAYOUT: u8 = 19;
+    /// [`Command::ComputeConstrainedLayout`](super::Command::ComputeConstrainedLayout)
+    pub(super) const COMPUTE_CONSTRAINED_LAYOUT: u8 = 20;
+    /// [`Command::SetNodeEnabled`](super::Command::SetNodeEnabled)
+    pub(super) const SET_NODE_ENABLED: u8 = 21;
+    /// [`Command::SetNodeForcedSize`](super::Command::SetNodeForcedSize)
+    pub(super) const SET_NODE_FORCED_SIZE: u8 = 22;
+    /// [`Command::SetStyleVariants`](super::Command::SetStyleVariants)
+    pub(super) const SET_STYLE_VARIANTS: u8 = 23;
+    /// [`Command::SetVariantOverlays`](super::Command::SetVariantOverlays)
+    pub(super) const SET_VARIANT_OVERLAYS: u8 = 24;
+    /// [`Command::SetActiveVariants`](super::Command::SetActiveVariants)
+    pub(super) const SET_ACTIVE_VARIANTS: u8 = 25;
+}
+
+/// An in-progress recording of the operations performed on a [`TaffyTree`]
+#[derive(Debug, Clone)]
+pub(crate) struct Recorder {
+    /// The encoded log
+    log: Vec<u8>,
+    /// Nodes whose style has been borrowed mutably since the last command was recorded, and which may have been modified
+    pub(crate) pending_styles: Vec<NodeId>,
+}
+
+impl Recorder {
+    /// Creates a recorder with an empty log
+    pub(crate) fn new() -> Self {
+        let mut log = Vec::new();
+        log.extend_from_slice(MAGIC);
+        log.push(FORMAT_VERSION);
+        log.push(FEATURES);
+        Self { log, pending_styles: Vec::new() }
+    }
+
+    /// Appends a command describing an operation which has just been performed on `tree` to the log
+    ///
+    /// The styles of nodes in [`pending_styles`](Self::pending_styles) are written first.
+    pub(crate) fn push<NodeContext>(&mut self, tree: &TaffyTree<NodeContext>, command: Command) {
+        let style = |node| tree.style(node).expect("recorded nodes exist");
+        let children = |node| tree.children(node).expect("recorded nodes exist");
+        let shared_style = |handle| tree.shared_style(handle).expect("recorded shared styles exist");
+
+        self.flush_pending_styles(tree);
+        let out = &mut self.log;
+        match command {
+            Command::NewLeaf(node, has_context) => {
+                out.push(tag::NEW_LEAF);
+                (node, style(node), has_context).encode(out);
+            }
+            Command::NewWithChildren(node) => {
+                out.push(tag::NEW_WITH_CHILDREN);
+                (node, style(node), children(node)).encode(out);
+            }
+            Command::Remove(node) => {
+                out.push(tag::REMOVE);
+                node.encode(out);
+            }
+            Command::SetHasContext(node, has_context) => {
+                out.push(tag::SET_HAS_CONTEXT);
+                (node, has_context).encode(out);
+            }
+            Command::AddChild(parent, child) => {
+                out.push(tag::ADD_CHILD);
+                (parent, child).encode(out);
+            }
+            Command::InsertChildAtIndex(parent, index, child) => {
+                out.push(tag::INSERT_CHILD_AT_INDEX);
+                (parent, index, child).encode(out);
+            }
+            Command::SetChildren(parent) => {
+                out.push(tag::SET_CHILDREN);
+                (parent, children(parent)).encode(out);
+            }
+            Command::RemoveChildAtIndex(parent, index) => {
+                out.push(tag::REMOVE_CHILD_AT_INDEX);
+                (parent, index).encode(out);
+            }
+            Command::ReplaceChildAtIndex(parent, index, child) => {
+                out.push(tag::REPLACE_CHILD_AT_INDEX);
+                (parent, index, child).encode(out);
+            }
+            Command::SetStyle(node) => {
+                out.push(tag::SET_STYLE);
+                (node, style(node)).encode(out);
+            }
+            Command::CreateSharedStyle(handle) => {
+                out.push(tag::CREATE_SHARED_STYLE);
+                (handle, shared_style(handle)).encode(out);
+            }
+            Command::RemoveSharedStyle(handle) => {
+                out.push(tag::REMOVE_SHARED_STYLE);
+                handle.encode(out);
+            }
+            Command::SetSharedStyle(handle) => {
+                out.push(tag::SET_SHARED_STYLE);
+                (handle, shared_style(handle)).encode(out);
+            }
+            Command::SetNodeStyleHandle(node, handle) => {
+                out.push(tag::SET_NODE_STYLE_HANDLE);
+                (node, handle).encode(out);
+            }
+            Command::MarkDirty(node) => {
+                out.push(tag::MARK_DIRTY);
+                node.encode(out);
+            }
+            Command::Clear => out.push(tag::CLEAR),
+            Command::SetLayoutConfig(config) => {
+                out.push(tag::SET_LAYOUT_CONFIG);
+                config.encode(out);
+            }
+            Command::ComputeLayout { node, available_space, measurements } => {
+                out.push(tag::COMPUTE_LAYOUT);
+                (node, available_space, measurements).encode(out);
+            }
+            Command::ComputeFitLayout { node, max_size, measurements } => {
+                out.push(tag::COMPUTE_FIT_LAYOUT);
+                (node, max_size, measurements).encode(out);
+            }
+            Command::ComputeConstrainedLayout { node, min_size, max_size, measurements } => {
+                out.push(tag::COMPUTE_CONSTRAINED_LAYOUT);
+                (node, (min_size, max_size), measurements).encode(out);
+            }
+            Command::SetNodeEnabled(node, enabled) => {
+                out.push(tag::SET_NODE_ENABLED);
+                (node, enabled).encode(out);
+            }
+            Command::SetNodeForcedSize(node, size) => {
+                out.push(tag::SET_NODE_FORCED_SIZE);
+                (node, size).encode(out);
+            }
+            Command::SetStyleVariants(node) => {
+                out.push(tag::SET_STYLE_VARIANTS);
+                (node, tree.style_variants(node).expect("recorded nodes exist")).encode(out);
+            }
+            Command::SetVariantOverlays(node) => {
+                out.push(tag::SET_VARIANT_OVERLAYS);
+                (node, tree.variant_overlays(node).expect("recorded nodes exist")).encode(out);
+            }
+            Command::SetActiveVariants(node, variants) => {
+                out.push(tag::SET_ACTIVE_VARIANTS);
+                (node, variants).encode(out);
+            }
+        }
+    }
+
+    /// Returns the encoded log, including the styles of any nodes in [`pending_styles`](Self::pending_styles)
+    pub(crate) fn finish<NodeContext>(mut self, tree: &TaffyTree<NodeContext>) -> Vec<u8> {
+        self.flush_pending_styles(tree);
+        self.log
+    }
+
+    /// Writes the styles of the nodes in [`pending_styles`](Self::pending_styles) to the log
+    fn flush_pending_styles<NodeContext>(&mut self, tree: &TaffyTree<NodeContext>) {
+        for node in self.pending_styles.drain(..) {
+            // The node may have been removed since its style was borrowed
+            if let Ok(style) = tree.style(node) {
+                self.log.push(tag::STYLE_MUT);
+                (node, style).encode(&mut self.log);
+            }
+        }
+    }
+}
+
+/// A tree reconstructed from a log by [`replay`]
+#[derive(Debug)]
+pub struct Replay {
+    /// The reconstructed tree, in the state the recorded tree was in when the recording was stopped
+    pub tree: TaffyTree,
+    /// Maps the ids of nodes in the recorded tree to the ids of the corresponding nodes in the reconstructed tree
+    node_ids: SecondaryMap<DefaultKey, NodeId>,
+    /// Maps the shared style handles of the recorded tree to those of the reconstructed tree
+    style_handles: SecondaryMap<DefaultKey, StyleHandle>,
+    /// The root node of the most recent layout computation, as a node of the reconstructed tree
+    last_layout_root: Option<NodeId>,
+}
+
+impl Replay {
+    /// Returns the id in the reconstructed tree of the node which had the id `recorded` in the recorded tree
+    pub fn node_id(&self, recorded: NodeId) -> Option<NodeId> {
+        self.node_ids.get(recorded.into()).copied()
+    }
+
+    /// Returns the root node of the most recent layout computation in the log (as a node of the reconstructed tree)
+    pub fn last_layout_root(&self) -> Option<NodeId> {
+        self.last_layout_root
+    }
+
+    /// Maps a recorded node id to the reconstructed tree
+    fn node(&self, recorded: NodeId) -> Result<NodeId, ReplayError> {
+        self.node_id(recorded).ok_or(ReplayError::UnknownNode(recorded))
+    }
+
+    /// Maps a list of recorded node ids to the reconstructed tree
+    fn nodes(&self, recorded: Vec<NodeId>) -> Result<Vec<NodeId>, ReplayError> {
+        recorded.into_iter().map(|node| self.node(node)).collect()
+    }
+
+    /// Maps a recorded shared style handle to the reconstructed tree
+    fn style_handle(&self, recorded: StyleHandle) -> Result<StyleHandle, ReplayError> {
+        self.style_handles.get(recorded.into()).copied().ok_or(ReplayError::UnknownStyleHandle(recorded))
+    }
+
+    /// Decodes a single command and applies it to the reconstructed tree
+    fn apply(&mut self, input: &mut Reader) -> Result<(), ReplayError> {
+        match input.byte()? {
+            tag::NEW_LEAF => {
+                let (recorded, style, has_context) = <(NodeId, Style, bool)>::decode(input)?;
+                let node = match has_context {
+                    true => self.tree.new_leaf_with_context(style, ())?,
+                    false => self.tree.new_leaf(style)?,
+                };
+                self.node_ids.insert(recorded.into(), node);
+            }
+            tag::NEW_WITH_CHILDREN => {
+                let (recorded, style, children) = <(NodeId, Style, Vec<NodeId>)>::decode(input)?;
+                let children = self.nodes(children)?;
+                let node = self.tree.new_with_children(style, &children)?;
+                self.node_ids.insert(recorded.into(), node);
+            }
+            tag::REMOVE => {
+                let recorded = NodeId::decode(input)?;
+                self.tree.remove(self.node(recorded)?)?;
+                self.node_ids.remove(recorded.into());
+            }
+            tag::SET_HAS_CONTEXT => {
+                let (node, has_context) = <(NodeId, bool)>::decode(input)?;
+                self.tree.set_node_context(self.node(node)?, has_context.then_some(()))?;
+            }
+            tag::ADD_CHILD => {
+                let (parent, child) = <(NodeId, NodeId)>::decode(input)?;
+                self.tree.add_child(self.node(parent)?, self.node(child)?)?;
+            }
+            tag::INSERT_CHILD_AT_INDEX => {
+                let (parent, index, child) = <(NodeId, usize, NodeId)>::decode(input)?;
+                self.tree.insert_child_at_index(self.node(parent)?, index, self.node(child)?)?;
+            }
+            tag::SET_CHILDREN => {
+                let (parent, children) = <(NodeId, Vec<NodeId>)>::decode(input)?;
+                let children = self.nodes(children)?;
+                self.tree.set_children(self.node(parent)?, &children)?;
+            }
+            tag::REMOVE_CHILD_AT_INDEX => {
+                let (parent, index) = <(NodeId, usize)>::decode(input)?;
+                self.tree.remove_child_at_index(self.node(parent)?, index)?;
+            }
+            tag::REPLACE_CHILD_AT_INDEX => {
+                let (parent, index, child) = <(NodeId, usize, NodeId)>::decode(input)?;
+                self.tree.replace_child_at_index(self.node(parent)?, index, self.node(child)?)?;
+            }
+            tag::SET_STYLE => {
+                let (node, style) = <(NodeId, Style)>::decode(input)?;
+                self.tree.set_style(self.node(node)?, style)?;
+            }
+            tag::STYLE_MUT => {
+                let (node, style) = <(NodeId, Style)>::decode(input)?;
+                *self.tree.try_style_mut(self.node(node)?)? = style;
+            }
+            tag::CREATE_SHARED_STYLE => {
+                let (recorded, style) = <(StyleHandle, Style)>::decode(input)?;
+                let handle = self.tree.create_shared_style(style);
+                self.style_handles.insert(recorded.into(), handle);
+            }
+            tag::REMOVE_SHARED_STYLE => {
+                let recorded = StyleHandle::decode(input)?;
+                self.tree.remove_shared_style(self.style_handle(recorded)?)?;
+                self.style_handles.remove(recorded.into());
+            }
+            tag::SET_SHARED_STYLE => {
+                let (handle, style) = <(StyleHandle, Style)>::decode(input)?;
+                self.tree.set_shared_style(self.style_handle(handle)?, style)?;
+            }
+            tag::SET_NODE_STYLE_HANDLE => {
+                let (node, handle) = <(NodeId, StyleHandle)>::decode(input)?;
+                self.tree.set_node_style_handle(self.node(node)?, self.style_handle(handle)?)?;
+            }
+            tag::MARK_DIRTY => {
+                let node = NodeId::decode(input)?;
+                self.tree.mark_dirty(self.node(node)?)?;
+            }
+            tag::CLEAR => {
+                self.tree.clear();
+                self.node_ids.clear();
+                self.last_layout_root = None;
+            }
+            tag::SET_LAYOUT_CONFIG => {
+                self.tree.set_layout_config(LayoutConfig::decode(input)?);
+            }
+            tag::COMPUTE_LAYOUT => {
+                let (node, available_space, measurements) =
+                    <(NodeId, Size<AvailableSpace>, Vec<MeasureOutput>)>::decode(input)?;
+                let node = self.node(node)?;
+                let mut measurements = measurements.into_iter();
+                self.tree.compute_layout_with_measure(node, available_space, |_, _, _, _, _| {
+                    measurements.next().unwrap_or(MeasureOutput::from_size(Size::ZERO))
+                })?;
+                self.last_layout_root = Some(node);
+            }
+            tag::COMPUTE_FIT_LAYOUT => {
+                let (node, max_size, measurements) = <(NodeId, Size<f32>, Vec<MeasureOutput>)>::decode(input)?;
+                let node = self.node(node)?;
+                let mut measurements = measurements.into_iter();
+                self.tree.compute_fit_layout_with_measure(node, max_size, |_, _, _, _, _| {
+                    measurements.next().unwrap_or(MeasureOutput::from_size(Size::ZERO))
+                })?;
+                self.last_layout_root = Some(node);
+            }
+            tag::COMPUTE_CONSTRAINED_LAYOUT => {
+                let (node, (min_size, max_size), measurements) =
+                    <(NodeId, (Size<f32>, Size<f32>), Vec<MeasureOutput>)>::decode(input)?;
+                let node = self.node(node)?;
+                let constraints = BoxConstraints::from_min_max(min_size, max_size);
+                let mut measurements = measurements.into_iter();
+                self.tree.compute_constrained_layout_with_measure(node, &constraints, |_, _, _, _, _| {
+                    measurements.next().unwrap_or(MeasureOutput::from_size(Size::ZERO))
+                })?;
+                self.last_layout_root = Some(node);
+            }
+            tag::SET_NODE_ENABLED => {
+                let (node, enabled) = <(NodeId, bool)>::decode(input)?;
+                self.tree.set_node_enabled(self.node(node)?, enabled)?;
+            }
+            tag::SET_NODE_FORCED_SIZE => {
+                let (node, size) = <(NodeId, Option<Size<f32>>)>::decode(input)?;
+                self.tree.set_node_forced_size(self.node(node)?, size)?;
+            }
+            tag::SET_STYLE_VARIANTS => {
+                let (node, variants) = <(NodeId, Vec<StyleVariant>)>::decode(input)?;
+                self.tree.set_style_variants(self.node(node)?, variants)?;
+            }
+            tag::SET_VARIANT_OVERLAYS => {
+                let (node, overlays) = <(NodeId, Vec<Vec<StyleCommand>>)>::decode(input)?;
+                self.tree.set_variant_overlays(self.node(node)?, overlays)?;
+            }
+            tag::SET_ACTIVE_VARIANTS => {
+                let (node, variants) = <(NodeId, u64)>::decode(input)?;
+                self.tree.set_active_variants(self.node(node)?, variants)?;
+            }
+            _ => return Err(ReplayError::InvalidValue),
+        }
+        Ok(())
+    }
+}
+
+/// Reconstructs a tree by replaying a log recorded with [`TaffyTree::start_recording`]
+///
+/// The outputs of the measure function while recording are returned again, in the same order, while replaying.
+/// Nodes which had a context in the recorded tree have a `()` context in the reconstructed tree.
+pub fn replay(log: &[u8]) -> Result<Replay, ReplayError> {
+    let header_is_valid = log.starts_with(MAGIC)
+        && log.get(MAGIC.len()) == Some(&FORMAT_VERSION)
+        && log.get(MAGIC.len() + 1) == Some(&FEATURES);
+    if !header_is_valid {
+        return Err(ReplayError::InvalidHeader);
+    }
+    let mut input = Reader::new(&log[MAGIC.len() + 2..]);
+
+    let mut replay = Replay {
+        tree: TaffyTree::new(),
+        node_ids: SecondaryMap::new(),
+        style_handles: SecondaryMap::new(),
+        last_layout_root: None,
+    };
+    while !input.is_empty() {
+        replay.apply(&mut input)?;
+    }
+    Ok(replay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, ReplayError};
+    use crate::geometry::Point;
+    use crate::prelude::*;
+    use crate::tree::MeasureOutput;
+
+    #[test]
+    fn replay_reproduces_recorded_layout() {
+        let mut tree: TaffyTree<Size<f32>> = TaffyTree::new();
+        let existing = tree.new_leaf_with_context(Style::default(), Size { width: 30.0, height: 10.0 }).unwrap();
+        tree.start_recording();
+        assert!(tree.is_recording());
+
+        let shared = tree.create_shared_style(Style { flex_grow: 1.0, ..Default::default() });
+        let child = tree.new_leaf(Style::default()).unwrap();
+        tree.set_node_style_handle(child, shared).unwrap();
+        let root = tree
+            .new_with_children(Style { size: Size::from_lengths(200.0, 100.0), ..Default::default() }, &[existing])
+            .unwrap();
+        tree.add_child(root, child).unwrap();
+        tree.try_style_mut(root).unwrap().padding = length(5.0);
+        tree.compute_layout_with_measure(root, Size::MAX_CONTENT, |known, _, _, context, _| {
+            known.unwrap_or(context.copied().unwrap_or(Size::ZERO))
+        })
+        .unwrap();
+        let log = tree.stop_recording().unwrap();
+        assert!(!tree.is_recording());
+
+        let replay = replay(&log).unwrap();
+        assert_eq!(replay.last_layout_root(), replay.node_id(root));
+        for node in [root, existing, child] {
+            let replayed = replay.node_id(node).unwrap();
+            assert_eq!(replay.tree.layout(replayed).unwrap(), tree.layout(node).unwrap());
+        }
+        assert_eq!(tree.layout(existing).unwrap().size.width, 30.0);
+        assert_eq!(tree.layout(child).unwrap().size.width, 160.0);
+    }
+
+    #[test]
+    fn replay_reproduces_measured_baselines() {
+        let mut tree: TaffyTree<f32> = TaffyTree::new();
+        tree.start_recording();
+        let text = tree.new_leaf_with_context(Style::default(), 8.0).unwrap();
+        let icon = tree.new_leaf(Style { size: Size::from_lengths(20.0, 20.0), ..Default::default() }).unwrap();
+        let root = tree
+            .new_with_children(Style { align_items: Some(AlignItems::Baseline), ..Default::default() }, &[text, icon])
+            .unwrap();
+        tree.compute_layout_with_measure(root, Size::MAX_CONTENT, |known, _, _, baseline, _| {
+            let baseline = Point { x: None, y: baseline.copied() };
+            MeasureOutput::from_size(known.unwrap_or(Size { width: 40.0, height: 10.0 }))
+                .with_baselines(baseline, baseline)
+        })
+        .unwrap();
+        let log = tree.stop_recording().unwrap();
+        assert_eq!(tree.layout(text).unwrap().location.y, 12.0);
+
+        let replay = replay(&log).unwrap();
+        let replayed = replay.node_id(text).unwrap();
+        assert_eq!(replay.tree.layout(replayed).unwrap(), tree.layout(text).unwrap());
+    }
+
+    #[test]
+    fn replay_rejects_invalid_logs() {
+        assert_eq!(replay(b"not a log").unwrap_err(), ReplayError::InvalidHeader);
+
+        let mut tree: TaffyTree = TaffyTree::new();
+        tree.start_recording();
+        tree.new_leaf(Style::default()).unwrap();
+        let log = tree.stop_recording().unwrap();
+        assert_eq!(replay(&log[..log.len() - 1]).unwrap_err(), ReplayError::UnexpectedEnd);
+    }
+}
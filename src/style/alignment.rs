@@ -26,6 +26,9 @@ pub enum AlignItems {
     Center,
     /// Items are aligned such as their baselines align
     Baseline,
+    /// Items are aligned such that their last baselines align, with the group of aligned items packed toward the
+    /// end of the axis
+    LastBaseline,
     /// Stretch to fill the container
     Stretch,
 }
@@ -0,0 +1,674 @@
+//! A compact representation of [`Style`] for trees with very many nodes
+//!
+//! [`CompactStyle`] stores the properties which are set on most nodes inline, and moves the rarely-used ones (such as
+//! grid templates, insets and the inherited properties) into a single heap allocation which is only made if one of them
+//! differs from its initial value. It is read-only: convert from and to a [`Style`] to edit it.
+use core::mem::size_of;
+
+use super::{
+    BoxGenerationMode, BoxSizing, CoreStyle, Dimension, Direction, Display, LengthPercentage, LengthPercentageAuto,
+    Overflow, Position, ScrollbarGutter, Style, WritingMode,
+};
+use crate::geometry::{Point, Rect, Size};
+use crate::util::sys::Box;
+
+#[cfg(feature = "grid")]
+use super::{
+    track_lists_heap_size, GridAutoFlow, GridContainerStyle, GridItemStyle, GridPlacement,
+    NonRepeatedTrackSizingFunction, TrackSizingFunction,
+};
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use super::{AlignContent, AlignItems, AlignSelf, JustifyContent};
+#[cfg(feature = "block_layout")]
+use super::{BlockContainerStyle, BlockItemStyle, TextAlign};
+#[cfg(feature = "flexbox")]
+use super::{FlexDirection, FlexWrap, FlexboxContainerStyle, FlexboxItemStyle};
+#[cfg(feature = "grid")]
+use crate::geometry::Line;
+#[cfg(feature = "grid")]
+use crate::util::sys::GridTrackVec;
+
+/// The properties of a [`CompactStyle`] which are rarely set, and so are stored in a separate allocation
+#[derive(Clone, PartialEq, Debug)]
+struct StyleExtras {
+    /// See [`Style::direction`]
+    direction: Direction,
+    /// See [`Style::writing_mode`]
+    writing_mode: WritingMode,
+    /// See [`Style::scrollbar_width`]
+    scrollbar_width: f32,
+    /// See [`Style::scrollbar_gutter`]
+    scrollbar_gutter: ScrollbarGutter,
+    /// See [`Style::inset`]
+    inset: Rect<LengthPercentageAuto>,
+    /// See [`Style::aspect_ratio`]
+    aspect_ratio: Option<f32>,
+    /// See [`Style::justify_items`]
+    #[cfg(feature = "grid")]
+    justify_items: Option<AlignItems>,
+    /// See [`Style::justify_self`]
+    #[cfg(feature = "grid")]
+    justify_self: Option<AlignSelf>,
+    /// See [`Style::grid_template_rows`]
+    #[cfg(feature = "grid")]
+    grid_template_rows: GridTrackVec<TrackSizingFunction>,
+    /// See [`Style::grid_template_columns`]
+    #[cfg(feature = "grid")]
+    grid_template_columns: GridTrackVec<TrackSizingFunction>,
+    /// See [`Style::grid_auto_rows`]
+    #[cfg(feature = "grid")]
+    grid_auto_rows: GridTrackVec<NonRepeatedTrackSizingFunction>,
+    /// See [`Style::grid_auto_columns`]
+    #[cfg(feature = "grid")]
+    grid_auto_columns: GridTrackVec<NonRepeatedTrackSizingFunction>,
+    /// See [`Style::grid_auto_flow`]
+    #[cfg(feature = "grid")]
+    grid_auto_flow: GridAutoFlow,
+    /// See [`Style::grid_row`]
+    #[cfg(feature = "grid")]
+    grid_row: Line<GridPlacement>,
+    /// See [`Style::grid_column`]
+    #[cfg(feature = "grid")]
+    grid_column: Line<GridPlacement>,
+}
+
+/// The rarely-set properties of [`Style::DEFAULT`], which are used by every [`CompactStyle`] without [`StyleExtras`]
+static DEFAULT_EXTRAS: StyleExtras = StyleExtras {
+    direction: Direction::Inherit,
+    writing_mode: WritingMode::Inherit,
+    scrollbar_width: 0.0,
+    scrollbar_gutter: ScrollbarGutter::Auto,
+    inset: Rect::auto(),
+    aspect_ratio: None,
+    #[cfg(feature = "grid")]
+    justify_items: None,
+    #[cfg(feature = "grid")]
+    justify_self: None,
+    #[cfg(feature = "grid")]
+    grid_template_rows: GridTrackVec::new(),
+    #[cfg(feature = "grid")]
+    grid_template_columns: GridTrackVec::new(),
+    #[cfg(feature = "grid")]
+    grid_auto_rows: GridTrackVec::new(),
+    #[cfg(feature = "grid")]
+    grid_auto_columns: GridTrackVec::new(),
+    #[cfg(feature = "grid")]
+    grid_auto_flow: GridAutoFlow::Row,
+    #[cfg(feature = "grid")]
+    grid_row: Line { start: GridPlacement::Auto, end: GridPlacement::Auto },
+    #[cfg(feature = "grid")]
+    grid_column: Line { start: GridPlacement::Auto, end: GridPlacement::Auto },
+};
+
+/// A read-only [`Style`] which uses less memory than a [`Style`] for the typical node
+///
+/// The properties which most nodes set (display, sizes, spacing and flexbox properties) are stored inline. The rest are
+/// stored in a single heap allocation, which is only made if one of them differs from its initial value. A
+/// `CompactStyle` implements the same style traits as a [`Style`], so it can be stored by a custom tree (see
+/// [`LayoutPartialTree`](crate::LayoutPartialTree)) in place of a [`Style`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct CompactStyle {
+    /// See [`Style::display`]
+    display: Display,
+    /// See [`Style::item_is_table`]
+    item_is_table: bool,
+    /// See [`Style::box_sizing`]
+    box_sizing: BoxSizing,
+    /// See [`Style::position`]
+    position: Position,
+    /// See [`Style::overflow`]
+    overflow: Point<Overflow>,
+    /// See [`Style::size`]
+    size: Size<Dimension>,
+    /// See [`Style::min_size`]
+    min_size: Size<Dimension>,
+    /// See [`Style::max_size`]
+    max_size: Size<Dimension>,
+    /// See [`Style::margin`]
+    margin: Rect<LengthPercentageAuto>,
+    /// See [`Style::padding`]
+    padding: Rect<LengthPercentage>,
+    /// See [`Style::border`]
+    border: Rect<LengthPercentage>,
+    /// See [`Style::align_items`]
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    align_items: Option<AlignItems>,
+    /// See [`Style::align_self`]
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    align_self: Option<AlignSelf>,
+    /// See [`Style::align_content`]
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    align_content: Option<AlignContent>,
+    /// See [`Style::justify_content`]
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    justify_content: Option<JustifyContent>,
+    /// See [`Style::gap`]
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    gap: Size<LengthPercentage>,
+    /// See [`Style::text_align`]
+    #[cfg(feature = "block_layout")]
+    text_align: TextAlign,
+    /// See [`Style::flex_direction`]
+    #[cfg(feature = "flexbox")]
+    flex_direction: FlexDirection,
+    /// See [`Style::flex_wrap`]
+    #[cfg(feature = "flexbox")]
+    flex_wrap: FlexWrap,
+    /// See [`Style::flex_basis`]
+    #[cfg(feature = "flexbox")]
+    flex_basis: Dimension,
+    /// See [`Style::flex_grow`]
+    #[cfg(feature = "flexbox")]
+    flex_grow: f32,
+    /// See [`Style::flex_shrink`]
+    #[cfg(feature = "flexbox")]
+    flex_shrink: f32,
+    /// The rarely-set properties, or `None` if all of them have their initial values
+    extras: Option<Box<StyleExtras>>,
+}
+
+impl CompactStyle {
+    /// Returns the rarely-set properties of this style
+    #[inline(always)]
+    fn extras(&self) -> &StyleExtras {
+        self.extras.as_deref().unwrap_or(&DEFAULT_EXTRAS)
+    }
+
+    /// Converts this style back into a [`Style`] (for example so that it can be edited)
+    pub fn to_style(&self) -> Style {
+        let extras = self.extras();
+        Style {
+            display: self.display,
+            item_is_table: self.item_is_table,
+            box_sizing: self.box_sizing,
+            direction: extras.direction,
+            writing_mode: extras.writing_mode,
+            overflow: self.overflow,
+            scrollbar_width: extras.scrollbar_width,
+            scrollbar_gutter: extras.scrollbar_gutter,
+            position: self.position,
+            inset: extras.inset,
+            size: self.size,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            aspect_ratio: extras.aspect_ratio,
+            margin: self.margin,
+            padding: self.padding,
+            border: self.border,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_items: self.align_items,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_self: self.align_self,
+            #[cfg(feature = "grid")]
+            justify_items: extras.justify_items,
+            #[cfg(feature = "grid")]
+            justify_self: extras.justify_self,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_content: self.align_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            justify_content: self.justify_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            gap: self.gap,
+            #[cfg(feature = "block_layout")]
+            text_align: self.text_align,
+            #[cfg(feature = "flexbox")]
+            flex_direction: self.flex_direction,
+            #[cfg(feature = "flexbox")]
+            flex_wrap: self.flex_wrap,
+            #[cfg(feature = "flexbox")]
+            flex_basis: self.flex_basis,
+            #[cfg(feature = "flexbox")]
+            flex_grow: self.flex_grow,
+            #[cfg(feature = "flexbox")]
+            flex_shrink: self.flex_shrink,
+            #[cfg(feature = "grid")]
+            grid_template_rows: extras.grid_template_rows.clone(),
+            #[cfg(feature = "grid")]
+            grid_template_columns: extras.grid_template_columns.clone(),
+            #[cfg(feature = "grid")]
+            grid_auto_rows: extras.grid_auto_rows.clone(),
+            #[cfg(feature = "grid")]
+            grid_auto_columns: extras.grid_auto_columns.clone(),
+            #[cfg(feature = "grid")]
+            grid_auto_flow: extras.grid_auto_flow,
+            #[cfg(feature = "grid")]
+            grid_row: extras.grid_row,
+            #[cfg(feature = "grid")]
+            grid_column: extras.grid_column,
+        }
+    }
+
+    /// The number of bytes this style has allocated on the heap (in addition to the `size_of::<CompactStyle>()` bytes
+    /// of the struct itself)
+    pub fn heap_size(&self) -> usize {
+        match &self.extras {
+            Some(extras) => size_of::<StyleExtras>() + extras.heap_size(),
+            None => 0,
+        }
+    }
+}
+
+impl StyleExtras {
+    /// The number of bytes allocated by the grid track lists of these properties
+    fn heap_size(&self) -> usize {
+        #[cfg(feature = "grid")]
+        {
+            track_lists_heap_size(
+                &self.grid_template_rows,
+                &self.grid_template_columns,
+                &self.grid_auto_rows,
+                &self.grid_auto_columns,
+            )
+        }
+        #[cfg(not(feature = "grid"))]
+        0
+    }
+}
+
+impl From<Style> for CompactStyle {
+    fn from(style: Style) -> Self {
+        // Destructured so that a field added to `Style` can't be forgotten here
+        let Style {
+            display,
+            item_is_table,
+            box_sizing,
+            direction,
+            writing_mode,
+            overflow,
+            scrollbar_width,
+            scrollbar_gutter,
+            position,
+            inset,
+            size,
+            min_size,
+            max_size,
+            aspect_ratio,
+            margin,
+            padding,
+            border,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_items,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_self,
+            #[cfg(feature = "grid")]
+            justify_items,
+            #[cfg(feature = "grid")]
+            justify_self,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            justify_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            gap,
+            #[cfg(feature = "block_layout")]
+            text_align,
+            #[cfg(feature = "flexbox")]
+            flex_direction,
+            #[cfg(feature = "flexbox")]
+            flex_wrap,
+            #[cfg(feature = "flexbox")]
+            flex_basis,
+            #[cfg(feature = "flexbox")]
+            flex_grow,
+            #[cfg(feature = "flexbox")]
+            flex_shrink,
+            #[cfg(feature = "grid")]
+            grid_template_rows,
+            #[cfg(feature = "grid")]
+            grid_template_columns,
+            #[cfg(feature = "grid")]
+            grid_auto_rows,
+            #[cfg(feature = "grid")]
+            grid_auto_columns,
+            #[cfg(feature = "grid")]
+            grid_auto_flow,
+            #[cfg(feature = "grid")]
+            grid_row,
+            #[cfg(feature = "grid")]
+            grid_column,
+        } = style;
+
+        let extras = StyleExtras {
+            direction,
+            writing_mode,
+            scrollbar_width,
+            scrollbar_gutter,
+            inset,
+            aspect_ratio,
+            #[cfg(feature = "grid")]
+            justify_items,
+            #[cfg(feature = "grid")]
+            justify_self,
+            #[cfg(feature = "grid")]
+            grid_template_rows,
+            #[cfg(feature = "grid")]
+            grid_template_columns,
+            #[cfg(feature = "grid")]
+            grid_auto_rows,
+            #[cfg(feature = "grid")]
+            grid_auto_columns,
+            #[cfg(feature = "grid")]
+            grid_auto_flow,
+            #[cfg(feature = "grid")]
+            grid_row,
+            #[cfg(feature = "grid")]
+            grid_column,
+        };
+        CompactStyle {
+            display,
+            item_is_table,
+            box_sizing,
+            position,
+            overflow,
+            size,
+            min_size,
+            max_size,
+            margin,
+            padding,
+            border,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_items,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_self,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            justify_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            gap,
+            #[cfg(feature = "block_layout")]
+            text_align,
+            #[cfg(feature = "flexbox")]
+            flex_direction,
+            #[cfg(feature = "flexbox")]
+            flex_wrap,
+            #[cfg(feature = "flexbox")]
+            flex_basis,
+            #[cfg(feature = "flexbox")]
+            flex_grow,
+            #[cfg(feature = "flexbox")]
+            flex_shrink,
+            extras: (extras != DEFAULT_EXTRAS).then(|| Box::new(extras)),
+        }
+    }
+}
+
+impl From<&Style> for CompactStyle {
+    fn from(style: &Style) -> Self {
+        CompactStyle::from(style.clone())
+    }
+}
+
+impl From<&CompactStyle> for Style {
+    fn from(style: &CompactStyle) -> Self {
+        style.to_style()
+    }
+}
+
+impl Default for CompactStyle {
+    fn default() -> Self {
+        CompactStyle::from(Style::DEFAULT)
+    }
+}
+
+impl CoreStyle for CompactStyle {
+    #[inline(always)]
+    fn box_generation_mode(&self) -> BoxGenerationMode {
+        match self.display {
+            Display::None => BoxGenerationMode::None,
+            _ => BoxGenerationMode::Normal,
+        }
+    }
+    #[inline(always)]
+    #[cfg(feature = "block_layout")]
+    fn is_block(&self) -> bool {
+        matches!(self.display, Display::Block)
+    }
+    #[inline(always)]
+    fn box_sizing(&self) -> BoxSizing {
+        self.box_sizing
+    }
+    #[inline(always)]
+    fn overflow(&self) -> Point<Overflow> {
+        self.overflow
+    }
+    #[inline(always)]
+    fn scrollbar_width(&self) -> f32 {
+        self.extras().scrollbar_width
+    }
+    #[inline(always)]
+    fn scrollbar_gutter(&self) -> ScrollbarGutter {
+        self.extras().scrollbar_gutter
+    }
+    #[inline(always)]
+    fn position(&self) -> Position {
+        self.position
+    }
+    #[inline(always)]
+    fn inset(&self) -> Rect<LengthPercentageAuto> {
+        self.extras().inset
+    }
+    #[inline(always)]
+    fn size(&self) -> Size<Dimension> {
+        self.size
+    }
+    #[inline(always)]
+    fn min_size(&self) -> Size<Dimension> {
+        self.min_size
+    }
+    #[inline(always)]
+    fn max_size(&self) -> Size<Dimension> {
+        self.max_size
+    }
+    #[inline(always)]
+    fn aspect_ratio(&self) -> Option<f32> {
+        self.extras().aspect_ratio
+    }
+    #[inline(always)]
+    fn margin(&self) -> Rect<LengthPercentageAuto> {
+        self.margin
+    }
+    #[inline(always)]
+    fn padding(&self) -> Rect<LengthPercentage> {
+        self.padding
+    }
+    #[inline(always)]
+    fn border(&self) -> Rect<LengthPercentage> {
+        self.border
+    }
+}
+
+#[cfg(feature = "block_layout")]
+impl BlockContainerStyle for CompactStyle {
+    #[inline(always)]
+    fn text_align(&self) -> TextAlign {
+        self.text_align
+    }
+}
+
+#[cfg(feature = "block_layout")]
+impl BlockItemStyle for CompactStyle {
+    #[inline(always)]
+    fn is_table(&self) -> bool {
+        self.item_is_table
+    }
+}
+
+#[cfg(feature = "flexbox")]
+impl FlexboxContainerStyle for CompactStyle {
+    #[inline(always)]
+    fn flex_direction(&self) -> FlexDirection {
+        self.flex_direction
+    }
+    #[inline(always)]
+    fn flex_wrap(&self) -> FlexWrap {
+        self.flex_wrap
+    }
+    #[inline(always)]
+    fn gap(&self) -> Size<LengthPercentage> {
+        self.gap
+    }
+    #[inline(always)]
+    fn align_content(&self) -> Option<AlignContent> {
+        self.align_content
+    }
+    #[inline(always)]
+    fn align_items(&self) -> Option<AlignItems> {
+        self.align_items
+    }
+    #[inline(always)]
+    fn justify_content(&self) -> Option<JustifyContent> {
+        self.justify_content
+    }
+}
+
+#[cfg(feature = "flexbox")]
+impl FlexboxItemStyle for CompactStyle {
+    #[inline(always)]
+    fn flex_basis(&self) -> Dimension {
+        self.flex_basis
+    }
+    #[inline(always)]
+    fn flex_grow(&self) -> f32 {
+        self.flex_grow
+    }
+    #[inline(always)]
+    fn flex_shrink(&self) -> f32 {
+        self.flex_shrink
+    }
+    #[inline(always)]
+    fn align_self(&self) -> Option<AlignSelf> {
+        self.align_self
+    }
+}
+
+#[cfg(feature = "grid")]
+impl GridContainerStyle for CompactStyle {
+    type TemplateTrackList<'a> = &'a [TrackSizingFunction] where Self: 'a;
+    type AutoTrackList<'a> = &'a [NonRepeatedTrackSizingFunction] where Self: 'a;
+
+    #[inline(always)]
+    fn grid_template_rows(&self) -> &[TrackSizingFunction] {
+        &self.extras().grid_template_rows
+    }
+    #[inline(always)]
+    fn grid_template_columns(&self) -> &[TrackSizingFunction] {
+        &self.extras().grid_template_columns
+    }
+    #[inline(always)]
+    fn grid_auto_rows(&self) -> &[NonRepeatedTrackSizingFunction] {
+        &self.extras().grid_auto_rows
+    }
+    #[inline(always)]
+    fn grid_auto_columns(&self) -> &[NonRepeatedTrackSizingFunction] {
+        &self.extras().grid_auto_columns
+    }
+    #[inline(always)]
+    fn grid_auto_flow(&self) -> GridAutoFlow {
+        self.extras().grid_auto_flow
+    }
+    #[inline(always)]
+    fn gap(&self) -> Size<LengthPercentage> {
+        self.gap
+    }
+    #[inline(always)]
+    fn align_content(&self) -> Option<AlignContent> {
+        self.align_content
+    }
+    #[inline(always)]
+    fn justify_content(&self) -> Option<JustifyContent> {
+        self.justify_content
+    }
+    #[inline(always)]
+    fn align_items(&self) -> Option<AlignItems> {
+        self.align_items
+    }
+    #[inline(always)]
+    fn justify_items(&self) -> Option<AlignItems> {
+        self.extras().justify_items
+    }
+}
+
+#[cfg(feature = "grid")]
+impl GridItemStyle for CompactStyle {
+    #[inline(always)]
+    fn grid_row(&self) -> Line<GridPlacement> {
+        self.extras().grid_row
+    }
+    #[inline(always)]
+    fn grid_column(&self) -> Line<GridPlacement> {
+        self.extras().grid_column
+    }
+    #[inline(always)]
+    fn align_self(&self) -> Option<AlignSelf> {
+        self.align_self
+    }
+    #[inline(always)]
+    fn justify_self(&self) -> Option<AlignSelf> {
+        self.extras().justify_self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompactStyle;
+    use crate::prelude::*;
+    use crate::style::CoreStyle;
+
+    #[test]
+    fn common_styles_do_not_allocate() {
+        let style = Style {
+            size: Size::from_lengths(100.0, 20.0),
+            padding: Rect::length(4.0),
+            #[cfg(feature = "flexbox")]
+            flex_grow: 1.0,
+            ..Default::default()
+        };
+        let compact = CompactStyle::from(&style);
+        assert_eq!(compact.heap_size(), 0);
+        assert_eq!(compact.size(), style.size);
+        assert_eq!(compact.to_style(), style);
+        assert_eq!(CompactStyle::default().to_style(), Style::DEFAULT);
+    }
+
+    #[test]
+    fn rarely_set_styles_round_trip() {
+        let style = Style {
+            position: Position::Absolute,
+            inset: Rect { left: length(1.0), right: auto(), top: percent(0.5), bottom: auto() },
+            aspect_ratio: Some(2.0),
+            direction: Direction::Rtl,
+            ..Default::default()
+        };
+        let compact = CompactStyle::from(&style);
+        assert!(compact.heap_size() > 0);
+        assert_eq!(compact.inset(), style.inset);
+        assert_eq!(compact.aspect_ratio(), Some(2.0));
+        assert_eq!(Style::from(&compact), style);
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn grid_templates_round_trip() {
+        use crate::style::GridContainerStyle;
+        use crate::style_helpers::fr;
+
+        let style = Style {
+            display: Display::Grid,
+            grid_template_columns: vec![length(10.0), fr(1.0)],
+            grid_auto_rows: vec![auto()],
+            ..Default::default()
+        };
+        let compact = CompactStyle::from(&style);
+        assert_eq!(compact.grid_template_columns(), &style.grid_template_columns[..]);
+        assert_eq!(compact.heap_size(), core::mem::size_of::<super::StyleExtras>() + style.heap_size());
+        assert_eq!(compact.to_style(), style);
+    }
+
+    #[test]
+    #[cfg(all(feature = "flexbox", feature = "grid", feature = "block_layout"))]
+    fn compact_style_is_smaller_than_style() {
+        assert_eq!(core::mem::size_of::<CompactStyle>(), 200);
+        assert!(core::mem::size_of::<CompactStyle>() < core::mem::size_of::<Style>());
+    }
+}
@@ -0,0 +1,192 @@
+//! Sparse sets of overrides to the properties of a [`Style`]
+use crate::geometry::{Point, Rect, Size};
+use crate::style::{
+    BoxSizing, Dimension, Direction, Display, LengthPercentage, LengthPercentageAuto, Overflow, Position,
+    ScrollbarGutter, Style, WritingMode,
+};
+
+#[cfg(feature = "grid")]
+use crate::geometry::Line;
+#[cfg(feature = "block_layout")]
+use crate::style::TextAlign;
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use crate::style::{AlignContent, AlignItems, AlignSelf, JustifyContent};
+#[cfg(feature = "flexbox")]
+use crate::style::{FlexDirection, FlexWrap};
+#[cfg(feature = "grid")]
+use crate::style::{GridAutoFlow, GridPlacement, NonRepeatedTrackSizingFunction, TrackSizingFunction};
+#[cfg(feature = "grid")]
+use crate::util::sys::GridTrackVec;
+
+/// Deserializes a present value as `Some`, so that overriding an `Option` property with `None` round-trips
+#[cfg(feature = "serde")]
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Generates [`StyleDelta`] and the methods which create and apply it from the list of the properties of [`Style`]
+macro_rules! style_delta {
+    ($($(#[$attr:meta])* $field:ident: $ty:ty,)*) => {
+        /// A sparse set of overrides to the properties of a [`Style`]. Each property which is `Some` replaces the
+        /// property of the style it is applied to, and each property which is `None` leaves it unchanged.
+        ///
+        /// Created with [`Style::diff`] and applied with [`Style::apply_delta`]. With the `serde` feature, only
+        /// the overridden properties are serialized, so a delta can be stored compactly (for example as the
+        /// differences of a skin from a base style).
+        #[derive(Clone, Debug, Default, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub struct StyleDelta {
+            $(
+                $(#[$attr])*
+                #[doc = concat!("Override of [`Style::", stringify!($field), "`]")]
+                #[cfg_attr(
+                    feature = "serde",
+                    serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")
+                )]
+                pub $field: Option<$ty>,
+            )*
+        }
+
+        impl StyleDelta {
+            /// Whether the delta doesn't override any property
+            pub fn is_empty(&self) -> bool {
+                $(
+                    $(#[$attr])*
+                    if self.$field.is_some() {
+                        return false;
+                    }
+                )*
+                true
+            }
+        }
+
+        impl Style {
+            /// Replaces each property of the style which is overridden by `delta`
+            pub fn apply_delta(&mut self, delta: &StyleDelta) {
+                $(
+                    $(#[$attr])*
+                    if let Some(value) = &delta.$field {
+                        self.$field = value.clone();
+                    }
+                )*
+            }
+
+            /// The delta which overrides the properties of this style which differ in `other`, such that
+            /// applying it to this style results in `other`
+            pub fn diff(&self, other: &Style) -> StyleDelta {
+                let mut delta = StyleDelta::default();
+                $(
+                    $(#[$attr])*
+                    if self.$field != other.$field {
+                        delta.$field = Some(other.$field.clone());
+                    }
+                )*
+                delta
+            }
+        }
+    };
+}
+
+style_delta! {
+    display: Display,
+    item_is_table: bool,
+    box_sizing: BoxSizing,
+    direction: Direction,
+    writing_mode: WritingMode,
+    overflow: Point<Overflow>,
+    scrollbar_width: f32,
+    scrollbar_gutter: ScrollbarGutter,
+    position: Position,
+    inset: Rect<LengthPercentageAuto>,
+    size: Size<Dimension>,
+    min_size: Size<Dimension>,
+    max_size: Size<Dimension>,
+    aspect_ratio: Option<f32>,
+    margin: Rect<LengthPercentageAuto>,
+    padding: Rect<LengthPercentage>,
+    border: Rect<LengthPercentage>,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    align_items: Option<AlignItems>,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    align_self: Option<AlignSelf>,
+    #[cfg(feature = "grid")]
+    justify_items: Option<AlignItems>,
+    #[cfg(feature = "grid")]
+    justify_self: Option<AlignSelf>,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    align_content: Option<AlignContent>,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    justify_content: Option<JustifyContent>,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    gap: Size<LengthPercentage>,
+    #[cfg(feature = "block_layout")]
+    text_align: TextAlign,
+    #[cfg(feature = "flexbox")]
+    flex_direction: FlexDirection,
+    #[cfg(feature = "flexbox")]
+    flex_wrap: FlexWrap,
+    #[cfg(feature = "flexbox")]
+    flex_basis: Dimension,
+    #[cfg(feature = "flexbox")]
+    flex_grow: f32,
+    #[cfg(feature = "flexbox")]
+    flex_shrink: f32,
+    #[cfg(feature = "grid")]
+    grid_template_rows: GridTrackVec<TrackSizingFunction>,
+    #[cfg(feature = "grid")]
+    grid_template_columns: GridTrackVec<TrackSizingFunction>,
+    #[cfg(feature = "grid")]
+    grid_auto_rows: GridTrackVec<NonRepeatedTrackSizingFunction>,
+    #[cfg(feature = "grid")]
+    grid_auto_columns: GridTrackVec<NonRepeatedTrackSizingFunction>,
+    #[cfg(feature = "grid")]
+    grid_auto_flow: GridAutoFlow,
+    #[cfg(feature = "grid")]
+    grid_row: Line<GridPlacement>,
+    #[cfg(feature = "grid")]
+    grid_column: Line<GridPlacement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StyleDelta;
+    use crate::geometry::Size;
+    use crate::style::{Display, Style};
+    use crate::style_helpers::{length, percent};
+
+    #[test]
+    fn diff_of_equal_styles_is_empty() {
+        let style = Style { size: Size { width: length(10.0), height: percent(0.5) }, ..Default::default() };
+        assert!(style.diff(&style.clone()).is_empty());
+        assert_eq!(StyleDelta::default(), Style::default().diff(&Style::default()));
+    }
+
+    #[test]
+    fn applying_a_diff_results_in_the_other_style() {
+        let base = Style { padding: length(4.0), ..Default::default() };
+        let skin = Style {
+            display: Display::None,
+            aspect_ratio: Some(2.0),
+            size: Size { width: length(100.0), height: percent(1.0) },
+            ..base.clone()
+        };
+
+        let delta = base.diff(&skin);
+        assert_eq!(delta.display, Some(Display::None));
+        assert_eq!(delta.aspect_ratio, Some(Some(2.0)));
+        assert_eq!(delta.padding, None);
+
+        let mut style = base.clone();
+        style.apply_delta(&delta);
+        assert_eq!(style, skin);
+
+        // Overriding an `Option` property with `None` is distinct from not overriding it
+        let mut style = skin.clone();
+        style.apply_delta(&skin.diff(&base));
+        assert_eq!(style, base);
+    }
+}
@@ -1,7 +1,9 @@
 //! Style types for representing lengths / sizes
 
 use crate::geometry::{Rect, Size};
-use crate::style_helpers::{FromLength, FromPercent, TaffyAuto, TaffyMaxContent, TaffyMinContent, TaffyZero};
+use crate::style_helpers::{
+    FromLength, FromPercent, TaffyAuto, TaffyMaxContent, TaffyMinContent, TaffyNumber, TaffyZero,
+};
 use crate::util::sys::abs;
 
 /// A unit of linear measurement
@@ -20,13 +22,27 @@ impl TaffyZero for LengthPercentage {
     const ZERO: Self = Self::Length(0.0);
 }
 impl FromLength for LengthPercentage {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Self::Length(value.into())
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Length(value.into_f32())
     }
 }
 impl FromPercent for LengthPercentage {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Self::Percent(percent.into())
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::Percent(percent.into_f32())
+    }
+}
+
+impl LengthPercentage {
+    /// Returns an absolute length, without the type annotation needed by the generic
+    /// [`length`](crate::style_helpers::length) helper
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Length(value.into_f32())
+    }
+
+    /// Returns a percentage, without the type annotation needed by the generic
+    /// [`percent`](crate::style_helpers::percent) helper
+    pub fn percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::Percent(percent.into_f32())
     }
 }
 
@@ -51,13 +67,13 @@ impl TaffyAuto for LengthPercentageAuto {
     const AUTO: Self = Self::Auto;
 }
 impl FromLength for LengthPercentageAuto {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Self::Length(value.into())
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Length(value.into_f32())
     }
 }
 impl FromPercent for LengthPercentageAuto {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Self::Percent(percent.into())
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::Percent(percent.into_f32())
     }
 }
 
@@ -71,6 +87,23 @@ impl From<LengthPercentage> for LengthPercentageAuto {
 }
 
 impl LengthPercentageAuto {
+    /// Returns an absolute length, without the type annotation needed by the generic
+    /// [`length`](crate::style_helpers::length) helper
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Length(value.into_f32())
+    }
+
+    /// Returns a percentage, without the type annotation needed by the generic
+    /// [`percent`](crate::style_helpers::percent) helper
+    pub fn percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::Percent(percent.into_f32())
+    }
+
+    /// Returns `Auto`, without the type annotation needed by the generic [`auto`](crate::style_helpers::auto) helper
+    pub const fn auto() -> Self {
+        Self::Auto
+    }
+
     /// Returns:
     ///   - Some(length) for Length variants
     ///   - Some(resolved) using the provided context for Percent variants
@@ -104,6 +137,18 @@ pub enum Dimension {
     Percent(f32),
     /// The dimension should be automatically computed
     Auto,
+    /// The dimension should be computed from the content of the node (`flex-basis: content` in CSS). Unlike `Auto`,
+    /// this does not fall back to the node's `size` in the main axis.
+    ///
+    /// This is only meaningful for `flex_basis`. When used for other properties it behaves the same as `Auto`.
+    Content,
+    /// The dimension is stored in percentage relative to the size of the layout root (the node passed to
+    /// [`TaffyTree::compute_layout`](crate::TaffyTree::compute_layout)) rather than the parent item, in the same axis.
+    ///
+    /// This is only supported for `size`, `min_size`, `max_size` and `flex_basis`, and is resolved by
+    /// [`TaffyTree`](crate::TaffyTree) before layout. When a [`LayoutPartialTree`](crate::LayoutPartialTree) other
+    /// than `TaffyTree` is laid out it behaves the same as `Auto`.
+    RootPercent(f32),
 }
 impl TaffyZero for Dimension {
     const ZERO: Self = Self::Length(0.0);
@@ -112,13 +157,13 @@ impl TaffyAuto for Dimension {
     const AUTO: Self = Self::Auto;
 }
 impl FromLength for Dimension {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Self::Length(value.into())
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Length(value.into_f32())
     }
 }
 impl FromPercent for Dimension {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Self::Percent(percent.into())
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::Percent(percent.into_f32())
     }
 }
 
@@ -142,6 +187,48 @@ impl From<LengthPercentageAuto> for Dimension {
 }
 
 impl Dimension {
+    /// Returns an absolute length, without the type annotation needed by the generic
+    /// [`length`](crate::style_helpers::length) helper
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Length(value.into_f32())
+    }
+
+    /// Returns a percentage, without the type annotation needed by the generic
+    /// [`percent`](crate::style_helpers::percent) helper
+    pub fn percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::Percent(percent.into_f32())
+    }
+
+    /// Returns `Auto`, without the type annotation needed by the generic [`auto`](crate::style_helpers::auto) helper
+    pub const fn auto() -> Self {
+        Self::Auto
+    }
+
+    /// Returns `Content` (`flex-basis: content` in CSS)
+    pub const fn content() -> Self {
+        Self::Content
+    }
+
+    /// Returns a percentage of the layout root's size
+    pub fn root_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Self::RootPercent(percent.into_f32())
+    }
+
+    /// Resolves a `RootPercent` value against the size of the layout root in the same axis, returning `Auto` if the
+    /// root's size is not known. Other values are returned unchanged.
+    pub(crate) fn resolve_root_percent(self, root_size: Option<f32>) -> Self {
+        match self {
+            Self::RootPercent(percent) => root_size.map_or(Self::Auto, |size| Self::Length(size * percent)),
+            other => other,
+        }
+    }
+
+    /// Returns true if the value behaves as `auto` (it is either `Auto` or `Content`)
+    #[inline(always)]
+    pub fn is_auto(self) -> bool {
+        matches!(self, Self::Auto | Self::Content)
+    }
+
     /// Get Length value if value is Length variant
     #[cfg(feature = "grid")]
     pub fn into_option(self) -> Option<f32> {
@@ -198,8 +285,8 @@ impl TaffyMinContent for AvailableSpace {
     const MIN_CONTENT: Self = Self::MinContent;
 }
 impl FromLength for AvailableSpace {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Self::Definite(value.into())
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Self::Definite(value.into_f32())
     }
 }
 
@@ -188,7 +188,8 @@ pub enum GenericGridPlacement<LineType: GridCoordinate> {
 /// A grid line placement using the normalized OriginZero coordinates to specify line positions.
 pub(crate) type OriginZeroGridPlacement = GenericGridPlacement<OriginZeroLine>;
 
-/// A grid line placement specification. Used for grid-[row/column]-[start/end]. Named tracks are not implemented.
+/// A grid line placement specification. Used for grid-[row/column]-[start/end]. Named lines are not implemented, and
+/// so neither are placements which refer to them (such as the named span `span 2 header`).
 ///
 /// Defaults to `GridPlacement::Auto`
 ///
@@ -312,6 +313,85 @@ impl Line<GridPlacement> {
             (Auto, Auto) => (0, 1, 0),
         }
     }
+
+    /// Checks that the placement is not contradictory
+    ///
+    /// A placement is rejected if either edge is grid line zero or a span of zero tracks, or if both edges are lines
+    /// counted from the same end of the grid and the end line is not after the start line. Lines counted from
+    /// opposite ends of the grid can only be compared once the number of tracks is known (see [`Self::normalize`]).
+    pub fn validate(&self) -> Result<(), GridPlacementError> {
+        for placement in [self.start, self.end] {
+            match placement {
+                GenericGridPlacement::Line(line) if line.as_i16() == 0 => return Err(GridPlacementError::ZeroLine),
+                GenericGridPlacement::Span(0) => return Err(GridPlacementError::ZeroSpan),
+                _ => {}
+            }
+        }
+        if let (GenericGridPlacement::Line(start), GenericGridPlacement::Line(end)) = (self.start, self.end) {
+            let (start, end) = (start.as_i16(), end.as_i16());
+            if start.signum() == end.signum() && start >= end {
+                return Err(GridPlacementError::EndNotAfterStart { start, end });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the placement and converts any lines counted from the end of the explicit grid (negative indices)
+    /// into lines counted from the start of the grid, for a grid with `explicit_track_count` explicit tracks
+    ///
+    /// Negative lines which fall before the start of the explicit grid have no positive equivalent and are left unchanged.
+    pub fn normalize(self, explicit_track_count: u16) -> Result<Self, GridPlacementError> {
+        self.validate()?;
+
+        let normalize_placement = |placement: GridPlacement| match placement {
+            GenericGridPlacement::Line(line) => {
+                let oz_line = line.into_origin_zero_line(explicit_track_count);
+                if oz_line.0 >= 0 {
+                    GenericGridPlacement::Line(GridLine::from(oz_line.0 + 1))
+                } else {
+                    placement
+                }
+            }
+            _ => placement,
+        };
+
+        if let (GenericGridPlacement::Line(start), GenericGridPlacement::Line(end)) = (self.start, self.end) {
+            if start.into_origin_zero_line(explicit_track_count) >= end.into_origin_zero_line(explicit_track_count) {
+                return Err(GridPlacementError::EndNotAfterStart { start: start.as_i16(), end: end.as_i16() });
+            }
+        }
+
+        Ok(Line { start: normalize_placement(self.start), end: normalize_placement(self.end) })
+    }
+}
+
+/// Error returned when a `Line<GridPlacement>` is contradictory. See `Line::<GridPlacement>::validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridPlacementError {
+    /// One of the edges was placed at grid line zero, which is not a valid grid line
+    ZeroLine,
+    /// One of the edges was a span of zero tracks
+    ZeroSpan,
+    /// Both edges were placed at grid lines, but the end line is not after the start line
+    EndNotAfterStart {
+        /// The start line
+        start: i16,
+        /// The end line
+        end: i16,
+    },
+}
+#[cfg(feature = "std")]
+impl std::error::Error for GridPlacementError {}
+impl core::fmt::Display for GridPlacementError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ZeroLine => f.write_str("Grid line 0 is not a valid grid line"),
+            Self::ZeroSpan => f.write_str("A grid item cannot span zero tracks"),
+            Self::EndNotAfterStart { start, end } => {
+                write!(f, "Grid placement ends at line {end} which is not after its start line {start}")
+            }
+        }
+    }
 }
 
 impl Line<OriginZeroGridPlacement> {
@@ -431,18 +511,18 @@ impl TaffyZero for MaxTrackSizingFunction {
     const ZERO: Self = Self::Fixed(LengthPercentage::ZERO);
 }
 impl FromLength for MaxTrackSizingFunction {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
         Self::Fixed(LengthPercentage::from_length(value))
     }
 }
 impl FromPercent for MaxTrackSizingFunction {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
         Self::Fixed(LengthPercentage::from_percent(percent))
     }
 }
 impl FromFlex for MaxTrackSizingFunction {
-    fn from_flex<Input: Into<f32> + Copy>(flex: Input) -> Self {
-        Self::Fraction(flex.into())
+    fn from_flex<Input: TaffyNumber>(flex: Input) -> Self {
+        Self::Fraction(flex.into_f32())
     }
 }
 
@@ -545,12 +625,12 @@ impl TaffyZero for MinTrackSizingFunction {
     const ZERO: Self = Self::Fixed(LengthPercentage::ZERO);
 }
 impl FromLength for MinTrackSizingFunction {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
         Self::Fixed(LengthPercentage::from_length(value))
     }
 }
 impl FromPercent for MinTrackSizingFunction {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
         Self::Fixed(LengthPercentage::from_percent(percent))
     }
 }
@@ -633,17 +713,17 @@ impl TaffyZero for NonRepeatedTrackSizingFunction {
     const ZERO: Self = Self { min: MinTrackSizingFunction::ZERO, max: MaxTrackSizingFunction::ZERO };
 }
 impl FromLength for NonRepeatedTrackSizingFunction {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
         Self { min: MinTrackSizingFunction::from_length(value), max: MaxTrackSizingFunction::from_length(value) }
     }
 }
 impl FromPercent for NonRepeatedTrackSizingFunction {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
         Self { min: MinTrackSizingFunction::from_percent(percent), max: MaxTrackSizingFunction::from_percent(percent) }
     }
 }
 impl FromFlex for NonRepeatedTrackSizingFunction {
-    fn from_flex<Input: Into<f32> + Copy>(flex: Input) -> Self {
+    fn from_flex<Input: TaffyNumber>(flex: Input) -> Self {
         Self { min: MinTrackSizingFunction::AUTO, max: MaxTrackSizingFunction::from_flex(flex) }
     }
 }
@@ -728,17 +808,17 @@ impl TaffyZero for TrackSizingFunction {
     const ZERO: Self = Self::Single(NonRepeatedTrackSizingFunction::ZERO);
 }
 impl FromLength for TrackSizingFunction {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
         Self::Single(NonRepeatedTrackSizingFunction::from_length(value))
     }
 }
 impl FromPercent for TrackSizingFunction {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
         Self::Single(NonRepeatedTrackSizingFunction::from_percent(percent))
     }
 }
 impl FromFlex for TrackSizingFunction {
-    fn from_flex<Input: Into<f32> + Copy>(flex: Input) -> Self {
+    fn from_flex<Input: TaffyNumber>(flex: Input) -> Self {
         Self::Single(NonRepeatedTrackSizingFunction::from_flex(flex))
     }
 }
@@ -747,3 +827,43 @@ impl From<MinMax<MinTrackSizingFunction, MaxTrackSizingFunction>> for TrackSizin
         Self::Single(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{GridPlacement, GridPlacementError};
+    use crate::geometry::Line;
+    use crate::style_helpers::{auto, line, span};
+
+    fn placement(start: GridPlacement, end: GridPlacement) -> Line<GridPlacement> {
+        Line { start, end }
+    }
+
+    #[test]
+    fn validate_rejects_contradictory_placements() {
+        assert_eq!(placement(line(1), line(3)).validate(), Ok(()));
+        assert_eq!(placement(line(1), line(-1)).validate(), Ok(()));
+        assert_eq!(placement(line(0), auto()).validate(), Err(GridPlacementError::ZeroLine));
+        assert_eq!(placement(line(1), span(0)).validate(), Err(GridPlacementError::ZeroSpan));
+        assert_eq!(
+            placement(line(3), line(2)).validate(),
+            Err(GridPlacementError::EndNotAfterStart { start: 3, end: 2 })
+        );
+        assert_eq!(
+            placement(line(-1), line(-1)).validate(),
+            Err(GridPlacementError::EndNotAfterStart { start: -1, end: -1 })
+        );
+    }
+
+    #[test]
+    fn normalize_resolves_negative_lines() {
+        // With 3 explicit tracks, line -1 is line 4
+        assert_eq!(placement(line(2), line(-1)).normalize(3), Ok(placement(line(2), line(4))));
+        assert_eq!(placement(line(-2), span(2)).normalize(3), Ok(placement(line(3), span(2))));
+        // Lines before the start of the explicit grid have no positive equivalent
+        assert_eq!(placement(line(-5), line(1)).normalize(3), Ok(placement(line(-5), line(1))));
+        assert_eq!(
+            placement(line(4), line(-2)).normalize(3),
+            Err(GridPlacementError::EndNotAfterStart { start: 4, end: -2 })
+        );
+    }
+}
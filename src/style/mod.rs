@@ -1,6 +1,11 @@
 //! A typed representation of [CSS style properties](https://css-tricks.com/snippets/css/a-guide-to-flexbox/) in Rust. Used as input to layout computation.
 mod alignment;
+#[cfg(feature = "compact_style")]
+mod compact;
+mod delta;
 mod dimension;
+mod validate;
+mod variant;
 
 #[cfg(feature = "block_layout")]
 mod block;
@@ -8,9 +13,16 @@ mod block;
 mod flex;
 #[cfg(feature = "grid")]
 mod grid;
+#[cfg(feature = "grid")]
+mod track_list;
 
 pub use self::alignment::{AlignContent, AlignItems, AlignSelf, JustifyContent, JustifyItems, JustifySelf};
+#[cfg(feature = "compact_style")]
+pub use self::compact::CompactStyle;
+pub use self::delta::StyleDelta;
 pub use self::dimension::{AvailableSpace, Dimension, LengthPercentage, LengthPercentageAuto};
+pub use self::validate::StyleWarning;
+pub use self::variant::{SizeCondition, StyleVariant};
 
 #[cfg(feature = "block_layout")]
 pub use self::block::{BlockContainerStyle, BlockItemStyle, TextAlign};
@@ -20,9 +32,11 @@ pub use self::flex::{FlexDirection, FlexWrap, FlexboxContainerStyle, FlexboxItem
 pub(crate) use self::grid::{GenericGridPlacement, OriginZeroGridPlacement};
 #[cfg(feature = "grid")]
 pub use self::grid::{
-    GridAutoFlow, GridContainerStyle, GridItemStyle, GridPlacement, GridTrackRepetition, MaxTrackSizingFunction,
-    MinTrackSizingFunction, NonRepeatedTrackSizingFunction, TrackSizingFunction,
+    GridAutoFlow, GridContainerStyle, GridItemStyle, GridPlacement, GridPlacementError, GridTrackRepetition,
+    MaxTrackSizingFunction, MinTrackSizingFunction, NonRepeatedTrackSizingFunction, TrackSizingFunction,
 };
+#[cfg(feature = "grid")]
+pub use self::track_list::{parse_implicit_track_list, parse_track_list, TrackListParseError, TrackListParseErrorKind};
 
 use crate::geometry::{Point, Rect, Size};
 
@@ -66,6 +80,11 @@ pub trait CoreStyle {
     fn scrollbar_width(&self) -> f32 {
         0.0
     }
+    /// Whether space for scrollbars should also be reserved when a node could scroll but is not `Overflow::Scroll`
+    #[inline(always)]
+    fn scrollbar_gutter(&self) -> ScrollbarGutter {
+        Style::DEFAULT.scrollbar_gutter
+    }
 
     // Position properties
     /// What should the `position` value of this struct use as a base offset?
@@ -135,6 +154,10 @@ pub enum Display {
     /// The children will follow the CSS Grid layout algorithm
     #[cfg(feature = "grid")]
     Grid,
+    /// The children will be placed on lines (along with content of the node which is not represented in the tree,
+    /// such as text) by an inline formatter. See [`compute_inline_layout`](crate::compute_inline_layout).
+    #[cfg(feature = "inline_layout")]
+    Inline,
     /// The node is hidden, and it's children will also be hidden
     None,
 }
@@ -173,6 +196,8 @@ impl core::fmt::Display for Display {
             Display::Flex => write!(f, "FLEX"),
             #[cfg(feature = "grid")]
             Display::Grid => write!(f, "GRID"),
+            #[cfg(feature = "inline_layout")]
+            Display::Inline => write!(f, "INLINE"),
         }
     }
 }
@@ -256,6 +281,117 @@ impl Default for BoxSizing {
     }
 }
 
+/// A set of default style values, which can be used as the default style of a [`TaffyTree`](crate::TaffyTree)
+///
+/// This allows layouts ported from other engines to behave identically without having to override the differing defaults
+/// on every style: pass [`StyleDefaults::style`] to [`TaffyTree::set_default_style`](crate::TaffyTree::set_default_style)
+/// and create styles from [`TaffyTree::default_style`](crate::TaffyTree::default_style).
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StyleDefaults {
+    /// The CSS initial values ([`Style::DEFAULT`])
+    #[default]
+    Css,
+    /// The defaults used by Yoga and React Native ([`Style::YOGA_DEFAULT`])
+    Yoga,
+}
+
+impl StyleDefaults {
+    /// Returns the default style for this set of defaults
+    pub const fn style(self) -> Style {
+        match self {
+            StyleDefaults::Css => Style::DEFAULT,
+            StyleDefaults::Yoga => Style::YOGA_DEFAULT,
+        }
+    }
+}
+
+/// The direction in which inline content (such as text) flows
+///
+/// Unlike most styles, `direction` is an inherited property: a node with [`Direction::Inherit`] takes the direction of its
+/// nearest ancestor which specifies one (or [`Direction::Ltr`] if no ancestor does). Use
+/// [`TaffyTree::computed_direction`](crate::TaffyTree::computed_direction) to resolve the value for a node.
+///
+/// Taffy's layout algorithms do not currently take the direction into account. It is exposed so that measure functions
+/// and other consumers of the tree have a single source of truth for it.
+///
+/// See <https://developer.mozilla.org/en-US/docs/Web/CSS/direction>
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    /// The direction is inherited from the node's parent
+    #[default]
+    Inherit,
+    /// Inline content flows from left to right
+    Ltr,
+    /// Inline content flows from right to left
+    Rtl,
+}
+
+/// Whether lines of inline content (such as text) are laid out horizontally or vertically, and the direction in which
+/// successive lines are stacked
+///
+/// Like [`Direction`], `writing-mode` is an inherited property: a node with [`WritingMode::Inherit`] takes the writing
+/// mode of its nearest ancestor which specifies one (or [`WritingMode::HorizontalTb`] if no ancestor does). Use
+/// [`TaffyTree::computed_writing_mode`](crate::TaffyTree::computed_writing_mode) to resolve the value for a node.
+///
+/// Taffy's layout algorithms do not currently take the writing mode into account. It is exposed so that measure
+/// functions and other consumers of the tree have a single source of truth for it.
+///
+/// See <https://developer.mozilla.org/en-US/docs/Web/CSS/writing-mode>
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WritingMode {
+    /// The writing mode is inherited from the node's parent
+    #[default]
+    Inherit,
+    /// Lines are horizontal and are stacked from top to bottom
+    HorizontalTb,
+    /// Lines are vertical and are stacked from right to left
+    VerticalRl,
+    /// Lines are vertical and are stacked from left to right
+    VerticalLr,
+}
+
+/// The computed values of a node's inherited properties, with [`Direction::Inherit`] and [`WritingMode::Inherit`]
+/// resolved from its ancestors
+///
+/// See [`TaffyTree::computed_inherited_style`](crate::TaffyTree::computed_inherited_style) and
+/// [`TaffyTree::cascade_inherited_styles`](crate::TaffyTree::cascade_inherited_styles).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct InheritedStyle {
+    /// The computed direction. Never [`Direction::Inherit`].
+    pub direction: Direction,
+    /// The computed writing mode. Never [`WritingMode::Inherit`].
+    pub writing_mode: WritingMode,
+}
+
+impl InheritedStyle {
+    /// The values inherited by a root node: [`Direction::Ltr`] and [`WritingMode::HorizontalTb`]
+    pub const INITIAL: InheritedStyle =
+        InheritedStyle { direction: Direction::Ltr, writing_mode: WritingMode::HorizontalTb };
+
+    /// Computes the inherited values of a node with the provided `style` whose parent's computed values are `self`
+    pub fn cascade(self, style: &Style) -> InheritedStyle {
+        InheritedStyle {
+            direction: match style.direction {
+                Direction::Inherit => self.direction,
+                direction => direction,
+            },
+            writing_mode: match style.writing_mode {
+                WritingMode::Inherit => self.writing_mode,
+                writing_mode => writing_mode,
+            },
+        }
+    }
+}
+
+impl Default for InheritedStyle {
+    fn default() -> Self {
+        Self::INITIAL
+    }
+}
+
 /// How children overflowing their container should affect layout
 ///
 /// In CSS the primary effect of this property is to control whether contents of a parent container that overflow that container should
@@ -263,11 +399,15 @@ impl Default for BoxSizing {
 /// the main ones being:
 ///
 ///   - The automatic minimum size Flexbox/CSS Grid items with non-`Visible` overflow is `0` rather than being content based
-///   - `Overflow::Scroll` nodes have space in the layout reserved for a scrollbar (width controlled by the `scrollbar_width` property)
+///   - `Overflow::Scroll` nodes have space in the layout reserved for a scrollbar (width controlled by the `scrollbar_width` property),
+///     as do `Overflow::Hidden` nodes with a stable [`ScrollbarGutter`]
 ///
 /// In Taffy, we only implement the layout related secondary effects as we are not concerned with drawing/painting. The amount of space reserved for
 /// a scrollbar is controlled by the `scrollbar_width` property. If this is `0` then `Scroll` behaves identically to `Hidden`.
 ///
+/// The overflow of each axis is applied independently. Unlike CSS, where `visible` computes to `auto` when the other
+/// axis is not `visible`, a node that only scrolls vertically keeps a content-based automatic minimum width.
+///
 /// <https://developer.mozilla.org/en-US/docs/Web/CSS/overflow>
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -277,7 +417,8 @@ pub enum Overflow {
     #[default]
     Visible,
     /// The automatic minimum size of this node as a flexbox/grid item should be based on the size of its content.
-    /// Content that overflows this node should *not* contribute to the scroll region of its parent.
+    /// Content that overflows this node should *not* contribute to the scroll region of its parent, and is not included
+    /// in the node's own content size (as it can't be scrolled to).
     Clip,
     /// The automatic minimum size of this node as a flexbox/grid item should be `0`.
     /// Content that overflows this node should *not* contribute to the scroll region of its parent.
@@ -310,6 +451,48 @@ impl Overflow {
     }
 }
 
+/// Whether space for a scrollbar is reserved in nodes that could scroll but do not always show a scrollbar
+///
+/// Space is always reserved for the scrollbars of `Overflow::Scroll` nodes. With [`ScrollbarGutter::Stable`], space is
+/// also reserved for `Overflow::Hidden` nodes (which may scroll when their content overflows), so that the layout of
+/// their content does not shift when the content grows past the size of the node. The amount of space reserved is
+/// controlled by the `scrollbar_width` property.
+///
+/// Taffy reserves the space for a vertical scrollbar on the right edge of a node and the space for a horizontal
+/// scrollbar on the bottom edge. [`ScrollbarGutter::StableBothEdges`] additionally reserves the same amount of space on
+/// the opposite edge, so that the content stays centered.
+///
+/// See <https://developer.mozilla.org/en-US/docs/Web/CSS/scrollbar-gutter>
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScrollbarGutter {
+    /// Space is only reserved for the scrollbars of `Overflow::Scroll` nodes
+    #[default]
+    Auto,
+    /// Space is reserved for the scrollbars of `Overflow::Scroll` and `Overflow::Hidden` nodes
+    Stable,
+    /// As [`ScrollbarGutter::Stable`], but the same amount of space is also reserved on the opposite edge
+    StableBothEdges,
+}
+
+impl ScrollbarGutter {
+    /// Returns the space that should be reserved for scrollbars on each edge of a node with the given overflow
+    pub(crate) fn resolve(self, overflow: Point<Overflow>, scrollbar_width: f32) -> Rect<f32> {
+        // The axis are switched (transposed) because a node that scrolls vertically needs *horizontal* space to be
+        // reserved for a scrollbar
+        let reserved = overflow.transpose().map(|overflow| match (self, overflow) {
+            (_, Overflow::Scroll) => scrollbar_width,
+            (Self::Stable | Self::StableBothEdges, Overflow::Hidden) => scrollbar_width,
+            _ => 0.0,
+        });
+        // TODO: make side configurable based on the `direction` property
+        match self {
+            Self::StableBothEdges => Rect { left: reserved.x, right: reserved.x, top: reserved.y, bottom: reserved.y },
+            Self::Auto | Self::Stable => Rect { left: 0.0, right: reserved.x, top: 0.0, bottom: reserved.y },
+        }
+    }
+}
+
 /// A typed representation of the CSS style information for a single node.
 ///
 /// The most important idea in flexbox is the notion of a "main" and "cross" axis, which are always perpendicular to each other.
@@ -335,12 +518,18 @@ pub struct Style {
     pub item_is_table: bool,
     /// Should size styles apply to the content box or the border box of the node
     pub box_sizing: BoxSizing,
+    /// The direction in which inline content flows. Inherited from the parent node by default.
+    pub direction: Direction,
+    /// Whether lines of inline content are horizontal or vertical. Inherited from the parent node by default.
+    pub writing_mode: WritingMode,
 
     // Overflow properties
     /// How children overflowing their container should affect layout
     pub overflow: Point<Overflow>,
     /// How much space (in points) should be reserved for the scrollbars of `Overflow::Scroll` and `Overflow::Auto` nodes.
     pub scrollbar_width: f32,
+    /// Whether space for scrollbars should also be reserved when the node could scroll but is not `Overflow::Scroll`
+    pub scrollbar_gutter: ScrollbarGutter,
 
     // Position properties
     /// What should the `position` value of this struct use as a base offset?
@@ -461,8 +650,11 @@ impl Style {
         display: Display::DEFAULT,
         item_is_table: false,
         box_sizing: BoxSizing::BorderBox,
+        direction: Direction::Inherit,
+        writing_mode: WritingMode::Inherit,
         overflow: Point { x: Overflow::Visible, y: Overflow::Visible },
         scrollbar_width: 0.0,
+        scrollbar_gutter: ScrollbarGutter::Auto,
         position: Position::Relative,
         inset: Rect::auto(),
         margin: Rect::zero(),
@@ -517,6 +709,117 @@ impl Style {
         #[cfg(feature = "grid")]
         grid_column: Line { start: GridPlacement::Auto, end: GridPlacement::Auto },
     };
+
+    /// The default layout used by Yoga and React Native
+    ///
+    /// This differs from [`Style::DEFAULT`] in that `flex_direction` defaults to `Column`, `align_content` defaults to
+    /// `FlexStart` and `flex_shrink` defaults to `0.0`.
+    #[allow(clippy::let_and_return)]
+    pub const YOGA_DEFAULT: Style = {
+        #[allow(unused_mut)]
+        let mut style = Style::DEFAULT;
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        {
+            style.align_content = Some(AlignContent::FlexStart);
+        }
+        #[cfg(feature = "flexbox")]
+        {
+            style.flex_direction = FlexDirection::Column;
+            style.flex_shrink = 0.0;
+        }
+        style
+    };
+
+    /// Sets `align_content` and `justify_content` together, like the CSS `place-content` shorthand
+    ///
+    /// The single-value form of the shorthand, which sets both axes to the same value, corresponds to passing the
+    /// same value for both arguments.
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    pub fn set_place_content(&mut self, align: Option<AlignContent>, justify: Option<JustifyContent>) {
+        self.align_content = align;
+        self.justify_content = justify;
+    }
+
+    /// Sets `align_items` and `justify_items` together, like the CSS `place-items` shorthand
+    ///
+    /// The single-value form of the shorthand, which sets both axes to the same value, corresponds to passing the
+    /// same value for both arguments. `justify_items` only exists with the `grid` feature, so `justify` is ignored
+    /// without it.
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    pub fn set_place_items(
+        &mut self,
+        align: Option<AlignItems>,
+        #[cfg_attr(not(feature = "grid"), allow(unused_variables))] justify: Option<JustifyItems>,
+    ) {
+        self.align_items = align;
+        #[cfg(feature = "grid")]
+        {
+            self.justify_items = justify;
+        }
+    }
+
+    /// Sets `align_self` and `justify_self` together, like the CSS `place-self` shorthand
+    ///
+    /// The single-value form of the shorthand, which sets both axes to the same value, corresponds to passing the
+    /// same value for both arguments. `justify_self` only exists with the `grid` feature, so `justify` is ignored
+    /// without it.
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    pub fn set_place_self(
+        &mut self,
+        align: Option<AlignSelf>,
+        #[cfg_attr(not(feature = "grid"), allow(unused_variables))] justify: Option<JustifySelf>,
+    ) {
+        self.align_self = align;
+        #[cfg(feature = "grid")]
+        {
+            self.justify_self = justify;
+        }
+    }
+
+    /// The number of bytes this style has allocated on the heap (in addition to the `size_of::<Style>()` bytes of the struct itself)
+    ///
+    /// Only the grid track lists allocate, so this is always zero if the `grid` feature is disabled.
+    pub fn heap_size(&self) -> usize {
+        #[cfg(feature = "grid")]
+        {
+            track_lists_heap_size(
+                &self.grid_template_rows,
+                &self.grid_template_columns,
+                &self.grid_auto_rows,
+                &self.grid_auto_columns,
+            )
+        }
+        #[cfg(not(feature = "grid"))]
+        0
+    }
+}
+
+/// The number of bytes allocated on the heap by the grid track lists of a style
+#[cfg(feature = "grid")]
+pub(crate) fn track_lists_heap_size(
+    template_rows: &GridTrackVec<TrackSizingFunction>,
+    template_columns: &GridTrackVec<TrackSizingFunction>,
+    auto_rows: &GridTrackVec<NonRepeatedTrackSizingFunction>,
+    auto_columns: &GridTrackVec<NonRepeatedTrackSizingFunction>,
+) -> usize {
+    use core::mem::size_of;
+
+    fn template_size(tracks: &GridTrackVec<TrackSizingFunction>) -> usize {
+        let repetition_size: usize = tracks
+            .iter()
+            .map(|track| match track {
+                TrackSizingFunction::Single(_) => 0,
+                TrackSizingFunction::Repeat(_, repeated) => {
+                    repeated.capacity() * size_of::<NonRepeatedTrackSizingFunction>()
+                }
+            })
+            .sum();
+        tracks.capacity() * size_of::<TrackSizingFunction>() + repetition_size
+    }
+
+    template_size(template_rows)
+        + template_size(template_columns)
+        + (auto_rows.capacity() + auto_columns.capacity()) * size_of::<NonRepeatedTrackSizingFunction>()
 }
 
 impl Default for Style {
@@ -551,6 +854,10 @@ impl CoreStyle for Style {
         self.scrollbar_width
     }
     #[inline(always)]
+    fn scrollbar_gutter(&self) -> ScrollbarGutter {
+        self.scrollbar_gutter
+    }
+    #[inline(always)]
     fn position(&self) -> Position {
         self.position
     }
@@ -610,6 +917,10 @@ impl<T: CoreStyle> CoreStyle for &'_ T {
         (*self).scrollbar_width()
     }
     #[inline(always)]
+    fn scrollbar_gutter(&self) -> ScrollbarGutter {
+        (*self).scrollbar_gutter()
+    }
+    #[inline(always)]
     fn position(&self) -> Position {
         (*self).position()
     }
@@ -923,8 +1234,11 @@ mod tests {
             display: Default::default(),
             item_is_table: false,
             box_sizing: Default::default(),
+            direction: Default::default(),
+            writing_mode: Default::default(),
             overflow: Default::default(),
             scrollbar_width: 0.0,
+            scrollbar_gutter: Default::default(),
             position: Default::default(),
             #[cfg(feature = "flexbox")]
             flex_direction: Default::default(),
@@ -1017,8 +1331,11 @@ mod tests {
         // Display and Position
         assert_type_size_and_align::<Display>(1, 1);
         assert_type_size_and_align::<BoxSizing>(1, 1);
+        assert_type_size_and_align::<Direction>(1, 1);
+        assert_type_size_and_align::<WritingMode>(1, 1);
         assert_type_size_and_align::<Position>(1, 1);
         assert_type_size_and_align::<Overflow>(1, 1);
+        assert_type_size_and_align::<ScrollbarGutter>(1, 1);
 
         // Dimensions and aggregations of Dimensions
         assert_type_size_and_align::<f32>(4, 4);
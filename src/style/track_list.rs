@@ -0,0 +1,414 @@
+//! Parsing of CSS track lists such as the value of `grid-template-columns`
+//!
+//! For example `"100px minmax(min-content, 1fr) repeat(auto-fill, 50px)"`. Line names (such as `[header-start]`) are
+//! accepted but ignored, as named lines are not yet supported.
+use super::{
+    GridTrackRepetition, LengthPercentage, MaxTrackSizingFunction, MinTrackSizingFunction,
+    NonRepeatedTrackSizingFunction, TrackSizingFunction,
+};
+use crate::geometry::MinMax;
+use crate::util::sys::{GridTrackVec, Vec};
+
+/// The reason that a track list failed to parse. See [`TrackListParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackListParseErrorKind {
+    /// The input ended part way through a value
+    UnexpectedEnd,
+    /// An unexpected character or keyword was found
+    UnexpectedToken,
+    /// A number was malformed, negative, or had a unit which is not valid in this position
+    InvalidValue,
+    /// The repetition count of a `repeat()` was not a positive integer, `auto-fill` or `auto-fit`
+    InvalidRepeatCount,
+    /// A `repeat()` was nested inside another `repeat()`
+    NestedRepeat,
+    /// A `repeat()` did not contain any tracks, or a track list or track was empty
+    Empty,
+}
+
+/// Error returned when a track list string could not be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackListParseError {
+    /// The byte offset in the input at which the error occurred
+    pub offset: usize,
+    /// The reason that parsing failed
+    pub kind: TrackListParseErrorKind,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TrackListParseError {}
+impl core::fmt::Display for TrackListParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let reason = match self.kind {
+            TrackListParseErrorKind::UnexpectedEnd => "unexpected end of input",
+            TrackListParseErrorKind::UnexpectedToken => "unexpected token",
+            TrackListParseErrorKind::InvalidValue => "invalid value",
+            TrackListParseErrorKind::InvalidRepeatCount => "invalid repeat() count",
+            TrackListParseErrorKind::NestedRepeat => "repeat() cannot be nested",
+            TrackListParseErrorKind::Empty => "expected at least one track",
+        };
+        write!(f, "Invalid track list at byte {}: {}", self.offset, reason)
+    }
+}
+
+/// Parses a CSS track list (as used by `grid-template-columns` and `grid-template-rows`)
+///
+/// Supports lengths (`px`, or a unitless `0`), percentages, `fr` units, `auto`, `min-content`, `max-content`,
+/// `minmax()`, `fit-content()` and `repeat()` (with a count, `auto-fill` or `auto-fit`).
+pub fn parse_track_list(input: &str) -> Result<GridTrackVec<TrackSizingFunction>, TrackListParseError> {
+    let mut parser = Parser { input, offset: 0 };
+    let tracks = parser.track_list(false)?;
+    match parser.peek() {
+        None => Ok(tracks),
+        Some(_) => Err(parser.error(TrackListParseErrorKind::UnexpectedToken)),
+    }
+}
+
+/// Parses a CSS list of implicit track sizes (as used by `grid-auto-columns` and `grid-auto-rows`)
+///
+/// Accepts the same track sizes as [`parse_track_list`], but not `repeat()` or line names. As with explicit tracks, a lone
+/// flexible length such as `1fr` is treated as `minmax(auto, 1fr)`, and `fr` units are not valid as the minimum of a `minmax()`.
+pub fn parse_implicit_track_list(
+    input: &str,
+) -> Result<GridTrackVec<NonRepeatedTrackSizingFunction>, TrackListParseError> {
+    let mut parser = Parser { input, offset: 0 };
+    let mut tracks = GridTrackVec::new();
+    while parser.peek().is_some() {
+        tracks.push(parser.track_size()?);
+    }
+    Ok(tracks)
+}
+
+impl<'a> TryFrom<&'a str> for NonRepeatedTrackSizingFunction {
+    type Error = TrackListParseError;
+
+    /// Parses a single track size such as `1fr` or `minmax(100px, max-content)`. See [`parse_implicit_track_list`].
+    fn try_from(value: &'a str) -> Result<Self, TrackListParseError> {
+        let mut tracks = parse_implicit_track_list(value)?;
+        match tracks.len() {
+            1 => Ok(tracks.remove(0)),
+            0 => Err(TrackListParseError { offset: 0, kind: TrackListParseErrorKind::Empty }),
+            _ => Err(TrackListParseError { offset: 0, kind: TrackListParseErrorKind::UnexpectedToken }),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for TrackSizingFunction {
+    type Error = TrackListParseError;
+
+    /// Parses a single track or `repeat()` from a string. See [`parse_track_list`].
+    fn try_from(value: &'a str) -> Result<Self, TrackListParseError> {
+        let mut tracks = parse_track_list(value)?;
+        match tracks.len() {
+            1 => Ok(tracks.remove(0)),
+            0 => Err(TrackListParseError { offset: 0, kind: TrackListParseErrorKind::Empty }),
+            _ => Err(TrackListParseError { offset: 0, kind: TrackListParseErrorKind::UnexpectedToken }),
+        }
+    }
+}
+
+/// A single argument to a track sizing function, before it is known whether it is used as a minimum or maximum
+#[derive(Debug, Clone, Copy)]
+enum Breadth {
+    /// A length or percentage
+    LengthPercentage(LengthPercentage),
+    /// A flexible length in `fr` units
+    Fraction(f32),
+    /// The `auto` keyword
+    Auto,
+    /// The `min-content` keyword
+    MinContent,
+    /// The `max-content` keyword
+    MaxContent,
+}
+
+impl Breadth {
+    /// Converts into a minimum track sizing function (for which `fr` units are invalid)
+    fn into_min(self) -> Option<MinTrackSizingFunction> {
+        match self {
+            Breadth::LengthPercentage(value) => Some(MinTrackSizingFunction::Fixed(value)),
+            Breadth::Fraction(_) => None,
+            Breadth::Auto => Some(MinTrackSizingFunction::Auto),
+            Breadth::MinContent => Some(MinTrackSizingFunction::MinContent),
+            Breadth::MaxContent => Some(MinTrackSizingFunction::MaxContent),
+        }
+    }
+
+    /// Converts into a maximum track sizing function
+    fn into_max(self) -> MaxTrackSizingFunction {
+        match self {
+            Breadth::LengthPercentage(value) => MaxTrackSizingFunction::Fixed(value),
+            Breadth::Fraction(value) => MaxTrackSizingFunction::Fraction(value),
+            Breadth::Auto => MaxTrackSizingFunction::Auto,
+            Breadth::MinContent => MaxTrackSizingFunction::MinContent,
+            Breadth::MaxContent => MaxTrackSizingFunction::MaxContent,
+        }
+    }
+
+    /// Converts into a track sizing function for a track specified by a single value
+    fn into_track(self) -> NonRepeatedTrackSizingFunction {
+        // A lone flexible length has an automatic minimum. All other values are used as both the minimum and maximum.
+        let min = self.into_min().unwrap_or(MinTrackSizingFunction::Auto);
+        MinMax { min, max: self.into_max() }
+    }
+}
+
+/// A recursive descent parser over a track list string
+struct Parser<'a> {
+    /// The full input string
+    input: &'a str,
+    /// The byte offset of the next unparsed character
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Creates an error of the specified kind at the current offset
+    fn error(&self, kind: TrackListParseErrorKind) -> TrackListParseError {
+        TrackListParseError { offset: self.offset, kind }
+    }
+
+    /// Skips whitespace and returns the next character without consuming it
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.offset..];
+        let trimmed = rest.trim_start();
+        self.offset += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    /// Consumes the specified character (after any whitespace), returning an error if it is not next
+    fn expect(&mut self, expected: char) -> Result<(), TrackListParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.offset += c.len_utf8();
+                Ok(())
+            }
+            Some(_) => Err(self.error(TrackListParseErrorKind::UnexpectedToken)),
+            None => Err(self.error(TrackListParseErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    /// Consumes and returns the longest run of characters matching `predicate`
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.input[self.offset..];
+        let len = rest.find(|c: char| !predicate(c)).unwrap_or(rest.len());
+        self.offset += len;
+        &rest[..len]
+    }
+
+    /// Parses tracks until the end of the input (or a closing parenthesis if `in_repeat` is set)
+    fn track_list(&mut self, in_repeat: bool) -> Result<GridTrackVec<TrackSizingFunction>, TrackListParseError> {
+        let mut tracks = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(')') => break,
+                Some('[') => self.line_names()?,
+                Some(c) if c.is_ascii_alphabetic() => {
+                    let start = self.offset;
+                    let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '-');
+                    if name.eq_ignore_ascii_case("repeat") {
+                        if in_repeat {
+                            self.offset = start;
+                            return Err(self.error(TrackListParseErrorKind::NestedRepeat));
+                        }
+                        tracks.push(self.repeat()?);
+                    } else {
+                        self.offset = start;
+                        tracks.push(TrackSizingFunction::Single(self.track_size()?));
+                    }
+                }
+                Some(_) => tracks.push(TrackSizingFunction::Single(self.track_size()?)),
+            }
+        }
+        Ok(tracks)
+    }
+
+    /// Skips over a bracketed list of line names
+    fn line_names(&mut self) -> Result<(), TrackListParseError> {
+        self.expect('[')?;
+        self.take_while(|c| c != ']');
+        self.expect(']')
+    }
+
+    /// Parses the arguments of a `repeat()` function (the name has already been consumed)
+    fn repeat(&mut self) -> Result<TrackSizingFunction, TrackListParseError> {
+        self.expect('(')?;
+        self.peek();
+        let count_offset = self.offset;
+        let count = self.take_while(|c| c.is_ascii_alphanumeric() || c == '-');
+        let repetition = if count.eq_ignore_ascii_case("auto-fill") {
+            GridTrackRepetition::AutoFill
+        } else if count.eq_ignore_ascii_case("auto-fit") {
+            GridTrackRepetition::AutoFit
+        } else {
+            match count.parse::<u16>() {
+                Ok(count) if count > 0 => GridTrackRepetition::Count(count),
+                _ => {
+                    return Err(TrackListParseError {
+                        offset: count_offset,
+                        kind: TrackListParseErrorKind::InvalidRepeatCount,
+                    })
+                }
+            }
+        };
+        self.expect(',')?;
+
+        self.peek();
+        let tracks_offset = self.offset;
+        let tracks = self
+            .track_list(true)?
+            .into_iter()
+            .map(|track| match track {
+                TrackSizingFunction::Single(track) => track,
+                TrackSizingFunction::Repeat(..) => unreachable!("nested repeat() is rejected by track_list"),
+            })
+            .collect::<GridTrackVec<_>>();
+        if tracks.is_empty() {
+            return Err(TrackListParseError { offset: tracks_offset, kind: TrackListParseErrorKind::Empty });
+        }
+        // Only fixed sizes may be automatically repeated (so `repeat(auto-fill, 1fr)` is invalid, while
+        // `repeat(auto-fill, minmax(100px, 1fr))` is allowed)
+        let is_auto_repetition = matches!(repetition, GridTrackRepetition::AutoFill | GridTrackRepetition::AutoFit);
+        if is_auto_repetition && !tracks.iter().all(|track| track.has_fixed_component()) {
+            return Err(TrackListParseError { offset: tracks_offset, kind: TrackListParseErrorKind::InvalidValue });
+        }
+        self.expect(')')?;
+        Ok(TrackSizingFunction::Repeat(repetition, tracks))
+    }
+
+    /// Parses a single track size: a breadth, `minmax()` or `fit-content()`
+    fn track_size(&mut self) -> Result<NonRepeatedTrackSizingFunction, TrackListParseError> {
+        self.peek();
+        let start = self.offset;
+        let name = self.take_while(|c| c.is_ascii_alphabetic() || c == '-');
+        if name.eq_ignore_ascii_case("minmax") {
+            self.expect('(')?;
+            let min_offset = self.offset;
+            let min = self
+                .breadth()?
+                .into_min()
+                .ok_or(TrackListParseError { offset: min_offset, kind: TrackListParseErrorKind::InvalidValue })?;
+            self.expect(',')?;
+            let max = self.breadth()?.into_max();
+            self.expect(')')?;
+            Ok(MinMax { min, max })
+        } else if name.eq_ignore_ascii_case("fit-content") {
+            self.expect('(')?;
+            self.peek();
+            let argument_offset = self.offset;
+            let argument = match self.breadth()? {
+                Breadth::LengthPercentage(value) => value,
+                _ => {
+                    return Err(TrackListParseError {
+                        offset: argument_offset,
+                        kind: TrackListParseErrorKind::InvalidValue,
+                    })
+                }
+            };
+            self.expect(')')?;
+            Ok(MinMax { min: MinTrackSizingFunction::Auto, max: MaxTrackSizingFunction::FitContent(argument) })
+        } else {
+            self.offset = start;
+            Ok(self.breadth()?.into_track())
+        }
+    }
+
+    /// Parses a keyword, length, percentage or flexible length
+    fn breadth(&mut self) -> Result<Breadth, TrackListParseError> {
+        match self.peek() {
+            None => return Err(self.error(TrackListParseErrorKind::UnexpectedEnd)),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let start = self.offset;
+                let keyword = self.take_while(|c| c.is_ascii_alphabetic() || c == '-');
+                return match keyword.to_ascii_lowercase().as_str() {
+                    "auto" => Ok(Breadth::Auto),
+                    "min-content" => Ok(Breadth::MinContent),
+                    "max-content" => Ok(Breadth::MaxContent),
+                    _ => {
+                        self.offset = start;
+                        Err(self.error(TrackListParseErrorKind::UnexpectedToken))
+                    }
+                };
+            }
+            Some(_) => {}
+        }
+
+        let start = self.offset;
+        let number = self.take_while(|c| c.is_ascii_digit() || c == '.' || c == '-' || c == '+');
+        let unit = self.take_while(|c| c.is_ascii_alphabetic() || c == '%');
+        let invalid = TrackListParseError { offset: start, kind: TrackListParseErrorKind::InvalidValue };
+        let value = match number.parse::<f32>() {
+            Ok(value) if value >= 0.0 && value.is_finite() => value,
+            _ => return Err(invalid),
+        };
+        match unit.to_ascii_lowercase().as_str() {
+            "px" => Ok(Breadth::LengthPercentage(LengthPercentage::Length(value))),
+            "" if value == 0.0 => Ok(Breadth::LengthPercentage(LengthPercentage::Length(0.0))),
+            "%" => Ok(Breadth::LengthPercentage(LengthPercentage::Percent(value / 100.0))),
+            "fr" => Ok(Breadth::Fraction(value)),
+            _ => Err(invalid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_implicit_track_list, parse_track_list, TrackListParseError, TrackListParseErrorKind};
+    use crate::prelude::*;
+    use crate::style::{GridTrackRepetition, NonRepeatedTrackSizingFunction, TrackSizingFunction};
+
+    #[test]
+    fn parses_track_list() {
+        let tracks =
+            parse_track_list("1fr auto [main-start] repeat(3, 100px) minmax(min-content, 50%) fit-content(20px)")
+                .unwrap();
+        assert_eq!(
+            tracks,
+            vec![
+                fr(1.0),
+                auto(),
+                TrackSizingFunction::Repeat(GridTrackRepetition::Count(3), vec![length(100.0)]),
+                minmax(min_content(), percent(0.5)),
+                fit_content(length(20.0)),
+            ]
+        );
+        assert_eq!(
+            parse_track_list("repeat(auto-fill, 10px 0)").unwrap(),
+            vec![TrackSizingFunction::Repeat(GridTrackRepetition::AutoFill, vec![length(10.0), zero()])]
+        );
+        assert_eq!(parse_track_list("  ").unwrap(), vec![]);
+        assert_eq!(TrackSizingFunction::try_from("max-content"), Ok(max_content()));
+    }
+
+    #[test]
+    fn rejects_invalid_track_lists() {
+        let error = |offset, kind| Err(TrackListParseError { offset, kind });
+        assert_eq!(parse_track_list("10px 10"), error(5, TrackListParseErrorKind::InvalidValue));
+        assert_eq!(parse_track_list("-1px"), error(0, TrackListParseErrorKind::InvalidValue));
+        assert_eq!(parse_track_list("minmax(1fr, 1fr)"), error(7, TrackListParseErrorKind::InvalidValue));
+        assert_eq!(parse_track_list("repeat(0, 1fr)"), error(7, TrackListParseErrorKind::InvalidRepeatCount));
+        assert_eq!(parse_track_list("repeat(2, repeat(2, 1px))"), error(10, TrackListParseErrorKind::NestedRepeat));
+        assert_eq!(parse_track_list("repeat(2, )"), error(10, TrackListParseErrorKind::Empty));
+        assert_eq!(parse_track_list("minmax(auto"), error(11, TrackListParseErrorKind::UnexpectedEnd));
+        assert_eq!(parse_track_list("1fr)"), error(3, TrackListParseErrorKind::UnexpectedToken));
+        assert_eq!(parse_track_list("big"), error(0, TrackListParseErrorKind::UnexpectedToken));
+        assert_eq!(parse_track_list("repeat(auto-fill, 1fr)"), error(18, TrackListParseErrorKind::InvalidValue));
+        assert_eq!(parse_track_list("repeat(auto-fit, 10px auto)"), error(17, TrackListParseErrorKind::InvalidValue));
+    }
+
+    #[test]
+    fn parses_implicit_track_list() {
+        assert_eq!(
+            parse_implicit_track_list("1fr minmax(100px, 2fr) auto").unwrap(),
+            vec![minmax(auto(), fr(1.0)), minmax(length(100.0), fr(2.0)), auto()]
+        );
+        assert_eq!(NonRepeatedTrackSizingFunction::try_from("3fr"), Ok(minmax(auto(), fr(3.0))));
+        assert_eq!(
+            parse_implicit_track_list("minmax(1fr, auto)"),
+            Err(TrackListParseError { offset: 7, kind: TrackListParseErrorKind::InvalidValue })
+        );
+        assert_eq!(
+            parse_implicit_track_list("repeat(2, 1fr)"),
+            Err(TrackListParseError { offset: 0, kind: TrackListParseErrorKind::UnexpectedToken })
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Detection of style properties which have no effect, for linting styles
+use core::fmt;
+
+#[cfg(feature = "grid")]
+use super::GridPlacementError;
+use super::{Dimension, Display, LengthPercentageAuto, Position, Style};
+use crate::util::sys::Vec;
+
+/// A style property which has no effect on layout, typically because it only applies to a different kind of node or is
+/// overridden by other properties. Returned by [`Style::validate`] and [`TaffyTree::validate_tree`](crate::TaffyTree::validate_tree).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyleWarning {
+    /// A property of flex or grid containers is set on a node with a different `display`, so is ignored
+    IgnoredContainerProperty {
+        /// The property which is ignored
+        property: &'static str,
+        /// The `display` of the node
+        display: Display,
+    },
+    /// A property of flex or grid items is set on a node whose parent has a different `display`, so is ignored
+    IgnoredItemProperty {
+        /// The property which is ignored
+        property: &'static str,
+        /// The `display` of the node's parent
+        parent_display: Display,
+    },
+    /// Both insets in an axis are set on a relatively positioned node, so the end inset (right or bottom) is ignored
+    IgnoredEndInset {
+        /// The inset which is ignored
+        property: &'static str,
+    },
+    /// The minimum size is larger than the maximum size in an axis, so the maximum size is ignored
+    MinSizeExceedsMaxSize {
+        /// The maximum size which is ignored
+        property: &'static str,
+    },
+    /// Both the width and height are set, so the aspect ratio is ignored
+    IgnoredAspectRatio,
+    /// A grid placement is contradictory, so the node is placed automatically instead
+    #[cfg(feature = "grid")]
+    InvalidGridPlacement {
+        /// The placement which is invalid
+        property: &'static str,
+        /// Why the placement is invalid
+        error: GridPlacementError,
+    },
+}
+
+impl StyleWarning {
+    /// The style property which the warning is about
+    pub fn property(&self) -> &'static str {
+        match self {
+            Self::IgnoredContainerProperty { property, .. }
+            | Self::IgnoredItemProperty { property, .. }
+            | Self::IgnoredEndInset { property }
+            | Self::MinSizeExceedsMaxSize { property } => property,
+            Self::IgnoredAspectRatio => "aspect_ratio",
+            #[cfg(feature = "grid")]
+            Self::InvalidGridPlacement { property, .. } => property,
+        }
+    }
+}
+
+impl fmt::Display for StyleWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IgnoredContainerProperty { property, display } => {
+                write!(f, "`{property}` has no effect on a node with `display: {display:?}`")
+            }
+            Self::IgnoredItemProperty { property, parent_display } => {
+                write!(f, "`{property}` has no effect on the child of a node with `display: {parent_display:?}`")
+            }
+            Self::IgnoredEndInset { property } => {
+                write!(f, "`{property}` has no effect as the opposite inset of this relatively positioned node is set")
+            }
+            Self::MinSizeExceedsMaxSize { property } => {
+                write!(f, "`{property}` has no effect as it is smaller than the corresponding minimum size")
+            }
+            Self::IgnoredAspectRatio => {
+                f.write_str("`aspect_ratio` has no effect as both the width and height are set")
+            }
+            #[cfg(feature = "grid")]
+            Self::InvalidGridPlacement { property, error } => write!(f, "`{property}` is invalid: {error}"),
+        }
+    }
+}
+
+/// Whether the minimum size is larger than the maximum size. Sizes of different units can't be compared without
+/// knowing the size of the parent, so are never reported.
+fn min_exceeds_max(min: Dimension, max: Dimension) -> bool {
+    match (min, max) {
+        (Dimension::Length(min), Dimension::Length(max))
+        | (Dimension::Percent(min), Dimension::Percent(max))
+        | (Dimension::RootPercent(min), Dimension::RootPercent(max)) => min > max,
+        _ => false,
+    }
+}
+
+impl Style {
+    /// Returns a warning for each property of the style which has no effect on layout, such as flexbox properties on a
+    /// node with `display: Grid` or a maximum size smaller than the minimum size
+    ///
+    /// Properties which only have an effect on the children of some kinds of node (such as `flex_grow`) can only be
+    /// checked along with the style of the parent, by [`TaffyTree::validate_tree`](crate::TaffyTree::validate_tree).
+    pub fn validate(&self) -> Vec<StyleWarning> {
+        self.validate_with_defaults(&Style::DEFAULT)
+    }
+
+    /// Returns a warning for each property of the style which has no effect on layout, as [`Style::validate`] does, but
+    /// treating the values of `defaults` (such as a tree's [`default_style`](crate::TaffyTree::default_style)) as unset
+    pub fn validate_with_defaults(&self, defaults: &Style) -> Vec<StyleWarning> {
+        let mut warnings = Vec::new();
+
+        #[cfg(any(feature = "flexbox", feature = "grid", feature = "block_layout"))]
+        if self.display != Display::None {
+            let display = self.display;
+            let mut container_property = |property, is_set: bool, applies: bool| {
+                if is_set && !applies {
+                    warnings.push(StyleWarning::IgnoredContainerProperty { property, display });
+                }
+            };
+            #[cfg(feature = "flexbox")]
+            {
+                let is_flex = display == Display::Flex;
+                container_property("flex_direction", self.flex_direction != defaults.flex_direction, is_flex);
+                container_property("flex_wrap", self.flex_wrap != defaults.flex_wrap, is_flex);
+            }
+            #[cfg(feature = "grid")]
+            {
+                let is_grid = display == Display::Grid;
+                let is_set = self.grid_template_rows != defaults.grid_template_rows;
+                container_property("grid_template_rows", is_set, is_grid);
+                let is_set = self.grid_template_columns != defaults.grid_template_columns;
+                container_property("grid_template_columns", is_set, is_grid);
+                container_property("grid_auto_rows", self.grid_auto_rows != defaults.grid_auto_rows, is_grid);
+                container_property("grid_auto_columns", self.grid_auto_columns != defaults.grid_auto_columns, is_grid);
+                container_property("grid_auto_flow", self.grid_auto_flow != defaults.grid_auto_flow, is_grid);
+                container_property("justify_items", self.justify_items != defaults.justify_items, is_grid);
+            }
+            #[cfg(feature = "block_layout")]
+            container_property("text_align", self.text_align != defaults.text_align, display == Display::Block);
+        }
+
+        if self.position == Position::Relative {
+            if self.inset.left != LengthPercentageAuto::Auto && self.inset.right != LengthPercentageAuto::Auto {
+                warnings.push(StyleWarning::IgnoredEndInset { property: "inset.right" });
+            }
+            if self.inset.top != LengthPercentageAuto::Auto && self.inset.bottom != LengthPercentageAuto::Auto {
+                warnings.push(StyleWarning::IgnoredEndInset { property: "inset.bottom" });
+            }
+        }
+
+        if min_exceeds_max(self.min_size.width, self.max_size.width) {
+            warnings.push(StyleWarning::MinSizeExceedsMaxSize { property: "max_size.width" });
+        }
+        if min_exceeds_max(self.min_size.height, self.max_size.height) {
+            warnings.push(StyleWarning::MinSizeExceedsMaxSize { property: "max_size.height" });
+        }
+        if self.aspect_ratio.is_some() && !self.size.width.is_auto() && !self.size.height.is_auto() {
+            warnings.push(StyleWarning::IgnoredAspectRatio);
+        }
+
+        #[cfg(feature = "grid")]
+        for (property, placement) in [("grid_row", self.grid_row), ("grid_column", self.grid_column)] {
+            if let Err(error) = placement.validate() {
+                warnings.push(StyleWarning::InvalidGridPlacement { property, error });
+            }
+        }
+
+        warnings
+    }
+
+    /// Pushes a warning to `warnings` for each property of the style which only has an effect on the children of a
+    /// different kind of node than its parent, which has `display: parent_display`. The values of `defaults` are
+    /// treated as unset.
+    #[cfg_attr(not(any(feature = "flexbox", feature = "grid")), allow(unused_variables))]
+    pub(crate) fn validate_as_child(
+        &self,
+        parent_display: Display,
+        defaults: &Style,
+        warnings: &mut Vec<StyleWarning>,
+    ) {
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        if parent_display != Display::None {
+            let mut item_property = |property, is_set: bool, applies: bool| {
+                if is_set && !applies {
+                    warnings.push(StyleWarning::IgnoredItemProperty { property, parent_display });
+                }
+            };
+            #[cfg(feature = "flexbox")]
+            {
+                let is_flex = parent_display == Display::Flex;
+                item_property("flex_grow", self.flex_grow != defaults.flex_grow, is_flex);
+                item_property("flex_shrink", self.flex_shrink != defaults.flex_shrink, is_flex);
+                item_property("flex_basis", self.flex_basis != defaults.flex_basis, is_flex);
+            }
+            #[cfg(feature = "grid")]
+            {
+                let is_grid = parent_display == Display::Grid;
+                item_property("grid_row", self.grid_row != defaults.grid_row, is_grid);
+                item_property("grid_column", self.grid_column != defaults.grid_column, is_grid);
+                item_property("justify_self", self.justify_self != defaults.justify_self, is_grid);
+            }
+            #[cfg(feature = "block_layout")]
+            item_property("align_self", self.align_self != defaults.align_self, parent_display != Display::Block);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StyleWarning;
+    #[cfg(feature = "flexbox")]
+    use crate::style::FlexWrap;
+    #[cfg(feature = "grid")]
+    use crate::style::GridPlacementError;
+    use crate::style::{Display, Style};
+    #[cfg(all(feature = "flexbox", feature = "grid"))]
+    use crate::{
+        geometry::{Line, Size},
+        style_helpers::{length, line, span},
+    };
+
+    #[test]
+    fn default_style_has_no_warnings() {
+        assert!(Style::default().validate().is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "flexbox", feature = "grid"))]
+    fn reports_properties_without_effect() {
+        let style = Style {
+            display: Display::Grid,
+            flex_wrap: FlexWrap::Wrap,
+            inset: length(10.0),
+            min_size: Size { width: length(20.0), height: length(5.0) },
+            max_size: Size { width: length(10.0), height: length(10.0) },
+            grid_row: Line { start: line(1), end: span(0) },
+            ..Default::default()
+        };
+        assert_eq!(
+            style.validate(),
+            [
+                StyleWarning::IgnoredContainerProperty { property: "flex_wrap", display: Display::Grid },
+                StyleWarning::IgnoredEndInset { property: "inset.right" },
+                StyleWarning::IgnoredEndInset { property: "inset.bottom" },
+                StyleWarning::MinSizeExceedsMaxSize { property: "max_size.width" },
+                StyleWarning::InvalidGridPlacement { property: "grid_row", error: GridPlacementError::ZeroSpan },
+            ]
+        );
+        assert_eq!(style.validate()[0].to_string(), "`flex_wrap` has no effect on a node with `display: Grid`");
+    }
+
+    #[test]
+    #[cfg(feature = "flexbox")]
+    fn hidden_nodes_are_not_reported_as_having_container_properties() {
+        let style = Style { display: Display::None, flex_wrap: FlexWrap::Wrap, ..Default::default() };
+        assert!(style.validate().is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+//! Alternate styles which a node switches to depending on its size (similar to CSS container queries)
+use crate::geometry::Size;
+use crate::style::Style;
+
+/// Bounds on the border-box size of a node. Each bound is inclusive, and is ignored if it is `None`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SizeCondition {
+    /// The minimum width
+    pub min_width: Option<f32>,
+    /// The maximum width
+    pub max_width: Option<f32>,
+    /// The minimum height
+    pub min_height: Option<f32>,
+    /// The maximum height
+    pub max_height: Option<f32>,
+}
+
+impl SizeCondition {
+    /// A condition which matches every size
+    pub const ANY: Self = Self { min_width: None, max_width: None, min_height: None, max_height: None };
+
+    /// Whether `size` is within the bounds of the condition
+    pub fn matches(&self, size: Size<f32>) -> bool {
+        let within = |value: f32, min: Option<f32>, max: Option<f32>| {
+            min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+        };
+        within(size.width, self.min_width, self.max_width) && within(size.height, self.min_height, self.max_height)
+    }
+}
+
+/// A style which replaces the style of a node while the node's size matches a [`SizeCondition`]. See
+/// [`TaffyTree::set_style_variants`](crate::TaffyTree::set_style_variants).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StyleVariant {
+    /// The sizes of the node for which the variant is used
+    pub condition: SizeCondition,
+    /// The style the node is laid out with while the variant is used
+    pub style: Style,
+}
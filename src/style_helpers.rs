@@ -73,10 +73,10 @@ where
 #[cfg(feature = "grid")]
 pub fn flex<Input, Output>(flex_fraction: Input) -> Output
 where
-    Input: Into<f32> + Copy,
+    Input: TaffyNumber,
     Output: From<MinMax<MinTrackSizingFunction, MaxTrackSizingFunction>>,
 {
-    MinMax { min: zero(), max: fr(flex_fraction.into()) }.into()
+    MinMax { min: zero(), max: fr(flex_fraction) }.into()
 }
 
 /// Returns the zero value for that type
@@ -365,160 +365,287 @@ impl<T: TaffyFitContent> Rect<T> {
     }
 }
 
+/// Holds the supertrait of [`TaffyNumber`], which is public but can't be named outside of Taffy
+mod sealed {
+    /// Prevents [`TaffyNumber`](super::TaffyNumber) from being implemented outside of Taffy
+    pub trait Sealed {}
+}
+
+/// A primitive number which can be used as a length, percentage or flex fraction by helpers such as [`length`],
+/// [`percent`] and `fr`, so that `length(20)` can be written instead of `length(20.0)`
+///
+/// Implemented for all primitive integer and floating point types. Values are converted to `f32` with `as`, so very
+/// large integers and `f64` values lose precision.
+pub trait TaffyNumber: sealed::Sealed + Copy {
+    /// Converts the number into an `f32`
+    fn into_f32(self) -> f32;
+}
+
+/// Implements [`TaffyNumber`] for primitive numeric types
+macro_rules! impl_taffy_number {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl TaffyNumber for $ty {
+                #[inline(always)]
+                fn into_f32(self) -> f32 {
+                    self as f32
+                }
+            }
+        )*
+    };
+}
+impl_taffy_number!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
 /// Returns a value of the inferred type which represent an absolute length
-pub fn length<Input: Into<f32> + Copy, T: FromLength>(value: Input) -> T {
+pub fn length<Input: TaffyNumber, T: FromLength>(value: Input) -> T {
     T::from_length(value)
 }
 
 /// Trait to create absolute length values from plain numbers
 pub trait FromLength {
-    /// Converts into an `Into<f32>` into Self
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self;
+    /// Converts a [`TaffyNumber`] into Self
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self;
 }
 impl FromLength for f32 {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        value.into()
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        value.into_f32()
     }
 }
 impl FromLength for Option<f32> {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Some(value.into())
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Some(value.into_f32())
     }
 }
 impl<T: FromLength> FromLength for Point<T> {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Point { x: T::from_length(value.into()), y: T::from_length(value.into()) }
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Point { x: T::from_length(value.into_f32()), y: T::from_length(value.into_f32()) }
     }
 }
 impl<T: FromLength> Point<T> {
     /// Returns a Point where x and y values are the same given absolute length
-    pub fn length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
         length::<Input, Self>(value)
     }
 }
 impl<T: FromLength> FromLength for Line<T> {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Line { start: T::from_length(value.into()), end: T::from_length(value.into()) }
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Line { start: T::from_length(value.into_f32()), end: T::from_length(value.into_f32()) }
     }
 }
 impl<T: FromLength> Line<T> {
     /// Returns a Line where both the start and end values are the same given absolute length
-    pub fn length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
         length::<Input, Self>(value)
     }
 }
 impl<T: FromLength> FromLength for Size<T> {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
-        Size { width: T::from_length(value.into()), height: T::from_length(value.into()) }
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
+        Size { width: T::from_length(value.into_f32()), height: T::from_length(value.into_f32()) }
     }
 }
 impl<T: FromLength> Size<T> {
     /// Returns a Size where both the width and height values the same given absolute length
-    pub fn length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
         length::<Input, Self>(value)
     }
 }
 impl<T: FromLength> FromLength for Rect<T> {
-    fn from_length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    fn from_length<Input: TaffyNumber>(value: Input) -> Self {
         Rect {
-            left: T::from_length(value.into()),
-            right: T::from_length(value.into()),
-            top: T::from_length(value.into()),
-            bottom: T::from_length(value.into()),
+            left: T::from_length(value.into_f32()),
+            right: T::from_length(value.into_f32()),
+            top: T::from_length(value.into_f32()),
+            bottom: T::from_length(value.into_f32()),
         }
     }
 }
 impl<T: FromLength> Rect<T> {
     /// Returns a Rect where the left, right, top and bottom values are all the same given absolute length
-    pub fn length<Input: Into<f32> + Copy>(value: Input) -> Self {
+    pub fn length<Input: TaffyNumber>(value: Input) -> Self {
         length::<Input, Self>(value)
     }
 }
 
+/// Creates a [`Rect`] from one to four lengths listed in the order of CSS shorthand properties such as `margin`.
+/// Used to implement [`margin!`](crate::margin), [`padding!`](crate::padding), [`border!`](crate::border) and
+/// [`inset!`](crate::inset).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __css_rect {
+    ($all:expr $(,)?) => {{
+        let all = $all;
+        $crate::__css_rect!(all, all, all, all)
+    }};
+    ($vertical:expr, $horizontal:expr $(,)?) => {{
+        let (vertical, horizontal) = ($vertical, $horizontal);
+        $crate::__css_rect!(vertical, horizontal, vertical, horizontal)
+    }};
+    ($top:expr, $horizontal:expr, $bottom:expr $(,)?) => {{
+        let horizontal = $horizontal;
+        $crate::__css_rect!($top, horizontal, $bottom, horizontal)
+    }};
+    ($top:expr, $right:expr, $bottom:expr, $left:expr $(,)?) => {{
+        let (top, right, bottom, left) = ($top, $right, $bottom, $left);
+        $crate::geometry::Rect {
+            left: $crate::style_helpers::length(left),
+            right: $crate::style_helpers::length(right),
+            top: $crate::style_helpers::length(top),
+            bottom: $crate::style_helpers::length(bottom),
+        }
+    }};
+}
+
+/// Creates a `margin` from one to four lengths, in the same order as the CSS `margin` shorthand property:
+///
+///   - `margin!(all)`
+///   - `margin!(vertical, horizontal)`
+///   - `margin!(top, horizontal, bottom)`
+///   - `margin!(top, right, bottom, left)`
+///
+/// ```
+/// # use taffy::prelude::*;
+/// let style = Style { margin: margin!(4.0, 8.0), ..Default::default() };
+/// assert_eq!(style.margin, Rect { left: length(8.0), right: length(8.0), top: length(4.0), bottom: length(4.0) });
+/// ```
+#[macro_export]
+macro_rules! margin {
+    ($($value:expr),+ $(,)?) => {
+        $crate::__css_rect!($($value),+)
+    };
+}
+
+/// Creates a `padding` from one to four lengths, in the same order as the CSS `padding` shorthand property.
+/// See [`margin!`](crate::margin) for the accepted forms.
+#[macro_export]
+macro_rules! padding {
+    ($($value:expr),+ $(,)?) => {
+        $crate::__css_rect!($($value),+)
+    };
+}
+
+/// Creates a `border` from one to four lengths, in the same order as the CSS `border-width` shorthand property.
+/// See [`margin!`](crate::margin) for the accepted forms.
+#[macro_export]
+macro_rules! border {
+    ($($value:expr),+ $(,)?) => {
+        $crate::__css_rect!($($value),+)
+    };
+}
+
+/// Creates an `inset` from one to four lengths, in the same order as the CSS `inset` shorthand property.
+/// See [`margin!`](crate::margin) for the accepted forms.
+#[macro_export]
+macro_rules! inset {
+    ($($value:expr),+ $(,)?) => {
+        $crate::__css_rect!($($value),+)
+    };
+}
+
+/// Creates a `gap` from one or two lengths, in the same order as the CSS `gap` shorthand property:
+/// `gap!(both)` or `gap!(row, column)`, where the row gap is the `height` and the column gap the `width`.
+#[macro_export]
+macro_rules! gap {
+    ($both:expr $(,)?) => {{
+        let both = $both;
+        $crate::gap!(both, both)
+    }};
+    ($row:expr, $column:expr $(,)?) => {{
+        let (row, column) = ($row, $column);
+        $crate::geometry::Size {
+            width: $crate::style_helpers::length(column),
+            height: $crate::style_helpers::length(row),
+        }
+    }};
+}
+
 /// Returns a value of the inferred type which represent a percentage
-pub fn percent<Input: Into<f32> + Copy, T: FromPercent>(percent: Input) -> T {
+pub fn percent<Input: TaffyNumber, T: FromPercent>(percent: Input) -> T {
     T::from_percent(percent)
 }
 
 /// Trait to create constant percent values from plain numbers
 pub trait FromPercent {
-    /// Converts into an `Into<f32>` into Self
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self;
+    /// Converts a [`TaffyNumber`] into Self
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self;
 }
 impl FromPercent for f32 {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        percent.into()
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        percent.into_f32()
     }
 }
 impl FromPercent for Option<f32> {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Some(percent.into())
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Some(percent.into_f32())
     }
 }
 impl<T: FromPercent> FromPercent for Point<T> {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Point { x: T::from_percent(percent.into()), y: T::from_percent(percent.into()) }
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Point { x: T::from_percent(percent.into_f32()), y: T::from_percent(percent.into_f32()) }
     }
 }
 impl<T: FromPercent> Point<T> {
     /// Returns a Point where both the x and y values are the constant percent value of the contained type
     /// (e.g. 2.1, Some(2.1), or Dimension::Length(2.1))
-    pub fn percent<Input: Into<f32> + Copy>(percent_value: Input) -> Self {
+    pub fn percent<Input: TaffyNumber>(percent_value: Input) -> Self {
         percent::<Input, Self>(percent_value)
     }
 }
 impl<T: FromPercent> FromPercent for Line<T> {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Line { start: T::from_percent(percent.into()), end: T::from_percent(percent.into()) }
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Line { start: T::from_percent(percent.into_f32()), end: T::from_percent(percent.into_f32()) }
     }
 }
 impl<T: FromPercent> Line<T> {
     /// Returns a Line where both the start and end values are the constant percent value of the contained type
     /// (e.g. 2.1, Some(2.1), or Dimension::Length(2.1))
-    pub fn percent<Input: Into<f32> + Copy>(percent_value: Input) -> Self {
+    pub fn percent<Input: TaffyNumber>(percent_value: Input) -> Self {
         percent::<Input, Self>(percent_value)
     }
 }
 impl<T: FromPercent> FromPercent for Size<T> {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
-        Size { width: T::from_percent(percent.into()), height: T::from_percent(percent.into()) }
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
+        Size { width: T::from_percent(percent.into_f32()), height: T::from_percent(percent.into_f32()) }
     }
 }
 impl<T: FromPercent> Size<T> {
     /// Returns a Size where both the width and height values are the constant percent value of the contained type
     /// (e.g. 2.1, Some(2.1), or Dimension::Length(2.1))
-    pub fn percent<Input: Into<f32> + Copy>(percent_value: Input) -> Self {
+    pub fn percent<Input: TaffyNumber>(percent_value: Input) -> Self {
         percent::<Input, Self>(percent_value)
     }
 }
 impl<T: FromPercent> FromPercent for Rect<T> {
-    fn from_percent<Input: Into<f32> + Copy>(percent: Input) -> Self {
+    fn from_percent<Input: TaffyNumber>(percent: Input) -> Self {
         Rect {
-            left: T::from_percent(percent.into()),
-            right: T::from_percent(percent.into()),
-            top: T::from_percent(percent.into()),
-            bottom: T::from_percent(percent.into()),
+            left: T::from_percent(percent.into_f32()),
+            right: T::from_percent(percent.into_f32()),
+            top: T::from_percent(percent.into_f32()),
+            bottom: T::from_percent(percent.into_f32()),
         }
     }
 }
 impl<T: FromPercent> Rect<T> {
     /// Returns a Rect where the left, right, top and bottom values are all constant percent value of the contained type
     /// (e.g. 2.1, Some(2.1), or Dimension::Length(2.1))
-    pub fn percent<Input: Into<f32> + Copy>(percent_value: Input) -> Self {
+    pub fn percent<Input: TaffyNumber>(percent_value: Input) -> Self {
         percent::<Input, Self>(percent_value)
     }
 }
 
 /// Create a `Fraction` track sizing function (`fr` in CSS)
+///
+/// When used as a whole track (rather than just the maximum of a [`minmax`]), this is equivalent to `minmax(auto, Nfr)`
+/// as `fr` units are not valid as a minimum track sizing function. Use [`flex`] for `minmax(0, Nfr)`.
 #[cfg(feature = "grid")]
-pub fn fr<Input: Into<f32> + Copy, T: FromFlex>(flex: Input) -> T {
+pub fn fr<Input: TaffyNumber, T: FromFlex>(flex: Input) -> T {
     T::from_flex(flex)
 }
 
 /// Trait to create constant percent values from plain numbers
 pub trait FromFlex {
-    /// Converts into an `Into<f32>` into Self
-    fn from_flex<Input: Into<f32> + Copy>(flex: Input) -> Self;
+    /// Converts a [`TaffyNumber`] into Self
+    fn from_flex<Input: TaffyNumber>(flex: Input) -> Self;
 }
 
 #[cfg(feature = "grid")]
@@ -544,3 +671,61 @@ mod repeat_fn_tests {
         assert_eq!(repeat("auto-fill", TEST_VEC), TrackSizingFunction::Repeat(GridTrackRepetition::AutoFill, TEST_VEC));
     }
 }
+
+#[cfg(test)]
+mod shorthand_macro_tests {
+    use crate::geometry::{Rect, Size};
+    use crate::style::{LengthPercentage, LengthPercentageAuto};
+
+    #[test]
+    fn css_rect_orders_values_like_css() {
+        let rect: Rect<LengthPercentage> = padding!(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(rect, Rect::xyzw(4.0, 2.0, 1.0, 3.0).map(LengthPercentage::Length));
+        let rect: Rect<LengthPercentage> = border!(1.0, 2.0, 3.0);
+        assert_eq!(rect, Rect::xyzw(2.0, 2.0, 1.0, 3.0).map(LengthPercentage::Length));
+        let rect: Rect<LengthPercentageAuto> = margin!(1.0, 2.0);
+        assert_eq!(rect, Rect::axes(LengthPercentageAuto::Length(2.0), LengthPercentageAuto::Length(1.0)));
+        let rect: Rect<LengthPercentageAuto> = inset!(5.0);
+        assert_eq!(rect, Rect::all(LengthPercentageAuto::Length(5.0)));
+    }
+
+    #[test]
+    fn gap_orders_row_before_column() {
+        let size: Size<LengthPercentage> = gap!(1.0, 2.0);
+        assert_eq!(size, Size { width: LengthPercentage::Length(2.0), height: LengthPercentage::Length(1.0) });
+        let size: Size<LengthPercentage> = gap!(3.0);
+        assert_eq!(size, Size::square(LengthPercentage::Length(3.0)));
+    }
+}
+
+#[cfg(test)]
+mod number_tests {
+    use super::{length, percent};
+    use crate::geometry::Size;
+    use crate::style::{Dimension, LengthPercentage, LengthPercentageAuto};
+
+    #[test]
+    fn helpers_accept_integers_and_f64() {
+        assert_eq!(length::<_, Dimension>(20), Dimension::Length(20.0));
+        assert_eq!(length::<_, LengthPercentage>(20u32), LengthPercentage::Length(20.0));
+        assert_eq!(percent::<_, Dimension>(0.5f64), Dimension::Percent(0.5));
+        assert_eq!(Size::<Dimension>::length(-3i8), Size::square(Dimension::Length(-3.0)));
+    }
+
+    #[test]
+    fn inherent_constructors_need_no_type_annotations() {
+        assert_eq!(Dimension::length(10), Dimension::Length(10.0));
+        assert_eq!(Dimension::percent(0.25), Dimension::Percent(0.25));
+        assert_eq!(Dimension::auto(), Dimension::Auto);
+        assert_eq!(LengthPercentageAuto::length(1u8), LengthPercentageAuto::Length(1.0));
+        assert_eq!(LengthPercentage::percent(1usize), LengthPercentage::Percent(1.0));
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn fr_accepts_integers() {
+        use super::fr;
+        use crate::style::MaxTrackSizingFunction;
+        assert_eq!(fr::<_, MaxTrackSizingFunction>(2), MaxTrackSizingFunction::Fraction(2.0));
+    }
+}
@@ -1,21 +1,111 @@
 //! A cache for storing the results of layout computation
-use crate::geometry::Size;
+use crate::geometry::{Line, Size};
 use crate::style::AvailableSpace;
-use crate::tree::{LayoutOutput, RunMode};
+use crate::tree::{LayoutConfig, LayoutInput, LayoutOutput, RequestedAxis, RunMode, SizingMode};
 
 /// The number of cache entries for each node in the tree
 const CACHE_SIZE: usize = 9;
 
+/// Whether a single dimension of a cached result matches the requested known dimension and available space.
+/// See [`CacheEntry::matches`].
+#[inline]
+fn dimension_matches(
+    known: Option<f32>,
+    cached_known: Option<f32>,
+    available: AvailableSpace,
+    cached_available: AvailableSpace,
+    cached_size: f32,
+    content_sized: bool,
+) -> bool {
+    (known == cached_known || (content_sized && known == Some(cached_size)))
+        && (known.is_some() || cached_available.is_roughly_equal(available))
+}
+
 /// Cached intermediate layout results
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub(crate) struct CacheEntry<T> {
-    /// The initial cached size of the node itself
-    known_dimensions: Size<Option<f32>>,
-    /// The initial cached size of the parent's node
-    available_space: Size<AvailableSpace>,
+    /// The inputs that the cached result was computed with
+    inputs: LayoutInput,
+    /// Whether the cached size in each dimension was determined by the node's content alone (the dimension was unknown
+    /// and the node's style could not have clamped it)
+    content_sized: Size<bool>,
     /// The cached size and baselines of the item
     content: T,
+    /// Whether the cached result may depend on the available width. See [`LayoutOutput::depends_on_available_width`].
+    depends_on_available_width: bool,
+    /// Whether the node's style contains percentages, which are resolved against the parent size
+    depends_on_parent_size: bool,
+}
+
+impl<T> CacheEntry<T> {
+    /// Whether the cached result is also the result of laying out the node with `inputs`, given that the cached result
+    /// has size `cached_size`.
+    ///
+    /// Every input which may affect the output of layout must match, with two exceptions:
+    ///
+    ///   - A known dimension matches if it is equal to the cached size in that dimension and the cached size in that
+    ///     dimension was content-sized, as laying out a node with its own content-based size as a known dimension
+    ///     produces that same size. This does not hold for a size which was clamped (by a known dimension smaller than
+    ///     the node's padding and border, or by its style), as the node's content is then laid out differently when that
+    ///     size is known. It is also an approximation where the node's descendants have percentage sizes (which resolve
+    ///     against a known size but not a content-based one), but without it a node may be measured a number of times
+    ///     exponential in its depth in the tree.
+    ///   - Available space in a dimension is ignored if the dimension is known, and available width is ignored if the
+    ///     result doesn't depend on it.
+    ///
+    /// A result computed for a single axis is only valid for that axis, whereas a result computed for both axes is
+    /// valid for any axis. The parent size is ignored if the node's style doesn't depend on it. If `dimensions_only` is
+    /// true (for the deprecated [`Cache::get`]) then only the known dimensions and available space are compared.
+    #[inline]
+    fn matches(&self, inputs: &LayoutInput, cached_size: Size<f32>, dimensions_only: bool) -> bool {
+        let entry = &self.inputs;
+        // A result which doesn't depend on the available width matches any available width
+        let available_width =
+            if self.depends_on_available_width { inputs.available_space.width } else { entry.available_space.width };
+        dimension_matches(
+            inputs.known_dimensions.width,
+            entry.known_dimensions.width,
+            available_width,
+            entry.available_space.width,
+            cached_size.width,
+            self.content_sized.width,
+        ) && dimension_matches(
+            inputs.known_dimensions.height,
+            entry.known_dimensions.height,
+            inputs.available_space.height,
+            entry.available_space.height,
+            cached_size.height,
+            self.content_sized.height,
+        ) && (dimensions_only
+            || ((entry.axis == RequestedAxis::Both || entry.axis == inputs.axis)
+                && entry.sizing_mode == inputs.sizing_mode
+                && (!self.depends_on_parent_size || entry.parent_size == inputs.parent_size)
+                && entry.vertical_margins_are_collapsible == inputs.vertical_margins_are_collapsible
+                && entry.config == inputs.config))
+    }
+}
+
+impl CacheEntry<Size<f32>> {
+    /// The cached size measurement as a [`LayoutOutput`]
+    #[inline]
+    fn output(&self) -> LayoutOutput {
+        let output = LayoutOutput::from_outer_size(self.content);
+        LayoutOutput { depends_on_available_width: self.depends_on_available_width, ..output }
+    }
+}
+
+/// The entries of a [`Cache`] which storing a result computed with some inputs may overwrite. See
+/// [`Cache::snapshot_slot`].
+#[cfg(all(feature = "taffy_tree", feature = "std"))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CacheSlotSnapshot {
+    /// The index of the measure entry which the result is stored in, if it is a size measurement
+    measure_slot: Option<usize>,
+    /// The measure entry at `measure_slot`
+    measure_entry: Option<CacheEntry<Size<f32>>>,
+    /// The cache entry for the node's final layout
+    final_layout_entry: Option<CacheEntry<LayoutOutput>>,
 }
 
 /// A cache for caching the results of a sizing a Grid Item or Flexbox Item
@@ -35,6 +125,9 @@ impl Default for Cache {
 }
 
 impl Cache {
+    /// The number of results a cache can hold: its preliminary size measurements and the final layout
+    pub(crate) const CAPACITY: usize = CACHE_SIZE + 1;
+
     /// Create a new empty cache
     pub const fn new() -> Self {
         Self { final_layout_entry: None, measure_entries: [None; CACHE_SIZE] }
@@ -105,6 +198,7 @@ impl Cache {
     }
 
     /// Try to retrieve a cached result from the cache
+    #[deprecated(note = "use `Cache::get_layout`, which only returns results computed with matching layout inputs")]
     #[inline]
     pub fn get(
         &self,
@@ -112,45 +206,12 @@ impl Cache {
         available_space: Size<AvailableSpace>,
         run_mode: RunMode,
     ) -> Option<LayoutOutput> {
-        match run_mode {
-            RunMode::PerformLayout => self
-                .final_layout_entry
-                .filter(|entry| {
-                    let cached_size = entry.content.size;
-                    (known_dimensions.width == entry.known_dimensions.width
-                        || known_dimensions.width == Some(cached_size.width))
-                        && (known_dimensions.height == entry.known_dimensions.height
-                            || known_dimensions.height == Some(cached_size.height))
-                        && (known_dimensions.width.is_some()
-                            || entry.available_space.width.is_roughly_equal(available_space.width))
-                        && (known_dimensions.height.is_some()
-                            || entry.available_space.height.is_roughly_equal(available_space.height))
-                })
-                .map(|e| e.content),
-            RunMode::ComputeSize => {
-                for entry in self.measure_entries.iter().flatten() {
-                    let cached_size = entry.content;
-
-                    if (known_dimensions.width == entry.known_dimensions.width
-                        || known_dimensions.width == Some(cached_size.width))
-                        && (known_dimensions.height == entry.known_dimensions.height
-                            || known_dimensions.height == Some(cached_size.height))
-                        && (known_dimensions.width.is_some()
-                            || entry.available_space.width.is_roughly_equal(available_space.width))
-                        && (known_dimensions.height.is_some()
-                            || entry.available_space.height.is_roughly_equal(available_space.height))
-                    {
-                        return Some(LayoutOutput::from_outer_size(cached_size));
-                    }
-                }
-
-                None
-            }
-            RunMode::PerformHiddenLayout => None,
-        }
+        let inputs = LayoutInput { known_dimensions, available_space, run_mode, ..Self::LEGACY_INPUTS };
+        self.find(&inputs, true)
     }
 
     /// Store a computed size in the cache
+    #[deprecated(note = "use `Cache::store_layout`, which also records the layout inputs which the result depends on")]
     pub fn store(
         &mut self,
         known_dimensions: Size<Option<f32>>,
@@ -158,14 +219,93 @@ impl Cache {
         run_mode: RunMode,
         layout_output: LayoutOutput,
     ) {
-        match run_mode {
+        let inputs = LayoutInput { known_dimensions, available_space, run_mode, ..Self::LEGACY_INPUTS };
+        self.store_layout(&inputs, layout_output, false, Size { width: false, height: false });
+    }
+
+    /// The inputs which results stored with the deprecated [`Cache::store`] (which doesn't take them) are recorded with
+    const LEGACY_INPUTS: LayoutInput = LayoutInput {
+        run_mode: RunMode::PerformLayout,
+        sizing_mode: SizingMode::InherentSize,
+        axis: RequestedAxis::Both,
+        known_dimensions: Size::NONE,
+        parent_size: Size::NONE,
+        available_space: Size { width: AvailableSpace::MaxContent, height: AvailableSpace::MaxContent },
+        vertical_margins_are_collapsible: Line::FALSE,
+        config: LayoutConfig::DEFAULT,
+    };
+
+    /// Try to retrieve a result computed with inputs matching `inputs` from the cache
+    ///
+    /// Only the style-dependent inputs recorded by [`Cache::store_layout`] are compared, so this doesn't need to access
+    /// the node's style.
+    #[inline]
+    pub fn get_layout(&self, inputs: &LayoutInput) -> Option<LayoutOutput> {
+        self.find(inputs, false)
+    }
+
+    /// Returns the first cached result which matches `inputs`, comparing only the known dimensions and available space
+    /// if `dimensions_only` is true. See [`CacheEntry::matches`].
+    #[inline]
+    fn find(&self, inputs: &LayoutInput, dimensions_only: bool) -> Option<LayoutOutput> {
+        match inputs.run_mode {
+            RunMode::PerformLayout => self
+                .final_layout_entry
+                .filter(|entry| entry.matches(inputs, entry.content.size, dimensions_only))
+                .map(|entry| entry.content),
+            RunMode::ComputeSize => self
+                .measure_entries
+                .iter()
+                .flatten()
+                .find(|entry| entry.matches(inputs, entry.content, dimensions_only))
+                .map(CacheEntry::output),
+            RunMode::PerformHiddenLayout => None,
+        }
+    }
+
+    /// Store a computed result in the cache
+    ///
+    /// `depends_on_parent_size` is whether the node's style contains percentages, which are resolved against the
+    /// parent size. `size_is_constrained` is whether the node's style may determine or clamp its size in each axis
+    /// (because it sets a size, a min or max size, or an aspect ratio which transfers such constraints between axes).
+    pub fn store_layout(
+        &mut self,
+        inputs: &LayoutInput,
+        layout_output: LayoutOutput,
+        depends_on_parent_size: bool,
+        size_is_constrained: Size<bool>,
+    ) {
+        match inputs.run_mode {
             RunMode::PerformLayout => {
-                self.final_layout_entry = Some(CacheEntry { known_dimensions, available_space, content: layout_output })
+                // A final layout also positions the node's descendants, which may be laid out differently once the
+                // node's size is known (as percentages resolve against it), so it is never treated as content-sized
+                let content_sized = Size { width: false, height: false };
+                self.final_layout_entry = Some(CacheEntry {
+                    inputs: *inputs,
+                    content_sized,
+                    content: layout_output,
+                    depends_on_available_width: layout_output.depends_on_available_width,
+                    depends_on_parent_size,
+                })
             }
             RunMode::ComputeSize => {
-                let cache_slot = Self::compute_cache_slot(known_dimensions, available_space);
-                self.measure_entries[cache_slot] =
-                    Some(CacheEntry { known_dimensions, available_space, content: layout_output.size });
+                let content_sized = Size {
+                    width: inputs.known_dimensions.width.is_none() && !size_is_constrained.width,
+                    height: inputs.known_dimensions.height.is_none() && !size_is_constrained.height,
+                };
+                let cache_slot = Self::compute_cache_slot(inputs.known_dimensions, inputs.available_space);
+                self.measure_entries[cache_slot] = Some(CacheEntry {
+                    inputs: *inputs,
+                    content_sized,
+                    content: layout_output.size,
+                    depends_on_available_width: layout_output.depends_on_available_width,
+                    depends_on_parent_size,
+                });
+
+                // Sizing a node may perform layout on its descendants (block layout lays out its children to determine
+                // its height) and so overwrite the layouts which were computed along with the cached final layout.
+                // Reusing the cached final layout would then leave those descendants with stale layouts.
+                self.final_layout_entry = None;
             }
             RunMode::PerformHiddenLayout => {}
         }
@@ -177,6 +317,60 @@ impl Cache {
         self.measure_entries = [None; CACHE_SIZE];
     }
 
+    /// Clear the cached final layout, keeping the cached size measurements
+    #[cfg(feature = "taffy_tree")]
+    pub(crate) fn clear_final_layout(&mut self) {
+        self.final_layout_entry = None;
+    }
+
+    /// Returns true if the cache holds the result of a full layout of the node
+    pub fn has_final_layout(&self) -> bool {
+        self.final_layout_entry.is_some()
+    }
+
+    /// Returns the number of cached size measurements
+    pub fn measure_entry_count(&self) -> usize {
+        self.measure_entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    /// Returns a copy of this cache holding only its size measurements (and not the final layout, which depends on the
+    /// layouts of the node's descendants)
+    #[cfg(all(feature = "taffy_tree", feature = "std"))]
+    pub(crate) fn measurements(&self) -> Cache {
+        Cache { final_layout_entry: None, measure_entries: self.measure_entries }
+    }
+
+    /// Returns a copy of the entries which storing a result computed with `inputs` may overwrite, which
+    /// [`Cache::restore_slot`] puts back. Much cheaper than copying the whole cache.
+    #[cfg(all(feature = "taffy_tree", feature = "std"))]
+    pub(crate) fn snapshot_slot(&self, inputs: &LayoutInput) -> CacheSlotSnapshot {
+        let measure_slot = (inputs.run_mode == RunMode::ComputeSize)
+            .then(|| Self::compute_cache_slot(inputs.known_dimensions, inputs.available_space));
+        CacheSlotSnapshot {
+            measure_slot,
+            measure_entry: measure_slot.and_then(|slot| self.measure_entries[slot]),
+            final_layout_entry: self.final_layout_entry,
+        }
+    }
+
+    /// Restores the entries copied by [`Cache::snapshot_slot`], undoing the storing of a result since
+    #[cfg(all(feature = "taffy_tree", feature = "std"))]
+    pub(crate) fn restore_slot(&mut self, snapshot: CacheSlotSnapshot) {
+        if let Some(slot) = snapshot.measure_slot {
+            self.measure_entries[slot] = snapshot.measure_entry;
+        }
+        self.final_layout_entry = snapshot.final_layout_entry;
+    }
+
+    /// Returns the inputs and output of each cached result, with the final layout (if any) last so that storing the
+    /// results in order in an empty cache (which discards the final layout when a size is stored) fills it the same way
+    #[cfg(feature = "taffy_tree")]
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (LayoutInput, LayoutOutput)> + '_ {
+        let measurements = self.measure_entries.iter().flatten();
+        let measurements = measurements.map(|entry| (entry.inputs, entry.output()));
+        measurements.chain(self.final_layout_entry.iter().map(|entry| (entry.inputs, entry.content)))
+    }
+
     /// Returns true if all cache entries are None, else false
     pub fn is_empty(&self) -> bool {
         self.final_layout_entry.is_none() && !self.measure_entries.iter().any(|entry| entry.is_some())
@@ -0,0 +1,437 @@
+//! A layout tree over node storage owned by something else, such as the component storage of an ECS
+//!
+//! [`ExternalTree`] implements the layout traits on top of a set of accessor closures, so that layout can run directly
+//! over an application's own node hierarchy and styles without first copying them into a [`TaffyTree`](crate::TaffyTree).
+//! The only state Taffy owns is each node's layout cache and unrounded layout, which are kept in a [`LayoutCaches`]
+//! that lives alongside the application's storage between layouts.
+//!
+//! ```rust
+//! use taffy::prelude::*;
+//! use taffy::{ExternalTree, LayoutCaches};
+//!
+//! // An application's own node storage: a style, a list of children and a layout per entity
+//! let styles = vec![
+//!     Style { size: Size::from_lengths(100.0, 50.0), ..Default::default() },
+//!     Style { flex_grow: 1.0, ..Default::default() },
+//! ];
+//! let children = vec![vec![NodeId::from(1u64)], vec![]];
+//! let mut layouts = vec![Layout::new(); 2];
+//!
+//! let mut caches = LayoutCaches::new();
+//! let mut tree = ExternalTree::new(
+//!     &mut caches,
+//!     |node| children[usize::from(node)].iter().copied(),
+//!     |node| &styles[usize::from(node)],
+//!     |node, layout| layouts[usize::from(node)] = *layout,
+//!     |_, _, _, _| Size::ZERO,
+//! );
+//! tree.compute_layout(NodeId::from(0u64), Size::MAX_CONTENT);
+//!
+//! assert_eq!(layouts[1].size, Size { width: 100.0, height: 50.0 });
+//! ```
+use std::collections::HashMap;
+
+use crate::compute::{
+    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout,
+};
+use crate::geometry::Size;
+use crate::style::{AvailableSpace, Display, Style};
+use crate::tree::{
+    Cache, Layout, LayoutConfig, LayoutInput, LayoutOutput, LayoutPartialTree, NodeId, RoundTree, RunMode,
+    TraversePartialTree, TraverseTree,
+};
+#[cfg(feature = "block_layout")]
+use crate::{compute::compute_block_layout, LayoutBlockContainer};
+#[cfg(feature = "flexbox")]
+use crate::{compute::compute_flexbox_layout, LayoutFlexboxContainer};
+#[cfg(feature = "grid")]
+use crate::{compute::compute_grid_layout, LayoutGridContainer};
+#[cfg(feature = "inline_layout")]
+use crate::{compute::compute_inline_layout, LayoutInlineContainer};
+
+/// The layout state that Taffy keeps for a node of an [`ExternalTree`]
+#[derive(Debug, Clone)]
+struct NodeState {
+    /// The node's layout cache
+    cache: Cache,
+    /// The node's layout before rounding
+    unrounded_layout: Layout,
+}
+
+/// The layout caches and unrounded layouts of the nodes of an [`ExternalTree`]
+///
+/// Keep this between layouts so that the layout of unchanged subtrees is not recomputed. When the style or children of
+/// a node change, call [`mark_dirty`](Self::mark_dirty) with the node and each of its ancestors.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutCaches {
+    /// The state of each node that has been laid out
+    nodes: HashMap<NodeId, NodeState>,
+}
+
+impl LayoutCaches {
+    /// Creates an empty set of caches
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears the layout cache of `node`, so that its layout is recomputed by the next layout. This does not mark the
+    /// node's ancestors dirty: the application must call this for each of them too, as only it knows their ids.
+    pub fn mark_dirty(&mut self, node: NodeId) {
+        if let Some(state) = self.nodes.get_mut(&node) {
+            state.cache.clear();
+        }
+    }
+
+    /// Discards the state of a node which has been removed from the application's storage
+    pub fn remove(&mut self, node: NodeId) {
+        self.nodes.remove(&node);
+    }
+
+    /// Discards the state of every node
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    /// Returns the layout of `node` before rounding, if it has been laid out
+    pub fn unrounded_layout(&self, node: NodeId) -> Option<&Layout> {
+        self.nodes.get(&node).map(|state| &state.unrounded_layout)
+    }
+
+    /// Returns the state of `node`, creating it if the node has not been laid out before
+    fn state_mut(&mut self, node: NodeId) -> &mut NodeState {
+        self.nodes.entry(node).or_insert_with(|| NodeState { cache: Cache::new(), unrounded_layout: Layout::new() })
+    }
+}
+
+/// A layout tree whose hierarchy and styles are read from external storage through accessor closures
+///
+/// - `children` returns the children of a node, in order.
+/// - `style` returns the style of a node. Styles are borrowed for the lifetime `'s`, and are never copied.
+/// - `set_layout` is called with the final layout of each node once layout has been computed.
+/// - `measure` is called to size leaf nodes, in the same way as the measure function passed to
+///   [`TaffyTree::compute_layout_with_measure`](crate::TaffyTree::compute_layout_with_measure).
+///
+/// See the [module](crate::tree::external) documentation for an example.
+pub struct ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn> {
+    /// The caches of the tree's nodes
+    caches: &'s mut LayoutCaches,
+    /// Returns the children of a node
+    children: ChildrenFn,
+    /// Returns the style of a node
+    style: StyleFn,
+    /// Receives the final layout of each node
+    set_layout: LayoutFn,
+    /// Measures leaf nodes
+    measure: MeasureFn,
+    /// The configuration used when computing layout
+    config: LayoutConfig,
+}
+
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    StyleFn: Fn(NodeId) -> &'s Style,
+    LayoutFn: FnMut(NodeId, &Layout),
+    MeasureFn: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, &Style) -> Size<f32>,
+{
+    /// Creates a tree over external storage, with the default [`LayoutConfig`]
+    pub fn new(
+        caches: &'s mut LayoutCaches,
+        children: ChildrenFn,
+        style: StyleFn,
+        set_layout: LayoutFn,
+        measure: MeasureFn,
+    ) -> Self {
+        Self { caches, children, style, set_layout, measure, config: LayoutConfig::DEFAULT }
+    }
+
+    /// Sets the configuration used when computing layout
+    pub fn with_config(mut self, config: LayoutConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Computes the layout of the subtree rooted at `root`, and passes the final layout of each node in it to the
+    /// `set_layout` closure
+    pub fn compute_layout(&mut self, root: NodeId, available_space: Size<AvailableSpace>) {
+        compute_root_layout(self, root, available_space);
+        if self.config.use_rounding {
+            round_layout(self, root);
+        } else {
+            self.output_unrounded_layouts(root);
+        }
+    }
+
+    /// Passes the unrounded layout of each node in the subtree rooted at `node` to the `set_layout` closure
+    fn output_unrounded_layouts(&mut self, node: NodeId) {
+        let layout = self.caches.state_mut(node).unrounded_layout;
+        (self.set_layout)(node, &layout);
+        for child in (self.children)(node) {
+            self.output_unrounded_layouts(child);
+        }
+    }
+}
+
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> TraversePartialTree
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+{
+    type ChildIter<'a> = ChildIter where Self: 'a;
+
+    #[inline(always)]
+    fn child_ids(&self, parent_node_id: NodeId) -> Self::ChildIter<'_> {
+        (self.children)(parent_node_id)
+    }
+
+    #[inline(always)]
+    fn child_count(&self, parent_node_id: NodeId) -> usize {
+        (self.children)(parent_node_id).len()
+    }
+
+    #[inline(always)]
+    fn get_child_id(&self, parent_node_id: NodeId, child_index: usize) -> NodeId {
+        (self.children)(parent_node_id).nth(child_index).unwrap()
+    }
+}
+
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> TraverseTree
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+{
+}
+
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> LayoutPartialTree
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    StyleFn: Fn(NodeId) -> &'s Style,
+    MeasureFn: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, &Style) -> Size<f32>,
+{
+    type CoreContainerStyle<'a> = &'a Style where Self: 'a;
+    type CacheMut<'b> = &'b mut Cache where Self: 'b;
+
+    #[inline(always)]
+    fn get_core_container_style(&self, node_id: NodeId) -> Self::CoreContainerStyle<'_> {
+        (self.style)(node_id)
+    }
+
+    #[inline(always)]
+    fn get_cache_mut(&mut self, node: NodeId) -> &mut Cache {
+        &mut self.caches.state_mut(node).cache
+    }
+
+    #[inline(always)]
+    fn set_unrounded_layout(&mut self, node_id: NodeId, layout: &Layout) {
+        #[cfg(feature = "fixed_point")]
+        let layout = &layout.quantized();
+        self.caches.state_mut(node_id).unrounded_layout = *layout;
+    }
+
+    #[inline(always)]
+    fn get_layout_config(&self) -> LayoutConfig {
+        self.config
+    }
+
+    #[inline(always)]
+    fn compute_child_layout(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        if inputs.run_mode == RunMode::PerformHiddenLayout {
+            return compute_hidden_layout(self, node);
+        }
+
+        compute_cached_layout(self, node, inputs, |tree, node, inputs| {
+            let style = (tree.style)(node);
+            let has_children = tree.child_count(node) > 0;
+            match (style.display, has_children) {
+                (Display::None, _) => compute_hidden_layout(tree, node),
+                #[cfg(feature = "block_layout")]
+                (Display::Block, true) => compute_block_layout(tree, node, inputs),
+                #[cfg(feature = "flexbox")]
+                (Display::Flex, true) => compute_flexbox_layout(tree, node, inputs),
+                #[cfg(feature = "grid")]
+                (Display::Grid, true) => compute_grid_layout(tree, node, inputs),
+                #[cfg(feature = "inline_layout")]
+                (Display::Inline, _) => compute_inline_layout(tree, node, inputs),
+                (_, false) => {
+                    let measure_function = |known_dimensions, available_space| {
+                        (tree.measure)(known_dimensions, available_space, node, style)
+                    };
+                    compute_leaf_layout(inputs, style, measure_function)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(feature = "block_layout")]
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> LayoutBlockContainer
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    StyleFn: Fn(NodeId) -> &'s Style,
+    MeasureFn: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, &Style) -> Size<f32>,
+{
+    type BlockContainerStyle<'a> = &'a Style where Self: 'a;
+    type BlockItemStyle<'a> = &'a Style where Self: 'a;
+
+    #[inline(always)]
+    fn get_block_container_style(&self, node_id: NodeId) -> Self::BlockContainerStyle<'_> {
+        (self.style)(node_id)
+    }
+
+    #[inline(always)]
+    fn get_block_child_style(&self, child_node_id: NodeId) -> Self::BlockItemStyle<'_> {
+        (self.style)(child_node_id)
+    }
+}
+
+#[cfg(feature = "flexbox")]
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> LayoutFlexboxContainer
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    StyleFn: Fn(NodeId) -> &'s Style,
+    MeasureFn: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, &Style) -> Size<f32>,
+{
+    type FlexboxContainerStyle<'a> = &'a Style where Self: 'a;
+    type FlexboxItemStyle<'a> = &'a Style where Self: 'a;
+
+    #[inline(always)]
+    fn get_flexbox_container_style(&self, node_id: NodeId) -> Self::FlexboxContainerStyle<'_> {
+        (self.style)(node_id)
+    }
+
+    #[inline(always)]
+    fn get_flexbox_child_style(&self, child_node_id: NodeId) -> Self::FlexboxItemStyle<'_> {
+        (self.style)(child_node_id)
+    }
+}
+
+#[cfg(feature = "grid")]
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> LayoutGridContainer
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    StyleFn: Fn(NodeId) -> &'s Style,
+    MeasureFn: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, &Style) -> Size<f32>,
+{
+    type GridContainerStyle<'a> = &'a Style where Self: 'a;
+    type GridItemStyle<'a> = &'a Style where Self: 'a;
+
+    #[inline(always)]
+    fn get_grid_container_style(&self, node_id: NodeId) -> Self::GridContainerStyle<'_> {
+        (self.style)(node_id)
+    }
+
+    #[inline(always)]
+    fn get_grid_child_style(&self, child_node_id: NodeId) -> Self::GridItemStyle<'_> {
+        (self.style)(child_node_id)
+    }
+}
+
+// Inline containers of an external tree only contain their children, so are laid out by the default formatter
+#[cfg(feature = "inline_layout")]
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> LayoutInlineContainer
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    StyleFn: Fn(NodeId) -> &'s Style,
+    MeasureFn: FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, &Style) -> Size<f32>,
+{
+}
+
+impl<'s, ChildrenFn, ChildIter, StyleFn, LayoutFn, MeasureFn> RoundTree
+    for ExternalTree<'s, ChildrenFn, StyleFn, LayoutFn, MeasureFn>
+where
+    ChildrenFn: Fn(NodeId) -> ChildIter,
+    ChildIter: ExactSizeIterator<Item = NodeId>,
+    LayoutFn: FnMut(NodeId, &Layout),
+{
+    #[inline(always)]
+    fn get_unrounded_layout(&self, node: NodeId) -> &Layout {
+        &self.caches.nodes[&node].unrounded_layout
+    }
+
+    #[inline(always)]
+    fn set_final_layout(&mut self, node_id: NodeId, layout: &Layout) {
+        (self.set_layout)(node_id, layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExternalTree, LayoutCaches};
+    use crate::prelude::*;
+    use crate::Point;
+
+    /// Node storage in the style of an ECS: one array per component, indexed by entity
+    struct World {
+        /// The style component of each entity
+        styles: Vec<Style>,
+        /// The children component of each entity
+        children: Vec<Vec<NodeId>>,
+        /// The layout component of each entity
+        layouts: Vec<Layout>,
+    }
+
+    impl World {
+        /// Lays out the tree rooted at entity 0, counting the number of times leaf nodes are measured
+        fn layout(&mut self, caches: &mut LayoutCaches, measure_count: &mut usize) {
+            let World { styles, children, layouts } = self;
+            let mut tree = ExternalTree::new(
+                caches,
+                |node| children[usize::from(node)].iter().copied(),
+                |node| &styles[usize::from(node)],
+                |node, layout| layouts[usize::from(node)] = *layout,
+                |_, _, _, _| {
+                    *measure_count += 1;
+                    Size { width: 10.0, height: 10.0 }
+                },
+            );
+            tree.compute_layout(NodeId::from(0u64), Size::MAX_CONTENT);
+        }
+    }
+
+    #[test]
+    fn layout_runs_over_external_storage() {
+        let mut world = World {
+            styles: vec![
+                Style { size: Size::from_lengths(100.0, 50.0), ..Default::default() },
+                Style { flex_grow: 1.0, ..Default::default() },
+                Style::default(),
+            ],
+            children: vec![vec![NodeId::from(1u64), NodeId::from(2u64)], vec![], vec![]],
+            layouts: vec![Layout::new(); 3],
+        };
+        let mut caches = LayoutCaches::new();
+        let mut measure_count = 0;
+        world.layout(&mut caches, &mut measure_count);
+
+        assert_eq!(world.layouts[0].size, Size { width: 100.0, height: 50.0 });
+        assert_eq!(world.layouts[1].size, Size { width: 90.0, height: 50.0 });
+        assert_eq!(world.layouts[2].location, Point { x: 90.0, y: 0.0 });
+        assert_eq!(world.layouts[2].size, Size { width: 10.0, height: 50.0 });
+
+        // An unchanged tree is laid out from the cache
+        let first_measure_count = measure_count;
+        world.layout(&mut caches, &mut measure_count);
+        assert_eq!(measure_count, first_measure_count);
+
+        // Changing a style only takes effect once the node and its ancestors are marked dirty
+        world.styles[2].size.width = length(30.0);
+        caches.mark_dirty(NodeId::from(2u64));
+        caches.mark_dirty(NodeId::from(0u64));
+        world.layout(&mut caches, &mut measure_count);
+        assert_eq!(world.layouts[1].size.width, 70.0);
+        assert_eq!(world.layouts[2].size.width, 30.0);
+    }
+}
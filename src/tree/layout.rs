@@ -27,6 +27,70 @@ pub enum SizingMode {
     InherentSize,
 }
 
+/// Which dimensions of the containing block percentage margins and padding are resolved against
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PercentResolutionMode {
+    /// All four sides resolve against the containing block's width. This is how CSS is specified
+    /// (see: <https://developer.mozilla.org/en-US/docs/Web/CSS/padding#values>).
+    #[default]
+    Width,
+    /// The left and right sides resolve against the containing block's width, and the top and bottom sides resolve
+    /// against its height. This matches the behaviour of some non-web UI frameworks.
+    PerAxis,
+}
+
+impl PercentResolutionMode {
+    /// Returns the size that percentage margins and padding should be resolved against given the size of the
+    /// containing block. Left and right sides resolve against the returned width, and top and bottom sides
+    /// against the returned height.
+    #[inline(always)]
+    pub fn basis<T: Copy>(self, containing_block_size: Size<T>) -> Size<T> {
+        match self {
+            Self::Width => Size { width: containing_block_size.width, height: containing_block_size.width },
+            Self::PerAxis => containing_block_size,
+        }
+    }
+}
+
+/// Switches for behaviours where layout may deviate from the web specifications
+///
+/// The default configuration follows the web specifications (except for rounding, which is enabled by default).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LayoutConfig {
+    /// Whether layout values are rounded to whole pixels after layout is computed
+    pub use_rounding: bool,
+    /// Which dimensions of the containing block percentage margins and padding are resolved against
+    pub percent_resolution_mode: PercentResolutionMode,
+    /// Whether a `min_size` of `auto` resolves to the content-based automatic minimum size of flex and grid items
+    /// (as specified by CSS). If `false` then it resolves to zero (as in Yoga and React Native).
+    pub use_automatic_minimum_size: bool,
+    /// Emulates the behaviour of the `stretch` crate (Taffy's predecessor) of clamping stretched flex items in the cross
+    /// axis by their maximum size *after* it has been transferred through the item's aspect ratio.
+    pub legacy_stretch: bool,
+    /// Emulates older versions of Taffy, in which grid items with `display: none` placed at definite lines outside of
+    /// the explicit grid still generated implicit tracks (and so grew the grid) although they weren't laid out.
+    pub legacy_hidden_grid_items: bool,
+}
+
+impl LayoutConfig {
+    /// The [`Default`] configuration, in a form that can be used in const functions
+    pub const DEFAULT: LayoutConfig = LayoutConfig {
+        use_rounding: true,
+        percent_resolution_mode: PercentResolutionMode::Width,
+        use_automatic_minimum_size: true,
+        legacy_stretch: false,
+        legacy_hidden_grid_items: false,
+    };
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 /// A set of margins that are available for collapsing with for block layout's margin collapsing
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -133,6 +197,9 @@ pub struct LayoutInput {
     pub available_space: Size<AvailableSpace>,
     /// Specific to CSS Block layout. Used for correctly computing margin collapsing. You probably want to set this to `Line::FALSE`.
     pub vertical_margins_are_collapsible: Line<bool>,
+    /// The configuration of behaviours where layout may deviate from the web specifications.
+    /// You probably want to set this to the value returned by [`LayoutPartialTree::get_layout_config`](crate::LayoutPartialTree::get_layout_config).
+    pub config: LayoutConfig,
 }
 
 impl LayoutInput {
@@ -147,7 +214,20 @@ impl LayoutInput {
         sizing_mode: SizingMode::InherentSize,
         axis: RequestedAxis::Both,
         vertical_margins_are_collapsible: Line::FALSE,
+        config: LayoutConfig::DEFAULT,
     };
+
+    /// Snap the sizes and definite available space of these inputs to the fixed point grid. See [`quantize`].
+    #[cfg(feature = "fixed_point")]
+    #[must_use]
+    pub(crate) fn quantized(self) -> Self {
+        Self {
+            known_dimensions: self.known_dimensions.map(|size| size.map(quantize)),
+            parent_size: self.parent_size.map(|size| size.map(quantize)),
+            available_space: self.available_space.map(|space| space.map_definite_value(quantize)),
+            ..self
+        }
+    }
 }
 
 /// A struct containing the result of laying a single node, which is returned up to the parent node
@@ -155,7 +235,7 @@ impl LayoutInput {
 /// A baseline is the line on which text sits. Your node likely has a baseline if it is a text node, or contains
 /// children that may be text nodes. See <https://www.w3.org/TR/css-writing-modes-3/#intro-baselines> for details.
 /// If your node does not have a baseline (or you are unsure how to compute it), then simply return `Point::NONE`
-/// for the first_baselines field
+/// for the first_baselines and last_baselines fields
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct LayoutOutput {
@@ -166,6 +246,8 @@ pub struct LayoutOutput {
     pub content_size: Size<f32>,
     /// The first baseline of the node in each dimension, if any
     pub first_baselines: Point<Option<f32>>,
+    /// The last baseline of the node in each dimension, if any
+    pub last_baselines: Point<Option<f32>>,
     /// Top margin that can be collapsed with. This is used for CSS block layout and can be set to
     /// `CollapsibleMarginSet::ZERO` for other layout modes that don't support margin collapsing
     pub top_margin: CollapsibleMarginSet,
@@ -175,6 +257,13 @@ pub struct LayoutOutput {
     /// Whether margins can be collapsed through this node. This is used for CSS block layout and can
     /// be set to `false` for other layout modes that don't support margin collapsing
     pub margins_can_collapse_through: bool,
+    /// The distance the content of a scroll container was moved towards the end of each axis by safe alignment. See
+    /// [`Layout::scroll_origin_shift`].
+    pub scroll_origin_shift: Point<f32>,
+    /// Whether the output may depend on the available width. If `false` (which is only the case for leaves whose
+    /// measure function returned a [`MeasureOutput`] which doesn't depend on it), the cached output is reused for any
+    /// available width
+    pub depends_on_available_width: bool,
 }
 
 impl LayoutOutput {
@@ -184,9 +273,12 @@ impl LayoutOutput {
         #[cfg(feature = "content_size")]
         content_size: Size::ZERO,
         first_baselines: Point::NONE,
+        last_baselines: Point::NONE,
         top_margin: CollapsibleMarginSet::ZERO,
         bottom_margin: CollapsibleMarginSet::ZERO,
         margins_can_collapse_through: false,
+        scroll_origin_shift: Point::ZERO,
+        depends_on_available_width: true,
     };
 
     /// A blank layout output
@@ -203,12 +295,27 @@ impl LayoutOutput {
             #[cfg(feature = "content_size")]
             content_size,
             first_baselines,
+            last_baselines: Point::NONE,
             top_margin: CollapsibleMarginSet::ZERO,
             bottom_margin: CollapsibleMarginSet::ZERO,
             margins_can_collapse_through: false,
+            scroll_origin_shift: Point::ZERO,
+            depends_on_available_width: true,
         }
     }
 
+    /// Set the last baselines of this output, which are otherwise left unset by the constructors
+    #[must_use]
+    pub fn with_last_baselines(self, last_baselines: Point<Option<f32>>) -> Self {
+        Self { last_baselines, ..self }
+    }
+
+    /// Set the scroll origin shift of this output, which is otherwise left as zero by the constructors
+    #[must_use]
+    pub fn with_scroll_origin_shift(self, scroll_origin_shift: Point<f32>) -> Self {
+        Self { scroll_origin_shift, ..self }
+    }
+
     /// Construct a SizeBaselinesAndMargins from just the container and content sizes
     pub fn from_sizes(size: Size<f32>, content_size: Size<f32>) -> Self {
         Self::from_sizes_and_baselines(size, content_size, Point::NONE)
@@ -218,6 +325,66 @@ impl LayoutOutput {
     pub fn from_outer_size(size: Size<f32>) -> Self {
         Self::from_sizes(size, Size::zero())
     }
+
+    /// Snap the sizes and baselines of this output to the fixed point grid. See [`quantize`].
+    #[cfg(feature = "fixed_point")]
+    #[must_use]
+    pub(crate) fn quantized(self) -> Self {
+        Self {
+            size: self.size.map(quantize),
+            #[cfg(feature = "content_size")]
+            content_size: self.content_size.map(quantize),
+            first_baselines: self.first_baselines.map(|baseline| baseline.map(quantize)),
+            last_baselines: self.last_baselines.map(|baseline| baseline.map(quantize)),
+            scroll_origin_shift: self.scroll_origin_shift.map(quantize),
+            ..self
+        }
+    }
+}
+
+/// The result of measuring the content of a leaf node with a measure function
+///
+/// Measure functions may return just the [`Size`] of the content, which converts into a `MeasureOutput` without
+/// baselines which may depend on the available width. Baselines are measured from the top-left corner of the content box
+/// (they are offset by the node's padding and border in its [`LayoutOutput`]), and are used to align the leaf with its
+/// siblings by `align-items: baseline`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MeasureOutput {
+    /// The size of the content
+    pub size: Size<f32>,
+    /// The first baseline of the content in each dimension, if any
+    pub first_baselines: Point<Option<f32>>,
+    /// The last baseline of the content in each dimension, if any
+    pub last_baselines: Point<Option<f32>>,
+    /// Whether the size may depend on the available width. Content which doesn't wrap (such as an image, or a single
+    /// line of text) can set this to `false`, so that the node is only measured again when its known dimensions change.
+    pub depends_on_available_width: bool,
+}
+
+impl MeasureOutput {
+    /// Create a `MeasureOutput` from the size of the content, without baselines
+    pub const fn from_size(size: Size<f32>) -> Self {
+        Self { size, first_baselines: Point::NONE, last_baselines: Point::NONE, depends_on_available_width: true }
+    }
+
+    /// Set the first and last baselines of the content
+    #[must_use]
+    pub fn with_baselines(self, first_baselines: Point<Option<f32>>, last_baselines: Point<Option<f32>>) -> Self {
+        Self { first_baselines, last_baselines, ..self }
+    }
+
+    /// Mark the size of the content as not depending on the available width
+    #[must_use]
+    pub fn independent_of_available_width(self) -> Self {
+        Self { depends_on_available_width: false, ..self }
+    }
+}
+
+impl From<Size<f32>> for MeasureOutput {
+    fn from(size: Size<f32>) -> Self {
+        Self::from_size(size)
+    }
 }
 
 /// The final result of a layout algorithm for a single node.
@@ -235,7 +402,10 @@ pub struct Layout {
     pub size: Size<f32>,
     #[cfg(feature = "content_size")]
     /// The width and height of the content inside the node. This may be larger than the size of the node in the case of
-    /// overflowing content and is useful for computing a "scroll width/height" for scrollable nodes
+    /// overflowing content and is useful for computing a "scroll width/height" for scrollable nodes. Content which is
+    /// clipped by [`Overflow::Clip`](crate::Overflow::Clip) is excluded, so it is never larger than the node in an axis
+    /// in which overflow is clipped. Content which overflows a descendant scroll container is scrolled by that
+    /// descendant, so is excluded too.
     pub content_size: Size<f32>,
     /// The size of the scrollbars in each dimension. If there is no scrollbar then the size will be zero.
     pub scrollbar_size: Size<f32>,
@@ -245,6 +415,17 @@ pub struct Layout {
     pub padding: Rect<f32>,
     /// The size of the margin of the node
     pub margin: Rect<f32>,
+    /// The distance from the top of the node to its first baseline, if it has one. Flex, grid and inline containers
+    /// have a baseline determined by their children, and leaves have one if their measure function returned it (see
+    /// [`MeasureOutput`]). Block layout doesn't compute baselines.
+    pub first_baseline: Option<f32>,
+    /// The distance the content of the node was moved towards the end of each axis because it is a scroll container.
+    ///
+    /// Content alignment (`justify-content` and `align-content`) in a scroll container is safe: content which would be
+    /// aligned past the start edge of the container (where it could never be scrolled to) is start-aligned instead.
+    /// This is how far the start of the scrollable overflow area was moved by doing so, which renderers can use as the
+    /// initial scroll position to show the content where the requested alignment would have placed it.
+    pub scroll_origin_shift: Point<f32>,
 }
 
 impl Default for Layout {
@@ -271,6 +452,8 @@ impl Layout {
             border: Rect::zero(),
             padding: Rect::zero(),
             margin: Rect::zero(),
+            first_baseline: None,
+            scroll_origin_shift: Point::ZERO,
         }
     }
 
@@ -290,10 +473,50 @@ impl Layout {
             border: Rect::zero(),
             padding: Rect::zero(),
             margin: Rect::zero(),
+            first_baseline: None,
+            scroll_origin_shift: Point::ZERO,
+        }
+    }
+}
+
+#[cfg(feature = "fixed_point")]
+impl Layout {
+    /// Snap every value of this layout to the fixed point grid. See [`quantize`].
+    #[must_use]
+    pub(crate) fn quantized(&self) -> Self {
+        Self {
+            order: self.order,
+            location: self.location.map(quantize),
+            size: self.size.map(quantize),
+            #[cfg(feature = "content_size")]
+            content_size: self.content_size.map(quantize),
+            scrollbar_size: self.scrollbar_size.map(quantize),
+            border: self.border.map(quantize),
+            padding: self.padding.map(quantize),
+            margin: self.margin.map(quantize),
+            first_baseline: self.first_baseline.map(quantize),
+            scroll_origin_shift: self.scroll_origin_shift.map(quantize),
         }
     }
 }
 
+/// The number of steps per pixel of the fixed point grid that layout values are snapped to
+#[cfg(feature = "fixed_point")]
+pub const FIXED_POINT_SCALE: f32 = 64.0;
+
+/// Snap a value to the nearest multiple of 1/[`FIXED_POINT_SCALE`] of a pixel
+///
+/// With the `fixed_point` feature enabled, the inputs and output of every node's layout, the sizes returned by measure
+/// functions, the sizes and offsets summed by the layout algorithms, the flexed sizes of flex items and the sizes of grid
+/// tracks are all snapped to this grid. Sums and differences of such values are exact in f32 (for magnitudes below 2^18
+/// px), so layouts no longer depend on the order in which sizes are added up or on tiny platform-specific differences in
+/// the values returned by measure functions.
+#[cfg(feature = "fixed_point")]
+#[inline(always)]
+pub fn quantize(value: f32) -> f32 {
+    crate::util::sys::round(value * FIXED_POINT_SCALE) / FIXED_POINT_SCALE
+}
+
 #[cfg(feature = "content_size")]
 impl Layout {
     /// Return the scroll width of the node.
@@ -10,7 +10,12 @@ mod node;
 pub mod traits;
 
 pub use cache::Cache;
-pub use layout::{CollapsibleMarginSet, Layout, LayoutInput, LayoutOutput, RequestedAxis, RunMode, SizingMode};
+#[cfg(feature = "fixed_point")]
+pub use layout::{quantize, FIXED_POINT_SCALE};
+pub use layout::{
+    CollapsibleMarginSet, Layout, LayoutConfig, LayoutInput, LayoutOutput, MeasureOutput, PercentResolutionMode,
+    RequestedAxis, RunMode, SizingMode,
+};
 pub use node::NodeId;
 pub(crate) use traits::LayoutPartialTreeExt;
 pub use traits::{LayoutPartialTree, PrintTree, RoundTree, TraversePartialTree, TraverseTree};
@@ -24,7 +29,23 @@ pub use traits::LayoutGridContainer;
 #[cfg(feature = "block_layout")]
 pub use traits::LayoutBlockContainer;
 
+#[cfg(feature = "inline_layout")]
+pub use traits::LayoutInlineContainer;
+
+#[cfg(feature = "external_tree")]
+pub mod external;
+#[cfg(feature = "external_tree")]
+pub use external::{ExternalTree, LayoutCaches};
+
 #[cfg(feature = "taffy_tree")]
 mod taffy_tree;
+#[cfg(all(feature = "taffy_tree", feature = "inline_layout"))]
+pub use taffy_tree::InlineLayout;
+#[cfg(all(feature = "taffy_tree", feature = "std"))]
+pub use taffy_tree::LayoutProgress;
 #[cfg(feature = "taffy_tree")]
-pub use taffy_tree::{TaffyError, TaffyResult, TaffyTree};
+pub use taffy_tree::{
+    BreadthFirstNodeIter, CacheState, DepthFirstNodeIter, LayoutAlgorithm, LayoutLimits, LayoutSnapshot, LayoutStats,
+    LengthProperty, MeasureTree, MemoryUsage, NodeInspection, ResolvedStyle, StyleCommand, StyleCommandBuffer,
+    StyleHandle, TaffyError, TaffyResult, TaffyTree,
+};
@@ -9,6 +9,14 @@ use slotmap::{DefaultKey, Key, KeyData};
 ///
 /// Internally it is a wrapper around a u64 and a `NodeId` can be converted to and from
 /// and u64 if needed.
+///
+/// Ids created by a [`TaffyTree`](crate::TaffyTree) include a generation counter which is incremented each time a
+/// slot is reused. Using the id of a removed node is therefore reported as an error rather than silently referring to
+/// whichever node has since taken its place.
+///
+/// When the `node_id_brand` feature is enabled, ids created by a [`TaffyTree`](crate::TaffyTree) also encode which tree
+/// created them, so that using an id with the wrong tree is reported as [`TaffyError::ForeignNode`](crate::TaffyError::ForeignNode)
+/// rather than silently referring to an unrelated node.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NodeId(u64);
 impl NodeId {
@@ -18,6 +26,31 @@ impl NodeId {
     }
 }
 
+/// The position of the brand within the `u64` representation of a [`NodeId`]. The brand occupies the top 8 bits of
+/// the (32 bit) slot index, leaving the slot version (which grows each time a slot is reused) untouched. Branded trees
+/// can therefore hold at most [`MAX_BRANDED_SLOTS`] nodes.
+#[cfg(feature = "node_id_brand")]
+const BRAND_SHIFT: u32 = 24;
+
+/// The number of slots which the index of a branded [`NodeId`] can address
+#[cfg(feature = "node_id_brand")]
+pub(crate) const MAX_BRANDED_SLOTS: u64 = 1 << BRAND_SHIFT;
+
+#[cfg(feature = "node_id_brand")]
+impl NodeId {
+    /// Returns the brand of the tree that created this node, or 0 if the id is unbranded
+    #[inline]
+    pub(crate) const fn brand(self) -> u8 {
+        (self.0 >> BRAND_SHIFT) as u8
+    }
+
+    /// Returns this id with its brand replaced by `brand`
+    #[inline]
+    pub(crate) const fn with_brand(self, brand: u8) -> Self {
+        Self((self.0 & !(0xff << BRAND_SHIFT)) | ((brand as u64) << BRAND_SHIFT))
+    }
+}
+
 impl From<u64> for NodeId {
     #[inline]
     fn from(raw: u64) -> Self {
@@ -55,6 +88,8 @@ impl From<DefaultKey> for NodeId {
 impl From<NodeId> for DefaultKey {
     #[inline]
     fn from(key: NodeId) -> Self {
+        #[cfg(feature = "node_id_brand")]
+        let key = key.with_brand(0);
         KeyData::from_ffi(key.0).into()
     }
 }
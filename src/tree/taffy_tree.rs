@@ -3,26 +3,53 @@
 use slotmap::SecondaryMap;
 #[cfg(feature = "std")]
 use slotmap::SparseSecondaryMap as SecondaryMap;
-use slotmap::{DefaultKey, SlotMap};
-
-use crate::geometry::Size;
-use crate::style::{AvailableSpace, Display, Style};
+use slotmap::{DefaultKey, Key, KeyData, SlotMap};
+
+use crate::geometry::{Point, Rect, Size};
+#[cfg(feature = "record")]
+use crate::record::{Command, Recorder};
+use crate::style::{
+    AvailableSpace, BoxSizing, Dimension, Direction, Display, InheritedStyle, LengthPercentage, LengthPercentageAuto,
+    Position, Style, StyleDelta, StyleVariant, StyleWarning, WritingMode,
+};
+use crate::style_helpers::TaffyMaxContent;
 use crate::tree::{
-    Cache, Layout, LayoutInput, LayoutOutput, LayoutPartialTree, NodeId, PrintTree, RoundTree, RunMode,
-    TraversePartialTree, TraverseTree,
+    Cache, Layout, LayoutConfig, LayoutInput, LayoutOutput, LayoutPartialTree, MeasureOutput, NodeId, PrintTree,
+    RoundTree, RunMode, TraversePartialTree, TraverseTree,
 };
+#[cfg(feature = "debug_checks")]
+use crate::util::checks::{find_invalid_layout_inputs, find_invalid_layout_outputs, find_invalid_style_values};
 use crate::util::debug::{debug_log, debug_log_node};
-use crate::util::sys::{new_vec_with_capacity, ChildrenVec, Vec};
+#[cfg(feature = "std")]
+use crate::util::hash::{LayoutHash, LayoutHasher};
+#[cfg(feature = "inline_layout")]
+use crate::util::sys::Box;
+use crate::util::sys::{f32_min, new_children_vec_with_capacity, new_vec_with_capacity, Arc, ChildrenVec, Vec};
+use crate::util::{MaybeResolve, ResolveOrZero};
+#[cfg(feature = "std")]
+use core::hash::Hasher;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 use crate::compute::{
-    compute_cached_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout, round_layout,
+    compute_cached_layout, compute_constrained_layout, compute_hidden_layout, compute_leaf_layout, compute_root_layout,
+    compute_root_layout_with_known_dimensions, round_layout, round_subtree_layout, store_cached_layout,
+    LayoutConstraints,
 };
 #[cfg(feature = "block_layout")]
 use crate::{compute::compute_block_layout, LayoutBlockContainer};
 #[cfg(feature = "flexbox")]
 use crate::{compute::compute_flexbox_layout, LayoutFlexboxContainer};
+#[cfg(feature = "inline_layout")]
+use crate::{
+    compute::{break_atoms_into_lines, compute_inline_layout, InlineFormatInput, LineBox},
+    LayoutInlineContainer,
+};
 #[cfg(feature = "grid")]
-use crate::{compute::compute_grid_layout, LayoutGridContainer};
+use crate::{
+    compute::{compute_grid_layout, GridOccupancyStats},
+    LayoutGridContainer,
+};
 
 /// The error Taffy generates on invalid operations
 pub type TaffyResult<T> = Result<T, TaffyError>;
@@ -45,6 +72,41 @@ pub enum TaffyError {
     InvalidChildNode(NodeId),
     /// The supplied node was not found in the [`TaffyTree`](crate::TaffyTree) instance.
     InvalidInputNode(NodeId),
+    /// The supplied shared style was not found in the [`TaffyTree`](crate::TaffyTree) instance.
+    InvalidStyleHandle(StyleHandle),
+    /// The supplied node was created by a different [`TaffyTree`](crate::TaffyTree) instance.
+    ///
+    /// Only detected when the `node_id_brand` feature is enabled.
+    ForeignNode(NodeId),
+    /// The supplied node is more than the tree's [`max_depth`](crate::TaffyTree::max_depth) levels below the root of the
+    /// layout being computed, so the layout was not computed.
+    MaxDepthExceeded(NodeId),
+    /// The new parent node is the node being moved, or one of its descendants.
+    ParentIsDescendant {
+        /// The node being moved
+        node: NodeId,
+        /// The parent it was being moved under
+        parent: NodeId,
+    },
+    /// No style preset with the supplied name was found in the [`TaffyTree`](crate::TaffyTree) instance.
+    #[cfg(feature = "std")]
+    InvalidStylePreset(String),
+    /// The slices describing the nodes of a tree have different lengths.
+    /// See [`TaffyTree::build_from_slices`](crate::TaffyTree::build_from_slices).
+    SliceLengthMismatch {
+        /// The number of styles
+        styles: usize,
+        /// The number of parent indices
+        parents: usize,
+    },
+    /// The parent of a node in the description of a tree does not come before it.
+    /// See [`TaffyTree::build_from_slices`](crate::TaffyTree::build_from_slices).
+    InvalidParentIndex {
+        /// The index of the node
+        index: usize,
+        /// The index of its parent
+        parent: usize,
+    },
 }
 
 impl core::fmt::Display for TaffyError {
@@ -58,6 +120,26 @@ impl core::fmt::Display for TaffyError {
             }
             TaffyError::InvalidChildNode(child) => write!(f, "Child Node {child:?} is not in the TaffyTree instance"),
             TaffyError::InvalidInputNode(node) => write!(f, "Supplied Node {node:?} is not in the TaffyTree instance"),
+            TaffyError::InvalidStyleHandle(handle) => {
+                write!(f, "Shared style {handle:?} is not in the TaffyTree instance")
+            }
+            TaffyError::ForeignNode(node) => write!(f, "Node {node:?} belongs to a different TaffyTree instance"),
+            TaffyError::MaxDepthExceeded(node) => {
+                write!(f, "Node {node:?} is nested deeper than the TaffyTree instance's maximum depth")
+            }
+            TaffyError::ParentIsDescendant { node, parent } => {
+                write!(f, "Node {node:?} cannot be moved under {parent:?}, which is itself or one of its descendants")
+            }
+            #[cfg(feature = "std")]
+            TaffyError::InvalidStylePreset(name) => {
+                write!(f, "Style preset {name:?} is not in the TaffyTree instance")
+            }
+            TaffyError::SliceLengthMismatch { styles, parents } => {
+                write!(f, "The number of styles ({styles}) differs from the number of parent indices ({parents})")
+            }
+            TaffyError::InvalidParentIndex { index, parent } => {
+                write!(f, "The parent of node {index} ({parent}) does not come before it")
+            }
         }
     }
 }
@@ -65,26 +147,514 @@ impl core::fmt::Display for TaffyError {
 #[cfg(feature = "std")]
 impl std::error::Error for TaffyError {}
 
-/// Global configuration values for a TaffyTree instance
+/// A handle to a [`Style`] that can be shared between many nodes in a [`TaffyTree`]
+///
+/// Created with [`TaffyTree::create_shared_style`] and assigned to nodes with [`TaffyTree::set_node_style_handle`].
+/// Internally it is a wrapper around a u64 and a `StyleHandle` can be converted to and from a u64 if needed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StyleHandle(u64);
+
+impl From<u64> for StyleHandle {
+    #[inline]
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
+}
+impl From<StyleHandle> for u64 {
+    #[inline]
+    fn from(handle: StyleHandle) -> Self {
+        handle.0
+    }
+}
+impl From<DefaultKey> for StyleHandle {
+    #[inline]
+    fn from(key: DefaultKey) -> Self {
+        Self(key.data().as_ffi())
+    }
+}
+impl From<StyleHandle> for DefaultKey {
+    #[inline]
+    fn from(handle: StyleHandle) -> Self {
+        KeyData::from_ffi(handle.0).into()
+    }
+}
+
+/// An approximate breakdown of the memory used by a [`TaffyTree`]. Returned by [`TaffyTree::memory_usage`].
+///
+/// Sizes are computed from the capacity of the tree's internal storage, so they include memory that has been
+/// allocated but is not currently in use. Memory owned by node contexts (beyond `size_of::<NodeContext>()`) is not included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// The number of nodes in the tree
+    pub node_count: usize,
+    /// The number of distinct styles stored in the tree. Shared styles are only counted once.
+    pub style_count: usize,
+    /// Bytes used by per-node data: layouts, caches and the lists of parents and children
+    pub node_bytes: usize,
+    /// Bytes used by styles, including heap allocations owned by the styles (such as grid track lists)
+    pub style_bytes: usize,
+    /// Bytes used to store node context data
+    pub context_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// The total number of bytes used by the tree
+    pub fn total_bytes(&self) -> usize {
+        self.node_bytes + self.style_bytes + self.context_bytes
+    }
+}
+
+/// The layout algorithm which lays out a node. Returned as part of a [`NodeInspection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutAlgorithm {
+    /// The node (or one of its ancestors) has `Display::None` and is hidden
+    Hidden,
+    /// The node has no children and is sized by its style and measure function
+    Leaf,
+    /// The node's children are laid out using CSS Block layout
+    #[cfg(feature = "block_layout")]
+    Block,
+    /// The node's children are laid out using CSS Flexbox layout
+    #[cfg(feature = "flexbox")]
+    Flexbox,
+    /// The node's children are laid out using CSS Grid layout
+    #[cfg(feature = "grid")]
+    Grid,
+    /// The node's children are placed on lines by an inline formatter
+    #[cfg(feature = "inline_layout")]
+    Inline,
+}
+
+/// Counts of the work done by a layout. Returned by [`TaffyTree::last_layout_stats`].
+///
+/// Counts which are much larger than the number of nodes in the tree indicate content which is expensive to lay out,
+/// such as deeply nested intrinsically sized containers (which are measured many times with different constraints).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LayoutStats {
+    /// The number of times a node was laid out or measured, including those answered from its cache
+    pub node_visits: usize,
+    /// The number of node visits which were answered from the node's cache
+    pub cache_hits: usize,
+    /// The number of node visits which were not in the node's cache, so were computed by a layout algorithm
+    pub cache_misses: usize,
+    /// The number of calls to the measure function
+    pub measure_calls: usize,
+    /// The number of times the Flexbox algorithm was run for a container
+    pub flex_passes: usize,
+    /// The number of times the Grid track sizing algorithm was run for an axis of a container. This is two for each
+    /// run of the Grid algorithm, plus one for each axis which had to be sized again.
+    pub grid_track_sizing_passes: usize,
+    /// The wall time spent in each [`LayoutAlgorithm`] (indexed by `algorithm as usize`), excluding the time spent
+    /// laying out the children of each node. See [`LayoutStats::time_in`].
+    #[cfg(feature = "std")]
+    algorithm_times: [std::time::Duration; 6],
+}
+
+impl LayoutStats {
+    /// The wall time spent running `algorithm`, excluding the time spent laying out the children of each node
+    #[cfg(feature = "std")]
+    pub fn time_in(&self, algorithm: LayoutAlgorithm) -> std::time::Duration {
+        self.algorithm_times[algorithm as usize]
+    }
+
+    /// The total wall time spent running layout algorithms
+    #[cfg(feature = "std")]
+    pub fn total_time(&self) -> std::time::Duration {
+        self.algorithm_times.iter().sum()
+    }
+}
+
+/// Limits on the work done by a single layout, to guard against degenerate layouts which would otherwise take too long
+/// to compute. See [`TaffyTree::set_layout_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LayoutLimits {
+    /// The maximum number of times a node is laid out or measured, including those answered from its cache (see
+    /// [`LayoutStats::node_visits`])
+    pub max_node_visits: Option<usize>,
+    /// The maximum wall time spent computing layout
+    #[cfg(feature = "std")]
+    pub max_time: Option<std::time::Duration>,
+}
+
+/// The progress of the layout in progress against the tree's [`LayoutLimits`], while set
+#[derive(Debug, Clone)]
+struct LayoutLimitsState {
+    /// The limits themselves
+    limits: LayoutLimits,
+    /// The number of nodes visited so far
+    node_visits: usize,
+    /// The time at which the layout started
+    #[cfg(feature = "std")]
+    start: std::time::Instant,
+    /// Whether a limit has been exceeded
+    is_exceeded: bool,
+    /// The nodes which were skipped because a limit was exceeded
+    skipped_nodes: Vec<NodeId>,
+}
+
+impl LayoutLimitsState {
+    /// Creates the state of a layout which is starting now
+    fn new(limits: LayoutLimits) -> Self {
+        Self {
+            limits,
+            node_visits: 0,
+            #[cfg(feature = "std")]
+            start: std::time::Instant::now(),
+            is_exceeded: false,
+            skipped_nodes: Vec::new(),
+        }
+    }
+
+    /// Whether a limit has been exceeded, in which case nodes which are not in the cache are skipped
+    fn is_exceeded(&mut self) -> bool {
+        if !self.is_exceeded {
+            let too_many_visits = self.limits.max_node_visits.map_or(false, |max| self.node_visits > max);
+            #[cfg(feature = "std")]
+            let too_long = self.limits.max_time.map_or(false, |max| self.start.elapsed() > max);
+            #[cfg(not(feature = "std"))]
+            let too_long = false;
+            self.is_exceeded = too_many_visits || too_long;
+        }
+        self.is_exceeded
+    }
+}
+
+/// The statistics of the layout in progress, while enabled. See [`TaffyTree::enable_layout_stats`].
+#[derive(Debug, Clone, Default)]
+struct LayoutStatsCollector {
+    /// The statistics collected so far
+    stats: LayoutStats,
+    /// The time spent laying out the children of the node currently being laid out
+    #[cfg(feature = "std")]
+    child_time: std::time::Duration,
+}
+
+impl LayoutStatsCollector {
+    /// Starts timing the layout of a node, returning the start time and the time spent on the children of its parent
+    /// so far (to be passed to [`LayoutStatsCollector::finish_node`])
+    #[cfg(feature = "std")]
+    fn start_node(&mut self) -> (std::time::Instant, std::time::Duration) {
+        (std::time::Instant::now(), core::mem::take(&mut self.child_time))
+    }
+
+    /// Records that a node was laid out by `algorithm`
+    fn finish_node(
+        &mut self,
+        algorithm: LayoutAlgorithm,
+        #[cfg(feature = "std")] (start, parent_child_time): (std::time::Instant, std::time::Duration),
+    ) {
+        #[cfg(feature = "flexbox")]
+        if algorithm == LayoutAlgorithm::Flexbox {
+            self.stats.flex_passes += 1;
+        }
+        #[cfg(feature = "std")]
+        {
+            let elapsed = start.elapsed();
+            self.stats.algorithm_times[algorithm as usize] += elapsed.saturating_sub(self.child_time);
+            self.child_time = parent_child_time + elapsed;
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = algorithm;
+    }
+}
+
+/// The state of a node's layout cache. Returned as part of a [`NodeInspection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheState {
+    /// Whether the node's layout must be recomputed (see [`TaffyTree::dirty`])
+    pub is_dirty: bool,
+    /// Whether the cache holds the result of a full layout of the node
+    pub has_final_layout: bool,
+    /// The number of cached size measurements for the node
+    pub measure_entry_count: usize,
+}
+
+/// The values of a node's style after percentages have been resolved against the parent's content box
+///
+/// Percentages are resolved using the parent's most recently computed layout, so these values are only meaningful once
+/// layout has been computed. `None` indicates an `auto` value or a percentage that could not be resolved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedStyle {
+    /// The direction of the node, with [`Direction::Inherit`] resolved (see [`TaffyTree::computed_direction`])
+    pub direction: Direction,
+    /// The writing mode of the node, with [`WritingMode::Inherit`] resolved (see [`TaffyTree::computed_writing_mode`])
+    pub writing_mode: WritingMode,
+    /// The resolved `size` of the node
+    pub size: Size<Option<f32>>,
+    /// The resolved `min_size` of the node
+    pub min_size: Size<Option<f32>>,
+    /// The resolved `max_size` of the node
+    pub max_size: Size<Option<f32>>,
+    /// The resolved `inset` of the node
+    pub inset: Rect<Option<f32>>,
+    /// The resolved `margin` of the node
+    pub margin: Rect<Option<f32>>,
+    /// The resolved `padding` of the node
+    pub padding: Rect<f32>,
+    /// The resolved `border` of the node
+    pub border: Rect<f32>,
+}
+
+/// Everything known about a single node in a [`TaffyTree`]. Returned by [`TaffyTree::inspect`].
+///
+/// This is intended to back tools such as an in-engine layout inspector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeInspection {
+    /// The node being inspected
+    pub node: NodeId,
+    /// A short description of the node, as used by [`TaffyTree::print_tree`]
+    pub label: &'static str,
+    /// The parent of the node (if any)
+    pub parent: Option<NodeId>,
+    /// The children of the node
+    pub children: Vec<NodeId>,
+    /// The style of the node, as specified
+    pub style: Style,
+    /// The style of the node with percentages and inherited values resolved
+    pub resolved_style: ResolvedStyle,
+    /// The final layout of the node (rounded if rounding is enabled)
+    pub layout: Layout,
+    /// The unrounded layout of the node
+    pub unrounded_layout: Layout,
+    /// The state of the node's layout cache
+    pub cache: CacheState,
+    /// The algorithm which lays out the node
+    pub algorithm: LayoutAlgorithm,
+    /// Statistics about the most recent placement of the node's items, if it has been laid out as a grid container
+    #[cfg(feature = "grid")]
+    pub grid_occupancy: Option<GridOccupancyStats>,
+}
+
+/// An immutable copy of the layouts, styles and hierarchy of a [`TaffyTree`]. Returned by [`TaffyTree::freeze`].
+///
+/// Unlike the tree it was taken from, a snapshot does not borrow anything and is `Send + Sync` regardless of the tree's
+/// `NodeContext`, so it can be shared between threads (for example behind an `Arc`) to answer layout queries while the
+/// tree itself is being modified and laid out again. [`NodeId`]s are those of the tree at the time it was frozen.
+#[derive(Debug, Clone)]
+pub struct LayoutSnapshot {
+    /// The layout and style of each node
+    nodes: SecondaryMap<DefaultKey, SnapshotNode>,
+    /// The children of each node
+    children: SlotMap<DefaultKey, ChildrenVec<NodeId>>,
+    /// The parent of each node
+    parents: SlotMap<DefaultKey, Option<NodeId>>,
+    /// The brand of the tree the snapshot was taken from
+    #[cfg(feature = "node_id_brand")]
+    brand: u8,
+}
+
+/// The per-node data stored in a [`LayoutSnapshot`]
+#[derive(Debug, Clone)]
+struct SnapshotNode {
+    /// The node's style, shared with the tree until the tree changes it
+    style: Arc<Style>,
+    /// The final layout of the node (rounded if rounding was enabled)
+    layout: Layout,
+    /// The node's user data. See [`TaffyTree::set_user_data`].
+    user_data: u64,
+}
+
+impl LayoutSnapshot {
+    /// Checks that `node` refers to a node in this snapshot, and returns it in the form in which the snapshot stores it
+    ///
+    /// See [`TaffyTree::own_node`].
+    #[inline(always)]
+    fn own_node(&self, node: NodeId, invalid: fn(NodeId) -> TaffyError) -> TaffyResult<NodeId> {
+        #[cfg(feature = "node_id_brand")]
+        let node = match node.brand() {
+            0 => node.with_brand(self.brand),
+            brand if brand != self.brand => return Err(TaffyError::ForeignNode(node)),
+            _ => node,
+        };
+        if !self.nodes.contains_key(node.into()) {
+            return Err(invalid(node));
+        }
+        Ok(node)
+    }
+
+    /// Returns the total number of nodes in the snapshot
+    pub fn total_node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return this node layout relative to its parent
+    pub fn layout(&self, node: NodeId) -> TaffyResult<&Layout> {
+        Ok(&self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].layout)
+    }
+
+    /// Returns the user data of the provided `node`. See [`TaffyTree::set_user_data`].
+    pub fn user_data(&self, node: NodeId) -> TaffyResult<u64> {
+        Ok(self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].user_data)
+    }
+
+    /// Return this node's layout together with its user data
+    pub fn layout_with_user_data(&self, node: NodeId) -> TaffyResult<(&Layout, u64)> {
+        let node = &self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()];
+        Ok((&node.layout, node.user_data))
+    }
+
+    /// Gets the [`Style`] of the provided `node`
+    pub fn style(&self, node: NodeId) -> TaffyResult<&Style> {
+        Ok(&self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].style)
+    }
+
+    /// Returns the children of the provided `parent`
+    pub fn children(&self, parent: NodeId) -> TaffyResult<&[NodeId]> {
+        Ok(&self.children[self.own_node(parent, TaffyError::InvalidParentNode)?.into()])
+    }
+
+    /// Returns the number of children of the provided `parent`
+    pub fn child_count(&self, parent: NodeId) -> TaffyResult<usize> {
+        Ok(self.children(parent)?.len())
+    }
+
+    /// Returns the `NodeId` of the parent node of the specified node (if it exists)
+    ///
+    /// Returns None if the specified node has no parent or does not exist
+    pub fn parent(&self, child_id: NodeId) -> Option<NodeId> {
+        self.parents[self.own_node(child_id, TaffyError::InvalidInputNode).ok()?.into()]
+    }
+
+    /// Returns the deepest node in the subtree rooted at `root` whose border box contains `point`
+    ///
+    /// `point` is in the same coordinate space as the location of `root` (that is, relative to its parent, or to the
+    /// origin of the layout if `root` is the root of the tree). Where siblings overlap, later children are treated as being
+    /// on top of earlier ones. Returns `None` if `root` is invalid or `point` lies outside of it.
+    pub fn hit_test(&self, root: NodeId, point: Point<f32>) -> Option<NodeId> {
+        let contains = |node: NodeId, point: Point<f32>| {
+            let layout = &self.nodes[node.into()].layout;
+            point.x >= layout.location.x
+                && point.y >= layout.location.y
+                && point.x < layout.location.x + layout.size.width
+                && point.y < layout.location.y + layout.size.height
+        };
+
+        let mut node = self.own_node(root, TaffyError::InvalidInputNode).ok()?;
+        let mut point = point;
+        if !contains(node, point) {
+            return None;
+        }
+        loop {
+            let location = self.nodes[node.into()].layout.location;
+            point = Point { x: point.x - location.x, y: point.y - location.y };
+            match self.children[node.into()].iter().rev().find(|&&child| contains(child, point)) {
+                Some(&child) => node = child,
+                None => return Some(node),
+            }
+        }
+    }
+}
+
+/// The outcome of a call to [`TaffyTree::compute_layout_budgeted`]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutProgress {
+    /// The time budget ran out before layout was complete. Call [`TaffyTree::compute_layout_budgeted`] again (typically
+    /// on the next frame) to continue. Until then the tree holds the layouts from the last complete layout, although
+    /// the unrounded layouts of some nodes may already have been updated.
+    Paused {
+        /// The number of node layouts which were deferred when the budget ran out (a node may be sized several times
+        /// under different constraints). Layouts which would have been requested with the results of deferred ones are
+        /// not counted, so this is a lower bound on the work which remains.
+        remaining_nodes: usize,
+    },
+    /// Layout is complete and can be read with [`TaffyTree::layout`]
+    Complete,
+}
+
+/// The checkpointed state of a layout being computed by [`TaffyTree::compute_layout_budgeted`]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+struct BudgetedLayout {
+    /// The root of the layout
+    root: NodeId,
+    /// The space available to the root
+    available_space: Size<AvailableSpace>,
+    /// The number of calls which have been paused so far
+    paused_calls: usize,
+    /// The number of paused calls after which the layout is completed regardless of the budget. This guarantees that
+    /// the layout completes even if each call evicts as many results from the cache as it adds.
+    max_paused_calls: usize,
+}
+
+/// The deadline of a call to [`TaffyTree::compute_layout_budgeted`]
+///
+/// Once the deadline has passed (and at least one node has been laid out by the call, so that every call makes
+/// progress), the layout of any node which is not in the cache is deferred: a placeholder is returned instead, and the
+/// layouts of its ancestors, which depend on the placeholder, are not cached. Everything else that was computed is
+/// cached, and so is reused by the next call.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone, Copy)]
-pub(crate) struct TaffyConfig {
-    /// Whether to round layout values
-    pub(crate) use_rounding: bool,
+struct LayoutDeadline {
+    /// The time after which layouts are deferred
+    deadline: std::time::Instant,
+    /// Whether a node has been laid out (and cached) by this call
+    has_computed: bool,
+    /// The number of layouts which have been deferred
+    deferred: usize,
+    /// The number of cache misses since a node was first laid out by this call, which determines when the clock is read
+    misses: usize,
+    /// Whether the clock has been found to be past the deadline
+    is_expired: bool,
 }
 
-impl Default for TaffyConfig {
-    fn default() -> Self {
-        Self { use_rounding: true }
+#[cfg(feature = "std")]
+impl LayoutDeadline {
+    /// The number of cache misses between reads of the clock, which is slow compared to laying out a single node
+    const CLOCK_CHECK_INTERVAL: usize = 16;
+
+    /// Create a deadline for a call to [`TaffyTree::compute_layout_budgeted`]
+    fn new(deadline: std::time::Instant) -> Self {
+        Self { deadline, has_computed: false, deferred: 0, misses: 0, is_expired: false }
+    }
+
+    /// Whether the layout of a node which is not in the cache should be deferred. The clock is read on the first cache
+    /// miss after a node has been laid out, and then on every [`Self::CLOCK_CHECK_INTERVAL`]th miss.
+    fn should_defer(&mut self) -> bool {
+        if !self.has_computed {
+            return false;
+        }
+        if !self.is_expired && self.misses % Self::CLOCK_CHECK_INTERVAL == 0 {
+            self.is_expired = std::time::Instant::now() >= self.deadline;
+        }
+        self.misses += 1;
+        self.is_expired
     }
 }
 
+/// The size measurements of removed nodes, keyed by [`TaffyTree::layout_key`]. See
+/// [`TaffyTree::enable_cache_memoization`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+struct CacheMemo {
+    /// The measurements of each removed node which had a layout key
+    entries: HashMap<u64, Cache>,
+    /// Whether layout has been computed since the entries were stored, in which case they are discarded before any
+    /// more are stored
+    is_stale: bool,
+}
+
+/// A [`Style`] stored in a [`TaffyTree`] along with the bookkeeping required to share it between nodes
+#[derive(Debug, Clone, PartialEq)]
+struct StyleEntry {
+    /// The style itself, which is shared with any [`LayoutSnapshot`]s taken since it was last changed
+    style: Arc<Style>,
+    /// The number of nodes which currently use this style
+    node_count: usize,
+    /// Whether a [`StyleHandle`] to this style has been handed out (and not yet released)
+    is_shared: bool,
+    /// The nodes which were assigned this style with [`TaffyTree::set_node_style_handle`] and still use it. Allows
+    /// [`TaffyTree::set_shared_style`] to mark them as dirty without visiting every node of the tree.
+    users: Vec<DefaultKey>,
+}
+
 /// Layout information for a given [`Node`](crate::node::Node)
 ///
 /// Stored in a [`TaffyTree`].
 #[derive(Debug, Clone, PartialEq)]
 struct NodeData {
-    /// The layout strategy used by this node
-    pub(crate) style: Style,
+    /// The key of the node's style in [`TaffyTree::styles`]
+    pub(crate) style: DefaultKey,
 
     /// The always unrounded results of the layout computation. We must store this separately from the rounded
     /// layout to avoid errors from rounding already-rounded values. See <https://github.com/DioxusLabs/taffy/issues/501>.
@@ -99,18 +669,49 @@ struct NodeData {
 
     /// The cached results of the layout computation
     pub(crate) cache: Cache,
+
+    /// Whether the node has been modified (rather than only marked dirty because a descendant was) since it was last
+    /// laid out by its parent
+    pub(crate) needs_relayout: bool,
+
+    /// The version of the node's content set with [`TaffyTree::set_node_content_version`]
+    pub(crate) content_version: u64,
+
+    /// The hash of the node's context set with [`TaffyTree::set_content_hash`]
+    #[cfg(feature = "std")]
+    pub(crate) content_hash: Option<u64>,
+
+    /// The hash of everything which determines the node's size measurements. See [`TaffyTree::layout_key`].
+    #[cfg(feature = "std")]
+    pub(crate) layout_key: Option<u64>,
+
+    /// Statistics about the most recent placement of the node's grid items (if it is a grid container)
+    #[cfg(feature = "grid")]
+    pub(crate) grid_occupancy: Option<GridOccupancyStats>,
+
+    /// Whether the node takes part in layout. See [`TaffyTree::set_node_enabled`].
+    pub(crate) enabled: bool,
 }
 
 impl NodeData {
     /// Create the data for a new node
     #[must_use]
-    pub const fn new(style: Style) -> Self {
+    pub const fn new(style: DefaultKey) -> Self {
         Self {
             style,
             cache: Cache::new(),
             unrounded_layout: Layout::new(),
             final_layout: Layout::new(),
             has_context: false,
+            needs_relayout: false,
+            content_version: 0,
+            #[cfg(feature = "std")]
+            content_hash: None,
+            #[cfg(feature = "std")]
+            layout_key: None,
+            #[cfg(feature = "grid")]
+            grid_occupancy: None,
+            enabled: true,
         }
     }
 
@@ -119,10 +720,18 @@ impl NodeData {
     /// This clears any cached data and signals that the data must be recomputed.
     #[inline]
     pub fn mark_dirty(&mut self) {
-        self.cache.clear()
+        self.cache.clear();
+        #[cfg(feature = "std")]
+        {
+            self.layout_key = None;
+        }
     }
 }
 
+/// The maximum number of times each layout of a [`TaffyTree`] lays out the tree while nodes switch style variants. See
+/// [`TaffyTree::set_style_variants`].
+const MAX_STYLE_VARIANT_PASSES: usize = 4;
+
 /// An entire tree of UI nodes. The entry point to Taffy's high-level API.
 ///
 /// Allows you to build a tree of UI nodes, run Taffy's layout algorithms over that tree, and then access the resultant layout.]
@@ -131,9 +740,18 @@ pub struct TaffyTree<NodeContext = ()> {
     /// The [`NodeData`] for each node stored in this tree
     nodes: SlotMap<DefaultKey, NodeData>,
 
+    /// The styles of the nodes in this tree. Nodes with identical shared styles point to the same entry.
+    styles: SlotMap<DefaultKey, StyleEntry>,
+
     /// Functions/closures that compute the intrinsic size of leaf nodes
     node_context_data: SecondaryMap<DefaultKey, NodeContext>,
 
+    /// The keys of the nodes created by [`TaffyTree::reconcile_children`]
+    node_keys: SecondaryMap<DefaultKey, u64>,
+
+    /// The capacity for children of nodes created without any. See [`TaffyTree::with_capacity_and_children`].
+    children_per_node: usize,
+
     /// The children of each node
     ///
     /// The indexes in the outer vector correspond to the position of the parent [`NodeData`]
@@ -145,7 +763,123 @@ pub struct TaffyTree<NodeContext = ()> {
     parents: SlotMap<DefaultKey, Option<NodeId>>,
 
     /// Layout mode configuration
-    config: TaffyConfig,
+    config: LayoutConfig,
+
+    /// The maximum depth of the trees which layout is computed for. See [`TaffyTree::set_max_depth`].
+    max_depth: Option<usize>,
+
+    /// The root and available space of the most recent call to [`TaffyTree::compute_layout`]
+    last_layout: Option<(NodeId, Size<AvailableSpace>)>,
+
+    /// The leaves whose content version has changed since layout was last computed. See
+    /// [`TaffyTree::set_node_content_version`].
+    content_changes: Vec<NodeId>,
+
+    /// The nodes whose styles were changed by the commands being applied by [`TaffyTree::apply`], kept between calls so
+    /// that its storage is reused
+    changed_nodes: Vec<NodeId>,
+
+    /// The sizes forced with [`TaffyTree::set_node_forced_size`]
+    forced_sizes: SecondaryMap<DefaultKey, Size<f32>>,
+
+    /// The non-zero offsets set with [`TaffyTree::set_layout_offset`]
+    layout_offsets: SecondaryMap<DefaultKey, Point<f32>>,
+
+    /// The non-zero user data set with [`TaffyTree::set_user_data`]
+    user_data: SecondaryMap<DefaultKey, u64>,
+
+    /// The inputs and output of each node's most recent final layout, which unlike its cache entry survive the node
+    /// being marked as dirty. Used by [`TaffyTree::compute_dirty_layouts`] to lay the node out again without its parent.
+    last_final_layouts: SecondaryMap<DefaultKey, (LayoutInput, LayoutOutput)>,
+
+    /// The nodes whose parent has measured their size without fixing it (for example to find their flex basis), in
+    /// which case a change to their content can change their size even if the inputs of their final layout don't.
+    /// Never cleared, which at worst makes [`TaffyTree::compute_dirty_layouts`] lay out more than needed.
+    content_sized_nodes: SecondaryMap<DefaultKey, ()>,
+
+    /// The number of disabled children of each node which has any. The children of other nodes don't need to be
+    /// filtered during layout.
+    disabled_child_counts: SecondaryMap<DefaultKey, usize>,
+
+    /// The nodes skipped by the most recent layout because it exceeded the tree's [`LayoutLimits`]
+    skipped_nodes: SecondaryMap<DefaultKey, ()>,
+
+    /// The style variants set with [`TaffyTree::set_style_variants`], along with the index of the variant each node is
+    /// currently laid out with (if any)
+    style_variants: SecondaryMap<DefaultKey, (Vec<StyleVariant>, Option<usize>)>,
+
+    /// The state variants set with [`TaffyTree::set_variant_overlays`] and [`TaffyTree::set_active_variants`]
+    state_variants: SecondaryMap<DefaultKey, StateVariants>,
+
+    /// The styles that nodes are laid out with, for the nodes which aren't laid out with their own style: nodes with a
+    /// forced size, an active style variant or state variant overlay, or root percentages (see [`Dimension::RootPercent`]). The styles are
+    /// refreshed before each layout, as the node's own style may have been modified in place since.
+    layout_styles: SecondaryMap<DefaultKey, Style>,
+
+    /// The nodes whose own style may use root percentages (see [`Dimension::RootPercent`]), which are the only nodes
+    /// whose layout style depends on the root of the layout. Nodes are removed when their layout style is next refreshed
+    /// if their style no longer uses root percentages.
+    root_percent_nodes: SecondaryMap<DefaultKey, ()>,
+
+    /// The nodes visited by [`TaffyTree::refresh_layout_styles`], kept between calls so that its storage is reused
+    layout_style_candidates: Vec<DefaultKey>,
+
+    /// The descendants of the leaf being measured, which its [`MeasureTree`] can read, kept between measurements so that
+    /// its storage is reused
+    measured_descendants: Vec<DefaultKey>,
+
+    /// The layout in progress, if [`TaffyTree::compute_layout_budgeted`] ran out of time
+    #[cfg(feature = "std")]
+    budgeted_layout: Option<BudgetedLayout>,
+
+    /// The deadline of the call to [`TaffyTree::compute_layout_budgeted`] in progress, if any
+    #[cfg(feature = "std")]
+    layout_deadline: Option<LayoutDeadline>,
+
+    /// The measurements of removed nodes, while enabled. See [`TaffyTree::enable_cache_memoization`].
+    #[cfg(feature = "std")]
+    cache_memo: Option<CacheMemo>,
+
+    /// The statistics of the most recent layout, while enabled. See [`TaffyTree::enable_layout_stats`].
+    layout_stats: Option<LayoutStatsCollector>,
+
+    /// The progress of the most recent layout against the tree's limits, while set. See
+    /// [`TaffyTree::set_layout_limits`].
+    layout_limits: Option<LayoutLimitsState>,
+
+    /// The style returned by [`TaffyTree::default_style`]
+    default_style: Style,
+
+    /// Named styles which nodes can be created from. See [`TaffyTree::set_style_preset`].
+    #[cfg(feature = "std")]
+    style_presets: HashMap<String, Style>,
+
+    /// The brand included in the [`NodeId`]s of nodes created by this tree
+    #[cfg(feature = "node_id_brand")]
+    brand: u8,
+
+    /// The log of operations performed on this tree, while recording. See [`TaffyTree::start_recording`].
+    #[cfg(feature = "record")]
+    recorder: Option<Recorder>,
+
+    /// The seed of the order in which the next `debug_determinism` check lays out the nodes of the tree
+    #[cfg(feature = "debug_determinism")]
+    determinism_seed: u64,
+
+    /// Breaks the content of inline containers into lines, if set. See [`TaffyTree::set_inline_layout`].
+    #[cfg(feature = "inline_layout")]
+    inline_layout: InlineLayoutSlot<NodeContext>,
+}
+
+/// Returns the brand for a newly created [`TaffyTree`]
+///
+/// Brands cycle through `1..=255` (0 marks an unbranded [`NodeId`]), so ids from two trees created 255 trees apart
+/// are not told apart.
+#[cfg(feature = "node_id_brand")]
+fn next_tree_brand() -> u8 {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    static NEXT_BRAND: AtomicU32 = AtomicU32::new(0);
+    (NEXT_BRAND.fetch_add(1, Ordering::Relaxed) % 255 + 1) as u8
 }
 
 impl Default for TaffyTree {
@@ -154,56 +888,384 @@ impl Default for TaffyTree {
     }
 }
 
-/// Iterator that wraps a slice of nodes, lazily converting them to u64
-pub struct TaffyTreeChildIter<'a>(core::slice::Iter<'a, NodeId>);
-impl<'a> Iterator for TaffyTreeChildIter<'a> {
-    type Item = NodeId;
+/// An edit to a single property of a node's [`Style`], queued in a [`StyleCommandBuffer`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum StyleCommand {
+    /// Sets [`Style::display`]
+    Display(Display),
+    /// Sets [`Style::position`]
+    Position(Position),
+    /// Sets the width of [`Style::size`]
+    Width(Dimension),
+    /// Sets the height of [`Style::size`]
+    Height(Dimension),
+    /// Sets the width of [`Style::min_size`]
+    MinWidth(Dimension),
+    /// Sets the height of [`Style::min_size`]
+    MinHeight(Dimension),
+    /// Sets the width of [`Style::max_size`]
+    MaxWidth(Dimension),
+    /// Sets the height of [`Style::max_size`]
+    MaxHeight(Dimension),
+    /// Sets the left edge of [`Style::inset`]
+    InsetLeft(LengthPercentageAuto),
+    /// Sets the right edge of [`Style::inset`]
+    InsetRight(LengthPercentageAuto),
+    /// Sets the top edge of [`Style::inset`]
+    InsetTop(LengthPercentageAuto),
+    /// Sets the bottom edge of [`Style::inset`]
+    InsetBottom(LengthPercentageAuto),
+    /// Sets the left edge of [`Style::margin`]
+    MarginLeft(LengthPercentageAuto),
+    /// Sets the right edge of [`Style::margin`]
+    MarginRight(LengthPercentageAuto),
+    /// Sets the top edge of [`Style::margin`]
+    MarginTop(LengthPercentageAuto),
+    /// Sets the bottom edge of [`Style::margin`]
+    MarginBottom(LengthPercentageAuto),
+    /// Sets the left edge of [`Style::padding`]
+    PaddingLeft(LengthPercentage),
+    /// Sets the right edge of [`Style::padding`]
+    PaddingRight(LengthPercentage),
+    /// Sets the top edge of [`Style::padding`]
+    PaddingTop(LengthPercentage),
+    /// Sets the bottom edge of [`Style::padding`]
+    PaddingBottom(LengthPercentage),
+    /// Sets the left edge of [`Style::border`]
+    BorderLeft(LengthPercentage),
+    /// Sets the right edge of [`Style::border`]
+    BorderRight(LengthPercentage),
+    /// Sets the top edge of [`Style::border`]
+    BorderTop(LengthPercentage),
+    /// Sets the bottom edge of [`Style::border`]
+    BorderBottom(LengthPercentage),
+    /// Sets [`Style::aspect_ratio`]
+    AspectRatio(Option<f32>),
+    /// Sets the width of [`Style::gap`] (the gap between columns)
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    ColumnGap(LengthPercentage),
+    /// Sets the height of [`Style::gap`] (the gap between rows)
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    RowGap(LengthPercentage),
+    /// Sets [`Style::flex_basis`]
+    #[cfg(feature = "flexbox")]
+    FlexBasis(Dimension),
+    /// Sets [`Style::flex_grow`]
+    #[cfg(feature = "flexbox")]
+    FlexGrow(f32),
+    /// Sets [`Style::flex_shrink`]
+    #[cfg(feature = "flexbox")]
+    FlexShrink(f32),
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().copied()
+impl StyleCommand {
+    /// Sets the property of `style`, returning whether its value changed
+    pub fn apply_to(self, style: &mut Style) -> bool {
+        /// Sets `property` to `value`, returning whether its value changed
+        fn set<T: PartialEq>(property: &mut T, value: T) -> bool {
+            let changed = *property != value;
+            *property = value;
+            changed
+        }
+
+        match self {
+            StyleCommand::Display(value) => set(&mut style.display, value),
+            StyleCommand::Position(value) => set(&mut style.position, value),
+            StyleCommand::Width(value) => set(&mut style.size.width, value),
+            StyleCommand::Height(value) => set(&mut style.size.height, value),
+            StyleCommand::MinWidth(value) => set(&mut style.min_size.width, value),
+            StyleCommand::MinHeight(value) => set(&mut style.min_size.height, value),
+            StyleCommand::MaxWidth(value) => set(&mut style.max_size.width, value),
+            StyleCommand::MaxHeight(value) => set(&mut style.max_size.height, value),
+            StyleCommand::InsetLeft(value) => set(&mut style.inset.left, value),
+            StyleCommand::InsetRight(value) => set(&mut style.inset.right, value),
+            StyleCommand::InsetTop(value) => set(&mut style.inset.top, value),
+            StyleCommand::InsetBottom(value) => set(&mut style.inset.bottom, value),
+            StyleCommand::MarginLeft(value) => set(&mut style.margin.left, value),
+            StyleCommand::MarginRight(value) => set(&mut style.margin.right, value),
+            StyleCommand::MarginTop(value) => set(&mut style.margin.top, value),
+            StyleCommand::MarginBottom(value) => set(&mut style.margin.bottom, value),
+            StyleCommand::PaddingLeft(value) => set(&mut style.padding.left, value),
+            StyleCommand::PaddingRight(value) => set(&mut style.padding.right, value),
+            StyleCommand::PaddingTop(value) => set(&mut style.padding.top, value),
+            StyleCommand::PaddingBottom(value) => set(&mut style.padding.bottom, value),
+            StyleCommand::BorderLeft(value) => set(&mut style.border.left, value),
+            StyleCommand::BorderRight(value) => set(&mut style.border.right, value),
+            StyleCommand::BorderTop(value) => set(&mut style.border.top, value),
+            StyleCommand::BorderBottom(value) => set(&mut style.border.bottom, value),
+            StyleCommand::AspectRatio(value) => set(&mut style.aspect_ratio, value),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            StyleCommand::ColumnGap(value) => set(&mut style.gap.width, value),
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            StyleCommand::RowGap(value) => set(&mut style.gap.height, value),
+            #[cfg(feature = "flexbox")]
+            StyleCommand::FlexBasis(value) => set(&mut style.flex_basis, value),
+            #[cfg(feature = "flexbox")]
+            StyleCommand::FlexGrow(value) => set(&mut style.flex_grow, value),
+            #[cfg(feature = "flexbox")]
+            StyleCommand::FlexShrink(value) => set(&mut style.flex_shrink, value),
+        }
     }
 }
 
-// TraversePartialTree impl for TaffyTree
-impl<NodeContext> TraversePartialTree for TaffyTree<NodeContext> {
-    type ChildIter<'a> = TaffyTreeChildIter<'a> where Self: 'a;
+/// A property of a node's [`Style`] holding a length, which can be set with [`TaffyTree::set_length_fast`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthProperty {
+    /// The width of [`Style::size`]
+    Width,
+    /// The height of [`Style::size`]
+    Height,
+    /// The width of [`Style::min_size`]
+    MinWidth,
+    /// The height of [`Style::min_size`]
+    MinHeight,
+    /// The width of [`Style::max_size`]
+    MaxWidth,
+    /// The height of [`Style::max_size`]
+    MaxHeight,
+    /// The left edge of [`Style::inset`]
+    InsetLeft,
+    /// The right edge of [`Style::inset`]
+    InsetRight,
+    /// The top edge of [`Style::inset`]
+    InsetTop,
+    /// The bottom edge of [`Style::inset`]
+    InsetBottom,
+    /// The left edge of [`Style::margin`]
+    MarginLeft,
+    /// The right edge of [`Style::margin`]
+    MarginRight,
+    /// The top edge of [`Style::margin`]
+    MarginTop,
+    /// The bottom edge of [`Style::margin`]
+    MarginBottom,
+}
 
-    #[inline(always)]
-    fn child_ids(&self, parent_node_id: NodeId) -> Self::ChildIter<'_> {
-        TaffyTreeChildIter(self.children[parent_node_id.into()].iter())
+impl LengthProperty {
+    /// Returns the [`StyleCommand`] which sets the property to a length of `value`
+    fn command(self, value: f32) -> StyleCommand {
+        match self {
+            LengthProperty::Width => StyleCommand::Width(Dimension::Length(value)),
+            LengthProperty::Height => StyleCommand::Height(Dimension::Length(value)),
+            LengthProperty::MinWidth => StyleCommand::MinWidth(Dimension::Length(value)),
+            LengthProperty::MinHeight => StyleCommand::MinHeight(Dimension::Length(value)),
+            LengthProperty::MaxWidth => StyleCommand::MaxWidth(Dimension::Length(value)),
+            LengthProperty::MaxHeight => StyleCommand::MaxHeight(Dimension::Length(value)),
+            LengthProperty::InsetLeft => StyleCommand::InsetLeft(LengthPercentageAuto::Length(value)),
+            LengthProperty::InsetRight => StyleCommand::InsetRight(LengthPercentageAuto::Length(value)),
+            LengthProperty::InsetTop => StyleCommand::InsetTop(LengthPercentageAuto::Length(value)),
+            LengthProperty::InsetBottom => StyleCommand::InsetBottom(LengthPercentageAuto::Length(value)),
+            LengthProperty::MarginLeft => StyleCommand::MarginLeft(LengthPercentageAuto::Length(value)),
+            LengthProperty::MarginRight => StyleCommand::MarginRight(LengthPercentageAuto::Length(value)),
+            LengthProperty::MarginTop => StyleCommand::MarginTop(LengthPercentageAuto::Length(value)),
+            LengthProperty::MarginBottom => StyleCommand::MarginBottom(LengthPercentageAuto::Length(value)),
+        }
     }
 
-    #[inline(always)]
-    fn child_count(&self, parent_node_id: NodeId) -> usize {
-        self.children[parent_node_id.into()].len()
+    /// Returns whether changing the property can change the size of the node or of its ancestors
+    ///
+    /// A node's inset is only read by its parent when placing it: relatively positioned nodes are offset after they have
+    /// been sized, and absolutely positioned nodes don't contribute to the size of their parent.
+    fn affects_size(self) -> bool {
+        !matches!(
+            self,
+            LengthProperty::InsetLeft
+                | LengthProperty::InsetRight
+                | LengthProperty::InsetTop
+                | LengthProperty::InsetBottom
+        )
     }
+}
 
-    #[inline(always)]
-    fn get_child_id(&self, parent_node_id: NodeId, id: usize) -> NodeId {
-        self.children[parent_node_id.into()][id]
+/// The state variants of a node (such as hovered or pressed). See [`TaffyTree::set_variant_overlays`].
+#[derive(Debug, Clone, Default)]
+struct StateVariants {
+    /// The edits each variant makes to the node's style, indexed by the bit of the variant
+    overlays: Vec<Vec<StyleCommand>>,
+    /// The bitset of the active variants
+    active: u64,
+    /// The node's style with the overlays of the active variants applied, while any active variant has an overlay
+    merged: Option<Style>,
+    /// Whether the style which `merged` was built from (the node's own style, or its active style variant) may have
+    /// changed since, so that `merged` is only rebuilt when it may be stale
+    is_merged_stale: bool,
+}
+
+/// Returns a copy of `style` with the overlays of the variants whose bits are set in `active` applied, in order
+fn merge_variant_overlays(style: &Style, overlays: &[Vec<StyleCommand>], active: u64) -> Style {
+    let mut style = style.clone();
+    for (index, overlay) in overlays.iter().enumerate().take(64) {
+        if active & (1 << index) != 0 {
+            for command in overlay {
+                command.apply_to(&mut style);
+            }
+        }
     }
+    style
 }
 
-// TraverseTree impl for TaffyTree
-impl<NodeContext> TraverseTree for TaffyTree<NodeContext> {}
+/// Returns whether two styles only differ in their inset, which only affects where their node is placed (see
+/// [`LengthProperty::affects_size`])
+fn only_inset_differs(a: &Style, b: &Style) -> bool {
+    a.inset != b.inset && Style { inset: b.inset, ..a.clone() } == *b
+}
 
-// PrintTree impl for TaffyTree
-impl<NodeContext> PrintTree for TaffyTree<NodeContext> {
-    #[inline(always)]
-    fn get_debug_label(&self, node_id: NodeId) -> &'static str {
-        let node = &self.nodes[node_id.into()];
-        let display = node.style.display;
-        let num_children = self.child_count(node_id);
+/// A queue of edits to the styles of many nodes, which are applied together with [`TaffyTree::apply`]
+///
+/// Applying the edits as a batch rather than one at a time with [`TaffyTree::try_style_mut`] marks each node (and its
+/// ancestors) as dirty once, however many of its properties were changed, and doesn't mark nodes whose properties were
+/// set to their current values.
+#[derive(Debug, Clone, Default)]
+pub struct StyleCommandBuffer {
+    /// The queued edits, in the order in which they were pushed
+    commands: Vec<(NodeId, StyleCommand)>,
+}
 
-        match (num_children, display) {
-            (_, Display::None) => "NONE",
-            (0, _) => "LEAF",
+impl StyleCommandBuffer {
+    /// Creates an empty buffer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty buffer which can hold `capacity` edits before reallocating
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { commands: new_vec_with_capacity(capacity) }
+    }
+
+    /// Queues an edit to the style of `node`. Edits to the same property of the same node are applied in order, so the
+    /// last one wins.
+    pub fn push(&mut self, node: NodeId, command: StyleCommand) {
+        self.commands.push((node, command));
+    }
+
+    /// Returns the number of queued edits
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns whether no edits are queued
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Discards all of the queued edits, keeping the buffer's storage
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+/// Iterator that wraps a slice of nodes, lazily converting them to u64
+pub struct TaffyTreeChildIter<'a>(core::slice::Iter<'a, NodeId>);
+impl<'a> Iterator for TaffyTreeChildIter<'a> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().copied()
+    }
+}
+
+/// Iterator over the children of a node which take part in layout, skipping those disabled with
+/// [`TaffyTree::set_node_enabled`]
+pub(crate) struct TaffyViewChildIter<'a> {
+    /// The node's children
+    children: core::slice::Iter<'a, NodeId>,
+    /// The data of the nodes in the tree, used to check whether each child is enabled
+    nodes: &'a SlotMap<DefaultKey, NodeData>,
+}
+
+impl Iterator for TaffyViewChildIter<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let nodes = self.nodes;
+        self.children.by_ref().copied().find(|child| nodes[(*child).into()].enabled)
+    }
+}
+
+/// Iterator over the nodes of a subtree in depth-first pre-order, as returned by [`TaffyTree::iter_nodes_depth_first`]
+pub struct DepthFirstNodeIter<'a, NodeContext> {
+    /// The tree being traversed
+    tree: &'a TaffyTree<NodeContext>,
+    /// The nodes still to be visited, with the next node to be visited last
+    stack: Vec<NodeId>,
+}
+
+impl<NodeContext> Iterator for DepthFirstNodeIter<'_, NodeContext> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(self.tree.children[node.into()].iter().rev().copied());
+        Some(node)
+    }
+}
+
+/// Iterator over the nodes of a subtree in breadth-first order, as returned by [`TaffyTree::iter_nodes_breadth_first`]
+pub struct BreadthFirstNodeIter<'a, NodeContext> {
+    /// The tree being traversed
+    tree: &'a TaffyTree<NodeContext>,
+    /// The nodes which have been reached so far, in the order they are visited
+    queue: Vec<NodeId>,
+    /// The index in `queue` of the next node to be visited
+    next_index: usize,
+}
+
+impl<NodeContext> Iterator for BreadthFirstNodeIter<'_, NodeContext> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = *self.queue.get(self.next_index)?;
+        self.next_index += 1;
+        self.queue.extend(self.tree.children[node.into()].iter().copied());
+        Some(node)
+    }
+}
+
+// TraversePartialTree impl for TaffyTree
+impl<NodeContext> TraversePartialTree for TaffyTree<NodeContext> {
+    type ChildIter<'a> = TaffyTreeChildIter<'a> where Self: 'a;
+
+    #[inline(always)]
+    fn child_ids(&self, parent_node_id: NodeId) -> Self::ChildIter<'_> {
+        TaffyTreeChildIter(self.children[parent_node_id.into()].iter())
+    }
+
+    #[inline(always)]
+    fn child_count(&self, parent_node_id: NodeId) -> usize {
+        self.children[parent_node_id.into()].len()
+    }
+
+    #[inline(always)]
+    fn get_child_id(&self, parent_node_id: NodeId, id: usize) -> NodeId {
+        self.children[parent_node_id.into()][id]
+    }
+}
+
+// TraverseTree impl for TaffyTree
+impl<NodeContext> TraverseTree for TaffyTree<NodeContext> {}
+
+// PrintTree impl for TaffyTree
+impl<NodeContext> PrintTree for TaffyTree<NodeContext> {
+    #[inline(always)]
+    fn get_debug_label(&self, node_id: NodeId) -> &'static str {
+        let style = &self.styles[self.nodes[node_id.into()].style].style;
+        let display = style.display;
+        let num_children = self.child_count(node_id);
+
+        match (num_children, display) {
+            (_, Display::None) => "NONE",
+            #[cfg(feature = "inline_layout")]
+            (_, Display::Inline) => "INLINE",
+            (0, _) => "LEAF",
             #[cfg(feature = "block_layout")]
             (_, Display::Block) => "BLOCK",
             #[cfg(feature = "flexbox")]
             (_, Display::Flex) => {
                 use crate::FlexDirection;
-                match node.style.flex_direction {
+                match style.flex_direction {
                     FlexDirection::Row | FlexDirection::RowReverse => "FLEX ROW",
                     FlexDirection::Column | FlexDirection::ColumnReverse => "FLEX COL",
                 }
@@ -223,13 +1285,192 @@ impl<NodeContext> PrintTree for TaffyTree<NodeContext> {
     }
 }
 
+/// Read-only access to a [`TaffyTree`] from within a measure function. See
+/// [`TaffyTree::compute_layout_with_tree_measure`].
+///
+/// This allows a leaf to be measured using the styles and layouts of its descendants, such as the inline boxes within a
+/// text node (which can be stored as children of the text node that are excluded from layout with
+/// [`TaffyTree::set_node_enabled`]).
+///
+/// Only the node being measured and its descendants can be read, as the cached measurements of the node are not
+/// discarded when any other node changes. Every other node is treated as if it was not in the tree.
+#[derive(Clone, Copy)]
+pub struct MeasureTree<'a> {
+    /// The node being measured
+    node: NodeId,
+    /// The nodes of the tree
+    nodes: &'a SlotMap<DefaultKey, NodeData>,
+    /// The styles of the tree
+    styles: &'a SlotMap<DefaultKey, StyleEntry>,
+    /// The styles that nodes are laid out with, for the nodes which aren't laid out with their own style
+    layout_styles: &'a SecondaryMap<DefaultKey, Style>,
+    /// The children of each node
+    children: &'a SlotMap<DefaultKey, ChildrenVec<NodeId>>,
+    /// The descendants of the node being measured, sorted so that they can be searched
+    descendants: &'a [DefaultKey],
+}
+
+impl<'a> MeasureTree<'a> {
+    /// Whether `node` is the node being measured or one of its descendants
+    fn can_read(&self, node: NodeId) -> bool {
+        node == self.node || self.descendants.binary_search(&node.into()).is_ok()
+    }
+
+    /// Returns the style that `node` is laid out with (which may differ from its own style, such as when its size has
+    /// been forced with [`TaffyTree::set_node_forced_size`]), or `None` if it is not the measured node or one of its
+    /// descendants
+    pub fn style(&self, node: NodeId) -> Option<&'a Style> {
+        if !self.can_read(node) {
+            return None;
+        }
+        match self.layout_styles.get(node.into()) {
+            Some(layout_style) => Some(layout_style),
+            None => Some(&self.styles[self.nodes.get(node.into())?.style].style),
+        }
+    }
+
+    /// Returns the children of `node`, including those which are disabled, or an empty slice if it is not the measured
+    /// node or one of its descendants
+    pub fn children(&self, node: NodeId) -> &'a [NodeId] {
+        match self.children.get(node.into()) {
+            Some(children) if self.can_read(node) => children,
+            _ => &[],
+        }
+    }
+
+    /// Returns the unrounded layout of `node` from the most recent layout in which it was laid out, or `None` if it is
+    /// not the measured node or one of its descendants. Descendants which are being laid out by the current layout may
+    /// not have been given their new layout yet.
+    pub fn layout(&self, node: NodeId) -> Option<&'a Layout> {
+        if !self.can_read(node) {
+            return None;
+        }
+        self.nodes.get(node.into()).map(|node_data| &node_data.unrounded_layout)
+    }
+}
+
+/// A measure function which is given read-only access to the tree. Used by [`TaffyView`] for all measure functions,
+/// with those which don't use the tree adapted by [`ignore_measure_tree`].
+pub(crate) trait TreeMeasureFunction<NodeContext>:
+    FnMut(
+    Size<Option<f32>>,
+    Size<AvailableSpace>,
+    NodeId,
+    Option<&mut NodeContext>,
+    &Style,
+    MeasureTree<'_>,
+) -> MeasureOutput
+{
+}
+
+impl<NodeContext, F> TreeMeasureFunction<NodeContext> for F where
+    F: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+        MeasureTree<'_>,
+    ) -> MeasureOutput
+{
+}
+
+/// Adapts a measure function which doesn't use the [`MeasureTree`] to a [`TreeMeasureFunction`]
+fn ignore_measure_tree<NodeContext, MeasureFunction, Measured>(
+    mut measure_function: MeasureFunction,
+) -> impl TreeMeasureFunction<NodeContext>
+where
+    MeasureFunction:
+        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Measured,
+    Measured: Into<MeasureOutput>,
+{
+    move |known_dimensions, available_space, node, context, style, _| {
+        measure_function(known_dimensions, available_space, node, context, style).into()
+    }
+}
+
+/// Adapts a measure function which uses the [`MeasureTree`], and returns anything convertible to a [`MeasureOutput`], to
+/// a [`TreeMeasureFunction`]
+fn tree_measure_function<NodeContext, MeasureFunction, Measured>(
+    mut measure_function: MeasureFunction,
+) -> impl TreeMeasureFunction<NodeContext>
+where
+    MeasureFunction: FnMut(
+        Size<Option<f32>>,
+        Size<AvailableSpace>,
+        NodeId,
+        Option<&mut NodeContext>,
+        &Style,
+        MeasureTree<'_>,
+    ) -> Measured,
+    Measured: Into<MeasureOutput>,
+{
+    move |known_dimensions, available_space, node, context, style, tree| {
+        measure_function(known_dimensions, available_space, node, context, style, tree).into()
+    }
+}
+
+/// Breaks the content of inline containers (nodes with [`Display::Inline`]) into lines. See
+/// [`TaffyTree::set_inline_layout`].
+///
+/// This allows text to be laid out by a text layout library, with the nodes of inline widgets (such as images or
+/// buttons) placed within it. The text is typically stored in the container's node context, and the children are
+/// measured by Taffy and passed to the formatter as atoms to place on the lines.
+#[cfg(feature = "inline_layout")]
+pub trait InlineLayout<NodeContext> {
+    /// Breaks the content of the inline container `node_id` into lines: the content described by its context (such as
+    /// its text) along with its in-flow children (`input.atoms`, each of which is hidden unless it is placed on a line).
+    /// The container is sized to fit the returned lines.
+    fn layout_lines(
+        &mut self,
+        node_id: NodeId,
+        node_context: Option<&mut NodeContext>,
+        style: &Style,
+        input: InlineFormatInput<'_>,
+    ) -> Vec<LineBox>;
+}
+
+/// An [`InlineLayout`] stored on a [`TaffyTree`], which is cloned along with the tree
+#[cfg(feature = "inline_layout")]
+trait StoredInlineLayout<NodeContext>: InlineLayout<NodeContext> + Send + Sync {
+    /// Clones the formatter into a new box
+    fn clone_box(&self) -> Box<dyn StoredInlineLayout<NodeContext>>;
+}
+
+#[cfg(feature = "inline_layout")]
+impl<NodeContext, T> StoredInlineLayout<NodeContext> for T
+where
+    T: InlineLayout<NodeContext> + Clone + Send + Sync + 'static,
+{
+    fn clone_box(&self) -> Box<dyn StoredInlineLayout<NodeContext>> {
+        Box::new(self.clone())
+    }
+}
+
+/// The formatter set with [`TaffyTree::set_inline_layout`], if any
+#[cfg(feature = "inline_layout")]
+struct InlineLayoutSlot<NodeContext>(Option<Box<dyn StoredInlineLayout<NodeContext>>>);
+
+#[cfg(feature = "inline_layout")]
+impl<NodeContext> Clone for InlineLayoutSlot<NodeContext> {
+    fn clone(&self) -> Self {
+        Self(self.0.as_ref().map(|inline_layout| inline_layout.clone_box()))
+    }
+}
+
+#[cfg(feature = "inline_layout")]
+impl<NodeContext> core::fmt::Debug for InlineLayoutSlot<NodeContext> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
 /// View over the Taffy tree that holds the tree itself along with a reference to the context
 /// and implements LayoutTree. This allows the context to be stored outside of the TaffyTree struct
 /// which makes the lifetimes of the context much more flexible.
 pub(crate) struct TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
     /// A reference to the TaffyTree
     pub(crate) taffy: &'t mut TaffyTree<NodeContext>,
@@ -237,49 +1478,65 @@ where
     pub(crate) measure_function: MeasureFunction,
 }
 
+/// The view which the layout passes of [`TaffyTree::run_layout`] lay out a tree with
+type LayoutPassView<'t, 'm, NodeContext> = TaffyView<'t, NodeContext, &'m mut dyn TreeMeasureFunction<NodeContext>>;
+
+impl<'t, NodeContext, MeasureFunction> TaffyView<'t, NodeContext, MeasureFunction>
+where
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
+{
+    /// Creates a view which lays out `taffy`, measuring its leaves with `measure_function`
+    pub(crate) fn new(taffy: &'t mut TaffyTree<NodeContext>, measure_function: MeasureFunction) -> Self {
+        Self { taffy, measure_function }
+    }
+}
+
 // TraversePartialTree impl for TaffyView
 impl<'t, NodeContext, MeasureFunction> TraversePartialTree for TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
-    type ChildIter<'a> = TaffyTreeChildIter<'a> where Self: 'a;
+    type ChildIter<'a> = TaffyViewChildIter<'a> where Self: 'a;
 
     #[inline(always)]
     fn child_ids(&self, parent_node_id: NodeId) -> Self::ChildIter<'_> {
-        self.taffy.child_ids(parent_node_id)
+        TaffyViewChildIter { children: self.taffy.children[parent_node_id.into()].iter(), nodes: &self.taffy.nodes }
     }
 
     #[inline(always)]
     fn child_count(&self, parent_node_id: NodeId) -> usize {
-        self.taffy.child_count(parent_node_id)
+        if !self.taffy.disabled_child_counts.contains_key(parent_node_id.into()) {
+            return self.taffy.child_count(parent_node_id);
+        }
+        self.child_ids(parent_node_id).count()
     }
 
     #[inline(always)]
     fn get_child_id(&self, parent_node_id: NodeId, child_index: usize) -> NodeId {
-        self.taffy.get_child_id(parent_node_id, child_index)
+        if !self.taffy.disabled_child_counts.contains_key(parent_node_id.into()) {
+            return self.taffy.get_child_id(parent_node_id, child_index);
+        }
+        self.child_ids(parent_node_id).nth(child_index).expect("child index out of bounds")
     }
 }
 
 // TraverseTree impl for TaffyView
 impl<'t, NodeContext, MeasureFunction> TraverseTree for TaffyView<'t, NodeContext, MeasureFunction> where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>
+    MeasureFunction: TreeMeasureFunction<NodeContext>
 {
 }
 
 // LayoutPartialTree impl for TaffyView
 impl<'t, NodeContext, MeasureFunction> LayoutPartialTree for TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
     type CoreContainerStyle<'a> = &'a Style where Self : 'a;
     type CacheMut<'b> = &'b mut Cache where Self : 'b;
 
     #[inline(always)]
     fn get_core_container_style(&self, node_id: NodeId) -> Self::CoreContainerStyle<'_> {
-        &self.taffy.nodes[node_id.into()].style
+        self.taffy.layout_style(node_id)
     }
 
     #[inline(always)]
@@ -289,25 +1546,97 @@ where
 
     #[inline(always)]
     fn set_unrounded_layout(&mut self, node_id: NodeId, layout: &Layout) {
+        let offset = match self.taffy.layout_offsets.is_empty() {
+            true => Point::ZERO,
+            false => self.taffy.layout_offsets.get(node_id.into()).copied().unwrap_or(Point::ZERO),
+        };
+        let layout = &Layout { location: layout.location + offset, ..*layout };
+        #[cfg(feature = "fixed_point")]
+        let layout = &layout.quantized();
         self.taffy.nodes[node_id.into()].unrounded_layout = *layout;
     }
 
+    #[inline(always)]
+    fn get_layout_config(&self) -> LayoutConfig {
+        self.taffy.config
+    }
+
     #[inline(always)]
     fn compute_child_layout(&mut self, node: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        if let Some(collector) = &mut self.taffy.layout_stats {
+            collector.stats.node_visits += 1;
+        }
+        if let Some(state) = &mut self.taffy.layout_limits {
+            state.node_visits += 1;
+        }
+
+        // Styles may also have been modified in place with `try_style_mut`, so are checked again here
+        #[cfg(feature = "debug_checks")]
+        {
+            self.taffy.debug_check_node_style(node);
+            let problems = find_invalid_layout_inputs(&inputs);
+            if !problems.is_empty() {
+                panic!("Invalid layout inputs for node {}:\n  {}", self.taffy.node_path(node), problems.join("\n  "));
+            }
+        }
+
+        if inputs.run_mode == RunMode::ComputeSize && !inputs.known_dimensions.both_axis_defined() {
+            self.taffy.content_sized_nodes.insert(node.into(), ());
+        }
+
         // If RunMode is PerformHiddenLayout then this indicates that an ancestor node is `Display::None`
         // and thus that we should lay out this node using hidden layout regardless of it's own display style.
         if inputs.run_mode == RunMode::PerformHiddenLayout {
             debug_log!("HIDDEN");
+            self.taffy.last_final_layouts.remove(node.into());
             return compute_hidden_layout(self, node);
         }
 
+        // Past the deadline of a budgeted layout, nodes which are not in the cache are deferred to the next call.
+        // Otherwise the cache entries which the node's result overwrites are kept, to be restored if its layout depends
+        // on a deferred node.
+        #[cfg(feature = "std")]
+        let deadline_state = match self.taffy.layout_deadline.as_mut() {
+            Some(deadline) => {
+                #[cfg(feature = "fixed_point")]
+                let cache_inputs = inputs.quantized();
+                #[cfg(not(feature = "fixed_point"))]
+                let cache_inputs = inputs;
+                let node_data = &self.taffy.nodes[node.into()];
+                match node_data.cache.get_layout(&cache_inputs) {
+                    Some(_) => None,
+                    None if deadline.should_defer() => {
+                        deadline.deferred += 1;
+                        let size = inputs.known_dimensions.unwrap_or(node_data.unrounded_layout.size);
+                        return LayoutOutput::from_outer_size(size);
+                    }
+                    None => Some((deadline.deferred, node_data.cache.snapshot_slot(&cache_inputs))),
+                }
+            }
+            None => None,
+        };
+
         // We run the following wrapped in "compute_cached_layout", which will check the cache for an entry matching the node and inputs and:
         //   - Return that entry if exists
         //   - Else call the passed closure (below) to compute the result
         //
         // If there was no cache match and a new result needs to be computed then that result will be added to the cache
-        compute_cached_layout(self, node, inputs, |tree, node, inputs| {
-            let display_mode = tree.taffy.nodes[node.into()].style.display;
+        let mut is_cache_miss = false;
+        let output = compute_cached_layout(self, node, inputs, |tree, node, inputs| {
+            is_cache_miss = true;
+
+            // Once the layout has exceeded its limits, nodes which are not in the cache keep their previous size
+            if let Some(state) = &mut tree.taffy.layout_limits {
+                if state.is_exceeded() {
+                    if tree.taffy.skipped_nodes.insert(node.into(), ()).is_none() {
+                        state.skipped_nodes.push(node);
+                    }
+                    return LayoutOutput::from_outer_size(
+                        inputs.known_dimensions.unwrap_or(tree.taffy.nodes[node.into()].unrounded_layout.size),
+                    );
+                }
+            }
+            let display_mode = tree.taffy.layout_style(node).display;
             let has_children = tree.child_count(node) > 0;
 
             debug_log!(display_mode);
@@ -319,35 +1648,103 @@ where
                 inputs.sizing_mode
             );
 
+            #[cfg(feature = "std")]
+            let timer = tree.taffy.layout_stats.as_mut().map(LayoutStatsCollector::start_node);
+
             // Dispatch to a layout algorithm based on the node's display style and whether the node has children or not.
-            match (display_mode, has_children) {
-                (Display::None, _) => compute_hidden_layout(tree, node),
+            let (algorithm, output) = match (display_mode, has_children) {
+                (Display::None, _) => (LayoutAlgorithm::Hidden, compute_hidden_layout(tree, node)),
                 #[cfg(feature = "block_layout")]
-                (Display::Block, true) => compute_block_layout(tree, node, inputs),
+                (Display::Block, true) => (LayoutAlgorithm::Block, compute_block_layout(tree, node, inputs)),
                 #[cfg(feature = "flexbox")]
-                (Display::Flex, true) => compute_flexbox_layout(tree, node, inputs),
+                (Display::Flex, true) => (LayoutAlgorithm::Flexbox, compute_flexbox_layout(tree, node, inputs)),
                 #[cfg(feature = "grid")]
-                (Display::Grid, true) => compute_grid_layout(tree, node, inputs),
+                (Display::Grid, true) => (LayoutAlgorithm::Grid, compute_grid_layout(tree, node, inputs)),
+                #[cfg(feature = "inline_layout")]
+                (Display::Inline, _) => (LayoutAlgorithm::Inline, compute_inline_layout(tree, node, inputs)),
                 (_, false) => {
                     let node_key = node.into();
-                    let style = &tree.taffy.nodes[node_key].style;
+                    tree.taffy.collect_measured_descendants(node_key);
+                    let style = match tree.taffy.layout_styles.get(node_key) {
+                        Some(layout_style) => layout_style,
+                        None => &tree.taffy.styles[tree.taffy.nodes[node_key].style].style,
+                    };
                     let has_context = tree.taffy.nodes[node_key].has_context;
                     let node_context = has_context.then(|| tree.taffy.node_context_data.get_mut(node_key)).flatten();
+                    let measure_tree = MeasureTree {
+                        node,
+                        nodes: &tree.taffy.nodes,
+                        styles: &tree.taffy.styles,
+                        layout_styles: &tree.taffy.layout_styles,
+                        children: &tree.taffy.children,
+                        descendants: &tree.taffy.measured_descendants,
+                    };
+                    let mut measure_calls = 0;
                     let measure_function = |known_dimensions, available_space| {
-                        (tree.measure_function)(known_dimensions, available_space, node, node_context, style)
+                        measure_calls += 1;
+                        let measure_function = &mut tree.measure_function;
+                        measure_function(known_dimensions, available_space, node, node_context, style, measure_tree)
                     };
-                    compute_leaf_layout(inputs, style, measure_function)
+                    let output = compute_leaf_layout(inputs, style, measure_function);
+                    if let Some(collector) = &mut tree.taffy.layout_stats {
+                        collector.stats.measure_calls += measure_calls;
+                    }
+                    (LayoutAlgorithm::Leaf, output)
                 }
+            };
+
+            if let Some(collector) = &mut tree.taffy.layout_stats {
+                #[cfg(feature = "std")]
+                collector.finish_node(algorithm, timer.expect("stats were enabled when the node was started"));
+                #[cfg(not(feature = "std"))]
+                collector.finish_node(algorithm);
             }
-        })
+            output
+        });
+
+        if let Some(collector) = &mut self.taffy.layout_stats {
+            if is_cache_miss {
+                collector.stats.cache_misses += 1;
+            } else {
+                collector.stats.cache_hits += 1;
+            }
+        }
+
+        // The children of the node have already been checked, so an invalid output must have been produced by this node
+        #[cfg(feature = "debug_checks")]
+        {
+            let problems = find_invalid_layout_outputs(&output);
+            if !problems.is_empty() {
+                panic!(
+                    "Layout of node {} produced an invalid output from valid inputs {inputs:?}:\n  {}",
+                    self.taffy.node_path(node),
+                    problems.join("\n  ")
+                );
+            }
+        }
+
+        #[cfg(feature = "std")]
+        if let Some((deferred, snapshot)) = deadline_state {
+            let deadline = self.taffy.layout_deadline.as_mut().expect("the deadline is only cleared after layout");
+            if deadline.deferred > deferred {
+                self.taffy.nodes[node.into()].cache.restore_slot(snapshot);
+                return output;
+            }
+            deadline.has_computed = true;
+        }
+
+        if inputs.run_mode == RunMode::PerformLayout {
+            self.taffy.last_final_layouts.insert(node.into(), (inputs, output));
+            self.taffy.nodes[node.into()].needs_relayout = false;
+        }
+        output
     }
 }
 
 #[cfg(feature = "block_layout")]
 impl<'t, NodeContext, MeasureFunction> LayoutBlockContainer for TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
     type BlockContainerStyle<'a> = &'a Style where Self: 'a;
     type BlockItemStyle<'a> = &'a Style where Self: 'a;
@@ -366,48 +1763,79 @@ where
 #[cfg(feature = "flexbox")]
 impl<'t, NodeContext, MeasureFunction> LayoutFlexboxContainer for TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
     type FlexboxContainerStyle<'a> = &'a Style where Self: 'a;
     type FlexboxItemStyle<'a> = &'a Style where Self: 'a;
 
     #[inline(always)]
     fn get_flexbox_container_style(&self, node_id: NodeId) -> Self::FlexboxContainerStyle<'_> {
-        &self.taffy.nodes[node_id.into()].style
+        self.taffy.layout_style(node_id)
     }
 
     #[inline(always)]
     fn get_flexbox_child_style(&self, child_node_id: NodeId) -> Self::FlexboxItemStyle<'_> {
-        &self.taffy.nodes[child_node_id.into()].style
+        self.taffy.layout_style(child_node_id)
     }
 }
 
 #[cfg(feature = "grid")]
 impl<'t, NodeContext, MeasureFunction> LayoutGridContainer for TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
     type GridContainerStyle<'a> = &'a Style where Self: 'a;
     type GridItemStyle<'a> = &'a Style where Self: 'a;
 
     #[inline(always)]
     fn get_grid_container_style(&self, node_id: NodeId) -> Self::GridContainerStyle<'_> {
-        &self.taffy.nodes[node_id.into()].style
+        self.taffy.layout_style(node_id)
     }
 
     #[inline(always)]
     fn get_grid_child_style(&self, child_node_id: NodeId) -> Self::GridItemStyle<'_> {
-        &self.taffy.nodes[child_node_id.into()].style
+        self.taffy.layout_style(child_node_id)
+    }
+
+    #[inline(always)]
+    fn set_grid_occupancy_stats(&mut self, node_id: NodeId, stats: GridOccupancyStats) {
+        self.taffy.nodes[node_id.into()].grid_occupancy = Some(stats);
+    }
+
+    #[inline(always)]
+    fn add_grid_track_sizing_passes(&mut self, _node_id: NodeId, passes: usize) {
+        if let Some(collector) = &mut self.taffy.layout_stats {
+            collector.stats.grid_track_sizing_passes += passes;
+        }
+    }
+}
+
+// LayoutInlineContainer impl for TaffyView
+#[cfg(feature = "inline_layout")]
+impl<NodeContext, MeasureFunction> LayoutInlineContainer for TaffyView<'_, NodeContext, MeasureFunction>
+where
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
+{
+    fn format_inline_content(&mut self, node_id: NodeId, input: InlineFormatInput<'_>) -> Vec<LineBox> {
+        let inline_layout = match &mut self.taffy.inline_layout.0 {
+            Some(inline_layout) => inline_layout,
+            None => return break_atoms_into_lines(&input),
+        };
+        let node_key = node_id.into();
+        let style = match self.taffy.layout_styles.get(node_key) {
+            Some(layout_style) => layout_style,
+            None => &self.taffy.styles[self.taffy.nodes[node_key].style].style,
+        };
+        let has_context = self.taffy.nodes[node_key].has_context;
+        let node_context = has_context.then(|| self.taffy.node_context_data.get_mut(node_key)).flatten();
+        inline_layout.layout_lines(node_id, node_context, style, input)
     }
 }
 
 // RoundTree impl for TaffyView
 impl<'t, NodeContext, MeasureFunction> RoundTree for TaffyView<'t, NodeContext, MeasureFunction>
 where
-    MeasureFunction:
-        FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
+    MeasureFunction: TreeMeasureFunction<NodeContext>,
 {
     #[inline(always)]
     fn get_unrounded_layout(&self, node: NodeId) -> &Layout {
@@ -433,100 +1861,613 @@ impl<NodeContext> TaffyTree<NodeContext> {
     /// Creates a new [`TaffyTree`] that can store `capacity` nodes before reallocation
     #[must_use]
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_children(capacity, 0)
+    }
+
+    /// Creates a new [`TaffyTree`] that can store `capacity` nodes before reallocation, in which nodes created without
+    /// children (with [`TaffyTree::new_leaf`] or [`TaffyTree::new_leaf_with_context`]) can have `children_per_node`
+    /// children added to them before their list of children reallocates
+    #[must_use]
+    pub fn with_capacity_and_children(capacity: usize, children_per_node: usize) -> Self {
         TaffyTree {
             // TODO: make this method const upstream,
             // so constructors here can be const
             nodes: SlotMap::with_capacity(capacity),
+            styles: SlotMap::with_capacity(capacity),
             children: SlotMap::with_capacity(capacity),
             parents: SlotMap::with_capacity(capacity),
             node_context_data: SecondaryMap::with_capacity(capacity),
-            config: TaffyConfig::default(),
+            node_keys: SecondaryMap::new(),
+            children_per_node,
+            config: LayoutConfig::default(),
+            max_depth: None,
+            last_layout: None,
+            content_changes: Vec::new(),
+            changed_nodes: Vec::new(),
+            forced_sizes: SecondaryMap::new(),
+            layout_offsets: SecondaryMap::new(),
+            user_data: SecondaryMap::new(),
+            last_final_layouts: SecondaryMap::new(),
+            content_sized_nodes: SecondaryMap::new(),
+            disabled_child_counts: SecondaryMap::new(),
+            skipped_nodes: SecondaryMap::new(),
+            style_variants: SecondaryMap::new(),
+            state_variants: SecondaryMap::new(),
+            layout_styles: SecondaryMap::new(),
+            root_percent_nodes: SecondaryMap::new(),
+            layout_style_candidates: Vec::new(),
+            measured_descendants: Vec::new(),
+            #[cfg(feature = "std")]
+            budgeted_layout: None,
+            #[cfg(feature = "std")]
+            layout_deadline: None,
+            #[cfg(feature = "std")]
+            cache_memo: None,
+            layout_stats: None,
+            layout_limits: None,
+            default_style: Style::DEFAULT,
+            #[cfg(feature = "std")]
+            style_presets: HashMap::new(),
+            #[cfg(feature = "node_id_brand")]
+            brand: next_tree_brand(),
+            #[cfg(feature = "record")]
+            recorder: None,
+            #[cfg(feature = "debug_determinism")]
+            determinism_seed: 0x9E37_79B9_7F4A_7C15,
+            #[cfg(feature = "inline_layout")]
+            inline_layout: InlineLayoutSlot(None),
         }
     }
 
+    /// Reserves capacity for at least `additional` more nodes to be created before the tree reallocates
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+        self.styles.reserve(additional);
+        self.children.reserve(additional);
+        self.parents.reserve(additional);
+    }
+
     /// Enable rounding of layout values. Rounding is enabled by default.
     pub fn enable_rounding(&mut self) {
         self.config.use_rounding = true;
+        #[cfg(feature = "record")]
+        self.record(Command::SetLayoutConfig(self.config));
     }
 
     /// Disable rounding of layout values. Rounding is enabled by default.
     pub fn disable_rounding(&mut self) {
         self.config.use_rounding = false;
+        #[cfg(feature = "record")]
+        self.record(Command::SetLayoutConfig(self.config));
     }
 
-    /// Creates and adds a new unattached leaf node to the tree, and returns the node of the new node
-    pub fn new_leaf(&mut self, layout: Style) -> TaffyResult<NodeId> {
-        let id = self.nodes.insert(NodeData::new(layout));
-        let _ = self.children.insert(new_vec_with_capacity(0));
-        let _ = self.parents.insert(None);
+    /// Set the configuration of behaviours where layout may deviate from the web specifications
+    ///
+    /// Changing any setting other than `use_rounding` marks every node in the tree as dirty.
+    pub fn set_layout_config(&mut self, config: LayoutConfig) {
+        let affects_layout = LayoutConfig { use_rounding: self.config.use_rounding, ..config } != self.config;
+        self.config = config;
+        if affects_layout {
+            for (_, node) in self.nodes.iter_mut() {
+                node.mark_dirty();
+                node.needs_relayout = true;
+            }
+        }
+        #[cfg(feature = "record")]
+        self.record(Command::SetLayoutConfig(config));
+    }
 
-        Ok(id.into())
+    /// Returns the configuration of behaviours where layout may deviate from the web specifications
+    pub fn layout_config(&self) -> LayoutConfig {
+        self.config
     }
 
-    /// Creates and adds a new unattached leaf node to the tree, and returns the [`NodeId`] of the new node
+    /// Set the maximum depth of the trees which layout is computed for, or `None` (the default) for no maximum
     ///
-    /// Creates and adds a new leaf node with a supplied context
-    pub fn new_leaf_with_context(&mut self, layout: Style, context: NodeContext) -> TaffyResult<NodeId> {
-        let mut data = NodeData::new(layout);
-        data.has_context = true;
-
-        let id = self.nodes.insert(data);
-        self.node_context_data.insert(id, context);
+    /// Layout recurses through the tree being laid out, so an extremely deep tree can overflow the stack. With a maximum
+    /// depth set, computing the layout of a node with a descendant more than `max_depth` levels below it instead returns
+    /// [`TaffyError::MaxDepthExceeded`] without computing any layout.
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        self.max_depth = max_depth;
+    }
 
-        let _ = self.children.insert(new_vec_with_capacity(0));
-        let _ = self.parents.insert(None);
+    /// Returns the maximum depth of the trees which layout is computed for. See [`TaffyTree::set_max_depth`].
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
 
-        Ok(id.into())
+    /// Enable reuse of the size measurements of removed nodes by identical nodes created to replace them
+    ///
+    /// This is intended for immediate-mode usage, where the tree is rebuilt from scratch (with [`TaffyTree::clear`] or
+    /// [`TaffyTree::remove`]) every frame and so would otherwise never have anything cached. Each node is given a
+    /// [layout key](Self::layout_key): a hash of its style, its [content hash](Self::set_content_hash), and the layout
+    /// keys of its children. When a node is removed its size measurements are kept under its layout key, and the next
+    /// time layout is computed they are copied into the cache of any new node with the same layout key.
+    ///
+    /// The final layouts of leaves are reused in the same way. Measurements are kept until layout is next computed
+    /// after they were stored (so every root of a tree which is rebuilt can reuse them), and are only reused with the
+    /// same constraints and [`LayoutConfig`] that they were computed with. The measure function is assumed to return
+    /// the same size for nodes with the same content hash.
+    #[cfg(feature = "std")]
+    pub fn enable_cache_memoization(&mut self) {
+        if self.cache_memo.is_none() {
+            self.cache_memo = Some(CacheMemo::default());
+        }
     }
 
-    /// Creates and adds a new node, which may have any number of `children`
-    pub fn new_with_children(&mut self, layout: Style, children: &[NodeId]) -> TaffyResult<NodeId> {
-        let id = NodeId::from(self.nodes.insert(NodeData::new(layout)));
+    /// Disable reuse of the size measurements of removed nodes, and discard any which have been kept. Cache
+    /// memoization is disabled by default. See [`TaffyTree::enable_cache_memoization`].
+    #[cfg(feature = "std")]
+    pub fn disable_cache_memoization(&mut self) {
+        self.cache_memo = None;
+    }
 
-        for child in children {
-            self.parents[(*child).into()] = Some(id);
+    /// Enable collection of [`LayoutStats`] for each layout, which are returned by
+    /// [`last_layout_stats`](Self::last_layout_stats). Collection is disabled by default, as timing each layout algorithm
+    /// has a small cost.
+    pub fn enable_layout_stats(&mut self) {
+        if self.layout_stats.is_none() {
+            self.layout_stats = Some(LayoutStatsCollector::default());
         }
+    }
 
-        let _ = self.children.insert(children.iter().copied().collect::<_>());
-        let _ = self.parents.insert(None);
+    /// Disable collection of [`LayoutStats`]. See [`TaffyTree::enable_layout_stats`].
+    pub fn disable_layout_stats(&mut self) {
+        self.layout_stats = None;
+    }
 
-        Ok(id)
+    /// Returns the statistics of the most recent call to compute layout (or `None` if collection is disabled). See
+    /// [`TaffyTree::enable_layout_stats`].
+    pub fn last_layout_stats(&self) -> Option<LayoutStats> {
+        self.layout_stats.as_ref().map(|collector| collector.stats)
     }
 
-    /// Drops all nodes in the tree
-    pub fn clear(&mut self) {
-        self.nodes.clear();
-        self.children.clear();
-        self.parents.clear();
+    /// Clears the statistics collected by previous layouts, if collection is enabled
+    fn reset_layout_stats(&mut self) {
+        if let Some(collector) = &mut self.layout_stats {
+            *collector = LayoutStatsCollector::default();
+        }
     }
 
-    /// Remove a specific node from the tree and drop it
+    /// Set limits on the work done by each layout, or `None` (the default) for no limits
     ///
-    /// Returns the id of the node removed.
-    pub fn remove(&mut self, node: NodeId) -> TaffyResult<NodeId> {
-        let key = node.into();
-        if let Some(parent) = self.parents[key] {
-            if let Some(children) = self.children.get_mut(parent.into()) {
-                children.retain(|f| *f != node);
-            }
+    /// Once a layout exceeds a limit, each node which it would lay out or measure again is skipped instead: the node
+    /// keeps the size it had in the previous layout (or the size imposed by its parent), and its descendants keep their
+    /// previous layouts. The layout is then completed quickly from the nodes' caches, so that a degenerate layout (such
+    /// as one from untrusted content) cannot stall the application. The skipped nodes are returned by
+    /// [`skipped_nodes`](Self::skipped_nodes), and are marked dirty (so are laid out properly) when layout is next
+    /// computed.
+    pub fn set_layout_limits(&mut self, limits: Option<LayoutLimits>) {
+        self.unmark_skipped_nodes();
+        self.layout_limits = limits.map(LayoutLimitsState::new);
+    }
+
+    /// Returns the limits on the work done by each layout. See [`TaffyTree::set_layout_limits`].
+    pub fn layout_limits(&self) -> Option<LayoutLimits> {
+        self.layout_limits.as_ref().map(|state| state.limits)
+    }
+
+    /// Returns the nodes which were skipped by the most recent layout because it exceeded the tree's limits (see
+    /// [`TaffyTree::set_layout_limits`]). The layouts of these nodes and their descendants are approximate.
+    pub fn skipped_nodes(&self) -> &[NodeId] {
+        match &self.layout_limits {
+            Some(state) => &state.skipped_nodes,
+            None => &[],
         }
+    }
 
-        // Remove "parent" references to a node when removing that node
-        if let Some(children) = self.children.get(key) {
-            for child in children.iter().copied() {
-                self.parents[child.into()] = None;
-            }
+    /// Marks the nodes skipped by the previous layout as dirty, so that their approximate layouts are not reused from
+    /// their caches, and starts counting the work done by a new layout against the tree's limits
+    fn reset_layout_limits(&mut self) {
+        self.unmark_skipped_nodes();
+        if let Some(state) = &mut self.layout_limits {
+            *state = LayoutLimitsState::new(state.limits);
         }
+    }
 
+    /// Marks the nodes skipped by the previous layout as dirty, and clears the list of skipped nodes
+    fn unmark_skipped_nodes(&mut self) {
+        let skipped_nodes = match &mut self.layout_limits {
+            Some(state) => core::mem::take(&mut state.skipped_nodes),
+            None => return,
+        };
+        for node in skipped_nodes {
+            // Skip nodes removed since the layout
+            if self.skipped_nodes.remove(node.into()).is_some() {
+                self.mark_dirty_unchecked(node);
+            }
+        }
+    }
+
+    /// Set a hash of the node's context, which must change whenever a change to the context could change the size that
+    /// the measure function returns for the node (for example the hash of a text node's text and font). Marks the node
+    /// as dirty if the hash changes.
+    ///
+    /// Nodes with a context are only given a [layout key](Self::layout_key) if they have a content hash.
+    #[cfg(feature = "std")]
+    pub fn set_content_hash(&mut self, node: NodeId, content_hash: Option<u64>) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if self.nodes[node.into()].content_hash != content_hash {
+            self.nodes[node.into()].content_hash = content_hash;
+            self.mark_dirty_unchecked(node);
+        }
+        Ok(())
+    }
+
+    /// Returns the key under which the node's size measurements are kept when cache memoization is enabled (see
+    /// [`TaffyTree::enable_cache_memoization`]), as of the last time layout was computed for it
+    ///
+    /// This is `None` if layout hasn't been computed with memoization enabled since the node was last modified, or if
+    /// the node or one of its descendants has a context but no [content hash](Self::set_content_hash).
+    #[cfg(feature = "std")]
+    pub fn layout_key(&self, node: NodeId) -> TaffyResult<Option<u64>> {
+        Ok(self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].layout_key)
+    }
+
+    /// Keeps the size measurements of a node which is being removed, if cache memoization is enabled. The final
+    /// layout of a leaf is also kept, as unlike that of a container it doesn't include the layouts of any descendants.
+    #[cfg(feature = "std")]
+    fn memoize_cache(cache_memo: &mut Option<CacheMemo>, data: &NodeData, is_leaf: bool) {
+        let (cache_memo, layout_key) = match (cache_memo.as_mut(), data.layout_key) {
+            (Some(cache_memo), Some(layout_key)) => (cache_memo, layout_key),
+            _ => return,
+        };
+        if cache_memo.is_stale {
+            cache_memo.entries.clear();
+            cache_memo.is_stale = false;
+        }
+        if !data.cache.is_empty() {
+            let cache = if is_leaf { data.cache.clone() } else { data.cache.measurements() };
+            cache_memo.entries.entry(layout_key).or_insert(cache);
+        }
+    }
+
+    /// Computes the layout keys of the nodes in the subtree rooted at `root` which are to be laid out, and seeds the
+    /// caches of new nodes with the memoized measurements of removed nodes with the same layout key
+    #[cfg(feature = "std")]
+    fn apply_cache_memo(&mut self, root: NodeId) {
+        // Content hashes are not recorded, so memoized measurements could not be reproduced when replaying
+        #[cfg(feature = "record")]
+        if self.is_recording() {
+            return;
+        }
+        let cache_memo = match self.cache_memo.as_mut() {
+            Some(cache_memo) => cache_memo,
+            None => return,
+        };
+
+        // Walk the subtree in post-order (so that children are keyed before their parents), skipping subtrees which
+        // already have a final layout as they will not be laid out again
+        let mut stack: Vec<(NodeId, bool)> = Vec::new();
+        stack.push((root, false));
+        while let Some((node, children_are_keyed)) = stack.pop() {
+            let data = &self.nodes[node.into()];
+            if !children_are_keyed {
+                if !(data.cache.has_final_layout() && data.layout_key.is_some()) {
+                    stack.push((node, true));
+                    stack.extend(self.children[node.into()].iter().map(|&child| (child, false)));
+                }
+                continue;
+            }
+
+            // A node whose measurements depend on a context which hasn't been hashed can't be keyed, and neither can
+            // its ancestors
+            let mut is_keyed = !(data.has_context && data.content_hash.is_none());
+            let mut hasher = LayoutHasher::new();
+            self.styles[data.style].style.layout_hash(&mut hasher);
+            data.has_context.layout_hash(&mut hasher);
+            hasher.write_u64(data.content_hash.unwrap_or(0));
+            hasher.write_usize(self.children[node.into()].len());
+            for child in self.children[node.into()].iter() {
+                match self.nodes[(*child).into()].layout_key {
+                    Some(child_key) => hasher.write_u64(child_key),
+                    None => is_keyed = false,
+                }
+            }
+            let layout_key = if is_keyed { Some(hasher.finish()) } else { None };
+
+            let data = &mut self.nodes[node.into()];
+            data.layout_key = layout_key;
+            if let Some(layout_key) = layout_key {
+                if data.cache.is_empty() {
+                    if let Some(cache) = cache_memo.entries.get(&layout_key) {
+                        data.cache = cache.clone();
+                    }
+                }
+            }
+        }
+        cache_memo.is_stale = true;
+    }
+
+    /// Set the style returned by [`TaffyTree::default_style`], such as the base style of a theme or the defaults of
+    /// another layout engine (`taffy.set_default_style(StyleDefaults::Yoga.style())`)
+    ///
+    /// This does not modify the styles of existing nodes.
+    pub fn set_default_style(&mut self, style: Style) {
+        self.default_style = style;
+    }
+
+    /// Returns the default style for nodes in this tree: the style set with
+    /// [`set_default_style`](Self::set_default_style), or otherwise [`Style::DEFAULT`]
+    ///
+    /// Use this as the base for styles (`Style { ..taffy.default_style() }`) to have unset properties take the tree's defaults.
+    pub fn default_style(&self) -> Style {
+        self.default_style.clone()
+    }
+
+    /// Adds a named style which nodes can be created from with [`new_leaf_from_preset`](Self::new_leaf_from_preset), or
+    /// replaces the style of the preset if one with the same name exists
+    ///
+    /// This does not modify the styles of nodes already created from the preset.
+    #[cfg(feature = "std")]
+    pub fn set_style_preset(&mut self, name: &str, style: Style) {
+        match self.style_presets.get_mut(name) {
+            Some(preset) => *preset = style,
+            None => {
+                self.style_presets.insert(String::from(name), style);
+            }
+        }
+    }
+
+    /// Returns the style of the named preset, if it exists
+    #[cfg(feature = "std")]
+    pub fn style_preset(&self, name: &str) -> Option<&Style> {
+        self.style_presets.get(name)
+    }
+
+    /// Removes the named preset, and returns its style if it existed
+    #[cfg(feature = "std")]
+    pub fn remove_style_preset(&mut self, name: &str) -> Option<Style> {
+        self.style_presets.remove(name)
+    }
+
+    /// Returns the style of the named preset (or the [`default_style`](Self::default_style) if `preset` is `None`)
+    /// with `delta` applied
+    #[cfg(feature = "std")]
+    fn preset_style(&self, preset: Option<&str>, delta: &StyleDelta) -> TaffyResult<Style> {
+        let mut style = match preset {
+            Some(name) => self
+                .style_presets
+                .get(name)
+                .cloned()
+                .ok_or_else(|| TaffyError::InvalidStylePreset(String::from(name)))?,
+            None => self.default_style(),
+        };
+        style.apply_delta(delta);
+        Ok(style)
+    }
+
+    /// Creates and adds a new unattached leaf node to the tree, and returns the node of the new node
+    pub fn new_leaf(&mut self, layout: Style) -> TaffyResult<NodeId> {
+        let style = self.insert_style(layout);
+        let id = self.nodes.insert(NodeData::new(style));
+        let _ = self.children.insert(new_children_vec_with_capacity(self.children_per_node));
+        let _ = self.parents.insert(None);
+        self.own_style_changed(id);
+
+        let id = self.node_id(id);
+        #[cfg(feature = "debug_checks")]
+        self.debug_check_node_style(id);
+        #[cfg(feature = "record")]
+        self.record(Command::NewLeaf(id, false));
+        Ok(id)
+    }
+
+    /// Creates and adds a new unattached leaf node whose style is the named preset (or the
+    /// [`default_style`](Self::default_style) if `preset` is `None`) with `delta` applied, and returns the node of the
+    /// new node
+    ///
+    /// This allows stored layouts to only contain the properties of each node which differ from its preset. Returns
+    /// [`TaffyError::InvalidStylePreset`] if the preset does not exist.
+    #[cfg(feature = "std")]
+    pub fn new_leaf_from_preset(&mut self, preset: Option<&str>, delta: &StyleDelta) -> TaffyResult<NodeId> {
+        let style = self.preset_style(preset, delta)?;
+        self.new_leaf(style)
+    }
+
+    /// Creates and adds a new unattached leaf node to the tree, and returns the [`NodeId`] of the new node
+    ///
+    /// Creates and adds a new leaf node with a supplied context
+    pub fn new_leaf_with_context(&mut self, layout: Style, context: NodeContext) -> TaffyResult<NodeId> {
+        let style = self.insert_style(layout);
+        let mut data = NodeData::new(style);
+        data.has_context = true;
+
+        let id = self.nodes.insert(data);
+        self.node_context_data.insert(id, context);
+
+        let _ = self.children.insert(new_children_vec_with_capacity(self.children_per_node));
+        let _ = self.parents.insert(None);
+        self.own_style_changed(id);
+
+        let id = self.node_id(id);
+        #[cfg(feature = "debug_checks")]
+        self.debug_check_node_style(id);
+        #[cfg(feature = "record")]
+        self.record(Command::NewLeaf(id, true));
+        Ok(id)
+    }
+
+    /// Creates and adds a new node, which may have any number of `children`
+    pub fn new_with_children(&mut self, layout: Style, children: &[NodeId]) -> TaffyResult<NodeId> {
+        let children = children
+            .iter()
+            .map(|&child| self.own_node(child, TaffyError::InvalidChildNode))
+            .collect::<TaffyResult<ChildrenVec<_>>>()?;
+        let style = self.insert_style(layout);
+        let key = self.nodes.insert(NodeData::new(style));
+        let id = self.node_id(key);
+
+        for child in &children {
+            self.set_parent_unchecked(*child, Some(id));
+        }
+
+        let _ = self.children.insert(children);
+        let _ = self.parents.insert(None);
+        self.own_style_changed(key);
+
+        #[cfg(feature = "debug_checks")]
+        self.debug_check_node_style(id);
+        #[cfg(feature = "record")]
+        self.record(Command::NewWithChildren(id));
+        Ok(id)
+    }
+
+    /// Creates and adds a new node, which may have any number of `children`, whose style is the named preset (or the
+    /// [`default_style`](Self::default_style) if `preset` is `None`) with `delta` applied
+    ///
+    /// See [`new_leaf_from_preset`](Self::new_leaf_from_preset).
+    #[cfg(feature = "std")]
+    pub fn new_with_children_from_preset(
+        &mut self,
+        preset: Option<&str>,
+        delta: &StyleDelta,
+        children: &[NodeId],
+    ) -> TaffyResult<NodeId> {
+        let style = self.preset_style(preset, delta)?;
+        self.new_with_children(style, children)
+    }
+
+    /// Creates many nodes at once from a flat description of a tree, and returns their [`NodeId`]s
+    ///
+    /// Node `i` is created with `styles[i]`, and is appended as a child of node `parents[i]` (an index into the same
+    /// slices) or left unattached if `parents[i]` is `None`. Children are attached in index order. This is much
+    /// faster than building a large tree one node at a time, as storage for all of the nodes is allocated up front.
+    ///
+    /// Returns an error without creating any nodes if `styles` and `parents` have different lengths, or if a node's
+    /// parent does not come before it (`parents[i] >= Some(i)`), which guarantees that the description cannot contain a
+    /// cycle.
+    pub fn build_from_slices(&mut self, styles: &[Style], parents: &[Option<usize>]) -> TaffyResult<Vec<NodeId>> {
+        if styles.len() != parents.len() {
+            return Err(TaffyError::SliceLengthMismatch { styles: styles.len(), parents: parents.len() });
+        }
+        let mut child_counts: Vec<usize> = core::iter::repeat(0).take(styles.len()).collect();
+        for (index, parent) in parents.iter().enumerate() {
+            if let Some(parent) = *parent {
+                if parent >= index {
+                    return Err(TaffyError::InvalidParentIndex { index, parent });
+                }
+                child_counts[parent] += 1;
+            }
+        }
+
+        self.nodes.reserve(styles.len());
+        self.styles.reserve(styles.len());
+        self.children.reserve(styles.len());
+        self.parents.reserve(styles.len());
+
+        let mut ids: Vec<NodeId> = new_vec_with_capacity(styles.len());
+        for ((style, parent), child_count) in styles.iter().zip(parents).zip(child_counts) {
+            let style = self.insert_style(style.clone());
+            let key = self.nodes.insert(NodeData::new(style));
+            let id = self.node_id(key);
+            let parent = parent.map(|parent| ids[parent]);
+            let _ = self.children.insert(new_children_vec_with_capacity(child_count));
+            let _ = self.parents.insert(parent);
+            if let Some(parent) = parent {
+                self.children[parent.into()].push(id);
+            }
+            self.own_style_changed(key);
+            #[cfg(feature = "debug_checks")]
+            self.debug_check_node_style(id);
+            #[cfg(feature = "record")]
+            {
+                self.record(Command::NewLeaf(id, false));
+                if let Some(parent) = parent {
+                    self.record(Command::AddChild(parent, id));
+                }
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Drops all nodes in the tree
+    ///
+    /// The storage allocated for nodes is retained, so rebuilding a tree of a similar size does not reallocate.
+    /// Shared styles created with [`create_shared_style`](Self::create_shared_style) remain valid, and style presets and
+    /// the default style are kept.
+    pub fn clear(&mut self) {
+        #[cfg(feature = "std")]
+        for (key, data) in self.nodes.iter() {
+            Self::memoize_cache(&mut self.cache_memo, data, self.children[key].is_empty());
+        }
+        self.nodes.clear();
+        self.children.clear();
+        self.parents.clear();
+        self.node_context_data.clear();
+        self.node_keys.clear();
+        self.content_changes.clear();
+        self.forced_sizes.clear();
+        self.layout_offsets.clear();
+        self.user_data.clear();
+        self.last_final_layouts.clear();
+        self.content_sized_nodes.clear();
+        self.disabled_child_counts.clear();
+        self.skipped_nodes.clear();
+        self.style_variants.clear();
+        self.state_variants.clear();
+        self.layout_styles.clear();
+        self.root_percent_nodes.clear();
+        self.last_layout = None;
+        self.styles.retain(|_, entry| {
+            entry.node_count = 0;
+            entry.users.clear();
+            entry.is_shared
+        });
+        #[cfg(feature = "record")]
+        self.record(Command::Clear);
+    }
+
+    /// Remove a specific node from the tree and drop it
+    ///
+    /// Returns the id of the node removed.
+    pub fn remove(&mut self, node: NodeId) -> TaffyResult<NodeId> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let key = node.into();
+        if let Some(parent) = self.parents[key] {
+            if let Some(children) = self.children.get_mut(parent.into()) {
+                children.retain(|f| *f != node);
+            }
+            self.set_parent_unchecked(node, None);
+        }
+
+        // Remove "parent" references to a node when removing that node
+        for index in 0..self.children.get(key).map_or(0, |children| children.len()) {
+            let child = self.children[key][index];
+            self.set_parent_unchecked(child, None);
+        }
+
+        #[cfg(feature = "std")]
+        let is_leaf = self.children.get(key).map_or(true, |children| children.is_empty());
         let _ = self.children.remove(key);
         let _ = self.parents.remove(key);
-        let _ = self.nodes.remove(key);
-
+        if let Some(data) = self.nodes.remove(key) {
+            #[cfg(feature = "std")]
+            Self::memoize_cache(&mut self.cache_memo, &data, is_leaf);
+            self.release_style(data.style, key);
+        }
+        self.forced_sizes.remove(key);
+        self.layout_offsets.remove(key);
+        self.user_data.remove(key);
+        self.last_final_layouts.remove(key);
+        self.content_sized_nodes.remove(key);
+        self.disabled_child_counts.remove(key);
+        self.skipped_nodes.remove(key);
+        self.style_variants.remove(key);
+        self.state_variants.remove(key);
+        self.layout_styles.remove(key);
+        self.root_percent_nodes.remove(key);
+        self.node_keys.remove(key);
+
+        #[cfg(feature = "record")]
+        self.record(Command::Remove(node));
         Ok(node)
     }
 
     /// Sets the context data associated with the node
     pub fn set_node_context(&mut self, node: NodeId, measure: Option<NodeContext>) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
         let key = node.into();
         if let Some(measure) = measure {
             self.nodes[key].has_context = true;
@@ -536,19 +2477,22 @@ impl<NodeContext> TaffyTree<NodeContext> {
             self.node_context_data.remove(key);
         }
 
-        self.mark_dirty(node)?;
+        self.mark_dirty_unchecked(node);
 
+        #[cfg(feature = "record")]
+        self.record(Command::SetHasContext(node, self.nodes[key].has_context));
         Ok(())
     }
 
     /// Gets a reference to the the context data associated with the node
     pub fn get_node_context(&self, node: NodeId) -> Option<&NodeContext> {
-        self.node_context_data.get(node.into())
+        self.node_context_data.get(self.own_node(node, TaffyError::InvalidInputNode).ok()?.into())
     }
 
     /// Gets a mutable reference to the the context data associated with the node
     pub fn get_node_context_mut(&mut self, node: NodeId) -> Option<&mut NodeContext> {
-        self.node_context_data.get_mut(node.into())
+        let key = self.own_node(node, TaffyError::InvalidInputNode).ok()?.into();
+        self.node_context_data.get_mut(key)
     }
 
     /// Gets mutable references to the the context data associated with the nodes. All keys must be valid and disjoint, otherwise None is returned.
@@ -556,22 +2500,34 @@ impl<NodeContext> TaffyTree<NodeContext> {
         &mut self,
         keys: [NodeId; N],
     ) -> Option<[&mut NodeContext; N]> {
+        if keys.iter().any(|&key| self.own_node(key, TaffyError::InvalidInputNode).is_err()) {
+            return None;
+        }
         self.node_context_data.get_disjoint_mut(keys.map(|k| k.into()))
     }
 
     /// Adds a `child` node under the supplied `parent`
     pub fn add_child(&mut self, parent: NodeId, child: NodeId) -> TaffyResult<()> {
+        let (parent, child) = (
+            self.own_node(parent, TaffyError::InvalidParentNode)?,
+            self.own_node(child, TaffyError::InvalidChildNode)?,
+        );
         let parent_key = parent.into();
-        let child_key = child.into();
-        self.parents[child_key] = Some(parent);
+        self.set_parent_unchecked(child, Some(parent));
         self.children[parent_key].push(child);
-        self.mark_dirty(parent)?;
+        self.mark_dirty_unchecked(parent);
 
+        #[cfg(feature = "record")]
+        self.record(Command::AddChild(parent, child));
         Ok(())
     }
 
     /// Inserts a `child` node at the given `child_index` under the supplied `parent`, shifting all children after it to the right.
     pub fn insert_child_at_index(&mut self, parent: NodeId, child_index: usize, child: NodeId) -> TaffyResult<()> {
+        let (parent, child) = (
+            self.own_node(parent, TaffyError::InvalidParentNode)?,
+            self.own_node(child, TaffyError::InvalidChildNode)?,
+        );
         let parent_key = parent.into();
 
         let child_count = self.children[parent_key].len();
@@ -579,33 +2535,40 @@ impl<NodeContext> TaffyTree<NodeContext> {
             return Err(TaffyError::ChildIndexOutOfBounds { parent, child_index, child_count });
         }
 
-        self.parents[child.into()] = Some(parent);
+        self.set_parent_unchecked(child, Some(parent));
         self.children[parent_key].insert(child_index, child);
-        self.mark_dirty(parent)?;
+        self.mark_dirty_unchecked(parent);
 
+        #[cfg(feature = "record")]
+        self.record(Command::InsertChildAtIndex(parent, child_index, child));
         Ok(())
     }
 
     /// Directly sets the `children` of the supplied `parent`
     pub fn set_children(&mut self, parent: NodeId, children: &[NodeId]) -> TaffyResult<()> {
+        let parent = self.own_node(parent, TaffyError::InvalidParentNode)?;
+        let children = children
+            .iter()
+            .map(|&child| self.own_node(child, TaffyError::InvalidChildNode))
+            .collect::<TaffyResult<ChildrenVec<_>>>()?;
         let parent_key = parent.into();
 
         // Remove node as parent from all its current children.
-        for child in &self.children[parent_key] {
-            self.parents[(*child).into()] = None;
+        for child in core::mem::take(&mut self.children[parent_key]) {
+            self.set_parent_unchecked(child, None);
         }
 
         // Build up relation node <-> child
-        for child in children {
-            self.parents[(*child).into()] = Some(parent);
+        for child in &children {
+            self.set_parent_unchecked(*child, Some(parent));
         }
 
-        let parent_children = &mut self.children[parent_key];
-        parent_children.clear();
-        children.iter().for_each(|child| parent_children.push(*child));
+        self.children[parent_key] = children;
 
-        self.mark_dirty(parent)?;
+        self.mark_dirty_unchecked(parent);
 
+        #[cfg(feature = "record")]
+        self.record(Command::SetChildren(parent));
         Ok(())
     }
 
@@ -613,6 +2576,10 @@ impl<NodeContext> TaffyTree<NodeContext> {
     ///
     /// The child is not removed from the tree entirely, it is simply no longer attached to its previous parent.
     pub fn remove_child(&mut self, parent: NodeId, child: NodeId) -> TaffyResult<NodeId> {
+        let (parent, child) = (
+            self.own_node(parent, TaffyError::InvalidParentNode)?,
+            self.own_node(child, TaffyError::InvalidChildNode)?,
+        );
         let index = self.children[parent.into()].iter().position(|n| *n == child).unwrap();
         self.remove_child_at_index(parent, index)
     }
@@ -621,6 +2588,7 @@ impl<NodeContext> TaffyTree<NodeContext> {
     ///
     /// The child is not removed from the tree entirely, it is simply no longer attached to its previous parent.
     pub fn remove_child_at_index(&mut self, parent: NodeId, child_index: usize) -> TaffyResult<NodeId> {
+        let parent = self.own_node(parent, TaffyError::InvalidParentNode)?;
         let parent_key = parent.into();
         let child_count = self.children[parent_key].len();
         if child_index >= child_count {
@@ -628,10 +2596,12 @@ impl<NodeContext> TaffyTree<NodeContext> {
         }
 
         let child = self.children[parent_key].remove(child_index);
-        self.parents[child.into()] = None;
+        self.set_parent_unchecked(child, None);
 
-        self.mark_dirty(parent)?;
+        self.mark_dirty_unchecked(parent);
 
+        #[cfg(feature = "record")]
+        self.record(Command::RemoveChildAtIndex(parent, child_index));
         Ok(child)
     }
 
@@ -644,6 +2614,10 @@ impl<NodeContext> TaffyTree<NodeContext> {
         child_index: usize,
         new_child: NodeId,
     ) -> TaffyResult<NodeId> {
+        let (parent, new_child) = (
+            self.own_node(parent, TaffyError::InvalidParentNode)?,
+            self.own_node(new_child, TaffyError::InvalidChildNode)?,
+        );
         let parent_key = parent.into();
 
         let child_count = self.children[parent_key].len();
@@ -651,17 +2625,158 @@ impl<NodeContext> TaffyTree<NodeContext> {
             return Err(TaffyError::ChildIndexOutOfBounds { parent, child_index, child_count });
         }
 
-        self.parents[new_child.into()] = Some(parent);
+        self.set_parent_unchecked(new_child, Some(parent));
         let old_child = core::mem::replace(&mut self.children[parent_key][child_index], new_child);
-        self.parents[old_child.into()] = None;
+        self.set_parent_unchecked(old_child, None);
 
-        self.mark_dirty(parent)?;
+        self.mark_dirty_unchecked(parent);
 
+        #[cfg(feature = "record")]
+        self.record(Command::ReplaceChildAtIndex(parent, child_index, new_child));
         Ok(old_child)
     }
 
+    /// Moves `node` (with all of its descendants) to the given `child_index` under `new_parent`, detaching it from its
+    /// current parent if it has one
+    ///
+    /// `child_index` is the index of `node` among the children of `new_parent` once it has been moved, so it may be at
+    /// most the number of children `new_parent` has without `node`. Moving a node within its current parent reorders it.
+    ///
+    /// Unlike removing the node and adding it again, only the ancestors of its old and new positions are marked dirty,
+    /// so the cached layouts of the moved subtree are kept.
+    pub fn reparent(&mut self, node: NodeId, new_parent: NodeId, child_index: usize) -> TaffyResult<()> {
+        let (node, new_parent) = (
+            self.own_node(node, TaffyError::InvalidChildNode)?,
+            self.own_node(new_parent, TaffyError::InvalidParentNode)?,
+        );
+
+        let mut ancestor = Some(new_parent);
+        while let Some(current) = ancestor {
+            if current == node {
+                return Err(TaffyError::ParentIsDescendant { node, parent: new_parent });
+            }
+            ancestor = self.parents[current.into()];
+        }
+
+        let old_position = self.parents[node.into()].map(|old_parent| {
+            (old_parent, self.children[old_parent.into()].iter().position(|&child| child == node).unwrap())
+        });
+        let is_same_parent = matches!(old_position, Some((old_parent, _)) if old_parent == new_parent);
+        let child_count = self.children[new_parent.into()].len() - usize::from(is_same_parent);
+        if child_index > child_count {
+            return Err(TaffyError::ChildIndexOutOfBounds { parent: new_parent, child_index, child_count });
+        }
+
+        if let Some((old_parent, old_index)) = old_position {
+            self.children[old_parent.into()].remove(old_index);
+            self.mark_dirty_unchecked(old_parent);
+            #[cfg(feature = "record")]
+            self.record(Command::RemoveChildAtIndex(old_parent, old_index));
+        }
+        self.set_parent_unchecked(node, Some(new_parent));
+        self.children[new_parent.into()].insert(child_index, node);
+        self.mark_dirty_unchecked(new_parent);
+
+        #[cfg(feature = "record")]
+        self.record(Command::InsertChildAtIndex(new_parent, child_index, node));
+        Ok(())
+    }
+
+    /// Updates the children of `parent` to be one node for each of `keys`, in order, and returns them
+    ///
+    /// A current child which was created for a key by a previous call is reused (keeping its style, context, descendants
+    /// and cached layouts) for the first occurrence of that key, and moved into position if needed. For every other key a
+    /// new leaf is created with the style returned by `create`. Current children which are not reused are removed from
+    /// the tree along with all of their descendants.
+    ///
+    /// `parent` is only marked dirty if its children change.
+    pub fn reconcile_children<K: Copy + Into<u64>>(
+        &mut self,
+        parent: NodeId,
+        keys: &[K],
+        mut create: impl FnMut(&K) -> Style,
+    ) -> TaffyResult<Vec<NodeId>> {
+        let parent = self.own_node(parent, TaffyError::InvalidParentNode)?;
+
+        // The current children which have keys, sorted by key and then by position. Each is taken when it is reused.
+        let mut keyed_children: Vec<(u64, usize, Option<NodeId>)> = self.children[parent.into()]
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &child)| Some((*self.node_keys.get(child.into())?, index, Some(child))))
+            .collect();
+        keyed_children.sort_unstable_by_key(|&(key, index, _)| (key, index));
+
+        let mut new_children: Vec<NodeId> = new_vec_with_capacity(keys.len());
+        for key in keys {
+            let key_value: u64 = (*key).into();
+            let first_match = keyed_children.partition_point(|&(other_key, _, _)| other_key < key_value);
+            let reused = keyed_children[first_match..]
+                .iter_mut()
+                .take_while(|(other_key, _, _)| *other_key == key_value)
+                .find_map(|(_, _, child)| child.take());
+            let child = match reused {
+                Some(child) => child,
+                None => {
+                    let child = self.new_leaf(create(key))?;
+                    self.node_keys.insert(child.into(), key_value);
+                    child
+                }
+            };
+            new_children.push(child);
+        }
+
+        if new_children[..] != self.children[parent.into()][..] {
+            let removed_children: Vec<NodeId> = self.children[parent.into()]
+                .iter()
+                .copied()
+                .filter(|&child| !self.node_keys.contains_key(child.into()))
+                .chain(keyed_children.iter().filter_map(|&(_, _, child)| child))
+                .collect();
+            self.set_children(parent, &new_children)?;
+            for child in removed_children {
+                self.remove_subtree(child)?;
+            }
+        }
+
+        Ok(new_children)
+    }
+
+    /// Sets the parent of `child`, keeping the disabled child counts of its old and new parents up to date
+    fn set_parent_unchecked(&mut self, child: NodeId, parent: Option<NodeId>) {
+        let old_parent = core::mem::replace(&mut self.parents[child.into()], parent);
+        if !self.nodes[child.into()].enabled {
+            if let Some(old_parent) = old_parent {
+                self.remove_disabled_child(old_parent);
+            }
+            if let Some(parent) = parent {
+                *self.disabled_child_counts.entry(parent.into()).unwrap().or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Decrements the disabled child count of `parent`, removing it once no child is disabled
+    fn remove_disabled_child(&mut self, parent: NodeId) {
+        let count = &mut self.disabled_child_counts[parent.into()];
+        *count -= 1;
+        if *count == 0 {
+            self.disabled_child_counts.remove(parent.into());
+        }
+    }
+
+    /// Removes `node` and all of its descendants from the tree
+    fn remove_subtree(&mut self, node: NodeId) -> TaffyResult<()> {
+        let mut stack = Vec::new();
+        stack.push(node);
+        while let Some(node) = stack.pop() {
+            stack.extend(self.children[node.into()].iter().copied());
+            self.remove(node)?;
+        }
+        Ok(())
+    }
+
     /// Returns the child node of the parent `node` at the provided `child_index`
     pub fn child_at_index(&self, parent: NodeId, child_index: usize) -> TaffyResult<NodeId> {
+        let parent = self.own_node(parent, TaffyError::InvalidParentNode)?;
         let parent_key = parent.into();
         let child_count = self.children[parent_key].len();
         if child_index >= child_count {
@@ -678,120 +2793,2014 @@ impl<NodeContext> TaffyTree<NodeContext> {
 
     /// Returns the `NodeId` of the parent node of the specified node (if it exists)
     ///
-    /// - Return None if the specified node has no parent
-    /// - Panics if the specified node does not exist
+    /// Returns None if the specified node has no parent or does not exist
     pub fn parent(&self, child_id: NodeId) -> Option<NodeId> {
-        self.parents[child_id.into()]
+        self.parents[self.own_node(child_id, TaffyError::InvalidInputNode).ok()?.into()]
     }
 
     /// Returns a list of children that belong to the parent node
     pub fn children(&self, parent: NodeId) -> TaffyResult<Vec<NodeId>> {
-        Ok(self.children[parent.into()].iter().copied().collect::<_>())
+        Ok(self.children[self.own_node(parent, TaffyError::InvalidParentNode)?.into()].iter().copied().collect::<_>())
     }
 
     /// Sets the [`Style`] of the provided `node`
+    ///
+    /// If the node was using a shared style then it stops doing so and gets its own copy of `style`.
     pub fn set_style(&mut self, node: NodeId, style: Style) -> TaffyResult<()> {
-        self.nodes[node.into()].style = style;
-        self.mark_dirty(node)?;
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let key = self.unshare_style(node.into());
+        self.styles[key].style = Arc::new(style);
+        self.own_style_changed(node.into());
+        self.mark_dirty_unchecked(node);
+        #[cfg(feature = "debug_checks")]
+        self.debug_check_node_style(node);
+        #[cfg(feature = "record")]
+        self.record(Command::SetStyle(node));
         Ok(())
     }
 
     /// Gets the [`Style`] of the provided `node`
     pub fn style(&self, node: NodeId) -> TaffyResult<&Style> {
-        Ok(&self.nodes[node.into()].style)
+        Ok(self.node_style(self.own_node(node, TaffyError::InvalidInputNode)?))
     }
 
     /// Gets the [`Style`] of the provided `node`
+    ///
+    /// If the node was using a shared style then it stops doing so and gets its own copy of that style, which is returned.
     pub fn try_style_mut(&mut self, node_id: NodeId) -> TaffyResult<&mut Style> {
-        self.nodes
-            .get_mut(node_id.into())
-            .ok_or(TaffyError::InvalidInputNode(node_id))
-            .map(|n: &mut NodeData| &mut n.style)
+        let node_id = self.own_node(node_id, TaffyError::InvalidInputNode)?;
+        let key = self.unshare_style(node_id.into());
+        // The edited style may use root percentages, which is checked when the node's layout style is next refreshed
+        self.own_style_changed(node_id.into());
+        self.root_percent_nodes.insert(node_id.into(), ());
+        #[cfg(feature = "record")]
+        if let Some(recorder) = &mut self.recorder {
+            if !recorder.pending_styles.contains(&node_id) {
+                recorder.pending_styles.push(node_id);
+            }
+        }
+        Ok(Arc::make_mut(&mut self.styles[key].style))
     }
 
-    /// Return this node layout relative to its parent
-    pub fn layout(&self, node: NodeId) -> TaffyResult<&Layout> {
-        if self.config.use_rounding {
-            Ok(&self.nodes[node.into()].final_layout)
-        } else {
-            Ok(&self.nodes[node.into()].unrounded_layout)
+    /// Edits the [`Style`] of the provided `node` in place with `edit`, which must return whether it changed the style.
+    /// Returns whether it did.
+    ///
+    /// Unlike [`try_style_mut`](Self::try_style_mut), the node is only marked as dirty, and a node using a shared style
+    /// only gets its own copy of that style, if `edit` changes it.
+    pub fn edit_style(&mut self, node: NodeId, edit: impl FnOnce(&mut Style) -> bool) -> TaffyResult<bool> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if !self.edit_style_unchecked(node, edit) {
+            return Ok(false);
         }
+        self.mark_dirty_unchecked(node);
+
+        #[cfg(feature = "debug_checks")]
+        self.debug_check_node_style(node);
+        #[cfg(feature = "record")]
+        self.record(Command::SetStyle(node));
+        Ok(true)
     }
 
-    /// Marks the layout computation of this node and its children as outdated
-    ///
-    /// Performs a recursive depth-first search up the tree until the root node is reached
+    /// Applies all of the edits queued in `commands` to the styles of their nodes, then clears the buffer so that it can
+    /// be reused
     ///
-    /// WARNING: this will stack-overflow if the tree contains a cycle
-    pub fn mark_dirty(&mut self, node: NodeId) -> TaffyResult<()> {
-        /// WARNING: this will stack-overflow if the tree contains a cycle
-        fn mark_dirty_recursive(
-            nodes: &mut SlotMap<DefaultKey, NodeData>,
-            parents: &SlotMap<DefaultKey, Option<NodeId>>,
-            node_key: DefaultKey,
-        ) {
-            nodes[node_key].mark_dirty();
+    /// The edits are applied atomically: if any of them refers to a node which is not in the tree then no style is
+    /// modified and the buffer is left unchanged. Each node whose style changed is marked as dirty once. Like
+    /// [`try_style_mut`](Self::try_style_mut), editing a node which uses a shared style gives it its own copy.
+    pub fn apply(&mut self, commands: &mut StyleCommandBuffer) -> TaffyResult<()> {
+        for (node, _) in commands.commands.iter_mut() {
+            *node = self.own_node(*node, TaffyError::InvalidInputNode)?;
+        }
 
-            if let Some(Some(node)) = parents.get(node_key) {
-                mark_dirty_recursive(nodes, parents, (*node).into());
+        let mut changed_nodes = core::mem::take(&mut self.changed_nodes);
+        for (node, command) in commands.commands.drain(..) {
+            if self.apply_style_command_unchecked(node, command) && changed_nodes.last() != Some(&node) {
+                changed_nodes.push(node);
             }
         }
 
-        mark_dirty_recursive(&mut self.nodes, &self.parents, node.into());
+        // Nodes may have been edited by several non-adjacent commands, but should only be marked dirty once
+        changed_nodes.sort_unstable_by_key(|node| u64::from(*node));
+        let mut previous = None;
+        for node in changed_nodes.drain(..) {
+            if previous == Some(node) {
+                continue;
+            }
+            previous = Some(node);
+            self.mark_dirty_unchecked(node);
+            #[cfg(feature = "debug_checks")]
+            self.debug_check_node_style(node);
+            #[cfg(feature = "record")]
+            self.record(Command::SetStyle(node));
+        }
+        self.changed_nodes = changed_nodes;
+        Ok(())
+    }
 
+    /// Sets a length property of a node's style, such as its width or an edge of its inset or margin
+    ///
+    /// This is a faster alternative to [`try_style_mut`](Self::try_style_mut) for properties which are changed often
+    /// (for example by animations): only the edited property is written, and nothing is marked as dirty if it already
+    /// had the value. Changing an edge of the inset, which only affects where the node is placed rather than the size of
+    /// it or of any of its ancestors, keeps the cached size measurements of the node and its ancestors, so the next
+    /// layout only places the node's ancestors again rather than measuring them.
+    pub fn set_length_fast(&mut self, node: NodeId, property: LengthProperty, value: f32) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if !self.apply_style_command_unchecked(node, property.command(value)) {
+            return Ok(());
+        }
+        match property.affects_size() {
+            true => self.mark_dirty_unchecked(node),
+            false => self.mark_position_dirty_unchecked(node),
+        }
+
+        #[cfg(feature = "debug_checks")]
+        self.debug_check_node_style(node);
+        #[cfg(feature = "record")]
+        self.record(Command::SetStyle(node));
         Ok(())
     }
 
-    /// Indicates whether the layout of this node (and its children) need to be recomputed
-    pub fn dirty(&self, node: NodeId) -> TaffyResult<bool> {
-        Ok(self.nodes[node.into()].cache.is_empty())
+    /// Applies an edit to the style of a node (which must be in this tree), returning whether the style changed
+    ///
+    /// A shared style is only copied if the edit changes it. The node is not marked as dirty.
+    fn apply_style_command_unchecked(&mut self, node: NodeId, command: StyleCommand) -> bool {
+        self.edit_style_unchecked(node, |style| command.apply_to(style))
     }
 
-    /// Updates the stored layout of the provided `node` and its children
-    pub fn compute_layout_with_measure<MeasureFunction>(
-        &mut self,
-        node_id: NodeId,
-        available_space: Size<AvailableSpace>,
-        measure_function: MeasureFunction,
-    ) -> Result<(), TaffyError>
-    where
-        MeasureFunction:
-            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Size<f32>,
-    {
-        let use_rounding = self.config.use_rounding;
-        let mut taffy_view = TaffyView { taffy: self, measure_function };
-        compute_root_layout(&mut taffy_view, node_id, available_space);
-        if use_rounding {
-            round_layout(&mut taffy_view, node_id);
+    /// Edits the style of a node (which must be in this tree) with `edit`, which returns whether it changed the style
+    ///
+    /// A shared style is only copied if the edit changes it. The node is not marked as dirty.
+    fn edit_style_unchecked(&mut self, node: NodeId, edit: impl FnOnce(&mut Style) -> bool) -> bool {
+        let key = node.into();
+        let style_key = self.nodes[key].style;
+        let entry = &self.styles[style_key];
+        let changed = if entry.node_count == 1 && !entry.is_shared {
+            edit(Arc::make_mut(&mut self.styles[style_key].style))
+        } else {
+            let mut style = Style::clone(&entry.style);
+            let changed = edit(&mut style);
+            if changed {
+                let style_key = self.unshare_style(key);
+                self.styles[style_key].style = Arc::new(style);
+            }
+            changed
+        };
+        if changed {
+            self.own_style_changed(key);
         }
-        Ok(())
+        changed
     }
 
-    /// Updates the stored layout of the provided `node` and its children
-    pub fn compute_layout(&mut self, node: NodeId, available_space: Size<AvailableSpace>) -> Result<(), TaffyError> {
-        self.compute_layout_with_measure(node, available_space, |_, _, _, _, _| Size::ZERO)
+    /// Creates a [`Style`] that can be shared by many nodes and returns a [`StyleHandle`] to it
+    ///
+    /// Nodes which share a style only store a single copy of it. Modifying the style of such a node through the per-node
+    /// API ([`set_style`](Self::set_style), [`try_style_mut`](Self::try_style_mut)) gives the node its own copy of the style
+    /// and leaves the other nodes unaffected.
+    pub fn create_shared_style(&mut self, style: Style) -> StyleHandle {
+        #[cfg(feature = "debug_checks")]
+        Self::debug_check_style(&style, || "new shared style".into());
+        let entry = StyleEntry { style: Arc::new(style), node_count: 0, is_shared: true, users: Vec::new() };
+        let handle = self.styles.insert(entry).into();
+        #[cfg(feature = "record")]
+        self.record(Command::CreateSharedStyle(handle));
+        handle
     }
 
-    /// Prints a debug representation of the tree's layout
-    #[cfg(feature = "std")]
-    pub fn print_tree(&mut self, root: NodeId) {
-        crate::util::print_tree(self, root)
+    /// Releases a [`StyleHandle`] created with [`create_shared_style`](Self::create_shared_style)
+    ///
+    /// Nodes currently using the style keep it, but the handle can no longer be assigned to nodes.
+    pub fn remove_shared_style(&mut self, handle: StyleHandle) -> TaffyResult<()> {
+        let key = handle.into();
+        let entry = self.styles.get_mut(key).filter(|e| e.is_shared).ok_or(TaffyError::InvalidStyleHandle(handle))?;
+        entry.is_shared = false;
+        if entry.node_count == 0 {
+            self.styles.remove(key);
+        }
+        #[cfg(feature = "record")]
+        self.record(Command::RemoveSharedStyle(handle));
+        Ok(())
     }
 
-    /// Returns an instance of LayoutTree representing the TaffyTree
-    #[cfg(test)]
-    pub(crate) fn as_layout_tree(&mut self) -> impl LayoutPartialTree + '_ {
-        TaffyView { taffy: self, measure_function: |_, _, _, _, _| Size::ZERO }
+    /// Gets the [`Style`] referred to by a [`StyleHandle`]
+    pub fn shared_style(&self, handle: StyleHandle) -> TaffyResult<&Style> {
+        self.styles
+            .get(handle.into())
+            .filter(|e| e.is_shared)
+            .map(|e| &*e.style)
+            .ok_or(TaffyError::InvalidStyleHandle(handle))
     }
-}
-
-#[cfg(test)]
-mod tests {
 
-    use super::*;
-    use crate::style::{Dimension, Display, FlexDirection};
-    use crate::style_helpers::*;
-    use crate::util::sys;
+    /// Sets the [`Style`] referred to by a [`StyleHandle`], updating every node that uses it
+    pub fn set_shared_style(&mut self, handle: StyleHandle, style: Style) -> TaffyResult<()> {
+        let key = handle.into();
+        let entry = self.styles.get_mut(key).filter(|e| e.is_shared).ok_or(TaffyError::InvalidStyleHandle(handle))?;
+        #[cfg(feature = "debug_checks")]
+        Self::debug_check_style(&style, || format!("shared style {handle:?}"));
+        entry.style = Arc::new(style);
+        for index in 0..self.styles[key].users.len() {
+            let node = NodeId::from(self.styles[key].users[index]);
+            self.own_style_changed(node.into());
+            self.mark_dirty_unchecked(node);
+        }
+        #[cfg(feature = "record")]
+        self.record(Command::SetSharedStyle(handle));
+        Ok(())
+    }
+
+    /// Makes the provided `node` use the shared style referred to by `handle`
+    ///
+    /// Neither the node nor the shared style are modified if either of them is invalid.
+    pub fn set_node_style_handle(&mut self, node: NodeId, handle: StyleHandle) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let key = handle.into();
+        if !matches!(self.styles.get(key), Some(entry) if entry.is_shared) {
+            return Err(TaffyError::InvalidStyleHandle(handle));
+        }
+        let node_key = node.into();
+        let old_key = self.nodes[node_key].style;
+        if old_key != key {
+            let entry = &mut self.styles[key];
+            entry.node_count += 1;
+            entry.users.push(node_key);
+            self.nodes[node_key].style = key;
+            self.release_style(old_key, node_key);
+            self.own_style_changed(node_key);
+            self.mark_dirty_unchecked(node);
+        }
+        #[cfg(feature = "record")]
+        self.record(Command::SetNodeStyleHandle(node, handle));
+        Ok(())
+    }
+
+    /// Returns the [`StyleHandle`] of the shared style used by the provided `node` (if it uses one)
+    pub fn node_style_handle(&self, node: NodeId) -> TaffyResult<Option<StyleHandle>> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let key = self.nodes[node.into()].style;
+        Ok(self.styles[key].is_shared.then_some(key.into()))
+    }
+
+    /// Returns an approximate breakdown of the memory used by this tree
+    pub fn memory_usage(&self) -> MemoryUsage {
+        use core::mem::size_of;
+
+        // Every slot in a SlotMap stores a u32 version alongside the value
+        fn slot_size<T>() -> usize {
+            size_of::<T>() + size_of::<u32>()
+        }
+
+        // Children stored inline (with the `smallvec` feature) are already counted in the size of the slot
+        #[cfg(feature = "smallvec")]
+        let children_heap_bytes: usize = self
+            .children
+            .iter()
+            .filter(|(_, children)| children.spilled())
+            .map(|(_, children)| children.capacity() * size_of::<NodeId>())
+            .sum();
+        #[cfg(not(feature = "smallvec"))]
+        let children_heap_bytes: usize =
+            self.children.iter().map(|(_, children)| children.capacity() * size_of::<NodeId>()).sum();
+        // Each style is stored in its own allocation, which may be shared by several entries (and snapshots) but is
+        // counted once for each entry
+        let style_heap_bytes: usize =
+            self.styles.iter().map(|(_, entry)| size_of::<Style>() + entry.style.heap_size()).sum();
+
+        MemoryUsage {
+            node_count: self.nodes.len(),
+            style_count: self.styles.len(),
+            node_bytes: self.nodes.capacity() * slot_size::<NodeData>()
+                + self.children.capacity() * slot_size::<ChildrenVec<NodeId>>()
+                + self.parents.capacity() * slot_size::<Option<NodeId>>()
+                + self.node_keys.len() * size_of::<(DefaultKey, u64)>()
+                + self.user_data.len() * size_of::<(DefaultKey, u64)>()
+                + self.layout_offsets.len() * size_of::<(DefaultKey, Point<f32>)>()
+                + self.last_final_layouts.len() * size_of::<(DefaultKey, (LayoutInput, LayoutOutput))>()
+                + self.disabled_child_counts.len() * size_of::<(DefaultKey, usize)>()
+                + children_heap_bytes,
+            style_bytes: self.styles.capacity() * slot_size::<StyleEntry>() + style_heap_bytes,
+            context_bytes: self.node_context_data.len() * size_of::<(DefaultKey, NodeContext)>(),
+        }
+    }
+
+    /// Takes an immutable snapshot of the layouts, styles and hierarchy of every node in the tree
+    ///
+    /// The snapshot can be sent to and shared between other threads, which can then query it while this tree continues
+    /// to be modified. It reflects the most recently computed layouts: call this after
+    /// [`compute_layout`](Self::compute_layout) has returned. Node contexts are not included.
+    ///
+    /// Styles are not copied: the snapshot shares them with the tree, and the tree only copies a style if it is changed
+    /// while a snapshot still uses it.
+    pub fn freeze(&self) -> LayoutSnapshot {
+        let use_rounding = self.config.use_rounding;
+        let mut nodes = SecondaryMap::with_capacity(self.nodes.len());
+        for (key, node) in self.nodes.iter() {
+            let layout = if use_rounding { node.final_layout } else { node.unrounded_layout };
+            let style = Arc::clone(&self.styles[node.style].style);
+            let user_data = self.user_data.get(key).copied().unwrap_or(0);
+            nodes.insert(key, SnapshotNode { style, layout, user_data });
+        }
+        LayoutSnapshot {
+            nodes,
+            children: self.children.clone(),
+            parents: self.parents.clone(),
+            #[cfg(feature = "node_id_brand")]
+            brand: self.brand,
+        }
+    }
+
+    /// Returns a warning for each style property in the subtree rooted at `root` which has no effect on layout, along
+    /// with the node whose style it was found in, in pre-order. See [`Style::validate`].
+    ///
+    /// Unlike [`Style::validate`], this also checks properties which only have an effect on the children of some kinds
+    /// of node (such as `flex_grow`, which only applies to the children of flex containers) against the node's parent.
+    /// Properties which have the value of the tree's [`default_style`](Self::default_style) are treated as unset.
+    pub fn validate_tree(&self, root: NodeId) -> TaffyResult<Vec<(NodeId, StyleWarning)>> {
+        let root = self.own_node(root, TaffyError::InvalidInputNode)?;
+        let mut warnings = Vec::new();
+        let mut stack = Vec::new();
+        stack.push(root);
+        while let Some(node) = stack.pop() {
+            let style = self.node_style(node);
+            let mut node_warnings = style.validate_with_defaults(&self.default_style);
+            if let Some(parent) = self.parents[node.into()] {
+                style.validate_as_child(self.node_style(parent).display, &self.default_style, &mut node_warnings);
+            }
+            warnings.extend(node_warnings.into_iter().map(|warning| (node, warning)));
+            stack.extend(self.children[node.into()].iter().rev().copied());
+        }
+        Ok(warnings)
+    }
+
+    /// Returns an iterator over the ids of `root` and all of its descendants in depth-first pre-order: each node is
+    /// followed by the subtree of its first child, then the subtree of its second child, and so on.
+    ///
+    /// The order depends only on the structure of the tree (not on how it was built or on the values of the ids), so is
+    /// stable for as long as the tree is unchanged.
+    pub fn iter_nodes_depth_first(&self, root: NodeId) -> TaffyResult<DepthFirstNodeIter<'_, NodeContext>> {
+        let root = self.own_node(root, TaffyError::InvalidInputNode)?;
+        let mut stack = new_vec_with_capacity(1);
+        stack.push(root);
+        Ok(DepthFirstNodeIter { tree: self, stack })
+    }
+
+    /// Returns an iterator over the ids of `root` and all of its descendants in breadth-first order: `root`, then its
+    /// children in order, then their children (the children of its first child, then of its second child...) and so on.
+    ///
+    /// The order depends only on the structure of the tree (not on how it was built or on the values of the ids), so is
+    /// stable for as long as the tree is unchanged.
+    pub fn iter_nodes_breadth_first(&self, root: NodeId) -> TaffyResult<BreadthFirstNodeIter<'_, NodeContext>> {
+        let root = self.own_node(root, TaffyError::InvalidInputNode)?;
+        let mut queue = new_vec_with_capacity(1);
+        queue.push(root);
+        Ok(BreadthFirstNodeIter { tree: self, queue, next_index: 0 })
+    }
+
+    /// Returns the style, layout, cache state and position in the tree of the provided `node` in a single call
+    pub fn inspect(&self, node_id: NodeId) -> TaffyResult<NodeInspection> {
+        let node_id = self.own_node(node_id, TaffyError::InvalidInputNode)?;
+        let node = self.nodes.get(node_id.into()).ok_or(TaffyError::InvalidInputNode(node_id))?;
+        let style = self.node_style(node_id);
+        let parent = self.parents[node_id.into()];
+
+        // Percentages resolve against the content box of the parent
+        let parent_size = match parent {
+            Some(parent) => {
+                let layout = &self.nodes[parent.into()].unrounded_layout;
+                (layout.size - layout.padding.sum_axes() - layout.border.sum_axes()).map(Some)
+            }
+            None => Size::NONE,
+        };
+        let percent_basis = self.config.percent_resolution_mode.basis(parent_size);
+        let inherited_style = self.computed_inherited_style(node_id)?;
+        let resolved_style = ResolvedStyle {
+            direction: inherited_style.direction,
+            writing_mode: inherited_style.writing_mode,
+            size: style.size.maybe_resolve(parent_size),
+            min_size: style.min_size.maybe_resolve(parent_size),
+            max_size: style.max_size.maybe_resolve(parent_size),
+            inset: Rect {
+                left: style.inset.left.maybe_resolve(parent_size.width),
+                right: style.inset.right.maybe_resolve(parent_size.width),
+                top: style.inset.top.maybe_resolve(parent_size.height),
+                bottom: style.inset.bottom.maybe_resolve(parent_size.height),
+            },
+            margin: style.margin.zip_size(percent_basis, |margin, basis| margin.maybe_resolve(basis)),
+            padding: style.padding.resolve_or_zero(percent_basis),
+            border: style.border.resolve_or_zero(parent_size.width),
+        };
+
+        let is_hidden = core::iter::successors(Some(node_id), |&node| self.parents[node.into()])
+            .any(|node| self.node_style(node).display == Display::None);
+        let algorithm = match (style.display, self.child_count(node_id) > 0) {
+            _ if is_hidden => LayoutAlgorithm::Hidden,
+            (Display::None, _) => LayoutAlgorithm::Hidden,
+            #[cfg(feature = "block_layout")]
+            (Display::Block, true) => LayoutAlgorithm::Block,
+            #[cfg(feature = "flexbox")]
+            (Display::Flex, true) => LayoutAlgorithm::Flexbox,
+            #[cfg(feature = "grid")]
+            (Display::Grid, true) => LayoutAlgorithm::Grid,
+            #[cfg(feature = "inline_layout")]
+            (Display::Inline, _) => LayoutAlgorithm::Inline,
+            (_, false) => LayoutAlgorithm::Leaf,
+        };
+
+        Ok(NodeInspection {
+            node: node_id,
+            label: self.get_debug_label(node_id),
+            parent,
+            children: self.children[node_id.into()].iter().copied().collect(),
+            style: style.clone(),
+            resolved_style,
+            layout: *self.layout(node_id)?,
+            unrounded_layout: node.unrounded_layout,
+            cache: CacheState {
+                is_dirty: node.cache.is_empty(),
+                has_final_layout: node.cache.has_final_layout(),
+                measure_entry_count: node.cache.measure_entry_count(),
+            },
+            algorithm,
+            #[cfg(feature = "grid")]
+            grid_occupancy: node.grid_occupancy,
+        })
+    }
+
+    /// Returns a reference to the style of a node
+    #[inline(always)]
+    fn node_style(&self, node: NodeId) -> &Style {
+        &self.styles[self.nodes[node.into()].style].style
+    }
+
+    /// Returns the style a node (which must be in this tree) is laid out with: its own style, unless its size has been
+    /// forced with [`TaffyTree::set_node_forced_size`], it has an active style variant (see
+    /// [`TaffyTree::set_style_variants`]) or it uses [`Dimension::RootPercent`]
+    #[inline(always)]
+    fn layout_style(&self, node: NodeId) -> &Style {
+        if !self.layout_styles.is_empty() {
+            if let Some(layout_style) = self.layout_styles.get(node.into()) {
+                return layout_style;
+            }
+        }
+        self.node_style(node)
+    }
+
+    /// Returns a copy of `style` which fixes the border-box size of its node to `size`
+    fn forced_style(style: &Style, size: Size<f32>) -> Style {
+        let size = Size { width: Dimension::length(size.width), height: Dimension::length(size.height) };
+        Style {
+            box_sizing: BoxSizing::BorderBox,
+            size,
+            min_size: size,
+            max_size: size,
+            aspect_ratio: None,
+            #[cfg(feature = "flexbox")]
+            flex_basis: Dimension::auto(),
+            #[cfg(feature = "flexbox")]
+            flex_grow: 0.0,
+            #[cfg(feature = "flexbox")]
+            flex_shrink: 0.0,
+            ..style.clone()
+        }
+    }
+
+    /// Records that the own style of the node stored under `key` may have changed: its state variant overlays are merged
+    /// into it again before the next layout, and it is added to [`TaffyTree::root_percent_nodes`] if it uses root
+    /// percentages
+    fn own_style_changed(&mut self, key: DefaultKey) {
+        self.state_style_changed(key);
+        if Self::uses_root_percent(&self.styles[self.nodes[key].style].style) {
+            self.root_percent_nodes.insert(key, ());
+        }
+    }
+
+    /// Records that the style which the state variant overlays of the node stored under `key` are merged into may have
+    /// changed, so that they are merged into it again before the next layout
+    fn state_style_changed(&mut self, key: DefaultKey) {
+        if let Some(state) = self.state_variants.get_mut(key) {
+            state.is_merged_stale = true;
+        }
+    }
+
+    /// Whether any of the properties of `style` which support [`Dimension::RootPercent`] use it
+    fn uses_root_percent(style: &Style) -> bool {
+        let is_root_percent = |dimension: Dimension| matches!(dimension, Dimension::RootPercent(_));
+        #[cfg(feature = "flexbox")]
+        if is_root_percent(style.flex_basis) {
+            return true;
+        }
+        [style.size, style.min_size, style.max_size]
+            .iter()
+            .any(|size| is_root_percent(size.width) || is_root_percent(size.height))
+    }
+
+    /// Collects the descendants of the leaf `node_key` (which are all disabled) into `measured_descendants`, sorted so
+    /// that the leaf's [`MeasureTree`] can check whether a node is one of them without walking up the tree
+    fn collect_measured_descendants(&mut self, node_key: DefaultKey) {
+        let descendants = &mut self.measured_descendants;
+        descendants.clear();
+        descendants.extend(self.children[node_key].iter().map(|&child| DefaultKey::from(child)));
+        let mut index = 0;
+        while index < descendants.len() {
+            let node_key = descendants[index];
+            descendants.extend(self.children[node_key].iter().map(|&child| DefaultKey::from(child)));
+            index += 1;
+        }
+        descendants.sort_unstable();
+    }
+
+    /// Recomputes the styles that nodes are laid out with from their current styles, their active style variants and
+    /// their forced sizes, marking the nodes whose layout style changed as dirty. Root percentages are resolved against
+    /// the size of `layout_root` (the root and available space of the layout about to be computed), and behave as
+    /// `auto` without one. The layout styles of nodes which use root percentages but are not in the subtree of
+    /// `layout_root` are left unchanged, as they are resolved against the root of their own layout.
+    ///
+    /// Only the nodes which may have a layout style are visited: those with a style variant, state variant or forced
+    /// size, those whose own style may use root percentages, and those which were given a layout style previously.
+    fn refresh_layout_styles(&mut self, layout_root: Option<(NodeId, Size<AvailableSpace>)>) {
+        let (root, available_size, root_size) = match layout_root {
+            Some((root, available_space)) => {
+                let available_size = available_space.into_options();
+                // The root's own root percentages are resolved against the available space, like percentages
+                let root_size = self.node_style(root).size.map(|dimension| match dimension {
+                    Dimension::RootPercent(percent) => Dimension::Percent(percent),
+                    other => other,
+                });
+                (Some(root), available_size, root_size.maybe_resolve(available_size).or(available_size))
+            }
+            None => (None, Size::NONE, Size::NONE),
+        };
+        // The nodes whose layout style changed (such as when the root is resized), and whether the change can affect
+        // their size
+        let mut changed_nodes = Vec::new();
+        let mut candidates = core::mem::take(&mut self.layout_style_candidates);
+        candidates.extend(self.style_variants.keys());
+        candidates.extend(self.state_variants.keys());
+        candidates.extend(self.forced_sizes.keys());
+        candidates.extend(self.root_percent_nodes.keys());
+        candidates.extend(self.layout_styles.keys());
+        candidates.sort_unstable();
+        candidates.dedup();
+        for &key in &candidates {
+            let node = &self.nodes[key];
+            let own_style: &Style = &self.styles[node.style].style;
+            if !self.root_percent_nodes.is_empty() && !Self::uses_root_percent(own_style) {
+                self.root_percent_nodes.remove(key);
+            }
+            let variant_style = match self.style_variants.is_empty() {
+                true => None,
+                false => self.style_variants.get(key).and_then(|(variants, active)| Some(&variants[(*active)?].style)),
+            };
+            let base_style = variant_style.unwrap_or(own_style);
+            // The merged style of the active state variants is stale if the style it was merged from has changed since
+            let state_style = match self.state_variants.is_empty() {
+                true => None,
+                false => match self.state_variants.get_mut(key) {
+                    Some(state) if state.merged.is_some() => {
+                        if state.is_merged_stale {
+                            state.merged = Some(merge_variant_overlays(base_style, &state.overlays, state.active));
+                            state.is_merged_stale = false;
+                        }
+                        state.merged.as_ref()
+                    }
+                    _ => None,
+                },
+            };
+            let forced_size = match self.forced_sizes.is_empty() {
+                true => None,
+                false => self.forced_sizes.get(key).copied(),
+            };
+            let style = state_style.unwrap_or(base_style);
+            let uses_root_percent = Self::uses_root_percent(style);
+            if uses_root_percent {
+                let mut ancestors = core::iter::successors(Some(NodeId::from(key)), |&node| self.parents[node.into()]);
+                if root.map_or(false, |root| !ancestors.any(|ancestor| ancestor == root)) {
+                    continue;
+                }
+            }
+            if variant_style.is_none() && state_style.is_none() && forced_size.is_none() && !uses_root_percent {
+                if let Some(previous_style) = self.layout_styles.remove(key) {
+                    if previous_style != *own_style {
+                        changed_nodes.push((NodeId::from(key), !only_inset_differs(&previous_style, own_style)));
+                    }
+                }
+                continue;
+            }
+
+            let mut layout_style = style.clone();
+            if uses_root_percent {
+                let basis = if Some(NodeId::from(key)) == root { available_size } else { root_size };
+                layout_style.size = layout_style.size.zip_map(basis, Dimension::resolve_root_percent);
+                layout_style.min_size = layout_style.min_size.zip_map(basis, Dimension::resolve_root_percent);
+                layout_style.max_size = layout_style.max_size.zip_map(basis, Dimension::resolve_root_percent);
+                #[cfg(feature = "flexbox")]
+                {
+                    let is_row =
+                        self.parents[key].map_or(true, |parent| self.node_style(parent).flex_direction.is_row());
+                    let main_basis = if is_row { basis.width } else { basis.height };
+                    layout_style.flex_basis = layout_style.flex_basis.resolve_root_percent(main_basis);
+                }
+            }
+            if let Some(size) = forced_size {
+                layout_style = Self::forced_style(&layout_style, size);
+            }
+            // Nodes without a layout style were laid out with their own style
+            let previous_style = self.layout_styles.get(key).unwrap_or(&self.styles[node.style].style);
+            if *previous_style != layout_style {
+                changed_nodes.push((NodeId::from(key), !only_inset_differs(previous_style, &layout_style)));
+                self.layout_styles.insert(key, layout_style);
+            }
+        }
+        candidates.clear();
+        self.layout_style_candidates = candidates;
+        for (node, affects_size) in changed_nodes {
+            match affects_size {
+                true => self.mark_dirty_unchecked(node),
+                false => self.mark_position_dirty_unchecked(node),
+            }
+        }
+    }
+
+    /// Describes the path from the root of the tree containing `node` down to `node`, as the id of the root followed by
+    /// the index and id of each node on the way
+    #[cfg(feature = "debug_checks")]
+    fn node_path(&self, node: NodeId) -> String {
+        let mut path = Vec::new();
+        let mut current = node;
+        while let Some(parent) = self.parents[current.into()] {
+            let index = self.children[parent.into()].iter().position(|&child| child == current).unwrap_or_default();
+            path.push(format!("[{index}] {current:?}"));
+            current = parent;
+        }
+        path.push(format!("{current:?}"));
+        path.reverse();
+        path.join(" > ")
+    }
+
+    /// Panics if `style` contains a value which layout cannot handle. `owner` describes what the style belongs to.
+    #[cfg(feature = "debug_checks")]
+    fn debug_check_style(style: &Style, owner: impl FnOnce() -> String) {
+        let problems = find_invalid_style_values(style);
+        if !problems.is_empty() {
+            panic!("Invalid style on {}:\n  {}", owner(), problems.join("\n  "));
+        }
+    }
+
+    /// Panics if the style of `node` contains a value which layout cannot handle
+    #[cfg(feature = "debug_checks")]
+    fn debug_check_node_style(&self, node: NodeId) {
+        Self::debug_check_style(self.node_style(node), || format!("node {}", self.node_path(node)));
+    }
+
+    /// Returns the [`NodeId`] of the node stored under `key`
+    #[cfg(feature = "node_id_brand")]
+    #[inline(always)]
+    fn node_id(&self, key: DefaultKey) -> NodeId {
+        let id = NodeId::from(key);
+        assert!(
+            (u64::from(id) & 0xffff_ffff) < super::node::MAX_BRANDED_SLOTS,
+            "A TaffyTree can hold at most {} nodes when the `node_id_brand` feature is enabled",
+            super::node::MAX_BRANDED_SLOTS - 1
+        );
+        id.with_brand(self.brand)
+    }
+
+    /// Returns the [`NodeId`] of the node stored under `key`
+    #[cfg(not(feature = "node_id_brand"))]
+    #[inline(always)]
+    fn node_id(&self, key: DefaultKey) -> NodeId {
+        NodeId::from(key)
+    }
+
+    /// Checks that `node` refers to a node in this tree, and returns it in the form in which this tree stores it
+    ///
+    /// Ids of removed nodes are rejected with the error built by `invalid`, even if their slot has since been reused by
+    /// a new node (the generation encoded in the id will not match). With the `node_id_brand` feature, ids created by a
+    /// different tree are also rejected and unbranded ids (for example ones built from a raw `u64`) are given this tree's brand.
+    #[inline(always)]
+    fn own_node(&self, node: NodeId, invalid: fn(NodeId) -> TaffyError) -> TaffyResult<NodeId> {
+        #[cfg(feature = "node_id_brand")]
+        let node = match node.brand() {
+            0 => node.with_brand(self.brand),
+            brand if brand != self.brand => return Err(TaffyError::ForeignNode(node)),
+            _ => node,
+        };
+        if !self.nodes.contains_key(node.into()) {
+            return Err(invalid(node));
+        }
+        Ok(node)
+    }
+
+    /// Checks that no node in the subtree rooted at `root` is more than [`max_depth`](Self::max_depth) levels below it
+    ///
+    /// The subtree is walked with an explicit stack, as the point of the check is that recursing through it may not be safe.
+    fn check_depth(&self, root: NodeId) -> TaffyResult<()> {
+        let max_depth = match self.max_depth {
+            Some(max_depth) => max_depth,
+            None => return Ok(()),
+        };
+        let mut stack = Vec::new();
+        stack.push((root, 0));
+        while let Some((node, depth)) = stack.pop() {
+            if depth > max_depth {
+                return Err(TaffyError::MaxDepthExceeded(node));
+            }
+            stack.extend(self.children[node.into()].iter().map(|&child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+
+    /// Stores a new style used by a single node and returns its key
+    fn insert_style(&mut self, style: Style) -> DefaultKey {
+        self.styles.insert(StyleEntry { style: Arc::new(style), node_count: 1, is_shared: false, users: Vec::new() })
+    }
+
+    /// Decrements the node count of a style which the node `node_key` has stopped using, removing the style if it is
+    /// no longer used
+    fn release_style(&mut self, key: DefaultKey, node_key: DefaultKey) {
+        let entry = &mut self.styles[key];
+        entry.node_count -= 1;
+        if let Some(index) = entry.users.iter().position(|&user| user == node_key) {
+            entry.users.swap_remove(index);
+        }
+        if entry.node_count == 0 && !entry.is_shared {
+            self.styles.remove(key);
+        }
+    }
+
+    /// Ensures that a node's style is not shared with any other node or handle and returns its key
+    ///
+    /// The node is given its own entry, which still points to the same [`Style`] until either entry is changed.
+    fn unshare_style(&mut self, node_key: DefaultKey) -> DefaultKey {
+        let key = self.nodes[node_key].style;
+        let entry = &self.styles[key];
+        if entry.node_count == 1 && !entry.is_shared {
+            return key;
+        }
+        let style = Arc::clone(&entry.style);
+        let new_key = self.styles.insert(StyleEntry { style, node_count: 1, is_shared: false, users: Vec::new() });
+        self.nodes[node_key].style = new_key;
+        self.release_style(key, node_key);
+        new_key
+    }
+
+    /// Resolves the [`Direction`] of the provided `node`, following [`Direction::Inherit`] up through its ancestors
+    ///
+    /// If neither the node nor any of its ancestors specify a direction then [`Direction::Ltr`] is returned.
+    pub fn computed_direction(&self, node_id: NodeId) -> TaffyResult<Direction> {
+        Ok(self.computed_inherited_style(node_id)?.direction)
+    }
+
+    /// Resolves the [`WritingMode`] of the provided `node`, following [`WritingMode::Inherit`] up through its ancestors
+    ///
+    /// If neither the node nor any of its ancestors specify a writing mode then [`WritingMode::HorizontalTb`] is
+    /// returned.
+    pub fn computed_writing_mode(&self, node_id: NodeId) -> TaffyResult<WritingMode> {
+        Ok(self.computed_inherited_style(node_id)?.writing_mode)
+    }
+
+    /// Resolves all of the inherited properties of the provided `node` by walking up through its ancestors until each
+    /// of them has been specified (or the root has been reached, in which case [`InheritedStyle::INITIAL`] is used)
+    ///
+    /// Use [`TaffyTree::cascade_inherited_styles`] to resolve the properties of a whole subtree instead of calling this
+    /// for each of its nodes.
+    pub fn computed_inherited_style(&self, node_id: NodeId) -> TaffyResult<InheritedStyle> {
+        let node_id = self.own_node(node_id, TaffyError::InvalidInputNode)?;
+        let (mut direction, mut writing_mode) = (None, None);
+        let mut current = Some(node_id);
+        while let Some(node) = current {
+            if direction.is_some() && writing_mode.is_some() {
+                break;
+            }
+            let style = self.node_style(node);
+            if direction.is_none() && style.direction != Direction::Inherit {
+                direction = Some(style.direction);
+            }
+            if writing_mode.is_none() && style.writing_mode != WritingMode::Inherit {
+                writing_mode = Some(style.writing_mode);
+            }
+            current = self.parents[node.into()];
+        }
+        Ok(InheritedStyle {
+            direction: direction.unwrap_or(InheritedStyle::INITIAL.direction),
+            writing_mode: writing_mode.unwrap_or(InheritedStyle::INITIAL.writing_mode),
+        })
+    }
+
+    /// Cascades the inherited properties of the provided `node` down to all of its descendants, calling `visit` with
+    /// the id, resolved properties and context of each node of the subtree (parents before their children)
+    ///
+    /// Each node is visited once, so this is cheaper than calling [`TaffyTree::computed_inherited_style`] for every
+    /// node. It is intended to be run before computing layout to hand the resolved properties to measure functions,
+    /// for example by storing them in the node contexts.
+    pub fn cascade_inherited_styles(
+        &mut self,
+        node_id: NodeId,
+        mut visit: impl FnMut(NodeId, InheritedStyle, Option<&mut NodeContext>),
+    ) -> TaffyResult<()> {
+        let node_id = self.own_node(node_id, TaffyError::InvalidInputNode)?;
+        let parent_style = match self.parents[node_id.into()] {
+            Some(parent) => self.computed_inherited_style(parent)?,
+            None => InheritedStyle::INITIAL,
+        };
+        let mut stack = Vec::new();
+        stack.push((node_id, parent_style));
+        while let Some((node, parent_style)) = stack.pop() {
+            let inherited_style = parent_style.cascade(self.node_style(node));
+            let has_context = self.nodes[node.into()].has_context;
+            let node_context = has_context.then(|| self.node_context_data.get_mut(node.into())).flatten();
+            visit(node, inherited_style, node_context);
+            stack.extend(self.children[node.into()].iter().rev().map(|&child| (child, inherited_style)));
+        }
+        Ok(())
+    }
+
+    /// Return this node layout relative to its parent
+    pub fn layout(&self, node: NodeId) -> TaffyResult<&Layout> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if self.config.use_rounding {
+            Ok(&self.nodes[node.into()].final_layout)
+        } else {
+            Ok(&self.nodes[node.into()].unrounded_layout)
+        }
+    }
+
+    /// Return this node's unrounded layout relative to its parent, regardless of whether rounding is enabled
+    pub fn unrounded_layout(&self, node: NodeId) -> TaffyResult<&Layout> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let node_data = self.nodes.get(node.into()).ok_or(TaffyError::InvalidInputNode(node))?;
+        Ok(&node_data.unrounded_layout)
+    }
+
+    /// Sets an offset which is added to the location of the node after it has been laid out, for animations (such as
+    /// shaking or sliding a node) which move a node without affecting the layout of anything else
+    ///
+    /// Nothing is marked as dirty: the stored layouts of the node and its descendants are moved immediately, and the
+    /// offset is added again whenever the node is laid out. The offset is included in the location returned by
+    /// [`layout`](Self::layout) (and so is taken into account by [`LayoutSnapshot::hit_test`]), but doesn't affect the
+    /// placement of the node's siblings, the content size of its parent or the baselines of its parent.
+    pub fn set_layout_offset(&mut self, node: NodeId, offset: Point<f32>) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let previous_offset = self.layout_offsets.get(node.into()).copied().unwrap_or(Point::ZERO);
+        if offset == previous_offset {
+            return Ok(());
+        }
+        match offset == Point::ZERO {
+            true => self.layout_offsets.remove(node.into()),
+            false => self.layout_offsets.insert(node.into(), offset),
+        };
+        let node_data = &mut self.nodes[node.into()];
+        node_data.unrounded_layout.location.x += offset.x - previous_offset.x;
+        node_data.unrounded_layout.location.y += offset.y - previous_offset.y;
+
+        if self.config.use_rounding {
+            // Layouts are rounded relative to the root of the most recent layout, so the ancestors above it are skipped
+            let layout_root = self.last_layout.map(|(root, _)| root);
+            let mut ancestor_locations: Vec<Point<f32>> = Vec::new();
+            let mut ancestor = self.parents[node.into()];
+            while let Some(current) = ancestor {
+                ancestor_locations.push(self.nodes[current.into()].unrounded_layout.location);
+                if Some(current) == layout_root {
+                    break;
+                }
+                ancestor = self.parents[current.into()];
+            }
+            ancestor_locations.reverse();
+            let mut taffy_view = TaffyView::new(self, ignore_measure_tree(|_, _, _, _, _| Size::ZERO));
+            round_subtree_layout(&mut taffy_view, node, &ancestor_locations);
+        }
+        Ok(())
+    }
+
+    /// Returns the offset added to the location of the node after it is laid out. See [`TaffyTree::set_layout_offset`].
+    pub fn layout_offset(&self, node: NodeId) -> TaffyResult<Point<f32>> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.layout_offsets.get(node.into()).copied().unwrap_or(Point::ZERO))
+    }
+
+    /// Attaches a value to the node which Taffy stores but never interprets (such as a hit slop or a pointer events flag
+    /// packed into 64 bits), so that renderers and input handling don't need to keep a separate map keyed by [`NodeId`]
+    ///
+    /// Changing it never marks the node as dirty. It is returned alongside the node's layout by
+    /// [`LayoutSnapshot::layout_with_user_data`]. New nodes have a user data of `0`.
+    pub fn set_user_data(&mut self, node: NodeId, user_data: u64) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        match user_data {
+            0 => self.user_data.remove(node.into()),
+            _ => self.user_data.insert(node.into(), user_data),
+        };
+        Ok(())
+    }
+
+    /// Returns the user data attached to the node. See [`TaffyTree::set_user_data`].
+    pub fn user_data(&self, node: NodeId) -> TaffyResult<u64> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.user_data.get(node.into()).copied().unwrap_or(0))
+    }
+
+    /// Marks the layout computation of this node and its children as outdated
+    ///
+    /// Performs a recursive depth-first search up the tree until the root node is reached
+    ///
+    /// WARNING: this will stack-overflow if the tree contains a cycle
+    pub fn mark_dirty(&mut self, node: NodeId) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        self.mark_dirty_unchecked(node);
+
+        #[cfg(feature = "record")]
+        self.record(Command::MarkDirty(node));
+        Ok(())
+    }
+
+    /// Marks the layout computation of a node (which must be in this tree) and its ancestors as outdated
+    fn mark_dirty_unchecked(&mut self, node: NodeId) {
+        /// WARNING: this will stack-overflow if the tree contains a cycle
+        fn mark_dirty_recursive(
+            nodes: &mut SlotMap<DefaultKey, NodeData>,
+            parents: &SlotMap<DefaultKey, Option<NodeId>>,
+            node_key: DefaultKey,
+        ) {
+            nodes[node_key].mark_dirty();
+
+            if let Some(Some(node)) = parents.get(node_key) {
+                mark_dirty_recursive(nodes, parents, (*node).into());
+            }
+        }
+
+        mark_dirty_recursive(&mut self.nodes, &self.parents, node.into());
+        self.nodes[node.into()].needs_relayout = true;
+    }
+
+    /// Marks a node (which must be in this tree) as needing to be placed again by its parent, after a change which affects
+    /// where it is placed but not the size of it or of any of its ancestors (such as a change to its inset)
+    ///
+    /// Only the cached final layouts of the node and its ancestors are discarded. Their cached size measurements remain
+    /// valid, so laying them out again doesn't measure any node.
+    fn mark_position_dirty_unchecked(&mut self, node: NodeId) {
+        let mut current = Some(node);
+        while let Some(ancestor) = current {
+            let node_data = &mut self.nodes[ancestor.into()];
+            node_data.cache.clear_final_layout();
+            #[cfg(feature = "std")]
+            {
+                node_data.layout_key = None;
+            }
+            current = self.parents[ancestor.into()];
+        }
+        self.nodes[node.into()].needs_relayout = true;
+    }
+
+    /// Sets the version of the node's content, which must change whenever a change to the node's context could change
+    /// the size that the measure function returns for it (for example a counter incremented on every edit of a text
+    /// node's text)
+    ///
+    /// Unlike [`mark_dirty`](Self::mark_dirty), changing the version of a leaf doesn't immediately discard the cached
+    /// layouts of its ancestors. Instead, the next layout first measures the leaf again under each of the constraints
+    /// that it has cached results for. Only if any of the sizes differ from the cached ones are the leaf and its ancestors
+    /// marked as dirty, so edits which don't change the size of a node (such as typing within a line of text which
+    /// doesn't wrap) don't cause the rest of the tree to be laid out again. Changing the version of a node with children
+    /// marks it as dirty, as its size doesn't depend on the measure function.
+    pub fn set_node_content_version(&mut self, node: NodeId, version: u64) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let node_data = &mut self.nodes[node.into()];
+        if node_data.content_version == version {
+            return Ok(());
+        }
+        node_data.content_version = version;
+        if !self.children[node.into()].is_empty() {
+            self.mark_dirty_unchecked(node);
+            #[cfg(feature = "record")]
+            self.record(Command::MarkDirty(node));
+        } else if !self.content_changes.contains(&node) {
+            self.content_changes.push(node);
+        }
+        Ok(())
+    }
+
+    /// Returns the version of the node's content. See [`TaffyTree::set_node_content_version`].
+    pub fn node_content_version(&self, node: NodeId) -> TaffyResult<u64> {
+        Ok(self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].content_version)
+    }
+
+    /// Measures each leaf whose content version has changed again under the constraints of its cached results, and marks
+    /// it (and so its ancestors) as dirty if any of the results differ. See [`TaffyTree::set_node_content_version`].
+    fn check_content_changes<MeasureFunction>(&mut self, measure_function: &mut MeasureFunction)
+    where
+        MeasureFunction: TreeMeasureFunction<NodeContext>,
+    {
+        let content_changes = core::mem::take(&mut self.content_changes);
+        if !content_changes.is_empty() {
+            self.refresh_layout_styles(self.last_layout);
+        }
+        for node in content_changes {
+            // Skip nodes removed (or given children) since their version was changed
+            match self.children.get(node.into()) {
+                Some(children) if children.is_empty() => {}
+                _ => continue,
+            }
+            let cache = self.nodes[node.into()].cache.clone();
+            self.nodes[node.into()].cache.clear();
+            let mut taffy_view = TaffyView::new(self, &mut *measure_function);
+            let is_unchanged = cache.entries().all(|(inputs, output)| {
+                let new_output = taffy_view.compute_child_layout(node, inputs);
+                match inputs.run_mode {
+                    RunMode::ComputeSize => new_output.size == output.size,
+                    RunMode::PerformLayout | RunMode::PerformHiddenLayout => new_output == output,
+                }
+            });
+            if !is_unchanged {
+                self.mark_dirty_unchecked(node);
+                #[cfg(feature = "record")]
+                self.record(Command::MarkDirty(node));
+            }
+        }
+    }
+
+    /// Sets whether a node takes part in layout
+    ///
+    /// Unlike [`Display::None`], which lays a node out as an empty box at its parent's origin, a disabled node and its
+    /// descendants are skipped entirely: their parent is laid out as if the node wasn't one of its children, and their
+    /// layouts (and cached results) keep the values from the last layout in which the node was enabled. This makes
+    /// toggling subtrees which are frequently shown and hidden (such as the pages of a tab view) cheap, as re-enabling a
+    /// node whose constraints haven't changed reuses its cached layout. Disabling the root of a layout has no effect.
+    ///
+    /// Only the node's parent is marked as dirty. Nodes are enabled by default.
+    pub fn set_node_enabled(&mut self, node: NodeId, enabled: bool) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let node_data = &mut self.nodes[node.into()];
+        if node_data.enabled == enabled {
+            return Ok(());
+        }
+        node_data.enabled = enabled;
+        if let Some(parent) = self.parents[node.into()] {
+            match enabled {
+                true => self.remove_disabled_child(parent),
+                false => *self.disabled_child_counts.entry(parent.into()).unwrap().or_insert(0) += 1,
+            }
+            self.mark_dirty_unchecked(parent);
+        }
+
+        #[cfg(feature = "record")]
+        self.record(Command::SetNodeEnabled(node, enabled));
+        Ok(())
+    }
+
+    /// Returns whether the node takes part in layout. See [`TaffyTree::set_node_enabled`].
+    pub fn is_node_enabled(&self, node: NodeId) -> TaffyResult<bool> {
+        Ok(self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].enabled)
+    }
+
+    /// Forces the node to be laid out with exactly the given border-box size, or stops doing so (`None`)
+    ///
+    /// While its size is forced, the node is laid out (and its parent lays out its other children) as if its style fixed
+    /// its size, with its flexibility and aspect ratio ignored. The style itself isn't modified: [`style`](Self::style)
+    /// still returns it, and it is used again once the size is no longer forced. This is useful to pin the size of a node
+    /// during an interaction such as dragging the divider of a split pane. Only the node and its ancestors are marked as
+    /// dirty, so the cached layouts of its descendants are reused wherever the new size doesn't affect them.
+    pub fn set_node_forced_size(&mut self, node: NodeId, size: Option<Size<f32>>) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if self.forced_sizes.get(node.into()).copied() == size {
+            return Ok(());
+        }
+        match size {
+            Some(size) => {
+                self.forced_sizes.insert(node.into(), size);
+            }
+            None => {
+                self.forced_sizes.remove(node.into());
+            }
+        }
+        self.mark_dirty_unchecked(node);
+
+        #[cfg(feature = "record")]
+        self.record(Command::SetNodeForcedSize(node, size));
+        Ok(())
+    }
+
+    /// Returns the size the node is forced to be laid out with, if any. See [`TaffyTree::set_node_forced_size`].
+    pub fn node_forced_size(&self, node: NodeId) -> TaffyResult<Option<Size<f32>>> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.forced_sizes.get(node.into()).copied())
+    }
+
+    /// Sets alternate styles which the node switches to depending on its size (similar to CSS container queries), or
+    /// removes them (with an empty list)
+    ///
+    /// After the node has been laid out, it is laid out with the style of the first variant whose condition matches its
+    /// border-box size, or its own style if none match. A node whose size switches it to a different variant is marked as
+    /// dirty and the tree is laid out again, up to a total of 4 passes, so that (for example) a panel can switch to a
+    /// column layout when it becomes narrow without the application having to run its own relayout loop. If a variant
+    /// changes the size of the node such that it no longer matches, the node's style may not match its final size.
+    ///
+    /// Variants are switched by every layout method (a budgeted layout switches them once it completes). The style of the
+    /// node itself isn't modified, and [`style`](Self::style) still returns it.
+    pub fn set_style_variants(&mut self, node: NodeId, variants: Vec<StyleVariant>) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if variants.is_empty() {
+            self.style_variants.remove(node.into());
+        } else {
+            // Start with the variant which matches the node's current size, to avoid a pass when they are set again
+            let size = self.nodes[node.into()].unrounded_layout.size;
+            let active = variants.iter().position(|variant| variant.condition.matches(size));
+            self.style_variants.insert(node.into(), (variants, active));
+        }
+        self.state_style_changed(node.into());
+        self.mark_dirty_unchecked(node);
+
+        #[cfg(feature = "record")]
+        self.record(Command::SetStyleVariants(node));
+        Ok(())
+    }
+
+    /// Returns the style variants of the node. See [`TaffyTree::set_style_variants`].
+    pub fn style_variants(&self, node: NodeId) -> TaffyResult<&[StyleVariant]> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.style_variants.get(node.into()).map_or(&[], |(variants, _)| variants.as_slice()))
+    }
+
+    /// Returns the index of the style variant the node is laid out with, if any. See
+    /// [`TaffyTree::set_style_variants`].
+    pub fn active_style_variant(&self, node: NodeId) -> TaffyResult<Option<usize>> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.style_variants.get(node.into()).and_then(|(_, active)| *active))
+    }
+
+    /// Sets the overlays of the node's state variants (such as hovered, pressed or disabled), as lists of edits to its
+    /// style. The overlay at index `i` is applied while bit `i` of the node's active variants is set (see
+    /// [`set_active_variants`](Self::set_active_variants)), so applications typically name the variants with constants
+    /// holding their bit. Only the first 64 overlays can be activated.
+    ///
+    /// The style of the node itself isn't modified: it remains the base style that the overlays are applied to, and
+    /// [`style`](Self::style) still returns it.
+    pub fn set_variant_overlays(&mut self, node: NodeId, overlays: Vec<Vec<StyleCommand>>) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        let mut state = self.state_variants.remove(node.into()).unwrap_or_default();
+        state.overlays = overlays;
+        self.update_state_variants(node, state);
+
+        #[cfg(feature = "record")]
+        self.record(Command::SetVariantOverlays(node));
+        Ok(())
+    }
+
+    /// Sets the bitset of the node's active state variants. See [`set_variant_overlays`](Self::set_variant_overlays).
+    ///
+    /// The overlays of the active variants are applied to the node's style in order (so later overlays take precedence)
+    /// once, when the active variants change, rather than on every layout. The node is only marked as dirty if the
+    /// merged style differs from the one it was laid out with, and if only its inset differs the cached size
+    /// measurements of the node and its ancestors are kept (as by [`set_length_fast`](Self::set_length_fast)).
+    pub fn set_active_variants(&mut self, node: NodeId, variants: u64) -> TaffyResult<()> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        if self.active_variants(node)? == variants {
+            return Ok(());
+        }
+        let mut state = self.state_variants.remove(node.into()).unwrap_or_default();
+        state.active = variants;
+        self.update_state_variants(node, state);
+
+        #[cfg(feature = "record")]
+        self.record(Command::SetActiveVariants(node, variants));
+        Ok(())
+    }
+
+    /// Returns the overlays of the node's state variants. See [`set_variant_overlays`](Self::set_variant_overlays).
+    pub fn variant_overlays(&self, node: NodeId) -> TaffyResult<&[Vec<StyleCommand>]> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.state_variants.get(node.into()).map_or(&[], |state| state.overlays.as_slice()))
+    }
+
+    /// Returns the bitset of the node's active state variants. See [`set_active_variants`](Self::set_active_variants).
+    pub fn active_variants(&self, node: NodeId) -> TaffyResult<u64> {
+        let node = self.own_node(node, TaffyError::InvalidInputNode)?;
+        Ok(self.state_variants.get(node.into()).map_or(0, |state| state.active))
+    }
+
+    /// Merges the overlays of the active state variants of a node (which must be in this tree) into its style, stores
+    /// the state variants, and marks the node as dirty if the style it is laid out with changed
+    fn update_state_variants(&mut self, node: NodeId, mut state: StateVariants) {
+        let key = node.into();
+        let base_style = match self.style_variants.get(key) {
+            Some((variants, Some(active))) => &variants[*active].style,
+            _ => self.node_style(node),
+        };
+        let is_active =
+            |(index, overlay): (usize, &Vec<StyleCommand>)| state.active & (1 << index) != 0 && !overlay.is_empty();
+        let has_active_overlay = state.overlays.iter().enumerate().take(64).any(is_active);
+        let merged = has_active_overlay.then(|| merge_variant_overlays(base_style, &state.overlays, state.active));
+        let previous_style = state.merged.as_ref().unwrap_or(base_style);
+        let new_style = merged.as_ref().unwrap_or(base_style);
+        let change = (previous_style != new_style).then(|| !only_inset_differs(previous_style, new_style));
+
+        state.merged = merged;
+        state.is_merged_stale = false;
+        if state.active != 0 || !state.overlays.is_empty() {
+            self.state_variants.insert(key, state);
+        }
+        match change {
+            Some(true) => self.mark_dirty_unchecked(node),
+            Some(false) => self.mark_position_dirty_unchecked(node),
+            None => {}
+        }
+    }
+
+    /// Activates the first style variant of each node in the subtree of `root` whose condition matches the size the
+    /// node was just laid out with, and marks the nodes whose active variant changed as dirty. Returns whether any did.
+    fn update_active_style_variants(&mut self, root: NodeId) -> bool {
+        let mut changed_nodes = Vec::new();
+        for (key, (variants, active)) in self.style_variants.iter_mut() {
+            // Nodes which weren't laid out keep their active variant
+            let node = NodeId::from(key);
+            if !core::iter::successors(Some(node), |&node| self.parents[node.into()]).any(|ancestor| ancestor == root) {
+                continue;
+            }
+            let size = self.nodes[key].unrounded_layout.size;
+            let matching = variants.iter().position(|variant| variant.condition.matches(size));
+            if matching != *active {
+                *active = matching;
+                changed_nodes.push(node);
+            }
+        }
+        let changed = !changed_nodes.is_empty();
+        for node in changed_nodes {
+            self.state_style_changed(node.into());
+            self.mark_dirty_unchecked(node);
+        }
+        changed
+    }
+
+    /// Indicates whether the layout of this node (and its children) need to be recomputed
+    ///
+    /// This is the case both when its cached size measurements have been discarded (for example because its style
+    /// changed) and when only its final layout has (for example because [`set_length_fast`](Self::set_length_fast)
+    /// changed the inset of one of its descendants, so that the node must place its children again).
+    pub fn dirty(&self, node: NodeId) -> TaffyResult<bool> {
+        Ok(!self.nodes[self.own_node(node, TaffyError::InvalidInputNode)?.into()].cache.has_final_layout())
+    }
+
+    /// Updates the stored layout of the provided `node` and its children
+    ///
+    /// The measure function returns the size of a leaf's content, either as a [`Size`] or as a [`MeasureOutput`] which
+    /// also holds its baselines and whether it depends on the available width.
+    pub fn compute_layout_with_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        measure_function: MeasureFunction,
+    ) -> Result<(), TaffyError>
+    where
+        MeasureFunction:
+            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        self.compute_layout_with_tree_measure(node_id, available_space, ignore_measure_tree(measure_function))
+    }
+
+    /// Updates the stored layout of the provided `node` and its children, with a measure function which is also given
+    /// read-only access to the styles, children and previous layouts of the measured node's descendants (see
+    /// [`MeasureTree`])
+    ///
+    /// This allows a leaf to be measured using its descendants (such as the inline boxes within a text node) without
+    /// keeping a copy of them outside of the tree. The node contexts of other nodes are not accessible.
+    pub fn compute_layout_with_tree_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        measure_function: MeasureFunction,
+    ) -> Result<(), TaffyError>
+    where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+            MeasureTree<'_>,
+        ) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        self.compute_full_layout(node_id, available_space, tree_measure_function(measure_function))
+    }
+
+    /// Updates the stored layout of the provided `node` and its children with a measure function which has already been
+    /// adapted to a [`TreeMeasureFunction`], as a later call to [`compute_dirty_layouts`](Self::compute_dirty_layouts)
+    /// can continue from
+    fn compute_full_layout<MeasureFunction>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<()>
+    where
+        MeasureFunction: TreeMeasureFunction<NodeContext>,
+    {
+        self.run_layout(
+            node_id,
+            available_space,
+            true,
+            measure_function,
+            |taffy_view, node_id| compute_root_layout(taffy_view, node_id, available_space),
+            #[cfg(feature = "record")]
+            |node, measurements| Command::ComputeLayout { node, available_space, measurements },
+        )?;
+        Ok(())
+    }
+
+    /// Checks that `node_id` is a node of this tree whose subtree is not too deep to lay out, and prepares the tree for
+    /// laying it out: the layout statistics and limits are reset, and the leaves whose content has changed are measured
+    /// again. Returns the node in the form in which the tree stores it (see [`own_node`](Self::own_node)).
+    fn prepare_layout<MeasureFunction>(
+        &mut self,
+        node_id: NodeId,
+        measure_function: &mut MeasureFunction,
+    ) -> TaffyResult<NodeId>
+    where
+        MeasureFunction: TreeMeasureFunction<NodeContext>,
+    {
+        let node_id = self.own_node(node_id, TaffyError::InvalidInputNode)?;
+        self.check_depth(node_id)?;
+        self.reset_layout_stats();
+        self.reset_layout_limits();
+        self.check_content_changes(measure_function);
+        Ok(node_id)
+    }
+
+    /// Lays out the subtree rooted at `node_id` by running `pass` on it. Used by each method which lays out a whole tree.
+    ///
+    /// After [`prepare_layout`](Self::prepare_layout), the layout styles are resolved against `available_space` and
+    /// `pass` is run, and then run again while the size of a node switches it to a different style variant (up to
+    /// [`MAX_STYLE_VARIANT_PASSES`] passes in total). The layout is then rounded, checked for determinism (with the
+    /// `debug_determinism` feature) and recorded as the command built by `command` (while recording). If `is_resumable`
+    /// then [`compute_dirty_layouts`](Self::compute_dirty_layouts) continues from this layout.
+    fn run_layout<MeasureFunction>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        is_resumable: bool,
+        mut measure_function: MeasureFunction,
+        mut pass: impl FnMut(&mut LayoutPassView<'_, '_, NodeContext>, NodeId),
+        #[cfg(feature = "record")] command: impl FnOnce(NodeId, &[MeasureOutput]) -> Command<'_>,
+    ) -> TaffyResult<NodeId>
+    where
+        MeasureFunction: TreeMeasureFunction<NodeContext>,
+    {
+        let node_id = self.prepare_layout(node_id, &mut measure_function)?;
+        #[cfg(feature = "record")]
+        let (is_recording, mut measurements) = (self.is_recording(), Vec::new());
+        #[cfg(feature = "record")]
+        let mut measure_function = |known_dimensions,
+                                    available_space,
+                                    node,
+                                    context: Option<&mut NodeContext>,
+                                    style: &Style,
+                                    tree: MeasureTree<'_>| {
+            let output = measure_function(known_dimensions, available_space, node, context, style, tree);
+            if is_recording {
+                measurements.push(output);
+            }
+            output
+        };
+        let measure_function: &mut dyn TreeMeasureFunction<NodeContext> = &mut measure_function;
+
+        #[cfg(feature = "std")]
+        self.apply_cache_memo(node_id);
+        self.last_layout = is_resumable.then_some((node_id, available_space));
+        let use_rounding = self.config.use_rounding;
+        self.refresh_layout_styles(Some((node_id, available_space)));
+        let mut taffy_view = LayoutPassView::new(self, &mut *measure_function);
+        pass(&mut taffy_view, node_id);
+
+        // Lay out again while the size of a node switches it to a different style variant
+        let mut passes = 1;
+        while passes < MAX_STYLE_VARIANT_PASSES && taffy_view.taffy.update_active_style_variants(node_id) {
+            taffy_view.taffy.refresh_layout_styles(Some((node_id, available_space)));
+            pass(&mut taffy_view, node_id);
+            passes += 1;
+        }
+
+        if use_rounding {
+            round_layout(&mut taffy_view, node_id);
+        }
+
+        #[cfg(feature = "debug_determinism")]
+        self.assert_layout_is_deterministic(node_id, available_space, measure_function, &mut pass);
+
+        #[cfg(feature = "record")]
+        self.record(command(node_id, &measurements));
+        Ok(node_id)
+    }
+
+    /// Recomputes the layout of the subtree rooted at `node_id` with all caches cleared, and panics if the result differs
+    /// from the layout that was just computed
+    ///
+    /// The layout is recomputed by running the `pass` of the layout (see [`run_layout`](Self::run_layout)) twice: once
+    /// from the root down, and once after first laying out every node of the subtree with the inputs of its final layout
+    /// in a shuffled order, so that layouts which depend on the order in which the caches of the nodes were filled are
+    /// caught as well. The order is seeded per layout, and the seed is included in the panic message.
+    #[cfg(feature = "debug_determinism")]
+    fn assert_layout_is_deterministic(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        measure_function: &mut dyn TreeMeasureFunction<NodeContext>,
+        pass: &mut impl FnMut(&mut LayoutPassView<'_, '_, NodeContext>, NodeId),
+    ) {
+        let mut subtree = Vec::new();
+        let mut stack = Vec::new();
+        stack.push(node_id);
+        while let Some(node) = stack.pop() {
+            subtree.push(node);
+            stack.extend(self.children[node.into()].iter().copied());
+        }
+
+        let first_run: Vec<Layout> = subtree.iter().map(|node| self.nodes[(*node).into()].unrounded_layout).collect();
+        let final_inputs: Vec<Option<LayoutInput>> =
+            subtree.iter().map(|node| self.last_final_layouts.get((*node).into()).map(|(inputs, _)| *inputs)).collect();
+
+        // Shuffle the nodes below the root with a xorshift generator, which is good enough to vary the order
+        let seed = self.determinism_seed;
+        self.determinism_seed = self.determinism_seed.wrapping_add(1);
+        let mut state = seed | 1;
+        let mut order: Vec<usize> = (1..subtree.len()).collect();
+        for i in (1..order.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            order.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+
+        for shuffled in [false, true] {
+            for node in subtree.iter() {
+                self.nodes[(*node).into()].cache.clear();
+            }
+
+            self.refresh_layout_styles(Some((node_id, available_space)));
+            let mut taffy_view = LayoutPassView::new(self, &mut *measure_function);
+            if shuffled {
+                for &index in order.iter() {
+                    if let Some(inputs) = final_inputs[index] {
+                        taffy_view.compute_child_layout(subtree[index], inputs);
+                    }
+                }
+            }
+            pass(&mut taffy_view, node_id);
+
+            for (node, first_layout) in subtree.iter().zip(first_run.iter()) {
+                let second_layout = &self.nodes[(*node).into()].unrounded_layout;
+                let order = if shuffled { "shuffled" } else { "in order" };
+                assert!(
+                    first_layout == second_layout,
+                    "Layout of node {node:?} is not deterministic (seed {seed}, {order}):\n  first run:  {first_layout:?}\n  second run: {second_layout:?}"
+                );
+            }
+        }
+    }
+
+    /// Sets the formatter which breaks the content of inline containers (nodes with [`Display::Inline`]) into lines,
+    /// which is used by every layout of the tree from now on
+    ///
+    /// Without a formatter, only the children of inline containers are placed on lines (see
+    /// [`break_atoms_into_lines`]). The inline containers of the tree are marked dirty, as their lines may change.
+    #[cfg(feature = "inline_layout")]
+    pub fn set_inline_layout(&mut self, inline_layout: impl InlineLayout<NodeContext> + Clone + Send + Sync + 'static) {
+        self.inline_layout = InlineLayoutSlot(Some(Box::new(inline_layout)));
+        self.mark_inline_containers_dirty();
+    }
+
+    /// Removes the formatter set with [`set_inline_layout`](Self::set_inline_layout), so that only the children of
+    /// inline containers are placed on lines. The inline containers of the tree are marked dirty.
+    #[cfg(feature = "inline_layout")]
+    pub fn clear_inline_layout(&mut self) {
+        self.inline_layout = InlineLayoutSlot(None);
+        self.mark_inline_containers_dirty();
+    }
+
+    /// Marks the nodes which are laid out as inline containers (and their ancestors) dirty
+    #[cfg(feature = "inline_layout")]
+    fn mark_inline_containers_dirty(&mut self) {
+        let inline_containers: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .filter(|(key, node)| {
+                let style = self.layout_styles.get(*key).unwrap_or(&self.styles[node.style].style);
+                style.display == Display::Inline
+            })
+            .map(|(key, _)| NodeId::from(key))
+            .collect();
+        for node in inline_containers {
+            self.mark_dirty_unchecked(node);
+        }
+    }
+
+    /// Updates the stored layout of the provided `node` and its children
+    pub fn compute_layout(&mut self, node: NodeId, available_space: Size<AvailableSpace>) -> Result<(), TaffyError> {
+        self.compute_layout_with_measure(node, available_space, |_, _, _, _, _| Size::ZERO)
+    }
+
+    /// Updates the stored layouts of the nodes which have been modified since they were last laid out, without laying out
+    /// the rest of the tree again
+    ///
+    /// Lays out the tree most recently laid out by [`compute_layout_with_measure`](Self::compute_layout_with_measure).
+    /// Each modified node is laid out again by laying out its parent with the constraints that the parent was given by
+    /// the previous layout. If this leaves the parent's size and baselines unchanged, and the parent's size wasn't based
+    /// on its content (such as a flex item with an `auto` flex basis), then nothing outside of the parent is affected
+    /// and its ancestors keep their current layouts. Otherwise the parent's own parent is laid out again, and so on, up
+    /// to the root (which is laid out with the available space most recently passed to `compute_layout`). For
+    /// example a change to the text of a leaf inside a container of fixed size only lays out that container again.
+    ///
+    /// Does nothing if layout has not been computed with `compute_layout` since the tree was created, or if it has since
+    /// been computed with [`compute_fit_layout`](Self::compute_fit_layout) or
+    /// [`compute_constrained_layout`](Self::compute_constrained_layout).
+    pub fn compute_dirty_layouts_with_measure<MeasureFunction, Measured>(
+        &mut self,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<()>
+    where
+        MeasureFunction:
+            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        self.compute_dirty_layouts_with_tree_measure(ignore_measure_tree(measure_function))
+    }
+
+    /// Updates the stored layouts of the nodes which have been modified since they were last laid out, with a measure
+    /// function which is also given read-only access to the measured node's descendants (see [`MeasureTree`]). See
+    /// [`compute_dirty_layouts_with_measure`](Self::compute_dirty_layouts_with_measure).
+    pub fn compute_dirty_layouts_with_tree_measure<MeasureFunction, Measured>(
+        &mut self,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<()>
+    where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+            MeasureTree<'_>,
+        ) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        let mut measure_function = tree_measure_function(measure_function);
+        let (root, available_space) = match self.last_layout {
+            Some(last_layout) => last_layout,
+            None => return Ok(()),
+        };
+        let root = self.prepare_layout(root, &mut measure_function)?;
+        self.refresh_layout_styles(Some((root, available_space)));
+
+        // Partial layouts are not recorded, so a full layout is performed instead while recording
+        #[cfg(feature = "record")]
+        if self.is_recording() {
+            return self.compute_full_layout(root, available_space, measure_function);
+        }
+
+        // Find the parents of the modified nodes. Only the ancestors of a modified node can have lost their final layout.
+        let mut relayout_nodes = Vec::new();
+        let mut stack = Vec::new();
+        stack.push(root);
+        while let Some(node) = stack.pop() {
+            let node_data = &mut self.nodes[node.into()];
+            if node_data.cache.has_final_layout() {
+                continue;
+            }
+            if node_data.needs_relayout {
+                node_data.needs_relayout = false;
+                match self.parents[node.into()] {
+                    Some(parent) if node != root => relayout_nodes.push(parent),
+                    _ => return self.compute_full_layout(root, available_space, measure_function),
+                }
+            }
+            stack.extend(self.children[node.into()].iter().copied());
+        }
+
+        // Lay out the deepest nodes first, so that laying out their ancestors again (if needed) can use their results
+        let depth = |node: NodeId| core::iter::successors(Some(node), |&node| self.parents[node.into()]).count();
+        relayout_nodes.sort_unstable_by_key(|&node| core::cmp::Reverse(depth(node)));
+
+        // The nodes whose layout was found to be unchanged, above which no further layout is needed
+        let mut unchanged_nodes = Vec::new();
+        let mut needs_full_layout = false;
+        let use_rounding = self.config.use_rounding;
+        let mut taffy_view = TaffyView::new(self, &mut measure_function);
+        'relayout_nodes: for mut node in relayout_nodes {
+            loop {
+                if taffy_view.taffy.nodes[node.into()].cache.has_final_layout() {
+                    continue 'relayout_nodes;
+                }
+                let (inputs, previous_output) = match taffy_view.taffy.last_final_layouts.get(node.into()).copied() {
+                    Some(last_final_layout) => last_final_layout,
+                    None => {
+                        needs_full_layout = true;
+                        break 'relayout_nodes;
+                    }
+                };
+                // The parent of a content-sized node must measure it again, even if its final layout is unchanged
+                let output = taffy_view.compute_child_layout(node, inputs);
+                let is_content_sized = taffy_view.taffy.content_sized_nodes.contains_key(node.into());
+                if output == previous_output && !(is_content_sized && node != root) {
+                    unchanged_nodes.push(node);
+                    continue 'relayout_nodes;
+                }
+                match taffy_view.taffy.parents[node.into()] {
+                    Some(parent) if node != root => node = parent,
+                    _ => {
+                        needs_full_layout = true;
+                        break 'relayout_nodes;
+                    }
+                }
+            }
+        }
+
+        // The ancestors of the unchanged nodes were only marked dirty because of changes which turned out not to affect
+        // them, so their previous final layouts are still valid
+        for &node in unchanged_nodes.iter() {
+            let mut ancestor = node;
+            while ancestor != root {
+                ancestor = match taffy_view.taffy.parents[ancestor.into()] {
+                    Some(parent) => parent,
+                    None => break,
+                };
+                if taffy_view.taffy.nodes[ancestor.into()].cache.has_final_layout() {
+                    break;
+                }
+                match taffy_view.taffy.last_final_layouts.get(ancestor.into()).copied() {
+                    Some((inputs, output)) => store_cached_layout(&mut taffy_view, ancestor, inputs, output),
+                    None => needs_full_layout = true,
+                }
+            }
+        }
+
+        if needs_full_layout || !taffy_view.taffy.nodes[root.into()].cache.has_final_layout() {
+            return self.compute_full_layout(root, available_space, measure_function);
+        }
+        // Nodes switching style variants are laid out again by a full layout, which bounds the number of passes
+        if taffy_view.taffy.update_active_style_variants(root) {
+            return self.compute_full_layout(root, available_space, measure_function);
+        }
+
+        if use_rounding {
+            let mut ancestor_locations = Vec::new();
+            for &node in unchanged_nodes.iter() {
+                ancestor_locations.clear();
+                if node != root {
+                    ancestor_locations.extend(
+                        core::iter::successors(taffy_view.taffy.parents[node.into()], |&ancestor| {
+                            (ancestor != root).then(|| taffy_view.taffy.parents[ancestor.into()]).flatten()
+                        })
+                        .map(|ancestor| taffy_view.taffy.nodes[ancestor.into()].unrounded_layout.location),
+                    );
+                    ancestor_locations.reverse();
+                }
+                round_subtree_layout(&mut taffy_view, node, &ancestor_locations);
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the stored layouts of the nodes which have been modified since they were last laid out, without laying out
+    /// the rest of the tree again. See [`compute_dirty_layouts_with_measure`](Self::compute_dirty_layouts_with_measure).
+    pub fn compute_dirty_layouts(&mut self) -> TaffyResult<()> {
+        self.compute_dirty_layouts_with_measure(|_, _, _, _, _| Size::ZERO)
+    }
+
+    /// Updates the stored layout of the provided `node` and its children, spreading the work over as many calls as are
+    /// needed to keep each one within `budget`
+    ///
+    /// Each call lays out the tree as [`compute_layout_with_measure`](Self::compute_layout_with_measure) would, until the
+    /// budget runs out. From then on the layout of each node which is not in the cache is deferred, and
+    /// [`LayoutProgress::Paused`] is returned. The layouts computed before the budget ran out were computed with the same
+    /// inputs as in a complete layout, and are cached, so the next call with the same `node` and `available_space` picks
+    /// up where this one left off. Calling with a different `node` or `available_space` starts again. Once every node has
+    /// been laid out, the layout is completed as by [`compute_layout_with_measure`](Self::compute_layout_with_measure)
+    /// (which is then served from the cache) and [`LayoutProgress::Complete`] is returned.
+    ///
+    /// Every call lays out at least one node, and a call may overrun its budget by the time taken to lay out a node
+    /// (excluding its descendants). If a layout has been paused more times than its nodes have cache entries then the
+    /// next call completes it regardless of the budget.
+    ///
+    /// The tree may be modified between calls. Modified nodes are simply laid out again. The budget is ignored while
+    /// recording (see [`start_recording`](Self::start_recording)) so that recordings replay exactly.
+    #[cfg(feature = "std")]
+    pub fn compute_layout_budgeted_with_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        budget: std::time::Duration,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<LayoutProgress>
+    where
+        MeasureFunction:
+            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        self.compute_layout_budgeted_with_tree_measure(
+            node_id,
+            available_space,
+            budget,
+            ignore_measure_tree(measure_function),
+        )
+    }
+
+    /// Updates the stored layout of the provided `node` and its children within `budget`, with a measure function which
+    /// is also given read-only access to the measured node's descendants (see [`MeasureTree`]). See
+    /// [`compute_layout_budgeted_with_measure`](Self::compute_layout_budgeted_with_measure).
+    #[cfg(feature = "std")]
+    pub fn compute_layout_budgeted_with_tree_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        available_space: Size<AvailableSpace>,
+        budget: std::time::Duration,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<LayoutProgress>
+    where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+            MeasureTree<'_>,
+        ) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        let mut measure_function = tree_measure_function(measure_function);
+        // A budget too large to represent can't run out
+        let deadline = std::time::Instant::now().checked_add(budget);
+        let node_id = self.prepare_layout(node_id, &mut measure_function)?;
+
+        let mut state = match self.budgeted_layout.take() {
+            Some(state) if state.root == node_id && state.available_space == available_space => state,
+            _ => {
+                // Each paused call fills at least one cache entry, so this bounds the number of calls needed
+                let max_paused_calls = self.iter_nodes_depth_first(node_id)?.count() * Cache::CAPACITY;
+                BudgetedLayout { root: node_id, available_space, paused_calls: 0, max_paused_calls }
+            }
+        };
+        #[cfg(feature = "record")]
+        let is_recording = self.is_recording();
+        #[cfg(not(feature = "record"))]
+        let is_recording = false;
+
+        if let (Some(deadline), false, true) = (deadline, is_recording, state.paused_calls < state.max_paused_calls) {
+            self.refresh_layout_styles(Some((node_id, available_space)));
+
+            self.layout_deadline = Some(LayoutDeadline::new(deadline));
+            let mut taffy_view = TaffyView::new(self, &mut measure_function);
+            compute_root_layout(&mut taffy_view, node_id, available_space);
+            let deferred = self.layout_deadline.take().map_or(0, |deadline| deadline.deferred);
+
+            if deferred > 0 {
+                state.paused_calls += 1;
+                self.budgeted_layout = Some(state);
+                return Ok(LayoutProgress::Paused { remaining_nodes: deferred });
+            }
+        }
+
+        self.compute_full_layout(node_id, available_space, measure_function)?;
+        Ok(LayoutProgress::Complete)
+    }
+
+    /// Updates the stored layout of the provided `node` and its children, spreading the work over as many calls as are
+    /// needed to keep each one within `budget`. See [`compute_layout_budgeted_with_measure`](Self::compute_layout_budgeted_with_measure).
+    #[cfg(feature = "std")]
+    pub fn compute_layout_budgeted(
+        &mut self,
+        node: NodeId,
+        available_space: Size<AvailableSpace>,
+        budget: std::time::Duration,
+    ) -> TaffyResult<LayoutProgress> {
+        self.compute_layout_budgeted_with_measure(node, available_space, budget, |_, _, _, _, _| Size::ZERO)
+    }
+
+    /// Sizes the provided `node` to fit its content (up to `max_size`) and updates the stored layout of it and its children
+    ///
+    /// The node is first laid out under max-content constraints to determine its preferred size. That size is then clamped
+    /// to `max_size` and the node is laid out again with the clamped size as definite available space. This is the typical
+    /// way of sizing a window or popup which should shrink-wrap its content. Returns the final size of the node.
+    pub fn compute_fit_layout_with_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        max_size: Size<f32>,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<Size<f32>>
+    where
+        MeasureFunction:
+            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        self.compute_fit_layout_with_tree_measure(node_id, max_size, ignore_measure_tree(measure_function))
+    }
+
+    /// Sizes the provided `node` to fit its content (up to `max_size`) and updates the stored layout of it and its
+    /// children, with a measure function which is also given read-only access to the measured node's descendants (see
+    /// [`MeasureTree`]). See [`compute_fit_layout_with_measure`](Self::compute_fit_layout_with_measure).
+    pub fn compute_fit_layout_with_tree_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        max_size: Size<f32>,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<Size<f32>>
+    where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+            MeasureTree<'_>,
+        ) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        let max_space = max_size.map(|max| AvailableSpace::from(max.is_finite().then_some(max)));
+        let node_id = self.run_layout(
+            node_id,
+            max_space,
+            false,
+            tree_measure_function(measure_function),
+            |taffy_view, node_id| {
+                // Lay out under a max-content constraint to determine the preferred size of the node. The unrounded size
+                // is used so that rounding is only applied once (after the final layout)
+                compute_root_layout(taffy_view, node_id, Size::MAX_CONTENT);
+                let content_size = taffy_view.taffy.nodes[node_id.into()].unrounded_layout.size;
+                let fit_size = content_size.zip_map(max_size, f32_min);
+
+                // Lay out again with the node forced to the clamped size
+                compute_root_layout_with_known_dimensions(
+                    taffy_view,
+                    node_id,
+                    fit_size.map(Some),
+                    fit_size.map(AvailableSpace::Definite),
+                );
+            },
+            #[cfg(feature = "record")]
+            |node, measurements| Command::ComputeFitLayout { node, max_size, measurements },
+        )?;
+        Ok(self.layout(node_id)?.size)
+    }
+
+    /// Sizes the provided `node` to fit its content (up to `max_size`) and updates the stored layout of it and its children
+    ///
+    /// See [`compute_fit_layout_with_measure`](Self::compute_fit_layout_with_measure) for details.
+    pub fn compute_fit_layout(&mut self, node: NodeId, max_size: Size<f32>) -> TaffyResult<Size<f32>> {
+        self.compute_fit_layout_with_measure(node, max_size, |_, _, _, _, _| Size::ZERO)
+    }
+
+    /// Lays out the provided `node` under size constraints (such as Flutter's `BoxConstraints`) and updates the stored
+    /// layout of it and its children. Returns the final size of the node.
+    ///
+    /// See [`compute_constrained_layout`](crate::compute_constrained_layout) for details.
+    pub fn compute_constrained_layout_with_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        constraints: &impl LayoutConstraints,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<Size<f32>>
+    where
+        MeasureFunction:
+            FnMut(Size<Option<f32>>, Size<AvailableSpace>, NodeId, Option<&mut NodeContext>, &Style) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        self.compute_constrained_layout_with_tree_measure(node_id, constraints, ignore_measure_tree(measure_function))
+    }
+
+    /// Lays out the provided `node` under size constraints and updates the stored layout of it and its children, with a
+    /// measure function which is also given read-only access to the measured node's descendants (see [`MeasureTree`]).
+    /// See [`compute_constrained_layout_with_measure`](Self::compute_constrained_layout_with_measure).
+    pub fn compute_constrained_layout_with_tree_measure<MeasureFunction, Measured>(
+        &mut self,
+        node_id: NodeId,
+        constraints: &impl LayoutConstraints,
+        measure_function: MeasureFunction,
+    ) -> TaffyResult<Size<f32>>
+    where
+        MeasureFunction: FnMut(
+            Size<Option<f32>>,
+            Size<AvailableSpace>,
+            NodeId,
+            Option<&mut NodeContext>,
+            &Style,
+            MeasureTree<'_>,
+        ) -> Measured,
+        Measured: Into<MeasureOutput>,
+    {
+        let node_id = self.run_layout(
+            node_id,
+            constraints.to_layout_inputs().1,
+            false,
+            tree_measure_function(measure_function),
+            |taffy_view, node_id| {
+                compute_constrained_layout(taffy_view, node_id, constraints);
+            },
+            #[cfg(feature = "record")]
+            |node, measurements| Command::ComputeConstrainedLayout {
+                node,
+                min_size: constraints.min_size(),
+                max_size: constraints.max_size(),
+                measurements,
+            },
+        )?;
+        Ok(self.layout(node_id)?.size)
+    }
+
+    /// Lays out the provided `node` under size constraints (such as Flutter's `BoxConstraints`) and updates the stored
+    /// layout of it and its children. Returns the final size of the node.
+    ///
+    /// See [`compute_constrained_layout`](crate::compute_constrained_layout) for details.
+    pub fn compute_constrained_layout(
+        &mut self,
+        node: NodeId,
+        constraints: &impl LayoutConstraints,
+    ) -> TaffyResult<Size<f32>> {
+        self.compute_constrained_layout_with_measure(node, constraints, |_, _, _, _, _| Size::ZERO)
+    }
+
+    /// Starts recording the operations performed on this tree into a log which can be replayed with
+    /// [`replay`](crate::record::replay). See the [`record`](crate::record) module for details.
+    ///
+    /// The log starts with a snapshot of the current state of the tree. Starting a recording clears the layout cache of
+    /// every node, so that the first layout computed while recording is measured in the same way as when it is replayed.
+    /// Any recording which is already in progress is discarded. The output of every call to the measure function
+    /// (including the baselines of the leaf, see [`MeasureOutput`]) is recorded, and returned again when replaying.
+    #[cfg(feature = "record")]
+    pub fn start_recording(&mut self) {
+        for (_, node) in self.nodes.iter_mut() {
+            node.mark_dirty();
+        }
+
+        self.recorder = Some(Recorder::new());
+        self.record(Command::SetLayoutConfig(self.config));
+        let shared_styles: Vec<StyleHandle> =
+            self.styles.iter().filter(|(_, entry)| entry.is_shared).map(|(key, _)| key.into()).collect();
+        for handle in shared_styles {
+            self.record(Command::CreateSharedStyle(handle));
+        }
+        let nodes: Vec<(NodeId, bool)> =
+            self.nodes.iter().map(|(key, data)| (self.node_id(key), data.has_context)).collect();
+        for &(node, has_context) in &nodes {
+            self.record(Command::NewLeaf(node, has_context));
+            if let Ok(Some(handle)) = self.node_style_handle(node) {
+                self.record(Command::SetNodeStyleHandle(node, handle));
+            }
+        }
+        for &(node, _) in &nodes {
+            if !self.children[node.into()].is_empty() {
+                self.record(Command::SetChildren(node));
+            }
+        }
+    }
+
+    /// Stops recording and returns the log, or `None` if no recording was in progress
+    #[cfg(feature = "record")]
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        let recorder = self.recorder.take()?;
+        Some(recorder.finish(self))
+    }
+
+    /// Returns whether the operations performed on this tree are being recorded
+    #[cfg(feature = "record")]
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Appends a command describing an operation which has just been performed on this tree to the log (if recording)
+    #[cfg(feature = "record")]
+    fn record(&mut self, command: Command) {
+        if let Some(mut recorder) = self.recorder.take() {
+            recorder.push(self, command);
+            self.recorder = Some(recorder);
+        }
+    }
+
+    /// Prints a debug representation of the tree's layout
+    #[cfg(feature = "std")]
+    pub fn print_tree(&mut self, root: NodeId) {
+        crate::util::print_tree(self, root)
+    }
+
+    /// Returns a canonical text snapshot of the layout of the tree, suitable for golden tests
+    ///
+    /// See [`snapshot_tree`](crate::util::snapshot_tree) and [`compare_snapshots`](crate::util::compare_snapshots).
+    #[cfg(feature = "std")]
+    pub fn snapshot(&self, root: NodeId) -> String {
+        crate::util::snapshot_tree(self, root)
+    }
+
+    /// Returns an instance of LayoutTree representing the TaffyTree
+    #[cfg(test)]
+    pub(crate) fn as_layout_tree(&mut self) -> impl LayoutPartialTree + '_ {
+        self.refresh_layout_styles(None);
+        TaffyView::new(self, ignore_measure_tree(|_, _, _, _, _| Size::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::geometry::Point;
+    use crate::style::{Dimension, Display, FlexDirection, FlexWrap, StyleDefaults};
+    use crate::style_helpers::*;
+    use crate::tree::PercentResolutionMode;
+    use crate::util::sys;
 
     fn size_measure_function(
         known_dimensions: Size<Option<f32>>,
@@ -804,355 +4813,2022 @@ mod tests {
     }
 
     #[test]
-    fn new_should_allocate_default_capacity() {
-        const DEFAULT_CAPACITY: usize = 16; // This is the capacity defined in the `impl Default`
-        let taffy: TaffyTree<()> = TaffyTree::new();
+    fn new_should_allocate_default_capacity() {
+        const DEFAULT_CAPACITY: usize = 16; // This is the capacity defined in the `impl Default`
+        let taffy: TaffyTree<()> = TaffyTree::new();
+
+        assert!(taffy.children.capacity() >= DEFAULT_CAPACITY);
+        assert!(taffy.parents.capacity() >= DEFAULT_CAPACITY);
+        assert!(taffy.nodes.capacity() >= DEFAULT_CAPACITY);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        const CAPACITY: usize = 8;
+        let taffy: TaffyTree<()> = TaffyTree::with_capacity(CAPACITY);
+
+        assert!(taffy.children.capacity() >= CAPACITY);
+        assert!(taffy.parents.capacity() >= CAPACITY);
+        assert!(taffy.nodes.capacity() >= CAPACITY);
+    }
+
+    #[test]
+    fn test_with_capacity_and_children() {
+        const CAPACITY: usize = 8;
+        let mut taffy: TaffyTree<()> = TaffyTree::with_capacity_and_children(CAPACITY, CAPACITY);
+        assert!(taffy.nodes.capacity() >= CAPACITY);
+
+        let leaf = taffy.new_leaf(Style::default()).unwrap();
+        assert!(taffy.children[leaf.into()].capacity() >= CAPACITY);
+    }
+
+    #[test]
+    fn test_reserve() {
+        const ADDITIONAL: usize = 100;
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let _ = taffy.new_leaf(Style::default()).unwrap();
+        taffy.reserve(ADDITIONAL);
+
+        assert!(taffy.nodes.capacity() > ADDITIONAL);
+        assert!(taffy.styles.capacity() > ADDITIONAL);
+        assert!(taffy.children.capacity() > ADDITIONAL);
+        assert!(taffy.parents.capacity() > ADDITIONAL);
+    }
+
+    #[test]
+    fn test_new_leaf() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let res = taffy.new_leaf(Style::default());
+        assert!(res.is_ok());
+        let node = res.unwrap();
+
+        // node should be in the taffy tree and have no children
+        assert!(taffy.child_count(node) == 0);
+    }
+
+    #[test]
+    fn new_leaf_with_context() {
+        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
+
+        let res = taffy.new_leaf_with_context(Style::default(), Size::ZERO);
+        assert!(res.is_ok());
+        let node = res.unwrap();
+
+        // node should be in the taffy tree and have no children
+        assert!(taffy.child_count(node) == 0);
+    }
+
+    /// Test that new_with_children works as expected
+    #[test]
+    fn test_new_with_children() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        // node should have two children
+        assert_eq!(taffy.child_count(node), 2);
+        assert_eq!(taffy.children(node).unwrap()[0], child0);
+        assert_eq!(taffy.children(node).unwrap()[1], child1);
+    }
+
+    #[test]
+    fn remove_node_should_remove() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let node = taffy.new_leaf(Style::default()).unwrap();
+
+        let _ = taffy.remove(node).unwrap();
+    }
+
+    #[test]
+    fn remove_node_should_detach_hierarchy() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        // Build a linear tree layout: <0> <- <1> <- <2>
+        let node2 = taffy.new_leaf(Style::default()).unwrap();
+        let node1 = taffy.new_with_children(Style::default(), &[node2]).unwrap();
+        let node0 = taffy.new_with_children(Style::default(), &[node1]).unwrap();
+
+        // Both node0 and node1 should have 1 child nodes
+        assert_eq!(taffy.children(node0).unwrap().as_slice(), &[node1]);
+        assert_eq!(taffy.children(node1).unwrap().as_slice(), &[node2]);
+
+        // Disconnect the tree: <0> <2>
+        let _ = taffy.remove(node1).unwrap();
+
+        // Both remaining nodes should have no child nodes
+        assert!(taffy.children(node0).unwrap().is_empty());
+        assert!(taffy.children(node2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn remove_last_node() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let parent = taffy.new_leaf(Style::default()).unwrap();
+        let child = taffy.new_leaf(Style::default()).unwrap();
+        taffy.add_child(parent, child).unwrap();
+
+        taffy.remove(child).unwrap();
+        taffy.remove(parent).unwrap();
+    }
+
+    #[test]
+    fn set_measure() {
+        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
+        let node = taffy.new_leaf_with_context(Style::default(), Size { width: 200.0, height: 200.0 }).unwrap();
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
+        assert_eq!(taffy.layout(node).unwrap().size.width, 200.0);
+
+        taffy.set_node_context(node, Some(Size { width: 100.0, height: 100.0 })).unwrap();
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
+        assert_eq!(taffy.layout(node).unwrap().size.width, 100.0);
+    }
+
+    #[test]
+    fn set_measure_of_previously_unmeasured_node() {
+        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
+        assert_eq!(taffy.layout(node).unwrap().size.width, 0.0);
+
+        taffy.set_node_context(node, Some(Size { width: 100.0, height: 100.0 })).unwrap();
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
+        assert_eq!(taffy.layout(node).unwrap().size.width, 100.0);
+    }
+
+    /// Test that adding `add_child()` works
+    #[test]
+    fn add_child() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        assert_eq!(taffy.child_count(node), 0);
+
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        taffy.add_child(node, child0).unwrap();
+        assert_eq!(taffy.child_count(node), 1);
+
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        taffy.add_child(node, child1).unwrap();
+        assert_eq!(taffy.child_count(node), 2);
+    }
+
+    #[test]
+    fn insert_child_at_index() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let child2 = taffy.new_leaf(Style::default()).unwrap();
+
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        assert_eq!(taffy.child_count(node), 0);
+
+        taffy.insert_child_at_index(node, 0, child0).unwrap();
+        assert_eq!(taffy.child_count(node), 1);
+        assert_eq!(taffy.children(node).unwrap()[0], child0);
+
+        taffy.insert_child_at_index(node, 0, child1).unwrap();
+        assert_eq!(taffy.child_count(node), 2);
+        assert_eq!(taffy.children(node).unwrap()[0], child1);
+        assert_eq!(taffy.children(node).unwrap()[1], child0);
+
+        taffy.insert_child_at_index(node, 1, child2).unwrap();
+        assert_eq!(taffy.child_count(node), 3);
+        assert_eq!(taffy.children(node).unwrap()[0], child1);
+        assert_eq!(taffy.children(node).unwrap()[1], child2);
+        assert_eq!(taffy.children(node).unwrap()[2], child0);
+    }
+
+    #[test]
+    fn set_children() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        assert_eq!(taffy.child_count(node), 2);
+        assert_eq!(taffy.children(node).unwrap()[0], child0);
+        assert_eq!(taffy.children(node).unwrap()[1], child1);
+
+        let child2 = taffy.new_leaf(Style::default()).unwrap();
+        let child3 = taffy.new_leaf(Style::default()).unwrap();
+        taffy.set_children(node, &[child2, child3]).unwrap();
+
+        assert_eq!(taffy.child_count(node), 2);
+        assert_eq!(taffy.children(node).unwrap()[0], child2);
+        assert_eq!(taffy.children(node).unwrap()[1], child3);
+    }
+
+    /// Test that removing a child works
+    #[test]
+    fn remove_child() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        assert_eq!(taffy.child_count(node), 2);
+
+        taffy.remove_child(node, child0).unwrap();
+        assert_eq!(taffy.child_count(node), 1);
+        assert_eq!(taffy.children(node).unwrap()[0], child1);
+
+        taffy.remove_child(node, child1).unwrap();
+        assert_eq!(taffy.child_count(node), 0);
+    }
+
+    #[test]
+    fn remove_child_at_index() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        assert_eq!(taffy.child_count(node), 2);
+
+        taffy.remove_child_at_index(node, 0).unwrap();
+        assert_eq!(taffy.child_count(node), 1);
+        assert_eq!(taffy.children(node).unwrap()[0], child1);
+
+        taffy.remove_child_at_index(node, 0).unwrap();
+        assert_eq!(taffy.child_count(node), 0);
+    }
+
+    // Related to: https://github.com/DioxusLabs/taffy/issues/510
+    #[test]
+    fn remove_child_updates_parents() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let parent = taffy.new_leaf(Style::default()).unwrap();
+        let child = taffy.new_leaf(Style::default()).unwrap();
+
+        taffy.add_child(parent, child).unwrap();
+
+        taffy.remove(parent).unwrap();
+
+        // Once the parent is removed this shouldn't panic.
+        assert!(taffy.set_children(child, &[]).is_ok());
+    }
+
+    #[test]
+    fn replace_child_at_index() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+
+        let node = taffy.new_with_children(Style::default(), &[child0]).unwrap();
+        assert_eq!(taffy.child_count(node), 1);
+        assert_eq!(taffy.children(node).unwrap()[0], child0);
+
+        taffy.replace_child_at_index(node, 0, child1).unwrap();
+        assert_eq!(taffy.child_count(node), 1);
+        assert_eq!(taffy.children(node).unwrap()[0], child1);
+    }
+
+    #[test]
+    fn reparent_moves_subtree_and_keeps_its_cache() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let panel = taffy.new_with_children(Style::default(), &[leaf]).unwrap();
+        let sibling = taffy.new_leaf(Style::default()).unwrap();
+        let old_parent = taffy.new_with_children(Style::default(), &[panel]).unwrap();
+        let new_parent = taffy.new_with_children(Style::default(), &[sibling]).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[old_parent, new_parent]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        taffy.reparent(panel, new_parent, 0).unwrap();
+
+        assert_eq!(taffy.children(old_parent).unwrap(), vec![]);
+        assert_eq!(taffy.children(new_parent).unwrap(), vec![panel, sibling]);
+        assert_eq!(taffy.parent(panel), Some(new_parent));
+        assert!(taffy.dirty(old_parent).unwrap());
+        assert!(taffy.dirty(new_parent).unwrap());
+        assert!(taffy.dirty(root).unwrap());
+        assert!(!taffy.dirty(panel).unwrap());
+        assert!(!taffy.dirty(leaf).unwrap());
+        assert!(!taffy.dirty(sibling).unwrap());
+    }
+
+    #[test]
+    fn reparent_within_the_same_parent_reorders() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let child2 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1, child2]).unwrap();
+
+        taffy.reparent(child0, node, 2).unwrap();
+        assert_eq!(taffy.children(node).unwrap(), vec![child1, child2, child0]);
+
+        assert_eq!(
+            taffy.reparent(child0, node, 3),
+            Err(TaffyError::ChildIndexOutOfBounds { parent: node, child_index: 3, child_count: 2 })
+        );
+        assert_eq!(taffy.children(node).unwrap(), vec![child1, child2, child0]);
+    }
+
+    #[test]
+    fn reconcile_children_reuses_nodes_with_matching_keys() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let unkeyed = taffy.new_leaf(Style::default()).unwrap();
+        let parent = taffy.new_with_children(Style::default(), &[unkeyed]).unwrap();
+
+        let mut created = Vec::new();
+        let first = taffy
+            .reconcile_children(parent, &[1u32, 2, 3], |&key| {
+                created.push(key);
+                Style::default()
+            })
+            .unwrap();
+        assert_eq!(created, vec![1, 2, 3]);
+        assert_eq!(taffy.children(parent).unwrap(), first);
+        assert_eq!(taffy.total_node_count(), 4);
+
+        created.clear();
+        let second = taffy
+            .reconcile_children(parent, &[3u32, 4, 1], |&key| {
+                created.push(key);
+                Style::default()
+            })
+            .unwrap();
+        assert_eq!(created, vec![4]);
+        assert_eq!(second[0], first[2]);
+        assert_eq!(second[2], first[0]);
+        assert_eq!(taffy.children(parent).unwrap(), second);
+        assert_eq!(taffy.parent(second[1]), Some(parent));
+        assert_eq!(taffy.total_node_count(), 4);
+        assert!(taffy.style(first[1]).is_err());
+    }
+
+    #[test]
+    fn reconcile_children_removes_the_descendants_of_removed_children() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let parent = taffy.new_leaf(Style::default()).unwrap();
+        let children = taffy.reconcile_children(parent, &[1u32, 2], |_| Style::default()).unwrap();
+        let grandchild = taffy.new_leaf(Style::default()).unwrap();
+        let great_grandchild = taffy.new_leaf(Style::default()).unwrap();
+        taffy.add_child(grandchild, great_grandchild).unwrap();
+        taffy.add_child(children[1], grandchild).unwrap();
+        assert_eq!(taffy.total_node_count(), 5);
+
+        taffy.reconcile_children(parent, &[1u32], |_| Style::default()).unwrap();
+        assert_eq!(taffy.children(parent).unwrap(), vec![children[0]]);
+        assert_eq!(taffy.total_node_count(), 2);
+        assert_eq!(taffy.node_keys.len(), 1);
+        assert!(taffy.style(grandchild).is_err());
+        assert!(taffy.style(great_grandchild).is_err());
+    }
+
+    #[test]
+    fn reconcile_children_with_unchanged_keys_does_not_dirty_parent() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let parent = taffy.new_leaf(Style::default()).unwrap();
+        let children = taffy.reconcile_children(parent, &[7u64, 8], |_| Style::default()).unwrap();
+        taffy.compute_layout(parent, Size::MAX_CONTENT).unwrap();
+
+        let unchanged = taffy.reconcile_children(parent, &[7u64, 8], |_| unreachable!()).unwrap();
+        assert_eq!(unchanged, children);
+        assert!(!taffy.dirty(parent).unwrap());
+    }
+
+    #[test]
+    fn reparent_under_own_descendant_is_an_error() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let grandchild = taffy.new_leaf(Style::default()).unwrap();
+        let child = taffy.new_with_children(Style::default(), &[grandchild]).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child]).unwrap();
+
+        assert_eq!(
+            taffy.reparent(node, grandchild, 0),
+            Err(TaffyError::ParentIsDescendant { node, parent: grandchild })
+        );
+        assert_eq!(taffy.reparent(node, node, 0), Err(TaffyError::ParentIsDescendant { node, parent: node }));
+        assert_eq!(taffy.parent(node), None);
+        assert_eq!(taffy.children(grandchild).unwrap(), vec![]);
+    }
+    #[test]
+    fn test_child_at_index() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let child2 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1, child2]).unwrap();
+
+        assert!(if let Ok(result) = taffy.child_at_index(node, 0) { result == child0 } else { false });
+        assert!(if let Ok(result) = taffy.child_at_index(node, 1) { result == child1 } else { false });
+        assert!(if let Ok(result) = taffy.child_at_index(node, 2) { result == child2 } else { false });
+    }
+    #[test]
+    fn test_child_count() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
 
-        assert!(taffy.children.capacity() >= DEFAULT_CAPACITY);
-        assert!(taffy.parents.capacity() >= DEFAULT_CAPACITY);
-        assert!(taffy.nodes.capacity() >= DEFAULT_CAPACITY);
+        assert!(taffy.child_count(node) == 2);
+        assert!(taffy.child_count(child0) == 0);
+        assert!(taffy.child_count(child1) == 0);
+    }
+
+    #[test]
+    fn clear_removes_all_nodes() {
+        let mut taffy: TaffyTree<u32> = TaffyTree::new();
+        let child = taffy.new_leaf_with_context(Style::default(), 7).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[child]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        taffy.clear();
+
+        assert_eq!(taffy.total_node_count(), 0);
+        assert!(taffy.layout(root).is_err());
+        assert!(taffy.get_node_context(child).is_none());
+        // The last layout was of a node which no longer exists, so there is nothing to relayout
+        assert!(taffy.compute_dirty_layouts().is_ok());
+
+        let new_root = taffy.new_leaf(Style::default()).unwrap();
+        assert_eq!(taffy.total_node_count(), 1);
+        assert!(taffy.get_node_context(new_root).is_none());
+    }
+
+    #[test]
+    fn iter_nodes_in_depth_first_and_breadth_first_order() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let grandchild0 = taffy.new_leaf(Style::default()).unwrap();
+        let grandchild1 = taffy.new_leaf(Style::default()).unwrap();
+        let child0 = taffy.new_with_children(Style::default(), &[grandchild0, grandchild1]).unwrap();
+        let grandchild2 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_with_children(Style::default(), &[grandchild2]).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        let depth_first: Vec<NodeId> = taffy.iter_nodes_depth_first(root).unwrap().collect();
+        assert_eq!(depth_first, [root, child0, grandchild0, grandchild1, child1, grandchild2]);
+        let breadth_first: Vec<NodeId> = taffy.iter_nodes_breadth_first(root).unwrap().collect();
+        assert_eq!(breadth_first, [root, child0, child1, grandchild0, grandchild1, grandchild2]);
+
+        let subtree: Vec<NodeId> = taffy.iter_nodes_depth_first(child0).unwrap().collect();
+        assert_eq!(subtree, [child0, grandchild0, grandchild1]);
+
+        taffy.remove(grandchild2).unwrap();
+        assert!(taffy.iter_nodes_breadth_first(grandchild2).is_err());
+    }
+
+    #[test]
+    fn disabled_nodes_are_skipped_and_keep_their_layout() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let first = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let grandchild = taffy.new_leaf(Style { size: Size::from_lengths(5.0, 5.0), ..Default::default() }).unwrap();
+        let tab = taffy
+            .new_with_children(Style { size: Size::from_lengths(20.0, 10.0), ..Default::default() }, &[grandchild])
+            .unwrap();
+        let last = taffy.new_leaf(Style { size: Size::from_lengths(30.0, 10.0), ..Default::default() }).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[first, tab, last]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(last).unwrap().location.x, 30.0);
+
+        taffy.set_node_enabled(tab, false).unwrap();
+        assert!(!taffy.is_node_enabled(tab).unwrap());
+        assert!(taffy.dirty(root).unwrap());
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(root).unwrap().size.width, 40.0);
+        assert_eq!(taffy.layout(last).unwrap().location.x, 10.0);
+        assert_eq!(taffy.layout(tab).unwrap().location.x, 10.0);
+        assert_eq!(taffy.layout(tab).unwrap().size.width, 20.0);
+        assert_eq!(taffy.layout(grandchild).unwrap().size.width, 5.0);
+
+        taffy.set_node_enabled(tab, true).unwrap();
+        assert!(!taffy.dirty(tab).unwrap());
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(last).unwrap().location.x, 30.0);
+
+        taffy.set_node_enabled(tab, false).unwrap();
+        taffy.remove(tab).unwrap();
+        assert!(!taffy.disabled_child_counts.contains_key(root.into()));
+    }
+
+    #[test]
+    fn disabled_child_counts_follow_the_disabled_node() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let other = taffy.new_leaf(Style { size: Size::from_lengths(20.0, 10.0), ..Default::default() }).unwrap();
+        let first = taffy.new_with_children(Style::default(), &[leaf, other]).unwrap();
+        let second = taffy.new_with_children(Style::default(), &[]).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[first, second]).unwrap();
+        let disabled_child_count =
+            |taffy: &TaffyTree<()>, node: NodeId| taffy.disabled_child_counts.get(node.into()).copied().unwrap_or(0);
+
+        taffy.set_node_enabled(leaf, false).unwrap();
+        assert_eq!(disabled_child_count(&taffy, first), 1);
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(first).unwrap().size.width, 20.0);
+
+        taffy.reparent(leaf, second, 0).unwrap();
+        assert_eq!((disabled_child_count(&taffy, first), disabled_child_count(&taffy, second)), (0, 1));
+        taffy.set_children(second, &[]).unwrap();
+        assert_eq!(disabled_child_count(&taffy, second), 0);
+        taffy.add_child(first, leaf).unwrap();
+        taffy.set_node_enabled(leaf, true).unwrap();
+        assert_eq!(disabled_child_count(&taffy, first), 0);
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(first).unwrap().size.width, 30.0);
+
+        taffy.set_node_enabled(first, false).unwrap();
+        taffy.remove(first).unwrap();
+        assert_eq!(disabled_child_count(&taffy, root), 0);
+    }
+
+    #[allow(clippy::vec_init_then_push)]
+    #[test]
+    fn test_children() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        let mut children = sys::Vec::new();
+        children.push(child0);
+        children.push(child1);
+
+        let children_result = taffy.children(node).unwrap();
+        assert_eq!(children_result, children);
+
+        assert!(taffy.children(child0).unwrap().is_empty());
+    }
+    #[test]
+    fn test_set_style() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        assert_eq!(taffy.style(node).unwrap().display, Display::Flex);
+
+        taffy.set_style(node, Style { display: Display::None, ..Style::default() }).unwrap();
+        assert_eq!(taffy.style(node).unwrap().display, Display::None);
+    }
+    #[test]
+    fn test_style() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let style = Style { display: Display::None, flex_direction: FlexDirection::RowReverse, ..Default::default() };
+
+        let node = taffy.new_leaf(style.clone()).unwrap();
+
+        let res = taffy.style(node);
+        assert!(res.is_ok());
+        assert!(res.unwrap() == &style);
+    }
+    #[test]
+    fn test_layout() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let node = taffy.new_leaf(Style::default()).unwrap();
+
+        // TODO: Improve this test?
+        let res = taffy.layout(node);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_mark_dirty() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child0 = taffy.new_leaf(Style::default()).unwrap();
+        let child1 = taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.dirty(child0), Ok(false));
+        assert_eq!(taffy.dirty(child1), Ok(false));
+        assert_eq!(taffy.dirty(node), Ok(false));
+
+        taffy.mark_dirty(node).unwrap();
+        assert_eq!(taffy.dirty(child0), Ok(false));
+        assert_eq!(taffy.dirty(child1), Ok(false));
+        assert_eq!(taffy.dirty(node), Ok(true));
+
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        taffy.mark_dirty(child0).unwrap();
+        assert_eq!(taffy.dirty(child0), Ok(true));
+        assert_eq!(taffy.dirty(child1), Ok(false));
+        assert_eq!(taffy.dirty(node), Ok(true));
+    }
+
+    #[test]
+    fn compute_fit_layout_shrinks_to_content() {
+        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
+        let child = taffy.new_leaf_with_context(Style::default(), Size { width: 80.0, height: 30.0 }).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[child]).unwrap();
+
+        let size = taffy
+            .compute_fit_layout_with_measure(root, Size { width: 500.0, height: 500.0 }, size_measure_function)
+            .unwrap();
+        assert_eq!(size, Size { width: 80.0, height: 30.0 });
+        assert_eq!(taffy.layout(root).unwrap().size, size);
+    }
+
+    #[test]
+    fn compute_fit_layout_clamps_to_max_size() {
+        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
+        let child = taffy.new_leaf_with_context(Style::default(), Size { width: 800.0, height: 30.0 }).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[child]).unwrap();
+
+        let size = taffy
+            .compute_fit_layout_with_measure(root, Size { width: 200.0, height: 100.0 }, size_measure_function)
+            .unwrap();
+        assert_eq!(size, Size { width: 200.0, height: 30.0 });
+        assert_eq!(taffy.layout(root).unwrap().size, size);
+    }
+
+    #[test]
+    fn compute_fit_layout_rejects_removed_nodes() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        taffy.remove(node).unwrap();
+
+        let max_size = Size { width: 100.0, height: 100.0 };
+        assert_eq!(taffy.compute_fit_layout(node, max_size), Err(TaffyError::InvalidInputNode(node)));
+    }
+
+    #[test]
+    fn computed_direction_is_inherited_from_ancestors() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style::default()).unwrap();
+        let ltr_leaf = taffy.new_leaf(Style { direction: Direction::Ltr, ..Default::default() }).unwrap();
+        let middle = taffy.new_with_children(Style::default(), &[leaf, ltr_leaf]).unwrap();
+        let root =
+            taffy.new_with_children(Style { direction: Direction::Rtl, ..Default::default() }, &[middle]).unwrap();
+
+        assert_eq!(taffy.computed_direction(root).unwrap(), Direction::Rtl);
+        assert_eq!(taffy.computed_direction(middle).unwrap(), Direction::Rtl);
+        assert_eq!(taffy.computed_direction(leaf).unwrap(), Direction::Rtl);
+        assert_eq!(taffy.computed_direction(ltr_leaf).unwrap(), Direction::Ltr);
+
+        taffy.remove_child(root, middle).unwrap();
+        assert_eq!(taffy.computed_direction(leaf).unwrap(), Direction::Ltr);
+    }
+
+    #[test]
+    fn inherited_properties_resolve_independently() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style { direction: Direction::Rtl, ..Default::default() }).unwrap();
+        let middle = taffy.new_with_children(Style::default(), &[leaf]).unwrap();
+        let root = taffy
+            .new_with_children(Style { writing_mode: WritingMode::VerticalRl, ..Default::default() }, &[middle])
+            .unwrap();
+
+        let expected = InheritedStyle { direction: Direction::Rtl, writing_mode: WritingMode::VerticalRl };
+        assert_eq!(taffy.computed_inherited_style(leaf).unwrap(), expected);
+        assert_eq!(taffy.computed_writing_mode(middle).unwrap(), WritingMode::VerticalRl);
+        assert_eq!(taffy.computed_inherited_style(root).unwrap().direction, Direction::Ltr);
+        assert_eq!(taffy.inspect(leaf).unwrap().resolved_style.writing_mode, WritingMode::VerticalRl);
+    }
+
+    #[test]
+    fn cascade_inherited_styles_visits_descendants_with_resolved_properties() {
+        let mut taffy: TaffyTree<InheritedStyle> = TaffyTree::new();
+        let leaf = taffy.new_leaf_with_context(Style::default(), InheritedStyle::INITIAL).unwrap();
+        let vertical_leaf = taffy
+            .new_leaf_with_context(
+                Style { writing_mode: WritingMode::VerticalLr, ..Default::default() },
+                InheritedStyle::INITIAL,
+            )
+            .unwrap();
+        let middle = taffy.new_with_children(Style::default(), &[leaf, vertical_leaf]).unwrap();
+        let root =
+            taffy.new_with_children(Style { direction: Direction::Rtl, ..Default::default() }, &[middle]).unwrap();
+
+        let mut visited = Vec::new();
+        taffy
+            .cascade_inherited_styles(root, |node, inherited_style, context| {
+                visited.push(node);
+                if let Some(context) = context {
+                    *context = inherited_style;
+                }
+            })
+            .unwrap();
+        assert_eq!(visited, [root, middle, leaf, vertical_leaf]);
+        assert_eq!(
+            *taffy.get_node_context(leaf).unwrap(),
+            InheritedStyle { direction: Direction::Rtl, writing_mode: WritingMode::HorizontalTb }
+        );
+        assert_eq!(
+            *taffy.get_node_context(vertical_leaf).unwrap(),
+            InheritedStyle { direction: Direction::Rtl, writing_mode: WritingMode::VerticalLr }
+        );
+
+        // Cascading from a descendant starts from the properties it inherits from its ancestors
+        let mut visited = Vec::new();
+        taffy
+            .cascade_inherited_styles(middle, |node, inherited_style, _| visited.push((node, inherited_style)))
+            .unwrap();
+        assert_eq!(visited[0], (middle, InheritedStyle { direction: Direction::Rtl, ..InheritedStyle::INITIAL }));
+    }
+
+    #[test]
+    fn shared_style_is_copied_on_write() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let handle = taffy.create_shared_style(Style { flex_grow: 1.0, ..Default::default() });
+        let node1 = taffy.new_leaf(Style::default()).unwrap();
+        let node2 = taffy.new_leaf(Style::default()).unwrap();
+        taffy.set_node_style_handle(node1, handle).unwrap();
+        taffy.set_node_style_handle(node2, handle).unwrap();
+
+        assert_eq!(taffy.node_style_handle(node1), Ok(Some(handle)));
+        assert_eq!(taffy.style(node2).unwrap().flex_grow, 1.0);
+
+        // Updating the shared style affects every node using it
+        taffy.set_shared_style(handle, Style { flex_grow: 2.0, ..Default::default() }).unwrap();
+        assert_eq!(taffy.style(node1).unwrap().flex_grow, 2.0);
+        assert_eq!(taffy.style(node2).unwrap().flex_grow, 2.0);
+
+        // Mutating a single node detaches it from the shared style
+        taffy.try_style_mut(node1).unwrap().flex_grow = 3.0;
+        assert_eq!(taffy.node_style_handle(node1), Ok(None));
+        assert_eq!(taffy.style(node1).unwrap().flex_grow, 3.0);
+        assert_eq!(taffy.style(node2).unwrap().flex_grow, 2.0);
+        assert_eq!(taffy.shared_style(handle).unwrap().flex_grow, 2.0);
+
+        // Nodes keep a removed shared style, but the handle can no longer be used
+        taffy.remove_shared_style(handle).unwrap();
+        assert_eq!(taffy.style(node2).unwrap().flex_grow, 2.0);
+        assert_eq!(taffy.set_node_style_handle(node1, handle), Err(TaffyError::InvalidStyleHandle(handle)));
+        taffy.remove(node2).unwrap();
+        assert_eq!(taffy.styles.len(), 1);
+    }
+
+    #[test]
+    fn edit_style_only_copies_shared_styles_and_marks_dirty_when_changed() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let handle = taffy.create_shared_style(Style { flex_grow: 1.0, ..Default::default() });
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        taffy.set_node_style_handle(node, handle).unwrap();
+        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+
+        let set_flex_grow = |value: f32| {
+            move |style: &mut Style| {
+                let changed = style.flex_grow != value;
+                style.flex_grow = value;
+                changed
+            }
+        };
+        assert_eq!(taffy.edit_style(node, set_flex_grow(1.0)), Ok(false));
+        assert_eq!(taffy.node_style_handle(node), Ok(Some(handle)));
+        assert!(!taffy.dirty(node).unwrap());
+
+        assert_eq!(taffy.edit_style(node, set_flex_grow(2.0)), Ok(true));
+        assert_eq!(taffy.node_style_handle(node), Ok(None));
+        assert_eq!(taffy.style(node).unwrap().flex_grow, 2.0);
+        assert_eq!(taffy.shared_style(handle).unwrap().flex_grow, 1.0);
+        assert!(taffy.dirty(node).unwrap());
+    }
+
+    #[test]
+    fn shared_style_handles_are_validated_before_use() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let handle = taffy.create_shared_style(Style::default());
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        let removed = taffy.new_leaf(Style::default()).unwrap();
+        taffy.remove(removed).unwrap();
+
+        assert_eq!(taffy.set_node_style_handle(removed, handle), Err(TaffyError::InvalidInputNode(removed)));
+        assert_eq!(taffy.node_style_handle(removed), Err(TaffyError::InvalidInputNode(removed)));
+        assert_eq!(taffy.styles[handle.into()].node_count, 0);
+
+        // Releasing the handle removes the style, as no node was assigned it
+        taffy.remove_shared_style(handle).unwrap();
+        assert_eq!(taffy.set_node_style_handle(node, handle), Err(TaffyError::InvalidStyleHandle(handle)));
+        assert_eq!(taffy.node_style_handle(node), Ok(None));
+        assert_eq!(taffy.styles.len(), 1);
+    }
+
+    #[test]
+    fn set_shared_style_only_marks_its_users_as_dirty() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let handle = taffy.create_shared_style(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() });
+        let user = taffy.new_leaf(Style::default()).unwrap();
+        let former_user = taffy.new_leaf(Style::default()).unwrap();
+        let other = taffy.new_leaf(Style::default()).unwrap();
+        taffy.set_node_style_handle(user, handle).unwrap();
+        taffy.set_node_style_handle(user, handle).unwrap();
+        taffy.set_node_style_handle(former_user, handle).unwrap();
+        taffy.set_style(former_user, Style::default()).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[user, former_user, other]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.styles[handle.into()].users, [DefaultKey::from(user)]);
+
+        taffy.set_shared_style(handle, Style { size: Size::from_lengths(20.0, 20.0), ..Default::default() }).unwrap();
+        assert!(taffy.dirty(user).unwrap());
+        assert!(taffy.dirty(root).unwrap());
+        assert!(!taffy.dirty(former_user).unwrap());
+        assert!(!taffy.dirty(other).unwrap());
+    }
+
+    #[test]
+    fn style_command_buffer_is_applied_atomically() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let handle = taffy.create_shared_style(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() });
+        let node1 = taffy.new_leaf(Style::default()).unwrap();
+        let node2 = taffy.new_leaf(Style::default()).unwrap();
+        taffy.set_node_style_handle(node2, handle).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[node1, node2]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        let mut commands = StyleCommandBuffer::new();
+        commands.push(node1, StyleCommand::Width(length(20.0)));
+        commands.push(node2, StyleCommand::Width(length(10.0)));
+        commands.push(node1, StyleCommand::MarginLeft(length(5.0)));
+        commands.push(node1, StyleCommand::Width(length(30.0)));
+        let removed = taffy.new_leaf(Style::default()).unwrap();
+        taffy.remove(removed).unwrap();
+        commands.push(removed, StyleCommand::Height(length(10.0)));
+        assert_eq!(taffy.apply(&mut commands), Err(TaffyError::InvalidInputNode(removed)));
+        assert_eq!(commands.len(), 5);
+        assert_eq!(taffy.style(node1).unwrap().size.width, Dimension::Auto);
+        assert!(!taffy.dirty(root).unwrap());
+
+        commands.commands.pop();
+        taffy.apply(&mut commands).unwrap();
+        assert!(commands.is_empty());
+        assert_eq!(taffy.style(node1).unwrap().size.width, length(30.0));
+        assert_eq!(taffy.style(node1).unwrap().margin.left, length(5.0));
+        assert!(taffy.dirty(node1).unwrap());
+        assert!(taffy.dirty(root).unwrap());
+
+        // Setting a property to its current value neither marks the node dirty nor copies its shared style
+        assert!(!taffy.dirty(node2).unwrap());
+        assert_eq!(taffy.node_style_handle(node2), Ok(Some(handle)));
+    }
+
+    #[test]
+    fn set_length_fast_keeps_measurements_when_inset_changes() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let text = taffy.new_leaf_with_context(Style::default(), ()).unwrap();
+        let moving = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let container = taffy.new_with_children(Style::default(), &[text, moving]).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[container]).unwrap();
+        // Lays the tree out, returning the number of times the text was measured
+        let compute_layout = |taffy: &mut TaffyTree<()>| {
+            let mut measure_count = 0;
+            taffy
+                .compute_layout_with_measure(root, Size::MAX_CONTENT, |_, _, node, _, _| {
+                    measure_count += usize::from(node == text);
+                    Size { width: 20.0, height: 10.0 }
+                })
+                .unwrap();
+            measure_count
+        };
+        assert!(compute_layout(&mut taffy) > 0);
+
+        taffy.set_length_fast(moving, LengthProperty::InsetLeft, 5.0).unwrap();
+        assert_eq!(taffy.style(moving).unwrap().inset.left, length(5.0));
+        assert!(taffy.dirty(moving).unwrap());
+        assert!(taffy.dirty(container).unwrap());
+        assert!(taffy.nodes[container.into()].cache.measure_entry_count() > 0);
+        assert_eq!(compute_layout(&mut taffy), 0);
+        assert!(!taffy.dirty(container).unwrap());
+        assert_eq!(taffy.layout(moving).unwrap().location.x, 25.0);
+        taffy.set_length_fast(moving, LengthProperty::InsetLeft, 5.0).unwrap();
+        assert!(taffy.nodes[container.into()].cache.has_final_layout());
+
+        taffy.set_length_fast(moving, LengthProperty::Width, 30.0).unwrap();
+        assert!(taffy.dirty(moving).unwrap());
+        assert!(taffy.dirty(container).unwrap());
+        compute_layout(&mut taffy);
+        assert_eq!(taffy.layout(container).unwrap().size.width, 50.0);
+    }
+
+    #[test]
+    #[cfg(feature = "flexbox")]
+    fn layout_limits_skip_nodes_once_exceeded() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf_style = Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() };
+        let leaves: Vec<NodeId> = (0..10).map(|_| taffy.new_leaf(leaf_style.clone()).unwrap()).collect();
+        let root = taffy
+            .new_with_children(Style { flex_direction: FlexDirection::Column, ..Default::default() }, &leaves)
+            .unwrap();
+
+        taffy.set_layout_limits(Some(LayoutLimits { max_node_visits: Some(5), ..Default::default() }));
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        let skipped = taffy.skipped_nodes().to_vec();
+        assert!(!skipped.is_empty());
+        assert!(skipped.iter().all(|node| leaves.contains(node)));
+
+        // Skipped nodes are laid out again by the next layout
+        taffy.set_layout_limits(Some(LayoutLimits { max_node_visits: Some(1000), ..Default::default() }));
+        assert!(skipped.iter().all(|&node| taffy.dirty(node).unwrap()));
+        taffy.compute_dirty_layouts().unwrap();
+        assert!(taffy.skipped_nodes().is_empty());
+        for (index, &leaf) in leaves.iter().enumerate() {
+            let layout = taffy.layout(leaf).unwrap();
+            assert_eq!((layout.location.y, layout.size), (10.0 * index as f32, Size { width: 10.0, height: 10.0 }));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "inline_layout")]
+    fn inline_containers_place_their_children_on_the_formatters_lines() {
+        use crate::compute::LineBoxItem;
+
+        /// Lays out the width of text stored in the node context, followed by the atoms, on a single line
+        #[derive(Clone)]
+        struct TextThenAtoms;
+        impl InlineLayout<f32> for TextThenAtoms {
+            fn layout_lines(
+                &mut self,
+                _: NodeId,
+                text_width: Option<&mut f32>,
+                _: &Style,
+                input: InlineFormatInput<'_>,
+            ) -> Vec<LineBox> {
+                let text_width = text_width.map_or(0.0, |width| *width);
+                let mut line = LineBox {
+                    location: Point::ZERO,
+                    size: Size { width: text_width, height: 16.0 },
+                    baseline: 12.0,
+                    items: Vec::new(),
+                };
+                for (index, atom) in input.atoms.iter().enumerate() {
+                    let offset = Point { x: line.size.width, y: line.size.height - atom.size.height };
+                    line.items.push(LineBoxItem { atom: index, offset });
+                    line.size.width += atom.size.width;
+                }
+                vec![line]
+            }
+        }
+
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        let icon = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let hidden = taffy.new_leaf(Style { display: Display::None, ..Default::default() }).unwrap();
+        let badge = taffy
+            .new_leaf(Style {
+                position: Position::Absolute,
+                inset: Rect { left: auto(), right: length(0.0), top: length(0.0), bottom: auto() },
+                size: Size::from_lengths(4.0, 4.0),
+                ..Default::default()
+            })
+            .unwrap();
+        let text = taffy
+            .new_with_children(
+                Style { display: Display::Inline, padding: Rect::length(2.0), ..Default::default() },
+                &[icon, hidden, badge],
+            )
+            .unwrap();
+        taffy.set_node_context(text, Some(50.0)).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[text]).unwrap();
+
+        taffy.set_inline_layout(TextThenAtoms);
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(text).unwrap().size, Size { width: 64.0, height: 20.0 });
+        assert_eq!(taffy.layout(icon).unwrap().location, Point { x: 52.0, y: 8.0 });
+        assert_eq!(taffy.layout(hidden).unwrap().size, Size::ZERO);
+        // Absolutely positioned children aren't placed on the lines
+        assert_eq!(taffy.layout(badge).unwrap().location, Point { x: 60.0, y: 0.0 });
+
+        // The formatter is also used when only the modified nodes are laid out again
+        taffy.set_node_context(text, Some(30.0)).unwrap();
+        taffy.compute_dirty_layouts().unwrap();
+        assert_eq!(taffy.layout(text).unwrap().size, Size { width: 44.0, height: 20.0 });
+        assert_eq!(taffy.layout(icon).unwrap().location, Point { x: 32.0, y: 8.0 });
+
+        // Without a formatter, only the children are placed on lines
+        taffy.clear_inline_layout();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(text).unwrap().size, Size { width: 14.0, height: 14.0 });
+        assert_eq!(taffy.layout(icon).unwrap().location, Point { x: 2.0, y: 2.0 });
+    }
+
+    #[test]
+    fn tree_measure_functions_can_read_descendants() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let inline_box = taffy.new_leaf(Style { size: Size::from_lengths(30.0, 20.0), ..Default::default() }).unwrap();
+        let text = taffy.new_with_children(Style::default(), &[inline_box]).unwrap();
+        taffy.set_node_enabled(inline_box, false).unwrap();
+        taffy.set_node_context(text, Some(())).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[text]).unwrap();
+
+        // The text is sized to fit the inline boxes stored as its (disabled) children
+        let measure = |_: Size<Option<f32>>,
+                       _: Size<AvailableSpace>,
+                       node: NodeId,
+                       _: Option<&mut ()>,
+                       _: &Style,
+                       tree: MeasureTree<'_>| {
+            // Nodes outside of the measured node can't be read
+            assert!(tree.style(root).is_none() && tree.children(root).is_empty() && tree.layout(root).is_none());
+            let length = |dimension| match dimension {
+                Dimension::Length(value) => value,
+                _ => 0.0,
+            };
+            tree.children(node)
+                .iter()
+                .filter_map(|&child| tree.style(child))
+                .map(|style| Size { width: length(style.size.width), height: length(style.size.height) })
+                .fold(Size::ZERO, |total, size| Size {
+                    width: total.width + size.width,
+                    height: total.height.max(size.height),
+                })
+        };
+        taffy.compute_layout_with_tree_measure(root, Size::MAX_CONTENT, measure).unwrap();
+        assert_eq!(taffy.layout(text).unwrap().size, Size { width: 30.0, height: 20.0 });
+
+        // The styles read are the ones the nodes are laid out with
+        taffy.set_node_forced_size(inline_box, Some(Size { width: 40.0, height: 25.0 })).unwrap();
+        taffy.compute_dirty_layouts_with_tree_measure(measure).unwrap();
+        assert_eq!(taffy.layout(text).unwrap().size, Size { width: 40.0, height: 25.0 });
+    }
+
+    #[test]
+    #[cfg(all(feature = "flexbox", feature = "grid"))]
+    fn layout_stats_count_the_work_done_by_the_last_layout() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let text = taffy.new_leaf_with_context(Style::default(), ()).unwrap();
+        let cell = taffy.new_leaf(Style::default()).unwrap();
+        let grid = taffy.new_with_children(Style { display: Display::Grid, ..Default::default() }, &[cell]).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[text, grid]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.last_layout_stats(), None);
+
+        taffy.enable_layout_stats();
+        taffy.mark_dirty(text).unwrap();
+        taffy.mark_dirty(cell).unwrap();
+        let mut measure_calls = 0;
+        taffy
+            .compute_layout_with_measure(root, Size::MAX_CONTENT, |_, _, _, _, _| {
+                measure_calls += 1;
+                Size { width: 10.0, height: 10.0 }
+            })
+            .unwrap();
+        let stats = taffy.last_layout_stats().unwrap();
+        assert_eq!(stats.measure_calls, measure_calls);
+        assert_eq!(stats.node_visits, stats.cache_hits + stats.cache_misses);
+        assert!(stats.cache_misses >= 4, "{stats:?}");
+        assert!(stats.flex_passes >= 1, "{stats:?}");
+        assert!(stats.grid_track_sizing_passes >= 2, "{stats:?}");
+        assert!(stats.total_time() >= stats.time_in(LayoutAlgorithm::Flexbox));
+
+        // Nothing is laid out again when nothing has changed
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        let stats = taffy.last_layout_stats().unwrap();
+        assert_eq!((stats.cache_misses, stats.measure_calls, stats.flex_passes), (0, 0, 0));
+
+        taffy.disable_layout_stats();
+        assert_eq!(taffy.last_layout_stats(), None);
+    }
+
+    /// Data which only some nodes have belongs in a sparse map on the tree rather than in [`NodeData`], which every
+    /// node pays for. Update the size here only when adding data which (nearly) every node needs.
+    #[test]
+    #[cfg(all(feature = "std", feature = "grid", feature = "content_size"))]
+    fn node_data_size_is_pinned() {
+        assert_eq!(core::mem::size_of::<NodeData>(), 1072);
+    }
+
+    #[test]
+    fn memory_usage_counts_shared_styles_once() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let handle = taffy.create_shared_style(Style::default());
+        for _ in 0..10 {
+            let node = taffy.new_leaf(Style::default()).unwrap();
+            taffy.set_node_style_handle(node, handle).unwrap();
+        }
+
+        let usage = taffy.memory_usage();
+        assert_eq!(usage.node_count, 10);
+        assert_eq!(usage.style_count, 1);
+        assert!(usage.style_bytes >= core::mem::size_of::<Style>());
+        assert_eq!(usage.total_bytes(), usage.node_bytes + usage.style_bytes + usage.context_bytes);
     }
 
     #[test]
-    fn test_with_capacity() {
-        const CAPACITY: usize = 8;
-        let taffy: TaffyTree<()> = TaffyTree::with_capacity(CAPACITY);
+    fn inspect_reports_resolved_style_and_cache_state() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy
+            .new_leaf(Style { size: Size { width: percent(0.5), height: length(10.0) }, ..Default::default() })
+            .unwrap();
+        let root = taffy
+            .new_with_children(
+                Style { size: Size::from_lengths(200.0, 100.0), padding: length(10.0), ..Default::default() },
+                &[child],
+            )
+            .unwrap();
 
-        assert!(taffy.children.capacity() >= CAPACITY);
-        assert!(taffy.parents.capacity() >= CAPACITY);
-        assert!(taffy.nodes.capacity() >= CAPACITY);
+        let inspection = taffy.inspect(child).unwrap();
+        assert!(inspection.cache.is_dirty);
+        assert!(!inspection.cache.has_final_layout);
+
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        let inspection = taffy.inspect(child).unwrap();
+        assert_eq!(inspection.parent, Some(root));
+        assert_eq!(inspection.algorithm, LayoutAlgorithm::Leaf);
+        assert_eq!(inspection.resolved_style.size, Size { width: Some(90.0), height: Some(10.0) });
+        assert_eq!(inspection.resolved_style.direction, Direction::Ltr);
+        assert_eq!(inspection.layout.size, Size { width: 90.0, height: 10.0 });
+        assert!(!inspection.cache.is_dirty);
+        assert!(inspection.cache.has_final_layout);
+
+        let inspection = taffy.inspect(root).unwrap();
+        assert_eq!(inspection.children, [child]);
+        assert_eq!(inspection.algorithm, LayoutAlgorithm::Flexbox);
+        assert_eq!(inspection.resolved_style.padding, Rect { left: 10.0, right: 10.0, top: 10.0, bottom: 10.0 });
+
+        taffy.try_style_mut(root).unwrap().display = Display::None;
+        assert_eq!(taffy.inspect(child).unwrap().algorithm, LayoutAlgorithm::Hidden);
     }
 
     #[test]
-    fn test_new_leaf() {
+    #[cfg(feature = "grid")]
+    fn inspect_reports_grid_occupancy_of_sparse_grid() {
+        use crate::style_helpers::line;
+
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let far_item =
+            taffy.new_leaf(Style { grid_row: line(1000), grid_column: line(1000), ..Default::default() }).unwrap();
+        let auto_item = taffy.new_leaf(Style::default()).unwrap();
+        let grid = taffy
+            .new_with_children(Style { display: Display::Grid, ..Default::default() }, &[far_item, auto_item])
+            .unwrap();
+        assert_eq!(taffy.inspect(grid).unwrap().grid_occupancy, None);
+        taffy.compute_layout(grid, Size::MAX_CONTENT).unwrap();
+
+        let stats = taffy.inspect(grid).unwrap().grid_occupancy.unwrap();
+        assert_eq!(stats.row_count, 1000);
+        assert_eq!(stats.column_count, 1000);
+        assert_eq!(stats.occupied_cell_count, 2);
+        assert_eq!(stats.interval_count, 2);
+        // Far less than one byte per cell
+        assert!(stats.allocated_bytes < 64 * 1024, "{}", stats.allocated_bytes);
+        assert_eq!(taffy.inspect(far_item).unwrap().grid_occupancy, None);
+    }
 
-        let res = taffy.new_leaf(Style::default());
-        assert!(res.is_ok());
-        let node = res.unwrap();
+    /// Builds a tree with a fixed size container (with the given display) and an auto-sized container, each holding two
+    /// leaves
+    fn dirty_layouts_tree(fixed_display: Display) -> (TaffyTree<()>, NodeId, [NodeId; 6]) {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = |taffy: &mut TaffyTree<()>, width: f32| {
+            taffy
+                .new_leaf(Style { size: Size::from_lengths(width, 10.3), flex_grow: 1.0, ..Default::default() })
+                .unwrap()
+        };
+        let fixed_leaves = [leaf(&mut taffy, 20.0), leaf(&mut taffy, 30.0)];
+        let auto_leaves = [leaf(&mut taffy, 20.0), leaf(&mut taffy, 30.0)];
+        let fixed = taffy
+            .new_with_children(
+                Style {
+                    display: fixed_display,
+                    size: Size::from_lengths(100.0, 50.0),
+                    padding: length(3.0),
+                    ..Default::default()
+                },
+                &fixed_leaves,
+            )
+            .unwrap();
+        let auto = taffy.new_with_children(Style::default(), &auto_leaves).unwrap();
+        let root = taffy
+            .new_with_children(
+                Style {
+                    flex_direction: FlexDirection::Column,
+                    size: Size::from_lengths(400.0, 400.0),
+                    padding: length(1.7),
+                    ..Default::default()
+                },
+                &[fixed, auto],
+            )
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        (taffy, root, [fixed, auto, fixed_leaves[0], fixed_leaves[1], auto_leaves[0], auto_leaves[1]])
+    }
 
-        // node should be in the taffy tree and have no children
-        assert!(taffy.child_count(node) == 0);
+    /// Asserts that laying out only the dirty parts of `taffy` produces the same layouts as laying out the whole tree
+    fn assert_dirty_layouts_match_full_layout(taffy: &mut TaffyTree<()>, root: NodeId) {
+        let mut expected = taffy.clone();
+        expected.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        taffy.compute_dirty_layouts().unwrap();
+        assert!(!taffy.dirty(root).unwrap());
+        for (node, _) in expected.nodes.iter() {
+            let node = expected.node_id(node);
+            assert_eq!(taffy.layout(node).unwrap(), expected.layout(node).unwrap(), "{node:?}");
+            assert_eq!(taffy.unrounded_layout(node).unwrap(), expected.unrounded_layout(node).unwrap(), "{node:?}");
+        }
     }
 
     #[test]
-    fn new_leaf_with_context() {
-        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
+    fn compute_dirty_layouts_matches_full_layout() {
+        // A change contained within a fixed size container
+        let (mut taffy, root, [_, _, fixed_leaf, ..]) = dirty_layouts_tree(Display::Flex);
+        taffy.set_style(fixed_leaf, Style { size: Size::from_lengths(45.1, 10.0), ..Default::default() }).unwrap();
+        assert_dirty_layouts_match_full_layout(&mut taffy, root);
+
+        // A change which resizes its auto-sized container, and so requires the root to be laid out again
+        let (mut taffy, root, [_, _, _, _, auto_leaf, _]) = dirty_layouts_tree(Display::Flex);
+        taffy.set_style(auto_leaf, Style { size: Size::from_lengths(20.0, 80.6), ..Default::default() }).unwrap();
+        assert_dirty_layouts_match_full_layout(&mut taffy, root);
+
+        // Changes to the children of a container, and to the style of a container itself
+        let (mut taffy, root, [fixed, auto, fixed_leaf, ..]) = dirty_layouts_tree(Display::Flex);
+        taffy.remove_child(fixed, fixed_leaf).unwrap();
+        let new_leaf = taffy.new_leaf(Style { size: Size::from_lengths(5.5, 5.5), ..Default::default() }).unwrap();
+        taffy.add_child(auto, new_leaf).unwrap();
+        taffy.try_style_mut(fixed).unwrap().margin = length(2.0);
+        assert_dirty_layouts_match_full_layout(&mut taffy, root);
+
+        // Changes within a hidden container
+        let (mut taffy, root, [_, _, fixed_leaf, ..]) = dirty_layouts_tree(Display::None);
+        taffy.set_style(fixed_leaf, Style { size: Size::from_lengths(45.1, 10.0), ..Default::default() }).unwrap();
+        assert_dirty_layouts_match_full_layout(&mut taffy, root);
+    }
 
-        let res = taffy.new_leaf_with_context(Style::default(), Size::ZERO);
-        assert!(res.is_ok());
-        let node = res.unwrap();
+    #[test]
+    fn compute_dirty_layouts_keeps_ancestors_of_contained_changes() {
+        let (mut taffy, root, [fixed, _, fixed_leaf, ..]) = dirty_layouts_tree(Display::Flex);
+        let root_output = taffy.last_final_layouts.get(root.into()).copied();
+        // Changing the height of the leaf would change the baseline of its container, so only its width is changed
+        taffy.set_style(fixed_leaf, Style { size: Size::from_lengths(45.1, 10.3), ..Default::default() }).unwrap();
+        assert!(taffy.dirty(root).unwrap());
+
+        taffy.compute_dirty_layouts().unwrap();
+        assert!(!taffy.dirty(fixed).unwrap());
+        assert!(!taffy.dirty(root).unwrap());
+        assert_eq!(taffy.last_final_layouts.get(root.into()).copied(), root_output);
+        assert_eq!(taffy.layout(fixed_leaf).unwrap().size.width, 45.0);
+    }
 
-        // node should be in the taffy tree and have no children
-        assert!(taffy.child_count(node) == 0);
+    #[test]
+    fn compute_dirty_layouts_measures_content_sized_parents_again() {
+        let measure = |known_dimensions: Size<Option<f32>>, _, _, width: Option<&mut f32>, _: &Style| {
+            known_dimensions.unwrap_or(Size { width: width.map_or(0.0, |width| *width), height: 10.0 })
+        };
+        let grow = Style { flex_grow: 1.0, ..Default::default() };
+
+        // The flex bases of `a` and `b` are the measured widths of their leaves
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        let a_leaf = taffy.new_leaf_with_context(grow.clone(), 100.0).unwrap();
+        let b_leaf = taffy.new_leaf_with_context(grow.clone(), 100.0).unwrap();
+        let a = taffy.new_with_children(grow.clone(), &[a_leaf]).unwrap();
+        let b = taffy.new_with_children(grow, &[b_leaf]).unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(400.0, 100.0), ..Default::default() }, &[a, b])
+            .unwrap();
+        taffy.compute_layout_with_measure(root, Size::MAX_CONTENT, measure).unwrap();
+        assert_eq!(taffy.layout(a).unwrap().size.width, 200.0);
+
+        // The final layout of `a` is unchanged with its previous width, but its flex basis grows
+        taffy.set_node_context(a_leaf, Some(150.0)).unwrap();
+        let mut expected = taffy.clone();
+        expected.compute_layout_with_measure(root, Size::MAX_CONTENT, measure).unwrap();
+        taffy.compute_dirty_layouts_with_measure(measure).unwrap();
+
+        assert_eq!(taffy.layout(a).unwrap().size.width, 225.0);
+        assert_eq!(taffy.layout(b).unwrap().size.width, 175.0);
+        for node in [root, a, b, a_leaf, b_leaf] {
+            assert_eq!(taffy.layout(node).unwrap(), expected.layout(node).unwrap());
+        }
     }
 
-    /// Test that new_with_children works as expected
     #[test]
-    fn test_new_with_children() {
+    fn compute_dirty_layouts_does_nothing_without_previous_layout() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
-
-        // node should have two children
-        assert_eq!(taffy.child_count(node), 2);
-        assert_eq!(taffy.children(node).unwrap()[0], child0);
-        assert_eq!(taffy.children(node).unwrap()[1], child1);
+        let node = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        taffy.compute_dirty_layouts().unwrap();
+        assert!(taffy.dirty(node).unwrap());
     }
 
     #[test]
-    fn remove_node_should_remove() {
+    #[cfg(all(feature = "flexbox", feature = "grid"))]
+    fn validate_tree_checks_item_properties_against_the_parent() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let flex_item = taffy.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+        let grid_item = taffy.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+        let grid = taffy
+            .new_with_children(
+                Style { display: Display::Grid, flex_wrap: FlexWrap::Wrap, ..Default::default() },
+                &[grid_item],
+            )
+            .unwrap();
+        let root = taffy.new_with_children(Style::default(), &[flex_item, grid]).unwrap();
+
+        assert_eq!(
+            taffy.validate_tree(root).unwrap(),
+            [
+                (grid, StyleWarning::IgnoredContainerProperty { property: "flex_wrap", display: Display::Grid }),
+                (grid_item, StyleWarning::IgnoredItemProperty { property: "flex_grow", parent_display: Display::Grid }),
+            ]
+        );
+        assert_eq!(taffy.validate_tree(flex_item).unwrap(), []);
+    }
 
-        let node = taffy.new_leaf(Style::default()).unwrap();
+    #[test]
+    #[cfg(all(feature = "flexbox", feature = "grid"))]
+    fn validate_tree_treats_the_default_style_as_unset() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        taffy.set_default_style(StyleDefaults::Yoga.style());
+        let grid_item = taffy.new_leaf(taffy.default_style()).unwrap();
+        let grid =
+            taffy.new_with_children(Style { display: Display::Grid, ..taffy.default_style() }, &[grid_item]).unwrap();
+
+        // The column direction and zero flex shrink of the defaults are not reported as being ignored by the grid
+        assert_eq!(taffy.validate_tree(grid).unwrap(), []);
+        assert_ne!(taffy.style(grid).unwrap().validate(), []);
+    }
 
-        let _ = taffy.remove(node).unwrap();
+    /// Returns the message that `f` panics with
+    #[cfg(feature = "debug_checks")]
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_err();
+        payload.downcast_ref::<String>().cloned().unwrap()
     }
 
     #[test]
-    fn remove_node_should_detach_hierarchy() {
+    #[cfg(feature = "debug_checks")]
+    fn debug_checks_report_the_path_of_a_node_with_an_invalid_style() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let first = taffy.new_leaf(Style::default()).unwrap();
+        let second = taffy.new_leaf(Style::default()).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[first, second]).unwrap();
 
-        // Build a linear tree layout: <0> <- <1> <- <2>
-        let node2 = taffy.new_leaf(Style::default()).unwrap();
-        let node1 = taffy.new_with_children(Style::default(), &[node2]).unwrap();
-        let node0 = taffy.new_with_children(Style::default(), &[node1]).unwrap();
+        let message = panic_message(|| {
+            taffy.set_style(second, Style { flex_grow: -1.0, ..Default::default() }).unwrap();
+        });
+        assert_eq!(
+            message,
+            format!("Invalid style on node {root:?} > [1] {second:?}:\n  `flex_grow` is negative (-1)")
+        );
+    }
 
-        // Both node0 and node1 should have 1 child nodes
-        assert_eq!(taffy.children(node0).unwrap().as_slice(), &[node1]);
-        assert_eq!(taffy.children(node1).unwrap().as_slice(), &[node2]);
+    #[test]
+    #[cfg(feature = "debug_checks")]
+    fn debug_checks_report_nan_layout_inputs() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let root = taffy.new_leaf(Style::default()).unwrap();
+
+        let message = panic_message(|| {
+            taffy
+                .compute_layout(
+                    root,
+                    Size { width: AvailableSpace::Definite(f32::NAN), height: AvailableSpace::MaxContent },
+                )
+                .unwrap();
+        });
+        assert_eq!(message, format!(
+                "Invalid layout inputs for node {root:?}:\n  `parent_size.width` is NaN\n  `available_space.width` is NaN"
+            ));
+    }
 
-        // Disconnect the tree: <0> <2>
-        let _ = taffy.remove(node1).unwrap();
+    /// Builds a row of text leaves (whose context is their text width) in an emptied tree, as an immediate-mode UI
+    /// would every frame
+    #[cfg(feature = "std")]
+    fn build_memoized_frame(taffy: &mut TaffyTree<f32>, text_widths: &[f32]) -> NodeId {
+        taffy.clear();
+        let leaves: Vec<NodeId> = text_widths
+            .iter()
+            .map(|&width| {
+                let leaf = taffy.new_leaf_with_context(Style::default(), width).unwrap();
+                taffy.set_content_hash(leaf, Some(width.to_bits() as u64)).unwrap();
+                leaf
+            })
+            .collect();
+        taffy.new_with_children(Style { size: length(200.0), ..Default::default() }, &leaves).unwrap()
+    }
 
-        // Both remaining nodes should have no child nodes
-        assert!(taffy.children(node0).unwrap().is_empty());
-        assert!(taffy.children(node2).unwrap().is_empty());
+    #[test]
+    fn content_version_changes_only_relayout_when_measurements_change() {
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        let text = taffy.new_leaf_with_context(Style::default(), 30.0).unwrap();
+        let sibling = taffy.new_leaf_with_context(Style::default(), 50.0).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[text, sibling]).unwrap();
+
+        let mut measured = Vec::new();
+        let mut compute = |taffy: &mut TaffyTree<f32>| {
+            measured.clear();
+            taffy
+                .compute_layout_with_measure(root, Size::MAX_CONTENT, |known, _, _, width, _| {
+                    let width = *width.unwrap();
+                    measured.push(width);
+                    Size { width: known.width.unwrap_or(width), height: known.height.unwrap_or(10.0) }
+                })
+                .unwrap();
+            measured.clone()
+        };
+        compute(&mut taffy);
+
+        // An edit which doesn't change the size of the leaf only measures the leaf again
+        taffy.set_node_content_version(text, 1).unwrap();
+        assert_eq!(taffy.node_content_version(text).unwrap(), 1);
+        assert!(!taffy.dirty(root).unwrap());
+        let measured = compute(&mut taffy);
+        #[cfg(not(feature = "debug_determinism"))]
+        assert!(measured.iter().all(|&width| width == 30.0));
+        assert!(!measured.is_empty());
+        assert!(!taffy.dirty(root).unwrap());
+
+        // Setting the same version again does nothing
+        taffy.set_node_content_version(text, 1).unwrap();
+        #[cfg(not(feature = "debug_determinism"))]
+        assert_eq!(compute(&mut taffy), Vec::<f32>::new());
+
+        // An edit which changes the size of the leaf lays out its ancestors again
+        *taffy.get_node_context_mut(text).unwrap() = 40.0;
+        taffy.set_node_content_version(text, 2).unwrap();
+        compute(&mut taffy);
+        assert_eq!(taffy.layout(text).unwrap().size.width, 40.0);
+        assert_eq!(taffy.layout(sibling).unwrap().location.x, 40.0);
     }
 
     #[test]
-    fn remove_last_node() {
-        let mut taffy: TaffyTree<()> = TaffyTree::new();
+    #[cfg(feature = "std")]
+    fn cache_memoization_reuses_measurements_of_rebuilt_nodes() {
+        let mut measured = Vec::new();
+        let mut compute_frame = |taffy: &mut TaffyTree<f32>, text_widths: &[f32]| {
+            let root = build_memoized_frame(taffy, text_widths);
+            measured.clear();
+            taffy
+                .compute_layout_with_measure(root, Size::MAX_CONTENT, |known, _, _, width, _| {
+                    let width = *width.unwrap();
+                    measured.push(width);
+                    Size { width: known.width.unwrap_or(width), height: known.height.unwrap_or(10.0) }
+                })
+                .unwrap();
+            let layouts: Vec<Layout> =
+                taffy.children(root).unwrap().iter().map(|&leaf| *taffy.layout(leaf).unwrap()).collect();
+            (layouts, measured.clone())
+        };
+
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        taffy.enable_cache_memoization();
+        let (first_layouts, first_measured) = compute_frame(&mut taffy, &[30.0, 50.0]);
+        assert!(first_measured.contains(&30.0) && first_measured.contains(&50.0));
+
+        // An identical tree is laid out without measuring its leaves again
+        let (layouts, measured) = compute_frame(&mut taffy, &[30.0, 50.0]);
+        assert_eq!(layouts, first_layouts);
+        #[cfg(not(feature = "debug_determinism"))]
+        assert_eq!(measured, Vec::<f32>::new());
+
+        // A leaf with a different content hash is measured again
+        let (layouts, measured) = compute_frame(&mut taffy, &[30.0, 70.0]);
+        assert_eq!(layouts[1].size.width, 70.0);
+        assert!(measured.contains(&70.0));
+
+        // Without memoization, everything is measured again
+        taffy.disable_cache_memoization();
+        let (layouts, measured) = compute_frame(&mut taffy, &[30.0, 70.0]);
+        assert_eq!(layouts[0], first_layouts[0]);
+        assert!(measured.contains(&30.0));
+    }
 
-        let parent = taffy.new_leaf(Style::default()).unwrap();
-        let child = taffy.new_leaf(Style::default()).unwrap();
-        taffy.add_child(parent, child).unwrap();
+    #[test]
+    #[cfg(feature = "std")]
+    fn nodes_with_unhashed_context_have_no_layout_key() {
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        taffy.enable_cache_memoization();
+        let hashed = taffy.new_leaf_with_context(Style::default(), 10.0).unwrap();
+        taffy.set_content_hash(hashed, Some(10)).unwrap();
+        let unhashed = taffy.new_leaf_with_context(Style::default(), 10.0).unwrap();
+        let parent = taffy.new_with_children(Style::default(), &[unhashed]).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[hashed, parent]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
 
-        taffy.remove(child).unwrap();
-        taffy.remove(parent).unwrap();
+        assert!(taffy.layout_key(hashed).unwrap().is_some());
+        assert_eq!(taffy.layout_key(unhashed).unwrap(), None);
+        assert_eq!(taffy.layout_key(parent).unwrap(), None);
+        assert_eq!(taffy.layout_key(root).unwrap(), None);
+
+        // Changing the content hash marks the node dirty, which clears its key until the next layout
+        taffy.set_content_hash(hashed, Some(11)).unwrap();
+        assert!(taffy.dirty(hashed).unwrap());
+        assert_eq!(taffy.layout_key(hashed).unwrap(), None);
     }
 
     #[test]
-    fn set_measure() {
-        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
-        let node = taffy.new_leaf_with_context(Style::default(), Size { width: 200.0, height: 200.0 }).unwrap();
-        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
-        assert_eq!(taffy.layout(node).unwrap().size.width, 200.0);
+    #[cfg(feature = "std")]
+    fn compute_layout_budgeted_resumes_until_complete() {
+        use std::time::Duration;
 
-        taffy.set_node_context(node, Some(Size { width: 100.0, height: 100.0 })).unwrap();
-        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
-        assert_eq!(taffy.layout(node).unwrap().size.width, 100.0);
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let children: Vec<NodeId> = (0..3)
+            .map(|_| taffy.new_leaf(Style { flex_grow: 1.0, min_size: length(10.0), ..Default::default() }).unwrap())
+            .collect();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(300.0, 100.0), ..Default::default() }, &children)
+            .unwrap();
+        let mut expected = taffy.clone();
+        expected.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        // With no budget, each call computes a single result before deferring the rest
+        let progress = taffy.compute_layout_budgeted(root, Size::MAX_CONTENT, Duration::ZERO).unwrap();
+        assert!(matches!(progress, LayoutProgress::Paused { .. }));
+
+        let mut progress = taffy.compute_layout_budgeted(root, Size::MAX_CONTENT, Duration::ZERO).unwrap();
+        while progress != LayoutProgress::Complete {
+            progress = taffy.compute_layout_budgeted(root, Size::MAX_CONTENT, Duration::ZERO).unwrap();
+        }
+
+        for &node in children.iter().chain([&root]) {
+            assert_eq!(taffy.layout(node).unwrap(), expected.layout(node).unwrap());
+        }
     }
 
     #[test]
-    fn set_measure_of_previously_unmeasured_node() {
-        let mut taffy: TaffyTree<Size<f32>> = TaffyTree::new();
-        let node = taffy.new_leaf(Style::default()).unwrap();
-        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
-        assert_eq!(taffy.layout(node).unwrap().size.width, 0.0);
-
-        taffy.set_node_context(node, Some(Size { width: 100.0, height: 100.0 })).unwrap();
-        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, size_measure_function).unwrap();
-        assert_eq!(taffy.layout(node).unwrap().size.width, 100.0);
+    #[cfg(feature = "std")]
+    fn layout_deadline_reads_the_clock_every_few_cache_misses() {
+        let mut deadline = LayoutDeadline::new(std::time::Instant::now());
+        assert!(!deadline.should_defer());
+
+        // The first miss after a node has been laid out reads the clock, and the next miss which does is a whole
+        // interval later
+        deadline.has_computed = true;
+        deadline.misses = 1;
+        for _ in 1..LayoutDeadline::CLOCK_CHECK_INTERVAL {
+            assert!(!deadline.should_defer());
+        }
+        assert!(deadline.should_defer());
+        assert!(deadline.should_defer());
     }
 
-    /// Test that adding `add_child()` works
     #[test]
-    fn add_child() {
+    #[cfg(feature = "std")]
+    fn compute_layout_budgeted_restarts_when_inputs_change() {
+        use std::time::Duration;
+
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let node = taffy.new_leaf(Style::default()).unwrap();
-        assert_eq!(taffy.child_count(node), 0);
+        let child = taffy.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+        let root = taffy
+            .new_with_children(
+                Style { size: Size { width: percent(1.0), height: percent(1.0) }, ..Default::default() },
+                &[child],
+            )
+            .unwrap();
 
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        taffy.add_child(node, child0).unwrap();
-        assert_eq!(taffy.child_count(node), 1);
+        let progress = taffy.compute_layout_budgeted(root, Size::MAX_CONTENT, Duration::ZERO).unwrap();
+        assert!(matches!(progress, LayoutProgress::Paused { .. }));
 
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        taffy.add_child(node, child1).unwrap();
-        assert_eq!(taffy.child_count(node), 2);
+        let available_space = Size { width: AvailableSpace::Definite(100.0), height: AvailableSpace::Definite(50.0) };
+        let progress = taffy.compute_layout_budgeted(root, available_space, Duration::ZERO).unwrap();
+        assert!(matches!(progress, LayoutProgress::Paused { .. }));
+
+        let progress = taffy.compute_layout_budgeted(root, available_space, Duration::MAX).unwrap();
+        assert_eq!(progress, LayoutProgress::Complete);
+        let mut expected = taffy.clone();
+        expected.compute_layout(root, available_space).unwrap();
+        assert_eq!(taffy.layout(child).unwrap(), expected.layout(child).unwrap());
+        assert_eq!(taffy.layout(child).unwrap().size.width, 100.0);
+
+        taffy.remove(child).unwrap();
+        assert!(taffy.compute_layout_budgeted(child, available_space, Duration::MAX).is_err());
     }
 
     #[test]
-    fn insert_child_at_index() {
-        let mut taffy: TaffyTree<()> = TaffyTree::new();
+    #[cfg(feature = "std")]
+    fn compute_layout_budgeted_measures_each_leaf_as_often_as_a_complete_layout() {
+        use std::time::Duration;
 
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let child2 = taffy.new_leaf(Style::default()).unwrap();
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        let mut leaves = Vec::new();
+        for width in [10.0, 20.0, 30.0, 40.0] {
+            leaves.push(taffy.new_leaf_with_context(Style { flex_grow: 1.0, ..Default::default() }, width).unwrap());
+        }
+        let rows: Vec<NodeId> = leaves
+            .chunks(2)
+            .map(|leaves| taffy.new_with_children(Style { flex_wrap: FlexWrap::Wrap, ..Default::default() }, leaves))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let root_style = Style {
+            flex_direction: FlexDirection::Column,
+            size: Size::from_lengths(50.0, 200.0),
+            ..Default::default()
+        };
+        let root = taffy.new_with_children(root_style, &rows).unwrap();
+
+        let measure_count = core::cell::Cell::new(0);
+        let mut measure = |known_dimensions: Size<Option<f32>>, _, _, width: Option<&mut f32>, _: &Style| {
+            measure_count.set(measure_count.get() + 1);
+            known_dimensions.unwrap_or(Size { width: *width.unwrap(), height: 10.0 })
+        };
+
+        let mut expected = taffy.clone();
+        expected.compute_layout_with_measure(root, Size::MAX_CONTENT, &mut measure).unwrap();
+        let complete_layout_measure_count = measure_count.take();
+
+        let mut calls = 0;
+        loop {
+            calls += 1;
+            let progress =
+                taffy.compute_layout_budgeted_with_measure(root, Size::MAX_CONTENT, Duration::ZERO, &mut measure);
+            if progress.unwrap() == LayoutProgress::Complete {
+                break;
+            }
+        }
 
-        let node = taffy.new_leaf(Style::default()).unwrap();
-        assert_eq!(taffy.child_count(node), 0);
+        // The final pass is served from the cache filled by the paused calls, so no measurement is repeated
+        assert!(calls > 1);
+        assert_eq!(measure_count.get(), complete_layout_measure_count);
+        for node in leaves.iter().chain(&rows).chain([&root]) {
+            assert_eq!(taffy.layout(*node).unwrap(), expected.layout(*node).unwrap());
+        }
+    }
 
-        taffy.insert_child_at_index(node, 0, child0).unwrap();
-        assert_eq!(taffy.child_count(node), 1);
-        assert_eq!(taffy.children(node).unwrap()[0], child0);
+    #[test]
+    fn freeze_captures_layouts_styles_and_hierarchy() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let first = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }).unwrap();
+        let second = taffy.new_leaf(Style { size: Size::from_lengths(30.0, 20.0), ..Default::default() }).unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(100.0, 100.0), ..Default::default() }, &[first, second])
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
 
-        taffy.insert_child_at_index(node, 0, child1).unwrap();
-        assert_eq!(taffy.child_count(node), 2);
-        assert_eq!(taffy.children(node).unwrap()[0], child1);
-        assert_eq!(taffy.children(node).unwrap()[1], child0);
+        let snapshot = taffy.freeze();
+        assert_eq!(snapshot.total_node_count(), 3);
+        assert_eq!(snapshot.children(root).unwrap(), [first, second]);
+        assert_eq!(snapshot.child_count(root).unwrap(), 2);
+        assert_eq!(snapshot.parent(second), Some(root));
+        assert_eq!(snapshot.parent(root), None);
+        assert_eq!(snapshot.style(second).unwrap(), taffy.style(second).unwrap());
+        assert_eq!(snapshot.layout(second).unwrap(), taffy.layout(second).unwrap());
+
+        // Changes to the tree are not reflected in the snapshot
+        taffy.set_style(second, Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        taffy.remove(first).unwrap();
+        assert_eq!(snapshot.layout(second).unwrap().size, Size { width: 30.0, height: 20.0 });
+        assert_eq!(snapshot.layout(first).unwrap().size, Size { width: 50.0, height: 50.0 });
+        assert_eq!(snapshot.children(root).unwrap(), [first, second]);
 
-        taffy.insert_child_at_index(node, 1, child2).unwrap();
-        assert_eq!(taffy.child_count(node), 3);
-        assert_eq!(taffy.children(node).unwrap()[0], child1);
-        assert_eq!(taffy.children(node).unwrap()[1], child2);
-        assert_eq!(taffy.children(node).unwrap()[2], child0);
+        let removed = taffy.new_leaf(Style::default()).unwrap();
+        assert!(snapshot.layout(removed).is_err());
     }
 
     #[test]
-    fn set_children() {
+    fn freeze_shares_styles_until_they_are_changed() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let node = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }).unwrap();
+        let tree_style = |taffy: &TaffyTree<()>| Arc::clone(&taffy.styles[taffy.nodes[node.into()].style].style);
 
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+        let snapshot = taffy.freeze();
+        assert!(Arc::ptr_eq(&snapshot.nodes[node.into()].style, &tree_style(&taffy)));
 
-        assert_eq!(taffy.child_count(node), 2);
-        assert_eq!(taffy.children(node).unwrap()[0], child0);
-        assert_eq!(taffy.children(node).unwrap()[1], child1);
+        taffy.try_style_mut(node).unwrap().size = Size::from_lengths(10.0, 10.0);
+        assert!(!Arc::ptr_eq(&snapshot.nodes[node.into()].style, &tree_style(&taffy)));
+        assert_eq!(snapshot.style(node).unwrap().size, Size::from_lengths(50.0, 50.0));
+        assert_eq!(taffy.style(node).unwrap().size, Size::from_lengths(10.0, 10.0));
+    }
 
-        let child2 = taffy.new_leaf(Style::default()).unwrap();
-        let child3 = taffy.new_leaf(Style::default()).unwrap();
-        taffy.set_children(node, &[child2, child3]).unwrap();
+    #[test]
+    fn snapshot_hit_test_returns_deepest_node() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let grandchild = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let first = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }).unwrap();
+        let second = taffy
+            .new_with_children(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }, &[grandchild])
+            .unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(100.0, 100.0), ..Default::default() }, &[first, second])
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
 
-        assert_eq!(taffy.child_count(node), 2);
-        assert_eq!(taffy.children(node).unwrap()[0], child2);
-        assert_eq!(taffy.children(node).unwrap()[1], child3);
+        let snapshot = taffy.freeze();
+        assert_eq!(snapshot.hit_test(root, Point { x: 25.0, y: 25.0 }), Some(first));
+        assert_eq!(snapshot.hit_test(root, Point { x: 55.0, y: 5.0 }), Some(grandchild));
+        assert_eq!(snapshot.hit_test(root, Point { x: 75.0, y: 25.0 }), Some(second));
+        assert_eq!(snapshot.hit_test(root, Point { x: 25.0, y: 75.0 }), Some(root));
+        assert_eq!(snapshot.hit_test(root, Point { x: 150.0, y: 25.0 }), None);
+        assert_eq!(snapshot.hit_test(second, Point { x: 55.0, y: 5.0 }), Some(grandchild));
     }
 
-    /// Test that removing a child works
     #[test]
-    fn remove_child() {
+    fn layout_offset_moves_node_without_invalidating_layout() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+        let grandchild = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let first = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }).unwrap();
+        let second = taffy
+            .new_with_children(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }, &[grandchild])
+            .unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(100.0, 100.0), ..Default::default() }, &[first, second])
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
 
-        assert_eq!(taffy.child_count(node), 2);
+        taffy.set_layout_offset(second, Point { x: 3.0, y: 4.0 }).unwrap();
+        assert!(!taffy.dirty(root).unwrap());
+        assert_eq!(taffy.layout_offset(second).unwrap(), Point { x: 3.0, y: 4.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 53.0, y: 4.0 });
+        assert_eq!(taffy.layout(first).unwrap().location, Point { x: 0.0, y: 0.0 });
+        assert_eq!(taffy.layout(grandchild).unwrap().location, Point { x: 0.0, y: 0.0 });
+
+        let snapshot = taffy.freeze();
+        assert_eq!(snapshot.hit_test(root, Point { x: 52.0, y: 2.0 }), Some(root));
+        assert_eq!(snapshot.hit_test(root, Point { x: 55.0, y: 6.0 }), Some(grandchild));
+        assert_eq!(snapshot.hit_test(root, Point { x: 99.0, y: 53.0 }), Some(second));
+        drop(snapshot);
+
+        // The offset is applied again when the node is laid out
+        taffy.set_style(root, Style { size: Size::from_lengths(120.0, 100.0), ..Default::default() }).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 53.0, y: 4.0 });
 
-        taffy.remove_child(node, child0).unwrap();
-        assert_eq!(taffy.child_count(node), 1);
-        assert_eq!(taffy.children(node).unwrap()[0], child1);
+        taffy.set_layout_offset(second, Point::ZERO).unwrap();
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 50.0, y: 0.0 });
+    }
 
-        taffy.remove_child(node, child1).unwrap();
-        assert_eq!(taffy.child_count(node), 0);
+    #[test]
+    fn layout_offset_rounds_relative_to_the_layout_root() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf_style = Style { size: Size::from_lengths(10.3, 10.0), flex_shrink: 0.0, ..Default::default() };
+        let first = taffy.new_leaf(leaf_style.clone()).unwrap();
+        let second = taffy.new_leaf(leaf_style).unwrap();
+        let subtree = taffy.new_with_children(Style::default(), &[first, second]).unwrap();
+        let container = taffy.new_with_children(Style::default(), &[subtree]).unwrap();
+        let root =
+            taffy.new_with_children(Style { padding: Rect::length(0.4), ..Default::default() }, &[container]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        taffy.compute_layout(subtree, Size::MAX_CONTENT).unwrap();
+
+        // The offset is rounded as laying out the subtree again would round it, without the location of `container`
+        taffy.set_layout_offset(second, Point { x: 0.1, y: 0.0 }).unwrap();
+        let offset_layout = *taffy.layout(second).unwrap();
+        assert_eq!(offset_layout.size.width, 11.0);
+        taffy.mark_dirty(second).unwrap();
+        taffy.compute_layout(subtree, Size::MAX_CONTENT).unwrap();
+        assert_eq!(*taffy.layout(second).unwrap(), offset_layout);
     }
 
     #[test]
-    fn remove_child_at_index() {
+    fn user_data_is_stored_without_dirtying_and_frozen_with_layout() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+        let child = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 20.0), ..Default::default() }).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[child]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.user_data(child).unwrap(), 0);
 
-        assert_eq!(taffy.child_count(node), 2);
+        taffy.set_user_data(child, 0xDEAD_BEEF).unwrap();
+        assert!(!taffy.dirty(root).unwrap());
+        assert_eq!(taffy.user_data(child).unwrap(), 0xDEAD_BEEF);
 
-        taffy.remove_child_at_index(node, 0).unwrap();
-        assert_eq!(taffy.child_count(node), 1);
-        assert_eq!(taffy.children(node).unwrap()[0], child1);
+        let snapshot = taffy.freeze();
+        let (layout, user_data) = snapshot.layout_with_user_data(child).unwrap();
+        assert_eq!(layout.size, Size { width: 10.0, height: 20.0 });
+        assert_eq!(user_data, 0xDEAD_BEEF);
+        assert_eq!(snapshot.user_data(root).unwrap(), 0);
+    }
 
-        taffy.remove_child_at_index(node, 0).unwrap();
-        assert_eq!(taffy.child_count(node), 0);
+    #[test]
+    fn snapshot_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LayoutSnapshot>();
     }
 
-    // Related to: https://github.com/DioxusLabs/taffy/issues/510
     #[test]
-    fn remove_child_updates_parents() {
+    fn unrounded_layout_is_available_when_rounding() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+        let root = taffy
+            .new_with_children(
+                Style { size: Size::from_lengths(100.0, 100.0), padding: length(10.4), ..Default::default() },
+                &[child],
+            )
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
 
-        let parent = taffy.new_leaf(Style::default()).unwrap();
-        let child = taffy.new_leaf(Style::default()).unwrap();
-
-        taffy.add_child(parent, child).unwrap();
-
-        taffy.remove(parent).unwrap();
+        assert_eq!(taffy.layout(child).unwrap().location.x, 10.0);
+        assert_eq!(taffy.unrounded_layout(child).unwrap().location.x, 10.4);
+    }
 
-        // Once the parent is removed this shouldn't panic.
-        assert!(taffy.set_children(child, &[]).is_ok());
+    #[test]
+    fn percent_resolution_mode_controls_vertical_percentages() {
+        for display in [Display::Flex, Display::Grid, Display::Block] {
+            let mut taffy: TaffyTree<()> = TaffyTree::new();
+            let child =
+                taffy.new_leaf(Style { margin: percent(0.1), padding: percent(0.1), ..Default::default() }).unwrap();
+            let root = taffy
+                .new_with_children(
+                    Style { display, size: Size::from_lengths(200.0, 100.0), ..Default::default() },
+                    &[child],
+                )
+                .unwrap();
+
+            taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            let layout = taffy.layout(child).unwrap();
+            assert_eq!(layout.margin, Rect { left: 20.0, right: 20.0, top: 20.0, bottom: 20.0 }, "{display:?}");
+            assert_eq!(layout.padding, Rect { left: 20.0, right: 20.0, top: 20.0, bottom: 20.0 }, "{display:?}");
+
+            taffy.set_layout_config(LayoutConfig {
+                percent_resolution_mode: PercentResolutionMode::PerAxis,
+                ..taffy.layout_config()
+            });
+            taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+            let layout = taffy.layout(child).unwrap();
+            assert_eq!(layout.margin, Rect { left: 20.0, right: 20.0, top: 10.0, bottom: 10.0 }, "{display:?}");
+            assert_eq!(layout.padding, Rect { left: 20.0, right: 20.0, top: 10.0, bottom: 10.0 }, "{display:?}");
+        }
     }
 
     #[test]
-    fn replace_child_at_index() {
+    fn layout_config_controls_automatic_minimum_size() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let grandchild = taffy.new_leaf(Style { size: Size::from_lengths(100.0, 10.0), ..Default::default() }).unwrap();
+        let child = taffy.new_with_children(Style::default(), &[grandchild]).unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() }, &[child])
+            .unwrap();
 
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-
-        let node = taffy.new_with_children(Style::default(), &[child0]).unwrap();
-        assert_eq!(taffy.child_count(node), 1);
-        assert_eq!(taffy.children(node).unwrap()[0], child0);
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(child).unwrap().size.width, 100.0);
 
-        taffy.replace_child_at_index(node, 0, child1).unwrap();
-        assert_eq!(taffy.child_count(node), 1);
-        assert_eq!(taffy.children(node).unwrap()[0], child1);
+        taffy.set_layout_config(LayoutConfig { use_automatic_minimum_size: false, ..LayoutConfig::DEFAULT });
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(child).unwrap().size.width, 50.0);
     }
+
     #[test]
-    fn test_child_at_index() {
+    fn layout_config_controls_legacy_stretch() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let child2 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1, child2]).unwrap();
+        let child = taffy
+            .new_leaf(Style {
+                aspect_ratio: Some(1.0),
+                max_size: Size { width: length(40.0), height: auto() },
+                ..Default::default()
+            })
+            .unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(100.0, 100.0), ..Default::default() }, &[child])
+            .unwrap();
 
-        assert!(if let Ok(result) = taffy.child_at_index(node, 0) { result == child0 } else { false });
-        assert!(if let Ok(result) = taffy.child_at_index(node, 1) { result == child1 } else { false });
-        assert!(if let Ok(result) = taffy.child_at_index(node, 2) { result == child2 } else { false });
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(child).unwrap().size.height, 100.0);
+
+        taffy.set_layout_config(LayoutConfig { legacy_stretch: true, ..LayoutConfig::DEFAULT });
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(child).unwrap().size.height, 40.0);
     }
+
     #[test]
-    fn test_child_count() {
+    #[cfg(feature = "grid")]
+    fn hidden_grid_items_do_not_generate_tracks() {
+        use crate::style_helpers::line;
+
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
+        let hidden_item = taffy
+            .new_leaf(Style { display: Display::None, grid_row: line(3), grid_column: line(3), ..Default::default() })
+            .unwrap();
+        let hidden_auto_item = taffy.new_leaf(Style { display: Display::None, ..Default::default() }).unwrap();
+        let auto_item = taffy.new_leaf(Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() }).unwrap();
+        let grid = taffy
+            .new_with_children(
+                Style { display: Display::Grid, ..Default::default() },
+                &[hidden_item, hidden_auto_item, auto_item],
+            )
+            .unwrap();
 
-        assert!(taffy.child_count(node) == 2);
-        assert!(taffy.child_count(child0) == 0);
-        assert!(taffy.child_count(child1) == 0);
+        taffy.compute_layout(grid, Size::MAX_CONTENT).unwrap();
+        let stats = taffy.inspect(grid).unwrap().grid_occupancy.unwrap();
+        assert_eq!((stats.row_count, stats.column_count, stats.occupied_cell_count), (1, 1, 1));
+        assert_eq!(taffy.layout(auto_item).unwrap().location, Point::ZERO);
+
+        taffy.set_layout_config(LayoutConfig { legacy_hidden_grid_items: true, ..LayoutConfig::DEFAULT });
+        taffy.compute_layout(grid, Size::MAX_CONTENT).unwrap();
+        let stats = taffy.inspect(grid).unwrap().grid_occupancy.unwrap();
+        assert_eq!((stats.row_count, stats.column_count, stats.occupied_cell_count), (3, 3, 1));
     }
 
-    #[allow(clippy::vec_init_then_push)]
+    #[cfg(feature = "node_id_brand")]
     #[test]
-    fn test_children() {
+    fn node_from_another_tree_is_rejected() {
+        let mut old_taffy: TaffyTree<()> = TaffyTree::new();
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
-
-        let mut children = sys::Vec::new();
-        children.push(child0);
-        children.push(child1);
+        let old_node = old_taffy.new_leaf(Style::default()).unwrap();
+        let node = taffy.new_leaf(Style::default()).unwrap();
+        // Both nodes occupy the first slot of their tree, so only the brand tells them apart
+        assert_ne!(old_node, node);
 
-        let children_result = taffy.children(node).unwrap();
-        assert_eq!(children_result, children);
+        assert_eq!(taffy.layout(old_node), Err(TaffyError::ForeignNode(old_node)));
+        assert_eq!(taffy.add_child(node, old_node), Err(TaffyError::ForeignNode(old_node)));
+        assert_eq!(taffy.compute_layout(old_node, Size::MAX_CONTENT), Err(TaffyError::ForeignNode(old_node)));
+        assert_eq!(taffy.parent(old_node), None);
 
-        assert!(taffy.children(child0).unwrap().is_empty());
+        // Ids round-tripped through a u64 keep their brand, and unbranded ids are accepted by any tree
+        assert_eq!(taffy.layout(NodeId::from(u64::from(old_node))), Err(TaffyError::ForeignNode(old_node)));
+        assert!(taffy.layout(NodeId::new(u64::from(node) & !0xff00_0000)).is_ok());
     }
+
+    #[cfg(feature = "node_id_brand")]
     #[test]
-    fn test_set_style() {
+    fn brand_does_not_affect_the_generation_of_reused_slots() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-
+        let removed = taffy.new_leaf(Style::default()).unwrap();
+        taffy.remove(removed).unwrap();
         let node = taffy.new_leaf(Style::default()).unwrap();
-        assert_eq!(taffy.style(node).unwrap().display, Display::Flex);
 
-        taffy.set_style(node, Style { display: Display::None, ..Style::default() }).unwrap();
-        assert_eq!(taffy.style(node).unwrap().display, Display::None);
+        // The slot is reused with a new generation, so the ids only differ in the generation (the upper 32 bits)
+        assert_eq!(u64::from(removed) & 0xffff_ffff, u64::from(node) & 0xffff_ffff);
+        assert_eq!(DefaultKey::from(node).data().as_ffi() >> 32, u64::from(node) >> 32);
+        assert_eq!(taffy.layout(removed), Err(TaffyError::InvalidInputNode(removed)));
+        assert!(taffy.layout(node).is_ok());
     }
+
     #[test]
-    fn test_style() {
+    fn build_from_slices() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let styles = [
+            Style { size: Size::from_lengths(100.0, 100.0), ..Default::default() },
+            Style { flex_grow: 1.0, ..Default::default() },
+            Style { size: Size::from_lengths(10.0, 10.0), ..Default::default() },
+            Style { flex_grow: 1.0, ..Default::default() },
+        ];
+        let ids = taffy.build_from_slices(&styles, &[None, Some(0), Some(1), Some(0)]).unwrap();
+
+        assert_eq!(ids.len(), 4);
+        assert_eq!(taffy.total_node_count(), 4);
+        assert_eq!(taffy.children(ids[0]).unwrap(), vec![ids[1], ids[3]]);
+        assert_eq!(taffy.children(ids[1]).unwrap(), vec![ids[2]]);
+        assert_eq!(taffy.parent(ids[2]), Some(ids[1]));
+        assert_eq!(taffy.parent(ids[0]), None);
+
+        taffy.compute_layout(ids[0], Size::MAX_CONTENT).unwrap();
+        // The 10px grandchild gives its parent a 10px flex basis, then the remaining 80px is shared equally
+        assert_eq!(taffy.layout(ids[1]).unwrap().size.width, 55.0);
+        assert_eq!(taffy.layout(ids[3]).unwrap().location.x, 55.0);
+    }
 
-        let style = Style { display: Display::None, flex_direction: FlexDirection::RowReverse, ..Default::default() };
-
-        let node = taffy.new_leaf(style.clone()).unwrap();
-
-        let res = taffy.style(node);
-        assert!(res.is_ok());
-        assert!(res.unwrap() == &style);
+    #[test]
+    fn build_from_slices_rejects_invalid_descriptions() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let styles = [Style::default(), Style::default()];
+        assert_eq!(
+            taffy.build_from_slices(&styles, &[Some(1), None]),
+            Err(TaffyError::InvalidParentIndex { index: 0, parent: 1 })
+        );
+        assert_eq!(
+            taffy.build_from_slices(&styles, &[None]),
+            Err(TaffyError::SliceLengthMismatch { styles: 2, parents: 1 })
+        );
+        assert_eq!(taffy.total_node_count(), 0);
     }
+
     #[test]
-    fn test_layout() {
+    fn removed_node_is_invalid_after_slot_reuse() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let parent = taffy.new_leaf(Style::default()).unwrap();
+        let removed = taffy.new_leaf(Style::default()).unwrap();
+        taffy.remove(removed).unwrap();
         let node = taffy.new_leaf(Style::default()).unwrap();
-
-        // TODO: Improve this test?
-        let res = taffy.layout(node);
-        assert!(res.is_ok());
+        assert_ne!(removed, node);
+
+        assert_eq!(taffy.layout(removed), Err(TaffyError::InvalidInputNode(removed)));
+        assert_eq!(taffy.set_style(removed, Style::default()), Err(TaffyError::InvalidInputNode(removed)));
+        assert_eq!(taffy.add_child(parent, removed), Err(TaffyError::InvalidChildNode(removed)));
+        assert_eq!(taffy.children(removed), Err(TaffyError::InvalidParentNode(removed)));
+        assert_eq!(taffy.remove(removed), Err(TaffyError::InvalidInputNode(removed)));
+        assert_eq!(taffy.parent(removed), None);
+        assert_eq!(taffy.children(parent), Ok(Vec::new()));
     }
 
     #[test]
-    fn test_mark_dirty() {
+    fn yoga_style_defaults() {
         let mut taffy: TaffyTree<()> = TaffyTree::new();
-        let child0 = taffy.new_leaf(Style::default()).unwrap();
-        let child1 = taffy.new_leaf(Style::default()).unwrap();
-        let node = taffy.new_with_children(Style::default(), &[child0, child1]).unwrap();
-
-        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        taffy.set_default_style(StyleDefaults::Yoga.style());
+        let child_style = Style { size: Size::from_lengths(50.0, 80.0), ..taffy.default_style() };
+        let first = taffy.new_leaf(child_style.clone()).unwrap();
+        let second = taffy.new_leaf(child_style).unwrap();
+        let root = taffy
+            .new_with_children(
+                Style { size: Size::from_lengths(100.0, 100.0), ..taffy.default_style() },
+                &[first, second],
+            )
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
 
-        assert_eq!(taffy.dirty(child0), Ok(false));
-        assert_eq!(taffy.dirty(child1), Ok(false));
-        assert_eq!(taffy.dirty(node), Ok(false));
+        // Children are laid out in a column and do not shrink to fit
+        assert_eq!(taffy.layout(first).unwrap().size, Size { width: 50.0, height: 80.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 0.0, y: 80.0 });
+        assert_eq!(taffy.layout(second).unwrap().size, Size { width: 50.0, height: 80.0 });
+    }
 
-        taffy.mark_dirty(node).unwrap();
-        assert_eq!(taffy.dirty(child0), Ok(false));
-        assert_eq!(taffy.dirty(child1), Ok(false));
-        assert_eq!(taffy.dirty(node), Ok(true));
+    #[test]
+    #[cfg(feature = "std")]
+    fn nodes_are_created_from_the_default_style_and_presets() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let theme = Style { padding: length(4.0), ..Default::default() };
+        taffy.set_default_style(theme.clone());
+        taffy.set_style_preset("button", Style { size: Size::from_lengths(80.0, 20.0), ..theme.clone() });
+
+        let delta = StyleDelta { flex_grow: Some(1.0), ..Default::default() };
+        let plain = taffy.new_leaf_from_preset(None, &delta).unwrap();
+        assert_eq!(taffy.style(plain).unwrap(), &Style { flex_grow: 1.0, ..theme.clone() });
+        let button = taffy.new_with_children_from_preset(Some("button"), &StyleDelta::default(), &[plain]).unwrap();
+        assert_eq!(taffy.style(button).unwrap().size, Size::from_lengths(80.0, 20.0));
+        assert_eq!(taffy.children(button).unwrap(), vec![plain]);
+
+        assert_eq!(
+            taffy.new_leaf_from_preset(Some("missing"), &delta),
+            Err(TaffyError::InvalidStylePreset(String::from("missing")))
+        );
+        assert!(taffy.remove_style_preset("button").is_some());
+        assert_eq!(taffy.style_preset("button"), None);
 
-        taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
-        taffy.mark_dirty(child0).unwrap();
-        assert_eq!(taffy.dirty(child0), Ok(true));
-        assert_eq!(taffy.dirty(child1), Ok(false));
-        assert_eq!(taffy.dirty(node), Ok(true));
+        // Resetting to a set of style defaults replaces the default style
+        taffy.set_default_style(StyleDefaults::Css.style());
+        assert_eq!(taffy.default_style(), Style::DEFAULT);
     }
 
     #[test]
@@ -1171,6 +6847,48 @@ mod tests {
         assert!(layout_result.is_ok());
     }
 
+    #[test]
+    fn max_depth_defaults_to_none() {
+        let taffy: TaffyTree<()> = TaffyTree::new();
+        assert_eq!(taffy.max_depth(), None);
+    }
+
+    #[test]
+    fn compute_layout_errors_when_tree_exceeds_max_depth() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        taffy.set_max_depth(Some(100));
+
+        // Deep enough that laying it out recursively would overflow the stack
+        let mut nodes = vec![taffy.new_leaf(Style::default()).unwrap()];
+        for _ in 0..100_000 {
+            nodes.push(taffy.new_with_children(Style::default(), &[*nodes.last().unwrap()]).unwrap());
+        }
+        let root = *nodes.last().unwrap();
+
+        // The first node found too deep is reported, which is the one 101 levels below the root
+        let too_deep = nodes[nodes.len() - 102];
+        assert_eq!(taffy.compute_layout(root, Size::MAX_CONTENT), Err(TaffyError::MaxDepthExceeded(too_deep)));
+        assert_eq!(
+            taffy.compute_fit_layout(root, Size { width: 100.0, height: 100.0 }),
+            Err(TaffyError::MaxDepthExceeded(too_deep))
+        );
+    }
+
+    #[test]
+    fn compute_layout_succeeds_when_tree_is_within_max_depth() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        taffy.set_max_depth(Some(10));
+
+        let mut root = taffy.new_leaf(Style::default()).unwrap();
+        for _ in 0..10 {
+            root = taffy.new_with_children(Style::default(), &[root]).unwrap();
+        }
+        assert_eq!(taffy.compute_layout(root, Size::MAX_CONTENT), Ok(()));
+
+        let root = taffy.new_with_children(Style::default(), &[root]).unwrap();
+        assert!(matches!(taffy.compute_layout(root, Size::MAX_CONTENT), Err(TaffyError::MaxDepthExceeded(_))));
+    }
+
     #[test]
     fn make_sure_layout_location_is_top_left() {
         use crate::prelude::Rect;
@@ -1214,4 +6932,402 @@ mod tests {
         assert_eq!(layout.location.x, 10f32);
         assert_eq!(layout.location.y, 30f32);
     }
+
+    #[test]
+    fn layout_exposes_first_baseline_of_containers() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+        let child = taffy
+            .new_leaf(Style { size: Size { width: length(10.0), height: length(20.0) }, ..Default::default() })
+            .unwrap();
+        let root =
+            taffy.new_with_children(Style { padding: Rect::all(length(5.0)), ..Default::default() }, &[child]).unwrap();
+
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        // A flex item without a baseline is synthesized from its bottom edge
+        assert_eq!(taffy.layout(root).unwrap().first_baseline, Some(25.0));
+        assert_eq!(taffy.layout(child).unwrap().first_baseline, None);
+    }
+
+    #[test]
+    #[cfg(feature = "flexbox")]
+    fn measure_functions_can_return_the_baselines_of_leaves() {
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        let tall = taffy.new_leaf_with_context(Style::default(), 20.0).unwrap();
+        let padded_style = Style { padding: Rect { top: length(5.0), ..Rect::zero() }, ..Default::default() };
+        let padded = taffy.new_leaf_with_context(padded_style, 10.0).unwrap();
+        let root_style = Style { align_items: Some(crate::style::AlignItems::Baseline), ..Default::default() };
+        let root = taffy.new_with_children(root_style, &[tall, padded]).unwrap();
+
+        taffy
+            .compute_layout_with_measure(root, Size::MAX_CONTENT, |_, _, _, baseline, _| {
+                let baseline = baseline.copied().unwrap_or(0.0);
+                let baselines = Point { x: None, y: Some(baseline) };
+                MeasureOutput::from_size(Size { width: 10.0, height: baseline + 10.0 })
+                    .with_baselines(baselines, baselines)
+            })
+            .unwrap();
+
+        // The baseline of the padded leaf is offset by its padding, and aligned with the baseline of the tall leaf
+        assert_eq!(taffy.layout(padded).unwrap().first_baseline, Some(15.0));
+        assert_eq!(taffy.layout(tall).unwrap().location.y, 0.0);
+        assert_eq!(taffy.layout(padded).unwrap().location.y, 5.0);
+    }
+
+    #[test]
+    fn leaves_independent_of_available_width_are_not_measured_again_when_it_changes() {
+        let measure_calls_after_resize = |independent: bool| {
+            let mut taffy: TaffyTree<()> = TaffyTree::new();
+            // The leaf is laid out as the root, as a flex container would size it under a max-content constraint
+            // rather than the available width
+            let icon = taffy.new_leaf_with_context(Style::default(), ()).unwrap();
+            let mut layout_with_width = |width: f32| {
+                let mut measure_calls = 0;
+                let available_space =
+                    Size { width: AvailableSpace::Definite(width), height: AvailableSpace::MaxContent };
+                taffy
+                    .compute_layout_with_measure(icon, available_space, |_, _, _, _, _| {
+                        measure_calls += 1;
+                        let output = MeasureOutput::from_size(Size { width: 10.0, height: 10.0 });
+                        if independent {
+                            output.independent_of_available_width()
+                        } else {
+                            output
+                        }
+                    })
+                    .unwrap();
+                measure_calls
+            };
+            layout_with_width(100.0);
+            let measure_calls = layout_with_width(200.0);
+            assert_eq!(taffy.layout(icon).unwrap().size, Size { width: 10.0, height: 10.0 });
+            measure_calls
+        };
+
+        assert!(measure_calls_after_resize(false) > 0);
+        assert_eq!(measure_calls_after_resize(true), 0);
+    }
+
+    #[test]
+    fn forced_size_overrides_style_without_modifying_it() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let pane_style = Style { flex_grow: 1.0, flex_basis: length(0.0), ..Default::default() };
+        let first = taffy.new_leaf(pane_style.clone()).unwrap();
+        let second = taffy.new_leaf(pane_style.clone()).unwrap();
+        let root = taffy
+            .new_with_children(Style { size: Size::from_lengths(300.0, 100.0), ..Default::default() }, &[first, second])
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(first).unwrap().size.width, 150.0);
+
+        taffy.set_node_forced_size(first, Some(Size { width: 100.0, height: 40.0 })).unwrap();
+        assert!(taffy.dirty(root).unwrap());
+        assert_eq!(taffy.node_forced_size(first).unwrap(), Some(Size { width: 100.0, height: 40.0 }));
+        assert_eq!(taffy.style(first).unwrap(), &pane_style);
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(first).unwrap().size, Size { width: 100.0, height: 40.0 });
+        assert_eq!(taffy.layout(second).unwrap().location.x, 100.0);
+        assert_eq!(taffy.layout(second).unwrap().size.width, 200.0);
+
+        // Edits made to the node's own style in place still apply while its size is forced
+        taffy.try_style_mut(first).unwrap().margin.left = length(10.0);
+        taffy.mark_dirty(first).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(first).unwrap().location.x, 10.0);
+        assert_eq!(taffy.layout(first).unwrap().size.width, 100.0);
+        assert_eq!(taffy.layout(second).unwrap().size.width, 190.0);
+
+        taffy.set_node_forced_size(first, None).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(first).unwrap().size, Size { width: 145.0, height: 100.0 });
+    }
+
+    #[test]
+    fn root_percent_resolves_against_the_layout_root() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let overlay_size = Size { width: Dimension::root_percent(1.0), height: Dimension::root_percent(0.5) };
+        let overlay_style = Style { size: overlay_size, flex_shrink: 0.0, ..Default::default() };
+        let overlay = taffy.new_leaf(overlay_style.clone()).unwrap();
+        let wrapper =
+            taffy.new_with_children(Style { size: Size::from_lengths(100.0, 100.0), ..Default::default() }, &[overlay]);
+        let root = taffy
+            .new_with_children(Style { size: Size::from_percent(1.0, 1.0), ..Default::default() }, &[wrapper.unwrap()])
+            .unwrap();
+
+        taffy.compute_layout(root, Size { width: length(400.0), height: length(300.0) }).unwrap();
+        assert_eq!(taffy.layout(overlay).unwrap().size, Size { width: 400.0, height: 150.0 });
+        assert_eq!(taffy.style(overlay).unwrap(), &overlay_style);
+
+        // The overlay is laid out again when the root is resized, although the size of its parent is unchanged
+        taffy.compute_layout(root, Size { width: length(800.0), height: length(600.0) }).unwrap();
+        assert_eq!(taffy.layout(overlay).unwrap().size, Size { width: 800.0, height: 300.0 });
+    }
+
+    #[test]
+    fn root_percent_only_resolves_the_nodes_of_the_layout_root() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let size = Size { width: Dimension::root_percent(0.5), height: length(10.0) };
+        let item_style = Style { size, flex_shrink: 0.0, ..Default::default() };
+        let first_item = taffy.new_leaf(item_style.clone()).unwrap();
+        let first_root = taffy.new_with_children(Style::default(), &[first_item]).unwrap();
+        let second_item = taffy.new_leaf(item_style).unwrap();
+        let second_root = taffy.new_with_children(Style::default(), &[second_item]).unwrap();
+        let available_space = |width: f32| Size { width: length(width), height: AvailableSpace::MaxContent };
+
+        taffy.compute_layout(first_root, available_space(400.0)).unwrap();
+        taffy.compute_layout(second_root, available_space(200.0)).unwrap();
+        assert!(!taffy.dirty(first_item).unwrap());
+        assert_eq!(taffy.layout(first_item).unwrap().size.width, 200.0);
+        assert_eq!(taffy.layout(second_item).unwrap().size.width, 100.0);
+
+        // Root percentages set in place are found when the layout styles are next refreshed
+        taffy.try_style_mut(first_root).unwrap().min_size.height = Dimension::root_percent(0.25);
+        taffy.mark_dirty(first_root).unwrap();
+        taffy.compute_layout(first_root, Size { width: length(400.0), height: length(400.0) }).unwrap();
+        assert_eq!(taffy.layout(first_root).unwrap().size.height, 100.0);
+
+        // Nodes which stop using root percentages are removed from the index
+        taffy.set_style(first_item, Style::default()).unwrap();
+        taffy.compute_layout(first_root, available_space(400.0)).unwrap();
+        assert!(!taffy.root_percent_nodes.contains_key(first_item.into()));
+        assert!(taffy.root_percent_nodes.contains_key(second_item.into()));
+    }
+
+    #[test]
+    fn style_variants_switch_with_the_size_of_the_node() {
+        use crate::style::{SizeCondition, StyleVariant};
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let item_style = Style { size: Size::from_lengths(100.0, 50.0), flex_shrink: 0.0, ..Default::default() };
+        let first = taffy.new_leaf(item_style.clone()).unwrap();
+        let second = taffy.new_leaf(item_style).unwrap();
+        let panel_style = Style { size: Size { width: percent(1.0), height: auto() }, ..Default::default() };
+        let panel = taffy.new_with_children(panel_style.clone(), &[first, second]).unwrap();
+        let narrow = StyleVariant {
+            condition: SizeCondition { max_width: Some(300.0), ..SizeCondition::ANY },
+            style: Style { flex_direction: FlexDirection::Column, ..panel_style.clone() },
+        };
+        taffy.set_style_variants(panel, vec![narrow.clone()]).unwrap();
+        let root = taffy.new_with_children(panel_style.clone(), &[panel]).unwrap();
+
+        taffy.compute_layout(root, Size { width: length(500.0), height: AvailableSpace::MaxContent }).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), None);
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 100.0, y: 0.0 });
+
+        taffy.compute_layout(root, Size { width: length(250.0), height: AvailableSpace::MaxContent }).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), Some(0));
+        assert_eq!(taffy.style(panel).unwrap(), &panel_style);
+        assert_eq!(taffy.style_variants(panel).unwrap(), &[narrow]);
+        assert_eq!(taffy.layout(panel).unwrap().size, Size { width: 250.0, height: 100.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 0.0, y: 50.0 });
+
+        taffy.compute_layout(root, Size { width: length(500.0), height: AvailableSpace::MaxContent }).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), None);
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 100.0, y: 0.0 });
+    }
+
+    /// Builds a panel holding two items of 200x50, which are stacked in a column by a style variant of the panel while it
+    /// is at most 300 wide, in a root node. Returns the root, the panel and the second item.
+    fn new_panel_with_narrow_variant(taffy: &mut TaffyTree<()>) -> (NodeId, NodeId, NodeId) {
+        use crate::style::{SizeCondition, StyleVariant};
+
+        let item_style = Style { size: Size::from_lengths(200.0, 50.0), flex_shrink: 0.0, ..Default::default() };
+        let first = taffy.new_leaf(item_style.clone()).unwrap();
+        let second = taffy.new_leaf(item_style).unwrap();
+        let panel_style = Style { size: Size { width: percent(1.0), height: auto() }, ..Default::default() };
+        let panel = taffy.new_with_children(panel_style.clone(), &[first, second]).unwrap();
+        let narrow = StyleVariant {
+            condition: SizeCondition { max_width: Some(300.0), ..SizeCondition::ANY },
+            style: Style { flex_direction: FlexDirection::Column, ..panel_style.clone() },
+        };
+        taffy.set_style_variants(panel, vec![narrow]).unwrap();
+        let root = taffy.new_with_children(panel_style, &[panel]).unwrap();
+        (root, panel, second)
+    }
+
+    #[test]
+    fn style_variants_switch_in_fit_layouts() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (root, panel, second) = new_panel_with_narrow_variant(&mut taffy);
+
+        taffy.compute_layout(root, Size { width: length(1000.0), height: AvailableSpace::MaxContent }).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), None);
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 200.0, y: 0.0 });
+
+        let size = taffy.compute_fit_layout(root, Size { width: 250.0, height: f32::INFINITY }).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), Some(0));
+        assert_eq!(size, Size { width: 200.0, height: 100.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 0.0, y: 50.0 });
+    }
+
+    #[test]
+    fn style_variants_switch_in_constrained_layouts() {
+        use crate::compute::BoxConstraints;
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (root, panel, second) = new_panel_with_narrow_variant(&mut taffy);
+
+        taffy.compute_constrained_layout(root, &BoxConstraints::tight(Size { width: 500.0, height: 400.0 })).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), None);
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 200.0, y: 0.0 });
+
+        let constraints = BoxConstraints::tight(Size { width: 250.0, height: 400.0 });
+        let size = taffy.compute_constrained_layout(root, &constraints).unwrap();
+        assert_eq!(taffy.active_style_variant(panel).unwrap(), Some(0));
+        assert_eq!(size, Size { width: 250.0, height: 400.0 });
+        assert_eq!(taffy.layout(panel).unwrap().size.width, 250.0);
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 0.0, y: 50.0 });
+    }
+
+    #[test]
+    #[cfg(feature = "debug_determinism")]
+    #[should_panic(expected = "is not deterministic")]
+    fn debug_determinism_checks_fit_layouts() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style::default()).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[leaf]).unwrap();
+
+        // The leaf is measured wider each time, so laying it out again gives a different result
+        let mut width = 0.0;
+        taffy
+            .compute_fit_layout_with_measure(root, Size { width: 1000.0, height: 1000.0 }, |_, _, _, _, _| {
+                width += 10.0;
+                Size { width, height: 10.0 }
+            })
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "debug_determinism")]
+    #[should_panic(expected = "is not deterministic")]
+    fn debug_determinism_checks_constrained_layouts() {
+        use crate::compute::BoxConstraints;
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let leaf = taffy.new_leaf(Style::default()).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[leaf]).unwrap();
+
+        // The leaf is measured wider each time, so laying it out again gives a different result
+        let mut width = 0.0;
+        let constraints = BoxConstraints::loose(Size { width: 1000.0, height: 1000.0 });
+        taffy
+            .compute_constrained_layout_with_measure(root, &constraints, |_, _, _, _, _| {
+                width += 10.0;
+                Size { width, height: 10.0 }
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn state_variants_apply_overlays_with_minimal_dirtying() {
+        const HOVERED: u64 = 1 << 0;
+        const PRESSED: u64 = 1 << 1;
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let button_style = Style { size: Size::from_lengths(100.0, 40.0), ..Default::default() };
+        let button = taffy.new_leaf(button_style.clone()).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[button]).unwrap();
+        let overlays = vec![
+            vec![StyleCommand::InsetLeft(length(5.0))],
+            vec![StyleCommand::Width(length(120.0)), StyleCommand::InsetLeft(length(0.0))],
+        ];
+        taffy.set_variant_overlays(button, overlays.clone()).unwrap();
+        assert_eq!(taffy.variant_overlays(button).unwrap(), overlays.as_slice());
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        // Changing only the inset keeps the cached size measurements
+        taffy.set_active_variants(button, HOVERED).unwrap();
+        assert!(taffy.dirty(button).unwrap());
+        assert!(taffy.nodes[button.into()].cache.measure_entry_count() > 0);
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(button).unwrap().location.x, 5.0);
+
+        // Later overlays take precedence
+        taffy.set_active_variants(button, HOVERED | PRESSED).unwrap();
+        assert!(taffy.dirty(button).unwrap());
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(button).unwrap().location.x, 0.0);
+        assert_eq!(taffy.layout(button).unwrap().size.width, 120.0);
+        assert_eq!(taffy.active_variants(button).unwrap(), HOVERED | PRESSED);
+        assert_eq!(taffy.style(button).unwrap(), &button_style);
+
+        taffy.set_active_variants(button, 0).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(button).unwrap().location.x, 0.0);
+        assert_eq!(taffy.layout(button).unwrap().size.width, 100.0);
+    }
+
+    #[test]
+    fn state_variant_overlays_are_merged_again_when_the_inset_changes() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let button = taffy.new_leaf(Style { size: Size::from_lengths(100.0, 40.0), ..Default::default() }).unwrap();
+        let root = taffy.new_with_children(Style::default(), &[button]).unwrap();
+        taffy.set_variant_overlays(button, vec![vec![StyleCommand::Width(length(120.0))]]).unwrap();
+        taffy.set_active_variants(button, 1).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(button).unwrap().size.width, 120.0);
+
+        // Editing the inset keeps the measurements, but the overlay is still merged into the edited style
+        taffy.set_length_fast(button, LengthProperty::InsetLeft, 7.0).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        assert_eq!(taffy.layout(button).unwrap().location.x, 7.0);
+        assert_eq!(taffy.layout(button).unwrap().size.width, 120.0);
+    }
+
+    #[test]
+    fn content_alignment_is_safe_in_scroll_containers() {
+        use crate::style::{JustifyContent, Overflow};
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy
+            .new_leaf(Style { size: Size::from_lengths(150.0, 10.0), flex_shrink: 0.0, ..Default::default() })
+            .unwrap();
+        let scroll_style = Style {
+            size: Size::from_lengths(100.0, 100.0),
+            overflow: Point { x: Overflow::Hidden, y: Overflow::Hidden },
+            justify_content: Some(JustifyContent::Center),
+            ..Default::default()
+        };
+        let root = taffy.new_with_children(scroll_style.clone(), &[child]).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(child).unwrap().location.x, 0.0);
+        assert_eq!(taffy.layout(root).unwrap().scroll_origin_shift, Point { x: 25.0, y: 0.0 });
+
+        // Content which overflows a container which isn't a scroll container is still centered
+        let visible_style = Style { overflow: Point { x: Overflow::Visible, y: Overflow::Visible }, ..scroll_style };
+        taffy.set_style(root, visible_style).unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(child).unwrap().location.x, -25.0);
+        assert_eq!(taffy.layout(root).unwrap().scroll_origin_shift, Point::ZERO);
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn grid_track_alignment_is_safe_in_scroll_containers() {
+        use crate::style::{AlignContent, Overflow};
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let item = taffy.new_leaf(Style::default()).unwrap();
+        let grid = taffy
+            .new_with_children(
+                Style {
+                    display: Display::Grid,
+                    size: Size::from_lengths(100.0, 100.0),
+                    overflow: Point { x: Overflow::Visible, y: Overflow::Scroll },
+                    scrollbar_width: 0.0,
+                    grid_template_rows: vec![length(150.0)],
+                    align_content: Some(AlignContent::End),
+                    ..Default::default()
+                },
+                &[item],
+            )
+            .unwrap();
+        taffy.compute_layout(grid, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(item).unwrap().location.y, 0.0);
+        assert_eq!(taffy.layout(grid).unwrap().scroll_origin_shift, Point { x: 0.0, y: 50.0 });
+    }
 }
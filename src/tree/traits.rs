@@ -126,13 +126,19 @@
 //! }
 //! ```
 //!
-use super::{Cache, Layout, LayoutInput, LayoutOutput, NodeId, RequestedAxis, RunMode, SizingMode};
+use super::{Cache, Layout, LayoutConfig, LayoutInput, LayoutOutput, NodeId, RequestedAxis, RunMode, SizingMode};
+#[cfg(feature = "grid")]
+use crate::compute::GridOccupancyStats;
+#[cfg(feature = "inline_layout")]
+use crate::compute::{break_atoms_into_lines, InlineFormatInput, LineBox};
 use crate::geometry::{AbsoluteAxis, Line, Size};
 use crate::style::{AvailableSpace, CoreStyle};
 #[cfg(feature = "flexbox")]
 use crate::style::{FlexboxContainerStyle, FlexboxItemStyle};
 #[cfg(feature = "grid")]
 use crate::style::{GridContainerStyle, GridItemStyle};
+#[cfg(feature = "inline_layout")]
+use crate::util::sys::Vec;
 #[cfg(feature = "block_layout")]
 use crate::{BlockContainerStyle, BlockItemStyle};
 use core::ops::{Deref, DerefMut};
@@ -190,6 +196,13 @@ pub trait LayoutPartialTree: TraversePartialTree {
 
     /// Compute the specified node's size or full layout given the specified constraints
     fn compute_child_layout(&mut self, node_id: NodeId, inputs: LayoutInput) -> LayoutOutput;
+
+    /// Get the configuration of behaviours where layout may deviate from the web specifications.
+    /// Defaults to [`LayoutConfig::DEFAULT`], which follows the web specifications.
+    #[inline(always)]
+    fn get_layout_config(&self) -> LayoutConfig {
+        LayoutConfig::DEFAULT
+    }
 }
 
 /// Trait used by the `round_layout` method which takes a tree of unrounded float-valued layouts and performs
@@ -250,6 +263,16 @@ pub trait LayoutGridContainer: LayoutPartialTree {
 
     /// Get the child's styles
     fn get_grid_child_style(&self, child_node_id: NodeId) -> Self::GridItemStyle<'_>;
+
+    /// Record statistics about the matrix used to place the container's items. Called each time the container's items
+    /// are placed. Defaults to discarding the statistics.
+    #[inline(always)]
+    fn set_grid_occupancy_stats(&mut self, _node_id: NodeId, _stats: GridOccupancyStats) {}
+
+    /// Record the number of times the track sizing algorithm was run for an axis of the container. Called each time the
+    /// container is laid out. Defaults to discarding the count.
+    #[inline(always)]
+    fn add_grid_track_sizing_passes(&mut self, _node_id: NodeId, _passes: usize) {}
 }
 
 #[cfg(feature = "block_layout")]
@@ -271,6 +294,18 @@ pub trait LayoutBlockContainer: LayoutPartialTree {
     fn get_block_child_style(&self, child_node_id: NodeId) -> Self::BlockItemStyle<'_>;
 }
 
+#[cfg(feature = "inline_layout")]
+/// Extends [`LayoutPartialTree`] with the inline formatter which places the children of inline containers on lines
+pub trait LayoutInlineContainer: LayoutPartialTree {
+    /// Break the content of an inline container into lines: both its in-flow children (given as `input.atoms`) and any
+    /// content of the container which is not represented in the tree (such as text). Called each time the container is
+    /// measured or laid out. Defaults to [`break_atoms_into_lines`], which only places the children.
+    #[inline(always)]
+    fn format_inline_content(&mut self, _node_id: NodeId, input: InlineFormatInput<'_>) -> Vec<LineBox> {
+        break_atoms_into_lines(&input)
+    }
+}
+
 // --- PRIVATE TRAITS
 
 /// A private trait which allows us to add extra convenience methods to types which implement
@@ -299,6 +334,7 @@ pub(crate) trait LayoutPartialTreeExt: LayoutPartialTree {
                 axis: axis.into(),
                 run_mode: RunMode::ComputeSize,
                 vertical_margins_are_collapsible,
+                config: self.get_layout_config(),
             },
         )
         .size
@@ -326,6 +362,7 @@ pub(crate) trait LayoutPartialTreeExt: LayoutPartialTree {
                 axis: RequestedAxis::Both,
                 run_mode: RunMode::PerformLayout,
                 vertical_margins_are_collapsible,
+                config: self.get_layout_config(),
             },
         )
     }
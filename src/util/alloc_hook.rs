@@ -0,0 +1,146 @@
+//! A global allocator which forwards to embedder-provided allocation functions
+//!
+//! Stable Rust does not allow collections to be generic over their allocator, so Taffy cannot thread a custom allocator
+//! through its internal `Vec`s directly. Instead, embedders which need to track every allocation (for example console
+//! ports which must allocate through the engine's allocator) can register [`HookAllocator`] as their global allocator
+//! and then provide their allocation functions at runtime with [`set_allocator_hook`]:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static GLOBAL: taffy::HookAllocator = taffy::HookAllocator;
+//! ```
+#![allow(unsafe_code)]
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ffi::c_void;
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+
+/// Allocates `size` bytes aligned to `align`. Must return null if the allocation fails.
+pub type AllocFunction = unsafe extern "C" fn(size: usize, align: usize, user_data: *mut c_void) -> *mut c_void;
+
+/// Frees a block of memory previously returned by the matching [`AllocFunction`] with the same `size` and `align`
+pub type FreeFunction = unsafe extern "C" fn(ptr: *mut c_void, size: usize, align: usize, user_data: *mut c_void);
+
+/// The error returned by [`set_allocator_hook`] if memory has already been allocated through [`HookAllocator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocatorInUse;
+
+impl core::fmt::Display for AllocatorInUse {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "The allocator hook cannot be changed after memory has been allocated")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocatorInUse {}
+
+/// The embedder-provided allocation function (null if none has been set)
+static ALLOC_FUNCTION: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+/// The embedder-provided free function (null if none has been set)
+static FREE_FUNCTION: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+/// The user data pointer passed to the embedder-provided functions
+static USER_DATA: AtomicPtr<c_void> = AtomicPtr::new(core::ptr::null_mut());
+/// No allocation has been made yet, so the hook can be changed
+const UNUSED: u8 = 0;
+/// [`set_allocator_hook`] is storing new functions. Allocations wait for it to finish.
+const CHANGING: u8 = 1;
+/// An allocation has been made, so the hook can no longer be changed
+const IN_USE: u8 = 2;
+/// Whether the hook is unused, being changed or in use (one of [`UNUSED`], [`CHANGING`] and [`IN_USE`])
+static STATE: AtomicU8 = AtomicU8::new(UNUSED);
+
+/// Marks the hook as in use, waiting for any concurrent call to [`set_allocator_hook`] to finish first so that every
+/// allocation (and the matching free) is made with a single, fully stored set of functions
+#[inline(always)]
+fn mark_in_use() {
+    if STATE.load(Ordering::Acquire) == IN_USE {
+        return;
+    }
+    while let Err(state) = STATE.compare_exchange_weak(UNUSED, IN_USE, Ordering::AcqRel, Ordering::Acquire) {
+        if state == IN_USE {
+            return;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+/// Sets the functions used by [`HookAllocator`] to allocate and free memory
+///
+/// Passing `None` restores the default behaviour of using the system allocator (or failing all allocations in `no_std` builds).
+///
+/// # Safety
+///
+/// `alloc` must behave like [`GlobalAlloc::alloc`] and `free` must be able to free any pointer returned by `alloc`.
+/// Both functions must be safe to call from any thread with the provided `user_data`.
+pub unsafe fn set_allocator_hook(
+    functions: Option<(AllocFunction, FreeFunction)>,
+    user_data: *mut c_void,
+) -> Result<(), AllocatorInUse> {
+    // Claim the hook, so that no allocation can be made until all of the functions have been stored
+    while let Err(state) = STATE.compare_exchange_weak(UNUSED, CHANGING, Ordering::Acquire, Ordering::Acquire) {
+        if state == IN_USE {
+            return Err(AllocatorInUse);
+        }
+        core::hint::spin_loop();
+    }
+    let (alloc_ptr, free_ptr) = match functions {
+        Some((alloc, free)) => (alloc as *mut (), free as *mut ()),
+        None => (core::ptr::null_mut(), core::ptr::null_mut()),
+    };
+    USER_DATA.store(user_data, Ordering::Relaxed);
+    FREE_FUNCTION.store(free_ptr, Ordering::Relaxed);
+    ALLOC_FUNCTION.store(alloc_ptr, Ordering::Relaxed);
+    STATE.store(UNUSED, Ordering::Release);
+    Ok(())
+}
+
+/// A [`GlobalAlloc`] which forwards to the functions set with [`set_allocator_hook`]
+///
+/// If no functions have been set then the system allocator is used (or, in `no_std` builds, all allocations fail).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HookAllocator;
+
+unsafe impl GlobalAlloc for HookAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        mark_in_use();
+        let alloc_fn = ALLOC_FUNCTION.load(Ordering::Acquire);
+        if alloc_fn.is_null() {
+            #[cfg(feature = "std")]
+            return std::alloc::System.alloc(layout);
+            #[cfg(not(feature = "std"))]
+            return core::ptr::null_mut();
+        }
+        let alloc_fn: AllocFunction = core::mem::transmute(alloc_fn);
+        alloc_fn(layout.size(), layout.align(), USER_DATA.load(Ordering::Acquire)) as *mut u8
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let free_fn = FREE_FUNCTION.load(Ordering::Acquire);
+        if free_fn.is_null() {
+            #[cfg(feature = "std")]
+            std::alloc::System.dealloc(ptr, layout);
+            return;
+        }
+        let free_fn: FreeFunction = core::mem::transmute(free_fn);
+        free_fn(ptr as *mut c_void, layout.size(), layout.align(), USER_DATA.load(Ordering::Acquire))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{set_allocator_hook, AllocatorInUse, HookAllocator};
+    use core::alloc::{GlobalAlloc, Layout};
+
+    #[test]
+    fn hook_cannot_be_changed_once_memory_has_been_allocated() {
+        // SAFETY: no functions are set, so the system allocator is used
+        unsafe {
+            assert_eq!(set_allocator_hook(None, core::ptr::null_mut()), Ok(()));
+            let layout = Layout::from_size_align(16, 8).unwrap();
+            let ptr = HookAllocator.alloc(layout);
+            assert!(!ptr.is_null());
+            assert_eq!(set_allocator_hook(None, core::ptr::null_mut()), Err(AllocatorInUse));
+            HookAllocator.dealloc(ptr, layout);
+        }
+    }
+}
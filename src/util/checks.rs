@@ -0,0 +1,245 @@
+//! Validation of styles and layout inputs and outputs, enabled by the `debug_checks` feature
+//!
+//! A NaN or infinite value in a style typically propagates through every ancestor of the node it was set on, so that by
+//! the time it is noticed in the output of layout there is no telling where it came from. These checks find such
+//! values where they enter layout.
+use crate::geometry::{Rect, Size};
+use crate::style::{AvailableSpace, Dimension, LengthPercentage, LengthPercentageAuto, Style};
+#[cfg(feature = "grid")]
+use crate::style::{
+    MaxTrackSizingFunction, MinTrackSizingFunction, NonRepeatedTrackSizingFunction, TrackSizingFunction,
+};
+use crate::tree::{LayoutInput, LayoutOutput};
+
+/// The values that a numeric style property may take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValidRange {
+    /// Any finite value
+    Finite,
+    /// Any finite value which is not negative
+    NonNegative,
+    /// Any value which is not negative, including infinity
+    NonNegativeOrInfinite,
+}
+
+/// A style value which may hold a number
+trait StyleValue: Copy {
+    /// Returns the number held by the value, if any
+    fn number(self) -> Option<f32>;
+}
+
+impl StyleValue for f32 {
+    fn number(self) -> Option<f32> {
+        Some(self)
+    }
+}
+
+impl StyleValue for Option<f32> {
+    fn number(self) -> Option<f32> {
+        self
+    }
+}
+
+impl StyleValue for LengthPercentage {
+    fn number(self) -> Option<f32> {
+        match self {
+            LengthPercentage::Length(value) | LengthPercentage::Percent(value) => Some(value),
+        }
+    }
+}
+
+impl StyleValue for LengthPercentageAuto {
+    fn number(self) -> Option<f32> {
+        match self {
+            LengthPercentageAuto::Length(value) | LengthPercentageAuto::Percent(value) => Some(value),
+            LengthPercentageAuto::Auto => None,
+        }
+    }
+}
+
+impl StyleValue for Dimension {
+    fn number(self) -> Option<f32> {
+        match self {
+            Dimension::Length(value) | Dimension::Percent(value) | Dimension::RootPercent(value) => Some(value),
+            Dimension::Auto | Dimension::Content => None,
+        }
+    }
+}
+
+/// Collects descriptions of the invalid values found in a style
+#[derive(Debug, Default)]
+struct StyleChecker {
+    /// A description of each invalid value
+    problems: Vec<String>,
+}
+
+impl StyleChecker {
+    /// Records a problem if `value` holds a number outside of `range`
+    fn check(&mut self, property: &str, value: impl StyleValue, range: ValidRange) {
+        let value = match value.number() {
+            Some(value) => value,
+            None => return,
+        };
+        let problem = if value.is_nan() {
+            "NaN"
+        } else if value < 0.0 && range != ValidRange::Finite {
+            "negative"
+        } else if value.is_infinite() && range != ValidRange::NonNegativeOrInfinite {
+            "infinite"
+        } else {
+            return;
+        };
+        self.problems.push(format!("`{property}` is {problem} ({value})"));
+    }
+
+    /// Checks both dimensions of a size
+    fn check_size(&mut self, property: &str, size: Size<impl StyleValue>, range: ValidRange) {
+        self.check(&format!("{property}.width"), size.width, range);
+        self.check(&format!("{property}.height"), size.height, range);
+    }
+
+    /// Checks all four sides of a rect
+    fn check_rect(&mut self, property: &str, rect: Rect<impl StyleValue>, range: ValidRange) {
+        self.check(&format!("{property}.left"), rect.left, range);
+        self.check(&format!("{property}.right"), rect.right, range);
+        self.check(&format!("{property}.top"), rect.top, range);
+        self.check(&format!("{property}.bottom"), rect.bottom, range);
+    }
+
+    /// Checks the sizing functions of a single grid track
+    #[cfg(feature = "grid")]
+    fn check_track(&mut self, property: &str, track: &NonRepeatedTrackSizingFunction) {
+        if let MinTrackSizingFunction::Fixed(value) = track.min {
+            self.check(&format!("{property}.min"), value, ValidRange::NonNegative);
+        }
+        match track.max {
+            MaxTrackSizingFunction::Fixed(value) | MaxTrackSizingFunction::FitContent(value) => {
+                self.check(&format!("{property}.max"), value, ValidRange::NonNegative)
+            }
+            MaxTrackSizingFunction::Fraction(value) => {
+                self.check(&format!("{property}.max"), value, ValidRange::NonNegative)
+            }
+            MaxTrackSizingFunction::MinContent | MaxTrackSizingFunction::MaxContent | MaxTrackSizingFunction::Auto => {}
+        }
+    }
+
+    /// Checks every track in a list of grid track definitions
+    #[cfg(feature = "grid")]
+    fn check_track_list<'a>(&mut self, property: &str, tracks: impl IntoIterator<Item = &'a TrackSizingFunction>) {
+        for (index, track) in tracks.into_iter().enumerate() {
+            match track {
+                TrackSizingFunction::Single(track) => self.check_track(&format!("{property}[{index}]"), track),
+                TrackSizingFunction::Repeat(_, repeated) => {
+                    for (repeated_index, track) in repeated.iter().enumerate() {
+                        self.check_track(&format!("{property}[{index}][{repeated_index}]"), track);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns a description of each NaN, infinite or negative value in `style` which layout cannot handle
+pub(crate) fn find_invalid_style_values(style: &Style) -> Vec<String> {
+    use ValidRange::{Finite, NonNegative, NonNegativeOrInfinite};
+
+    let mut checker = StyleChecker::default();
+    checker.check("scrollbar_width", style.scrollbar_width, NonNegative);
+    checker.check_rect("inset", style.inset, Finite);
+    checker.check_size("size", style.size, NonNegative);
+    checker.check_size("min_size", style.min_size, NonNegative);
+    checker.check_size("max_size", style.max_size, NonNegativeOrInfinite);
+    checker.check("aspect_ratio", style.aspect_ratio, NonNegative);
+    checker.check_rect("margin", style.margin, Finite);
+    checker.check_rect("padding", style.padding, NonNegative);
+    checker.check_rect("border", style.border, NonNegative);
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    checker.check_size("gap", style.gap, NonNegative);
+    #[cfg(feature = "flexbox")]
+    {
+        checker.check("flex_basis", style.flex_basis, NonNegative);
+        checker.check("flex_grow", style.flex_grow, NonNegative);
+        checker.check("flex_shrink", style.flex_shrink, NonNegative);
+    }
+    #[cfg(feature = "grid")]
+    {
+        checker.check_track_list("grid_template_rows", style.grid_template_rows.iter());
+        checker.check_track_list("grid_template_columns", style.grid_template_columns.iter());
+        for (index, track) in style.grid_auto_rows.iter().enumerate() {
+            checker.check_track(&format!("grid_auto_rows[{index}]"), track);
+        }
+        for (index, track) in style.grid_auto_columns.iter().enumerate() {
+            checker.check_track(&format!("grid_auto_columns[{index}]"), track);
+        }
+    }
+    checker.problems
+}
+
+/// Returns a description of each NaN value in the inputs to the layout of a node
+pub(crate) fn find_invalid_layout_inputs(inputs: &LayoutInput) -> Vec<String> {
+    let mut problems = Vec::new();
+    check_nan(&mut problems, "known_dimensions.width", inputs.known_dimensions.width);
+    check_nan(&mut problems, "known_dimensions.height", inputs.known_dimensions.height);
+    check_nan(&mut problems, "parent_size.width", inputs.parent_size.width);
+    check_nan(&mut problems, "parent_size.height", inputs.parent_size.height);
+    check_nan(&mut problems, "available_space.width", definite_space(inputs.available_space.width));
+    check_nan(&mut problems, "available_space.height", definite_space(inputs.available_space.height));
+    problems
+}
+
+/// Returns a description of each NaN value in the output of the layout of a node
+///
+/// Unlike styles, outputs may legitimately be negative: a node sized with a negative amount of available space by its
+/// parent may be given a negative size.
+pub(crate) fn find_invalid_layout_outputs(output: &LayoutOutput) -> Vec<String> {
+    let mut problems = Vec::new();
+    check_nan(&mut problems, "size.width", Some(output.size.width));
+    check_nan(&mut problems, "size.height", Some(output.size.height));
+    problems
+}
+
+/// Records a problem if `value` is NaN
+fn check_nan(problems: &mut Vec<String>, name: &str, value: Option<f32>) {
+    if value.map_or(false, f32::is_nan) {
+        problems.push(format!("`{name}` is NaN"));
+    }
+}
+
+/// Returns the amount of definite available space, if any
+fn definite_space(space: AvailableSpace) -> Option<f32> {
+    match space {
+        AvailableSpace::Definite(value) => Some(value),
+        AvailableSpace::MinContent | AvailableSpace::MaxContent => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_invalid_style_values;
+    use crate::geometry::{Rect, Size};
+    use crate::style::{Dimension, LengthPercentage, Style};
+    use crate::style_helpers::length;
+
+    #[test]
+    fn finds_nan_negative_and_infinite_values() {
+        assert_eq!(find_invalid_style_values(&Style::default()), Vec::<String>::new());
+
+        let style = Style {
+            size: length(f32::NAN),
+            max_size: length(f32::INFINITY),
+            margin: length(-10.0),
+            padding: Rect { left: LengthPercentage::Length(f32::INFINITY), ..length(0.0) },
+            min_size: Size { width: Dimension::Auto, height: Dimension::Length(-1.0) },
+            ..Default::default()
+        };
+        assert_eq!(
+            find_invalid_style_values(&style),
+            [
+                "`size.width` is NaN (NaN)",
+                "`size.height` is NaN (NaN)",
+                "`min_size.height` is negative (-1)",
+                "`padding.left` is infinite (inf)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,609 @@
+//! A compact binary encoding of styles and other values, shared by the [`record`](crate::record) and
+//! [`binary`](crate::binary) formats
+//!
+//! Integers are written as LEB128 variable length integers and floats in little endian byte order. Structs which are
+//! expected to grow new fields over time ([`Style`] and [`LayoutConfig`]) are written as a list of optional fields, so
+//! that data written by one version of Taffy can be read by another.
+use core::fmt;
+
+#[cfg(feature = "grid")]
+use crate::compute::grid::GridLine;
+use crate::geometry::{Line, MinMax, Point, Rect, Size};
+#[cfg(feature = "block_layout")]
+use crate::style::TextAlign;
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use crate::style::{AlignContent, AlignItems};
+use crate::style::{
+    AvailableSpace, BoxSizing, Dimension, Direction, Display, LengthPercentage, LengthPercentageAuto, Overflow,
+    Position, ScrollbarGutter, SizeCondition, Style, StyleVariant, WritingMode,
+};
+#[cfg(feature = "flexbox")]
+use crate::style::{FlexDirection, FlexWrap};
+#[cfg(feature = "grid")]
+use crate::style::{
+    GridAutoFlow, GridPlacement, GridTrackRepetition, MaxTrackSizingFunction, MinTrackSizingFunction,
+    TrackSizingFunction,
+};
+use crate::tree::{LayoutConfig, MeasureOutput, NodeId, PercentResolutionMode, StyleCommand, StyleHandle};
+use crate::util::sys::Vec;
+
+/// An error that occurs while decoding a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CodecError {
+    /// The buffer ends part way through a value
+    UnexpectedEnd,
+    /// The buffer contains a value which is not valid
+    InvalidValue,
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::UnexpectedEnd => write!(f, "The data ends part way through a value"),
+            CodecError::InvalidValue => write!(f, "The data contains an invalid value"),
+        }
+    }
+}
+
+/// Reads values from an encoded buffer
+pub(crate) struct Reader<'a> {
+    /// The bytes which have not yet been read
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader which reads from the start of `bytes`
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns whether every byte has been read
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Reads a single byte
+    pub(crate) fn byte(&mut self) -> Result<u8, CodecError> {
+        let (&byte, rest) = self.bytes.split_first().ok_or(CodecError::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(byte)
+    }
+
+    /// Reads `len` bytes
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], CodecError> {
+        if self.bytes.len() < len {
+            return Err(CodecError::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    /// Reads `N` bytes
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+}
+
+/// A value which can be written to and read from a buffer
+pub(crate) trait Codec: Sized {
+    /// Appends the encoded value to `out`
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Reads a value encoded with [`Codec::encode`]
+    fn decode(input: &mut Reader) -> Result<Self, CodecError>;
+}
+
+impl Codec for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        input.byte()
+    }
+}
+
+impl Codec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        match input.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CodecError::InvalidValue),
+        }
+    }
+}
+
+impl Codec for u16 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(u16::from_le_bytes(input.array()?))
+    }
+}
+
+impl Codec for i16 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(i16::from_le_bytes(input.array()?))
+    }
+}
+
+impl Codec for f32 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(f32::from_le_bytes(input.array()?))
+    }
+}
+
+/// Encoded as a LEB128 variable length integer, so that small values take a single byte
+impl Codec for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut value = *self;
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = input.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(CodecError::InvalidValue)
+    }
+}
+
+impl Codec for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        usize::try_from(u64::decode(input)?).map_err(|_| CodecError::InvalidValue)
+    }
+}
+
+impl Codec for NodeId {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u64::from(*self).encode(out);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(NodeId::from(u64::decode(input)?))
+    }
+}
+
+impl Codec for StyleHandle {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u64::from(*self).encode(out);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(StyleHandle::from(u64::decode(input)?))
+    }
+}
+
+impl<T: Codec> Codec for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+            None => out.push(0),
+        }
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        match bool::decode(input)? {
+            true => Ok(Some(T::decode(input)?)),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: Codec> Codec for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        let len = usize::decode(input)?;
+        // Every value takes at least one byte, so a longer length must be invalid (and would risk a huge allocation)
+        if len > input.bytes.len() {
+            return Err(CodecError::UnexpectedEnd);
+        }
+        (0..len).map(|_| T::decode(input)).collect()
+    }
+}
+
+/// Slices can only be encoded. They are decoded as a [`Vec`].
+impl<T: Codec> Codec for &[T] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for value in self.iter() {
+            value.encode(out);
+        }
+    }
+    fn decode(_input: &mut Reader) -> Result<Self, CodecError> {
+        unreachable!("slices are decoded as a Vec")
+    }
+}
+
+/// References can only be encoded. They are decoded as the referenced type.
+impl<T: Codec> Codec for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self).encode(out);
+    }
+    fn decode(_input: &mut Reader) -> Result<Self, CodecError> {
+        unreachable!("references are decoded as the referenced type")
+    }
+}
+
+/// Implements [`Codec`] for tuples by encoding each element in turn
+macro_rules! tuple_codec {
+    ($($name:ident),+) => {
+        impl<$($name: Codec),+> Codec for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode(out);)+
+            }
+            fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+                Ok(($($name::decode(input)?,)+))
+            }
+        }
+    };
+}
+tuple_codec!(A, B);
+tuple_codec!(A, B, C);
+
+/// Implements [`Codec`] for structs by encoding each field in turn
+macro_rules! struct_codec {
+    ($ty:ident $(<$param:ident>)? { $($(#[$attr:meta])* $field:ident),* $(,)? }) => {
+        impl$(<$param: Codec>)? Codec for $ty$(<$param>)? {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $($(#[$attr])* self.$field.encode(out);)*
+            }
+            fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+                Ok(Self { $($(#[$attr])* $field: Codec::decode(input)?,)* })
+            }
+        }
+    };
+}
+struct_codec!(Size<T> { width, height });
+struct_codec!(Rect<T> { left, right, top, bottom });
+struct_codec!(Point<T> { x, y });
+struct_codec!(Line<T> { start, end });
+struct_codec!(SizeCondition { min_width, max_width, min_height, max_height });
+struct_codec!(StyleVariant { condition, style });
+struct_codec!(MeasureOutput { size, first_baselines, last_baselines, depends_on_available_width });
+
+impl<Min: Codec, Max: Codec> Codec for MinMax<Min, Max> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.min.encode(out);
+        self.max.encode(out);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(MinMax { min: Codec::decode(input)?, max: Codec::decode(input)? })
+    }
+}
+
+/// Implements [`Codec`] for enums as a tag byte (listed explicitly so that it is stable across feature flags) followed by
+/// the variant's value (if any)
+macro_rules! enum_codec {
+    ($ty:ident { $($(#[$attr:meta])* $tag:literal => $variant:ident $(($value:ident))?),* $(,)? }) => {
+        impl Codec for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                match self {
+                    $($(#[$attr])* $ty::$variant $(($value))? => {
+                        out.push($tag);
+                        $($value.encode(out);)?
+                    })*
+                }
+            }
+            fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+                match input.byte()? {
+                    $($(#[$attr])* $tag => Ok($ty::$variant $((enum_codec!(@decode input $value)))?),)*
+                    _ => Err(CodecError::InvalidValue),
+                }
+            }
+        }
+    };
+    (@decode $input:ident $value:ident) => {
+        Codec::decode($input)?
+    };
+}
+
+enum_codec!(Display {
+    #[cfg(feature = "block_layout")]
+    0 => Block,
+    #[cfg(feature = "flexbox")]
+    1 => Flex,
+    #[cfg(feature = "grid")]
+    2 => Grid,
+    3 => None,
+    #[cfg(feature = "inline_layout")]
+    4 => Inline,
+});
+enum_codec!(BoxSizing { 0 => BorderBox, 1 => ContentBox });
+enum_codec!(Direction { 0 => Inherit, 1 => Ltr, 2 => Rtl });
+enum_codec!(WritingMode { 0 => Inherit, 1 => HorizontalTb, 2 => VerticalRl, 3 => VerticalLr });
+enum_codec!(Overflow { 0 => Visible, 1 => Clip, 2 => Hidden, 3 => Scroll });
+enum_codec!(ScrollbarGutter { 0 => Auto, 1 => Stable, 2 => StableBothEdges });
+enum_codec!(Position { 0 => Relative, 1 => Absolute });
+enum_codec!(PercentResolutionMode { 0 => Width, 1 => PerAxis });
+enum_codec!(LengthPercentage { 0 => Length(value), 1 => Percent(value) });
+enum_codec!(LengthPercentageAuto { 0 => Length(value), 1 => Percent(value), 2 => Auto });
+enum_codec!(Dimension { 0 => Length(value), 1 => Percent(value), 2 => Auto, 3 => Content, 4 => RootPercent(value) });
+enum_codec!(AvailableSpace { 0 => Definite(value), 1 => MinContent, 2 => MaxContent });
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+enum_codec!(AlignItems {
+    0 => Start,
+    1 => End,
+    2 => FlexStart,
+    3 => FlexEnd,
+    4 => Center,
+    5 => Baseline,
+    6 => Stretch,
+    7 => LastBaseline,
+});
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+enum_codec!(AlignContent {
+    0 => Start,
+    1 => End,
+    2 => FlexStart,
+    3 => FlexEnd,
+    4 => Center,
+    5 => Stretch,
+    6 => SpaceBetween,
+    7 => SpaceEvenly,
+    8 => SpaceAround,
+});
+#[cfg(feature = "block_layout")]
+enum_codec!(TextAlign { 0 => Auto, 1 => LegacyLeft, 2 => LegacyRight, 3 => LegacyCenter });
+#[cfg(feature = "flexbox")]
+enum_codec!(FlexDirection { 0 => Row, 1 => Column, 2 => RowReverse, 3 => ColumnReverse });
+#[cfg(feature = "flexbox")]
+enum_codec!(FlexWrap { 0 => NoWrap, 1 => Wrap, 2 => WrapReverse });
+#[cfg(feature = "grid")]
+enum_codec!(GridAutoFlow { 0 => Row, 1 => Column, 2 => RowDense, 3 => ColumnDense });
+#[cfg(feature = "grid")]
+enum_codec!(GridPlacement { 0 => Auto, 1 => Line(value), 2 => Span(value) });
+#[cfg(feature = "grid")]
+enum_codec!(MinTrackSizingFunction { 0 => Fixed(value), 1 => MinContent, 2 => MaxContent, 3 => Auto });
+#[cfg(feature = "grid")]
+enum_codec!(MaxTrackSizingFunction {
+    0 => Fixed(value),
+    1 => MinContent,
+    2 => MaxContent,
+    3 => FitContent(value),
+    4 => Auto,
+    5 => Fraction(value),
+});
+#[cfg(feature = "grid")]
+enum_codec!(GridTrackRepetition { 0 => AutoFill, 1 => AutoFit, 2 => Count(value) });
+enum_codec!(StyleCommand {
+    0 => Display(value),
+    1 => Position(value),
+    2 => Width(value),
+    3 => Height(value),
+    4 => MinWidth(value),
+    5 => MinHeight(value),
+    6 => MaxWidth(value),
+    7 => MaxHeight(value),
+    8 => InsetLeft(value),
+    9 => InsetRight(value),
+    10 => InsetTop(value),
+    11 => InsetBottom(value),
+    12 => MarginLeft(value),
+    13 => MarginRight(value),
+    14 => MarginTop(value),
+    15 => MarginBottom(value),
+    16 => PaddingLeft(value),
+    17 => PaddingRight(value),
+    18 => PaddingTop(value),
+    19 => PaddingBottom(value),
+    20 => BorderLeft(value),
+    21 => BorderRight(value),
+    22 => BorderTop(value),
+    23 => BorderBottom(value),
+    24 => AspectRatio(value),
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    25 => ColumnGap(value),
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    26 => RowGap(value),
+    #[cfg(feature = "flexbox")]
+    27 => FlexBasis(value),
+    #[cfg(feature = "flexbox")]
+    28 => FlexGrow(value),
+    #[cfg(feature = "flexbox")]
+    29 => FlexShrink(value),
+});
+
+#[cfg(feature = "grid")]
+impl Codec for GridLine {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_i16().encode(out);
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        Ok(GridLine::from(i16::decode(input)?))
+    }
+}
+
+#[cfg(feature = "grid")]
+impl Codec for TrackSizingFunction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            TrackSizingFunction::Single(track) => {
+                out.push(0);
+                track.encode(out);
+            }
+            TrackSizingFunction::Repeat(repetition, tracks) => {
+                out.push(1);
+                repetition.encode(out);
+                tracks.encode(out);
+            }
+        }
+    }
+    fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+        match input.byte()? {
+            0 => Ok(TrackSizingFunction::Single(Codec::decode(input)?)),
+            1 => Ok(TrackSizingFunction::Repeat(Codec::decode(input)?, Codec::decode(input)?)),
+            _ => Err(CodecError::InvalidValue),
+        }
+    }
+}
+
+/// Writes a list of optional fields. Each field is written as a tag identifying it followed by the length of its value,
+/// so that readers can skip fields they don't know about.
+pub(crate) struct FieldWriter {
+    /// The encoded fields
+    bytes: Vec<u8>,
+    /// A buffer used to encode the value of each field before its length is known
+    value: Vec<u8>,
+}
+
+impl FieldWriter {
+    /// Creates a writer with no fields
+    pub(crate) fn new() -> Self {
+        Self { bytes: Vec::new(), value: Vec::new() }
+    }
+
+    /// Writes a field
+    pub(crate) fn field(&mut self, tag: u64, value: &impl Codec) {
+        self.value.clear();
+        value.encode(&mut self.value);
+        tag.encode(&mut self.bytes);
+        self.value.as_slice().encode(&mut self.bytes);
+    }
+
+    /// Appends the list of fields to `out`
+    pub(crate) fn finish(self, out: &mut Vec<u8>) {
+        self.bytes.as_slice().encode(out);
+    }
+}
+
+/// Reads a list of fields written by a [`FieldWriter`], calling `read_field` with the tag and value of each field
+///
+/// `read_field` should ignore fields with unknown tags. It does not need to read the whole value of a field.
+pub(crate) fn read_fields(
+    input: &mut Reader,
+    mut read_field: impl FnMut(u64, &mut Reader) -> Result<(), CodecError>,
+) -> Result<(), CodecError> {
+    let len = usize::decode(input)?;
+    let mut fields = Reader::new(input.take(len)?);
+    while !fields.is_empty() {
+        let tag = u64::decode(&mut fields)?;
+        let len = usize::decode(&mut fields)?;
+        read_field(tag, &mut Reader::new(fields.take(len)?))?;
+    }
+    Ok(())
+}
+
+/// Implements [`Codec`] for a struct as a list of optional fields (see [`FieldWriter`]), each identified by a tag which is
+/// listed explicitly so that it is stable across versions and feature flags
+///
+/// Fields equal to their default value are omitted, and fields which are missing when decoding take their default value.
+macro_rules! fields_codec {
+    ($ty:ident = $default:expr; { $($(#[$attr:meta])* $tag:literal => $field:ident),* $(,)? }) => {
+        impl Codec for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                let default = $default;
+                let mut fields = FieldWriter::new();
+                $($(#[$attr])* if self.$field != default.$field {
+                    fields.field($tag, &self.$field);
+                })*
+                fields.finish(out);
+            }
+            fn decode(input: &mut Reader) -> Result<Self, CodecError> {
+                let mut value = $default;
+                read_fields(input, |tag, input| {
+                    match tag {
+                        $($(#[$attr])* $tag => value.$field = Codec::decode(input)?,)*
+                        _ => {}
+                    }
+                    Ok(())
+                })?;
+                Ok(value)
+            }
+        }
+    };
+}
+#[cfg(feature = "binary")]
+pub(crate) use fields_codec;
+
+fields_codec!(LayoutConfig = LayoutConfig::DEFAULT; {
+    0 => use_rounding,
+    1 => percent_resolution_mode,
+    2 => use_automatic_minimum_size,
+    3 => legacy_stretch,
+    4 => legacy_hidden_grid_items,
+});
+
+fields_codec!(Style = Style::DEFAULT; {
+    0 => display,
+    1 => item_is_table,
+    2 => box_sizing,
+    3 => direction,
+    4 => overflow,
+    5 => scrollbar_width,
+    6 => position,
+    7 => inset,
+    8 => size,
+    9 => min_size,
+    10 => max_size,
+    11 => aspect_ratio,
+    12 => margin,
+    13 => padding,
+    14 => border,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    15 => align_items,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    16 => align_self,
+    #[cfg(feature = "grid")]
+    17 => justify_items,
+    #[cfg(feature = "grid")]
+    18 => justify_self,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    19 => align_content,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    20 => justify_content,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    21 => gap,
+    #[cfg(feature = "block_layout")]
+    22 => text_align,
+    #[cfg(feature = "flexbox")]
+    23 => flex_direction,
+    #[cfg(feature = "flexbox")]
+    24 => flex_wrap,
+    #[cfg(feature = "flexbox")]
+    25 => flex_basis,
+    #[cfg(feature = "flexbox")]
+    26 => flex_grow,
+    #[cfg(feature = "flexbox")]
+    27 => flex_shrink,
+    #[cfg(feature = "grid")]
+    28 => grid_template_rows,
+    #[cfg(feature = "grid")]
+    29 => grid_template_columns,
+    #[cfg(feature = "grid")]
+    30 => grid_auto_rows,
+    #[cfg(feature = "grid")]
+    31 => grid_auto_columns,
+    #[cfg(feature = "grid")]
+    32 => grid_auto_flow,
+    #[cfg(feature = "grid")]
+    33 => grid_row,
+    #[cfg(feature = "grid")]
+    34 => grid_column,
+    35 => scrollbar_gutter,
+    36 => writing_mode,
+});
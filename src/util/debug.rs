@@ -67,30 +67,72 @@ impl DebugLogger {
 #[cfg(any(feature = "debug", feature = "profile"))]
 pub(crate) static NODE_LOGGER: DebugLogger = DebugLogger::new();
 
+/// Emit a trace event for a `debug_log!` call. The events are emitted by these functions (which are never inlined)
+/// rather than by the expansion of `debug_log!` so that the many log calls in the layout algorithms don't each add to
+/// the stack frames of the (deeply recursive) layout functions in unoptimised builds.
+#[cfg(feature = "tracing")]
+pub(crate) mod trace {
+    use core::fmt::{Debug, Display};
+
+    /// Emit a trace event displaying `message`
+    #[inline(never)]
+    pub(crate) fn log(message: impl Display) {
+        ::tracing::trace!("{}", message);
+    }
+
+    /// Emit a trace event displaying `message`, preceded by `label`
+    #[inline(never)]
+    pub(crate) fn labelled_log(label: &str, message: impl Display) {
+        ::tracing::trace!("{} {}", label, message);
+    }
+
+    /// Emit a trace event debug printing `message`
+    #[inline(never)]
+    pub(crate) fn debug_log(message: impl Debug) {
+        ::tracing::trace!("{:?}", message);
+    }
+
+    /// Emit a trace event debug printing `message`, preceded by `label`
+    #[inline(never)]
+    pub(crate) fn labelled_debug_log(label: &str, message: impl Debug) {
+        ::tracing::trace!("{} {:?}", label, message);
+    }
+}
+
+/// Log a message about the layout of the current node. With the `tracing` feature the message is emitted as a trace
+/// event (within the span of the node), otherwise with the `debug` feature it is printed.
 macro_rules! debug_log {
     // String literal label with debug printing
     ($label:literal, dbg:$item:expr) => {{
-        #[cfg(feature = "debug")]
+        #[cfg(feature = "tracing")]
+        $crate::util::debug::trace::labelled_debug_log($label, $item);
+        #[cfg(all(feature = "debug", not(feature = "tracing")))]
         $crate::util::debug::NODE_LOGGER.labelled_debug_log($label, $item);
     }};
     // String literal label with display printing
     ($label:literal, $item:expr) => {{
-        #[cfg(feature = "debug")]
+        #[cfg(feature = "tracing")]
+        $crate::util::debug::trace::labelled_log($label, $item);
+        #[cfg(all(feature = "debug", not(feature = "tracing")))]
         $crate::util::debug::NODE_LOGGER.labelled_log($label, $item);
     }};
     // Debug printing
     (dbg:$item:expr) => {{
-        #[cfg(feature = "debug")]
+        #[cfg(feature = "tracing")]
+        $crate::util::debug::trace::debug_log($item);
+        #[cfg(all(feature = "debug", not(feature = "tracing")))]
         $crate::util::debug::NODE_LOGGER.debug_log($item);
     }};
     // Display printing
     ($item:expr) => {{
-        #[cfg(feature = "debug")]
+        #[cfg(feature = "tracing")]
+        $crate::util::debug::trace::log($item);
+        #[cfg(all(feature = "debug", not(feature = "tracing")))]
         $crate::util::debug::NODE_LOGGER.log($item);
     }};
     // Blank newline
     () => {{
-        #[cfg(feature = "debug")]
+        #[cfg(all(feature = "debug", not(feature = "tracing")))]
         println!();
     }};
 }
@@ -105,17 +147,21 @@ macro_rules! debug_log_node {
     };
 }
 
+/// Start logging about the layout of a node. With the `tracing` feature this enters a span for the node, which is exited
+/// at the end of the calling scope (so needs no matching `debug_pop_node!`).
 macro_rules! debug_push_node {
     ($node_id:expr) => {
-        #[cfg(any(feature = "debug", feature = "profile"))]
+        #[cfg(all(any(feature = "debug", feature = "profile"), not(feature = "tracing")))]
         $crate::util::debug::NODE_LOGGER.push_node($node_id);
+        #[cfg(feature = "tracing")]
+        let _node_span = ::tracing::trace_span!("layout", node = ?$node_id).entered();
         debug_log!("");
     };
 }
 
 macro_rules! debug_pop_node {
     () => {
-        #[cfg(any(feature = "debug", feature = "profile"))]
+        #[cfg(all(any(feature = "debug", feature = "profile"), not(feature = "tracing")))]
         $crate::util::debug::NODE_LOGGER.pop_node();
     };
 }
@@ -127,6 +173,9 @@ macro_rules! time {
         let start = ::std::time::Instant::now();
         $($code)*
         let duration = ::std::time::Instant::now().duration_since(start);
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!("Performed {} in {}ms", $label, duration.as_millis());
+        #[cfg(not(feature = "tracing"))]
         crate::util::debug::NODE_LOGGER.log(format_args!("Performed {} in {}ms", $label, duration.as_millis()));
     };
 }
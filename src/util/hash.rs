@@ -0,0 +1,339 @@
+//! Hashing of styles into 64-bit keys, used to recognise nodes whose layout can be reused after a tree is rebuilt
+//!
+//! Hashes are only meant to be compared within a single run of a program: they are not stable across versions of Taffy
+//! or feature flags.
+use core::hash::{Hash, Hasher};
+use core::mem::discriminant;
+
+#[cfg(feature = "grid")]
+use crate::compute::grid::GridLine;
+use crate::geometry::{Line, MinMax, Point, Rect, Size};
+#[cfg(feature = "block_layout")]
+use crate::style::TextAlign;
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+use crate::style::{AlignContent, AlignItems};
+use crate::style::{
+    BoxSizing, Dimension, Direction, Display, LengthPercentage, LengthPercentageAuto, Overflow, Position,
+    ScrollbarGutter, Style, WritingMode,
+};
+#[cfg(feature = "flexbox")]
+use crate::style::{FlexDirection, FlexWrap};
+#[cfg(feature = "grid")]
+use crate::style::{
+    GridAutoFlow, GridPlacement, GridTrackRepetition, MaxTrackSizingFunction, MinTrackSizingFunction,
+    TrackSizingFunction,
+};
+
+/// A 64-bit FNV-1a hasher
+pub(crate) struct LayoutHasher(u64);
+
+impl LayoutHasher {
+    /// Creates a hasher which has not been given any input
+    pub(crate) const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for LayoutHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A value which can be fed to a [`LayoutHasher`]. Values which compare equal produce the same hash.
+pub(crate) trait LayoutHash {
+    /// Feeds the value to `hasher`
+    fn layout_hash(&self, hasher: &mut LayoutHasher);
+}
+
+/// Hashed by its bits, with `-0.0` treated as `0.0` (as the two compare equal)
+impl LayoutHash for f32 {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        let value = if *self == 0.0 { 0.0f32 } else { *self };
+        hasher.write_u32(value.to_bits());
+    }
+}
+
+impl LayoutHash for bool {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        self.hash(hasher);
+    }
+}
+
+impl LayoutHash for u16 {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        self.hash(hasher);
+    }
+}
+
+#[cfg(feature = "grid")]
+impl LayoutHash for GridLine {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        self.as_i16().hash(hasher);
+    }
+}
+
+impl<T: LayoutHash> LayoutHash for Option<T> {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        match self {
+            Some(value) => {
+                hasher.write_u8(1);
+                value.layout_hash(hasher);
+            }
+            None => hasher.write_u8(0),
+        }
+    }
+}
+
+impl<T: LayoutHash> LayoutHash for [T] {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        hasher.write_usize(self.len());
+        for value in self {
+            value.layout_hash(hasher);
+        }
+    }
+}
+
+/// Implements [`LayoutHash`] for structs by hashing each field in turn
+macro_rules! struct_hash {
+    ($ty:ident <$($param:ident),+> { $($field:ident),* $(,)? }) => {
+        impl<$($param: LayoutHash),+> LayoutHash for $ty<$($param),+> {
+            fn layout_hash(&self, hasher: &mut LayoutHasher) {
+                $(self.$field.layout_hash(hasher);)*
+            }
+        }
+    };
+}
+struct_hash!(Size<T> { width, height });
+struct_hash!(Rect<T> { left, right, top, bottom });
+struct_hash!(Point<T> { x, y });
+struct_hash!(Line<T> { start, end });
+struct_hash!(MinMax<Min, Max> { min, max });
+
+/// Implements [`LayoutHash`] for enums by hashing the variant followed by its value (if any)
+macro_rules! enum_hash {
+    ($ty:ident { $($variant:ident $(($value:ident))?),* $(,)? }) => {
+        impl LayoutHash for $ty {
+            fn layout_hash(&self, hasher: &mut LayoutHasher) {
+                discriminant(self).hash(hasher);
+                match self {
+                    $($ty::$variant $(($value))? => {
+                        $($value.layout_hash(hasher);)?
+                    })*
+                }
+            }
+        }
+    };
+}
+
+/// Implements [`LayoutHash`] for enums whose variants hold no values
+macro_rules! fieldless_enum_hash {
+    ($($(#[$attr:meta])* $ty:ident),* $(,)?) => {
+        $($(#[$attr])*
+        impl LayoutHash for $ty {
+            fn layout_hash(&self, hasher: &mut LayoutHasher) {
+                discriminant(self).hash(hasher);
+            }
+        })*
+    };
+}
+
+fieldless_enum_hash!(
+    Display,
+    BoxSizing,
+    Direction,
+    WritingMode,
+    Overflow,
+    ScrollbarGutter,
+    Position,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    AlignItems,
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    AlignContent,
+    #[cfg(feature = "block_layout")]
+    TextAlign,
+    #[cfg(feature = "flexbox")]
+    FlexDirection,
+    #[cfg(feature = "flexbox")]
+    FlexWrap,
+    #[cfg(feature = "grid")]
+    GridAutoFlow,
+);
+enum_hash!(LengthPercentage { Length(value), Percent(value) });
+enum_hash!(LengthPercentageAuto { Length(value), Percent(value), Auto });
+enum_hash!(Dimension { Length(value), Percent(value), RootPercent(value), Auto, Content });
+#[cfg(feature = "grid")]
+enum_hash!(GridPlacement { Auto, Line(value), Span(value) });
+#[cfg(feature = "grid")]
+enum_hash!(MinTrackSizingFunction { Fixed(value), MinContent, MaxContent, Auto });
+#[cfg(feature = "grid")]
+enum_hash!(MaxTrackSizingFunction { Fixed(value), MinContent, MaxContent, FitContent(value), Auto, Fraction(value) });
+#[cfg(feature = "grid")]
+enum_hash!(GridTrackRepetition { AutoFill, AutoFit, Count(value) });
+
+#[cfg(feature = "grid")]
+impl LayoutHash for TrackSizingFunction {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        discriminant(self).hash(hasher);
+        match self {
+            TrackSizingFunction::Single(track) => track.layout_hash(hasher),
+            TrackSizingFunction::Repeat(repetition, tracks) => {
+                repetition.layout_hash(hasher);
+                tracks.layout_hash(hasher);
+            }
+        }
+    }
+}
+
+impl LayoutHash for Style {
+    fn layout_hash(&self, hasher: &mut LayoutHasher) {
+        // Destructured so that a field added to `Style` can't be forgotten here
+        let Style {
+            display,
+            item_is_table,
+            box_sizing,
+            direction,
+            writing_mode,
+            overflow,
+            scrollbar_width,
+            scrollbar_gutter,
+            position,
+            inset,
+            size,
+            min_size,
+            max_size,
+            aspect_ratio,
+            margin,
+            padding,
+            border,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_items,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_self,
+            #[cfg(feature = "grid")]
+            justify_items,
+            #[cfg(feature = "grid")]
+            justify_self,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            align_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            justify_content,
+            #[cfg(any(feature = "flexbox", feature = "grid"))]
+            gap,
+            #[cfg(feature = "block_layout")]
+            text_align,
+            #[cfg(feature = "flexbox")]
+            flex_direction,
+            #[cfg(feature = "flexbox")]
+            flex_wrap,
+            #[cfg(feature = "flexbox")]
+            flex_basis,
+            #[cfg(feature = "flexbox")]
+            flex_grow,
+            #[cfg(feature = "flexbox")]
+            flex_shrink,
+            #[cfg(feature = "grid")]
+            grid_template_rows,
+            #[cfg(feature = "grid")]
+            grid_template_columns,
+            #[cfg(feature = "grid")]
+            grid_auto_rows,
+            #[cfg(feature = "grid")]
+            grid_auto_columns,
+            #[cfg(feature = "grid")]
+            grid_auto_flow,
+            #[cfg(feature = "grid")]
+            grid_row,
+            #[cfg(feature = "grid")]
+            grid_column,
+        } = self;
+
+        display.layout_hash(hasher);
+        item_is_table.layout_hash(hasher);
+        box_sizing.layout_hash(hasher);
+        direction.layout_hash(hasher);
+        writing_mode.layout_hash(hasher);
+        overflow.layout_hash(hasher);
+        scrollbar_width.layout_hash(hasher);
+        scrollbar_gutter.layout_hash(hasher);
+        position.layout_hash(hasher);
+        inset.layout_hash(hasher);
+        size.layout_hash(hasher);
+        min_size.layout_hash(hasher);
+        max_size.layout_hash(hasher);
+        aspect_ratio.layout_hash(hasher);
+        margin.layout_hash(hasher);
+        padding.layout_hash(hasher);
+        border.layout_hash(hasher);
+        #[cfg(any(feature = "flexbox", feature = "grid"))]
+        {
+            align_items.layout_hash(hasher);
+            align_self.layout_hash(hasher);
+            align_content.layout_hash(hasher);
+            justify_content.layout_hash(hasher);
+            gap.layout_hash(hasher);
+        }
+        #[cfg(feature = "block_layout")]
+        text_align.layout_hash(hasher);
+        #[cfg(feature = "flexbox")]
+        {
+            flex_direction.layout_hash(hasher);
+            flex_wrap.layout_hash(hasher);
+            flex_basis.layout_hash(hasher);
+            flex_grow.layout_hash(hasher);
+            flex_shrink.layout_hash(hasher);
+        }
+        #[cfg(feature = "grid")]
+        {
+            justify_items.layout_hash(hasher);
+            justify_self.layout_hash(hasher);
+            grid_template_rows.layout_hash(hasher);
+            grid_template_columns.layout_hash(hasher);
+            grid_auto_rows.layout_hash(hasher);
+            grid_auto_columns.layout_hash(hasher);
+            grid_auto_flow.layout_hash(hasher);
+            grid_row.layout_hash(hasher);
+            grid_column.layout_hash(hasher);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LayoutHash, LayoutHasher};
+    use crate::style::Style;
+    use crate::style_helpers::{length, percent};
+    use core::hash::Hasher;
+
+    fn hash_style(style: &Style) -> u64 {
+        let mut hasher = LayoutHasher::new();
+        style.layout_hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_styles_have_equal_hashes() {
+        let style = Style { size: length(10.0), margin: percent(0.5), ..Default::default() };
+        assert_eq!(hash_style(&style), hash_style(&style.clone()));
+        assert_eq!(
+            hash_style(&Style { aspect_ratio: Some(0.0), ..Default::default() }),
+            hash_style(&Style { aspect_ratio: Some(-0.0), ..Default::default() })
+        );
+
+        assert_ne!(hash_style(&style), hash_style(&Style::default()));
+        assert_ne!(
+            hash_style(&Style { size: length(10.0), ..Default::default() }),
+            hash_style(&Style { size: percent(10.0), ..Default::default() })
+        );
+        assert_ne!(
+            hash_style(&Style { aspect_ratio: None, ..Default::default() }),
+            hash_style(&Style { aspect_ratio: Some(0.0), ..Default::default() })
+        );
+    }
+}
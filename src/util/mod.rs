@@ -1,16 +1,38 @@
 //! Helpful misc. utilities such as a function to debug print a tree
 mod math;
+pub(crate) mod precision;
 mod resolve;
 pub(crate) mod sys;
 
+#[cfg(any(feature = "record", feature = "binary"))]
+pub(crate) mod codec;
+
+#[cfg(all(feature = "taffy_tree", feature = "std"))]
+pub(crate) mod hash;
+
+#[cfg(feature = "debug_checks")]
+pub(crate) mod checks;
+
 pub use math::MaybeMath;
+#[cfg(feature = "flexbox")]
+pub(crate) use resolve::RectLanes;
 pub use resolve::{MaybeResolve, ResolveOrZero};
 
 #[doc(hidden)]
 #[macro_use]
 pub(crate) mod debug;
 
+#[cfg(feature = "allocator_hook")]
+mod alloc_hook;
+#[cfg(feature = "allocator_hook")]
+pub use alloc_hook::{set_allocator_hook, AllocFunction, AllocatorInUse, FreeFunction, HookAllocator};
+
 #[cfg(feature = "std")]
 mod print;
 #[cfg(feature = "std")]
 pub use print::print_tree;
+
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "std")]
+pub use snapshot::{compare_snapshots, snapshot_tree, SnapshotMismatch};
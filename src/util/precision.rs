@@ -0,0 +1,108 @@
+//! The precision in which the layout algorithms sum sizes and offsets
+//!
+//! Styles and layouts are always `f32`. With the `f64` feature enabled the layout algorithms sum the sizes of
+//! consecutive items, and accumulate the offsets at which they are placed, in double precision, only converting the
+//! result back to `f32`. This avoids the error which otherwise builds up when many items are laid out along very large
+//! (100k+ px) axes.
+//!
+//! With the `fixed_point` feature enabled every value is snapped to the fixed point grid (see
+//! [`quantize`](crate::quantize)) before it is added, which makes the sums exact and so independent of the order in which
+//! the values are added.
+
+/// The type in which the layout algorithms sum sizes and accumulate offsets
+#[cfg(feature = "f64")]
+pub(crate) type Scalar = f64;
+/// The type in which the layout algorithms sum sizes and accumulate offsets
+#[cfg(not(feature = "f64"))]
+pub(crate) type Scalar = f32;
+
+/// Rounds a [`Scalar`] to the nearest whole number
+#[cfg(not(feature = "f64"))]
+pub(crate) use crate::util::sys::round as round_scalar;
+/// Rounds a [`Scalar`] to the nearest whole number
+#[cfg(feature = "f64")]
+pub(crate) use crate::util::sys::round_f64 as round_scalar;
+
+/// Sums an iterator of `f32`s in [`Scalar`] precision
+pub(crate) trait PreciseSum: Iterator<Item = f32> + Sized {
+    /// Sums the values in [`Scalar`] precision and converts the result back to `f32`
+    #[inline(always)]
+    #[allow(clippy::unnecessary_cast, clippy::useless_conversion)] // Only unnecessary if the `f64` feature is disabled
+    fn precise_sum(self) -> f32 {
+        self.map(|value| Scalar::from(snap(value))).sum::<Scalar>() as f32
+    }
+}
+
+impl<I: Iterator<Item = f32>> PreciseSum for I {}
+
+/// A running offset which is accumulated in [`Scalar`] precision
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Offset(Scalar);
+
+impl Offset {
+    /// Creates an offset starting at `value`
+    #[inline(always)]
+    #[allow(clippy::useless_conversion)] // The conversion is only useless if the `f64` feature is disabled
+    pub(crate) fn new(value: f32) -> Self {
+        Self(Scalar::from(value))
+    }
+
+    /// Returns the current value of the offset
+    #[inline(always)]
+    #[allow(clippy::unnecessary_cast)] // The cast is only unnecessary if the `f64` feature is disabled
+    pub(crate) fn get(self) -> f32 {
+        self.0 as f32
+    }
+
+    /// Advances the offset by `value`
+    #[inline(always)]
+    #[allow(clippy::useless_conversion)] // The conversion is only useless if the `f64` feature is disabled
+    pub(crate) fn add(&mut self, value: f32) {
+        self.0 += Scalar::from(snap(value));
+    }
+}
+
+/// Snaps a value which is about to be summed to the fixed point grid, if the `fixed_point` feature is enabled
+#[inline(always)]
+#[allow(clippy::let_and_return)] // Only a plain return if the `fixed_point` feature is disabled
+fn snap(value: f32) -> f32 {
+    #[cfg(feature = "fixed_point")]
+    let value = crate::tree::quantize(value);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Offset, PreciseSum};
+
+    #[test]
+    #[cfg(feature = "fixed_point")]
+    fn sums_of_snapped_values_do_not_depend_on_their_order() {
+        let values = [0.1f32, 1e5, 0.2, -1e5, 0.3];
+        let forwards = values.into_iter().precise_sum();
+        let backwards = values.into_iter().rev().precise_sum();
+        assert_eq!(forwards, backwards);
+        assert_eq!(forwards, crate::tree::quantize(0.1) + crate::tree::quantize(0.2) + crate::tree::quantize(0.3));
+    }
+
+    #[test]
+    fn offsets_and_sums_start_from_the_provided_values() {
+        let mut offset = Offset::new(10.0);
+        offset.add(2.5);
+        offset.add(-0.5);
+        assert_eq!(offset.get(), 12.0);
+        assert_eq!([1.0f32, 2.0, 3.5].into_iter().precise_sum(), 6.5);
+    }
+
+    #[test]
+    #[cfg(feature = "f64")]
+    fn offsets_are_accumulated_in_double_precision() {
+        // 0.1 is not exactly representable, so summing it 1 million times in f32 drifts by over 1000 px
+        let mut offset = Offset::new(0.0);
+        for _ in 0..1_000_000 {
+            offset.add(0.1);
+        }
+        assert!((offset.get() - 100_000.0).abs() < 0.01);
+        assert!((core::iter::repeat(0.1f32).take(1_000_000).precise_sum() - 100_000.0).abs() < 0.01);
+    }
+}
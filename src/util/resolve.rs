@@ -22,6 +22,24 @@ pub trait MaybeResolve<In, Out> {
 pub trait ResolveOrZero<TContext, TOutput: TaffyZero> {
     /// Resolve a dimension that might be dependent on a context, with a default fallback value
     fn resolve_or_zero(self, context: TContext) -> TOutput;
+
+    /// Resolves four values against their corresponding contexts, such as the four sides of a [`Rect`] or the same
+    /// side of four [`Rect`]s. Implementations may override this to resolve all four at once with SIMD.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn resolve_lanes_or_zero(values: [Self; 4], context: [TContext; 4]) -> [TOutput; 4]
+    where
+        Self: Sized,
+    {
+        let [a, b, c, d] = values;
+        let [context_a, context_b, context_c, context_d] = context;
+        [
+            a.resolve_or_zero(context_a),
+            b.resolve_or_zero(context_b),
+            c.resolve_or_zero(context_c),
+            d.resolve_or_zero(context_d),
+        ]
+    }
 }
 
 impl MaybeResolve<Option<f32>, Option<f32>> for LengthPercentage {
@@ -55,7 +73,8 @@ impl MaybeResolve<Option<f32>, Option<f32>> for Dimension {
         match self {
             Dimension::Length(length) => Some(length),
             Dimension::Percent(percent) => context.map(|dim| dim * percent),
-            Dimension::Auto => None,
+            // Root percentages are resolved by `TaffyTree` before layout, and otherwise behave as `auto`
+            Dimension::Auto | Dimension::Content | Dimension::RootPercent(_) => None,
         }
     }
 }
@@ -83,6 +102,12 @@ impl ResolveOrZero<Option<f32>, f32> for LengthPercentage {
     fn resolve_or_zero(self, context: Option<f32>) -> f32 {
         self.maybe_resolve(context).unwrap_or(0.0)
     }
+
+    #[cfg(feature = "simd")]
+    #[inline(always)]
+    fn resolve_lanes_or_zero(values: [Self; 4], context: [Option<f32>; 4]) -> [f32; 4] {
+        simd::resolve_lanes_or_zero(values, context)
+    }
 }
 
 impl ResolveOrZero<Option<f32>, f32> for LengthPercentageAuto {
@@ -90,6 +115,12 @@ impl ResolveOrZero<Option<f32>, f32> for LengthPercentageAuto {
     fn resolve_or_zero(self, context: Option<f32>) -> f32 {
         self.maybe_resolve(context).unwrap_or(0.0)
     }
+
+    #[cfg(feature = "simd")]
+    #[inline(always)]
+    fn resolve_lanes_or_zero(values: [Self; 4], context: [Option<f32>; 4]) -> [f32; 4] {
+        simd::resolve_lanes_or_zero(values, context)
+    }
 }
 
 impl ResolveOrZero<Option<f32>, f32> for Dimension {
@@ -97,6 +128,12 @@ impl ResolveOrZero<Option<f32>, f32> for Dimension {
     fn resolve_or_zero(self, context: Option<f32>) -> f32 {
         self.maybe_resolve(context).unwrap_or(0.0)
     }
+
+    #[cfg(feature = "simd")]
+    #[inline(always)]
+    fn resolve_lanes_or_zero(values: [Self; 4], context: [Option<f32>; 4]) -> [f32; 4] {
+        simd::resolve_lanes_or_zero(values, context)
+    }
 }
 
 // Generic ResolveOrZero for Size
@@ -111,12 +148,11 @@ impl<In, Out: TaffyZero, T: ResolveOrZero<In, Out>> ResolveOrZero<Size<In>, Size
 impl<In: Copy, Out: TaffyZero, T: ResolveOrZero<In, Out>> ResolveOrZero<Size<In>, Rect<Out>> for Rect<T> {
     /// Converts any `parent`-relative values for Rect into an absolute Rect
     fn resolve_or_zero(self, context: Size<In>) -> Rect<Out> {
-        Rect {
-            left: self.left.resolve_or_zero(context.width),
-            right: self.right.resolve_or_zero(context.width),
-            top: self.top.resolve_or_zero(context.height),
-            bottom: self.bottom.resolve_or_zero(context.height),
-        }
+        let [left, right, top, bottom] = T::resolve_lanes_or_zero(
+            [self.left, self.right, self.top, self.bottom],
+            [context.width, context.width, context.height, context.height],
+        );
+        Rect { left, right, top, bottom }
     }
 }
 
@@ -124,13 +160,119 @@ impl<In: Copy, Out: TaffyZero, T: ResolveOrZero<In, Out>> ResolveOrZero<Size<In>
 impl<Out: TaffyZero, T: ResolveOrZero<Option<f32>, Out>> ResolveOrZero<Option<f32>, Rect<Out>> for Rect<T> {
     /// Converts any `parent`-relative values for Rect into an absolute Rect
     fn resolve_or_zero(self, context: Option<f32>) -> Rect<Out> {
-        Rect {
-            left: self.left.resolve_or_zero(context),
-            right: self.right.resolve_or_zero(context),
-            top: self.top.resolve_or_zero(context),
-            bottom: self.bottom.resolve_or_zero(context),
+        let [left, right, top, bottom] =
+            T::resolve_lanes_or_zero([self.left, self.right, self.top, self.bottom], [context; 4]);
+        Rect { left, right, top, bottom }
+    }
+}
+
+/// The sides of up to four [`Rect`]s stored as a struct of arrays (one array per side), so that the same side of every
+/// rect can be resolved at once with [`ResolveOrZero::resolve_lanes_or_zero`]
+#[cfg(feature = "flexbox")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RectLanes<T> {
+    /// The left side of each rect
+    left: [T; 4],
+    /// The right side of each rect
+    right: [T; 4],
+    /// The top side of each rect
+    top: [T; 4],
+    /// The bottom side of each rect
+    bottom: [T; 4],
+}
+
+#[cfg(feature = "flexbox")]
+impl<T: Copy + TaffyZero> RectLanes<T> {
+    /// Transposes the first four of `rects` into lanes, filling the lanes of any missing rects with zero
+    pub(crate) fn new(rects: impl IntoIterator<Item = Rect<T>>) -> Self {
+        let mut lanes = Self { left: [T::ZERO; 4], right: [T::ZERO; 4], top: [T::ZERO; 4], bottom: [T::ZERO; 4] };
+        for (lane, rect) in rects.into_iter().take(4).enumerate() {
+            lanes.left[lane] = rect.left;
+            lanes.right[lane] = rect.right;
+            lanes.top[lane] = rect.top;
+            lanes.bottom[lane] = rect.bottom;
+        }
+        lanes
+    }
+}
+
+#[cfg(feature = "flexbox")]
+impl<T: ResolveOrZero<Option<f32>, f32>> RectLanes<T> {
+    /// Resolves every rect against `context`, the horizontal sides against its width and the vertical sides against
+    /// its height
+    pub(crate) fn resolve_or_zero(self, context: Size<Option<f32>>) -> [Rect<f32>; 4] {
+        let left = T::resolve_lanes_or_zero(self.left, [context.width; 4]);
+        let right = T::resolve_lanes_or_zero(self.right, [context.width; 4]);
+        let top = T::resolve_lanes_or_zero(self.top, [context.height; 4]);
+        let bottom = T::resolve_lanes_or_zero(self.bottom, [context.height; 4]);
+        core::array::from_fn(|lane| Rect { left: left[lane], right: right[lane], top: top[lane], bottom: bottom[lane] })
+    }
+}
+
+/// Resolution of four values as one SIMD vector, used with the `simd` feature
+#[cfg(feature = "simd")]
+mod simd {
+    use crate::style::{Dimension, LengthPercentage, LengthPercentageAuto};
+    use core::simd::{f32x4, mask32x4, Select};
+
+    /// Splits a value into its absolute length and the fraction of the context it is a percentage of
+    pub(super) trait LengthAndPercent: Copy {
+        /// Returns the absolute length (zero if the value is not a length) and the percentage (if the value is one)
+        fn length_and_percent(self) -> (f32, Option<f32>);
+    }
+
+    impl LengthAndPercent for LengthPercentage {
+        fn length_and_percent(self) -> (f32, Option<f32>) {
+            match self {
+                LengthPercentage::Length(length) => (length, None),
+                LengthPercentage::Percent(percent) => (0.0, Some(percent)),
+            }
+        }
+    }
+
+    impl LengthAndPercent for LengthPercentageAuto {
+        fn length_and_percent(self) -> (f32, Option<f32>) {
+            match self {
+                LengthPercentageAuto::Length(length) => (length, None),
+                LengthPercentageAuto::Percent(percent) => (0.0, Some(percent)),
+                LengthPercentageAuto::Auto => (0.0, None),
+            }
+        }
+    }
+
+    impl LengthAndPercent for Dimension {
+        fn length_and_percent(self) -> (f32, Option<f32>) {
+            match self {
+                Dimension::Length(length) => (length, None),
+                Dimension::Percent(percent) => (0.0, Some(percent)),
+                Dimension::Auto | Dimension::Content | Dimension::RootPercent(_) => (0.0, None),
+            }
         }
     }
+
+    /// Resolves four values against their percentage bases
+    ///
+    /// Every value's percentage is multiplied by its basis in a single vector multiplication, and the product is then
+    /// selected over the value's length for the values which are percentages of a known basis.
+    #[inline(always)]
+    pub(super) fn resolve_lanes_or_zero<T: LengthAndPercent>(values: [T; 4], context: [Option<f32>; 4]) -> [f32; 4] {
+        let mut lengths = [0.0; 4];
+        let mut percents = [0.0; 4];
+        let mut bases = [0.0; 4];
+        let mut is_resolved_percent = [false; 4];
+        for (lane, value) in values.into_iter().enumerate() {
+            let (length, percent) = value.length_and_percent();
+            lengths[lane] = length;
+            if let (Some(percent), Some(basis)) = (percent, context[lane]) {
+                percents[lane] = percent;
+                bases[lane] = basis;
+                is_resolved_percent[lane] = true;
+            }
+        }
+
+        let products = f32x4::from_array(bases) * f32x4::from_array(percents);
+        mask32x4::from_array(is_resolved_percent).select(products, f32x4::from_array(lengths)).to_array()
+    }
 }
 
 #[cfg(test)]
@@ -330,4 +472,50 @@ mod tests {
             roz_case(Rect::from_percent(5.0, 5.0, 5.0, 5.0), Some(0.0), Rect::zero());
         }
     }
+
+    #[cfg(feature = "flexbox")]
+    mod rect_lanes {
+        use crate::geometry::{Rect, Size};
+        use crate::style::Dimension;
+        use crate::util::resolve::RectLanes;
+
+        #[test]
+        fn resolves_each_lane_against_the_context() {
+            let rects = [
+                Rect::from_length(1.0, 2.0, 3.0, 4.0),
+                Rect::from_percent(0.1, 0.2, 0.5, 1.0),
+                Rect::<Dimension>::auto(),
+            ];
+            let resolved = RectLanes::new(rects).resolve_or_zero(Size::new(100.0, 10.0));
+            assert_eq!(resolved[0], Rect::new(1.0, 2.0, 3.0, 4.0));
+            assert_eq!(resolved[1], Rect::new(10.0, 20.0, 5.0, 10.0));
+            assert_eq!(resolved[2], Rect::zero());
+            // The lane without a rect is zero
+            assert_eq!(resolved[3], Rect::zero());
+        }
+
+        #[test]
+        fn resolves_percentages_of_an_unknown_context_to_zero() {
+            let rects = [Rect::from_percent(0.1, 0.2, 0.5, 1.0), Rect::from_length(1.0, 2.0, 3.0, 4.0)];
+            let resolved = RectLanes::new(rects).resolve_or_zero(Size { width: None, height: Some(10.0) });
+            assert_eq!(resolved[0], Rect::new(0.0, 0.0, 5.0, 10.0));
+            assert_eq!(resolved[1], Rect::new(1.0, 2.0, 3.0, 4.0));
+        }
+
+        #[test]
+        fn matches_resolving_each_rect() {
+            use crate::util::ResolveOrZero;
+            let rects = [
+                Rect::from_percent(0.25, 0.5, 0.75, 1.0),
+                Rect::from_length(5.0, 6.0, 7.0, 8.0),
+                Rect::from_percent(1.0, 1.0, 1.0, 1.0),
+                Rect::<Dimension>::auto(),
+            ];
+            let context = Size::new(40.0, 20.0);
+            let resolved = RectLanes::new(rects).resolve_or_zero(context);
+            for (rect, resolved) in rects.into_iter().zip(resolved) {
+                assert_eq!(rect.resolve_or_zero(context), resolved);
+            }
+        }
+    }
 }
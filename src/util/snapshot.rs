@@ -0,0 +1,200 @@
+//! Contains functions for producing and comparing canonical text snapshots of a tree's computed layout
+//!
+//! Snapshots are intended for "golden" regression tests: store the output of [`snapshot_tree`] alongside the test and
+//! check future layouts against it using [`compare_snapshots`].
+use core::fmt::Write;
+
+use crate::geometry::{Point, Rect, Size};
+use crate::tree::{NodeId, PrintTree};
+
+/// Produces a stable, canonical JSON representation of the computed layout of the subtree rooted at `root`
+///
+/// - Object keys are sorted alphabetically and values are printed with 3 decimal places (with `-0` normalized to `0`).
+/// - Node ids are omitted, so snapshots do not depend on the order in which nodes were created. Children are nested
+///   within their parent in order.
+pub fn snapshot_tree(tree: &impl PrintTree, root: NodeId) -> String {
+    let mut output = String::new();
+    write_node(tree, root, 0, &mut output);
+    output.push('\n');
+    return output;
+
+    /// Recursive function that writes each node in the tree
+    fn write_node(tree: &impl PrintTree, node_id: NodeId, depth: usize, output: &mut String) {
+        let layout = tree.get_final_layout(node_id);
+        let indent = "  ".repeat(depth + 1);
+
+        output.push_str("{\n");
+        let _ = writeln!(output, "{indent}\"border\": {},", rect(layout.border));
+        let _ = write!(output, "{indent}\"children\": [");
+        let child_count = tree.child_count(node_id);
+        for (index, child) in tree.child_ids(node_id).enumerate() {
+            output.push('\n');
+            output.push_str(&indent);
+            output.push_str("  ");
+            write_node(tree, child, depth + 2, output);
+            if index + 1 < child_count {
+                output.push(',');
+            }
+        }
+        if child_count > 0 {
+            output.push('\n');
+            output.push_str(&indent);
+        }
+        output.push_str("],\n");
+        #[cfg(feature = "content_size")]
+        let _ = writeln!(output, "{indent}\"content_size\": {},", size(layout.content_size));
+        let _ = writeln!(output, "{indent}\"label\": \"{}\",", tree.get_debug_label(node_id));
+        let _ = writeln!(output, "{indent}\"location\": {},", point(layout.location));
+        let _ = writeln!(output, "{indent}\"margin\": {},", rect(layout.margin));
+        let _ = writeln!(output, "{indent}\"order\": {},", layout.order);
+        let _ = writeln!(output, "{indent}\"padding\": {},", rect(layout.padding));
+        let _ = writeln!(output, "{indent}\"scrollbar_size\": {},", size(layout.scrollbar_size));
+        let _ = writeln!(output, "{indent}\"size\": {}", size(layout.size));
+        output.push_str(&"  ".repeat(depth));
+        output.push('}');
+    }
+
+    /// Formats a value with a fixed precision
+    fn number(value: f32) -> String {
+        let formatted = format!("{value:.3}");
+        if formatted == "-0.000" {
+            String::from("0.000")
+        } else {
+            formatted
+        }
+    }
+
+    /// Formats a point as a JSON object
+    fn point(value: Point<f32>) -> String {
+        format!("{{ \"x\": {}, \"y\": {} }}", number(value.x), number(value.y))
+    }
+
+    /// Formats a size as a JSON object
+    fn size(value: Size<f32>) -> String {
+        format!("{{ \"height\": {}, \"width\": {} }}", number(value.height), number(value.width))
+    }
+
+    /// Formats a rect as a JSON object
+    fn rect(value: Rect<f32>) -> String {
+        format!(
+            "{{ \"bottom\": {}, \"left\": {}, \"right\": {}, \"top\": {} }}",
+            number(value.bottom),
+            number(value.left),
+            number(value.right),
+            number(value.top)
+        )
+    }
+}
+
+/// The first difference found by [`compare_snapshots`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotMismatch {
+    /// The (1-based) line number at which the snapshots differ
+    pub line: usize,
+    /// The line from the expected snapshot (empty if the expected snapshot has fewer lines)
+    pub expected: String,
+    /// The line from the actual snapshot (empty if the actual snapshot has fewer lines)
+    pub actual: String,
+}
+
+impl core::fmt::Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Snapshots differ at line {}:\n  expected: {}\n  actual:   {}", self.line, self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for SnapshotMismatch {}
+
+/// Compares two snapshots produced by [`snapshot_tree`], allowing each numeric value to differ by up to `tolerance`
+///
+/// All non-numeric content (keys, labels and structure) must match exactly.
+pub fn compare_snapshots(expected: &str, actual: &str, tolerance: f32) -> Result<(), SnapshotMismatch> {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 0;
+    loop {
+        line += 1;
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return Ok(()),
+            (expected_line, actual_line) => {
+                let expected_line = expected_line.unwrap_or("");
+                let actual_line = actual_line.unwrap_or("");
+                if !lines_match(expected_line, actual_line, tolerance) {
+                    return Err(SnapshotMismatch {
+                        line,
+                        expected: String::from(expected_line),
+                        actual: String::from(actual_line),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Compares a single line of two snapshots
+    fn lines_match(expected: &str, actual: &str, tolerance: f32) -> bool {
+        let mut expected_tokens = tokens(expected);
+        let mut actual_tokens = tokens(actual);
+        loop {
+            match (expected_tokens.next(), actual_tokens.next()) {
+                (None, None) => return true,
+                (Some(Token::Number(a)), Some(Token::Number(b))) if (a - b).abs() <= tolerance => {}
+                (Some(Token::Text(a)), Some(Token::Text(b))) if a == b => {}
+                _ => return false,
+            }
+        }
+    }
+
+    /// A piece of a snapshot line
+    enum Token<'a> {
+        /// A numeric value
+        Number(f32),
+        /// Any other content
+        Text(&'a str),
+    }
+
+    /// Splits a line into numeric values and the text between them
+    fn tokens(line: &str) -> impl Iterator<Item = Token<'_>> {
+        let is_numeric = |c: char| c.is_ascii_digit() || c == '.' || c == '-';
+        let mut rest = line;
+        core::iter::from_fn(move || {
+            let first = rest.chars().next()?;
+            let numeric = is_numeric(first);
+            let end = rest.find(|c: char| is_numeric(c) != numeric).unwrap_or(rest.len());
+            let (token, remainder) = rest.split_at(end);
+            rest = remainder;
+            Some(match token.parse::<f32>() {
+                Ok(value) if numeric => Token::Number(value),
+                _ => Token::Text(token),
+            })
+        })
+    }
+}
+
+#[cfg(all(test, feature = "taffy_tree"))]
+mod tests {
+    use super::{compare_snapshots, snapshot_tree};
+    use crate::prelude::*;
+
+    #[test]
+    fn snapshot_is_stable_and_comparable() {
+        let mut tree: TaffyTree<()> = TaffyTree::new();
+        let child = tree
+            .new_leaf(Style { size: Size::from_lengths(10.0, 20.0), margin: length(1.0), ..Default::default() })
+            .unwrap();
+        let root = tree.new_with_children(Style::default(), &[child]).unwrap();
+        tree.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+        let snapshot = snapshot_tree(&tree, root);
+        assert_eq!(snapshot, tree.snapshot(root));
+        assert!(snapshot.contains("\"label\": \"FLEX ROW\""));
+        assert!(snapshot.contains("\"size\": { \"height\": 20.000, \"width\": 10.000 }"));
+
+        let nudged = snapshot.replace("10.000", "10.004");
+        assert!(compare_snapshots(&snapshot, &nudged, 0.01).is_ok());
+        let mismatch = compare_snapshots(&snapshot, &nudged, 0.001).unwrap_err();
+        assert_eq!(mismatch.expected.trim(), "\"size\": { \"height\": 20.000, \"width\": 10.000 }");
+
+        let relabelled = snapshot.replace("LEAF", "NONE");
+        assert!(compare_snapshots(&snapshot, &relabelled, 1.0).is_err());
+    }
+}
@@ -17,11 +17,28 @@ pub(crate) use self::core::*;
 mod std {
     /// An allocation-backend agnostic vector type
     pub(crate) type Vec<A> = std::vec::Vec<A>;
+    /// An allocation-backend agnostic box type
+    #[cfg(any(feature = "compact_style", feature = "inline_layout"))]
+    pub(crate) type Box<A> = std::boxed::Box<A>;
+    /// An allocation-backend agnostic atomically reference-counted pointer type
+    #[cfg(feature = "taffy_tree")]
+    pub(crate) type Arc<A> = std::sync::Arc<A>;
     /// A vector of child nodes
+    #[cfg(not(feature = "smallvec"))]
     pub(crate) type ChildrenVec<A> = std::vec::Vec<A>;
+    /// A vector of child nodes, which stores up to 4 of them inline
+    #[cfg(feature = "smallvec")]
+    pub(crate) type ChildrenVec<A> = smallvec::SmallVec<[A; 4]>;
     #[cfg(feature = "grid")]
     /// A vector of grid tracks
     pub(crate) type GridTrackVec<A> = std::vec::Vec<A>;
+    #[cfg(all(feature = "grid", not(feature = "smallvec")))]
+    /// A vector of the tracks and gutters of a grid which is being laid out
+    pub(crate) type LayoutTrackVec<A> = std::vec::Vec<A>;
+    #[cfg(all(feature = "grid", feature = "smallvec"))]
+    /// A vector of the tracks and gutters of a grid which is being laid out, which stores up to 8 of them (enough for
+    /// 3 tracks, their gutters and the grid's edges) inline
+    pub(crate) type LayoutTrackVec<A> = smallvec::SmallVec<[A; 8]>;
 
     /// Creates a new vector with the capacity for the specified number of items before it must be resized
     #[must_use]
@@ -29,6 +46,12 @@ mod std {
         Vec::with_capacity(capacity)
     }
 
+    /// Creates a new vector of child nodes with the capacity for the specified number of children before it must be resized
+    #[must_use]
+    pub(crate) fn new_children_vec_with_capacity<A>(capacity: usize) -> ChildrenVec<A> {
+        ChildrenVec::with_capacity(capacity)
+    }
+
     /// Rounds to the nearest whole number
     #[must_use]
     #[inline(always)]
@@ -36,6 +59,14 @@ mod std {
         value.round()
     }
 
+    /// Rounds to the nearest whole number
+    #[cfg(feature = "f64")]
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn round_f64(value: f64) -> f64 {
+        value.round()
+    }
+
     /// Computes the absolute value
     #[must_use]
     #[inline(always)]
@@ -64,11 +95,28 @@ mod alloc {
 
     /// An allocation-backend agnostic vector type
     pub(crate) type Vec<A> = alloc::vec::Vec<A>;
+    /// An allocation-backend agnostic box type
+    #[cfg(any(feature = "compact_style", feature = "inline_layout"))]
+    pub(crate) type Box<A> = alloc::boxed::Box<A>;
+    /// An allocation-backend agnostic atomically reference-counted pointer type
+    #[cfg(feature = "taffy_tree")]
+    pub(crate) type Arc<A> = alloc::sync::Arc<A>;
     /// A vector of child nodes
+    #[cfg(not(feature = "smallvec"))]
     pub(crate) type ChildrenVec<A> = alloc::vec::Vec<A>;
+    /// A vector of child nodes, which stores up to 4 of them inline
+    #[cfg(feature = "smallvec")]
+    pub(crate) type ChildrenVec<A> = smallvec::SmallVec<[A; 4]>;
     #[cfg(feature = "grid")]
     /// A vector of grid tracks
     pub(crate) type GridTrackVec<A> = alloc::vec::Vec<A>;
+    #[cfg(all(feature = "grid", not(feature = "smallvec")))]
+    /// A vector of the tracks and gutters of a grid which is being laid out
+    pub(crate) type LayoutTrackVec<A> = alloc::vec::Vec<A>;
+    #[cfg(all(feature = "grid", feature = "smallvec"))]
+    /// A vector of the tracks and gutters of a grid which is being laid out, which stores up to 8 of them (enough for
+    /// 3 tracks, their gutters and the grid's edges) inline
+    pub(crate) type LayoutTrackVec<A> = smallvec::SmallVec<[A; 8]>;
 
     /// Creates a new vector with the capacity for the specified number of items before it must be resized
     #[must_use]
@@ -76,6 +124,12 @@ mod alloc {
         Vec::with_capacity(capacity)
     }
 
+    /// Creates a new vector of child nodes with the capacity for the specified number of children before it must be resized
+    #[must_use]
+    pub(crate) fn new_children_vec_with_capacity<A>(capacity: usize) -> ChildrenVec<A> {
+        ChildrenVec::with_capacity(capacity)
+    }
+
     /// Rounds to the nearest whole number
     #[must_use]
     #[inline(always)]
@@ -83,6 +137,14 @@ mod alloc {
         num_traits::float::FloatCore::round(value)
     }
 
+    /// Rounds to the nearest whole number
+    #[cfg(feature = "f64")]
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn round_f64(value: f64) -> f64 {
+        num_traits::float::FloatCore::round(value)
+    }
+
     /// Computes the absolute value
     #[must_use]
     #[inline(always)]
@@ -132,6 +194,14 @@ mod core {
         arrayvec::ArrayVec::new()
     }
 
+    /// Creates a new vector of child nodes with the capacity for the specified number of children before it must be resized
+    ///
+    /// This vector cannot be resized.
+    #[must_use]
+    pub(crate) fn new_children_vec_with_capacity<A>(_capacity: usize) -> ChildrenVec<A> {
+        arrayvec::ArrayVec::new()
+    }
+
     /// Rounds to the nearest whole number
     #[inline]
     #[must_use]
@@ -140,6 +210,14 @@ mod core {
         num_traits::float::FloatCore::round(value)
     }
 
+    /// Rounds to the nearest whole number
+    #[cfg(feature = "f64")]
+    #[must_use]
+    #[inline(always)]
+    pub(crate) fn round_f64(value: f64) -> f64 {
+        num_traits::float::FloatCore::round(value)
+    }
+
     /// Computes the absolute value
     #[inline]
     #[must_use]
@@ -145,9 +145,8 @@ pub(crate) fn gap(input: &stylo::Gap) -> taffy::LengthPercentage {
 
 #[inline]
 pub(crate) fn flex_basis(input: &stylo::FlexBasis) -> taffy::Dimension {
-    // TODO: Support flex-basis: content in Taffy
     match input {
-        stylo::FlexBasis::Content => taffy::Dimension::Auto,
+        stylo::FlexBasis::Content => taffy::Dimension::Content,
         stylo::FlexBasis::Size(size) => dimension(size),
     }
 }
@@ -202,6 +201,7 @@ pub(crate) fn item_alignment(input: stylo::AlignFlags) -> Option<taffy::AlignIte
         stylo::AlignFlags::END => Some(taffy::AlignItems::End),
         stylo::AlignFlags::CENTER => Some(taffy::AlignItems::Center),
         stylo::AlignFlags::BASELINE => Some(taffy::AlignItems::Baseline),
+        stylo::AlignFlags::LAST_BASELINE => Some(taffy::AlignItems::LastBaseline),
         // Should never be hit. But no real reason to panic here.
         _ => None,
     }
@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod baseline_alignment {
+    use taffy::prelude::*;
+
+    /// Creates a "form row" made of a single-line label and a two-line input (whose lines are 20px tall and which has
+    /// 8px of bottom padding), returning the container, the label and the input
+    fn form_row(taffy: &mut TaffyTree<()>, container_style: Style) -> (NodeId, NodeId, NodeId) {
+        let label = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 20.0), ..Default::default() }).unwrap();
+        let first_line = taffy.new_leaf(Style { size: Size::from_lengths(100.0, 20.0), ..Default::default() }).unwrap();
+        let last_line = taffy.new_leaf(Style { size: Size::from_lengths(100.0, 20.0), ..Default::default() }).unwrap();
+        let input = taffy
+            .new_with_children(
+                Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: Rect { left: zero(), right: zero(), top: zero(), bottom: length(8.0) },
+                    ..Default::default()
+                },
+                &[first_line, last_line],
+            )
+            .unwrap();
+        let container = taffy.new_with_children(container_style, &[label, input]).unwrap();
+
+        (container, label, input)
+    }
+
+    #[test]
+    fn flex_row_aligns_last_baselines() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (container, label, input) =
+            form_row(&mut taffy, Style { align_items: Some(AlignItems::LastBaseline), ..Default::default() });
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        // The label's baseline lines up with the input's last line, and the input sits at the end of the line
+        assert_eq!(taffy.layout(container).unwrap().size.height, 48.0);
+        assert_eq!(taffy.layout(label).unwrap().location.y, 20.0);
+        assert_eq!(taffy.layout(input).unwrap().location.y, 0.0);
+    }
+
+    #[test]
+    fn flex_row_aligns_first_baselines_separately_from_last_baselines() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (container, label, input) =
+            form_row(&mut taffy, Style { align_items: Some(AlignItems::Baseline), ..Default::default() });
+        let last_baseline_item = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(10.0, 10.0),
+                align_self: Some(AlignSelf::LastBaseline),
+                ..Default::default()
+            })
+            .unwrap();
+        taffy.add_child(container, last_baseline_item).unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        // A last baseline aligned item alone in its group is aligned to the end of the line
+        assert_eq!(taffy.layout(label).unwrap().location.y, 0.0);
+        assert_eq!(taffy.layout(input).unwrap().location.y, 0.0);
+        assert_eq!(taffy.layout(last_baseline_item).unwrap().location.y, 38.0);
+    }
+
+    #[test]
+    fn flex_column_falls_back_to_start_and_end_alignment() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let first = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(20.0, 10.0),
+                align_self: Some(AlignSelf::Baseline),
+                ..Default::default()
+            })
+            .unwrap();
+        let last = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(20.0, 10.0),
+                align_self: Some(AlignSelf::LastBaseline),
+                ..Default::default()
+            })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    flex_direction: FlexDirection::Column,
+                    size: Size { width: length(100.0), height: auto() },
+                    ..Default::default()
+                },
+                &[first, last],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(first).unwrap().location.x, 0.0);
+        assert_eq!(taffy.layout(last).unwrap().location.x, 80.0);
+    }
+
+    #[test]
+    fn grid_row_aligns_last_baselines() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (container, label, input) = form_row(
+            &mut taffy,
+            Style {
+                display: Display::Grid,
+                grid_template_columns: vec![auto(), auto()],
+                align_items: Some(AlignItems::LastBaseline),
+                ..Default::default()
+            },
+        );
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(container).unwrap().size.height, 48.0);
+        assert_eq!(taffy.layout(label).unwrap().location.y, 20.0);
+        assert_eq!(taffy.layout(input).unwrap().location.y, 0.0);
+    }
+
+    #[test]
+    fn grid_only_shims_items_in_a_baseline_sharing_group() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let short = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(10.0, 10.0),
+                align_self: Some(AlignSelf::Baseline),
+                ..Default::default()
+            })
+            .unwrap();
+        let tall = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(10.0, 30.0),
+                align_self: Some(AlignSelf::Baseline),
+                ..Default::default()
+            })
+            .unwrap();
+        let start = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(10.0, 5.0),
+                align_self: Some(AlignSelf::Start),
+                ..Default::default()
+            })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    display: Display::Grid,
+                    grid_template_columns: vec![auto(), auto(), auto()],
+                    ..Default::default()
+                },
+                &[short, tall, start],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(short).unwrap().location.y, 20.0);
+        assert_eq!(taffy.layout(tall).unwrap().location.y, 0.0);
+        assert_eq!(taffy.layout(start).unwrap().location.y, 0.0);
+    }
+
+    #[test]
+    fn grid_justify_self_falls_back_to_start_and_end_alignment() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let first = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(20.0, 10.0),
+                justify_self: Some(JustifySelf::Baseline),
+                ..Default::default()
+            })
+            .unwrap();
+        let last = taffy
+            .new_leaf(Style {
+                size: Size::from_lengths(20.0, 10.0),
+                justify_self: Some(JustifySelf::LastBaseline),
+                ..Default::default()
+            })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style { display: Display::Grid, grid_template_columns: vec![length(100.0)], ..Default::default() },
+                &[first, last],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        assert_eq!(taffy.layout(first).unwrap().location.x, 0.0);
+        assert_eq!(taffy.layout(last).unwrap().location.x, 80.0);
+    }
+}
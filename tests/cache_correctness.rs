@@ -0,0 +1,358 @@
+//! Compares layouts computed by a `TaffyTree` (with caching) to layouts computed by a tree that caches nothing, across
+//! randomly generated trees and random style mutations between layouts.
+
+use taffy::prelude::*;
+use taffy::{compute_block_layout, compute_flexbox_layout, compute_grid_layout, compute_leaf_layout};
+use taffy::{compute_cached_layout, compute_hidden_layout, compute_root_layout, Cache};
+use taffy::{LayoutBlockContainer, LayoutFlexboxContainer, LayoutGridContainer, LayoutInput, LayoutOutput};
+use taffy::{Point, RunMode};
+
+mod common;
+use common::{random_rect, Rng};
+
+fn random_dimension(rng: &mut Rng) -> Dimension {
+    match rng.below(4) {
+        0 | 1 => Dimension::Auto,
+        2 => length(rng.f32(0.0, 200.0)),
+        _ => percent(rng.f32(0.0, 1.0)),
+    }
+}
+
+fn random_optional_dimension(rng: &mut Rng) -> Dimension {
+    if rng.chance(0.8) {
+        Dimension::Auto
+    } else {
+        random_dimension(rng)
+    }
+}
+
+fn random_length_percentage(rng: &mut Rng) -> LengthPercentage {
+    match rng.below(4) {
+        0 | 1 => zero(),
+        2 => length(rng.f32(0.0, 20.0)),
+        _ => percent(rng.f32(0.0, 0.2)),
+    }
+}
+
+fn random_length_percentage_auto(rng: &mut Rng) -> LengthPercentageAuto {
+    match rng.below(6) {
+        0..=2 => zero(),
+        3 => auto(),
+        4 => length(rng.f32(-10.0, 20.0)),
+        _ => percent(rng.f32(0.0, 0.2)),
+    }
+}
+
+fn random_tracks(rng: &mut Rng) -> Vec<NonRepeatedTrackSizingFunction> {
+    (0..rng.below(4))
+        .map(|_| match rng.below(7) {
+            0 => auto(),
+            1 => fr(rng.f32(0.5, 2.0)),
+            2 => length(rng.f32(0.0, 100.0)),
+            3 => percent(rng.f32(0.0, 0.5)),
+            4 => minmax(length(rng.f32(0.0, 50.0)), fr(1.0)),
+            5 => min_content(),
+            _ => max_content(),
+        })
+        .collect()
+}
+
+fn random_overflow(rng: &mut Rng) -> Point<Overflow> {
+    let overflows = [Overflow::Visible, Overflow::Clip, Overflow::Hidden, Overflow::Scroll];
+    Point { x: rng.pick(&overflows), y: rng.pick(&overflows) }
+}
+
+fn random_style(rng: &mut Rng, is_leaf: bool) -> Style {
+    let display = if is_leaf { Display::Flex } else { rng.pick(&[Display::Flex, Display::Grid, Display::Block]) };
+    let alignments =
+        [AlignItems::Start, AlignItems::End, AlignItems::Center, AlignItems::Baseline, AlignItems::Stretch];
+    let content_alignments =
+        [AlignContent::Start, AlignContent::Center, AlignContent::Stretch, AlignContent::SpaceBetween];
+
+    Style {
+        display,
+        position: if rng.chance(0.1) { Position::Absolute } else { Position::Relative },
+        inset: if rng.chance(0.2) { random_rect(rng, random_length_percentage_auto) } else { Rect::auto() },
+        overflow: random_overflow(rng),
+        scrollbar_width: rng.pick(&[0.0, 10.0]),
+        size: Size { width: random_dimension(rng), height: random_dimension(rng) },
+        min_size: Size { width: random_optional_dimension(rng), height: random_optional_dimension(rng) },
+        max_size: Size { width: random_optional_dimension(rng), height: random_optional_dimension(rng) },
+        aspect_ratio: if rng.chance(0.1) { Some(rng.f32(0.5, 2.0)) } else { None },
+        margin: random_rect(rng, random_length_percentage_auto),
+        padding: random_rect(rng, random_length_percentage),
+        border: random_rect(rng, random_length_percentage),
+        gap: Size { width: random_length_percentage(rng), height: random_length_percentage(rng) },
+        align_items: if rng.chance(0.5) { Some(rng.pick(&alignments)) } else { None },
+        align_self: if rng.chance(0.2) { Some(rng.pick(&alignments)) } else { None },
+        align_content: if rng.chance(0.5) { Some(rng.pick(&content_alignments)) } else { None },
+        justify_content: if rng.chance(0.5) { Some(rng.pick(&content_alignments)) } else { None },
+        flex_direction: rng.pick(&[
+            FlexDirection::Row,
+            FlexDirection::Column,
+            FlexDirection::RowReverse,
+            FlexDirection::ColumnReverse,
+        ]),
+        flex_wrap: rng.pick(&[FlexWrap::NoWrap, FlexWrap::Wrap, FlexWrap::WrapReverse]),
+        flex_basis: random_optional_dimension(rng),
+        flex_grow: rng.pick(&[0.0, 0.0, 1.0, 2.0]),
+        flex_shrink: rng.pick(&[0.0, 1.0, 1.0, 2.0]),
+        grid_template_rows: random_tracks(rng).into_iter().map(TrackSizingFunction::Single).collect(),
+        grid_template_columns: random_tracks(rng).into_iter().map(TrackSizingFunction::Single).collect(),
+        grid_auto_rows: random_tracks(rng),
+        grid_auto_columns: random_tracks(rng),
+        ..Default::default()
+    }
+}
+
+/// A node of a randomly generated tree. Leaves may hold text of the given width.
+struct NodeDesc {
+    style: Style,
+    text: Option<f32>,
+    children: Vec<NodeDesc>,
+}
+
+fn random_tree(rng: &mut Rng, depth: usize) -> NodeDesc {
+    let child_count = if depth == 0 { 0 } else { rng.below(5) };
+    let children = (0..child_count).map(|_| random_tree(rng, depth - 1)).collect();
+    let text = if child_count == 0 && rng.chance(0.5) { Some(rng.f32(10.0, 200.0)) } else { None };
+    NodeDesc { style: random_style(rng, child_count == 0), text, children }
+}
+
+/// Measures text of the given width made up of 30px words, with 20px lines
+fn measure_text(known_dimensions: Size<Option<f32>>, available_space: Size<AvailableSpace>, text: f32) -> Size<f32> {
+    let min_width = text.min(30.0);
+    let width = known_dimensions.width.unwrap_or(match available_space.width {
+        AvailableSpace::MinContent => min_width,
+        AvailableSpace::MaxContent => text,
+        AvailableSpace::Definite(space) => space.clamp(min_width, text),
+    });
+    let lines = if width > 0.0 { (text / width).ceil() } else { text / 30.0 };
+    Size { width, height: known_dimensions.height.unwrap_or(lines * 20.0) }
+}
+
+/// A tree which recomputes the layout of a node every time it is requested
+struct UncachedTree {
+    styles: Vec<Style>,
+    texts: Vec<Option<f32>>,
+    children: Vec<Vec<NodeId>>,
+    layouts: Vec<Layout>,
+}
+
+impl UncachedTree {
+    fn add(&mut self, desc: &NodeDesc) -> NodeId {
+        let children = desc.children.iter().map(|child| self.add(child)).collect();
+        self.styles.push(desc.style.clone());
+        self.texts.push(desc.text);
+        self.children.push(children);
+        self.layouts.push(Layout::new());
+        NodeId::from(self.styles.len() - 1)
+    }
+}
+
+impl TraversePartialTree for UncachedTree {
+    type ChildIter<'a> = core::iter::Copied<core::slice::Iter<'a, NodeId>>;
+
+    fn child_ids(&self, node_id: NodeId) -> Self::ChildIter<'_> {
+        self.children[usize::from(node_id)].iter().copied()
+    }
+
+    fn child_count(&self, node_id: NodeId) -> usize {
+        self.children[usize::from(node_id)].len()
+    }
+
+    fn get_child_id(&self, node_id: NodeId, index: usize) -> NodeId {
+        self.children[usize::from(node_id)][index]
+    }
+}
+
+impl LayoutPartialTree for UncachedTree {
+    type CoreContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+    type CacheMut<'b>
+        = Box<Cache>
+    where
+        Self: 'b;
+
+    fn get_core_container_style(&self, node_id: NodeId) -> Self::CoreContainerStyle<'_> {
+        &self.styles[usize::from(node_id)]
+    }
+
+    fn set_unrounded_layout(&mut self, node_id: NodeId, layout: &Layout) {
+        self.layouts[usize::from(node_id)] = *layout;
+    }
+
+    fn get_cache_mut(&mut self, _node_id: NodeId) -> Box<Cache> {
+        Box::new(Cache::new())
+    }
+
+    fn compute_child_layout(&mut self, node_id: NodeId, inputs: LayoutInput) -> LayoutOutput {
+        if inputs.run_mode == RunMode::PerformHiddenLayout {
+            return compute_hidden_layout(self, node_id);
+        }
+        compute_cached_layout(self, node_id, inputs, |tree, node_id, inputs| {
+            let index = usize::from(node_id);
+            match (tree.styles[index].display, tree.children[index].is_empty()) {
+                (Display::None, _) => compute_hidden_layout(tree, node_id),
+                (Display::Block, false) => compute_block_layout(tree, node_id, inputs),
+                (Display::Flex, false) => compute_flexbox_layout(tree, node_id, inputs),
+                (Display::Grid, false) => compute_grid_layout(tree, node_id, inputs),
+                #[cfg(feature = "inline_layout")]
+                (Display::Inline, false) => taffy::compute_inline_layout(tree, node_id, inputs),
+                (_, true) => {
+                    let text = tree.texts[index];
+                    compute_leaf_layout(inputs, &tree.styles[index], |known_dimensions, available_space| match text {
+                        Some(text) => measure_text(known_dimensions, available_space, text),
+                        None => Size::ZERO,
+                    })
+                }
+            }
+        })
+    }
+}
+
+#[cfg(feature = "inline_layout")]
+impl taffy::LayoutInlineContainer for UncachedTree {}
+
+impl LayoutFlexboxContainer for UncachedTree {
+    type FlexboxContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+    type FlexboxItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_flexbox_container_style(&self, node_id: NodeId) -> Self::FlexboxContainerStyle<'_> {
+        &self.styles[usize::from(node_id)]
+    }
+
+    fn get_flexbox_child_style(&self, child_node_id: NodeId) -> Self::FlexboxItemStyle<'_> {
+        &self.styles[usize::from(child_node_id)]
+    }
+}
+
+impl LayoutGridContainer for UncachedTree {
+    type GridContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+    type GridItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_grid_container_style(&self, node_id: NodeId) -> Self::GridContainerStyle<'_> {
+        &self.styles[usize::from(node_id)]
+    }
+
+    fn get_grid_child_style(&self, child_node_id: NodeId) -> Self::GridItemStyle<'_> {
+        &self.styles[usize::from(child_node_id)]
+    }
+}
+
+impl LayoutBlockContainer for UncachedTree {
+    type BlockContainerStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+    type BlockItemStyle<'a>
+        = &'a Style
+    where
+        Self: 'a;
+
+    fn get_block_container_style(&self, node_id: NodeId) -> Self::BlockContainerStyle<'_> {
+        &self.styles[usize::from(node_id)]
+    }
+
+    fn get_block_child_style(&self, child_node_id: NodeId) -> Self::BlockItemStyle<'_> {
+        &self.styles[usize::from(child_node_id)]
+    }
+}
+
+/// Adds the nodes of `desc` to `taffy`, returning their ids in the same (post-)order as [`UncachedTree::add`]
+fn add_to_taffy(taffy: &mut TaffyTree<f32>, desc: &NodeDesc, ids: &mut Vec<NodeId>) -> NodeId {
+    let children: Vec<_> = desc.children.iter().map(|child| add_to_taffy(taffy, child, ids)).collect();
+    let node = match desc.text {
+        Some(text) => taffy.new_leaf_with_context(desc.style.clone(), text).unwrap(),
+        None => taffy.new_with_children(desc.style.clone(), &children).unwrap(),
+    };
+    ids.push(node);
+    node
+}
+
+fn random_available_space(rng: &mut Rng) -> Size<AvailableSpace> {
+    let random = |rng: &mut Rng| match rng.below(3) {
+        0 => AvailableSpace::MinContent,
+        1 => AvailableSpace::MaxContent,
+        _ => AvailableSpace::Definite(rng.f32(0.0, 400.0)),
+    };
+    Size { width: random(rng), height: random(rng) }
+}
+
+fn assert_layouts_match(seed: u64, pass: usize, taffy: &TaffyTree<f32>, ids: &[NodeId], uncached: &UncachedTree) {
+    let is_close = |a: f32, b: f32| (a - b).abs() < 0.01 || (a.is_nan() && b.is_nan());
+    for (index, &id) in ids.iter().enumerate() {
+        let cached = taffy.unrounded_layout(id).unwrap();
+        let expected = &uncached.layouts[index];
+        let matches = is_close(cached.location.x, expected.location.x)
+            && is_close(cached.location.y, expected.location.y)
+            && is_close(cached.size.width, expected.size.width)
+            && is_close(cached.size.height, expected.size.height);
+        assert!(
+            matches,
+            "seed {seed}, pass {pass}, node {index}: cached layout {:?} {:?} differs from uncached layout {:?} {:?}",
+            cached.location, cached.size, expected.location, expected.size
+        );
+    }
+}
+
+#[test]
+fn cached_layout_matches_uncached_layout() {
+    for seed in 1..=500u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let desc = random_tree(&mut rng, 3);
+
+        let mut taffy: TaffyTree<f32> = TaffyTree::new();
+        taffy.disable_rounding();
+        let mut ids = Vec::new();
+        let root = add_to_taffy(&mut taffy, &desc, &mut ids);
+
+        let mut uncached =
+            UncachedTree { styles: Vec::new(), texts: Vec::new(), children: Vec::new(), layouts: Vec::new() };
+        let uncached_root = uncached.add(&desc);
+
+        // Lay out repeatedly, mutating the style of a random node in between, so that later layouts run against caches
+        // that were populated by earlier ones
+        for pass in 0..4 {
+            let available_space = random_available_space(&mut rng);
+            taffy
+                .compute_layout_with_measure(root, available_space, |known_dimensions, available_space, _, text, _| {
+                    match text {
+                        Some(text) => measure_text(known_dimensions, available_space, *text),
+                        None => Size::ZERO,
+                    }
+                })
+                .unwrap();
+            compute_root_layout(&mut uncached, uncached_root, available_space);
+            assert_layouts_match(seed, pass, &taffy, &ids, &uncached);
+
+            let index = rng.below(ids.len());
+            let mut style = uncached.styles[index].clone();
+            match rng.below(4) {
+                0 => style.overflow = random_overflow(&mut rng),
+                1 => style.size = Size { width: random_dimension(&mut rng), height: random_dimension(&mut rng) },
+                2 => style.padding = random_rect(&mut rng, random_length_percentage),
+                _ => {
+                    style =
+                        random_style(&mut rng, style.display == Display::Flex && uncached.children[index].is_empty())
+                }
+            }
+            taffy.set_style(ids[index], style.clone()).unwrap();
+            uncached.styles[index] = style;
+        }
+    }
+}
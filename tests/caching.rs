@@ -1,4 +1,5 @@
-#[cfg(test)]
+// The debug_determinism feature recomputes every layout, which makes the measure counts meaningless
+#[cfg(all(test, not(feature = "debug_determinism")))]
 mod caching {
     use taffy::prelude::*;
 
@@ -38,6 +39,63 @@ mod caching {
         assert_eq!(taffy.get_node_context_mut(leaf).unwrap().count, 4);
     }
 
+    #[test]
+    fn measure_count_flexbox_stretched_definite_cross_size() {
+        let mut taffy: TaffyTree<CountMeasure> = TaffyTree::new();
+
+        let leaf = taffy.new_leaf_with_context(Style::default(), CountMeasure::new()).unwrap();
+        let node = taffy
+            .new_with_children(Style { size: Size::from_lengths(200.0, 100.0), ..Default::default() }, &[leaf])
+            .unwrap();
+
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, count_measure_function).unwrap();
+
+        // The stretched height is known when determining the flex base size, so the leaf is not measured again to
+        // determine its hypothetical cross size
+        assert_eq!(taffy.get_node_context_mut(leaf).unwrap().count, 3);
+        assert_eq!(taffy.layout(leaf).unwrap().size, Size { width: 50.0, height: 100.0 });
+    }
+
+    #[test]
+    fn measure_count_flexbox_definite_size_items() {
+        let mut taffy: TaffyTree<CountMeasure> = TaffyTree::new();
+
+        let leaf_style = Style { size: Size::from_lengths(50.0, 50.0), ..Default::default() };
+        let leaf = taffy.new_leaf_with_context(leaf_style, CountMeasure::new()).unwrap();
+        let node = taffy
+            .new_with_children(Style { size: Size::from_lengths(200.0, 100.0), ..Default::default() }, &[leaf])
+            .unwrap();
+
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, count_measure_function).unwrap();
+
+        // The leaf's size is fully determined by its style and its line does not shrink, so its min-content size is
+        // never measured
+        assert_eq!(taffy.get_node_context_mut(leaf).unwrap().count, 1);
+        assert_eq!(taffy.layout(leaf).unwrap().size, Size { width: 50.0, height: 50.0 });
+    }
+
+    #[test]
+    fn measure_count_flexbox_definite_size_items_shrinking() {
+        let mut taffy: TaffyTree<CountMeasure> = TaffyTree::new();
+
+        let leaf_style = Style { size: Size::from_lengths(150.0, 50.0), ..Default::default() };
+        let leaf_a = taffy.new_leaf_with_context(leaf_style.clone(), CountMeasure::new()).unwrap();
+        let leaf_b = taffy.new_leaf_with_context(leaf_style, CountMeasure::new()).unwrap();
+        let node = taffy
+            .new_with_children(
+                Style { size: Size::from_lengths(200.0, 100.0), ..Default::default() },
+                &[leaf_a, leaf_b],
+            )
+            .unwrap();
+
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, count_measure_function).unwrap();
+
+        // The line shrinks, so the automatic minimum size of each leaf must be determined from its min-content size
+        assert_eq!(taffy.get_node_context_mut(leaf_a).unwrap().count, 2);
+        assert_eq!(taffy.get_node_context_mut(leaf_b).unwrap().count, 2);
+        assert_eq!(taffy.layout(leaf_a).unwrap().size, Size { width: 100.0, height: 50.0 });
+    }
+
     #[test]
     #[cfg(feature = "grid")]
     fn measure_count_grid() {
@@ -0,0 +1,41 @@
+//! Helpers shared by the tests which lay out randomly generated trees
+
+use taffy::Rect;
+
+/// A small deterministic pseudo-random number generator (xorshift64*)
+pub struct Rng(pub u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A random number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns `true` with probability `p`
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.f32(0.0, 1.0) < p
+    }
+
+    /// A random number in `min..max`, rounded to a quarter pixel
+    pub fn f32(&mut self, min: f32, max: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        ((min + unit * (max - min)) * 4.0).round() / 4.0
+    }
+
+    /// A random item of `items`
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+/// A rect whose sides are each generated by `f`, in the order left, right, top, bottom
+pub fn random_rect<T>(rng: &mut Rng, mut f: impl FnMut(&mut Rng) -> T) -> Rect<T> {
+    Rect { left: f(rng), right: f(rng), top: f(rng), bottom: f(rng) }
+}
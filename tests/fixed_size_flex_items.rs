@@ -0,0 +1,164 @@
+//! Compares layouts of flex containers whose items have fixed sizes (which are sized directly from their styles) to
+//! layouts of the same containers with each item's main size given as its flex basis instead (which are sized by the
+//! general flexbox algorithm), across randomly generated trees.
+
+use taffy::prelude::*;
+use taffy::Point;
+
+mod common;
+use common::{random_rect, Rng};
+
+fn random_optional_length(rng: &mut Rng, max: f32) -> Dimension {
+    if rng.chance(0.7) {
+        Dimension::Auto
+    } else {
+        length(rng.f32(0.0, max))
+    }
+}
+
+fn random_length_rect(rng: &mut Rng, max: f32) -> Rect<LengthPercentage> {
+    random_rect(rng, |rng| if rng.chance(0.5) { zero() } else { length(rng.f32(0.0, max)) })
+}
+
+fn random_container_style(rng: &mut Rng) -> Style {
+    Style {
+        flex_direction: rng.pick(&[
+            FlexDirection::Row,
+            FlexDirection::Column,
+            FlexDirection::RowReverse,
+            FlexDirection::ColumnReverse,
+        ]),
+        justify_content: rng.pick(&[
+            None,
+            Some(JustifyContent::Start),
+            Some(JustifyContent::End),
+            Some(JustifyContent::Center),
+            Some(JustifyContent::FlexEnd),
+            Some(JustifyContent::SpaceBetween),
+            Some(JustifyContent::SpaceAround),
+            Some(JustifyContent::SpaceEvenly),
+        ]),
+        align_items: rng.pick(&[
+            None,
+            Some(AlignItems::Start),
+            Some(AlignItems::End),
+            Some(AlignItems::FlexEnd),
+            Some(AlignItems::Center),
+            Some(AlignItems::Stretch),
+        ]),
+        align_content: rng.pick(&[None, Some(AlignContent::Center), Some(AlignContent::SpaceBetween)]),
+        gap: Size { width: length(rng.f32(0.0, 10.0)), height: length(rng.f32(0.0, 10.0)) },
+        padding: random_length_rect(rng, 10.0),
+        border: random_length_rect(rng, 5.0),
+        overflow: rng.pick(&[
+            Point { x: Overflow::Visible, y: Overflow::Visible },
+            Point { x: Overflow::Scroll, y: Overflow::Scroll },
+        ]),
+        box_sizing: rng.pick(&[BoxSizing::BorderBox, BoxSizing::ContentBox]),
+        size: Size::from_lengths(rng.f32(0.0, 300.0), rng.f32(0.0, 300.0)),
+        ..Default::default()
+    }
+}
+
+fn random_item_style(rng: &mut Rng) -> Style {
+    Style {
+        size: Size::from_lengths(rng.f32(0.0, 120.0), rng.f32(0.0, 120.0)),
+        min_size: Size { width: random_optional_length(rng, 100.0), height: random_optional_length(rng, 100.0) },
+        max_size: Size { width: random_optional_length(rng, 100.0), height: random_optional_length(rng, 100.0) },
+        margin: random_length_rect(rng, 10.0).map(LengthPercentageAuto::from),
+        padding: random_length_rect(rng, 10.0),
+        border: random_length_rect(rng, 5.0),
+        inset: if rng.chance(0.2) {
+            Rect {
+                left: length(rng.f32(-10.0, 10.0)),
+                right: auto(),
+                top: length(rng.f32(-10.0, 10.0)),
+                bottom: auto(),
+            }
+        } else {
+            Rect::auto()
+        },
+        flex_grow: if rng.chance(0.6) { 0.0 } else { rng.f32(0.0, 2.0) },
+        flex_shrink: if rng.chance(0.5) { 0.0 } else { rng.f32(0.0, 2.0) },
+        align_self: rng.pick(&[None, Some(AlignSelf::Center), Some(AlignSelf::FlexEnd), Some(AlignSelf::Stretch)]),
+        overflow: rng.pick(&[
+            Point { x: Overflow::Visible, y: Overflow::Visible },
+            Point { x: Overflow::Hidden, y: Overflow::Hidden },
+        ]),
+        box_sizing: rng.pick(&[BoxSizing::BorderBox, BoxSizing::ContentBox]),
+        ..Default::default()
+    }
+}
+
+/// A node of a generated tree, holding its style and its children
+struct Node {
+    style: Style,
+    children: Vec<Node>,
+}
+
+/// Generates a flex container whose items each have a fixed size, and may themselves be such containers
+fn random_container(rng: &mut Rng, depth: usize) -> Node {
+    let count = rng.below(5);
+    let children = (0..count)
+        .map(|_| {
+            let item_style = random_item_style(rng);
+            if depth > 0 && rng.chance(0.5) {
+                let container = random_container(rng, depth - 1);
+                let style = Style { size: item_style.size, ..container.style };
+                Node {
+                    style: Style { margin: item_style.margin, flex_grow: item_style.flex_grow, ..style },
+                    ..container
+                }
+            } else {
+                Node { style: item_style, children: Vec::new() }
+            }
+        })
+        .collect();
+    Node { style: random_container_style(rng), children }
+}
+
+/// Creates the generated tree in `taffy`, giving each item a flex basis equal to its main size if `use_flex_basis`
+fn build(taffy: &mut TaffyTree<()>, node: &Node, use_flex_basis: bool) -> NodeId {
+    let children: Vec<NodeId> = node
+        .children
+        .iter()
+        .map(|child| {
+            let child_id = build(taffy, child, use_flex_basis);
+            if use_flex_basis {
+                let mut style = taffy.style(child_id).unwrap().clone();
+                style.flex_basis = match node.style.flex_direction {
+                    FlexDirection::Row | FlexDirection::RowReverse => style.size.width,
+                    FlexDirection::Column | FlexDirection::ColumnReverse => style.size.height,
+                };
+                taffy.set_style(child_id, style).unwrap();
+            }
+            child_id
+        })
+        .collect();
+    taffy.new_with_children(node.style.clone(), &children).unwrap()
+}
+
+/// Asserts that the layouts of corresponding nodes of the two trees are equal
+fn assert_layouts_equal(taffy: &TaffyTree<()>, fixed: NodeId, flexed: NodeId, seed: u64) {
+    assert_eq!(taffy.unrounded_layout(fixed).unwrap(), taffy.unrounded_layout(flexed).unwrap(), "seed {seed}");
+    let children = taffy.children(fixed).unwrap().into_iter().zip(taffy.children(flexed).unwrap());
+    for (fixed_child, flexed_child) in children {
+        assert_layouts_equal(taffy, fixed_child, flexed_child, seed);
+    }
+}
+
+#[test]
+fn fixed_size_flex_items_are_laid_out_as_by_the_flexbox_algorithm() {
+    for seed in 1..500 {
+        let mut rng = Rng(seed);
+        let tree = random_container(&mut rng, 2);
+
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let fixed = build(&mut taffy, &tree, false);
+        let flexed = build(&mut taffy, &tree, true);
+        taffy.compute_layout(fixed, Size::MAX_CONTENT).unwrap();
+        taffy.compute_layout(flexed, Size::MAX_CONTENT).unwrap();
+
+        assert_layouts_equal(&taffy, fixed, flexed, seed);
+    }
+}
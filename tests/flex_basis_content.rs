@@ -0,0 +1,58 @@
+use taffy::prelude::*;
+use taffy::Point;
+
+/// Lays out a row flex container holding a single item with the given flex basis and style, which itself holds
+/// 50x10 content, and returns the size of the item
+fn item_size(flex_basis: Dimension, item_style: Style) -> Size<f32> {
+    let mut taffy: TaffyTree<()> = TaffyTree::new();
+    let content = taffy.new_leaf(Style { size: Size::from_lengths(50.0, 10.0), ..Default::default() }).unwrap();
+    let item = taffy.new_with_children(Style { flex_basis, ..item_style }, &[content]).unwrap();
+    let container = taffy
+        .new_with_children(
+            Style {
+                size: Size::from_lengths(500.0, 100.0),
+                align_items: Some(AlignItems::Start),
+                ..Default::default()
+            },
+            &[item],
+        )
+        .unwrap();
+    taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+    taffy.layout(item).unwrap().size
+}
+
+#[test]
+fn flex_basis_content_ignores_main_size() {
+    let style = Style { flex_shrink: 0.0, size: Size { width: length(100.0), height: auto() }, ..Default::default() };
+
+    // `auto` uses the item's width as its flex basis, while `content` sizes it based on its content
+    assert_eq!(item_size(Dimension::Auto, style.clone()), Size { width: 100.0, height: 10.0 });
+    assert_eq!(item_size(Dimension::Content, style), Size { width: 50.0, height: 10.0 });
+}
+
+#[test]
+fn flex_basis_content_without_main_size_matches_auto() {
+    assert_eq!(item_size(Dimension::Content, Style::default()), item_size(Dimension::Auto, Style::default()));
+}
+
+#[test]
+fn flex_basis_content_uses_aspect_ratio_and_cross_size() {
+    // The item is a scroll container so that its content does not floor its size through its automatic minimum size
+    let style = Style {
+        flex_shrink: 0.0,
+        overflow: Point { x: Overflow::Hidden, y: Overflow::Hidden },
+        aspect_ratio: Some(2.0),
+        size: Size { width: length(100.0), height: length(20.0) },
+        ..Default::default()
+    };
+
+    assert_eq!(item_size(Dimension::Content, style), Size { width: 40.0, height: 20.0 });
+}
+
+#[test]
+fn flex_basis_content_grows_from_content_size() {
+    let style = Style { flex_grow: 1.0, size: Size { width: length(100.0), height: auto() }, ..Default::default() };
+
+    // Free space is distributed on top of the content size rather than the width
+    assert_eq!(item_size(Dimension::Content, style).width, 500.0);
+}
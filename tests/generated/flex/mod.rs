@@ -255,6 +255,7 @@ mod flex_grow_within_constrained_min_column;
 mod flex_grow_within_constrained_min_max_column;
 mod flex_grow_within_constrained_min_row;
 mod flex_grow_within_max_width;
+mod flex_nowrap_in_sidebar_fits_available_width;
 mod flex_root_ignored;
 mod flex_row_relative_all_sides;
 mod flex_shrink_by_outer_margin_with_max_size;
@@ -263,6 +264,16 @@ mod flex_shrink_flex_grow_row;
 mod flex_shrink_to_zero;
 mod flex_wrap_align_stretch_fits_one_row;
 mod flex_wrap_children_with_min_main_overriding_flex_basis;
+mod flex_wrap_column_width_includes_all_columns;
+mod flex_wrap_in_nested_sidebar_shrinks_with_main_content;
+mod flex_wrap_in_sidebar_does_not_grow_to_available_width;
+mod flex_wrap_in_sidebar_does_not_shrink_below_min_content;
+mod flex_wrap_in_sidebar_fits_available_width;
+mod flex_wrap_max_content_width_includes_gap_and_padding;
+mod flex_wrap_max_content_width_is_single_line;
+mod flex_wrap_min_content_width_includes_padding;
+mod flex_wrap_min_content_width_is_largest_item;
+mod flex_wrap_min_content_width_uses_item_min_content_contributions;
 mod flex_wrap_wrap_to_child_height;
 mod gap_column_gap_child_margins;
 mod gap_column_gap_determines_parent_width;
@@ -345,6 +356,9 @@ mod justify_content_row_min_width_and_margin;
 mod justify_content_row_space_around;
 mod justify_content_row_space_between;
 mod justify_content_row_space_evenly;
+mod justify_content_space_around_many_fractional_items;
+mod justify_content_space_between_many_fractional_items;
+mod justify_content_space_evenly_many_fractional_items;
 mod margin_and_flex_column;
 mod margin_and_flex_row;
 mod margin_and_stretch_column;
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod grid_content_distribution {
+    use taffy::geometry::Point;
+    use taffy::prelude::*;
+
+    /// Lays out a 200x100 grid with two auto columns and one auto row containing a 20x10 and a 30x10 item,
+    /// returning the items
+    fn grid(taffy: &mut TaffyTree<()>, style: Style) -> (NodeId, NodeId) {
+        let first = taffy.new_leaf(Style { size: Size::from_lengths(20.0, 10.0), ..Default::default() }).unwrap();
+        let second = taffy.new_leaf(Style { size: Size::from_lengths(30.0, 10.0), ..Default::default() }).unwrap();
+        let grid = taffy
+            .new_with_children(
+                Style {
+                    display: Display::Grid,
+                    size: Size::from_lengths(200.0, 100.0),
+                    grid_template_columns: vec![auto(), auto()],
+                    grid_template_rows: vec![auto()],
+                    ..style
+                },
+                &[first, second],
+            )
+            .unwrap();
+        taffy.compute_layout(grid, Size::MAX_CONTENT).unwrap();
+
+        (first, second)
+    }
+
+    #[test]
+    fn stretch_distributes_free_space_into_auto_tracks() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (first, second) = grid(&mut taffy, Style::default());
+
+        // Each auto column grows by half of the 150px of free space
+        assert_eq!(taffy.layout(first).unwrap().location, Point { x: 0.0, y: 0.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 95.0, y: 0.0 });
+    }
+
+    #[test]
+    fn centered_grid_does_not_stretch_auto_tracks() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (first, second) = grid(
+            &mut taffy,
+            Style {
+                justify_content: Some(JustifyContent::Center),
+                align_content: Some(AlignContent::Center),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(taffy.layout(first).unwrap().location, Point { x: 75.0, y: 45.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 95.0, y: 45.0 });
+    }
+
+    #[test]
+    fn distributed_alignment_does_not_stretch_auto_tracks() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (first, second) = grid(
+            &mut taffy,
+            Style {
+                justify_content: Some(JustifyContent::SpaceBetween),
+                align_content: Some(AlignContent::End),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(taffy.layout(first).unwrap().location, Point { x: 0.0, y: 90.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 170.0, y: 90.0 });
+    }
+
+    #[test]
+    fn stretch_is_applied_per_axis() {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let (first, second) = grid(
+            &mut taffy,
+            Style {
+                justify_content: Some(JustifyContent::End),
+                align_items: Some(AlignItems::End),
+                ..Default::default()
+            },
+        );
+
+        // The row still stretches to fill the container, so the end-aligned items sit at the bottom of the grid
+        assert_eq!(taffy.layout(first).unwrap().location, Point { x: 150.0, y: 90.0 });
+        assert_eq!(taffy.layout(second).unwrap().location, Point { x: 170.0, y: 90.0 });
+    }
+}
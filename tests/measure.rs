@@ -38,6 +38,19 @@ mod measure {
         Size { width, height }
     }
 
+    #[test]
+    #[cfg(feature = "fixed_point")]
+    fn measured_size_is_quantized() {
+        let mut taffy: TaffyTree<FixedMeasure> = TaffyTree::new();
+        taffy.disable_rounding();
+        let child = taffy.new_leaf_with_context(Style::default(), FixedMeasure { width: 10.3, height: 20.01 }).unwrap();
+        let node = taffy.new_with_children(Style::default(), &[child]).unwrap();
+        taffy.compute_layout_with_measure(node, Size::MAX_CONTENT, fixed_measure_function).unwrap();
+
+        assert_eq!(taffy.layout(child).unwrap().size, Size { width: 659.0 / 64.0, height: 1281.0 / 64.0 });
+        assert_eq!(taffy.layout(node).unwrap().size, Size { width: 659.0 / 64.0, height: 1281.0 / 64.0 });
+    }
+
     #[test]
     fn measure_root() {
         let mut taffy: TaffyTree<FixedMeasure> = TaffyTree::new();
@@ -0,0 +1,82 @@
+use taffy::prelude::*;
+use taffy::Point;
+
+/// Lays out a 100x100 container holding a single item, which itself holds 200x200 content, and returns the size of the
+/// item. The item is shrunk (flexbox) or placed in a `1fr` track (grid) in both axes, so it can only be smaller than its
+/// content in an axis where its automatic minimum size is not content-based.
+fn item_size(display: Display, flex_direction: FlexDirection, overflow: Point<Overflow>) -> Size<f32> {
+    let mut taffy: TaffyTree<()> = TaffyTree::new();
+    let content = taffy.new_leaf(Style { size: Size::from_lengths(200.0, 200.0), ..Default::default() }).unwrap();
+    let item = taffy.new_with_children(Style { overflow, ..Default::default() }, &[content]).unwrap();
+    let container = taffy
+        .new_with_children(
+            Style {
+                display,
+                flex_direction,
+                grid_template_rows: vec![fr(1.0)],
+                grid_template_columns: vec![fr(1.0)],
+                size: Size::from_lengths(100.0, 100.0),
+                ..Default::default()
+            },
+            &[item],
+        )
+        .unwrap();
+    taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+    taffy.layout(item).unwrap().size
+}
+
+#[test]
+fn flexbox_automatic_minimum_size_is_per_axis() {
+    let scroll_y = Point { x: Overflow::Visible, y: Overflow::Scroll };
+    let scroll_x = Point { x: Overflow::Scroll, y: Overflow::Visible };
+
+    // Scrolling in the cross axis does not relax the automatic minimum size in the main axis
+    assert_eq!(item_size(Display::Flex, FlexDirection::Row, scroll_y).width, 200.0);
+    assert_eq!(item_size(Display::Flex, FlexDirection::Column, scroll_x).height, 200.0);
+
+    // Scrolling in the main axis does
+    assert_eq!(item_size(Display::Flex, FlexDirection::Row, scroll_x).width, 100.0);
+    assert_eq!(item_size(Display::Flex, FlexDirection::Column, scroll_y).height, 100.0);
+}
+
+#[test]
+fn grid_automatic_minimum_size_is_per_axis() {
+    let scroll_y = Point { x: Overflow::Visible, y: Overflow::Scroll };
+    let scroll_x = Point { x: Overflow::Scroll, y: Overflow::Visible };
+    let hidden_y = Point { x: Overflow::Visible, y: Overflow::Hidden };
+
+    assert_eq!(item_size(Display::Grid, FlexDirection::Row, scroll_y), Size { width: 200.0, height: 100.0 });
+    assert_eq!(item_size(Display::Grid, FlexDirection::Row, scroll_x), Size { width: 100.0, height: 200.0 });
+    assert_eq!(item_size(Display::Grid, FlexDirection::Row, hidden_y), Size { width: 200.0, height: 100.0 });
+}
+
+#[test]
+fn grid_automatic_minimum_size_only_considers_spanned_tracks() {
+    // The item spans two auto tracks, so its automatic minimum size is content-based even though
+    // the grid also has a flexible track (which the item does not span)
+    let mut taffy: TaffyTree<()> = TaffyTree::new();
+    let content = taffy.new_leaf(Style { size: Size::from_lengths(150.0, 10.0), ..Default::default() }).unwrap();
+    let item = taffy
+        .new_with_children(
+            Style { grid_column: Line { start: line(1), end: span(2) }, ..Default::default() },
+            &[content],
+        )
+        .unwrap();
+    let sibling = taffy.new_leaf(Style { grid_column: line(3), ..Default::default() }).unwrap();
+    let container = taffy
+        .new_with_children(
+            Style {
+                display: Display::Grid,
+                grid_template_columns: vec![auto(), auto(), fr(1.0)],
+                size: Size { width: length(100.0), height: auto() },
+                ..Default::default()
+            },
+            &[item, sibling],
+        )
+        .unwrap();
+    taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+    assert_eq!(taffy.layout(item).unwrap().size.width, 150.0);
+    assert_eq!(taffy.layout(sibling).unwrap().location.x, 150.0);
+    assert_eq!(taffy.layout(sibling).unwrap().size.width, 0.0);
+}
@@ -1,4 +1,5 @@
 use taffy::prelude::*;
+use taffy::Point;
 
 #[test]
 fn relayout() {
@@ -389,3 +390,70 @@ fn relayout_is_stable_with_rounding() {
         assert_eq!(inner_layout.size.height, 1080.0);
     }
 }
+
+#[test]
+fn percentage_gaps_in_content_sized_container() {
+    // Lays out a wrapping flex container holding two 60x60 items, optionally inside a 200x200 parent, and returns the
+    // size of the container and the location of the item on the second line
+    fn layout(parent_display: Option<Display>, flex_direction: FlexDirection, size: Size<Dimension>) -> [Layout; 2] {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let children: Vec<_> = (0..2)
+            .map(|_| taffy.new_leaf(Style { size: Size::from_lengths(60.0, 60.0), ..Default::default() }).unwrap())
+            .collect();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    flex_direction,
+                    flex_wrap: FlexWrap::Wrap,
+                    size,
+                    gap: Size { width: percent(0.5), height: percent(0.5) },
+                    ..Default::default()
+                },
+                &children,
+            )
+            .unwrap();
+        let root = match parent_display {
+            Some(display) => taffy
+                .new_with_children(
+                    Style {
+                        display,
+                        align_items: Some(AlignItems::Start),
+                        size: Size::from_lengths(200.0, 200.0),
+                        ..Default::default()
+                    },
+                    &[container],
+                )
+                .unwrap(),
+            None => container,
+        };
+
+        // Lay out twice so that the second pass is served from the cache
+        taffy.compute_layout(root, Size { width: length(200.0), height: length(200.0) }).unwrap();
+        let first = [*taffy.layout(container).unwrap(), *taffy.layout(children[1]).unwrap()];
+        taffy.mark_dirty(root).unwrap();
+        taffy.compute_layout(root, Size { width: length(200.0), height: length(200.0) }).unwrap();
+        let second = [*taffy.layout(container).unwrap(), *taffy.layout(children[1]).unwrap()];
+        assert_eq!(first, second, "{parent_display:?}");
+        first
+    }
+
+    for parent_display in [None, Some(Display::Flex), Some(Display::Block), Some(Display::Grid)] {
+        // The row gap between the lines of a row container is zero while its content-sized height is measured. Block and
+        // grid layout (and the root) don't pass that height back down to an item which isn't stretched, so the gap stays
+        // zero when the lines are placed, whereas a flex parent passes it down as the known height, which the gap then
+        // resolves against.
+        let row_size = Size { width: length(100.0), height: auto() };
+        let [container, item] = layout(parent_display, FlexDirection::Row, row_size);
+        assert_eq!(container.size, Size { width: 100.0, height: 120.0 }, "{parent_display:?}");
+        let row_gap = match parent_display {
+            Some(Display::Flex) => container.size.height * 0.5,
+            _ => 0.0,
+        };
+        assert_eq!(item.location, Point { x: 0.0, y: 60.0 + row_gap }, "{parent_display:?}");
+
+        // Whereas the column gap between the lines of a column container is resolved against the content-sized width
+        let column_size = Size { width: auto(), height: length(100.0) };
+        let [container, item] = layout(parent_display, FlexDirection::Column, column_size);
+        assert_eq!(item.location, Point { x: 60.0 + container.size.width * 0.5, y: 0.0 }, "{parent_display:?}");
+    }
+}
@@ -27,3 +27,34 @@ fn rounding_doesnt_leave_gaps() {
     let layout_b = taffy.layout(child_b).unwrap();
     assert_eq!(layout_a.location.x + layout_a.size.width, layout_b.location.x);
 }
+
+#[test]
+#[cfg(feature = "f64")]
+fn rounding_is_accurate_far_from_origin() {
+    let mut taffy: TaffyTree<()> = TaffyTree::new();
+
+    // 2^24: beyond this point f32 cannot represent fractional (or odd) values
+    let offset = 16_777_216.0;
+    let leaf = taffy
+        .new_leaf(Style {
+            size: Size { width: length(1.0), height: length(1.0) },
+            margin: Rect { left: length(0.5), right: zero(), top: zero(), bottom: zero() },
+            ..Default::default()
+        })
+        .unwrap();
+    let wrapper = taffy
+        .new_with_children(
+            Style {
+                margin: Rect { left: length(offset), right: zero(), top: zero(), bottom: zero() },
+                ..Default::default()
+            },
+            &[leaf],
+        )
+        .unwrap();
+    let root = taffy.new_with_children(Style::default(), &[wrapper]).unwrap();
+
+    taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+
+    assert_eq!(taffy.layout(wrapper).unwrap().location.x, offset);
+    assert_eq!(taffy.layout(leaf).unwrap().size.width, 1.0);
+}
@@ -0,0 +1,87 @@
+use taffy::prelude::*;
+use taffy::Point;
+
+/// Lays out a 100x100 container with a 10px scrollbar width and a single child which fills it, and returns the
+/// location and size of the child
+fn child_layout(display: Display, overflow: Overflow, scrollbar_gutter: ScrollbarGutter) -> (Point<f32>, Size<f32>) {
+    let mut taffy: TaffyTree<()> = TaffyTree::new();
+    let child = taffy.new_leaf(Style { flex_grow: 1.0, ..Default::default() }).unwrap();
+    let container = taffy
+        .new_with_children(
+            Style {
+                display,
+                size: Size::from_lengths(100.0, 100.0),
+                overflow: Point { x: overflow, y: overflow },
+                scrollbar_width: 10.0,
+                scrollbar_gutter,
+                ..Default::default()
+            },
+            &[child],
+        )
+        .unwrap();
+    taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+    let layout = taffy.layout(child).unwrap();
+    (layout.location, layout.size)
+}
+
+#[test]
+fn scrollbar_gutter_reserves_space_for_scroll_containers() {
+    for display in [Display::Flex, Display::Grid, Display::Block] {
+        // Without a stable gutter, space is only reserved for `Overflow::Scroll`
+        let (_, size) = child_layout(display, Overflow::Hidden, ScrollbarGutter::Auto);
+        assert_eq!(size.width, 100.0, "{display:?}");
+        let (_, size) = child_layout(display, Overflow::Scroll, ScrollbarGutter::Auto);
+        assert_eq!(size.width, 90.0, "{display:?}");
+
+        // A stable gutter also reserves space for `Overflow::Hidden`
+        let (location, size) = child_layout(display, Overflow::Hidden, ScrollbarGutter::Stable);
+        assert_eq!((location.x, size.width), (0.0, 90.0), "{display:?}");
+        let (location, size) = child_layout(display, Overflow::Scroll, ScrollbarGutter::Stable);
+        assert_eq!((location.x, size.width), (0.0, 90.0), "{display:?}");
+
+        // But not for nodes which are not scroll containers
+        let (_, size) = child_layout(display, Overflow::Visible, ScrollbarGutter::Stable);
+        assert_eq!(size.width, 100.0, "{display:?}");
+        let (_, size) = child_layout(display, Overflow::Clip, ScrollbarGutter::Stable);
+        assert_eq!(size.width, 100.0, "{display:?}");
+
+        // `StableBothEdges` reserves the same space on the opposite edge
+        let (location, size) = child_layout(display, Overflow::Hidden, ScrollbarGutter::StableBothEdges);
+        assert_eq!((location.x, size.width), (10.0, 80.0), "{display:?}");
+        let (location, size) = child_layout(display, Overflow::Scroll, ScrollbarGutter::StableBothEdges);
+        assert_eq!((location.x, size.width), (10.0, 80.0), "{display:?}");
+    }
+}
+
+#[test]
+fn scrollbar_gutter_offsets_absolutely_positioned_children() {
+    for display in [Display::Flex, Display::Grid, Display::Block] {
+        let mut taffy: TaffyTree<()> = TaffyTree::new();
+        let child = taffy
+            .new_leaf(Style {
+                position: Position::Absolute,
+                inset: Rect { left: zero(), right: zero(), top: zero(), bottom: zero() },
+                ..Default::default()
+            })
+            .unwrap();
+        let container = taffy
+            .new_with_children(
+                Style {
+                    display,
+                    size: Size::from_lengths(100.0, 100.0),
+                    overflow: Point { x: Overflow::Hidden, y: Overflow::Hidden },
+                    scrollbar_width: 10.0,
+                    scrollbar_gutter: ScrollbarGutter::StableBothEdges,
+                    ..Default::default()
+                },
+                &[child],
+            )
+            .unwrap();
+        taffy.compute_layout(container, Size::MAX_CONTENT).unwrap();
+
+        let layout = taffy.layout(child).unwrap();
+        assert_eq!(layout.location, Point { x: 10.0, y: 10.0 }, "{display:?}");
+        assert_eq!(layout.size, Size { width: 80.0, height: 80.0 }, "{display:?}");
+    }
+}